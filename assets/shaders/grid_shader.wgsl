@@ -44,7 +44,7 @@ fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
     // If we're on a grid line, show the line color, otherwise transparent
     if (grid_factor > 0.01) {
         let final_color = material.line_colour.rgb * gradient;
-        let final_alpha = grid_factor * material.line_colour.a;
+        let final_alpha = grid_factor * material.line_colour.a * material.grid_intensity;
         return vec4<f32>(final_color, final_alpha);
     } else {
         // Transparent background
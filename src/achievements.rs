@@ -0,0 +1,203 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::contracts::ContractStatus;
+use crate::game_mode::GameMode;
+use crate::user_config::UserConfig;
+
+/// Counters tracked over the current run, bumped by small hooks in the systems
+/// that do the actual work (construction, contracts, unlocks, payout)
+#[derive(Resource, Default, Debug, Clone)]
+pub struct RunCounters {
+    /// Keyed by building kind ("Splitter", "Combiner", ...), sizes folded together
+    pub buildings_placed: HashMap<String, u32>,
+    pub wires_placed: u32,
+    pub contracts_completed: u32,
+    pub money_earned: i64,
+    pub clusters_unlocked: u32,
+    /// Highest total throughput delivered to sinks in one fulfillment tick
+    pub max_throughput: f32,
+}
+
+impl RunCounters {
+    pub fn record_building(&mut self, name: &str) {
+        let kind = building_kind(name);
+        if kind == "Link" {
+            self.wires_placed += 1;
+        } else {
+            *self.buildings_placed.entry(kind.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    pub fn get(&self, counter: &Counter) -> f32 {
+        match counter {
+            Counter::BuildingsPlaced(kind) => self.buildings_placed.get(kind).copied().unwrap_or(0) as f32,
+            Counter::WiresPlaced => self.wires_placed as f32,
+            Counter::ContractsCompleted => self.contracts_completed as f32,
+            Counter::MoneyEarned => self.money_earned as f32,
+            Counter::ClustersUnlocked => self.clusters_unlocked as f32,
+            Counter::MaxThroughput => self.max_throughput,
+        }
+    }
+}
+
+/// "Splitter 3x1" -> "Splitter"
+pub fn building_kind(name: &str) -> &str {
+    name.split_whitespace().next().unwrap_or(name)
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub enum Counter {
+    BuildingsPlaced(String),
+    WiresPlaced,
+    ContractsCompleted,
+    MoneyEarned,
+    ClustersUnlocked,
+    MaxThroughput,
+}
+
+impl Counter {
+    /// Short noun for progress text, e.g. "splitters placed"
+    pub fn describe(&self) -> String {
+        match self {
+            Counter::BuildingsPlaced(kind) => format!("{}s placed", kind.to_lowercase()),
+            Counter::WiresPlaced => "wires placed".to_string(),
+            Counter::ContractsCompleted => "contracts completed".to_string(),
+            Counter::MoneyEarned => "money earned".to_string(),
+            Counter::ClustersUnlocked => "clusters unlocked".to_string(),
+            Counter::MaxThroughput => "peak throughput".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    AtLeast,
+    AtMost,
+    Equal,
+}
+
+/// Achievement condition, a comparison or a conjunction of them
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub enum Condition {
+    Compare { counter: Counter, op: Comparison, value: f32 },
+    All(Vec<Condition>),
+}
+
+impl Condition {
+    pub fn evaluate(&self, counters: &RunCounters) -> bool {
+        match self {
+            Condition::Compare { counter, op, value } => {
+                let current = counters.get(counter);
+                match op {
+                    Comparison::AtLeast => current >= *value,
+                    Comparison::AtMost => current <= *value,
+                    Comparison::Equal => (current - *value).abs() < f32::EPSILON,
+                }
+            }
+            Condition::All(conditions) => conditions.iter().all(|c| c.evaluate(counters)),
+        }
+    }
+
+    /// Progress towards a single "at least" target, as (current, target). Other shapes
+    /// don't have a meaningful fraction.
+    pub fn progress(&self, counters: &RunCounters) -> Option<(f32, f32)> {
+        match self {
+            Condition::Compare { counter, op: Comparison::AtLeast, value } if *value > 0.0 => {
+                Some((counters.get(counter).min(*value), *value))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn progress_text(&self, counters: &RunCounters) -> Option<String> {
+        let Condition::Compare { counter, .. } = self else {
+            return None;
+        };
+        self.progress(counters)
+            .map(|(current, target)| format!("{:.0}/{:.0} {}", current, target, counter.describe()))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AchievementDef {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub icon: String,
+    pub condition: Condition,
+}
+
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AchievementLibrary {
+    pub achievements: Vec<AchievementDef>,
+}
+
+/// Sent when an achievement is earned, for the toast
+#[derive(Message, Debug, Clone)]
+pub struct AchievementEarned {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+fn load_achievements(mut commands: Commands) {
+    let ron_str = std::fs::read_to_string("assets/text/achievements.ron")
+        .expect("Failed to read achievements.ron");
+    let achievements: Vec<AchievementDef> = ron::from_str(&ron_str)
+        .expect("Failed to parse achievements from RON");
+    commands.insert_resource(AchievementLibrary { achievements });
+}
+
+/// Evaluates the not-yet-earned achievements whenever the counters move
+fn check_achievements(
+    counters: Res<RunCounters>,
+    library: Res<AchievementLibrary>,
+    game_mode: Res<GameMode>,
+    mut user_config: ResMut<UserConfig>,
+    mut earned: MessageWriter<AchievementEarned>,
+) {
+    if !game_mode.counts_for_records() {
+        return;
+    }
+    for achievement in library.achievements.iter() {
+        if user_config.earned_achievements.contains(&achievement.id) {
+            continue;
+        }
+        if achievement.condition.evaluate(&counters) {
+            info!("Achievement earned: {}", achievement.name);
+            user_config.earned_achievements.push(achievement.id.clone());
+            earned.write(AchievementEarned {
+                id: achievement.id.clone(),
+                name: achievement.name.clone(),
+                description: achievement.description.clone(),
+            });
+        }
+    }
+}
+
+/// Contract resolution hook, counts contracts as they reach Completed
+fn count_completed_contracts(
+    statuses: Query<&ContractStatus, Changed<ContractStatus>>,
+    mut counters: ResMut<RunCounters>,
+) {
+    let completed = statuses.iter().filter(|s| **s == ContractStatus::Completed).count() as u32;
+    if completed > 0 {
+        counters.contracts_completed += completed;
+    }
+}
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunCounters>()
+            .add_message::<AchievementEarned>()
+            .add_systems(PreStartup, load_achievements)
+            .add_systems(Update, (
+                count_completed_contracts,
+                check_achievements.run_if(resource_changed::<RunCounters>),
+            ).chain());
+    }
+}
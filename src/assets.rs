@@ -34,6 +34,9 @@ pub enum MachineType {
     Combiner,
     Delinker,
     Trunker,
+    DeIdentifier,
+    Cleaner,
+    FilterSplitter,
 }
 
 /// Machine variant for buildings that come in different sizes
@@ -77,17 +80,19 @@ impl MachineKey {
 }
 
 /// Utility icon indices for common UI sprites
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct UtilityIcons {
     pub arrow_up: usize,
     pub arrow_double_up: usize,
     pub arrow_down: usize,
     pub arrow_double_down: usize,
     pub money: usize,
+    pub lock: usize,
 }
 
-// The main resource now holds handles for textures, colors, and icons
-#[derive(Resource)]
+// The main resource now holds handles for textures, colors, and icons.
+// Default is all placeholder handles, for running visuals in tests without loading anything
+#[derive(Resource, Default)]
 pub struct GameAssets {
     pub small_sprites_texture: Handle<Image>,
     pub small_sprites_layout: Handle<TextureAtlasLayout>,
@@ -190,32 +195,28 @@ impl GameAssets {
         }
     }
 
-    pub fn wire_index(&self, input: Direction, output: Direction) -> usize {
-        match input {
-            Direction::Left => match output {
-                Direction::Up => 8,
-                Direction::Right => 0,
-                Direction::Down => 5,
-                _ => 0,
-            },
-            Direction::Up => match output {
-                Direction::Left => 8,
-                Direction::Right => 6,
-                Direction::Down => 2,
-                _ => 0,
-            },
-            Direction::Right => match output {
-                Direction::Left => 0,
-                Direction::Up => 6,
-                Direction::Down => 3,
-                _ => 0,
-            },
-            Direction::Down => match output {
-                Direction::Left => 5,
-                Direction::Up => 2,
-                Direction::Right => 3,
-                _ => 0,
-            },
+    /// Wire piece joining the side data comes in on to the side it leaves by
+    pub fn wire_index(&self, input_side: Direction, output_side: Direction) -> usize {
+        self.wire_index_for_sides(&[input_side, output_side])
+    }
+
+    /// Wire piece for whichever sides are joined up. Three sides is a T, four the cross.
+    /// There's no end cap in the atlas so a single connection gets the straight piece along it.
+    pub fn wire_index_for_sides(&self, sides: &[Direction]) -> usize {
+        let has = |d: Direction| sides.contains(&d);
+        let (up, down, left, right) = (has(Direction::Up), has(Direction::Down), has(Direction::Left), has(Direction::Right));
+        match (up, down, left, right) {
+            (true, true, true, true) => 4,
+            (false, true, true, true) => 1,
+            (true, false, true, true) => 7,
+            // no T pieces for the vertical ones, the straight run reads best
+            (true, true, _, _) => 2,
+            (true, false, true, false) => 8,
+            (true, false, false, true) => 6,
+            (false, true, true, false) => 5,
+            (false, true, false, true) => 3,
+            (_, _, true, _) | (_, _, _, true) => 0,
+            _ => 2,
         }
     }
 
@@ -340,12 +341,8 @@ pub fn load_assets(
     );
     let wires_layout_handle = texture_atlas_layouts.add(wires_layout);
 
-    // Initialize faction colors
-    let mut faction_colors = HashMap::new();
-    faction_colors.insert(Faction::Academia, Color::srgb(0.2, 0.8, 1.0));    // Cyan
-    faction_colors.insert(Faction::Corporate, Color::srgb(0.9, 0.9, 0.3));   // Yellow
-    faction_colors.insert(Faction::Government, Color::srgb(0.3, 1.0, 0.3));  // Green
-    faction_colors.insert(Faction::Criminal, Color::srgb(1.0, 0.3, 0.3));    // Red
+    // Initialize faction colors, `sync_palette` swaps them if the colorblind palette's picked
+    let faction_colors: HashMap<Faction, Color> = crate::palette::Palette::standard().factions().collect();
 
     // Initialize faction icons - small (16x16) in small_sprites atlas
     let mut faction_icons_small = HashMap::new();
@@ -368,6 +365,7 @@ pub fn load_assets(
         arrow_down: 33,
         arrow_double_down: 32,
         money: 1,
+        lock: 4,
     };
 
     // Initialize machine sprite mappings: MachineKey -> sprite_index
@@ -377,21 +375,28 @@ pub fn load_assets(
     // 1x1 buildings (Collector at index 0, Aggregator at index 1)
     machines.insert(MachineKey::single(MachineType::Collector), 1);
     machines.insert(MachineKey::single(MachineType::Aggregator),0);
+    machines.insert(MachineKey::single(MachineType::Cleaner), 2);
     
     // 2x1 buildings (Splitter, Combiner, Delinker, Trunker)
     machines.insert(MachineKey::new(MachineType::Splitter, MachineVariant::Size2),1);
+    // filter splitters borrow the splitter frames for now
+    machines.insert(MachineKey::new(MachineType::FilterSplitter, MachineVariant::Size2), 1);
     machines.insert(MachineKey::new(MachineType::Combiner, MachineVariant::Size2), 0);
     machines.insert(MachineKey::new(MachineType::Delinker, MachineVariant::Size2), 3);
     machines.insert(MachineKey::new(MachineType::Trunker, MachineVariant::Size2), 2);
+    // no art of its own yet, borrows the delinker frame
+    machines.insert(MachineKey::new(MachineType::DeIdentifier, MachineVariant::Size2), 3);
     
     // 3x1 buildings (Splitter, Combiner, Delinker, Trunker)
     machines.insert(MachineKey::new(MachineType::Splitter, MachineVariant::Size3), 1);
+    machines.insert(MachineKey::new(MachineType::FilterSplitter, MachineVariant::Size3), 1);
     machines.insert(MachineKey::new(MachineType::Combiner, MachineVariant::Size3), 3);
     machines.insert(MachineKey::new(MachineType::Delinker, MachineVariant::Size3), 3);
     machines.insert(MachineKey::new(MachineType::Trunker, MachineVariant::Size3), 2);
     
     // 4x1 buildings (Splitter, Combiner, Delinker, Trunker)
     machines.insert(MachineKey::new(MachineType::Splitter, MachineVariant::Size4), 1);
+    machines.insert(MachineKey::new(MachineType::FilterSplitter, MachineVariant::Size4), 1);
     machines.insert(MachineKey::new(MachineType::Combiner, MachineVariant::Size4), 0);
     machines.insert(MachineKey::new(MachineType::Delinker, MachineVariant::Size4), 3);
     machines.insert(MachineKey::new(MachineType::Trunker, MachineVariant::Size4), 2);
@@ -0,0 +1,293 @@
+use bevy::audio::{AudioSinkPlayback, Volume};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use bevy::time::common_conditions::on_timer;
+
+use crate::contracts::{ContractFulfillment, ContractFulfillmentStatus, ContractStatus};
+use crate::player::Player;
+use crate::settings::Settings;
+
+/// Bankruptcy stage at which the run is considered lost for music purposes
+pub const GAME_OVER_BANKRUPTCY_STAGE: u32 = 3;
+const AUDIO_CONFIG_PATH: &str = "assets/text/audio.ron";
+
+/// Track paths and fade timings, loaded from assets/text/audio.ron
+#[derive(Resource, Deserialize, Debug, Clone)]
+pub struct AudioConfig {
+    pub calm: String,
+    pub tense: String,
+    pub game_over: String,
+    pub game_over_sting: String,
+    pub startup_fade_seconds: f32,
+    pub crossfade_seconds: f32,
+    /// One-shot sound effect files
+    #[serde(default)]
+    pub sfx: HashMap<Sfx, String>,
+}
+
+impl AudioConfig {
+    /// No tracks and no sounds, for when audio.ron can't be read
+    pub fn silent() -> Self {
+        Self {
+            calm: String::new(),
+            tense: String::new(),
+            game_over: String::new(),
+            game_over_sting: String::new(),
+            startup_fade_seconds: 2.0,
+            crossfade_seconds: 3.0,
+            sfx: HashMap::new(),
+        }
+    }
+}
+
+/// Short feedback sounds, paths come from `AudioConfig::sfx`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum Sfx {
+    Place,
+    Remove,
+    ContractAccepted,
+    EventPopup,
+    /// A faction cluster opening up
+    Fanfare,
+    /// Two things wired together
+    Connect,
+    /// A connection cut, usually by a removal
+    Disconnect,
+}
+
+/// Plays a one-shot sound effect at the sfx volume
+#[derive(Message, Debug, Clone, Copy)]
+pub struct PlaySfx(pub Sfx);
+
+/// The sound effects that loaded, missing files are left out
+#[derive(Resource, Default)]
+pub struct SfxHandles(pub HashMap<Sfx, Handle<AudioSource>>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MusicPhase {
+    Calm,
+    Tense,
+    GameOver,
+}
+
+/// Tracks which phase the music should be in and which one is actually playing
+#[derive(Resource, Default)]
+pub struct MusicDirector {
+    pub desired: Option<MusicPhase>,
+    pub playing: Option<MusicPhase>,
+}
+
+impl MusicDirector {
+    /// The phase to start a track for, if the desired one isn't playing yet, and whether it's
+    /// the very first track (that one fades in from silence, later ones crossfade).
+    /// Counts it as playing from here on
+    pub fn begin_transition(&mut self) -> Option<(MusicPhase, bool)> {
+        let desired = self.desired?;
+        if self.playing == Some(desired) {
+            return None;
+        }
+        let first = self.playing.is_none();
+        self.playing = Some(desired);
+        Some((desired, first))
+    }
+}
+
+/// A music track entity. `level` is its fade position (0..1), scaled by the
+/// music volume setting to get the actual sink volume.
+#[derive(Component)]
+pub struct MusicTrack {
+    pub phase: MusicPhase,
+    pub level: f32,
+    pub target: f32,
+    /// Seconds to go from silent to full (or back)
+    pub fade_seconds: f32,
+}
+
+impl MusicTrack {
+    /// Step the fade towards the target. Returns true once the track has faded out completely.
+    pub fn step(&mut self, dt: f32) -> bool {
+        let rate = if self.fade_seconds > 0.0 { dt / self.fade_seconds } else { 1.0 };
+        if self.level < self.target {
+            self.level = (self.level + rate).min(self.target);
+        } else if self.level > self.target {
+            self.level = (self.level - rate).max(self.target);
+        }
+        self.target == 0.0 && self.level <= 0.0
+    }
+}
+
+/// Pick the music phase from a snapshot of the game state
+pub fn desired_music_phase(any_contract_failing: bool, bankruptcy_active: bool, bankruptcy_stage: u32) -> MusicPhase {
+    if bankruptcy_stage >= GAME_OVER_BANKRUPTCY_STAGE {
+        MusicPhase::GameOver
+    } else if any_contract_failing || bankruptcy_active {
+        MusicPhase::Tense
+    } else {
+        MusicPhase::Calm
+    }
+}
+
+fn load_audio_config(mut commands: Commands) {
+    let config = std::fs::read_to_string(AUDIO_CONFIG_PATH)
+        .map_err(|e| e.to_string())
+        .and_then(|ron_str| ron::from_str::<AudioConfig>(&ron_str).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            warn!("Couldn't load {}: {}, playing silence instead", AUDIO_CONFIG_PATH, e);
+            AudioConfig::silent()
+        });
+    commands.insert_resource(config);
+}
+
+fn load_sfx(mut commands: Commands, config: Res<AudioConfig>, asset_server: Res<AssetServer>) {
+    let handles = config
+        .sfx
+        .iter()
+        .filter_map(|(sfx, path)| load_track(&asset_server, path).map(|handle| (*sfx, handle)))
+        .collect();
+    commands.insert_resource(SfxHandles(handles));
+}
+
+/// Each sound at most once a frame, so a dragged wire run doesn't stack a dozen clicks
+fn play_sfx(
+    mut commands: Commands,
+    mut requests: MessageReader<PlaySfx>,
+    handles: Res<SfxHandles>,
+    settings: Res<Settings>,
+) {
+    let mut played = Vec::new();
+    for PlaySfx(sfx) in requests.read() {
+        if played.contains(sfx) {
+            continue;
+        }
+        played.push(*sfx);
+        let volume = settings.audio.sfx_level();
+        if volume <= 0.0 {
+            continue;
+        }
+        if let Some(handle) = handles.0.get(sfx) {
+            commands.spawn((AudioPlayer::new(handle.clone()), PlaybackSettings::DESPAWN.with_volume(Volume::Linear(volume))));
+        }
+    }
+}
+
+/// Load a track if the file exists, otherwise warn and stay silent
+fn load_track(asset_server: &AssetServer, path: &str) -> Option<Handle<AudioSource>> {
+    // the silent fallback config
+    if path.is_empty() {
+        return None;
+    }
+    if std::path::Path::new("assets").join(path).exists() {
+        Some(asset_server.load(path.to_string()))
+    } else {
+        warn!("Music track {} is missing, playing silence instead", path);
+        None
+    }
+}
+
+fn update_music_phase(
+    mut director: ResMut<MusicDirector>,
+    player: Res<Player>,
+    debt: Res<crate::events::DebtState>,
+    contract_query: Query<(&ContractStatus, &ContractFulfillment)>,
+) {
+    let any_failing = contract_query.iter().any(|(status, fulfillment)| {
+        *status == ContractStatus::Active && fulfillment.status == ContractFulfillmentStatus::Failing
+    });
+    let bankruptcy_active = debt.insolvent_seconds > 0.0 || player.bankruptcy_stage > 0;
+    let phase = desired_music_phase(any_failing, bankruptcy_active, player.bankruptcy_stage);
+    if director.desired != Some(phase) {
+        director.desired = Some(phase);
+    }
+}
+
+/// Starts the desired track and fades out the others when the phase changes
+fn start_music_transition(
+    mut commands: Commands,
+    mut director: ResMut<MusicDirector>,
+    config: Res<AudioConfig>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    mut tracks: Query<&mut MusicTrack>,
+) {
+    let previous = director.playing;
+    let Some((desired, first)) = director.begin_transition() else {
+        return;
+    };
+
+    let fade_seconds = if first {
+        config.startup_fade_seconds
+    } else {
+        config.crossfade_seconds
+    };
+
+    for mut track in tracks.iter_mut() {
+        track.target = 0.0;
+        track.fade_seconds = config.crossfade_seconds;
+    }
+
+    let path = match desired {
+        MusicPhase::Calm => &config.calm,
+        MusicPhase::Tense => &config.tense,
+        MusicPhase::GameOver => &config.game_over,
+    };
+
+    if desired == MusicPhase::GameOver
+        && let Some(sting) = load_track(&asset_server, &config.game_over_sting)
+    {
+        commands.spawn((AudioPlayer::new(sting), PlaybackSettings::DESPAWN.with_volume(Volume::Linear(settings.audio.music_level()))));
+    }
+
+    if let Some(handle) = load_track(&asset_server, path) {
+        commands.spawn((
+            AudioPlayer::new(handle),
+            PlaybackSettings::LOOP.with_volume(Volume::Linear(0.0)),
+            MusicTrack {
+                phase: desired,
+                level: 0.0,
+                target: 1.0,
+                fade_seconds,
+            },
+        ));
+    }
+
+    info!("Music phase: {:?} -> {:?}", previous, desired);
+}
+
+/// Ramps track volumes and despawns tracks that finished fading out
+fn fade_music_tracks(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut tracks: Query<(Entity, &mut MusicTrack, Option<&mut AudioSink>)>,
+) {
+    let master = settings.audio.music_level();
+    for (entity, mut track, sink) in tracks.iter_mut() {
+        if track.step(time.delta_secs()) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(mut sink) = sink {
+            sink.set_volume(Volume::Linear(track.level * master));
+        }
+    }
+}
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicDirector>()
+            .init_resource::<SfxHandles>()
+            .add_message::<PlaySfx>()
+            .add_systems(PreStartup, load_audio_config)
+            .add_systems(Startup, load_sfx)
+            .add_systems(Update, play_sfx)
+            .add_systems(Update, (
+                update_music_phase.run_if(on_timer(Duration::from_millis(500))),
+                start_music_transition,
+                fade_music_tracks,
+            ).chain());
+    }
+}
@@ -1,5 +1,7 @@
 use std::ops::Range;
 
+use bevy::math::I64Vec2;
+
 use bevy::{
     app::{Plugin, Startup, Update},
     camera::{Camera, Camera2d, Projection},
@@ -16,14 +18,44 @@ use bevy::{
     prelude::*
 };
 
-use crate::{grid::{GridPosition, Grid}, ui::BlocksWorldScroll};
+use crate::{
+    grid::{GridPosition, Grid},
+    pause::GameState,
+    settings::Settings,
+    ui::{interactive_event::InteractiveEventModal, BlocksWorldClicks, BlocksWorldScroll},
+    world_gen::WorldBounds,
+};
+
+/// Largest shake offset at full trauma and zoom 1, world units
+const MAX_SHAKE_OFFSET: f32 = 14.0;
+/// Trauma lost per second
+const SHAKE_DECAY: f32 = 1.6;
+
+/// Shakes the camera, the amount is trauma from 0 to 1 and stacks up to 1
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ShakeScreen(pub f32);
+
+/// Current trauma and the offset last added to the camera, taken back off next frame
+#[derive(Resource, Default, Debug)]
+struct ScreenShake {
+    trauma: f32,
+    offset: Vec2,
+}
 
 #[derive(Debug, Resource)]
 struct CameraSettings {
     /// Clamp the orthographic camera's scale to this range
     pub orthographic_zoom_range: Range<f32>,
+    /// Wider range for photo mode
+    pub photo_zoom_range: Range<f32>,
     /// Multiply mouse wheel inputs by this factor when using the orthographic camera
     pub orthographic_zoom_speed: f32,
+    /// World units per second at zoom 1 for keyboard and edge panning
+    pub pan_speed: f32,
+    /// How close to the window border the cursor has to be to edge pan, in px
+    pub edge_pan_margin: f32,
+    /// Tiles the camera may drift past the edge of the world
+    pub world_margin_tiles: i64,
 }
 
 pub struct GameCameraPlugin;
@@ -34,11 +66,25 @@ impl Plugin for GameCameraPlugin {
             // In orthographic projections, we specify camera scale relative to a default value of 1,
             // in which one unit in world space corresponds to one pixel.
             orthographic_zoom_range: 0.5..10.0,
+            photo_zoom_range: 0.1..40.0,
             // This value was hand-tuned to ensure that zooming in and out feels smooth but not slow.
             orthographic_zoom_speed: 0.2,
+            pan_speed: 900.0,
+            edge_pan_margin: 20.0,
+            world_margin_tiles: 4,
         });
+        app.init_resource::<ScreenShake>().add_message::<ShakeScreen>();
         app.add_systems(Startup, startup);
-        app.add_systems(Update, (zoom, pan_camera));
+        app.add_systems(Update, (
+            zoom,
+            pan_camera,
+            // same states as the shop, so you can look around while manually paused
+            keyboard_pan_camera.run_if(in_state(GameState::Running).or(in_state(GameState::ManualPause))),
+            gamepad_pan_camera.run_if(in_state(GameState::Running).or(in_state(GameState::ManualPause))),
+            // photo mode can go anywhere
+            clamp_camera.run_if(not(crate::photo_mode::photo_mode_active)),
+            shake_camera,
+        ).chain());
     }
 }
 
@@ -51,6 +97,7 @@ fn zoom(
     camera_settings: Res<CameraSettings>,
     mouse_wheel_input: Res<AccumulatedMouseScroll>,
     scroll_blocker_query: Query<&Interaction, With<BlocksWorldScroll>>,
+    photo_mode: Res<crate::photo_mode::PhotoMode>,
 ) {
     // Check if cursor is over any BlocksWorldScroll UI panel
     for interaction in scroll_blocker_query.iter() {
@@ -69,10 +116,12 @@ fn zoom(
         // and negative values result in multiplicative decreases.
         let multiplicative_zoom = 1. + delta_zoom;
 
-        orthographic.scale = (orthographic.scale * multiplicative_zoom).clamp(
-            camera_settings.orthographic_zoom_range.start,
-            camera_settings.orthographic_zoom_range.end,
-        );
+        let range = if photo_mode.active {
+            &camera_settings.photo_zoom_range
+        } else {
+            &camera_settings.orthographic_zoom_range
+        };
+        orthographic.scale = (orthographic.scale * multiplicative_zoom).clamp(range.start, range.end);
     }
 }
 
@@ -102,6 +151,144 @@ fn pan_camera(
     camera_transform.translation.y -= delta.y; // Y is inverted in screen space
 }
 
+/// WASD/arrow keys, plus edge panning when it's switched on in settings
+fn keyboard_pan_camera(
+    camera_query: Single<(&mut Transform, &Projection), With<Camera>>,
+    camera_settings: Res<CameraSettings>,
+    settings: Res<Settings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    window: Single<&Window, With<bevy::window::PrimaryWindow>>,
+    ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    modals: Query<(), With<InteractiveEventModal>>,
+) {
+    if !modals.is_empty() {
+        return;
+    }
+
+    let mut direction = Vec2::ZERO;
+    if keyboard.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        direction.y += 1.0;
+    }
+    if keyboard.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        direction.y -= 1.0;
+    }
+    if keyboard.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        direction.x -= 1.0;
+    }
+    if keyboard.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        direction.x += 1.0;
+    }
+
+    let over_ui = ui_blocker_query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed);
+    if settings.edge_scroll && !over_ui && let Some(cursor) = window.cursor_position() {
+        let margin = camera_settings.edge_pan_margin;
+        if cursor.x <= margin {
+            direction.x -= 1.0;
+        } else if cursor.x >= window.width() - margin {
+            direction.x += 1.0;
+        }
+        // cursor y goes down the screen
+        if cursor.y <= margin {
+            direction.y += 1.0;
+        } else if cursor.y >= window.height() - margin {
+            direction.y -= 1.0;
+        }
+    }
+
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let (mut camera_transform, projection) = camera_query.into_inner();
+    let camera_scale = if let Projection::Orthographic(orthographic) = projection {
+        orthographic.scale
+    } else {
+        1.0
+    };
+    let delta = direction.normalize() * camera_settings.pan_speed * camera_scale * time.delta_secs();
+    camera_transform.translation.x += delta.x;
+    camera_transform.translation.y += delta.y;
+}
+
+/// Left stick pans at the same speed as the keyboard
+fn gamepad_pan_camera(
+    camera_query: Single<(&mut Transform, &Projection), With<Camera>>,
+    camera_settings: Res<CameraSettings>,
+    gamepads: Query<&Gamepad>,
+    time: Res<Time<Real>>,
+    modals: Query<(), With<InteractiveEventModal>>,
+) {
+    if !modals.is_empty() {
+        return;
+    }
+    let Some(stick) = gamepads.iter().map(|g| g.left_stick()).find(|s| *s != Vec2::ZERO) else {
+        return;
+    };
+    let (mut camera_transform, projection) = camera_query.into_inner();
+    let camera_scale = if let Projection::Orthographic(orthographic) = projection {
+        orthographic.scale
+    } else {
+        1.0
+    };
+    // stick magnitude up to 1 so a light push pans slowly
+    let delta = stick.clamp_length_max(1.0) * camera_settings.pan_speed * camera_scale * time.delta_secs();
+    camera_transform.translation.x += delta.x;
+    camera_transform.translation.y += delta.y;
+}
+
+/// Keeps the camera within a few tiles of the generated world
+fn clamp_camera(
+    mut camera_transform: Single<&mut Transform, With<Camera>>,
+    camera_settings: Res<CameraSettings>,
+    grid: Res<Grid>,
+    bounds: Res<WorldBounds>,
+) {
+    let margin = I64Vec2::splat(camera_settings.world_margin_tiles);
+    let min = grid.grid_to_world_center(&GridPosition(bounds.min - margin));
+    let max = grid.grid_to_world_center(&GridPosition(bounds.max + margin));
+    let clamped = camera_transform.translation.xy().clamp(min.min(max), min.max(max));
+    if clamped != camera_transform.translation.xy() {
+        camera_transform.translation.x = clamped.x;
+        camera_transform.translation.y = clamped.y;
+    }
+}
+
+/// Offsets the camera by the current trauma, scaled by the screen shake setting. The offset
+/// sits on top of wherever panning and the clamp left the camera
+fn shake_camera(
+    camera_query: Single<(&mut Transform, &Projection), With<Camera>>,
+    mut shake: ResMut<ScreenShake>,
+    mut requests: MessageReader<ShakeScreen>,
+    settings: Res<Settings>,
+    time: Res<Time<Real>>,
+) {
+    for ShakeScreen(trauma) in requests.read() {
+        shake.trauma = (shake.trauma + trauma).min(1.0);
+    }
+    if shake.trauma <= 0.0 && shake.offset == Vec2::ZERO {
+        return;
+    }
+    let (mut camera_transform, projection) = camera_query.into_inner();
+    let camera_scale = if let Projection::Orthographic(orthographic) = projection {
+        orthographic.scale
+    } else {
+        1.0
+    };
+
+    let previous = shake.offset;
+    shake.trauma = (shake.trauma - SHAKE_DECAY * time.delta_secs()).max(0.0);
+    // squared so small knocks barely register and big ones really rattle
+    let amount = shake.trauma * shake.trauma * settings.screen_shake_strength() * MAX_SHAKE_OFFSET * camera_scale;
+    let t = time.elapsed_secs();
+    shake.offset = Vec2::new((t * 47.0).sin(), (t * 61.0 + 1.3).sin()) * amount;
+
+    let delta = shake.offset - previous;
+    camera_transform.translation.x += delta.x;
+    camera_transform.translation.y += delta.y;
+}
+
 pub fn focus_camera_on_grid_pos(grid_pos: &GridPosition, grid: &Grid, camera_transform: &mut Transform, orthographic: &mut OrthographicProjection) {
     camera_transform.translation = grid.grid_to_world_center(grid_pos).extend(camera_transform.translation.z);
     orthographic.scale = 0.7;
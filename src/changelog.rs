@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::user_config::UserConfig;
+
+pub const CHANGELOG_PATH: &str = "assets/text/changelog.ron";
+
+/// major.minor.patch with an optional pre-release tag, ordered like semver so a
+/// pre-release comes before its release (1.0.0-beta < 1.0.0). Build metadata after a '+'
+/// is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Dot separated identifiers after the '-', empty for a release
+    pub pre: Vec<String>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Version {
+        Version { major, minor, patch, pre: Vec::new() }
+    }
+
+    pub fn parse(s: &str) -> Option<Version> {
+        let s = s.trim().trim_start_matches('v');
+        let s = s.split('+').next()?;
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect::<Vec<_>>()),
+            None => (s, Vec::new()),
+        };
+        if pre.iter().any(|identifier| identifier.is_empty()) {
+            return None;
+        }
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        let major = parts.next()??;
+        let minor = parts.next().unwrap_or(Some(0))?;
+        let patch = parts.next().unwrap_or(Some(0))?;
+        Some(Version { major, minor, patch, pre })
+    }
+
+    pub fn current() -> Version {
+        Version::parse(env!("CARGO_PKG_VERSION")).expect("crate version is semver")
+    }
+}
+
+/// Numeric identifiers compare as numbers and come before alphanumeric ones
+fn compare_pre_identifier(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+        (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => std::cmp::Ordering::Equal,
+                (true, false) => std::cmp::Ordering::Greater,
+                (false, true) => std::cmp::Ordering::Less,
+                (false, false) => self
+                    .pre
+                    .iter()
+                    .zip(other.pre.iter())
+                    .map(|(a, b)| compare_pre_identifier(a, b))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or_else(|| self.pre.len().cmp(&other.pre.len())),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeCategory {
+    Feature,
+    Balance,
+    Fix,
+}
+
+impl ChangeCategory {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            ChangeCategory::Feature => "+",
+            ChangeCategory::Balance => "~",
+            ChangeCategory::Fix => "!",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            ChangeCategory::Feature => Color::srgb(0.3, 0.9, 0.3),
+            ChangeCategory::Balance => Color::srgb(0.95, 0.85, 0.25),
+            ChangeCategory::Fix => Color::srgb(0.4, 0.7, 1.0),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChangelogEntry {
+    pub category: ChangeCategory,
+    pub text: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChangelogRelease {
+    pub version: String,
+    pub date: String,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+impl ChangelogRelease {
+    pub fn parsed_version(&self) -> Option<Version> {
+        Version::parse(&self.version)
+    }
+}
+
+/// Releases newest first. Not inserted at all if the file is missing.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Changelog {
+    pub releases: Vec<ChangelogRelease>,
+}
+
+impl Changelog {
+    /// Releases the player hasn't seen yet, newest first. A fresh install (nothing seen
+    /// before) shows nothing, and so does running an older build than last time.
+    pub fn unseen(&self, last_seen: Option<Version>, current: Version) -> Vec<&ChangelogRelease> {
+        let Some(last_seen) = last_seen else {
+            return Vec::new();
+        };
+        if current <= last_seen {
+            return Vec::new();
+        }
+        self.releases
+            .iter()
+            .filter(|r| r.parsed_version().is_some_and(|v| v > last_seen && v <= current))
+            .collect()
+    }
+}
+
+/// What the "What's new" panel should show next time it opens
+#[derive(Resource, Debug, Default)]
+pub struct WhatsNew {
+    /// Versions to show, empty means the whole changelog
+    pub versions: Vec<String>,
+    pub open: bool,
+}
+
+fn load_changelog(mut commands: Commands) {
+    let Ok(ron_str) = std::fs::read_to_string(CHANGELOG_PATH) else {
+        info!("No changelog at {}, skipping what's new", CHANGELOG_PATH);
+        return;
+    };
+    match ron::from_str::<Vec<ChangelogRelease>>(&ron_str) {
+        Ok(mut releases) => {
+            releases.sort_by(|a, b| b.parsed_version().cmp(&a.parsed_version()));
+            commands.insert_resource(Changelog { releases });
+        }
+        Err(e) => warn!("Failed to parse {}: {}", CHANGELOG_PATH, e),
+    }
+}
+
+/// Opens the panel for anything new since last launch, then marks this version as seen
+fn check_whats_new(
+    changelog: Option<Res<Changelog>>,
+    mut user_config: ResMut<UserConfig>,
+    mut whats_new: ResMut<WhatsNew>,
+) {
+    let Some(changelog) = changelog else { return };
+    let current = Version::current();
+    let last_seen = user_config.last_seen_version.as_deref().and_then(Version::parse);
+    let unseen = changelog.unseen(last_seen, current);
+    if !unseen.is_empty() {
+        info!("Showing what's new for {} release(s)", unseen.len());
+        whats_new.versions = unseen.iter().map(|r| r.version.clone()).collect();
+        whats_new.open = true;
+    }
+    let current_string = env!("CARGO_PKG_VERSION").to_string();
+    if user_config.last_seen_version.as_ref() != Some(&current_string) {
+        user_config.last_seen_version = Some(current_string);
+    }
+}
+
+pub struct ChangelogPlugin;
+
+impl Plugin for ChangelogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WhatsNew>()
+            .add_systems(PreStartup, load_changelog)
+            .add_systems(Startup, check_whats_new);
+    }
+}
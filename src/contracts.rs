@@ -1,10 +1,14 @@
 use bevy::ecs::entity;
 use bevy::{prelude::*};
 use bevy::ecs::relationship::{RelationshipTarget};
-use serde::Deserialize;
-use crate::factory::logical::{Dataset};
+use serde::{Deserialize, Serialize};
+use crate::factory::logical::{BasicDataType, DataAttribute, Dataset};
+use crate::difficulty::DifficultyMultipliers;
 use crate::factions::{Faction, ReputationLevel, Unlocked};
-use bevy::platform::collections::HashMap;
+use crate::grid::GridPosition;
+use crate::trace::TraceLog;
+use crate::trace_sim;
+use bevy::platform::collections::{HashMap, HashSet};
 use rand::seq::SliceRandom;
 use bevy_prng::WyRand;
 use bevy_rand::prelude::GlobalRng;
@@ -14,21 +18,37 @@ use rand::prelude::IndexedRandom;
 
 // Add the Deserialize trait to your existing components that are in the RON file
 #[derive(Component, Deserialize, Debug)]
+#[require(crate::economics::ContractEarnings, ContractReputation, ContractFailing)]
 pub struct Contract;
 
+/// Seconds an active contract has been falling short without a break
+#[derive(Component, Default, Debug)]
+pub struct ContractFailing(pub f32);
+
+/// Seconds of unbroken fulfillment towards the next reputation tick, and how long the
+/// card's "+rep" indicator has left since the last one
+#[derive(Component, Default, Debug)]
+pub struct ContractReputation {
+    pub progress: f32,
+    pub flash: f32,
+    pub last_gain: i32,
+}
+
 #[derive(Component, Deserialize, Debug)]
 pub struct ContractTimeout(pub f32);
 
-#[derive(Component, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Component, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ContractStatus {
     Pending,
     Active,
     Completed,
     Rejected,
     Failed,
+    /// Left pending until its timeout ran out
+    Expired,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ContractFulfillmentStatus {
     Exceeding,
     Meeting,
@@ -36,7 +56,7 @@ pub enum ContractFulfillmentStatus {
 }
 
 
-#[derive(Component, Default, Deserialize, Clone, Debug)]
+#[derive(Component, Default, Deserialize, Serialize, Clone, Debug)]
 pub struct ContractDescription {
     pub name: String,
     pub description: String,
@@ -54,9 +74,16 @@ pub struct ContractDefinition {
     pub reputation: ReputationLevel,
     pub base_threshold: f64,
     pub base_money: f64,
+    /// Total data to deliver before the contract is done, ongoing contracts leave it out
+    #[serde(default = "ongoing_volume")]
+    pub total_required: f64,
     pub dataset: Dataset,
 }
 
+pub fn ongoing_volume() -> f64 {
+    f64::INFINITY
+}
+
 // A resource to hold all contracts loaded from the RON file
 #[derive(Resource, Debug, Default)]
 pub struct ContractLibrary {
@@ -98,6 +125,61 @@ impl SinkContracts {
 }
 
 
+/// Share of the throughput that still counts when a requested type arrives without all
+/// of its required attributes
+pub const PARTIAL_ATTRIBUTE_CREDIT: f64 = 0.5;
+
+/// One requested data type's part of a contract's throughput
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeContribution {
+    pub data_type: BasicDataType,
+    /// Everything arriving that carries this type, whatever its attributes
+    pub arriving: f64,
+    /// What of that counted towards the contract
+    pub counted: f64,
+    /// Required attributes some of the arriving data was missing
+    pub missing: HashSet<DataAttribute>,
+}
+
+/// What `amount`/s of `incoming` is worth to a contract asking for `required`, per requested
+/// type. Each requested type is an equal share of the throughput: a type that isn't there counts
+/// for nothing, one short of a required attribute counts at `partial_credit`, and one with
+/// everything (extra attributes are fine) counts in full. Types nobody asked for are ignored.
+pub fn match_contribution(incoming: &Dataset, amount: f64, required: &Dataset, partial_credit: f64) -> Vec<TypeContribution> {
+    let share = amount / required.contents.len().max(1) as f64;
+    let mut contributions: Vec<TypeContribution> = required
+        .contents
+        .iter()
+        .map(|(data_type, wanted)| {
+            let (arriving, counted, missing) = match incoming.contents.get(data_type) {
+                None => (0.0, 0.0, HashSet::new()),
+                Some(attributes) if wanted.is_subset(attributes) => (amount, share, HashSet::new()),
+                Some(attributes) => (amount, share * partial_credit, wanted.difference(attributes).copied().collect()),
+            };
+            TypeContribution { data_type: *data_type, arriving, counted, missing }
+        })
+        .collect();
+    contributions.sort_by_key(|c| c.data_type);
+    contributions
+}
+
+/// `match_contribution` over everything arriving at a sink, one entry per requested type
+pub fn sum_contributions<'a>(
+    incoming: impl IntoIterator<Item = (&'a Dataset, f64)>,
+    required: &Dataset,
+    partial_credit: f64,
+) -> Vec<TypeContribution> {
+    let mut total = match_contribution(&Dataset { contents: HashMap::new() }, 0.0, required, partial_credit);
+    for (dataset, amount) in incoming {
+        for (sum, part) in total.iter_mut().zip(match_contribution(dataset, amount, required, partial_credit)) {
+            sum.arriving += part.arriving;
+            sum.counted += part.counted;
+            sum.missing.extend(part.missing);
+        }
+    }
+    total
+}
+
 // duplicates base_threshold and base_money in ContractBundle but i think its ok
 #[derive(Component, Debug)]
 pub struct ContractFulfillment {
@@ -105,6 +187,12 @@ pub struct ContractFulfillment {
     pub status: ContractFulfillmentStatus,
     pub base_threshold: f64,
     pub base_money: f64,
+    /// Cumulative data delivered over the contract's lifetime
+    pub delivered: f64,
+    pub total_required: f64,
+    /// How the last second's throughput came together, per requested type, for showing why
+    /// it's lower than what's arriving
+    pub contributions: Vec<TypeContribution>,
 }
 
 impl ContractFulfillment {
@@ -122,6 +210,33 @@ impl ContractFulfillment {
         self.status = self.get_fulfillment_status();
     }
 
+    /// Counts the per-type breakdown towards the throughput
+    pub fn update_contributions(&mut self, contributions: Vec<TypeContribution>) {
+        self.update_throughput(contributions.iter().map(|c| c.counted).sum());
+        self.contributions = contributions;
+    }
+
+    /// Why the throughput is below what's arriving, e.g. "Economic needs Cleaned, no Biometric"
+    pub fn shortfall_reason(&self) -> Option<String> {
+        let reasons: Vec<String> = self
+            .contributions
+            .iter()
+            .filter_map(|c| {
+                if c.arriving <= 0.0 {
+                    return Some(format!("no {:?}", c.data_type));
+                }
+                if c.missing.is_empty() {
+                    return None;
+                }
+                let mut missing: Vec<_> = c.missing.iter().collect();
+                missing.sort();
+                let names: Vec<String> = missing.iter().map(|a| format!("{:?}", a)).collect();
+                Some(format!("{:?} needs {}", c.data_type, names.join(", ")))
+            })
+            .collect();
+        (!reasons.is_empty()).then(|| reasons.join(", "))
+    }
+
     fn get_fulfillment_status(&mut self) -> ContractFulfillmentStatus {
         let threshold_fraction = self.throughput / self.base_threshold;
         get_fulfillment_status(threshold_fraction)
@@ -133,9 +248,49 @@ impl ContractFulfillment {
             status: ContractFulfillmentStatus::Failing,
             base_threshold,
             base_money,
+            delivered: 0.0,
+            total_required: ongoing_volume(),
+            contributions: Vec::new(),
         }
     }
 
+    pub fn with_total_required(mut self, total_required: f64) -> Self {
+        self.total_required = total_required;
+        self
+    }
+
+    /// Ongoing contracts never finish
+    pub fn has_volume_goal(&self) -> bool {
+        self.total_required.is_finite()
+    }
+
+    pub fn volume_progress(&self) -> f64 {
+        if !self.has_volume_goal() || self.total_required <= 0.0 {
+            return 0.0;
+        }
+        (self.delivered / self.total_required).clamp(0.0, 1.0)
+    }
+
+    pub fn is_volume_complete(&self) -> bool {
+        self.has_volume_goal() && self.delivered >= self.total_required
+    }
+
+    /// Money fee and reputation hit for cancelling. The hit grows the further in the contract is,
+    /// and doubles when it's already failing.
+    pub fn cancellation_penalty(&self) -> (i32, i32) {
+        let progress = if self.has_volume_goal() {
+            self.volume_progress()
+        } else {
+            (self.delivered / (self.base_threshold * CANCEL_ONGOING_FULL_SECONDS).max(1.0)).clamp(0.0, 1.0)
+        };
+        let mut reputation = CANCEL_REPUTATION_MIN
+            + ((CANCEL_REPUTATION_MAX - CANCEL_REPUTATION_MIN) as f64 * progress).round() as i32;
+        if self.status == ContractFulfillmentStatus::Failing {
+            reputation *= 2;
+        }
+        (CANCEL_FEE, reputation)
+    }
+
 }
 
 
@@ -153,22 +308,109 @@ pub struct ContractBundle {
 
 const MAX_CONTRACTS_PER_SINK: usize = 4;
 const MAX_PENDING_CONTRACTS: usize = 3;
+/// A newly unlocked sink's first offer turns up this long after it opens
+const WELCOME_CONTRACT_DELAY_SECONDS: f32 = 2.0;
+/// How long a new sink stays favoured by the random generator, and by how much
+const RECENT_UNLOCK_SECONDS: f32 = 180.0;
+const RECENT_UNLOCK_WEIGHT: f32 = 3.0;
+/// Reputation lost with a faction when one of its offers is left to lapse
+const EXPIRED_OFFER_REPUTATION_PENALTY: i32 = 2;
+/// Seconds a contract has to keep Meeting/Exceeding for each reputation tick
+pub const REPUTATION_TICK_SECONDS: f32 = 30.0;
+pub const FAILED_CONTRACT_REPUTATION_PENALTY: i32 = 2;
+/// An active contract failing for this long is pulled, scaled by the difficulty's `failing_time`
+pub const FAILING_CONTRACT_SECONDS: f32 = 120.0;
+/// How long the "+rep" indicator stays on the card
+pub const REPUTATION_FLASH_SECONDS: f32 = 3.0;
+/// Lump sum for finishing a contract's volume, in seconds of its base income
+const COMPLETION_BONUS_SECONDS: f64 = 60.0;
+const COMPLETED_CONTRACT_REPUTATION_GAIN: i32 = 5;
+/// How long the "Completed!" card stays in the sidebar
+pub const COMPLETED_DISPLAY_SECONDS: f32 = 4.0;
+/// Flat fee for walking away from an accepted contract
+pub const CANCEL_FEE: i32 = 200;
+/// Reputation lost cancelling, from just accepted up to well under way
+const CANCEL_REPUTATION_MIN: i32 = 2;
+const CANCEL_REPUTATION_MAX: i32 = 10;
+/// Ongoing contracts count as fully under way after this many seconds of deliveries at threshold
+const CANCEL_ONGOING_FULL_SECONDS: f64 = 300.0;
 
 // --- Resources ---
 
-#[derive(Resource)]
-struct GameTimer {
-    timer: Timer,
+/// Early-game contract pacing, one offer every `interval_secs` for the first `window_secs`
+#[derive(Resource, Debug, Clone)]
+pub struct ContractPacing {
+    pub window_secs: f32,
+    pub interval_secs: f32,
 }
 
-impl Default for GameTimer {
+impl Default for ContractPacing {
     fn default() -> Self {
         Self {
-            timer: Timer::from_seconds(60.0, TimerMode::Once), // 1 minute timer
+            window_secs: 60.0,
+            interval_secs: 5.0,
         }
     }
 }
 
+impl ContractPacing {
+    /// The default schedule with the gaps scaled by the difficulty
+    pub fn for_difficulty(multipliers: &DifficultyMultipliers) -> Self {
+        let base = Self::default();
+        Self {
+            interval_secs: base.interval_secs * multipliers.contract_pacing,
+            ..base
+        }
+    }
+
+    /// Beats that have come due after `elapsed` seconds. Beats land on each multiple of the
+    /// interval strictly inside the window.
+    pub fn beats_due(&self, elapsed: f32) -> u32 {
+        if self.interval_secs <= 0.0 {
+            return 0;
+        }
+        let last_beat = ((self.window_secs / self.interval_secs).ceil() as u32).saturating_sub(1);
+        ((elapsed / self.interval_secs).floor() as u32).min(last_beat)
+    }
+}
+
+/// How far into the first-minute schedule we are. Beats are counted rather than detected
+/// per frame, so the frame rate doesn't matter, and a beat blocked by the pending cap
+/// stays owed until there's room.
+#[derive(Resource, Default)]
+struct FirstMinuteProgress {
+    elapsed: f32,
+    beats_spawned: u32,
+}
+
+/// A sink that's just opened up, its welcome offer comes when this runs out
+#[derive(Component, Debug)]
+pub struct WelcomeContractDue(pub f32);
+
+/// Seconds left of a new sink being picked more often for offers
+#[derive(Component, Debug)]
+pub struct RecentlyUnlocked(pub f32);
+
+/// Already had its welcome, unlocking again after losing reputation doesn't get another
+#[derive(Component, Debug)]
+pub struct Welcomed;
+
+/// Pushes a contract's threshold up by `factor` for a while, e.g. a hostile faction leaning
+/// on the buyer. Ignored for a contract that's already raised.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct RaiseContractThreshold {
+    pub contract: Entity,
+    pub factor: f64,
+    pub seconds: f32,
+}
+
+/// On a contract with a raised threshold, taken back off when the seconds run out
+#[derive(Component, Debug)]
+pub struct ThresholdRaised {
+    pub factor: f64,
+    pub seconds: f32,
+}
+
 // --- Plugin and Systems ---
 
 pub struct ContractsPlugin;
@@ -176,72 +418,169 @@ pub struct ContractsPlugin;
 impl Plugin for ContractsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(PreStartup, load_contracts_from_ron)
-            .init_resource::<GameTimer>()
+            .init_resource::<ContractPacing>()
+            .init_resource::<FirstMinuteProgress>()
+            .add_message::<ContractDelivered>()
+            .add_message::<RaiseContractThreshold>()
+            .add_systems(Update, queue_welcome_contracts)
+            .add_systems(Update, sync_contract_pacing.run_if(resource_changed::<crate::difficulty::Difficulty>))
             .add_systems(Update, (
-                first_minute_system,
-                generate_random_pending_contract_system.run_if(on_timer(std::time::Duration::from_secs(20))),
-            ));
+                first_minute_system.run_if(in_state(crate::pause::GameState::Running)),
+                (spawn_welcome_contracts, tick_recently_unlocked).run_if(in_state(crate::pause::GameState::Running)),
+                // state checked before the timer so paused time doesn't count towards it
+                generate_random_pending_contract_system.run_if(
+                    in_state(crate::pause::GameState::Running).and(on_timer(std::time::Duration::from_secs(20))),
+                ),
+            ).run_if(contract_generation_enabled))
+            .add_systems(Update, (
+                expire_pending_contracts,
+                build_contract_reputation,
+                (fail_struggling_contracts, penalize_failed_contracts).chain(),
+                complete_finished_contracts,
+                clear_completed_contracts,
+                (raise_contract_thresholds, restore_raised_thresholds).chain(),
+            ).run_if(in_state(crate::pause::GameState::Running)));
     }
 }
-// um super sus but not a lot of time left go ai
-/// System that runs only during the first 1 minute of the game
+/// Picked on the setup screen or restored by a load, either way the schedule follows
+fn sync_contract_pacing(difficulty: Res<crate::difficulty::Difficulty>, mut pacing: ResMut<ContractPacing>) {
+    *pacing = ContractPacing::for_difficulty(&difficulty.multipliers());
+}
+
+fn contract_generation_enabled(game_mode: Res<crate::game_mode::GameMode>) -> bool {
+    game_mode.contracts_enabled()
+}
+
+/// Spawns the early contracts on the first-minute schedule, catching up on owed beats
+/// one per frame once the pending cap allows
 fn first_minute_system(
-    mut game_timer: ResMut<GameTimer>,
+    mut progress: ResMut<FirstMinuteProgress>,
+    pacing: Res<ContractPacing>,
     time: Res<Time>,
     mut commands: Commands,
     contract_library: Res<ContractLibrary>,
     sinks: Query<(Entity, &Faction, &ReputationLevel, &SinkContracts), (With<Unlocked>, With<SinkBuilding>)>,
     contract_query: Query<&ContractStatus>,
+    descriptions: Query<&ContractDescription>,
+    production: Res<crate::factory::logical::ProductionSummary>,
+    difficulty: Res<crate::difficulty::Difficulty>,
     mut rng: Single<&mut WyRand, With<GlobalRng>>,
 ) {
+    if progress.elapsed < pacing.window_secs {
+        progress.elapsed += time.delta_secs();
+    }
+    if progress.beats_spawned >= pacing.beats_due(progress.elapsed) {
+        return;
+    }
     if contract_query.iter().filter(|&status| *status == ContractStatus::Pending).count() >= MAX_PENDING_CONTRACTS {
-        // Already at max pending contracts
+        // Already at max pending contracts, the beat waits
         return;
     }
-    // Tick the timer
-    game_timer.timer.tick(time.delta());
-
-    // Only run if the first minute hasn't passed yet
-    if !game_timer.timer.finished() {
-        // Example: Generate contracts more frequently during the first minute
-        // This could be any logic you want to run only in the first minute
-        
-        // For demonstration, let's generate a contract every 5 seconds during the first minute
-        if game_timer.timer.elapsed_secs() > 0.0 && game_timer.timer.elapsed_secs() % 5.0 < time.delta_secs() {
-            // Only consider sinks that are not full
-            let sink_entities: Vec<_> = sinks
-                .iter()
-                .filter(|(_, _, _, sink_contracts)| {
-                    sink_contracts.get_current_contracts(&contract_query).len() < MAX_CONTRACTS_PER_SINK
-                })
-                .collect();
-
-            if let Some((sink_entity, faction, reputation, _)) = sink_entities.choose(&mut rng) {
-                if let Some(contract_bundle) = find_and_generate_contract(**faction, **reputation, &contract_library) {
-                    let contract_entity = commands.spawn(contract_bundle).id();
-                    commands.entity(contract_entity).insert(AssociatedWithSink(*sink_entity));
-                    info!("Generated first-minute contract {:?} for sink {:?} at {:.1}s", 
-                          contract_entity, sink_entity, game_timer.timer.elapsed_secs());
-                }
-            }
+
+    // Only consider sinks that are not full
+    let sink_entities: Vec<_> = sinks
+        .iter()
+        .filter(|(_, _, _, sink_contracts)| {
+            sink_contracts.get_current_contracts(&contract_query).len() < MAX_CONTRACTS_PER_SINK
+        })
+        .collect();
+
+    if let Some(&(sink_entity, faction, reputation, sink_contracts)) = sink_entities.choose(&mut rng) {
+        let on_sink = contract_names_on_sink(sink_contracts, &contract_query, &descriptions);
+        if let Some(contract_bundle) = find_and_generate_contract(*faction, *reputation, &contract_library, &production, &on_sink, &difficulty.multipliers(), &mut rng) {
+            let contract_entity = commands.spawn(contract_bundle).id();
+            commands.entity(contract_entity).insert(AssociatedWithSink(sink_entity));
+            progress.beats_spawned += 1;
+            info!("Generated first-minute contract {:?} for sink {:?} at {:.1}s (beat {})",
+                  contract_entity, sink_entity, progress.elapsed, progress.beats_spawned);
+        }
+    }
+}
+
+/// Same removal the lock markers fade on. Only sinks, and only the first time they open
+fn queue_welcome_contracts(
+    mut commands: Commands,
+    mut removed_locked: RemovedComponents<crate::factions::Locked>,
+    sinks: Query<(), (With<SinkBuilding>, Without<Welcomed>)>,
+) {
+    for entity in removed_locked.read() {
+        if sinks.get(entity).is_ok() {
+            commands.entity(entity).insert((
+                WelcomeContractDue(WELCOME_CONTRACT_DELAY_SECONDS),
+                RecentlyUnlocked(RECENT_UNLOCK_SECONDS),
+                Welcomed,
+            ));
         }
     }
-    // After 1 minute, this system will stop doing anything but will still run
-    // You could also remove the system entirely after the timer finishes if needed
 }
 
-/// System to generate a new pending random contract every 2 minutes and link it to a random SinkBuilding
+/// A guaranteed first offer for each newly unlocked sink. It doesn't wait for room under
+/// `MAX_PENDING_CONTRACTS`, a new cluster shouldn't sit empty behind old offers
+fn spawn_welcome_contracts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut due: Query<(Entity, &mut WelcomeContractDue, &Faction, &ReputationLevel, &SinkContracts, &GridPosition), With<Unlocked>>,
+    contract_library: Res<ContractLibrary>,
+    (contract_query, descriptions): (Query<&ContractStatus>, Query<&ContractDescription>),
+    production: Res<crate::factory::logical::ProductionSummary>,
+    difficulty: Res<crate::difficulty::Difficulty>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+) {
+    for (sink_entity, mut welcome, faction, reputation, sink_contracts, position) in due.iter_mut() {
+        welcome.0 -= time.delta_secs();
+        if welcome.0 > 0.0 {
+            continue;
+        }
+        commands.entity(sink_entity).remove::<WelcomeContractDue>();
+        if sink_contracts.get_current_contracts(&contract_query).len() >= MAX_CONTRACTS_PER_SINK {
+            continue;
+        }
+        let on_sink = contract_names_on_sink(sink_contracts, &contract_query, &descriptions);
+        let Some(contract_bundle) = find_and_generate_contract(*faction, *reputation, &contract_library, &production, &on_sink, &difficulty.multipliers(), &mut rng) else {
+            info!("No welcome contract for {:?} sink {:?} at {:?}", faction, sink_entity, reputation);
+            continue;
+        };
+        news_writer.write(crate::events::AddNewsfeedItemEvent {
+            faction: *faction,
+            headline: format!("New {:?} territory sends its first offer: \"{}\"", faction, contract_bundle.description.name),
+            payload: Some(crate::events::NewsPayload::Position(*position)),
+        });
+        let contract_entity = commands.spawn(contract_bundle).id();
+        commands.entity(contract_entity).insert(AssociatedWithSink(sink_entity));
+        info!("Generated welcome contract {:?} for newly unlocked sink {:?}", contract_entity, sink_entity);
+    }
+}
+
+fn tick_recently_unlocked(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut recent: Query<(Entity, &mut RecentlyUnlocked)>,
+) {
+    for (entity, mut recent) in recent.iter_mut() {
+        recent.0 -= time.delta_secs();
+        if recent.0 <= 0.0 {
+            commands.entity(entity).remove::<RecentlyUnlocked>();
+        }
+    }
+}
+
+/// System to generate a new pending random contract every 2 minutes and link it to a random
+/// SinkBuilding, sinks that only just opened picked more often
 fn generate_random_pending_contract_system(
     mut commands: Commands,
     contract_library: Res<ContractLibrary>,
-    sinks: Query<(Entity, &Faction, &ReputationLevel, &SinkContracts), (With<Unlocked>, With<SinkBuilding>)>,
+    sinks: Query<(Entity, &Faction, &ReputationLevel, &SinkContracts, Has<RecentlyUnlocked>), (With<Unlocked>, With<SinkBuilding>)>,
     contract_query: Query<&ContractStatus>,
+    descriptions: Query<&ContractDescription>,
+    production: Res<crate::factory::logical::ProductionSummary>,
+    difficulty: Res<crate::difficulty::Difficulty>,
     mut rng: Single<&mut WyRand, With<GlobalRng>>
 ) {
     // Only consider sinks that are not full
     let sink_entities: Vec<_> = sinks
         .iter()
-        .filter(|(_, _, _, sink_contracts)| {
+        .filter(|(_, _, _, sink_contracts, _)| {
             sink_contracts.get_current_contracts(&contract_query).len() < MAX_CONTRACTS_PER_SINK
         })
         .collect();
@@ -251,11 +590,15 @@ fn generate_random_pending_contract_system(
         return;
     }
 
-    if let Some((sink_entity, faction, reputation, _)) = sink_entities.choose(&mut rng) {
-        // Pick a random contract definition
-        if let Some(contract_bundle) = find_and_generate_contract(**faction, **reputation, &contract_library) {
+    let picked = sink_entities
+        .choose_weighted(&mut rng, |(_, _, _, _, recent)| if *recent { RECENT_UNLOCK_WEIGHT } else { 1.0 })
+        .ok();
+    if let Some(&(sink_entity, faction, reputation, sink_contracts, _)) = picked {
+        // Pick a contract definition, leaning towards what the player already makes
+        let on_sink = contract_names_on_sink(sink_contracts, &contract_query, &descriptions);
+        if let Some(contract_bundle) = find_and_generate_contract(*faction, *reputation, &contract_library, &production, &on_sink, &difficulty.multipliers(), &mut rng) {
             let contract_entity = commands.spawn(contract_bundle).id();
-            commands.entity(contract_entity).insert(AssociatedWithSink(*sink_entity));
+            commands.entity(contract_entity).insert(AssociatedWithSink(sink_entity));
             info!("Generated new pending contract {:?} for sink {:?}", contract_entity, sink_entity);
         } else {
             info!("No suitable contract found for sink {:?} with faction {:?} and reputation {:?}", sink_entity, faction, reputation);
@@ -265,6 +608,216 @@ fn generate_random_pending_contract_system(
     }
 }
 
+/// Counts down pending offers, ones still being walked over by an emissary wait until
+/// they're actually on the board. Accepted contracts have their timeout removed.
+fn expire_pending_contracts(
+    time: Res<Time>,
+    mut contracts: Query<(Entity, &mut ContractStatus, &mut ContractTimeout, &Faction, &ContractDescription, Option<&AssociatedWithSink>), Without<crate::emissary::AwaitingEmissary>>,
+    sink_positions: Query<&GridPosition>,
+    mut reputations: crate::factions::ReputationChanges,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+    mut trace: ResMut<TraceLog>,
+) {
+    for (entity, mut status, mut timeout, faction, description, sink) in contracts.iter_mut() {
+        if *status != ContractStatus::Pending {
+            continue;
+        }
+        timeout.0 -= time.delta_secs();
+        if timeout.0 > 0.0 {
+            continue;
+        }
+        info!("Pending contract {:?} ({}) expired", entity, description.name);
+        trace_sim!(trace, Contract, [entity], "contract {:?} {:?} -> Expired", entity, *status);
+        *status = ContractStatus::Expired;
+        reputations.add(*faction, -EXPIRED_OFFER_REPUTATION_PENALTY, crate::factions::ReputationSource::Contract(sink.map(|s| s.0)));
+        news_writer.write(crate::events::AddNewsfeedItemEvent {
+            faction: *faction,
+            headline: format!("{:?} offer \"{}\" lapsed without an answer", faction, description.name),
+            payload: sink_payload(sink, &sink_positions, *faction),
+        });
+    }
+}
+
+/// Active contracts that keep delivering slowly win the issuing faction over, +1 every
+/// `REPUTATION_TICK_SECONDS` (+2 if exceeding at the time). Falling short starts it over.
+fn build_contract_reputation(
+    time: Res<Time>,
+    mut contracts: Query<(&ContractStatus, &ContractFulfillment, &Faction, &mut ContractReputation, Option<&AssociatedWithSink>)>,
+    mut reputations: crate::factions::ReputationChanges,
+) {
+    for (status, fulfillment, faction, mut reputation, sink) in contracts.iter_mut() {
+        reputation.flash = (reputation.flash - time.delta_secs()).max(0.0);
+        if *status != ContractStatus::Active || fulfillment.status == ContractFulfillmentStatus::Failing {
+            reputation.progress = 0.0;
+            continue;
+        }
+        reputation.progress += time.delta_secs();
+        if reputation.progress < REPUTATION_TICK_SECONDS {
+            continue;
+        }
+        reputation.progress -= REPUTATION_TICK_SECONDS;
+        let gain = if fulfillment.status == ContractFulfillmentStatus::Exceeding { 2 } else { 1 };
+        reputations.add(*faction, gain, crate::factions::ReputationSource::Contract(sink.map(|s| s.0)));
+        reputation.last_gain = gain;
+        reputation.flash = REPUTATION_FLASH_SECONDS;
+        info!("{:?} reputation +{} from a fulfilled contract", faction, gain);
+    }
+}
+
+/// On a contract that just delivered its full volume, shown in the sidebar until it runs out
+#[derive(Component, Debug)]
+pub struct ContractCompleted {
+    pub bonus: i32,
+    pub display: f32,
+}
+
+/// A contract delivered its full volume and paid its bonus
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ContractDelivered {
+    pub contract: Entity,
+    pub faction: Faction,
+    pub bonus: i32,
+}
+
+/// Flips contracts that have delivered their total to Completed and pays them out.
+/// Completed no longer counts towards `MAX_CONTRACTS_PER_SINK` so the slot opens back up.
+fn complete_finished_contracts(
+    mut commands: Commands,
+    mut contracts: Query<(Entity, &mut ContractStatus, &ContractFulfillment, &Faction, &ContractDescription, Option<&AssociatedWithSink>)>,
+    sink_positions: Query<&GridPosition>,
+    mut player: ResMut<crate::player::Player>,
+    mut reputations: crate::factions::ReputationChanges,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+    mut delivered: MessageWriter<ContractDelivered>,
+    mut trace: ResMut<TraceLog>,
+) {
+    for (entity, mut status, fulfillment, faction, description, sink) in contracts.iter_mut() {
+        if *status != ContractStatus::Active || !fulfillment.is_volume_complete() {
+            continue;
+        }
+        *status = ContractStatus::Completed;
+        let bonus = (fulfillment.base_money * COMPLETION_BONUS_SECONDS).round() as i32;
+        trace_sim!(trace, Contract, [entity], "contract {:?} Active -> Completed (bonus ${})", entity, bonus);
+        player.money += bonus;
+        reputations.add(*faction, COMPLETED_CONTRACT_REPUTATION_GAIN, crate::factions::ReputationSource::Contract(sink.map(|s| s.0)));
+        commands.entity(entity).insert(ContractCompleted { bonus, display: COMPLETED_DISPLAY_SECONDS });
+        delivered.write(ContractDelivered { contract: entity, faction: *faction, bonus });
+        info!("Contract {:?} ({}) completed after {:.0} delivered, bonus {}", entity, description.name, fulfillment.delivered, bonus);
+        news_writer.write(crate::events::AddNewsfeedItemEvent {
+            faction: *faction,
+            headline: format!("\"{}\" delivered in full, {:?} pays a ${} bonus", description.name, faction, bonus),
+            payload: sink_payload(sink, &sink_positions, *faction),
+        });
+    }
+}
+
+/// News about a contract points at its sink, or the faction if it's lost track of one
+fn sink_payload(
+    sink: Option<&AssociatedWithSink>,
+    positions: &Query<&GridPosition>,
+    faction: Faction,
+) -> Option<crate::events::NewsPayload> {
+    Some(match sink.and_then(|s| positions.get(s.0).ok()) {
+        Some(position) => crate::events::NewsPayload::Position(*position),
+        None => crate::events::NewsPayload::Faction(faction),
+    })
+}
+
+/// Drops completed contracts once their card has been up long enough
+fn clear_completed_contracts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut completed: Query<(Entity, &mut ContractCompleted)>,
+) {
+    for (entity, mut completed) in completed.iter_mut() {
+        completed.display -= time.delta_secs();
+        if completed.display <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn raise_contract_thresholds(
+    mut commands: Commands,
+    mut raises: MessageReader<RaiseContractThreshold>,
+    mut contracts: Query<&mut ContractFulfillment, Without<ThresholdRaised>>,
+) {
+    let mut raised = HashSet::new();
+    for raise in raises.read() {
+        if !raised.insert(raise.contract) {
+            continue;
+        }
+        let Ok(mut fulfillment) = contracts.get_mut(raise.contract) else { continue };
+        fulfillment.base_threshold *= raise.factor;
+        commands.entity(raise.contract).insert(ThresholdRaised { factor: raise.factor, seconds: raise.seconds });
+        info!("Contract {:?} threshold raised x{:.2} for {:.0}s", raise.contract, raise.factor, raise.seconds);
+    }
+}
+
+fn restore_raised_thresholds(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut contracts: Query<(Entity, &mut ContractFulfillment, &mut ThresholdRaised)>,
+) {
+    for (entity, mut fulfillment, mut raised) in contracts.iter_mut() {
+        raised.seconds -= time.delta_secs();
+        if raised.seconds > 0.0 {
+            continue;
+        }
+        fulfillment.base_threshold /= raised.factor;
+        commands.entity(entity).remove::<ThresholdRaised>();
+        info!("Contract {:?} threshold back to {:.1}", entity, fulfillment.base_threshold);
+    }
+}
+
+/// Active contracts that stay Failing for `FAILING_CONTRACT_SECONDS` are pulled by their
+/// faction. Meeting the threshold for a moment starts the clock over.
+fn fail_struggling_contracts(
+    time: Res<Time>,
+    difficulty: Res<crate::difficulty::Difficulty>,
+    mut contracts: Query<(Entity, &mut ContractStatus, &ContractFulfillment, &mut ContractFailing, &Faction, &ContractDescription, Option<&AssociatedWithSink>)>,
+    sink_positions: Query<&GridPosition>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+) {
+    let limit = FAILING_CONTRACT_SECONDS * difficulty.multipliers().failing_time;
+    for (entity, mut status, fulfillment, mut failing, faction, description, sink) in contracts.iter_mut() {
+        if *status != ContractStatus::Active || fulfillment.status != ContractFulfillmentStatus::Failing {
+            failing.0 = 0.0;
+            continue;
+        }
+        failing.0 += time.delta_secs();
+        if failing.0 < limit {
+            continue;
+        }
+        *status = ContractStatus::Failed;
+        info!("Contract {:?} ({}) failed after {:.0}s short", entity, description.name, failing.0);
+        news_writer.write(crate::events::AddNewsfeedItemEvent {
+            faction: *faction,
+            headline: format!("{:?} pulls \"{}\" after it kept coming up short", faction, description.name),
+            payload: sink_payload(sink, &sink_positions, *faction),
+        });
+    }
+}
+
+/// On a failed contract once its reputation penalty has been taken
+#[derive(Component)]
+pub struct FailurePenalized;
+
+fn penalize_failed_contracts(
+    mut commands: Commands,
+    contracts: Query<(Entity, &ContractStatus, &Faction, Option<&AssociatedWithSink>), (Changed<ContractStatus>, Without<FailurePenalized>)>,
+    mut reputations: crate::factions::ReputationChanges,
+) {
+    for (entity, status, faction, sink) in contracts.iter() {
+        if *status != ContractStatus::Failed {
+            continue;
+        }
+        reputations.add(*faction, -FAILED_CONTRACT_REPUTATION_PENALTY, crate::factions::ReputationSource::Contract(sink.map(|s| s.0)));
+        commands.entity(entity).insert(FailurePenalized);
+        info!("{:?} reputation -{} for failed contract {:?}", faction, FAILED_CONTRACT_REPUTATION_PENALTY, entity);
+    }
+}
+
 // Startup system to load the contracts.ron file
 fn load_contracts_from_ron(mut commands: Commands) {
     // Read the file from the assets folder.
@@ -289,12 +842,17 @@ fn load_contracts_from_ron(mut commands: Commands) {
 }
 
 /// A test system to verify contract generation logic at startup.
-fn test_find_and_generate_contract(library: Res<ContractLibrary>, mut commands: Commands) {
+fn test_find_and_generate_contract(
+    library: Res<ContractLibrary>,
+    mut commands: Commands,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+) {
+    let production = crate::factory::logical::ProductionSummary::default();
     let faction_corporate = Faction::Academia;
     let reputation = ReputationLevel::Neutral;
 
     if let Some(mut contract_bundle) =
-        find_and_generate_contract(faction_corporate, reputation, &library)
+        find_and_generate_contract(faction_corporate, reputation, &library, &production, &[], &DifficultyMultipliers::default(), &mut rng)
     {
         info!(
             "  -> SUCCESS: Found contract '{:?}'", contract_bundle
@@ -307,7 +865,7 @@ fn test_find_and_generate_contract(library: Res<ContractLibrary>, mut commands:
     }
 
     if let Some(mut contract_bundle) =
-        find_and_generate_contract(faction_corporate, reputation, &library)
+        find_and_generate_contract(faction_corporate, reputation, &library, &production, &[], &DifficultyMultipliers::default(), &mut rng)
     {
         info!(
             "  -> SUCCESS: Found contract '{:?}'", contract_bundle
@@ -320,7 +878,7 @@ fn test_find_and_generate_contract(library: Res<ContractLibrary>, mut commands:
     }
 
     if let Some(mut contract_bundle) =
-        find_and_generate_contract(faction_corporate, reputation, &library)
+        find_and_generate_contract(faction_corporate, reputation, &library, &production, &[], &DifficultyMultipliers::default(), &mut rng)
     {
         info!(
             "  -> SUCCESS: Found contract '{:?}'", contract_bundle
@@ -335,16 +893,41 @@ fn test_find_and_generate_contract(library: Res<ContractLibrary>, mut commands:
 
 // --- Contract Generation Logic ---
 
-/// Finds a suitable contract from the library for a given sink.
+/// Weight every candidate gets on top of its coverage, so offers the player can't make yet
+/// still come up now and then
+const STRETCH_CONTRACT_WEIGHT: f32 = 0.15;
+
+/// Picks a contract for a sink at random, weighted towards datasets the player is already
+/// close to producing. Definitions already pending or active on that sink are skipped, told
+/// apart by name since that's what ends up on the card. The difficulty scales what it asks,
+/// pays and how long it waits.
 pub fn find_and_generate_contract(
     sink_faction: Faction,
     sink_reputation: ReputationLevel,
     library: &ContractLibrary,
+    production: &crate::factory::logical::ProductionSummary,
+    on_sink: &[&str],
+    difficulty: &DifficultyMultipliers,
+    rng: &mut WyRand,
 ) -> Option<ContractBundle> {
-    // Find an available contract that matches the sink's faction and reputation
-    let suitable_contract = library.all_contracts().into_iter().find(|c| {
-        c.faction == sink_faction && sink_reputation >= c.reputation
-    })?;
+    // sorted so the same seed and production make the same pick
+    let mut candidates: Vec<&ContractDefinition> = library
+        .all_contracts()
+        .into_iter()
+        .filter(|c| c.faction == sink_faction && sink_reputation >= c.reputation)
+        .filter(|c| !on_sink.contains(&c.name.as_str()))
+        .collect();
+    candidates.sort_by_key(|c| c.id);
+    let weighted: Vec<(&ContractDefinition, f32)> = candidates
+        .into_iter()
+        .map(|c| (c, STRETCH_CONTRACT_WEIGHT + production.coverage(&c.dataset)))
+        .collect();
+    if cfg!(debug_assertions) {
+        for (definition, weight) in &weighted {
+            info!("Contract candidate {} '{}' ({}) weight {:.2}", definition.id, definition.name, definition.dataset, weight);
+        }
+    }
+    let (suitable_contract, _) = weighted.choose_weighted(rng, |(_, weight)| *weight).ok()?;
 
     // Use the found contract definition to create a ContractBundle
     Some(ContractBundle {
@@ -352,18 +935,32 @@ pub fn find_and_generate_contract(
         status: ContractStatus::Pending,
         dataset: suitable_contract.dataset.clone(),
         faction: suitable_contract.faction.clone(),
-        timeout: ContractTimeout(120.0), // Default timeout
+        timeout: ContractTimeout(120.0 * difficulty.failing_time), // Default timeout
         description: ContractDescription {
             name: suitable_contract.name.clone(),
             description: suitable_contract.description.clone(),
         },
         fulfillment_info: ContractFulfillment::new(
-            suitable_contract.base_threshold, 
-            suitable_contract.base_money
-        ),
+            suitable_contract.base_threshold * difficulty.contract_threshold as f64,
+            suitable_contract.base_money * difficulty.contract_money as f64
+        ).with_total_required(suitable_contract.total_required),
     })
 }
 
+/// Names of the contracts a sink already has pending or active
+fn contract_names_on_sink<'a>(
+    sink_contracts: &SinkContracts,
+    contract_query: &Query<&ContractStatus>,
+    descriptions: &'a Query<&ContractDescription>,
+) -> Vec<&'a str> {
+    sink_contracts
+        .get_current_contracts(contract_query)
+        .into_iter()
+        .filter_map(|contract| descriptions.get(contract).ok())
+        .map(|description| description.name.as_str())
+        .collect()
+}
+
 fn get_fulfillment_status(threshold_fraction: f64) -> ContractFulfillmentStatus {
     if threshold_fraction >= 2.0 {
         ContractFulfillmentStatus::Exceeding
@@ -0,0 +1,301 @@
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Everything the player can rebind from the settings panel
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    RotateBuilding,
+    FlipBuilding,
+    /// Pick up the building under the cursor
+    Pipette,
+    ClearSelection,
+    RemoveBuilding,
+    Pause,
+    ToggleOverlay,
+    ToggleSidebar,
+    /// Dev helper, fires a random interactive event
+    TestEvent,
+    QuickSave,
+    QuickLoad,
+    /// Held together with Ctrl
+    Undo,
+    PhotoMode,
+    SpeedUp,
+    SlowDown,
+    ContractsTab,
+    FactionsTab,
+    EventLogTab,
+}
+
+impl InputAction {
+    /// In the order the settings panel lists them
+    pub const ALL: [InputAction; 18] = [
+        InputAction::RotateBuilding,
+        InputAction::FlipBuilding,
+        InputAction::Pipette,
+        InputAction::ClearSelection,
+        InputAction::RemoveBuilding,
+        InputAction::Pause,
+        InputAction::ToggleOverlay,
+        InputAction::ToggleSidebar,
+        InputAction::TestEvent,
+        InputAction::QuickSave,
+        InputAction::QuickLoad,
+        InputAction::Undo,
+        InputAction::PhotoMode,
+        InputAction::SpeedUp,
+        InputAction::SlowDown,
+        InputAction::ContractsTab,
+        InputAction::FactionsTab,
+        InputAction::EventLogTab,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputAction::RotateBuilding => "Rotate",
+            InputAction::FlipBuilding => "Flip",
+            InputAction::Pipette => "Pick building",
+            InputAction::ClearSelection => "Clear selection",
+            InputAction::RemoveBuilding => "Remove",
+            InputAction::Pause => "Pause",
+            InputAction::ToggleOverlay => "Throughput overlay",
+            InputAction::ToggleSidebar => "Contracts sidebar",
+            InputAction::TestEvent => "Test event",
+            InputAction::QuickSave => "Quicksave",
+            InputAction::QuickLoad => "Quickload",
+            InputAction::Undo => "Undo (Ctrl+)",
+            InputAction::PhotoMode => "Photo mode",
+            InputAction::SpeedUp => "Speed up",
+            InputAction::SlowDown => "Slow down",
+            InputAction::ContractsTab => "Contracts tab",
+            InputAction::FactionsTab => "Factions tab",
+            InputAction::EventLogTab => "Event log tab",
+        }
+    }
+
+    fn default_binding(&self) -> Binding {
+        match self {
+            InputAction::RotateBuilding => Binding::Key(KeyCode::KeyR),
+            InputAction::FlipBuilding => Binding::Key(KeyCode::KeyF),
+            InputAction::Pipette => Binding::Key(KeyCode::KeyQ),
+            InputAction::ClearSelection => Binding::Mouse(MouseButton::Right),
+            InputAction::RemoveBuilding => Binding::Mouse(MouseButton::Right),
+            InputAction::Pause => Binding::Key(KeyCode::Space),
+            InputAction::ToggleOverlay => Binding::Key(KeyCode::KeyT),
+            InputAction::ToggleSidebar => Binding::Key(KeyCode::KeyC),
+            InputAction::TestEvent => Binding::Key(KeyCode::KeyE),
+            InputAction::QuickSave => Binding::Key(KeyCode::F5),
+            InputAction::QuickLoad => Binding::Key(KeyCode::F9),
+            InputAction::Undo => Binding::Key(KeyCode::KeyZ),
+            InputAction::PhotoMode => Binding::Key(KeyCode::F12),
+            InputAction::SpeedUp => Binding::Key(KeyCode::Equal),
+            InputAction::SlowDown => Binding::Key(KeyCode::Minus),
+            InputAction::ContractsTab => Binding::Key(KeyCode::F1),
+            InputAction::FactionsTab => Binding::Key(KeyCode::F2),
+            InputAction::EventLogTab => Binding::Key(KeyCode::F3),
+        }
+    }
+
+    /// Clearing the selection and removing never fire together (one needs something selected,
+    /// the other nothing), so they're allowed on the same button
+    pub fn can_share_with(&self, other: InputAction) -> bool {
+        matches!(
+            (self, other),
+            (InputAction::ClearSelection, InputAction::RemoveBuilding)
+                | (InputAction::RemoveBuilding, InputAction::ClearSelection)
+        )
+    }
+}
+
+/// Keys the rebinding UI recognises, by the name they're saved under
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::KeyA, "A"), (KeyCode::KeyB, "B"), (KeyCode::KeyC, "C"), (KeyCode::KeyD, "D"),
+    (KeyCode::KeyE, "E"), (KeyCode::KeyF, "F"), (KeyCode::KeyG, "G"), (KeyCode::KeyH, "H"),
+    (KeyCode::KeyI, "I"), (KeyCode::KeyJ, "J"), (KeyCode::KeyK, "K"), (KeyCode::KeyL, "L"),
+    (KeyCode::KeyM, "M"), (KeyCode::KeyN, "N"), (KeyCode::KeyO, "O"), (KeyCode::KeyP, "P"),
+    (KeyCode::KeyQ, "Q"), (KeyCode::KeyR, "R"), (KeyCode::KeyS, "S"), (KeyCode::KeyT, "T"),
+    (KeyCode::KeyU, "U"), (KeyCode::KeyV, "V"), (KeyCode::KeyW, "W"), (KeyCode::KeyX, "X"),
+    (KeyCode::KeyY, "Y"), (KeyCode::KeyZ, "Z"),
+    (KeyCode::Digit0, "0"), (KeyCode::Digit1, "1"), (KeyCode::Digit2, "2"), (KeyCode::Digit3, "3"),
+    (KeyCode::Digit4, "4"), (KeyCode::Digit5, "5"), (KeyCode::Digit6, "6"), (KeyCode::Digit7, "7"),
+    (KeyCode::Digit8, "8"), (KeyCode::Digit9, "9"),
+    (KeyCode::F1, "F1"), (KeyCode::F2, "F2"), (KeyCode::F3, "F3"), (KeyCode::F4, "F4"),
+    (KeyCode::F5, "F5"), (KeyCode::F6, "F6"), (KeyCode::F7, "F7"), (KeyCode::F8, "F8"),
+    (KeyCode::F9, "F9"), (KeyCode::F10, "F10"), (KeyCode::F11, "F11"), (KeyCode::F12, "F12"),
+    (KeyCode::Space, "Space"), (KeyCode::Tab, "Tab"), (KeyCode::Enter, "Enter"),
+    (KeyCode::Backspace, "Backspace"), (KeyCode::Delete, "Delete"), (KeyCode::Insert, "Insert"),
+    (KeyCode::Home, "Home"), (KeyCode::End, "End"), (KeyCode::PageUp, "PageUp"), (KeyCode::PageDown, "PageDown"),
+    (KeyCode::ArrowUp, "Up"), (KeyCode::ArrowDown, "Down"), (KeyCode::ArrowLeft, "Left"), (KeyCode::ArrowRight, "Right"),
+    (KeyCode::Backquote, "`"), (KeyCode::Minus, "-"), (KeyCode::Equal, "="), (KeyCode::BracketLeft, "["),
+    (KeyCode::BracketRight, "]"), (KeyCode::Backslash, "\\"), (KeyCode::Semicolon, ";"), (KeyCode::Quote, "'"),
+    (KeyCode::Comma, ","), (KeyCode::Period, "."), (KeyCode::Slash, "/"),
+    (KeyCode::ShiftLeft, "LShift"), (KeyCode::ShiftRight, "RShift"),
+    (KeyCode::AltLeft, "LAlt"), (KeyCode::AltRight, "RAlt"),
+];
+
+const MOUSE_NAMES: &[(MouseButton, &str)] = &[
+    (MouseButton::Left, "Left mouse"),
+    (MouseButton::Right, "Right mouse"),
+    (MouseButton::Middle, "Middle mouse"),
+    (MouseButton::Back, "Mouse back"),
+    (MouseButton::Forward, "Mouse forward"),
+];
+
+/// One key or mouse button. Saved by name, bevy's input types aren't serde here.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(into = "String", try_from = "String")]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// None for keys the table above doesn't know, those can't be saved so they aren't offered
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Binding::Key(key) => KEY_NAMES.iter().find(|(k, _)| k == key).map(|(_, n)| *n),
+            Binding::Mouse(button) => MOUSE_NAMES.iter().find(|(b, _)| b == button).map(|(_, n)| *n),
+        }
+    }
+
+    pub fn just_pressed(&self, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self {
+            Binding::Key(key) => keys.just_pressed(*key),
+            Binding::Mouse(button) => mouse.just_pressed(*button),
+        }
+    }
+
+    pub fn pressed(&self, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self {
+            Binding::Key(key) => keys.pressed(*key),
+            Binding::Mouse(button) => mouse.pressed(*button),
+        }
+    }
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        KEY_NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(k, _)| Binding::Key(*k))
+            .or_else(|| MOUSE_NAMES.iter().find(|(_, n)| *n == name).map(|(b, _)| Binding::Mouse(*b)))
+            .ok_or_else(|| format!("unknown key or button '{}'", name))
+    }
+}
+
+/// Which key or button each action is on
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct InputBindings {
+    bindings: HashMap<InputAction, Binding>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            bindings: InputAction::ALL.iter().map(|a| (*a, a.default_binding())).collect(),
+        }
+    }
+}
+
+impl InputBindings {
+    pub fn get(&self, action: InputAction) -> Binding {
+        self.bindings.get(&action).copied().unwrap_or_else(|| action.default_binding())
+    }
+
+    /// The other action already on `binding`, if putting `action` there would clash with it
+    pub fn conflict(&self, action: InputAction, binding: Binding) -> Option<InputAction> {
+        InputAction::ALL
+            .into_iter()
+            .find(|other| *other != action && !action.can_share_with(*other) && self.get(*other) == binding)
+    }
+
+    /// Refuses (and hands back the clashing action) rather than silently unbinding something else
+    pub fn rebind(&mut self, action: InputAction, binding: Binding) -> Result<(), InputAction> {
+        if let Some(other) = self.conflict(action, binding) {
+            return Err(other);
+        }
+        self.bindings.insert(action, binding);
+        Ok(())
+    }
+
+    /// Every action's binding in panel order, the shape kept in the user config
+    pub fn to_list(&self) -> Vec<(InputAction, Binding)> {
+        InputAction::ALL.iter().map(|a| (*a, self.get(*a))).collect()
+    }
+
+    /// Saved bindings over the defaults, so actions added since the file was written still get a key
+    pub fn from_list(list: &[(InputAction, Binding)]) -> Self {
+        let mut bindings = Self::default();
+        for (action, binding) in list {
+            bindings.bindings.insert(*action, *binding);
+        }
+        bindings
+    }
+}
+
+/// Keyboard, mouse and the bindings together, for systems that only check actions
+#[derive(SystemParam)]
+pub struct Controls<'w> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    bindings: Res<'w, InputBindings>,
+}
+
+impl Controls<'_> {
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.bindings.get(action).just_pressed(&self.keys, &self.mouse)
+    }
+
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.bindings.get(action).pressed(&self.keys, &self.mouse)
+    }
+
+    /// For modifier checks that aren't bindable
+    pub fn keys(&self) -> &ButtonInput<KeyCode> {
+        &self.keys
+    }
+}
+
+/// Bindings come back from the user config before anything reads input
+fn load_input_bindings(config: Res<crate::user_config::UserConfig>, mut bindings: ResMut<InputBindings>) {
+    if let Some(saved) = &config.bindings {
+        *bindings = InputBindings::from_list(saved);
+    }
+}
+
+fn persist_input_bindings(bindings: Res<InputBindings>, mut config: ResMut<crate::user_config::UserConfig>) {
+    let list = bindings.to_list();
+    if config.bindings.as_ref() != Some(&list) {
+        config.bindings = Some(list);
+    }
+}
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputBindings>()
+            .add_systems(PreStartup, load_input_bindings)
+            .add_systems(Update, persist_input_bindings.run_if(resource_changed::<InputBindings>.and(not(resource_added::<InputBindings>))));
+    }
+}
@@ -0,0 +1,324 @@
+use bevy::prelude::*;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::assets::GameAssets;
+use crate::contracts::ContractStatus;
+use crate::player::Player;
+use crate::save::{SaveGameRequest, CRASH_SAVE_SLOT};
+use crate::trace::TraceLog;
+use crate::ui::interactive_event::ScalableText;
+
+pub const CRASH_REPORT_DIR: &str = "crash_reports";
+/// How many trace entries go into a report
+const CRASH_TRACE_ENTRIES: usize = 50;
+
+/// Copy of the state worth having in a crash report. The panic hook can't reach the
+/// world, so this is refreshed from a system and read from the hook.
+#[derive(Debug, Clone, Default)]
+pub struct CrashSnapshot {
+    pub game_state: String,
+    pub money: i32,
+    pub contract_count: usize,
+    pub world_seed: Option<u64>,
+    pub trace_tail: Vec<String>,
+}
+
+static SNAPSHOT: Mutex<Option<CrashSnapshot>> = Mutex::new(None);
+/// Report written by the hook that the overlay hasn't shown yet
+static PENDING_REPORT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub fn format_crash_report(
+    timestamp: u64,
+    payload: &str,
+    location: &str,
+    thread: &str,
+    backtrace: &str,
+    snapshot: Option<&CrashSnapshot>,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "LD58 crash report");
+    let _ = writeln!(out, "time: {}", timestamp);
+    let _ = writeln!(out, "thread: {}", thread);
+    let _ = writeln!(out, "location: {}", location);
+    let _ = writeln!(out, "panic: {}", payload);
+    let _ = writeln!(out);
+    match snapshot {
+        Some(snapshot) => {
+            let _ = writeln!(out, "state: {}", snapshot.game_state);
+            let _ = writeln!(out, "money: {}", snapshot.money);
+            let _ = writeln!(out, "contracts: {}", snapshot.contract_count);
+            let _ = writeln!(
+                out,
+                "world seed: {}",
+                snapshot.world_seed.map_or("unknown".to_string(), |s| s.to_string())
+            );
+            let _ = writeln!(out);
+            let _ = writeln!(out, "last {} trace entries:", snapshot.trace_tail.len());
+            for line in snapshot.trace_tail.iter() {
+                let _ = writeln!(out, "  {}", line);
+            }
+        }
+        None => {
+            let _ = writeln!(out, "no state snapshot (crashed during startup)");
+        }
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "backtrace:");
+    let _ = writeln!(out, "{}", backtrace);
+    out
+}
+
+fn payload_string(info: &std::panic::PanicHookInfo) -> String {
+    if let Some(s) = info.payload().downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    }
+}
+
+/// Writes a crash report for every panic, then hands over to the default hook so the
+/// console output stays the same
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let location = info
+            .location()
+            .map_or("unknown".to_string(), |l| format!("{}:{}", l.file(), l.line()));
+        let thread = std::thread::current().name().unwrap_or("unnamed").to_string();
+        let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+        // try_lock so a panic while snapshotting can't deadlock the hook
+        let snapshot = SNAPSHOT.try_lock().ok().and_then(|s| s.clone());
+
+        let report = format_crash_report(
+            timestamp,
+            &payload_string(info),
+            &location,
+            &thread,
+            &backtrace,
+            snapshot.as_ref(),
+        );
+        let path = PathBuf::from(CRASH_REPORT_DIR).join(format!("crash_{}.txt", timestamp));
+        let written = std::fs::create_dir_all(CRASH_REPORT_DIR)
+            .and_then(|_| std::fs::write(&path, report));
+        match written {
+            Ok(()) => {
+                eprintln!("Crash report written to {}", path.display());
+                if let Ok(mut pending) = PENDING_REPORT.try_lock() {
+                    *pending = Some(path);
+                }
+            }
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+
+        previous(info);
+    }));
+}
+
+fn update_crash_snapshot(
+    state: Res<State<crate::pause::GameState>>,
+    player: Res<Player>,
+    contracts: Query<&ContractStatus>,
+    trace: Res<TraceLog>,
+    seed: Option<Res<crate::world_gen::WorldSeed>>,
+) {
+    let skip = trace.len().saturating_sub(CRASH_TRACE_ENTRIES);
+    let snapshot = CrashSnapshot {
+        game_state: format!("{:?}", state.get()),
+        money: player.money,
+        contract_count: contracts.iter().count(),
+        world_seed: seed.map(|s| s.0),
+        trace_tail: trace
+            .entries()
+            .skip(skip)
+            .map(|e| format!("[{:>9.2}] {:?}: {}", e.time, e.category, e.message))
+            .collect(),
+    };
+    if let Ok(mut slot) = SNAPSHOT.lock() {
+        *slot = Some(snapshot);
+    }
+}
+
+/// Runs a system with panics caught. A panic disables that system for the rest of the
+/// session (with the overlay explaining it) instead of taking the whole run down.
+#[cfg(feature = "crash_guard")]
+pub fn guarded<M, S>(name: &'static str, system: S) -> impl FnMut(&mut World)
+where
+    S: IntoSystem<(), (), M> + Copy + Send + Sync + 'static,
+{
+    let mut disabled = false;
+    move |world: &mut World| {
+        if disabled {
+            return;
+        }
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            world.run_system_cached(system)
+        }));
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("System {} failed to run: {}", name, e),
+            Err(_) => {
+                disabled = true;
+                error!("System {} panicked and has been disabled for this session", name);
+                world.resource_mut::<DisabledSystems>().0.push(name);
+            }
+        }
+    }
+}
+
+/// Without the guard the wrapped system just runs directly
+#[cfg(not(feature = "crash_guard"))]
+pub fn guarded<M, S>(name: &'static str, system: S) -> impl FnMut(&mut World)
+where
+    S: IntoSystem<(), (), M> + Copy + Send + Sync + 'static,
+{
+    move |world: &mut World| {
+        if let Err(e) = world.run_system_cached(system) {
+            warn!("System {} failed to run: {}", name, e);
+        }
+    }
+}
+
+/// Systems knocked out by a caught panic
+#[derive(Resource, Default)]
+pub struct DisabledSystems(pub Vec<&'static str>);
+
+#[derive(Component)]
+pub struct CrashOverlay;
+
+#[derive(Component)]
+pub struct CrashQuitButton;
+
+#[derive(Component)]
+pub struct CrashContinueButton;
+
+/// Shows the overlay once the hook has written a report we survived
+fn show_crash_overlay(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    disabled: Res<DisabledSystems>,
+    existing: Query<(), With<CrashOverlay>>,
+) {
+    let Some(path) = PENDING_REPORT.try_lock().ok().and_then(|mut p| p.take()) else {
+        return;
+    };
+    if !existing.is_empty() {
+        return;
+    }
+
+    let detail = if disabled.0.is_empty() {
+        String::new()
+    } else {
+        format!("\nDisabled for this session: {}", disabled.0.join(", "))
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(30.0),
+                left: Val::Percent(30.0),
+                width: Val::Percent(40.0),
+                padding: UiRect::all(Val::Vw(1.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.6),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.15, 0.05, 0.05, 0.97)),
+            GlobalZIndex(2000),
+            CrashOverlay,
+            crate::ui::BlocksWorldClicks,
+        ))
+        .with_children(|overlay| {
+            overlay.spawn((
+                Text::new("Something went wrong"),
+                game_assets.text_font(24.0),
+                ScalableText::from_vw(1.6),
+                TextColor(Color::srgb(1.0, 0.4, 0.35)),
+            ));
+            overlay.spawn((
+                Text::new(format!(
+                    "A crash report was written to {}. Please include it if you report the bug.{}",
+                    path.display(),
+                    detail
+                )),
+                game_assets.text_font(14.0),
+                ScalableText::from_vw(0.9),
+                TextColor(Color::WHITE),
+            ));
+            overlay
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Vw(0.6),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (label, is_quit) in [("Continue", false), ("Save and quit", true)] {
+                        let mut button = row.spawn((
+                            Node {
+                                padding: UiRect::axes(Val::Vw(0.6), Val::Vw(0.25)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                            Interaction::None,
+                        ));
+                        if is_quit {
+                            button.insert(CrashQuitButton);
+                        } else {
+                            button.insert(CrashContinueButton);
+                        }
+                        button.with_children(|b| {
+                            b.spawn((
+                                Text::new(label),
+                                game_assets.text_font(14.0),
+                                ScalableText::from_vw(0.9),
+                                TextColor(Color::WHITE),
+                            ));
+                        });
+                    }
+                });
+        });
+}
+
+fn handle_crash_overlay_buttons(
+    mut commands: Commands,
+    quit_query: Query<&Interaction, (Changed<Interaction>, With<CrashQuitButton>)>,
+    continue_query: Query<&Interaction, (Changed<Interaction>, With<CrashContinueButton>)>,
+    overlay_query: Query<Entity, With<CrashOverlay>>,
+    (mut save, mut exit): (MessageWriter<SaveGameRequest>, MessageWriter<AppExit>),
+    mut quitting: Local<bool>,
+) {
+    // the save goes out a frame ahead of the exit so it's handled whichever order these run in
+    if *quitting {
+        exit.write(AppExit::Success);
+        return;
+    }
+    if quit_query.iter().any(|i| *i == Interaction::Pressed) {
+        info!("Saving to the {} slot before quitting", CRASH_SAVE_SLOT);
+        save.write(SaveGameRequest { slot: CRASH_SAVE_SLOT.to_string() });
+        *quitting = true;
+    }
+    if continue_query.iter().any(|i| *i == Interaction::Pressed) {
+        for overlay in overlay_query.iter() {
+            commands.entity(overlay).despawn();
+        }
+    }
+}
+
+pub struct CrashPlugin;
+
+impl Plugin for CrashPlugin {
+    fn build(&self, app: &mut App) {
+        install_panic_hook();
+        app.init_resource::<DisabledSystems>()
+            .add_systems(Last, update_crash_snapshot.run_if(bevy::time::common_conditions::on_timer(std::time::Duration::from_secs(1))))
+            .add_systems(Update, (show_crash_overlay, handle_crash_overlay_buttons));
+    }
+}
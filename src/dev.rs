@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use crate::game_mode::GameMode;
+use crate::pause::GameState;
+
+/// Developer tools (trace viewer etc.), enabled by launching with `--dev` or in sandbox mode
+#[derive(Resource, Default)]
+pub struct DevMode(pub bool);
+
+impl DevMode {
+    pub fn from_args() -> Self {
+        Self(std::env::args().any(|arg| arg == "--dev"))
+    }
+}
+
+/// The setup screen decides sandbox, so dev mode follows whatever mode the run starts in
+pub fn follow_game_mode(game_mode: Res<GameMode>, mut dev_mode: ResMut<DevMode>) {
+    dev_mode.0 = DevMode::from_args().0 || game_mode.sandbox;
+}
+
+pub struct DevPlugin;
+
+impl Plugin for DevPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DevMode::from_args())
+            .add_systems(OnExit(GameState::Setup), follow_game_mode);
+    }
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::pause::GameState;
+use crate::player::Player;
+
+/// What every multiplier scales, so 1.0 across the board is Normal
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct DifficultyMultipliers {
+    /// Throughput a contract asks for
+    pub contract_threshold: f32,
+    /// Money a contract pays
+    pub contract_money: f32,
+    /// Gap before a random event can come round again, lower is more often
+    pub event_cooldown: f32,
+    /// Contract timeouts, how long a failing contract lasts and the creditors' clock, lower is less time
+    pub failing_time: f32,
+    pub starting_money: f32,
+    /// Every source world gen places
+    pub source_throughput: f32,
+    /// Gap between the first minute's contract offers, lower is more often
+    pub contract_pacing: f32,
+}
+
+impl Default for DifficultyMultipliers {
+    fn default() -> Self {
+        Self {
+            contract_threshold: 1.0,
+            contract_money: 1.0,
+            event_cooldown: 1.0,
+            failing_time: 1.0,
+            starting_money: 1.0,
+            source_throughput: 1.0,
+            contract_pacing: 1.0,
+        }
+    }
+}
+
+/// Picked on the setup screen before world gen, or with `--difficulty <easy|normal|hard>`
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Custom(DifficultyMultipliers),
+}
+
+impl Difficulty {
+    /// The presets in the order the setup screen cycles through them, Custom comes last
+    pub const PRESETS: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn multipliers(&self) -> DifficultyMultipliers {
+        match self {
+            Difficulty::Easy => DifficultyMultipliers {
+                contract_threshold: 0.75,
+                contract_money: 1.25,
+                event_cooldown: 1.5,
+                failing_time: 1.5,
+                starting_money: 2.0,
+                source_throughput: 1.25,
+                contract_pacing: 0.8,
+            },
+            Difficulty::Normal => DifficultyMultipliers::default(),
+            Difficulty::Hard => DifficultyMultipliers {
+                contract_threshold: 1.3,
+                contract_money: 0.8,
+                event_cooldown: 0.6,
+                failing_time: 0.6,
+                starting_money: 0.5,
+                source_throughput: 0.8,
+                contract_pacing: 1.3,
+            },
+            Difficulty::Custom(multipliers) => *multipliers,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Custom(_) => "Custom",
+        }
+    }
+
+    /// Lets a run skip the setup screen, like `--sandbox`. Unknown names are ignored with a warning
+    pub fn from_args() -> Option<Self> {
+        let args: Vec<String> = std::env::args().collect();
+        let name = args.iter().position(|arg| arg == "--difficulty").and_then(|i| args.get(i + 1))?;
+        let found = Self::PRESETS.into_iter().find(|d| d.name().eq_ignore_ascii_case(name));
+        if found.is_none() {
+            warn!("Unknown difficulty '{}', picking one on the setup screen instead", name);
+        }
+        found
+    }
+}
+
+/// Set when `--difficulty` already picked one, the setup screen goes straight to Running
+#[derive(Resource)]
+pub struct SkipSetupScreen;
+
+/// Runs once on leaving Setup, alongside world gen
+fn apply_starting_money(difficulty: Res<Difficulty>, mut player: ResMut<Player>) {
+    player.money = (crate::player::STARTING_MONEY as f32 * difficulty.multipliers().starting_money).round() as i32;
+    info!("Starting a {} run with ${}", difficulty.name(), player.money);
+}
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        match Difficulty::from_args() {
+            Some(difficulty) => {
+                app.insert_resource(difficulty).insert_resource(SkipSetupScreen);
+            }
+            None => {
+                app.init_resource::<Difficulty>();
+            }
+        }
+        app.add_systems(OnExit(GameState::Setup), apply_starting_money);
+    }
+}
@@ -0,0 +1,167 @@
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+use crate::contracts::{AssociatedWithSink, ContractFulfillment, ContractStatus};
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::buildings::{Tile, Tiles};
+use crate::factory::logical::LogicalLink;
+
+/// Lifetime money credited by a contract, accumulated at payout
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct ContractEarnings(pub f64);
+
+/// What the player paid to build this entity (wires and player-placed buildings)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BuildCost(pub i32);
+
+/// The bits of `BuildingData` needed after placement, since the `Arc<dyn Building>` isn't kept
+#[derive(Component, Debug, Clone)]
+pub struct BuildingInfo {
+    pub name: String,
+    pub cost: i32,
+    pub upkeep: i32,
+}
+
+impl BuildingInfo {
+    pub fn from_data(data: &crate::factory::buildings::buildings::BuildingData) -> Self {
+        Self {
+            name: data.name.clone(),
+            cost: data.cost,
+            upkeep: data.upkeep_per_sec,
+        }
+    }
+}
+
+/// Upkeep couldn't be paid, the building stops processing until money recovers
+#[derive(Component, Debug)]
+pub struct Unpowered;
+
+/// Estimated cost of the infrastructure feeding a sink building.
+///
+/// Method: walk upstream from the sink through its logical links, adding the build cost
+/// of every wire segment on each link and of every building reached, then continue from
+/// that building's own inputs. Each entity is counted once per sink, but upstream
+/// infrastructure shared between several sinks is counted in full for each of them, so
+/// the totals don't add up to what was actually spent. Good enough for comparing contracts.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct SinkInfraCost(pub i32);
+
+/// Set when any logical link appears, changes or goes away
+#[derive(Resource, Default)]
+pub struct InfraCostDirty(pub bool);
+
+/// Whether a changed link actually changed the topology, rather than just being re-written
+/// with the same segments and endpoints by chain reassembly
+#[derive(Component, Clone, PartialEq)]
+struct LinkTopology {
+    source: Entity,
+    links: Vec<Entity>,
+}
+
+fn mark_topology_changes(
+    mut commands: Commands,
+    links: Query<(Entity, &LogicalLink, Option<&LinkTopology>), Changed<LogicalLink>>,
+    mut removed: RemovedComponents<LogicalLink>,
+    mut dirty: ResMut<InfraCostDirty>,
+) {
+    for (entity, link, previous) in links.iter() {
+        let topology = LinkTopology { source: link.source, links: link.links.clone() };
+        if previous != Some(&topology) {
+            commands.entity(entity).insert(topology);
+            dirty.0 = true;
+        }
+    }
+    for entity in removed.read() {
+        if let Ok(mut entity_commands) = commands.get_entity(entity) {
+            entity_commands.remove::<LinkTopology>();
+        }
+        dirty.0 = true;
+    }
+}
+
+/// Recomputes every sink's cached infra cost after a topology change
+fn recompute_sink_infra_costs(
+    mut commands: Commands,
+    mut dirty: ResMut<InfraCostDirty>,
+    sinks: Query<(Entity, &Tiles), With<SinkBuilding>>,
+    tiles_of: Query<&Tiles>,
+    tile_parent: Query<&Tile>,
+    links: Query<&LogicalLink>,
+    costs: Query<&BuildCost>,
+) {
+    if !dirty.0 {
+        return;
+    }
+    dirty.0 = false;
+
+    for (sink, sink_tiles) in sinks.iter() {
+        let mut seen: HashSet<Entity> = HashSet::new();
+        let mut stack: Vec<Entity> = sink_tiles.iter().copied().collect();
+        let mut total = 0;
+        while let Some(tile) = stack.pop() {
+            let Ok(link) = links.get(tile) else { continue };
+            for &segment in link.links.iter() {
+                if seen.insert(segment) {
+                    total += costs.get(segment).map_or(0, |c| c.0);
+                }
+            }
+            let building = tile_parent.get(link.source).map_or(link.source, |t| t.0);
+            if seen.insert(building) {
+                total += costs.get(building).map_or(0, |c| c.0);
+                if let Ok(inputs) = tiles_of.get(building) {
+                    stack.extend(inputs.iter().copied());
+                }
+            }
+        }
+        commands.entity(sink).insert(SinkInfraCost(total));
+    }
+}
+
+/// A contract's share of its sink's infra cost, split by the threshold each active
+/// contract at that sink asks for. Thresholds don't move while a contract runs, so the
+/// share holds still where the live throughput would jitter tick to tick.
+pub fn contract_infra_share(
+    contract: Entity,
+    sink: Entity,
+    sink_cost: i32,
+    contracts: &Query<(Entity, &ContractStatus, &ContractFulfillment, &AssociatedWithSink)>,
+) -> f64 {
+    let siblings: Vec<(Entity, f64)> = contracts
+        .iter()
+        .filter(|(_, status, _, associated)| **status == ContractStatus::Active && associated.0 == sink)
+        .map(|(entity, _, fulfillment, _)| (entity, fulfillment.base_threshold))
+        .collect();
+    if siblings.is_empty() {
+        return sink_cost as f64;
+    }
+    let total: f64 = siblings.iter().map(|(_, t)| t).sum();
+    let share = if total > 0.0 {
+        siblings.iter().find(|(e, _)| *e == contract).map_or(0.0, |(_, t)| t / total)
+    } else {
+        1.0 / siblings.len() as f64
+    };
+    sink_cost as f64 * share
+}
+
+/// Earnings per unit of infra, `None` while nothing has been attributed
+pub fn return_ratio(earned: f64, infra: f64) -> Option<f64> {
+    (infra > 0.0).then(|| earned / infra)
+}
+
+pub fn return_ratio_color(ratio: Option<f64>) -> Color {
+    match ratio {
+        Some(r) if r >= 3.0 => Color::srgb(0.3, 0.9, 0.3),
+        Some(r) if r >= 1.0 => Color::srgb(0.95, 0.85, 0.25),
+        Some(_) => Color::srgb(1.0, 0.3, 0.3),
+        None => Color::srgb(0.7, 0.7, 0.7),
+    }
+}
+
+pub struct EconomicsPlugin;
+
+impl Plugin for EconomicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InfraCostDirty>()
+            .add_systems(PostUpdate, (mark_topology_changes, recompute_sink_infra_costs).chain());
+    }
+}
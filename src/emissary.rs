@@ -0,0 +1,313 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_prng::WyRand;
+use bevy_rand::prelude::GlobalRng;
+use rand::Rng;
+
+use crate::assets::{GameAssets, IconSize};
+use crate::contracts::{AssociatedWithSink, Contract, ContractStatus};
+use crate::factions::Faction;
+use crate::factory::building_visuals::z_layers;
+use crate::grid::{Grid, GridPosition, WorldMap};
+use crate::save::PlacedBuilding;
+use crate::settings::Settings;
+use crate::world_gen::in_world_bounds;
+
+/// Chance a new pending offer is walked over by an emissary instead of just appearing
+const EMISSARY_CHANCE: f64 = 0.35;
+/// Roughly how long the walk in takes
+const EMISSARY_WALK_SECONDS: f32 = 20.0;
+/// Ignored this long and the offer is delivered silently
+const EMISSARY_PATIENCE_SECONDS: f32 = 60.0;
+/// Close enough to the factory to count as arrived, in cells
+const ARRIVAL_DISTANCE: i64 = 2;
+const DEPART_SECONDS: f32 = 1.5;
+const HIGHLIGHT_SECONDS: f32 = 6.0;
+/// Click radius around the emissary, world px
+const CLICK_RADIUS: f32 = 28.0;
+
+/// On a pending contract while an emissary is still bringing it. Hidden from the sidebar.
+#[derive(Component)]
+pub struct AwaitingEmissary;
+
+/// Pulses the contract's card for a few seconds after an emissary hands it over
+#[derive(Component)]
+pub struct ContractHighlight(pub f32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmissaryState {
+    Walking,
+    Departing,
+}
+
+/// Decorative NPC carrying an offer. Never goes in the `WorldMap`.
+#[derive(Component)]
+pub struct Emissary {
+    pub faction: Faction,
+    pub contract: Entity,
+    pub cell: I64Vec2,
+    pub previous: Option<I64Vec2>,
+    pub target: I64Vec2,
+    pub step_seconds: f32,
+    pub step_progress: f32,
+    pub age: f32,
+    pub state: EmissaryState,
+    pub depart_timer: f32,
+}
+
+/// Next cell of a greedy walk towards `target`: the free neighbour closest to it, not
+/// doubling straight back. Walks straight through when boxed in, they're only decorative.
+pub fn greedy_step(cell: I64Vec2, previous: Option<I64Vec2>, target: I64Vec2, world_map: &WorldMap) -> I64Vec2 {
+    let neighbours = [I64Vec2::X, I64Vec2::NEG_X, I64Vec2::Y, I64Vec2::NEG_Y].map(|d| cell + d);
+    let free = neighbours
+        .iter()
+        .copied()
+        .filter(|n| Some(*n) != previous && in_world_bounds(*n) && !world_map.contains_key(&GridPosition(*n)))
+        .min_by_key(|n| (*n - target).abs().element_sum());
+    let delta = (target - cell).signum();
+    let straight = if delta.x != 0 { I64Vec2::new(delta.x, 0) } else { I64Vec2::new(0, delta.y) };
+    free.unwrap_or(cell + straight)
+}
+
+/// Middle of everything the player has built, or the origin before they've built anything
+fn factory_centroid(buildings: &Query<&GridPosition, With<PlacedBuilding>>) -> I64Vec2 {
+    let (sum, count) = buildings
+        .iter()
+        .fold((I64Vec2::ZERO, 0), |(sum, count), p| (sum + p.0, count + 1));
+    if count == 0 { I64Vec2::ZERO } else { sum / count }
+}
+
+/// Walks out from the centroid in a random direction until the unlocked area ends
+fn edge_of_unlocked_area(from: I64Vec2, world_map: &WorldMap, rng: &mut WyRand) -> I64Vec2 {
+    let angle = rng.random_range(0.0..std::f32::consts::TAU);
+    let direction = Vec2::from_angle(angle);
+    let mut edge = from;
+    for distance in 1..200 {
+        let cell = from + (direction * distance as f32).round().as_i64vec2();
+        if !in_world_bounds(cell) {
+            break;
+        }
+        if world_map.contains_key(&GridPosition(cell)) {
+            // past the buildings around the factory, a blocked cell is the lock boundary
+            if distance > 12 {
+                break;
+            }
+            continue;
+        }
+        edge = cell;
+    }
+    edge
+}
+
+/// Sends an emissary with some of the new pending offers, one per faction at a time
+pub fn dispatch_emissaries(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    game_assets: Res<GameAssets>,
+    grid: Res<Grid>,
+    world_map: Res<WorldMap>,
+    new_contracts: Query<(Entity, &ContractStatus, &Faction), (Added<Contract>, With<AssociatedWithSink>)>,
+    emissaries: Query<&Emissary>,
+    buildings: Query<&GridPosition, With<PlacedBuilding>>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+) {
+    if !settings.emissaries {
+        return;
+    }
+    let mut busy: Vec<Faction> = emissaries.iter().map(|e| e.faction).collect();
+    for (contract, status, faction) in new_contracts.iter() {
+        if *status != ContractStatus::Pending || busy.contains(faction) {
+            continue;
+        }
+        if !rng.random_bool(EMISSARY_CHANCE) {
+            continue;
+        }
+        busy.push(*faction);
+        let target = factory_centroid(&buildings);
+        let start = edge_of_unlocked_area(target, &world_map, &mut rng);
+        let distance = (start - target).abs().element_sum().max(1);
+        info!("{:?} emissary setting out from {:?} with contract {:?}", faction, start, contract);
+
+        commands.entity(contract).insert(AwaitingEmissary);
+        let mut color = game_assets.faction_color(*faction);
+        color.set_alpha(0.9);
+        commands
+            .spawn((
+                Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(24.0)),
+                    ..default()
+                },
+                Transform::from_translation(grid.grid_to_world_center(&GridPosition(start)).extend(z_layers::BADGE)),
+                Emissary {
+                    faction: *faction,
+                    contract,
+                    cell: start,
+                    previous: None,
+                    target,
+                    step_seconds: EMISSARY_WALK_SECONDS / distance as f32,
+                    step_progress: 0.0,
+                    age: 0.0,
+                    state: EmissaryState::Walking,
+                    depart_timer: DEPART_SECONDS,
+                },
+            ))
+            .with_children(|emissary| {
+                if let Some((atlas_id, index)) = game_assets.faction_icon(*faction, IconSize::Small) {
+                    let (image, layout) = game_assets.get_atlas(atlas_id);
+                    emissary.spawn((
+                        Sprite {
+                            image,
+                            texture_atlas: Some(TextureAtlas { layout, index }),
+                            custom_size: Some(Vec2::splat(20.0)),
+                            ..default()
+                        },
+                        Transform::from_xyz(0.0, 0.0, 0.1),
+                    ));
+                }
+            });
+    }
+}
+
+/// Hands the offer over. `highlight` is false for the silent fallback.
+fn deliver(commands: &mut Commands, emissary: &mut Emissary, highlight: bool) {
+    if let Ok(mut contract) = commands.get_entity(emissary.contract) {
+        contract.remove::<AwaitingEmissary>();
+        if highlight {
+            contract.insert(ContractHighlight(HIGHLIGHT_SECONDS));
+        }
+    }
+    emissary.state = EmissaryState::Departing;
+}
+
+/// Steps the walk a cell at a time with a little bob, delivering on arrival or giving up
+/// and delivering silently once patience runs out
+pub fn walk_emissaries(
+    mut commands: Commands,
+    time: Res<Time>,
+    grid: Res<Grid>,
+    world_map: Res<WorldMap>,
+    mut emissaries: Query<(Entity, &mut Emissary, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut emissary, mut transform, mut sprite) in emissaries.iter_mut() {
+        emissary.age += time.delta_secs();
+        match emissary.state {
+            EmissaryState::Walking => {
+                if emissary.age >= EMISSARY_PATIENCE_SECONDS {
+                    info!("{:?} emissary gave up waiting, offer delivered quietly", emissary.faction);
+                    deliver(&mut commands, &mut emissary, false);
+                    continue;
+                }
+                if (emissary.cell - emissary.target).abs().max_element() <= ARRIVAL_DISTANCE {
+                    info!("{:?} emissary arrived", emissary.faction);
+                    deliver(&mut commands, &mut emissary, true);
+                    continue;
+                }
+                let next = greedy_step(emissary.cell, emissary.previous, emissary.target, &world_map);
+                emissary.step_progress += time.delta_secs() / emissary.step_seconds;
+                if emissary.step_progress >= 1.0 {
+                    emissary.step_progress = 0.0;
+                    emissary.previous = Some(emissary.cell);
+                    emissary.cell = next;
+                }
+                let from = grid.grid_to_world_center(&GridPosition(emissary.cell));
+                let to = grid.grid_to_world_center(&GridPosition(next));
+                let bob = (emissary.age * 8.0).sin().abs() * 4.0;
+                let position = from.lerp(to, emissary.step_progress) + Vec2::new(0.0, bob);
+                transform.translation = position.extend(transform.translation.z);
+            }
+            EmissaryState::Departing => {
+                emissary.depart_timer -= time.delta_secs();
+                let alpha = (emissary.depart_timer / DEPART_SECONDS).clamp(0.0, 1.0);
+                sprite.color.set_alpha(alpha);
+                transform.translation.y += time.delta_secs() * 20.0;
+                if emissary.depart_timer <= 0.0 {
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Clicking an emissary on its way in takes the offer early
+pub fn click_emissaries(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut emissaries: Query<(&mut Emissary, &Transform)>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|p| camera.viewport_to_world_2d(camera_transform, p).ok())
+    else {
+        return;
+    };
+    for (mut emissary, transform) in emissaries.iter_mut() {
+        if emissary.state == EmissaryState::Walking && transform.translation.xy().distance(cursor) <= CLICK_RADIUS {
+            info!("{:?} emissary greeted early", emissary.faction);
+            deliver(&mut commands, &mut emissary, true);
+        }
+    }
+}
+
+/// Emissaries whose contract went away (rejected, expired) just leave
+pub fn drop_orphaned_emissaries(
+    mut emissaries: Query<&mut Emissary>,
+    contracts: Query<(), With<Contract>>,
+) {
+    for mut emissary in emissaries.iter_mut() {
+        if emissary.state == EmissaryState::Walking && !contracts.contains(emissary.contract) {
+            emissary.state = EmissaryState::Departing;
+        }
+    }
+}
+
+/// Turning emissaries off in settings delivers whatever is still on the way
+pub fn disable_emissaries(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut emissaries: Query<&mut Emissary>,
+) {
+    if settings.emissaries {
+        return;
+    }
+    for mut emissary in emissaries.iter_mut() {
+        if emissary.state == EmissaryState::Walking {
+            deliver(&mut commands, &mut emissary, false);
+        }
+    }
+}
+
+pub fn tick_contract_highlights(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut highlights: Query<(Entity, &mut ContractHighlight)>,
+) {
+    for (entity, mut highlight) in highlights.iter_mut() {
+        highlight.0 -= time.delta_secs();
+        if highlight.0 <= 0.0 {
+            commands.entity(entity).remove::<ContractHighlight>();
+        }
+    }
+}
+
+pub struct EmissaryPlugin;
+
+impl Plugin for EmissaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            dispatch_emissaries,
+            drop_orphaned_emissaries,
+            disable_emissaries.run_if(resource_changed::<Settings>),
+            click_emissaries,
+            walk_emissaries,
+        ).chain().run_if(in_state(crate::pause::GameState::Running)))
+            .add_systems(Update, tick_contract_highlights);
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::interactive_events::*;
+use crate::factions::FactionReputations;
+use crate::player::Player;
+
+/// Insolvent this long and the creditors call
+pub const CREDITORS_CALL_SECONDS: f32 = 30.0;
+/// Still insolvent this long after they called and it's the final notice
+pub const FINAL_NOTICE_SECONDS: f32 = 60.0;
+/// A loan costs this much on top of what was borrowed, paid back with it
+pub const LOAN_INTEREST_PERCENT: i32 = 25;
+/// Share of a building's price that `SellRandomBuildings` gets back
+pub const SELL_REFUND_FRACTION: f32 = 0.5;
+
+const CREDITORS_CALL_EVENT: &str = "creditors_call";
+const FINAL_NOTICE_EVENT: &str = "final_notice";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Loan {
+    /// Still owed, interest included
+    pub remaining: i32,
+    pub per_second: i32,
+}
+
+/// How long the player's been under and what they owe. `Player.bankruptcy_stage` says how
+/// far along the chain they are: 1 once the creditors have called, 2 at the final notice,
+/// `GAME_OVER_BANKRUPTCY_STAGE` when it's over
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DebtState {
+    /// Seconds insolvent since the last stage started
+    pub insolvent_seconds: f32,
+    pub loans: Vec<Loan>,
+}
+
+impl DebtState {
+    pub fn take_loan(&mut self, principal: i32, per_second: i32) {
+        self.loans.push(Loan {
+            remaining: principal + principal * LOAN_INTEREST_PERCENT / 100,
+            per_second: per_second.max(1),
+        });
+    }
+
+    /// What the loans take out every second, less on the last second of one
+    pub fn repayment_per_second(&self) -> i32 {
+        self.loans.iter().map(|loan| loan.per_second.min(loan.remaining)).sum()
+    }
+
+    pub fn total_owed(&self) -> i32 {
+        self.loans.iter().map(|loan| loan.remaining).sum()
+    }
+
+    /// Seconds until the next stage of the chain, None when it isn't running. Both waits get
+    /// the difficulty's `failing_time`
+    pub fn next_stage_in(&self, bankruptcy_stage: u32, failing_time: f32) -> Option<f32> {
+        let threshold = failing_time * match bankruptcy_stage {
+            0 if self.insolvent_seconds > 0.0 => CREDITORS_CALL_SECONDS,
+            1 => FINAL_NOTICE_SECONDS,
+            _ => return None,
+        };
+        Some((threshold - self.insolvent_seconds).max(0.0))
+    }
+}
+
+fn show_debt_event(
+    event_id: &str,
+    library: &InteractiveEventLibrary,
+    context: &GameContext,
+    event_writer: &mut MessageWriter<ShowInteractiveEvent>,
+) -> bool {
+    match library.get_event_by_id(event_id) {
+        Some(event) if context.check_requirements(&event.requirements, event.repeatable, &event.id) => {
+            event_writer.write(ShowInteractiveEvent(event.into()));
+            true
+        }
+        _ => {
+            warn!("Debt event '{}' missing or not eligible, skipping it", event_id);
+            false
+        }
+    }
+}
+
+/// Counts how long the player's been insolvent. The creditors call after
+/// `CREDITORS_CALL_SECONDS`, and if that doesn't fix it the final notice comes
+/// `FINAL_NOTICE_SECONDS` later. Getting back in the black at any point calls it all off
+pub fn bankruptcy_update_system(
+    time: Res<Time>,
+    mut player: ResMut<Player>,
+    mut debt: ResMut<DebtState>,
+    mut event_writer: MessageWriter<ShowInteractiveEvent>,
+    library: Res<InteractiveEventLibrary>,
+    factions: Res<FactionReputations>,
+    (event_state, difficulty): (Res<EventState>, Res<crate::difficulty::Difficulty>),
+    (buildings, production): (Res<BuildingCounts>, Res<crate::factory::logical::ProductionSummary>),
+    mut bankrupt: MessageWriter<WentBankrupt>,
+) {
+    // lost is lost
+    if player.bankruptcy_stage >= crate::audio::GAME_OVER_BANKRUPTCY_STAGE {
+        return;
+    }
+    if !(player.money <= 0 && player.net_income < 0) {
+        if player.bankruptcy_stage > 0 {
+            info!("Back in the black, creditors called off");
+        }
+        debt.insolvent_seconds = 0.0;
+        player.bankruptcy_stage = 0;
+        return;
+    }
+
+    debt.insolvent_seconds += time.delta_secs();
+    player.money = 0;
+    let failing_time = difficulty.multipliers().failing_time;
+    let Some(remaining) = debt.next_stage_in(player.bankruptcy_stage, failing_time) else { return };
+    if remaining > 0.0 {
+        return;
+    }
+    debt.insolvent_seconds = 0.0;
+    player.bankruptcy_stage += 1;
+    let event_id = if player.bankruptcy_stage == 1 {
+        warn!("Insolvent for {:.0}s, the creditors are calling", CREDITORS_CALL_SECONDS * failing_time);
+        bankrupt.write(WentBankrupt);
+        CREDITORS_CALL_EVENT
+    } else {
+        warn!("Still insolvent, final notice");
+        FINAL_NOTICE_EVENT
+    };
+    let context = GameContext {
+        player: &player,
+        factions: &factions,
+        event_state: &event_state,
+        buildings: &buildings,
+        production: &production,
+    };
+    let shown = show_debt_event(event_id, &library, &context, &mut event_writer);
+    // the final notice's only way out is its Bankruptcy consequence, no notice means no way out
+    if !shown && event_id == FINAL_NOTICE_EVENT {
+        player.bankruptcy_stage = crate::audio::GAME_OVER_BANKRUPTCY_STAGE;
+    }
+}
+
+/// Runs on the money tick after upkeep. Pays what it can of every loan, the full amount
+/// still shows in `net_income` so a short balance reads as insolvent
+pub fn repay_loans(
+    mut player: ResMut<Player>,
+    mut debt: ResMut<DebtState>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+) {
+    if debt.loans.is_empty() {
+        return;
+    }
+    let due = debt.repayment_per_second();
+    let mut available = player.money.max(0);
+    for loan in debt.loans.iter_mut() {
+        let paid = loan.per_second.min(loan.remaining).min(available);
+        loan.remaining -= paid;
+        available -= paid;
+        player.money -= paid;
+    }
+    player.net_income -= due;
+
+    let before = debt.loans.len();
+    debt.loans.retain(|loan| loan.remaining > 0);
+    if debt.loans.len() < before {
+        info!("Loan paid off, {} left", debt.loans.len());
+        news_writer.write(crate::events::AddNewsfeedItemEvent {
+            faction: crate::factions::Faction::default(),
+            headline: "Loan paid off".to_string(),
+            payload: None,
+        });
+    }
+}
@@ -1,53 +1,11 @@
-/// System to update bankruptcy state and trigger bankruptcy events
-pub fn bankruptcy_update_system(
-    time: Res<Time>,
-    mut player: ResMut<Player>,
-    mut event_writer: MessageWriter<ShowInteractiveEvent>,
-    library: Res<InteractiveEventLibrary>,
-    factions: Res<FactionReputations>,
-    event_state: Res<EventState>,
-) {
-    // Only tick timer if player is bankrupt
-    if player.money <= 0 && player.net_income < 0 {
-        player.bankruptcy_timer += time.delta().as_secs_f32();
-        // Clamp money to 0
-        player.money = 0;
-        // If timer exceeds threshold, advance stage and trigger event
-        let stage_duration = 30.0; // seconds per stage
-        if player.bankruptcy_timer >= stage_duration {
-            player.bankruptcy_stage += 1;
-            player.bankruptcy_timer = 0.0;
-            // Find best bankruptcy event for this stage
-            let context = GameContext {
-                player: &player,
-                factions: &factions,
-                event_state: &event_state,
-            };
-            // Event id convention: "bankruptcy_stage_{n}" or similar
-            let stage_id = format!("bankruptcy_stage_{}", player.bankruptcy_stage);
-            // Find all eligible manual events for this stage
-            let mut candidates: Vec<_> = library.events.iter()
-                .filter(|e| e.id == stage_id && e.trigger_mode == EventTriggerMode::Manual && context.check_requirements(&e.requirements, e.repeatable, &e.id))
-                .collect();
-            // Pick the highest-priority event (largest value)
-            candidates.sort_by_key(|e| -(e.priority));
-            if let Some(event) = candidates.first() {
-                let event_data: InteractiveEventData = (*event).into();
-                event_writer.write(ShowInteractiveEvent(event_data));
-            }
-        }
-    } else {
-        // Not bankrupt, reset timer and stage
-        player.bankruptcy_timer = 0.0;
-        player.bankruptcy_stage = 0;
-    }
-}
 use bevy::prelude::*;
 use rand::Rng;
 
 use super::interactive_events::*;
-use crate::factions::FactionReputations;
+use crate::factions::{FactionReputations, ReputationChanges, ReputationSource};
 use crate::player::Player;
+use crate::trace::TraceLog;
+use crate::trace_sim;
 
 /// Timer resource for random event triggering
 #[derive(Resource)]
@@ -71,6 +29,8 @@ pub fn handle_manual_event_triggers(
     player: Res<Player>,
     factions: Res<FactionReputations>,
     event_state: Res<EventState>,
+    buildings: Res<BuildingCounts>,
+    production: Res<crate::factory::logical::ProductionSummary>,
     mut show_event: MessageWriter<ShowInteractiveEvent>,
 ) {
     for trigger in trigger_events.read() {
@@ -79,6 +39,8 @@ pub fn handle_manual_event_triggers(
             player: &player,
             factions: &factions,
             event_state: &event_state,
+            buildings: &buildings,
+            production: &production,
         };
 
         // Check if event exists and can be triggered
@@ -105,7 +67,10 @@ pub fn random_event_trigger_system(
     player: Res<Player>,
     factions: Res<FactionReputations>,
     event_state: Res<EventState>,
+    buildings: Res<BuildingCounts>,
+    production: Res<crate::factory::logical::ProductionSummary>,
     queued_events: Res<crate::ui::interactive_event::QueuedEvents>,
+    difficulty: Res<crate::difficulty::Difficulty>,
     mut event_writer: MessageWriter<ShowInteractiveEvent>,
 ) {
     if timer.timer.tick(time.delta()).just_finished() {
@@ -114,6 +79,8 @@ pub fn random_event_trigger_system(
             player: &player,
             factions: &factions,
             event_state: &event_state,
+            buildings: &buildings,
+            production: &production,
         };
 
         // Get queued event IDs to filter them out
@@ -122,7 +89,12 @@ pub fn random_event_trigger_system(
             .collect();
 
         // Get all eligible random events with their weights
-        let eligible = library.get_eligible_random_events(&context, time.elapsed_secs_f64(), &queued_ids);
+        let eligible = library.get_eligible_random_events(
+            &context,
+            time.elapsed_secs_f64(),
+            &queued_ids,
+            difficulty.multipliers().event_cooldown,
+        );
         
         if eligible.is_empty() {
             return;
@@ -151,6 +123,8 @@ pub fn forced_event_checker_system(
     player: Res<Player>,
     factions: Res<FactionReputations>,
     event_state: Res<EventState>,
+    buildings: Res<BuildingCounts>,
+    production: Res<crate::factory::logical::ProductionSummary>,
     queued_events: Res<crate::ui::interactive_event::QueuedEvents>,
     existing_modals: Query<(), With<crate::ui::interactive_event::InteractiveEventModal>>,
     mut event_writer: MessageWriter<ShowInteractiveEvent>,
@@ -165,6 +139,8 @@ pub fn forced_event_checker_system(
         player: &player,
         factions: &factions,
         event_state: &event_state,
+        buildings: &buildings,
+        production: &production,
     };
 
     // Get all forced events that should trigger
@@ -178,27 +154,121 @@ pub fn forced_event_checker_system(
     }
 }
 
+/// The player's own buildings, what events get to tear down or sell off
+type RemovableBuildings<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static crate::save::PlacedBuilding, &'static crate::grid::GridPosition),
+    Without<crate::factory::buildings::Undeletable>,
+>;
+
+/// Marks up to `count` random buildings for a sell-off, returns each one's name, price and
+/// position. Wires are placed buildings too but selling one isn't much of an event
+fn sell_random_buildings(
+    buildings: &RemovableBuildings,
+    count: u32,
+    commands: &mut Commands,
+) -> Vec<(String, i32, crate::grid::GridPosition)> {
+    let mut candidates: Vec<_> = buildings
+        .iter()
+        .filter(|(_, placed, _)| !matches!(placed.descriptor, crate::save::BuildingDescriptor::Link { .. }))
+        .collect();
+    let mut rng = rand::rng();
+    let mut removed = Vec::new();
+    for _ in 0..count {
+        if candidates.is_empty() {
+            break;
+        }
+        let (entity, placed, position) = candidates.swap_remove(rng.random_range(0..candidates.len()));
+        // not the player's doing, so no getting it back with Ctrl+Z
+        commands
+            .entity(entity)
+            .insert((crate::factory::MarkedForRemoval, crate::factory::undo::NotUndoable));
+        let data = placed.descriptor.building().data();
+        removed.push((data.name, data.cost, *position));
+    }
+    removed
+}
+
+/// Severity a destroy event leaves on a wire it hits, enough to break the chain
+const DESTROYED_WIRE_SEVERITY: u8 = 2;
+/// Camera trauma when a destroy event lands
+const DESTROY_SHAKE: f32 = 0.7;
+
+/// Hits up to `count` random buildings for a destroy event. Wires go through `damage_segment`
+/// and heal or get repaired, anything bigger is torn down. Returns each hit's name and position
+fn destroy_random_buildings(
+    buildings: &RemovableBuildings,
+    damaged: &Query<&crate::factory::damage::Damaged>,
+    count: u32,
+    commands: &mut Commands,
+) -> Vec<(String, crate::grid::GridPosition)> {
+    let mut candidates: Vec<_> = buildings.iter().collect();
+    let mut rng = rand::rng();
+    let mut hit = Vec::new();
+    for _ in 0..count {
+        if candidates.is_empty() {
+            break;
+        }
+        let (entity, placed, position) = candidates.swap_remove(rng.random_range(0..candidates.len()));
+        let name = placed.descriptor.building().data().name;
+        if matches!(placed.descriptor, crate::save::BuildingDescriptor::Link { .. }) {
+            crate::factory::damage::damage_segment(commands, entity, DESTROYED_WIRE_SEVERITY, damaged.get(entity).ok());
+            hit.push((format!("{} (damaged)", name), *position));
+        } else {
+            // not the player's doing, so no getting it back with Ctrl+Z
+            commands
+                .entity(entity)
+                .insert((crate::factory::MarkedForRemoval, crate::factory::undo::NotUndoable));
+            hit.push((name, *position));
+        }
+    }
+    hit
+}
+
 /// System that handles player choice consequences
 pub fn handle_player_choice_system(
     time: Res<Time>,
     mut choice_events: MessageReader<PlayerChoiceEvent>,
     library: Res<InteractiveEventLibrary>,
     mut player: ResMut<Player>,
-    mut factions: ResMut<FactionReputations>,
+    mut factions: ReputationChanges,
     mut event_state: ResMut<EventState>,
+    mut market: ResMut<crate::market::MarketPrices>,
+    mut trace: ResMut<TraceLog>,
+    mut commands: Commands,
+    (world_map, cluster_cells, mut debt): (Res<crate::grid::WorldMap>, Res<crate::world_gen::ClusterCells>, ResMut<super::DebtState>),
+    (buildings, damaged): (RemovableBuildings, Query<&crate::factory::damage::Damaged>),
+    sinks: Query<(Entity, &crate::factions::Faction), (With<crate::factory::buildings::sink::SinkBuilding>, With<crate::factions::Unlocked>)>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+    mut ignored_events: MessageReader<EventIgnored>,
+    (mut bankrupt, mut shake): (MessageWriter<WentBankrupt>, MessageWriter<crate::camera::ShakeScreen>),
 ) {
-    for choice_event in choice_events.read() {
+    // an ignored bubble goes through the same path, with the event's ignored consequences as the choice
+    let picked: Vec<(String, Option<usize>)> = choice_events
+        .read()
+        .map(|c| (c.event_id.clone(), Some(c.choice_index)))
+        .chain(ignored_events.read().map(|e| (e.event_id.clone(), None)))
+        .collect();
+    for (event_id, choice_index) in picked {
         // Find the event by ID
-        let event = library.events.iter().find(|e| e.id == choice_event.event_id);
+        let event = library.events.iter().find(|e| e.id == event_id);
         
         if let Some(event) = event {
             // Mark event as completed
             event_state.complete_event(event.id.clone(), time.elapsed_secs_f64());
 
             // Get the chosen option
-            if let Some(choice) = event.choices.get(choice_event.choice_index) {
+            let consequences = match choice_index {
+                Some(index) => event.choices.get(index).map(|choice| &choice.consequences),
+                None => Some(&event.ignored_consequences),
+            };
+            if let Some(consequences) = consequences {
                 // Apply all consequences
-                for consequence in &choice.consequences {
+                for consequence in consequences {
+                    // hidden only matters to the tooltip
+                    let consequence = consequence.revealed();
+                    trace_sim!(trace, Consequence, [], "event {} choice {:?}: {:?}", event.id, choice_index, consequence);
                     match consequence {
                         ConsequenceType::UnlockEvent(event_id) => {
                             event_state.unlock_event(event_id.clone());
@@ -209,7 +279,7 @@ pub fn handle_player_choice_system(
                             info!("Money changed by: {}, new balance: {}", amount, player.money);
                         }
                         ConsequenceType::ModifyReputation { faction, amount } => {
-                            factions.add(*faction, *amount);
+                            factions.add(*faction, *amount, ReputationSource::Event);
                             info!("Reputation with {:?} changed by: {}", faction, amount);
                         }
                         ConsequenceType::CompleteEvent(event_id) => {
@@ -218,16 +288,93 @@ pub fn handle_player_choice_system(
                         }
                         ConsequenceType::Bankruptcy => {
                             player.money = 0;
-                            warn!("Player went bankrupt!");
+                            warn!("Player went bankrupt, run over");
+                            // the debt chain already sent this when the creditors called
+                            if player.bankruptcy_stage == 0 {
+                                bankrupt.write(WentBankrupt);
+                            }
+                            player.bankruptcy_stage = crate::audio::GAME_OVER_BANKRUPTCY_STAGE;
                         }
                         ConsequenceType::UnlockContract(contract_id) => {
                             //TODO: implement contract unlocking
                         }
+                        ConsequenceType::MarketShock { data_type, delta } => {
+                            market.shock(*data_type, *delta);
+                            info!("Market shock on {:?} by {}, now {}", data_type, delta, market.get(*data_type));
+                        }
+                        ConsequenceType::DestroyRandomBuilding { count } => {
+                            let picked = destroy_random_buildings(&buildings, &damaged, *count, &mut commands);
+                            let first_position = picked.first().map(|(_, position)| *position);
+                            let destroyed: Vec<String> = picked.into_iter().map(|(name, _)| name).collect();
+                            info!("Event {} destroyed {:?}", event.id, destroyed);
+                            if !destroyed.is_empty() {
+                                shake.write(crate::camera::ShakeScreen(DESTROY_SHAKE));
+                                news_writer.write(crate::events::AddNewsfeedItemEvent {
+                                    faction: event.faction.unwrap_or_default(),
+                                    headline: format!("Sabotage! Lost {}", destroyed.join(", ")),
+                                    // where the first one stood, the rest are usually nearby
+                                    payload: first_position.map(crate::events::NewsPayload::Position),
+                                });
+                            }
+                        }
+                        ConsequenceType::SpawnSource { faction, dataset, near_player } => {
+                            let Some(cell) = crate::world_gen::find_event_source_cell(*near_player, &world_map, &cluster_cells, &mut rand::rng()) else {
+                                warn!("No free cell for the source from event {}", event.id);
+                                continue;
+                            };
+                            crate::world_gen::spawn_event_source(cell, dataset.clone(), *faction, factions.get_level(*faction), &mut commands);
+                            info!("Event {} spawned a {:?} source at {:?}", event.id, faction, cell);
+                            news_writer.write(crate::events::AddNewsfeedItemEvent {
+                                faction: *faction,
+                                headline: format!("New {} data source opens up at ({}, {})", dataset, cell.x, cell.y),
+                                payload: Some(crate::events::NewsPayload::Position(crate::grid::GridPosition(cell))),
+                            });
+                        }
+                        ConsequenceType::DisableSinksForSeconds { faction, seconds } => {
+                            let mut disabled = 0;
+                            for (entity, sink_faction) in sinks.iter() {
+                                if sink_faction == faction {
+                                    commands.entity(entity).insert(crate::factory::buildings::sink::SinkDisabled(*seconds));
+                                    disabled += 1;
+                                }
+                            }
+                            info!("Event {} disabled {} {:?} sink(s) for {}s", event.id, disabled, faction, seconds);
+                            if disabled > 0 {
+                                news_writer.write(crate::events::AddNewsfeedItemEvent {
+                                    faction: *faction,
+                                    headline: format!("{:?} sinks offline for {:.0} seconds", faction, seconds),
+                                    payload: Some(crate::events::NewsPayload::Faction(*faction)),
+                                });
+                            }
+                        }
+                        ConsequenceType::SellRandomBuildings { count } => {
+                            let sold = sell_random_buildings(&buildings, *count, &mut commands);
+                            let refund: i32 = sold
+                                .iter()
+                                .map(|(_, cost, _)| (*cost as f32 * super::SELL_REFUND_FRACTION).round() as i32)
+                                .sum();
+                            player.money += refund;
+                            info!("Event {} sold {} building(s) for ${}", event.id, sold.len(), refund);
+                            if !sold.is_empty() {
+                                news_writer.write(crate::events::AddNewsfeedItemEvent {
+                                    faction: event.faction.unwrap_or_default(),
+                                    headline: format!("Fire sale: {} building(s) sold for ${}", sold.len(), refund),
+                                    payload: sold.first().map(|(_, _, position)| crate::events::NewsPayload::Position(*position)),
+                                });
+                            }
+                        }
+                        ConsequenceType::TakeLoan { principal, per_second } => {
+                            player.money += principal;
+                            debt.take_loan(*principal, *per_second);
+                            info!("Took a ${} loan at ${}/s, ${} owed in total", principal, per_second, debt.total_owed());
+                        }
+                        // revealed() already took the wrapper off
+                        ConsequenceType::Hidden(_) => {}
                     }
                 }
             }
         } else {
-            warn!("Could not find event with ID: {}", choice_event.event_id);
+            warn!("Could not find event with ID: {}", event_id);
         }
     }
 }
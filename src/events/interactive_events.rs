@@ -4,8 +4,10 @@ use std::collections::{HashMap, HashSet};
 
 use crate::factions::{Faction, FactionReputations, ReputationLevel};
 use crate::player::Player;
+use crate::factory::logical::{BasicDataType, DataAttribute, Dataset, ProductionSummary};
 
 pub const RANDOM_EVENT_COOLDOWN_SECONDS: f32 = 60.0; // 2 minutes
+pub const BUBBLE_EXPIRY_SECONDS: f32 = 120.0;
 
 /// Requirements that must be met for an event to trigger
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -33,7 +35,13 @@ pub enum Requirements {
     EventUnlocked(String),
     /// Event with this ID must NOT be completed
     EventNotCompleted(String),
-    ContractFulfilled(i32)
+    ContractFulfilled(i32),
+    /// At least this many of a building placed, by its shop name ("Aggregator", "De-Identifier")
+    MinBuildingCount { building_name: String, count: u32 },
+    /// Something's putting out this data type at this rate or more, per second
+    ProducesDataType { data_type: BasicDataType, min_rate: f32 },
+    /// Some data with this attribute on it has been moving lately
+    HasAttributeInProduction(DataAttribute),
 }
 
 /// How an event should be triggered
@@ -59,10 +67,38 @@ pub enum ConsequenceType {
     ModifyReputation { faction: Faction, amount: i32 },
     /// Mark a specific event as completed
     CompleteEvent(String),
-    /// Trigger bankruptcy (game over?)
+    /// The run is lost, what the final notice of the debt chain ends in
     Bankruptcy,
-    UnlockContract(i32)
+    UnlockContract(i32),
+    /// Jolt the market price multiplier of a data type
+    MarketShock { data_type: BasicDataType, delta: f32 },
+    /// Tear down this many of the player's buildings at random, undeletable ones are safe
+    DestroyRandomBuilding { count: u32 },
+    /// Drop a new source into the world, around the start area if near_player
+    SpawnSource { faction: Faction, dataset: Dataset, near_player: bool },
+    /// That faction's sinks take no data for a while
+    DisableSinksForSeconds { faction: Faction, seconds: f32 },
+    /// Sell this many of the player's buildings at random for part of what they cost
+    SellRandomBuildings { count: u32 },
+    /// Money now, paid back with interest a bit every second until it's gone
+    TakeLoan { principal: i32, per_second: i32 },
+    /// Applied like the one inside, but the choice tooltip only says "???".
+    /// Written around any other consequence, e.g. `Hidden(ModifyMoney(-500))`
+    Hidden(Box<ConsequenceType>),
+}
+
+impl ConsequenceType {
+    /// The consequence that actually happens, with any Hidden wrapper taken off
+    pub fn revealed(&self) -> &ConsequenceType {
+        match self {
+            ConsequenceType::Hidden(inner) => inner.revealed(),
+            other => other,
+        }
+    }
 
+    pub fn is_hidden(&self) -> bool {
+        matches!(self, ConsequenceType::Hidden(_))
+    }
 }
 
 /// A single choice option within an interactive event
@@ -90,7 +126,17 @@ pub struct InteractiveEventItem {
     #[serde(default)]
     pub priority: i32,
     #[serde(default)]
-    pub popup_urgency: bool
+    pub popup_urgency: bool,
+    /// How long a queued bubble waits before the event is missed
+    #[serde(default = "default_bubble_expiry")]
+    pub expiry_seconds: f32,
+    /// Applied when the bubble runs out without being clicked
+    #[serde(default)]
+    pub ignored_consequences: Vec<ConsequenceType>,
+}
+
+pub fn default_bubble_expiry() -> f32 {
+    BUBBLE_EXPIRY_SECONDS
 }
 
 /// Message sent when player makes a choice in an interactive event
@@ -100,6 +146,17 @@ pub struct PlayerChoiceEvent {
     pub choice_index: usize,
 }
 
+/// The player just went under, from the bankruptcy timer reaching its first stage or an
+/// event's `Bankruptcy` consequence
+#[derive(Message, Clone, Debug)]
+pub struct WentBankrupt;
+
+/// Message sent when a queued event's bubble runs out before the player clicks it
+#[derive(Message, Clone, Debug)]
+pub struct EventIgnored {
+    pub event_id: String,
+}
+
 /// Resource containing all interactive events as a flat list
 #[derive(Resource, Debug)]
 pub struct InteractiveEventLibrary {
@@ -151,7 +208,14 @@ impl InteractiveEventLibrary {
     }
 
     /// Get all random events that meet their requirements
-    pub fn get_eligible_random_events(&self, context: &GameContext, current_time: f64, queued_event_ids: &[String]) -> Vec<(usize, f32)> {
+    /// `cooldown_scale` is the difficulty's, on top of `RANDOM_EVENT_COOLDOWN_SECONDS`
+    pub fn get_eligible_random_events(
+        &self,
+        context: &GameContext,
+        current_time: f64,
+        queued_event_ids: &[String],
+        cooldown_scale: f32,
+    ) -> Vec<(usize, f32)> {
         self.random_event_indices
             .iter()
             .filter_map(|&idx| {
@@ -161,7 +225,7 @@ impl InteractiveEventLibrary {
                         // Check if this event was completed recently
                         if let Some(&last_time) = context.event_state.last_completion_time.get(&event.id) {
                             let time_since_completion = current_time - last_time;
-                            if time_since_completion < RANDOM_EVENT_COOLDOWN_SECONDS as f64 {
+                            if time_since_completion < (RANDOM_EVENT_COOLDOWN_SECONDS * cooldown_scale) as f64 {
                                 return None; // Event is on cooldown
                             }
                         }
@@ -236,11 +300,42 @@ impl EventState {
     }
 }
 
+/// How many of each building the player has down, by name. Only recounted when one's
+/// placed, changed or removed
+#[derive(Resource, Default, Debug)]
+pub struct BuildingCounts(pub HashMap<String, u32>);
+
+impl BuildingCounts {
+    pub fn get(&self, building_name: &str) -> u32 {
+        self.0
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(building_name))
+            .map_or(0, |(_, count)| *count)
+    }
+}
+
+pub fn count_placed_buildings(
+    mut counts: ResMut<BuildingCounts>,
+    placed: Query<&crate::save::PlacedBuilding>,
+    changed: Query<(), Changed<crate::save::PlacedBuilding>>,
+    mut removed: RemovedComponents<crate::save::PlacedBuilding>,
+) {
+    if changed.is_empty() && removed.read().count() == 0 {
+        return;
+    }
+    counts.0.clear();
+    for building in placed.iter() {
+        *counts.0.entry(building.descriptor.building().data().name).or_insert(0) += 1;
+    }
+}
+
 /// Context for checking event requirements
 pub struct GameContext<'a> {
     pub player: &'a Player,
     pub factions: &'a FactionReputations,
     pub event_state: &'a EventState,
+    pub buildings: &'a BuildingCounts,
+    pub production: &'a ProductionSummary,
 }
 
 impl<'a> GameContext<'a> {
@@ -279,6 +374,11 @@ impl<'a> GameContext<'a> {
                 // TODO: Implement contract checking when contract system is ready
                 false // For now, always fail this requirement
             }
+            Requirements::MinBuildingCount { building_name, count } => self.buildings.get(building_name) >= *count,
+            Requirements::ProducesDataType { data_type, min_rate } => {
+                self.production.rate(*data_type) >= *min_rate
+            }
+            Requirements::HasAttributeInProduction(attribute) => self.production.has_attribute(*attribute),
         }
     }
 }
@@ -292,6 +392,9 @@ pub struct InteractiveEventData {
     pub faction: Option<Faction>,  // Optional: which faction this event relates to
     pub choices: Vec<EventChoice>,
     pub popup_urgency: bool,  // If true, shows immediately; if false, queues as bubble
+    /// Seconds left while queued as a bubble, counts down from the event's expiry_seconds
+    pub expires_in: f32,
+    pub expiry_seconds: f32,
 }
 
 /// Message to show an interactive event modal (internal - triggered by systems)
@@ -315,6 +418,8 @@ impl From<&InteractiveEventItem> for InteractiveEventData {
             faction: item.faction,
             choices: item.choices.clone(),
             popup_urgency: item.popup_urgency,
+            expires_in: item.expiry_seconds,
+            expiry_seconds: item.expiry_seconds,
         }
     }
 }
@@ -8,52 +8,93 @@ pub mod newsfeed_events;
 // pub mod interactive_events; // Old version - replaced by interactive_events2
 pub mod interactive_events;
 pub mod event_triggers;
+pub mod debt;
 
-pub use newsfeed_events::{NewsItem, AddNewsfeedItemEvent};
+pub use newsfeed_events::{NewsItem, NewsPayload, AddNewsfeedItemEvent};
 pub use interactive_events::*;
 pub use event_triggers::*;
+pub use debt::*;
 
 #[derive(Resource, Deserialize, Debug)]
 pub struct NewsLibrary(pub HashMap<Faction, HashMap<ReputationLevel, Vec<NewsItem>>>);
 
-// A startup system to read the file and insert it as a resource.
-fn load_news_events_from_ron(mut commands: Commands) {
-    // Read the file from the assets folder.
-    let ron_str = std::fs::read_to_string("assets/text/news.ron")
-        .expect("Failed to read news.ron");
+const NEWS_PATH: &str = "assets/text/news.ron";
+const INTERACTIVE_EVENTS_PATH: &str = "assets/text/interactive_events (1).ron";
 
-    // Parse the RON string into our NewsLibrary struct.
-    let news_library: NewsLibrary = ron::from_str(&ron_str)
-        .expect("Failed to parse news events from RON");
-
-    // Insert the fully loaded data as a Bevy Resource.
-    commands.insert_resource(news_library);
-    info!("News events loaded and inserted as a Resource.");
+fn read_news_library() -> Result<NewsLibrary, String> {
+    let ron_str = std::fs::read_to_string(NEWS_PATH)
+        .map_err(|e| format!("Failed to read {}: {}", NEWS_PATH, e))?;
+    ron::from_str(&ron_str).map_err(|e| format!("Failed to parse {}: {}", NEWS_PATH, e))
 }
 
-// A startup system to read interactive events from RON file.
-fn load_interactive_events_from_ron(mut commands: Commands) {
-    // Read the file from the assets folder.
-    let ron_str = std::fs::read_to_string("assets/text/interactive_events (1).ron")
-        .expect("Failed to read interactive_events.ron");
+fn read_interactive_event_library() -> Result<InteractiveEventLibrary, String> {
+    let ron_str = std::fs::read_to_string(INTERACTIVE_EVENTS_PATH)
+        .map_err(|e| format!("Failed to read {}: {}", INTERACTIVE_EVENTS_PATH, e))?;
 
     // Parse the RON events as a Vec
     #[derive(Deserialize)]
     struct EventsFile {
         events: Vec<InteractiveEventItem>,
     }
-    
+
     let events_file: EventsFile = ron::from_str(&ron_str)
-        .expect("Failed to parse interactive events from RON");
+        .map_err(|e| format!("Failed to parse {}: {}", INTERACTIVE_EVENTS_PATH, e))?;
 
     // Create library with pre-built indices
-    let event_library = InteractiveEventLibrary::new(events_file.events);
+    Ok(InteractiveEventLibrary::new(events_file.events))
+}
+
+// A startup system to read the file and insert it as a resource.
+fn load_news_events_from_ron(mut commands: Commands) {
+    let news_library = read_news_library().unwrap_or_else(|e| panic!("{}", e));
+
+    // Insert the fully loaded data as a Bevy Resource.
+    commands.insert_resource(news_library);
+    info!("News events loaded and inserted as a Resource.");
+}
+
+// A startup system to read interactive events from RON file.
+fn load_interactive_events_from_ron(mut commands: Commands) {
+    let event_library = read_interactive_event_library().unwrap_or_else(|e| panic!("{}", e));
 
     // Insert the fully loaded data as a Bevy Resource.
     commands.insert_resource(event_library);
     info!("Interactive events loaded and inserted as a Resource.");
 }
 
+/// Ctrl+R re-reads news.ron and the interactive events file while the game runs, so text and
+/// weights can be tuned without a restart. Trigger history in `EventState` survives it,
+/// Ctrl+Shift+R wipes that too. A file that doesn't parse is logged and the old data kept.
+fn hot_reload_event_libraries(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut news_library: ResMut<NewsLibrary>,
+    mut event_library: ResMut<InteractiveEventLibrary>,
+    mut event_state: ResMut<EventState>,
+) {
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl || !keyboard.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    match read_news_library() {
+        Ok(library) => {
+            *news_library = library;
+            info!("Reloaded {}", NEWS_PATH);
+        }
+        Err(e) => error!("{}, keeping the old news", e),
+    }
+    match read_interactive_event_library() {
+        Ok(library) => {
+            info!("Reloaded {} ({} events)", INTERACTIVE_EVENTS_PATH, library.events.len());
+            *event_library = library;
+        }
+        Err(e) => error!("{}, keeping the old events", e),
+    }
+    if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        *event_state = EventState::default();
+        info!("Event trigger history reset");
+    }
+}
+
 /// Plugin for events system.
 pub struct EventsPlugin;
 
@@ -64,11 +105,19 @@ impl Plugin for EventsPlugin {
         app.add_message::<ShowInteractiveEvent>()
             .add_message::<TriggerInteractiveEvent>()
             .add_message::<PlayerChoiceEvent>()
+            .add_message::<EventIgnored>()
+            .add_message::<WentBankrupt>()
             .add_message::<AddNewsfeedItemEvent>()
             .init_resource::<EventState>()
             .init_resource::<Player>()
             .init_resource::<RandomEventTimer>()
+            .init_resource::<DebtState>()
+            .init_resource::<BuildingCounts>()
             .add_systems(PreStartup, (load_news_events_from_ron, load_interactive_events_from_ron))
+            .add_systems(Update, count_placed_buildings)
+            .add_systems(Update, hot_reload_event_libraries.run_if(|dev_mode: Res<crate::dev::DevMode>| {
+                dev_mode.0 || cfg!(debug_assertions)
+            }))
             // These systems should only run during normal gameplay (not paused or in modal)
             .add_systems(Update, (
                 random_event_trigger_system,
@@ -77,7 +126,8 @@ impl Plugin for EventsPlugin {
                 bankruptcy_update_system,
             ).run_if(in_state(GameState::Running).and(not(in_state(GameState::EventModal)))))
             .add_systems(Update, (
-                handle_player_choice_system,
+                // applies RON-defined consequences, so a bad event definition shouldn't end the run
+                crate::crash::guarded("handle_player_choice_system", handle_player_choice_system),
             ));
     }
 }
\ No newline at end of file
@@ -9,6 +9,20 @@ use rand::prelude::IndexedRandom;
 pub struct NewsItem {
     pub id: u32,
     pub text: String,
+    /// Manual interactive event a click on this headline opens
+    #[serde(default)]
+    pub event_id: Option<String>,
+}
+
+/// What clicking a newsfeed item leads to
+#[derive(Debug, Clone)]
+pub enum NewsPayload {
+    /// Camera goes to that faction's sink nearest the start area
+    Faction(Faction),
+    /// Camera goes to the cell
+    Position(crate::grid::GridPosition),
+    /// Triggers that manual interactive event
+    Event(String),
 }
 
 /// Bevy event to add an item to the newsfeed.
@@ -16,15 +30,17 @@ pub struct NewsItem {
 pub struct AddNewsfeedItemEvent {
     pub faction: Faction,
     pub headline: String,
+    /// None for decorative items, they don't react to the mouse
+    pub payload: Option<NewsPayload>,
 }
 
 
-pub fn get_news_headline(
+pub fn get_news_headline<'a>(
     faction: Faction,
     rep: u32,
-    news_library: &NewsLibrary,
+    news_library: &'a NewsLibrary,
     recent_ids: &mut Vec<u32>,
-) -> Option<(u32, String)> {
+) -> Option<&'a NewsItem> {
     let rep_level = reputation_score_to_level(rep);
     
     // Get the faction's data
@@ -50,10 +66,10 @@ pub fn get_news_headline(
             .collect();
         
         let mut rng = rand::rng();
-        return available_items.choose(&mut rng).map(|item| (item.id, item.text.clone()));
+        return available_items.choose(&mut rng).copied();
     }
     
     // Select a random item
     let mut rng = rand::rng();
-    available_items.choose(&mut rng).map(|item| (item.id, item.text.clone()))
+    available_items.choose(&mut rng).copied()
 }
@@ -1,5 +1,12 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::buildings::source::SourceBuilding;
+use crate::factory::logical::BasicDataType;
+use crate::grid::GridPosition;
+use crate::trace::TraceLog;
+use crate::trace_sim;
+use crate::world_gen::ClusterIconMarker;
 use serde::{Deserialize, Serialize};
 
 /// Enum for the four factions in the game.
@@ -83,16 +90,97 @@ impl FactionReputations {
     }
 }
 
+/// What moved a faction's reputation, so the feedback can point at it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationSource {
+    /// A contract, with the sink it's at if it still has one
+    Contract(Option<Entity>),
+    /// An event's consequence
+    Event,
+    /// Game setup or a loaded save, nothing to announce
+    Setup,
+}
+
+/// A faction's score moved. `delta` is after clamping, so it's never 0
+#[derive(Message, Debug, Clone)]
+pub struct ReputationChanged {
+    pub faction: Faction,
+    pub delta: i32,
+    pub source: ReputationSource,
+}
+
+/// Changes reputation, traces it and sends `ReputationChanged` for it. Anything that moves a
+/// score goes through here rather than `ResMut<FactionReputations>`.
+#[derive(SystemParam)]
+pub struct ReputationChanges<'w> {
+    reputations: ResMut<'w, FactionReputations>,
+    changed: MessageWriter<'w, ReputationChanged>,
+    trace: ResMut<'w, TraceLog>,
+}
+
+impl ReputationChanges<'_> {
+    pub fn get(&self, faction: Faction) -> i32 {
+        self.reputations.get(faction)
+    }
+
+    pub fn get_level(&self, faction: Faction) -> ReputationLevel {
+        self.reputations.get_level(faction)
+    }
+
+    pub fn add(&mut self, faction: Faction, delta: i32, source: ReputationSource) {
+        let before = self.reputations.get(faction);
+        self.reputations.add(faction, delta);
+        self.announce(faction, before, source);
+    }
+
+    pub fn set(&mut self, faction: Faction, value: i32, source: ReputationSource) {
+        let before = self.reputations.get(faction);
+        self.reputations.set(faction, value);
+        self.announce(faction, before, source);
+    }
+
+    fn announce(&mut self, faction: Faction, before: i32, source: ReputationSource) {
+        let now = self.reputations.get(faction);
+        let delta = now - before;
+        if delta == 0 {
+            return;
+        }
+        match source {
+            ReputationSource::Contract(Some(sink)) => {
+                trace_sim!(self.trace, Reputation, [sink], "{:?} reputation {:+} from a contract at {:?} (now {})", faction, delta, sink, now);
+            }
+            _ => trace_sim!(self.trace, Reputation, [], "{:?} reputation {:+} from {:?} (now {})", faction, delta, source, now),
+        }
+        self.changed.write(ReputationChanged { faction, delta, source });
+    }
+}
+
 /// Plugin for reputation system.
 pub struct FactionsPlugin;
 
 impl Plugin for FactionsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<FactionReputations>()
+            .add_message::<ClusterUnlocked>()
+            .add_message::<ReputationChanged>()
+            // the mode's only settled once the setup screen's done with it
+            .add_systems(OnExit(crate::pause::GameState::Setup), apply_game_mode_reputation)
             .add_systems(Update, lock_unlock_by_reputation_system.run_if(resource_changed::<FactionReputations>));
             // .add_systems(Update, debug_print_locked_unlocked_sinks);
     }
 }
+/// Sandbox starts every faction at the top of the Exclusive band so everything unlocks
+fn apply_game_mode_reputation(
+    game_mode: Res<crate::game_mode::GameMode>,
+    mut reputations: ReputationChanges,
+) {
+    if game_mode.sandbox {
+        for faction in [Faction::Corporate, Faction::Academia, Faction::Government, Faction::Criminal] {
+            reputations.set(faction, 100, ReputationSource::Setup);
+        }
+    }
+}
+
 /// Debug system to print all locked and unlocked SinkBuilding entities
 pub fn debug_print_locked_unlocked_sinks(
     q_locked: Query<Entity, (With<SinkBuilding>, With<Locked>, Without<Unlocked>)>,
@@ -107,16 +195,46 @@ pub fn debug_print_locked_unlocked_sinks(
     // reputations.add(Faction::Corporate, 1);
 }
 
+/// Lowest score of each reputation band, in ascending order.
+/// Everything that reasons about bands (unlocks, progress previews) goes through this.
+pub const REPUTATION_BANDS: [(ReputationLevel, u32); 6] = [
+    (ReputationLevel::Hostile, 0),
+    (ReputationLevel::Untrusted, 16),
+    (ReputationLevel::Neutral, 31),
+    (ReputationLevel::Friendly, 46),
+    (ReputationLevel::Trusted, 61),
+    (ReputationLevel::Exclusive, 81),
+];
+
 pub fn reputation_score_to_level(score: u32) -> ReputationLevel {
-    match score {
-        0..=15 => ReputationLevel::Hostile,     // Hostile
-        16..=30 => ReputationLevel::Untrusted,  // Untrusted
-        31..=45 => ReputationLevel::Neutral,    // Neutral
-        46..=60 => ReputationLevel::Friendly,   // Friendly
-        61..=80 => ReputationLevel::Trusted,    // Trusted
-        81..=100 => ReputationLevel::Exclusive, // Exclusive
-        _ => ReputationLevel::Neutral,          // Default to neutral if out of bounds
+    if score > 100 {
+        return ReputationLevel::Neutral; // Default to neutral if out of bounds
+    }
+    REPUTATION_BANDS
+        .iter()
+        .rev()
+        .find(|(_, min)| score >= *min)
+        .map(|(level, _)| *level)
+        .unwrap_or(ReputationLevel::Hostile)
+}
+
+/// Lowest score that counts as the given reputation level
+pub fn reputation_level_min_score(level: ReputationLevel) -> u32 {
+    REPUTATION_BANDS
+        .iter()
+        .find(|(band, _)| *band == level)
+        .map(|(_, min)| *min)
+        .unwrap_or(0)
+}
+
+/// How far a score is towards reaching a level, 0..1. Hits 1.0 exactly when
+/// `reputation_score_to_level(score) >= level`.
+pub fn reputation_progress_fraction(score: i32, level: ReputationLevel) -> f32 {
+    let required = reputation_level_min_score(level);
+    if required == 0 || score.max(0) as u32 >= required {
+        return 1.0;
     }
+    (score.max(0) as f32 / required as f32).clamp(0.0, 1.0)
 }
 
 /// Convert a ReputationLevel enum to its string name
@@ -138,13 +256,29 @@ pub struct Locked;
 #[derive(Component)]
 pub struct Unlocked;
 
+/// A locked cluster opening up, with what the player just got access to
+#[derive(Message, Debug, Clone)]
+pub struct ClusterUnlocked {
+    pub faction: Faction,
+    pub center: GridPosition,
+    pub source_count: usize,
+    pub sink_count: usize,
+    /// Data types the cluster's sources put out, sorted
+    pub data_types: Vec<BasicDataType>,
+}
+
 /// System to lock or unlock entities based on their faction reputation level
 /// if expensive try only run when faction reputation changes or other optimisation
 pub fn lock_unlock_by_reputation_system(
     mut commands: Commands,
-    q_locked: Query<(Entity, &Faction, &ReputationLevel), (With<Locked>, Without<Unlocked>)>,
+    q_locked: Query<
+        (Entity, &Faction, &ReputationLevel, Has<SinkBuilding>, Option<&GridPosition>, Has<ClusterIconMarker>, Option<&SourceBuilding>),
+        (With<Locked>, Without<Unlocked>),
+    >,
     q_unlocked: Query<(Entity, &Faction, &ReputationLevel), (With<Unlocked>, Without<Locked>)>,
     reputations: Res<FactionReputations>,
+    mut counters: ResMut<crate::achievements::RunCounters>,
+    mut unlocked_events: MessageWriter<ClusterUnlocked>,
 ) {
     // Lock entities if their current reputation level is too low
     for (entity, faction, &level) in q_unlocked.iter() {
@@ -153,9 +287,52 @@ pub fn lock_unlock_by_reputation_system(
         }
     }
     // Unlock entities if their current reputation level is high enough
-    for (entity, faction, &level) in q_locked.iter() {
+    let mut clusters: Vec<(ReputationLevel, ClusterUnlocked)> = Vec::new();
+    let mut members = Vec::new();
+    for (entity, faction, &level, is_sink, position, is_icon, source) in q_locked.iter() {
         if level <= reputations.get_level(*faction) {
+            // every cluster has exactly one sink, so sinks count the clusters
+            if is_sink {
+                counters.clusters_unlocked += 1;
+            }
             commands.entity(entity).remove::<Locked>().insert((Unlocked,));
+
+            let Some(&position) = position else { continue };
+            if is_icon {
+                clusters.push((level, ClusterUnlocked {
+                    faction: *faction,
+                    center: position,
+                    source_count: 0,
+                    sink_count: 0,
+                    data_types: Vec::new(),
+                }));
+            } else if is_sink || source.is_some() {
+                members.push((*faction, level, position, is_sink, source));
+            }
+        }
+    }
+
+    // sources and sinks belong to the nearest cluster of their faction and tier that opened
+    for (faction, level, position, is_sink, source) in members {
+        let Some((_, cluster)) = clusters
+            .iter_mut()
+            .filter(|(l, c)| *l == level && c.faction == faction)
+            .min_by_key(|(_, c)| (c.center.0 - position.0).length_squared())
+        else {
+            continue;
+        };
+        if is_sink {
+            cluster.sink_count += 1;
+        }
+        if let Some(source) = source {
+            cluster.source_count += 1;
+            cluster.data_types.extend(source.shape.contents.keys().copied());
         }
     }
-}
\ No newline at end of file
+    for (_, mut cluster) in clusters {
+        cluster.data_types.sort();
+        cluster.data_types.dedup();
+        info!("{:?} cluster at {:?} unlocked", cluster.faction, cluster.center);
+        unlocked_events.write(cluster);
+    }
+}
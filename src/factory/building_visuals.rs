@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::factions::{Faction, ReputationLevel};
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::grid::{Grid, GridPosition, Orientation};
+
+/// Named z layers for world sprites so decorations, badges and lock overlays
+/// stack predictably on top of the same building
+pub mod z_layers {
+    /// Decorations drawn behind building tiles (e.g. sink trim)
+    pub const BEHIND_BUILDING: f32 = -0.5;
+    /// Building tiles themselves
+    pub const BUILDING: f32 = 0.0;
+    /// Small ornaments on top of a building (tier pips, labels)
+    pub const ORNAMENT: f32 = 5.0;
+    /// Alarms and badges anchored above buildings
+    pub const BADGE: f32 = 40.0;
+    /// Line around the world and the shade past it
+    pub const WORLD_BORDER: f32 = 45.0;
+    /// Locked cell overlay
+    pub const LOCK_OVERLAY: f32 = 50.0;
+    /// Central faction icon of a locked cluster
+    pub const LOCK_ICON: f32 = 60.0;
+}
+
+/// Marker for the starter sinks near origin, which get a neutral labelled look
+#[derive(Component)]
+pub struct StarterSink;
+
+/// Faction-coloured trim sprite behind a sink
+#[derive(Component)]
+pub struct SinkTrim;
+
+/// Container for the reputation tier pips on a sink
+#[derive(Component)]
+pub struct SinkTierPips;
+
+const TRIM_PADDING: f32 = 6.0;
+const PIP_SIZE: f32 = 10.0;
+const PIP_GAP: f32 = 4.0;
+
+fn tier_pip_count(level: ReputationLevel) -> usize {
+    match level {
+        ReputationLevel::Friendly => 1,
+        ReputationLevel::Trusted => 2,
+        ReputationLevel::Exclusive => 3,
+        _ => 0,
+    }
+}
+
+/// Offset from the sink's anchor cell to the centre of its footprint
+fn sink_footprint_offset(sink: &SinkBuilding, grid_pos: &GridPosition, grid: &Grid) -> Vec2 {
+    grid.calculate_building_sprite_position(grid_pos, sink.size.x, sink.size.y, Orientation::default())
+        - grid.grid_to_world_center(grid_pos)
+}
+
+/// Adds faction trim, tier pips and (for starter sinks) a label as children of new sinks,
+/// so they get despawned along with the sink
+pub fn decorate_sinks(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &SinkBuilding, &GridPosition, Option<&Faction>, Option<&ReputationLevel>, Has<StarterSink>),
+        Added<SinkBuilding>,
+    >,
+    game_assets: Res<GameAssets>,
+    grid: Res<Grid>,
+) {
+    for (entity, sink, grid_pos, faction, reputation, is_starter) in query.iter() {
+        let offset = sink_footprint_offset(sink, grid_pos, &grid);
+        let size = Vec2::new(sink.size.x as f32, sink.size.y as f32) * grid.scale;
+
+        let trim_color = match (faction, is_starter) {
+            (_, true) => Color::srgb(0.6, 0.6, 0.6),
+            (Some(faction), false) => game_assets.faction_color(*faction),
+            (None, false) => continue,
+        };
+
+        let trim = commands
+            .spawn((
+                SinkTrim,
+                Sprite {
+                    color: trim_color,
+                    custom_size: Some(size + Vec2::splat(TRIM_PADDING * 2.0)),
+                    ..default()
+                },
+                Transform::from_translation(offset.extend(z_layers::BEHIND_BUILDING)),
+            ))
+            .id();
+        commands.entity(entity).insert_if_new(Visibility::default()).add_child(trim);
+
+        if is_starter && let Some(faction) = faction {
+            let label = commands
+                .spawn((
+                    Text2d::new(format!("{:?}", faction)),
+                    game_assets.text_font(14.0),
+                    TextColor(game_assets.faction_color(*faction)),
+                    Transform::from_translation(
+                        (offset - Vec2::new(0.0, size.y / 2.0 + 12.0)).extend(z_layers::ORNAMENT),
+                    ),
+                ))
+                .id();
+            commands.entity(entity).add_child(label);
+        }
+
+        if let Some(reputation) = reputation {
+            spawn_tier_pips(&mut commands, entity, *reputation, offset, size);
+        }
+    }
+}
+
+fn spawn_tier_pips(
+    commands: &mut Commands,
+    sink: Entity,
+    reputation: ReputationLevel,
+    offset: Vec2,
+    size: Vec2,
+) {
+    let count = tier_pip_count(reputation);
+    let total_width = count as f32 * PIP_SIZE + count.saturating_sub(1) as f32 * PIP_GAP;
+    let origin = offset + Vec2::new(-total_width / 2.0 + PIP_SIZE / 2.0, size.y / 2.0 - PIP_SIZE);
+
+    let pips = commands
+        .spawn((
+            SinkTierPips,
+            Transform::from_translation(origin.extend(z_layers::ORNAMENT)),
+            Visibility::default(),
+        ))
+        .with_children(|parent| {
+            for i in 0..count {
+                parent.spawn((
+                    Sprite {
+                        color: Color::srgb(0.95, 0.85, 0.25),
+                        custom_size: Some(Vec2::splat(PIP_SIZE)),
+                        ..default()
+                    },
+                    Transform::from_xyz(i as f32 * (PIP_SIZE + PIP_GAP), 0.0, 0.0),
+                ));
+            }
+        })
+        .id();
+    commands.entity(sink).add_child(pips);
+}
+
+/// Rebuilds the tier pips when the reputation level shown on a sink changes
+pub fn update_sink_tier_pips(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &SinkBuilding, &GridPosition, Ref<ReputationLevel>, &Children),
+        Changed<ReputationLevel>,
+    >,
+    pips_query: Query<(), With<SinkTierPips>>,
+    grid: Res<Grid>,
+) {
+    for (entity, sink, grid_pos, reputation, children) in query.iter() {
+        // freshly spawned sinks were already decorated
+        if reputation.is_added() {
+            continue;
+        }
+        for child in children.iter() {
+            if pips_query.contains(child) {
+                commands.entity(child).despawn();
+            }
+        }
+        let offset = sink_footprint_offset(sink, grid_pos, &grid);
+        let size = Vec2::new(sink.size.x as f32, sink.size.y as f32) * grid.scale;
+        spawn_tier_pips(&mut commands, entity, *reputation, offset, size);
+    }
+}
+
+pub struct BuildingVisualsPlugin;
+
+impl Plugin for BuildingVisualsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (decorate_sinks, update_sink_tier_pips).chain());
+    }
+}
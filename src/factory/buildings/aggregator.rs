@@ -1,19 +1,51 @@
-use crate::factory::buildings::buildings::{Building, BuildingData, SpriteResource};
-use crate::factory::buildings::Tiles;
+use crate::factory::buildings::buildings::{spawn_port_tiles, Building, BuildingData, PortKind, SpriteResource};
+use crate::factory::buildings::{Tile, Tiles};
 use crate::factory::logical::{
-    pass_data_internal, DataAttribute, DataBuffer, DataSink, DataSource,
+    pass_data_internal, DataAttribute, DataSink, DataSource, Dataset,
 };
 use crate::assets::{MachineType, MachineVariant};
-use crate::grid::{GridPosition, GridSprite, Orientation};
+use crate::grid::{Direction, GridPosition, GridSprite, Orientation};
+use bevy::math::I64Vec2;
 use bevy::color::Color;
-use bevy::ecs::related;
-use bevy::prelude::{Commands, Component, Query, Res, Time};
+use bevy::ecs::relationship::RelatedSpawner;
+use bevy::prelude::{Commands, Component, Query, Res, SpawnWith, Without, Time};
 use bevy::prelude::{Entity, SpawnRelated};
 use bevy::sprite::Text2d;
 
+/// Distinct data types an aggregator needs coming in before it'll tag anything Aggregated
+pub const DEFAULT_MIN_AGGREGATE_TYPES: usize = 2;
+/// Share of the throughput that still gets through, unchanged, when there's nothing to aggregate
+pub const PASSTHROUGH_RATE: f32 = 0.5;
+
 #[derive(Component, Clone)]
 pub struct Aggregator {
     pub(crate) throughput: f32,
+    pub(crate) min_types: usize,
+}
+
+impl Aggregator {
+    pub fn new(throughput: f32) -> Self {
+        Self { throughput, min_types: DEFAULT_MIN_AGGREGATE_TYPES }
+    }
+}
+
+/// Whether the aggregator is tagging its output this frame, for the tooltip
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct AggregationActive(pub bool);
+
+/// Aggregating one kind of data isn't aggregating anything
+pub fn can_aggregate(dataset: &Dataset, min_types: usize) -> bool {
+    dataset.contents.len() >= min_types
+}
+
+/// The dataset that comes out of an aggregator fed `dataset`: tagged Aggregated if it mixes
+/// enough data types, unchanged otherwise
+pub fn aggregate_dataset(dataset: &Dataset, min_types: usize) -> Dataset {
+    if can_aggregate(dataset, min_types) {
+        dataset.clone().with_attribute(DataAttribute::Aggregated)
+    } else {
+        dataset.clone()
+    }
 }
 
 impl Building for Aggregator {
@@ -23,36 +55,32 @@ impl Building for Aggregator {
         position: GridPosition,
         orientation: Orientation,
     ) -> Entity {
+        let ports = self.ports(orientation);
+        let throughput = self.throughput;
         commands
             .spawn((
                 position,
-                related!(
-                    Tiles[
-                    (
-                        DataSink {
-                            direction: orientation.direction.opposite(),
-                            buffer: DataBuffer::default(),
-                        },
-                        position,
-                        // GridSprite(Color::linear_rgba(1.0, 0.0, 1.0, 0.3)),
-                        // Text2d::default(),
-                    ),
-                    (
-                        DataSource {
-                            direction: orientation.direction,
-                            throughput: self.throughput,
-                            limited: true,
-                            buffer: DataBuffer::default()
-                        },
-                        position,
-                        // GridSprite(Color::linear_rgba(1.0, 0.0, 1.0, 0.3)),
-                    )
-                ]),
+                Tiles::spawn(SpawnWith(
+                    move |spawner: &mut RelatedSpawner<Tile> /* Type */| {
+                        spawn_port_tiles(spawner, position, ports, throughput);
+                    },
+                )),
                 self.clone(),
             ))
             .id()
     }
 
+    fn ports(&self, orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        vec![
+            (I64Vec2::ZERO, orientation.direction.opposite(), PortKind::Input),
+            (I64Vec2::ZERO, orientation.direction, PortKind::Output),
+        ]
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::Aggregator { throughput: self.throughput, min_types: self.min_types })
+    }
+
     fn data(&self) -> BuildingData {
         BuildingData {
             sprite: Some(SpriteResource::Machine(MachineType::Aggregator, MachineVariant::Single)),
@@ -60,17 +88,21 @@ impl Building for Aggregator {
             grid_height: 1,
             cost: 75,
             name: "Aggregator".to_string(),
+            upkeep_per_sec: 1,
+            throughput: self.throughput,
+            description: "Marks data Aggregated, needs two or more types mixed in".to_string(),
         }
     }
 }
 
 pub fn do_aggregation(
-    aggregators: Query<(&Aggregator, &Tiles)>,
+    mut commands: Commands,
+    aggregators: Query<(Entity, &Aggregator, &Tiles, Option<&AggregationActive>), (Without<crate::factory::feedback::FeedbackLoop>, Without<crate::economics::Unpowered>)>,
     mut sinks: Query<(Entity, &mut DataSink)>,
     mut sources: Query<(Entity, &mut DataSource)>,
     time: Res<Time>,
 ) {
-    for (agg, tiles) in aggregators {
+    for (entity, agg, tiles, was_active) in aggregators {
         let Some((_, mut sink)) = sinks.iter_mut().find(|(entity, _)| tiles.contains(entity))
         else {
             continue;
@@ -82,15 +114,16 @@ pub fn do_aggregation(
             continue;
         };
 
-        let aggregated_shape = sink
-            .buffer
-            .shape
-            .as_ref()
-            .map(|ds| ds.clone().with_attribute(DataAttribute::Aggregated));
-
-        if aggregated_shape.is_some() {
-            source.buffer.set_shape(aggregated_shape.as_ref());
-            pass_data_internal(&mut source, &mut sink, agg.throughput * time.delta_secs());
+        let Some(incoming) = sink.buffer.shape.clone() else {
+            continue;
+        };
+        let active = can_aggregate(&incoming, agg.min_types);
+        if was_active != Some(&AggregationActive(active)) {
+            commands.entity(entity).insert(AggregationActive(active));
         }
+
+        let rate = if active { 1.0 } else { PASSTHROUGH_RATE };
+        source.buffer.set_shape(Some(&aggregate_dataset(&incoming, agg.min_types)));
+        pass_data_internal(&mut source, &mut sink, agg.throughput * rate * time.delta_secs());
     }
 }
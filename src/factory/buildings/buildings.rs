@@ -1,4 +1,8 @@
-use crate::grid::{GridAtlasSprite, GridPosition, Orientation};
+use crate::grid::{calculate_occupied_cells_rotated, Direction, GridAtlasSprite, GridPosition, Orientation};
+use crate::factory::buildings::{FootprintTile, Tile, Tiles};
+use crate::factory::logical::{DataBuffer, DataSink, DataSource};
+use bevy::ecs::relationship::RelatedSpawner;
+use bevy::math::I64Vec2;
 use crate::ui::tooltip::attach_tooltip;
 use bevy::prelude::*;
 
@@ -21,6 +25,23 @@ pub trait Building: Send + Sync {
 
         attach_tooltip(commands, id);
 
+        // only the anchor and the port tiles carry a GridPosition, so any other cell of the
+        // footprint gets a bare tile, otherwise it looks free and things get placed on top
+        let footprint = calculate_occupied_cells_rotated(*position, data.grid_width, data.grid_height, orientation);
+        commands.queue(move |world: &mut World| {
+            let Some(tiles) = world.get::<Tiles>(id) else { return };
+            let mut covered: Vec<I64Vec2> = tiles
+                .iter()
+                .filter_map(|tile| world.get::<GridPosition>(*tile).map(|p| p.0))
+                .collect();
+            covered.extend(world.get::<GridPosition>(id).map(|p| p.0));
+            for cell in footprint {
+                if !covered.contains(&cell) {
+                    world.spawn((FootprintTile, Tile(id), GridPosition(cell)));
+                }
+            }
+        });
+
         match data.sprite {
             Some(SpriteResource::Atlas(atlas_id, index)) => {
                 commands.entity(id).insert(GridAtlasSprite {
@@ -63,6 +84,62 @@ pub trait Building: Send + Sync {
     }
 
     fn data(&self) -> BuildingData;
+
+    /// The data ports this building spawns: cell offset from the anchor, the side the port
+    /// faces and whether data goes in or out there. Spawning and the placement ghost both
+    /// read this, so they can't disagree.
+    fn ports(&self, _orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        Vec::new()
+    }
+
+    /// How to rebuild this building from a save. Buildings world gen places (sources, sinks)
+    /// have none, they come back with the world instead.
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortKind {
+    /// A `DataSink` tile
+    Input,
+    /// A `DataSource` tile
+    Output,
+}
+
+/// Spawns a `DataSink` or `DataSource` tile for each port, sources limited to `throughput`
+pub fn spawn_port_tiles(
+    spawner: &mut RelatedSpawner<Tile>,
+    position: GridPosition,
+    ports: Vec<(I64Vec2, Direction, PortKind)>,
+    throughput: f32,
+) {
+    for (offset, direction, kind) in ports {
+        let tile_position = GridPosition(*position + offset);
+        match kind {
+            PortKind::Input => spawner.spawn((
+                DataSink {
+                    direction,
+                    buffer: DataBuffer::default(),
+                },
+                tile_position,
+            )),
+            PortKind::Output => spawner.spawn((
+                DataSource {
+                    direction,
+                    throughput,
+                    limited: true,
+                    buffer: DataBuffer::default(),
+                },
+                tile_position,
+            )),
+        };
+    }
+}
+
+/// Cell offset of the i-th tile along a building's layout
+pub fn layout_offset(orientation: Orientation, i: i64) -> I64Vec2 {
+    GridPosition(I64Vec2::ZERO).offset(orientation.layout_direction(), i).0
 }
 
 #[derive(Clone)]
@@ -80,4 +157,10 @@ pub struct BuildingData {
     pub grid_height: i64,
     pub cost: i32,
     pub name: String,
+    /// Money drained every second while it's placed, 0 for free
+    pub upkeep_per_sec: i32,
+    /// Data per second through the whole building, 0 where it doesn't apply
+    pub throughput: f32,
+    /// One line for the shop tooltip
+    pub description: String,
 }
@@ -0,0 +1,100 @@
+use crate::factory::buildings::buildings::{spawn_port_tiles, Building, BuildingData, PortKind, SpriteResource};
+use crate::factory::buildings::{Tile, Tiles};
+use crate::factory::logical::{DataAttribute, DataSink, DataSource};
+use crate::assets::{MachineType, MachineVariant};
+use crate::grid::{Direction, GridPosition, Orientation};
+use bevy::math::I64Vec2;
+use bevy::ecs::relationship::RelatedSpawner;
+use bevy::prelude::{Commands, Component, Query, Res, SpawnWith, Without, Time};
+use bevy::prelude::{Entity, SpawnRelated};
+
+/// Share of what goes into a cleaner that comes back out, the rest is lost to processing
+pub const CLEANER_OUTPUT_FRACTION: f32 = 0.7;
+
+#[derive(Component, Clone)]
+pub struct Cleaner {
+    pub(crate) throughput: f32,
+}
+
+impl Building for Cleaner {
+    fn spawn_naked(
+        &self,
+        commands: &mut Commands,
+        position: GridPosition,
+        orientation: Orientation,
+    ) -> Entity {
+        let ports = self.ports(orientation);
+        let throughput = self.throughput;
+        commands
+            .spawn((
+                position,
+                Tiles::spawn(SpawnWith(
+                    move |spawner: &mut RelatedSpawner<Tile> /* Type */| {
+                        spawn_port_tiles(spawner, position, ports, throughput);
+                    },
+                )),
+                self.clone(),
+            ))
+            .id()
+    }
+
+    fn ports(&self, orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        vec![
+            (I64Vec2::ZERO, orientation.direction.opposite(), PortKind::Input),
+            (I64Vec2::ZERO, orientation.direction, PortKind::Output),
+        ]
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::Cleaner { throughput: self.throughput })
+    }
+
+    fn data(&self) -> BuildingData {
+        BuildingData {
+            sprite: Some(SpriteResource::Machine(MachineType::Cleaner, MachineVariant::Single)),
+            grid_width: 1,
+            grid_height: 1,
+            cost: 70,
+            name: "Cleaner".to_string(),
+            upkeep_per_sec: 2,
+            throughput: self.throughput,
+            description: "Marks data Cleaned, but loses some of it on the way".to_string(),
+        }
+    }
+}
+
+/// Same as aggregation but with `Cleaned`, and only `CLEANER_OUTPUT_FRACTION` of the input makes it out
+pub fn do_cleaning(
+    cleaners: Query<(&Cleaner, &Tiles), (Without<crate::factory::feedback::FeedbackLoop>, Without<crate::economics::Unpowered>)>,
+    mut sinks: Query<(Entity, &mut DataSink)>,
+    mut sources: Query<(Entity, &mut DataSource)>,
+    time: Res<Time>,
+) {
+    for (cleaner, tiles) in cleaners {
+        let Some((_, mut sink)) = sinks.iter_mut().find(|(entity, _)| tiles.contains(entity))
+        else {
+            continue;
+        };
+        let Some((_, mut source)) = sources
+            .iter_mut()
+            .find(|(entity, _)| tiles.contains(entity))
+        else {
+            continue;
+        };
+
+        let Some(cleaned_shape) = sink
+            .buffer
+            .shape
+            .as_ref()
+            .map(|ds| ds.clone().with_attribute(DataAttribute::Cleaned))
+        else {
+            continue;
+        };
+
+        let amount = (cleaner.throughput * time.delta_secs())
+            .min(sink.buffer.value)
+            .min(source.headroom() / CLEANER_OUTPUT_FRACTION);
+        sink.buffer.remove(amount);
+        source.buffer.add(&cleaned_shape, amount * CLEANER_OUTPUT_FRACTION);
+    }
+}
@@ -1,14 +1,17 @@
-use crate::factory::buildings::buildings::{Building, BuildingData, SpriteResource};
+use crate::factory::buildings::buildings::{
+    layout_offset, spawn_port_tiles, Building, BuildingData, PortKind, SpriteResource,
+};
 use crate::factory::buildings::{Tile, Tiles};
 use crate::factory::logical::{
-    BasicDataType, DataAttribute, DataBuffer, DataSink, DataSource, Dataset,
+    BasicDataType, DataAttribute, DataSink, DataSource, Dataset, PortOutputs,
 };
-use crate::grid::{GridPosition, GridSprite, Orientation};
+use crate::grid::{Direction, GridPosition, GridSprite, Orientation};
+use bevy::math::I64Vec2;
 use crate::assets::{MachineType, MachineVariant};
 use bevy::color::Color;
 use bevy::ecs::relationship::RelatedSpawner;
 use bevy::platform::collections::{HashMap, HashSet};
-use bevy::prelude::{Commands, Component, Query, Res, SpawnWith, Time};
+use bevy::prelude::{Commands, Component, DetectChangesMut, Query, Res, Without, SpawnWith, Time};
 use bevy::prelude::{Entity, SpawnRelated};
 use bevy::sprite::Text2d;
 use std::hash::Hash;
@@ -19,6 +22,12 @@ pub struct Combiner {
     pub(crate) sink_count: i64,
 }
 
+impl Combiner {
+    pub fn new(throughput: f32, sink_count: i64) -> Self {
+        Self { throughput, sink_count }
+    }
+}
+
 impl Building for Combiner {
     fn spawn_naked(
         &self,
@@ -26,41 +35,33 @@ impl Building for Combiner {
         position: GridPosition,
         orientation: Orientation,
     ) -> Entity {
-        let sink_count = self.sink_count;
+        let ports = self.ports(orientation);
         let throughput = self.throughput;
         commands
             .spawn((
                 position,
                 Tiles::spawn(SpawnWith(
                     move |spawner: &mut RelatedSpawner<Tile> /* Type */| {
-                        for i in 0..sink_count {
-                            spawner.spawn((
-                                DataSink {
-                                    direction: orientation.direction.opposite(),
-                                    buffer: DataBuffer::default(),
-                                },
-                                // Text2d::default(),
-                                position.offset(orientation.layout_direction(), i as i64),
-                                // GridSprite(Color::linear_rgba(0.7, 0.3, 1.0, 0.3)),
-                            ));
-                        }
-                        spawner.spawn((
-                            DataSource {
-                                throughput,
-                                limited: true,
-                                direction: orientation.direction,
-                                buffer: DataBuffer::default(),
-                            },
-                            position,
-                            // Text2d::default(),
-                        ));
+                        spawn_port_tiles(spawner, position, ports, throughput);
                     },
                 )),
                 self.clone(),
+                PortOutputs::default(),
             ))
             .id()
     }
 
+    fn ports(&self, orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        (0..self.sink_count)
+            .map(|i| (layout_offset(orientation, i), orientation.direction.opposite(), PortKind::Input))
+            .chain(std::iter::once((I64Vec2::ZERO, orientation.direction, PortKind::Output)))
+            .collect()
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::Combiner { throughput: self.throughput, sink_count: self.sink_count })
+    }
+
     fn data(&self) -> BuildingData {
         let variant = match self.sink_count {
             2 => MachineVariant::Size2,
@@ -73,8 +74,12 @@ impl Building for Combiner {
             sprite: Some(SpriteResource::Machine(MachineType::Combiner, variant)),
             grid_width: self.sink_count,
             grid_height: 1,
-            cost: 60,
+            // 60 for the 2-way, so upgrading in place costs the difference
+            cost: 30 * self.sink_count as i32,
             name: format!("Combiner {}x1", self.sink_count),
+            upkeep_per_sec: 1,
+            throughput: self.throughput,
+            description: "Merges data of different types into one dataset".to_string(),
         }
     }
 }
@@ -103,13 +108,13 @@ where
     })
 }
 pub fn do_combining(
-    combiners: Query<(&Combiner, &Tiles)>,
+    combiners: Query<(&Combiner, &Tiles, &mut PortOutputs), (Without<crate::factory::feedback::FeedbackLoop>, Without<crate::economics::Unpowered>)>,
     mut sinks: Query<(Entity, &mut DataSink)>,
     mut sources: Query<(Entity, &mut DataSource)>,
     time: Res<Time>,
 ) {
-    for (combiner, tiles) in combiners {
-        let Some((_, mut source)) = sources
+    for (combiner, tiles, mut port_outputs) in combiners {
+        let Some((source_entity, mut source)) = sources
             .iter_mut()
             .find(|(entity, _)| tiles.contains(entity))
         else {
@@ -128,14 +133,27 @@ pub fn do_combining(
             .collect::<Vec<_>>();
 
         // Make sure all the datasets in every sink have disjoint BasicDataTypes
-        let Some(disjoint_data) =
-            get_disjoint_data(sinks.iter().filter_map(|s| s.buffer.shape.as_ref()))
-        else {
+        let disjoint = get_disjoint_data(sinks.iter().filter_map(|s| s.buffer.shape.as_ref()));
+        let stalled = match &disjoint {
+            None => Some("Inputs share a data type".to_string()),
+            Some(contents) if contents.is_empty() => Some("No input".to_string()),
+            Some(_) => None,
+        };
+        port_outputs.set_if_neq(PortOutputs {
+            inputs: sinks.iter().map(|s| s.buffer.shape.clone()).collect(),
+            outputs: vec![(
+                source_entity,
+                disjoint.clone().filter(|_| stalled.is_none()).map(|contents| Dataset { contents }),
+            )],
+            stalled,
+        });
+        let Some(disjoint_data) = disjoint else {
             continue;
         };
         let smallest_buffer_amount = sinks.iter().map(|s| s.buffer.value).reduce(f32::min);
         let process_amount = smallest_buffer_amount
-            .map_or(0., |sba| sba.min(time.delta_secs() * combiner.throughput));
+            .map_or(0., |sba| sba.min(time.delta_secs() * combiner.throughput))
+            .min(source.headroom());
 
         source.buffer.add(
             &Dataset {
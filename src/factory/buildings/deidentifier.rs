@@ -0,0 +1,101 @@
+use crate::factory::buildings::buildings::{
+    layout_offset, spawn_port_tiles, Building, BuildingData, PortKind, SpriteResource,
+};
+use crate::factory::buildings::{Tile, Tiles};
+use crate::factory::logical::{DataAttribute, DataSink, DataSource};
+use crate::assets::{MachineType, MachineVariant};
+use crate::grid::{Direction, GridPosition, Orientation};
+use bevy::math::I64Vec2;
+use bevy::ecs::relationship::RelatedSpawner;
+use bevy::prelude::{Commands, Component, Query, Res, SpawnWith, Without, Time};
+use bevy::prelude::{Entity, SpawnRelated};
+
+/// Strips identifying info off whatever goes through it. Takes data in on the first tile
+/// and hands it out of the second one.
+#[derive(Component, Clone)]
+pub struct DeIdentifier {
+    pub(crate) throughput: f32,
+}
+
+impl Building for DeIdentifier {
+    fn spawn_naked(
+        &self,
+        commands: &mut Commands,
+        position: GridPosition,
+        orientation: Orientation,
+    ) -> Entity {
+        let ports = self.ports(orientation);
+        let throughput = self.throughput;
+        commands
+            .spawn((
+                position,
+                Tiles::spawn(SpawnWith(
+                    move |spawner: &mut RelatedSpawner<Tile> /* Type */| {
+                        spawn_port_tiles(spawner, position, ports, throughput);
+                    },
+                )),
+                self.clone(),
+            ))
+            .id()
+    }
+
+    fn ports(&self, orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        vec![
+            (I64Vec2::ZERO, orientation.direction.opposite(), PortKind::Input),
+            (layout_offset(orientation, 1), orientation.direction, PortKind::Output),
+        ]
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::DeIdentifier { throughput: self.throughput })
+    }
+
+    fn data(&self) -> BuildingData {
+        BuildingData {
+            sprite: Some(SpriteResource::Machine(MachineType::DeIdentifier, MachineVariant::Size2)),
+            grid_width: 2,
+            grid_height: 1,
+            cost: 90,
+            name: "De-Identifier".to_string(),
+            upkeep_per_sec: 2,
+            throughput: self.throughput,
+            description: "Strips identities, marking data De-Identified".to_string(),
+        }
+    }
+}
+
+pub fn do_deidentifying(
+    deidentifiers: Query<(&DeIdentifier, &Tiles), (Without<crate::factory::feedback::FeedbackLoop>, Without<crate::economics::Unpowered>)>,
+    mut sinks: Query<(Entity, &mut DataSink)>,
+    mut sources: Query<(Entity, &mut DataSource)>,
+    time: Res<Time>,
+) {
+    for (deidentifier, tiles) in deidentifiers {
+        let Some((_, mut sink)) = sinks.iter_mut().find(|(entity, _)| tiles.contains(entity))
+        else {
+            continue;
+        };
+        let Some((_, mut source)) = sources
+            .iter_mut()
+            .find(|(entity, _)| tiles.contains(entity))
+        else {
+            continue;
+        };
+
+        let Some(deidentified_shape) = sink
+            .buffer
+            .shape
+            .as_ref()
+            .map(|ds| ds.clone().with_attribute(DataAttribute::DeIdentified))
+        else {
+            continue;
+        };
+
+        // not pass_data_internal, that adds with the sink's shape and would undo the attribute
+        let amount = (deidentifier.throughput * time.delta_secs())
+            .min(sink.buffer.value)
+            .min(source.headroom());
+        sink.buffer.remove(amount);
+        source.buffer.add(&deidentified_shape, amount);
+    }
+}
@@ -1,12 +1,15 @@
-use crate::factory::buildings::buildings::{Building, BuildingData, SpriteResource};
+use crate::factory::buildings::buildings::{
+    layout_offset, spawn_port_tiles, Building, BuildingData, PortKind, SpriteResource,
+};
 use crate::factory::buildings::{Tile, Tiles};
-use crate::factory::logical::{DataBuffer, DataSink, DataSource, Dataset};
-use crate::grid::{GridPosition, GridSprite, Orientation};
+use crate::factory::logical::{DataSink, DataSource, Dataset, PortOutputs};
+use crate::grid::{Direction, GridPosition, GridSprite, Orientation};
+use bevy::math::I64Vec2;
 use crate::assets::{MachineType, MachineVariant};
 use bevy::color::Color;
 use bevy::ecs::relationship::RelatedSpawner;
 use bevy::platform::collections::HashMap;
-use bevy::prelude::{Commands, Component, Query, Res, SpawnWith, Time};
+use bevy::prelude::{Commands, Component, DetectChangesMut, Query, Res, Without, SpawnWith, Time};
 use bevy::prelude::{Entity, SpawnRelated};
 use bevy::sprite::Text2d;
 
@@ -23,41 +26,32 @@ impl Building for Delinker {
         position: GridPosition,
         orientation: Orientation,
     ) -> Entity {
-        let source_count = self.source_count;
+        let ports = self.ports(orientation);
         let throughput = self.throughput;
         commands
             .spawn((
                 position,
                 Tiles::spawn(SpawnWith(
                     move |spawner: &mut RelatedSpawner<Tile> /* Type */| {
-                        spawner.spawn((
-                            DataSink {
-                                direction: orientation.direction.opposite(),
-                                buffer: DataBuffer::default(),
-                            },
-                            position,
-                            // GridSprite(Color::linear_rgba(1.0, 0.5, 0.0, 0.3)),
-                            // Text2d::default(),
-                        ));
-                        for i in 0..source_count {
-                            spawner.spawn((
-                                // GridSprite(Color::linear_rgba(1.0, 0.5, 0.0, 0.3)),
-                                DataSource {
-                                    direction: orientation.direction,
-                                    throughput,
-                                    limited: true,
-                                    buffer: DataBuffer::default(),
-                                },
-                                position.offset(orientation.layout_direction(), i as i64),
-                            ));
-                        }
+                        spawn_port_tiles(spawner, position, ports, throughput);
                     },
                 )),
                 self.clone(),
+                PortOutputs::default(),
             ))
             .id()
     }
 
+    fn ports(&self, orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        std::iter::once((I64Vec2::ZERO, orientation.direction.opposite(), PortKind::Input))
+            .chain((0..self.source_count).map(|i| (layout_offset(orientation, i), orientation.direction, PortKind::Output)))
+            .collect()
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::Delinker { throughput: self.throughput, source_count: self.source_count })
+    }
+
     fn data(&self) -> BuildingData {
         let variant = match self.source_count {
             2 => MachineVariant::Size2,
@@ -72,40 +66,34 @@ impl Building for Delinker {
             grid_height: 1,
             cost: 60,
             name: format!("Delinker {}x1", self.source_count),
+            upkeep_per_sec: 1,
+            throughput: self.throughput,
+            description: "Pulls a mixed dataset apart, one data type per output".to_string(),
         }
     }
 }
 
 pub fn do_delinking(
-    splitters: Query<(&Delinker, &Tiles)>,
+    splitters: Query<(&Delinker, &Tiles, &mut PortOutputs), (Without<crate::factory::feedback::FeedbackLoop>, Without<crate::economics::Unpowered>)>,
     mut sinks: Query<(Entity, &mut DataSink)>,
     mut sources: Query<(Entity, &mut DataSource)>,
     time: Res<Time>,
 ) {
-    for (splitter, tiles) in splitters {
+    for (splitter, tiles, mut port_outputs) in splitters {
         let Some((_, mut sink)) = sinks.iter_mut().find(|(entity, _)| tiles.contains(entity))
         else {
             continue;
         };
 
-        let Some(shape) = &sink.buffer.shape else {
-            continue;
-        };
-
-        let process_amount = (splitter.throughput * time.delta_secs()).min(sink.buffer.value);
-
         let sources = sources
             .iter_mut()
             .sort_by_key::<Entity, _>(|&entity| entity)
             .filter(|(entity, _source)| tiles.contains(&entity))
             .collect::<Vec<_>>();
 
-        let mut entries = shape.contents.iter().collect::<Vec<_>>();
-
-        if entries.len() != sources.len() {
-            continue;
-        }
-
+        // worked out before anything can stop it, so the hover panel shows what it would do
+        let shape = sink.buffer.shape.clone();
+        let mut entries = shape.iter().flat_map(|s| s.contents.iter()).collect::<Vec<_>>();
         entries.sort_by_key(|e| e.0);
         let datasets = entries
             .into_iter()
@@ -113,6 +101,34 @@ pub fn do_delinking(
                 contents: HashMap::from([(data_type.clone(), attr_set.clone())]),
             })
             .collect::<Vec<_>>();
+        let stalled = match &shape {
+            None => Some("No input".to_string()),
+            Some(_) if datasets.len() != sources.len() => Some(format!(
+                "Needs {} data types in, has {}",
+                sources.len(),
+                datasets.len()
+            )),
+            Some(_) => None,
+        };
+        port_outputs.set_if_neq(PortOutputs {
+            inputs: vec![shape],
+            outputs: sources
+                .iter()
+                .enumerate()
+                .map(|(i, (entity, _))| (*entity, datasets.get(i).filter(|_| stalled.is_none()).cloned()))
+                .collect(),
+            stalled: stalled.clone(),
+        });
+        if stalled.is_some() {
+            continue;
+        }
+
+        let process_amount = (splitter.throughput * time.delta_secs()).min(sink.buffer.value);
+        // a full output holds the whole machine up
+        let process_amount = sources
+            .iter()
+            .map(|(_, source)| source.headroom())
+            .fold(process_amount, f32::min);
 
         for (ds, (_, mut source)) in datasets.iter().zip(sources) {
             source.buffer.add(ds, process_amount);
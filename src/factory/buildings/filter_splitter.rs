@@ -0,0 +1,207 @@
+use crate::factory::buildings::buildings::{layout_offset, Building, BuildingData, PortKind, SpriteResource};
+use crate::factory::buildings::{Tile, Tiles};
+use crate::factory::logical::{BasicDataType, DataBuffer, DataSink, DataSource, Dataset};
+use crate::assets::{MachineType, MachineVariant};
+use crate::grid::{Direction, GridPosition, Orientation};
+use bevy::math::I64Vec2;
+use bevy::ecs::relationship::RelatedSpawner;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Commands, Component, Query, Res, SpawnWith, Without, Time};
+use bevy::prelude::{Entity, SpawnRelated};
+
+/// Order data types are cycled through when configuring a port, `None` lets anything through
+pub const FILTER_CYCLE: [Option<BasicDataType>; 5] = [
+    None,
+    Some(BasicDataType::Biometric),
+    Some(BasicDataType::Economic),
+    Some(BasicDataType::Behavioural),
+    Some(BasicDataType::Telemetry),
+];
+
+/// Splitter that sorts by data type. Every output but the last can be given a type and only
+/// that type leaves through it, whatever's left over goes out of the last one.
+#[derive(Component, Clone)]
+pub struct FilterSplitter {
+    pub(crate) throughput: f32,
+    pub(crate) source_count: i64,
+    pub(crate) filters: HashMap<usize, Option<BasicDataType>>,
+}
+
+/// Which output of its filter splitter a source tile is
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FilterPort(pub usize);
+
+impl FilterSplitter {
+    pub fn new(throughput: f32, source_count: i64) -> Self {
+        Self {
+            throughput,
+            source_count,
+            filters: HashMap::new(),
+        }
+    }
+
+    pub fn overflow_port(&self) -> usize {
+        (self.source_count - 1).max(0) as usize
+    }
+
+    pub fn filter(&self, port: usize) -> Option<BasicDataType> {
+        self.filters.get(&port).copied().flatten()
+    }
+
+    /// Moves a port on to the next data type in `FILTER_CYCLE`
+    pub fn cycle_filter(&mut self, port: usize) {
+        let current = FILTER_CYCLE.iter().position(|f| *f == self.filter(port)).unwrap_or(0);
+        self.filters.insert(port, FILTER_CYCLE[(current + 1) % FILTER_CYCLE.len()]);
+    }
+
+    /// Filters in port order, for saving
+    pub fn filter_list(&self) -> Vec<Option<BasicDataType>> {
+        (0..self.overflow_port()).map(|port| self.filter(port)).collect()
+    }
+}
+
+impl Building for FilterSplitter {
+    fn spawn_naked(
+        &self,
+        commands: &mut Commands,
+        position: GridPosition,
+        orientation: Orientation,
+    ) -> Entity {
+        let ports = self.ports(orientation);
+        let throughput = self.throughput;
+        commands
+            .spawn((
+                position,
+                Tiles::spawn(SpawnWith(
+                    move |spawner: &mut RelatedSpawner<Tile> /* Type */| {
+                        // like spawn_port_tiles but the outputs need to know their port
+                        let mut output_index = 0;
+                        for (offset, direction, kind) in ports {
+                            let tile_position = GridPosition(*position + offset);
+                            match kind {
+                                PortKind::Input => spawner.spawn((
+                                    DataSink {
+                                        direction,
+                                        buffer: DataBuffer::default(),
+                                    },
+                                    tile_position,
+                                )),
+                                PortKind::Output => {
+                                    output_index += 1;
+                                    spawner.spawn((
+                                        DataSource {
+                                            direction,
+                                            throughput,
+                                            limited: true,
+                                            buffer: DataBuffer::default(),
+                                        },
+                                        FilterPort(output_index - 1),
+                                        tile_position,
+                                    ))
+                                }
+                            };
+                        }
+                    },
+                )),
+                self.clone(),
+            ))
+            .id()
+    }
+
+    fn ports(&self, orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        (0..self.source_count)
+            .map(|i| (layout_offset(orientation, i), orientation.effective_direction(), PortKind::Output))
+            .chain(std::iter::once((I64Vec2::ZERO, orientation.direction.opposite(), PortKind::Input)))
+            .collect()
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::FilterSplitter {
+            throughput: self.throughput,
+            source_count: self.source_count,
+            filters: self.filter_list(),
+        })
+    }
+
+    fn data(&self) -> BuildingData {
+        let variant = match self.source_count {
+            2 => MachineVariant::Size2,
+            3 => MachineVariant::Size3,
+            4 => MachineVariant::Size4,
+            _ => MachineVariant::Size2,
+        };
+
+        BuildingData {
+            sprite: Some(SpriteResource::Machine(MachineType::FilterSplitter, variant)),
+            grid_width: self.source_count,
+            grid_height: 1,
+            cost: 80,
+            name: format!("Filter Splitter {}x1", self.source_count),
+            upkeep_per_sec: 2,
+            throughput: self.throughput,
+            description: "Sends each data type to its assigned port, the rest overflow".to_string(),
+        }
+    }
+}
+
+/// Pulls each assigned type out of the incoming dataset onto its port, the rest goes to overflow.
+/// Like the delinker every output gets the full processed amount of its part.
+pub fn do_filter_splitting(
+    splitters: Query<(&FilterSplitter, &Tiles), (Without<crate::factory::feedback::FeedbackLoop>, Without<crate::economics::Unpowered>)>,
+    mut sinks: Query<(Entity, &mut DataSink)>,
+    mut sources: Query<(Entity, &FilterPort, &mut DataSource)>,
+    time: Res<Time>,
+) {
+    for (splitter, tiles) in splitters {
+        let Some((_, mut sink)) = sinks.iter_mut().find(|(entity, _)| tiles.contains(entity))
+        else {
+            continue;
+        };
+
+        let Some(shape) = &sink.buffer.shape else {
+            continue;
+        };
+
+        let process_amount = (splitter.throughput * time.delta_secs()).min(sink.buffer.value);
+
+        let mut sources = sources
+            .iter_mut()
+            .filter(|(entity, _, _)| tiles.contains(entity))
+            .map(|(_, port, source)| (port.0, source))
+            .collect::<Vec<_>>();
+        sources.sort_by_key(|(port, _)| *port);
+
+        let overflow_port = splitter.overflow_port();
+        // a full port that would get something holds the whole machine up
+        let receiving = |port: usize| {
+            port == overflow_port || splitter.filter(port).is_some_and(|t| shape.contents.contains_key(&t))
+        };
+        let process_amount = sources
+            .iter()
+            .filter(|(port, _)| receiving(*port))
+            .map(|(_, source)| source.headroom())
+            .fold(process_amount, f32::min);
+        let mut leftover = shape.clone();
+        for (port, source) in sources.iter_mut() {
+            if *port == overflow_port {
+                continue;
+            }
+            let Some(data_type) = splitter.filter(*port) else { continue };
+            // first port asking for a type gets it
+            if let Some(attributes) = leftover.contents.remove(&data_type) {
+                let part = Dataset {
+                    contents: HashMap::from([(data_type, attributes)]),
+                };
+                source.buffer.add(&part, process_amount);
+            }
+        }
+
+        if !leftover.contents.is_empty() {
+            if let Some((_, source)) = sources.iter_mut().find(|(port, _)| *port == overflow_port) {
+                source.buffer.add(&leftover, process_amount);
+            }
+        }
+
+        sink.buffer.remove(process_amount);
+    }
+}
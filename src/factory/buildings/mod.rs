@@ -3,11 +3,15 @@ use bevy::prelude::{Bundle, Component, Deref, DerefMut};
 use bevy::prelude::{Entity, SpawnRelated};
 pub mod aggregator;
 pub mod buildings;
-pub(crate) mod combiner;
+pub mod cleaner;
+pub mod combiner;
+pub mod deidentifier;
 pub mod delinker;
-pub(crate) mod sink;
-pub(crate) mod source;
-pub(crate) mod splitter;
+pub mod filter_splitter;
+pub mod sink;
+pub mod source;
+pub mod splitter;
+pub mod trunk_link;
 pub(crate) mod trunker;
 
 #[derive(Component, Debug, Deref, DerefMut)]
@@ -27,3 +31,7 @@ pub struct TileThroughputData {
 
 #[derive(Component)]
 pub struct Undeletable;
+
+/// A tile with no port on it, only there so the WorldMap knows the building covers the cell
+#[derive(Component)]
+pub struct FootprintTile;
@@ -1,11 +1,15 @@
-use crate::contracts::SinkContracts;
+use crate::contracts::{ContractFulfillment, ContractStatus, SinkContracts};
 use crate::factory::buildings::buildings::{Building, BuildingData};
 use crate::factory::buildings::{Tile, Tiles};
-use crate::factory::logical::{DataBuffer, DataSink};
-use crate::grid::{Direction, GridAtlasSprite, GridPosition, Orientation};
+use crate::factory::logical::{DataBuffer, DataSink, SinkDeliveries};
+use crate::grid::{Direction, GridAtlasSprite, GridPosition, Orientation, WorldMap};
+use crate::factory::buildings::source::SourceBuilding;
+use crate::save::PlacedBuilding;
+use crate::world_gen::{LockMarker, WorldBounds};
+use bevy::prelude::{Or, With};
 use bevy::ecs::relationship::RelatedSpawner;
 use bevy::math::I64Vec2;
-use bevy::prelude::{Commands, Component, Entity, Query, Changed, Res};
+use bevy::prelude::{info, Commands, Component, Entity, Query, Changed, Res, Sprite};
 use bevy::prelude::{SpawnRelated, SpawnWith};
 use bevy::sprite::Text2d;
 use bevy::text::{TextFont, TextColor};
@@ -16,7 +20,7 @@ use std::collections::VecDeque;
 use std::ops::Add;
 
 #[derive(Component, Clone)]
-#[require(SinkContracts)]
+#[require(SinkContracts, SinkDeliveries, SinkCapacity)]
 pub struct SinkBuilding {
     pub size: I64Vec2,
 }
@@ -103,6 +107,9 @@ impl Building for SinkBuilding {
     fn data(&self) -> BuildingData {
         BuildingData {
             name: String::from("Sink"),
+            upkeep_per_sec: 0,
+            throughput: 0.,
+            description: "Takes data in for the contracts it holds".to_string(),
             cost: 0,
             grid_width: self.size.x,
             grid_height: self.size.y,
@@ -110,6 +117,75 @@ impl Building for SinkBuilding {
         }
     }
 }
+/// A sink takes up to this many times what its active contracts ask for
+pub const SINK_OVERFLOW_FACTOR: f32 = 2.5;
+
+/// How much a sink building will take per second, anything past it backs up the chain
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct SinkCapacity {
+    pub per_sec: f32,
+    /// Turned away since the last throughput tick
+    pub refused: f32,
+    /// Whether the cap was binding over the last second
+    pub capped: bool,
+}
+
+/// Sums the thresholds of each sink's active contracts into its cap
+pub fn update_sink_capacity(
+    mut sinks: Query<(&SinkContracts, &mut SinkCapacity)>,
+    contracts: Query<(&ContractStatus, &ContractFulfillment)>,
+) {
+    for (sink_contracts, mut capacity) in sinks.iter_mut() {
+        let needed: f64 = sink_contracts
+            .contracts()
+            .iter()
+            .filter_map(|contract| contracts.get(*contract).ok())
+            .filter(|(status, _)| **status == ContractStatus::Active)
+            .map(|(_, fulfillment)| fulfillment.base_threshold)
+            .sum();
+        let per_sec = needed as f32 * SINK_OVERFLOW_FACTOR;
+        if capacity.per_sec != per_sec {
+            capacity.per_sec = per_sec;
+        }
+    }
+}
+
+/// On the throughput timer, whether anything got turned away since the last tick
+pub fn settle_sink_capacity(mut sinks: Query<&mut SinkCapacity>) {
+    for mut capacity in sinks.iter_mut() {
+        capacity.capped = capacity.refused > 0.01;
+        capacity.refused = 0.;
+    }
+}
+
+/// Sink shut off by an event, takes no data until the seconds run out
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SinkDisabled(pub f32);
+
+const DISABLED_TINT: Color = Color::srgb(0.4, 0.4, 0.4);
+
+/// Counts down disabled sinks and greys out their tiles while they're off
+pub fn tick_disabled_sinks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sinks: Query<(Entity, &mut SinkDisabled, &Tiles)>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    for (entity, mut disabled, tiles) in sinks.iter_mut() {
+        disabled.0 -= time.delta_secs();
+        let color = if disabled.0 <= 0.0 { Color::WHITE } else { DISABLED_TINT };
+        for tile in tiles.iter() {
+            if let Ok(mut sprite) = sprites.get_mut(*tile) {
+                sprite.color = color;
+            }
+        }
+        if disabled.0 <= 0.0 {
+            info!("Sink {:?} back online", entity);
+            commands.entity(entity).remove::<SinkDisabled>();
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TilePlacement {
     Inner,
@@ -117,7 +193,7 @@ pub enum TilePlacement {
     Corner(Direction, Direction),
 }
 
-fn get_placement((x, y): (i64, i64), size: I64Vec2) -> TilePlacement {
+pub(crate) fn get_placement((x, y): (i64, i64), size: I64Vec2) -> TilePlacement {
     assert!(size.x > 0 && size.y > 0, "size must be > 0");
 
     let left = x == 0;
@@ -156,16 +232,110 @@ fn get_placement((x, y): (i64, i64), size: I64Vec2) -> TilePlacement {
     }
 }
 
-// System to update debug text on sinks showing their buffer values
-// pub fn update_sink_debug_text(
-//     mut query: Query<(&DataSink, &ThroughputTracker, &mut Text2d)>,
-// ) {
-//     for (sink, tracker, mut text) in query.iter_mut() {
-//         // Format buffer information: type, value, and throughput
-//         let buffer_info = format!(
-//             "{}",
-//             tracker.average_throughput
-//         );
-//         **text = buffer_info;
-//     }
-// }
+/// Whether a side of a sink can be reached by the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortAccess {
+    /// At least one adjacent cell is unlocked and free
+    Open,
+    /// Adjacent cells are taken by player buildings/wires, may still connect
+    Occupied,
+    /// Locked territory or out of bounds
+    Blocked,
+}
+
+/// Reachability of each side of a sink, kept up to date as the map changes
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SinkAccess {
+    pub up: PortAccess,
+    pub down: PortAccess,
+    pub left: PortAccess,
+    pub right: PortAccess,
+}
+
+impl SinkAccess {
+    pub fn side(&self, direction: Direction) -> PortAccess {
+        match direction {
+            Direction::Up => self.up,
+            Direction::Down => self.down,
+            Direction::Left => self.left,
+            Direction::Right => self.right,
+        }
+    }
+
+    pub fn has_access(&self) -> bool {
+        Direction::ALL.iter().any(|d| self.side(*d) != PortAccess::Blocked)
+    }
+}
+
+/// Classify each side of a sink footprint from per-cell lookups.
+/// A side takes the best status of the cells along it.
+pub fn classify_sink_sides(
+    anchor: I64Vec2,
+    size: I64Vec2,
+    is_blocked: impl Fn(I64Vec2) -> bool,
+    is_occupied: impl Fn(I64Vec2) -> bool,
+) -> SinkAccess {
+    let classify_cell = |cell: I64Vec2| {
+        if is_blocked(cell) {
+            PortAccess::Blocked
+        } else if is_occupied(cell) {
+            PortAccess::Occupied
+        } else {
+            PortAccess::Open
+        }
+    };
+    let best = |cells: Vec<I64Vec2>| {
+        let statuses: Vec<_> = cells.into_iter().map(classify_cell).collect();
+        if statuses.contains(&PortAccess::Open) {
+            PortAccess::Open
+        } else if statuses.contains(&PortAccess::Occupied) {
+            PortAccess::Occupied
+        } else {
+            PortAccess::Blocked
+        }
+    };
+
+    SinkAccess {
+        up: best((0..size.x).map(|x| anchor + I64Vec2::new(x, size.y)).collect()),
+        down: best((0..size.x).map(|x| anchor + I64Vec2::new(x, -1)).collect()),
+        left: best((0..size.y).map(|y| anchor + I64Vec2::new(-1, y)).collect()),
+        right: best((0..size.y).map(|y| anchor + I64Vec2::new(size.x, y)).collect()),
+    }
+}
+
+/// Recomputes sink side access whenever the world map changes.
+/// Cells under world sources and sinks can't be built on, so they count as blocked
+/// rather than occupied, only the player's own buildings and wires are "occupied".
+pub fn update_sink_access(
+    mut commands: Commands,
+    sinks: Query<(Entity, &SinkBuilding, &GridPosition, Option<&SinkAccess>)>,
+    world_map: Res<WorldMap>,
+    bounds: Res<WorldBounds>,
+    lock_markers: Query<(), With<LockMarker>>,
+    placed: Query<(), With<PlacedBuilding>>,
+    world_buildings: Query<(), Or<(With<SinkBuilding>, With<SourceBuilding>)>>,
+    tiles: Query<&Tile>,
+) {
+    // wires carry PlacedBuilding themselves, anything bigger has it on the tile's parent
+    let owner = |entity: Entity| tiles.get(entity).map(|tile| tile.0).unwrap_or(entity);
+    let is_player = |entity: Entity| placed.contains(owner(entity));
+    let is_world_building = |entity: Entity| !is_player(entity) && world_buildings.contains(owner(entity));
+
+    for (entity, sink, position, current) in sinks.iter() {
+        let cell_entities = |cell: I64Vec2| world_map.get(&GridPosition(cell));
+        let access = classify_sink_sides(
+            position.0,
+            sink.size,
+            |cell| {
+                !bounds.contains(cell)
+                    || cell_entities(cell).is_some_and(|es| {
+                        es.iter().any(|e| lock_markers.contains(*e) || is_world_building(*e))
+                    })
+            },
+            |cell| cell_entities(cell).is_some_and(|es| es.iter().any(|e| is_player(*e))),
+        );
+        if current != Some(&access) {
+            commands.entity(entity).insert(access);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::factory::buildings::buildings::{Building, BuildingData, SpriteResource};
+use crate::factory::buildings::sink::{get_placement, TilePlacement};
 use crate::factory::buildings::{Tile, Tiles};
 use crate::factory::logical::{DataBuffer, DataSource, Dataset};
 use crate::grid::{Direction, GridPosition, GridSprite, Orientation};
@@ -6,16 +7,17 @@ use crate::assets::{MachineType, MachineVariant};
 use bevy::color::Color;
 use bevy::ecs::relationship::RelatedSpawner;
 use bevy::math::I64Vec2;
-use bevy::prelude::{Commands, Component, Entity};
+use bevy::prelude::{info, Commands, Component, Entity, MessageWriter, Query, With, Without};
 use bevy::prelude::{SpawnRelated, SpawnWith};
 
 #[derive(Component, Clone)]
 pub struct SourceBuilding {
-    pub(crate) directions: Vec<Direction>,
-    pub(crate) throughput: f32,
-    pub(crate) limited: bool,
-    pub(crate) size: I64Vec2,
-    pub(crate) shape: Dataset,
+    pub directions: Vec<Direction>,
+    pub throughput: f32,
+    /// Carries a `SourcePool` and stops producing once it's drained
+    pub limited: bool,
+    pub size: I64Vec2,
+    pub shape: Dataset,
 }
 
 impl Building for SourceBuilding {
@@ -25,24 +27,46 @@ impl Building for SourceBuilding {
         position: GridPosition,
         orientation: Orientation,
     ) -> Entity {
-        let throughput_per_side = self.throughput / self.directions.len() as f32;
         let directions = self
             .directions
             .iter()
             .map(|dir| orientation.transform_relative(*dir))
             .collect::<Vec<_>>();
+        // a bigger source gets a port on every edge cell facing one of its directions,
+        // so it can feed more than four lines
+        let ports = match (self.size.x, self.size.y) {
+            (x, y) if x <= 1 && y <= 1 => directions.iter().map(|dir| (*dir, position)).collect::<Vec<_>>(),
+            (width, height) => {
+                let mut ports = Vec::new();
+                for x in 0..width {
+                    for y in 0..height {
+                        let sides = match get_placement((x, y), self.size) {
+                            TilePlacement::Inner => vec![],
+                            TilePlacement::Edge(side) => vec![side],
+                            TilePlacement::Corner(h, v) => vec![h, v],
+                        };
+                        let cell = GridPosition(*position + I64Vec2::new(x, y));
+                        ports.extend(sides.into_iter().filter(|side| directions.contains(side)).map(|side| (side, cell)));
+                    }
+                }
+                ports
+            }
+        };
+        // evened out until allocate_source_output knows what each line wants
+        let throughput_per_port = self.throughput / ports.len().max(1) as f32;
         let shape = self.shape.clone();
-        let bundles = directions
+        let bundles = ports
             .iter()
-            .map(|dir| {
+            .map(|(dir, cell)| {
                 (
                     DataSource {
                         direction: *dir,
-                        throughput: throughput_per_side,
+                        throughput: throughput_per_port,
                         buffer: DataBuffer::with_shape(Some(shape.clone())),
-                        limited: self.limited,
+                        // world sources make data out of nothing, a limited one runs out through its SourcePool
+                        limited: false,
                     },
-                    position,
+                    *cell,
                     // GridSprite(Color::linear_rgba(1.0, 0.0, 0.0, 0.1)),
                     // Text2d removed for performance - thousands of sources × 4 directions = 10k+ text entities
                     // Text2d::new("0"),
@@ -72,6 +96,67 @@ impl Building for SourceBuilding {
             grid_height: self.size.y,
             cost: 0,
             name: "Source".to_string(),
+            upkeep_per_sec: 0,
+            throughput: self.throughput,
+            description: "Produces data for anything wired to its sides".to_string(),
+        }
+    }
+}
+
+/// What's left in a limited source. Drains by whatever its tiles hand out, gone for good at zero.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SourcePool {
+    pub remaining: f32,
+    pub capacity: f32,
+}
+
+impl SourcePool {
+    pub fn new(capacity: f32) -> Self {
+        Self { remaining: capacity, capacity }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.capacity > 0. { (self.remaining / self.capacity).clamp(0., 1.) } else { 0. }
+    }
+}
+
+/// A limited source that's run dry. Its tiles have lost their `DataSource`.
+#[derive(Component)]
+pub struct Depleted;
+
+/// Runs with `calculate_throughput`, before `reset_delta` clears the second's `last_out`
+pub fn drain_source_pools(
+    mut commands: Commands,
+    mut sources: Query<(Entity, &Tiles, &mut SourcePool, &GridPosition), (With<SourceBuilding>, Without<Depleted>)>,
+    data_sources: Query<&DataSource>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+) {
+    for (entity, tiles, mut pool, position) in sources.iter_mut() {
+        let drawn: f32 = tiles
+            .iter()
+            .filter_map(|tile| data_sources.get(*tile).ok())
+            .map(|source| source.buffer.last_out)
+            .sum();
+        if drawn > 0. {
+            pool.remaining = (pool.remaining - drawn).max(0.);
+        }
+        if pool.remaining > 0. {
+            continue;
+        }
+
+        // on_data_source_removed takes the logical links down with each tile
+        for tile in tiles.iter() {
+            commands.entity(*tile).remove::<DataSource>();
+        }
+        commands.entity(entity).insert(Depleted);
+        info!("Source at {:?} depleted", position.0);
+        // drawn from right up until now, worth telling the player
+        if drawn > 0. {
+            news_writer.write(crate::events::AddNewsfeedItemEvent {
+                faction: crate::factions::Faction::default(),
+                headline: format!("A data source at ({}, {}) has run dry", position.0.x, position.0.y),
+                payload: Some(crate::events::NewsPayload::Position(*position)),
+            });
         }
     }
 }
@@ -1,11 +1,14 @@
-use crate::factory::buildings::buildings::{Building, BuildingData, SpriteResource};
+use crate::factory::buildings::buildings::{
+    layout_offset, spawn_port_tiles, Building, BuildingData, PortKind, SpriteResource,
+};
 use crate::factory::buildings::{Tile, Tiles};
-use crate::factory::logical::{pass_data_internal, DataBuffer, DataSink, DataSource};
-use crate::grid::{GridPosition, GridSprite, Orientation};
+use crate::factory::logical::{pass_data_internal, DataSink, DataSource};
+use crate::grid::{Direction, GridPosition, GridSprite, Orientation};
+use bevy::math::I64Vec2;
 use crate::assets::{MachineType, MachineVariant};
 use bevy::color::Color;
 use bevy::ecs::relationship::RelatedSpawner;
-use bevy::prelude::{Commands, Component, Query, Res, SpawnWith, Time};
+use bevy::prelude::{Commands, Component, Query, Res, Without, SpawnWith, Time};
 use bevy::prelude::{Entity, SpawnRelated};
 use bevy::sprite::Text2d;
 
@@ -15,6 +18,12 @@ pub struct Splitter {
     pub(crate) source_count: i64,
 }
 
+impl Splitter {
+    pub fn new(throughput: f32, source_count: i64) -> Self {
+        Self { throughput, source_count }
+    }
+}
+
 impl Building for Splitter {
     fn spawn_naked(
         &self,
@@ -22,34 +31,14 @@ impl Building for Splitter {
         position: GridPosition,
         orientation: Orientation,
     ) -> Entity {
-        let source_count = self.source_count;
+        let ports = self.ports(orientation);
         let throughput = self.throughput;
         commands
             .spawn((
                 position,
                 Tiles::spawn(SpawnWith(
                     move |spawner: &mut RelatedSpawner<Tile> /* Type */| {
-                        for i in 0..source_count {
-                            spawner.spawn((
-                                // GridSprite(Color::linear_rgba(0.1, 0.3, 1.0, 0.3)),
-                                DataSource {
-                                    direction: orientation.effective_direction(),
-                                    throughput,
-                                    limited: true,
-                                    buffer: DataBuffer::default(),
-                                },
-                                position.offset(orientation.layout_direction(), i as i64),
-                            ));
-                        }
-                        spawner.spawn((
-                            DataSink {
-                                direction: orientation.direction.opposite(),
-                                buffer: DataBuffer::default(),
-                            },
-                            position,
-                            // GridSprite(Color::linear_rgba(0.1, 0.3, 1.0, 0.3)),
-                            // Text2d::default(),
-                        ));
+                        spawn_port_tiles(spawner, position, ports, throughput);
                     },
                 )),
                 self.clone(),
@@ -57,6 +46,17 @@ impl Building for Splitter {
             .id()
     }
 
+    fn ports(&self, orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        (0..self.source_count)
+            .map(|i| (layout_offset(orientation, i), orientation.effective_direction(), PortKind::Output))
+            .chain(std::iter::once((I64Vec2::ZERO, orientation.direction.opposite(), PortKind::Input)))
+            .collect()
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::Splitter { throughput: self.throughput, source_count: self.source_count })
+    }
+
     fn data(&self) -> BuildingData {
         let variant = match self.source_count {
             2 => MachineVariant::Size2,
@@ -69,14 +69,18 @@ impl Building for Splitter {
             sprite: Some(SpriteResource::Machine(MachineType::Splitter, variant)),
             grid_width: self.source_count,
             grid_height: 1,
-            cost: 60,
+            // 60 for the 2-way, so upgrading in place costs the difference
+            cost: 30 * self.source_count as i32,
             name: format!("Splitter {}x1", self.source_count),
+            upkeep_per_sec: 1,
+            throughput: self.throughput,
+            description: "Shares its input evenly between every output".to_string(),
         }
     }
 }
 
 pub fn do_splitting(
-    splitters: Query<(&Splitter, &Tiles)>,
+    splitters: Query<(&Splitter, &Tiles), (Without<crate::factory::feedback::FeedbackLoop>, Without<crate::economics::Unpowered>)>,
     mut sinks: Query<(Entity, &mut DataSink)>,
     mut sources: Query<(Entity, &mut DataSource)>,
     time: Res<Time>,
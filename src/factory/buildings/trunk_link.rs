@@ -0,0 +1,67 @@
+use crate::factory::buildings::buildings::{Building, BuildingData, SpriteResource};
+use crate::factory::buildings::Tile;
+use crate::factory::physical::{PhysicalLink, PhysicalSink, PhysicalSource, LINK_THROUGHPUT};
+use crate::grid::{calculate_occupied_cells_rotated, GridPosition, Orientation};
+use crate::assets::{MachineType, MachineVariant};
+use bevy::prelude::{Commands, Component, Entity};
+
+/// Trunk capacity relative to a plain link
+pub const TRUNK_THROUGHPUT_FACTOR: f32 = 4.0;
+
+/// A 2x1 heavy link. Chains like a wire but only joins other trunks or a Trunker's ports.
+#[derive(Component, Clone)]
+pub struct TrunkLink {
+    pub(crate) throughput: f32,
+}
+
+impl Default for TrunkLink {
+    fn default() -> Self {
+        Self { throughput: LINK_THROUGHPUT * TRUNK_THROUGHPUT_FACTOR }
+    }
+}
+
+/// One cell of a trunk, a `PhysicalLink` as far as chains are concerned
+#[derive(Component)]
+pub struct TrunkSegment;
+
+impl Building for TrunkLink {
+    fn spawn_naked(
+        &self,
+        commands: &mut Commands,
+        position: GridPosition,
+        orientation: Orientation,
+    ) -> Entity {
+        let parent = commands.spawn((position, self.clone())).id();
+        let segments: Vec<Entity> = calculate_occupied_cells_rotated(*position, 2, 1, orientation)
+            .into_iter()
+            .map(|cell| {
+                commands
+                    .spawn((PhysicalLink { throughput: self.throughput }, TrunkSegment, GridPosition(cell), Tile(parent)))
+                    .id()
+            })
+            .collect();
+        // the two halves are one piece, data runs from the anchor cell to the other
+        let flow = orientation.layout_direction();
+        commands.entity(segments[0]).insert(PhysicalSource(segments[1], flow));
+        commands.entity(segments[1]).insert(PhysicalSink(segments[0], flow));
+        parent
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::TrunkLink { throughput: self.throughput })
+    }
+
+    fn data(&self) -> BuildingData {
+        BuildingData {
+            sprite: Some(SpriteResource::Machine(MachineType::Trunker, MachineVariant::Size2)),
+            grid_width: 2,
+            grid_height: 1,
+            // two cells of link at four times the capacity
+            cost: 200,
+            name: "Trunk Link".to_string(),
+            upkeep_per_sec: 0,
+            throughput: self.throughput,
+            description: "Heavy two-cell wire, only joins other trunks or a Trunker".to_string(),
+        }
+    }
+}
@@ -1,11 +1,14 @@
-use crate::factory::buildings::buildings::{Building, BuildingData, SpriteResource};
+use crate::factory::buildings::buildings::{
+    layout_offset, spawn_port_tiles, Building, BuildingData, PortKind, SpriteResource,
+};
 use crate::factory::buildings::{Tile, Tiles};
-use crate::factory::logical::{DataBuffer, DataSink, DataSource};
-use crate::grid::{GridPosition, GridSprite, Orientation};
+use crate::factory::logical::{DataSink, DataSource, PortOutputs};
+use crate::grid::{Direction, GridPosition, GridSprite, Orientation};
+use bevy::math::I64Vec2;
 use crate::assets::{MachineType, MachineVariant};
 use bevy::color::Color;
 use bevy::ecs::relationship::RelatedSpawner;
-use bevy::prelude::{Commands, Component, Query, Res, SpawnWith, Time};
+use bevy::prelude::{Commands, Component, DetectChangesMut, Query, Res, Without, SpawnWith, Time};
 use bevy::prelude::{Entity, SpawnRelated};
 use bevy::sprite::Text2d;
 
@@ -22,41 +25,33 @@ impl Building for Trunker {
         position: GridPosition,
         orientation: Orientation,
     ) -> Entity {
-        let sink_count = self.sink_count;
+        let ports = self.ports(orientation);
         let throughput = self.throughput_per_sink * self.sink_count as f32;
         commands
             .spawn((
                 position,
                 Tiles::spawn(SpawnWith(
                     move |spawner: &mut RelatedSpawner<Tile> /* Type */| {
-                        for i in 0..sink_count {
-                            spawner.spawn((
-                                DataSink {
-                                    direction: orientation.direction.opposite(),
-                                    buffer: DataBuffer::default(),
-                                },
-                                // Text2d::default(),
-                                position.offset(orientation.layout_direction(), i as i64),
-                                // GridSprite(Color::linear_rgba(0.7, 0.3, 1.0, 0.3)),
-                            ));
-                        }
-                        spawner.spawn((
-                            DataSource {
-                                throughput,
-                                limited: true,
-                                direction: orientation.effective_direction(),
-                                buffer: DataBuffer::default(),
-                            },
-                            position,
-                            // Text2d::default(),
-                        ));
+                        spawn_port_tiles(spawner, position, ports, throughput);
                     },
                 )),
                 self.clone(),
+                PortOutputs::default(),
             ))
             .id()
     }
 
+    fn ports(&self, orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        (0..self.sink_count)
+            .map(|i| (layout_offset(orientation, i), orientation.direction.opposite(), PortKind::Input))
+            .chain(std::iter::once((I64Vec2::ZERO, orientation.effective_direction(), PortKind::Output)))
+            .collect()
+    }
+
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::Trunker { throughput_per_sink: self.throughput_per_sink, sink_count: self.sink_count })
+    }
+
     fn data(&self) -> BuildingData {
         let variant = match self.sink_count {
             2 => MachineVariant::Size2,
@@ -71,17 +66,20 @@ impl Building for Trunker {
             grid_height: 1,
             cost: 60,
             name: format!("Trunker {}x1", self.sink_count),
+            upkeep_per_sec: 1,
+            throughput: self.throughput_per_sink * self.sink_count as f32,
+            description: "Bundles several inputs onto one trunk link".to_string(),
         }
     }
 }
 pub fn do_trunking(
-    combiners: Query<(&Trunker, &Tiles)>,
+    combiners: Query<(&Trunker, &Tiles, &mut PortOutputs), (Without<crate::factory::feedback::FeedbackLoop>, Without<crate::economics::Unpowered>)>,
     mut sinks: Query<(Entity, &mut DataSink)>,
     mut sources: Query<(Entity, &mut DataSource)>,
     time: Res<Time>,
 ) {
-    for (trunker, tiles) in combiners {
-        let Some((_, mut source)) = sources
+    for (trunker, tiles, mut port_outputs) in combiners {
+        let Some((source_entity, mut source)) = sources
             .iter_mut()
             .find(|(entity, _)| tiles.contains(entity))
         else {
@@ -100,14 +98,31 @@ pub fn do_trunking(
             .collect::<Vec<_>>();
 
         let any_empty = sinks.iter().any(|s| s.buffer.shape.is_none());
+        let all_have_same_shape = sinks
+            .windows(2)
+            .all(|w| w[0].buffer.shape == w[1].buffer.shape);
+        let stalled = if any_empty {
+            Some("Waiting on every input".to_string())
+        } else if !all_have_same_shape {
+            Some("Inputs need the same dataset".to_string())
+        } else {
+            None
+        };
+        port_outputs.set_if_neq(PortOutputs {
+            inputs: sinks.iter().map(|s| s.buffer.shape.clone()).collect(),
+            outputs: vec![(
+                source_entity,
+                sinks.first().and_then(|s| s.buffer.shape.clone()).filter(|_| stalled.is_none()),
+            )],
+            stalled,
+        });
         if any_empty {
             continue;
         }
 
-        let all_have_same_shape = sinks
-            .windows(2)
-            .all(|w| w[0].buffer.shape == w[1].buffer.shape);
         if all_have_same_shape {
+            // inputs share what's left in the output buffer
+            let mut room = source.headroom();
             // Make sure all the datasets in every sink have disjoint BasicDataTypes
             let shape = sinks
                 .first()
@@ -121,12 +136,13 @@ pub fn do_trunking(
             sinks
                 .iter_mut()
                 .map(|s| {
-                    (
-                        s.buffer
-                            .value
-                            .min(trunker.throughput_per_sink * time.delta_secs()),
-                        s,
-                    )
+                    let value = s
+                        .buffer
+                        .value
+                        .min(trunker.throughput_per_sink * time.delta_secs())
+                        .min(room);
+                    room -= value;
+                    (value, s)
                 })
                 .for_each(|(value, sink)| {
                     sink.buffer.remove(value);
@@ -0,0 +1,179 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::economics::BuildCost;
+use crate::factions::Faction;
+use crate::factory::buildings::{Tile, Tiles, Undeletable};
+use crate::factory::building_visuals::z_layers;
+use crate::factory::physical::PhysicalLink;
+use crate::factory::undo::NotUndoable;
+use crate::factory::{MarkedForRemoval, RemoveBuildingRequest};
+use crate::grid::{Grid, GridPosition, WorldMap};
+use crate::ui::interaction::MouseButtonEvent;
+use crate::ui::BlocksWorldClicks;
+
+/// Share of the build cost handed back for anything torn down with the rectangle
+pub const BULK_REMOVE_REFUND_FRACTION: f32 = 0.6;
+pub(crate) const FLASH_SECONDS: f32 = 0.6;
+
+/// Shift + right-drag in progress, from the cell the drag started on
+#[derive(Resource, Default)]
+pub struct BulkRemoveDrag {
+    pub start: Option<GridPosition>,
+}
+
+/// The red rectangle shown while dragging
+#[derive(Component)]
+pub struct BulkRemoveRect;
+
+/// On the tiles of an undeletable building caught in the rectangle, fades back to normal
+#[derive(Component)]
+pub struct CantRemoveFlash(pub f32);
+
+pub(crate) fn cursor_cell(
+    window: &Window,
+    camera: &(&Camera, &GlobalTransform),
+    grid: &Grid,
+) -> Option<GridPosition> {
+    let (camera, camera_transform) = *camera;
+    let cursor = window.cursor_position()?;
+    let world = camera.viewport_to_world_2d(camera_transform, cursor).ok()?;
+    Some(grid.world_to_grid(world))
+}
+
+/// Inclusive min/max cells of the dragged area
+fn drag_bounds(a: GridPosition, b: GridPosition) -> (I64Vec2, I64Vec2) {
+    (a.0.min(b.0), a.0.max(b.0))
+}
+
+pub fn bulk_remove_drag(
+    mut commands: Commands,
+    mut drag: ResMut<BulkRemoveDrag>,
+    mut mouse_event: ResMut<MouseButtonEvent>,
+    // grouped to stay under the system param limit
+    (mouse, keyboard): (Res<ButtonInput<MouseButton>>, Res<ButtonInput<KeyCode>>),
+    (window, camera): (Single<&Window, With<PrimaryWindow>>, Single<(&Camera, &GlobalTransform)>),
+    grid: Res<Grid>,
+    world_map: Res<WorldMap>,
+    ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    mut rect_query: Query<(Entity, &mut Transform, &mut Sprite), With<BulkRemoveRect>>,
+    links: Query<Option<&BuildCost>, crate::factory::physical::PlainWire>,
+    tiles: Query<&Tile>,
+    buildings: Query<(Option<&BuildCost>, Has<Undeletable>, &Tiles)>,
+    mut removal_events: MessageWriter<RemoveBuildingRequest>,
+    mut player: ResMut<crate::player::Player>,
+    game_mode: Res<crate::game_mode::GameMode>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+) {
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let Some(cell) = cursor_cell(&window, &*camera, &grid) else { return };
+
+    if drag.start.is_none() {
+        let over_ui = ui_blocker_query
+            .iter()
+            .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed);
+        if shift && mouse.just_pressed(MouseButton::Right) && !over_ui {
+            // take the press so the single right-click removal doesn't also fire
+            mouse_event.handle();
+            drag.start = Some(cell);
+            commands.spawn((
+                Sprite {
+                    color: Color::srgba(1.0, 0.2, 0.2, 0.25),
+                    custom_size: Some(Vec2::splat(grid.scale)),
+                    ..default()
+                },
+                Transform::from_translation(grid.grid_to_world_center(&cell).extend(z_layers::BADGE)),
+                BulkRemoveRect,
+            ));
+        }
+        return;
+    }
+    let start = drag.start.unwrap();
+    let (min, max) = drag_bounds(start, cell);
+
+    if mouse.pressed(MouseButton::Right) {
+        // snap to cell boundaries: from the bottom-left corner of min to the top-right of max
+        let from = grid.grid_to_world_corner(&GridPosition(min));
+        let to = grid.grid_to_world_corner(&GridPosition(max + I64Vec2::ONE));
+        for (_, mut transform, mut sprite) in rect_query.iter_mut() {
+            transform.translation = ((from + to) / 2.0).extend(z_layers::BADGE);
+            sprite.custom_size = Some(to - from);
+        }
+        return;
+    }
+
+    // released, tear down everything in the rectangle
+    drag.start = None;
+    for (entity, _, _) in rect_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let mut removed = 0;
+    let mut refund = 0;
+    let mut seen_buildings = Vec::new();
+    for x in min.x..=max.x {
+        for y in min.y..=max.y {
+            let Some(entities) = world_map.get(&GridPosition(I64Vec2::new(x, y))) else { continue };
+            for &entity in entities.iter() {
+                if let Ok(cost) = links.get(entity) {
+                    refund += crate::ui::shop::wire_refund(cost, &game_mode);
+                    removed += 1;
+                    commands
+                        .entity(entity)
+                        .remove::<PhysicalLink>()
+                        .insert((MarkedForRemoval, NotUndoable));
+                    continue;
+                }
+                let Ok(tile) = tiles.get(entity) else { continue };
+                if seen_buildings.contains(&tile.0) {
+                    continue;
+                }
+                seen_buildings.push(tile.0);
+                let Ok((cost, undeletable, building_tiles)) = buildings.get(tile.0) else { continue };
+                if undeletable {
+                    for tile in building_tiles.iter() {
+                        commands.entity(*tile).insert(CantRemoveFlash(FLASH_SECONDS));
+                    }
+                    continue;
+                }
+                if let Some(cost) = cost {
+                    refund += (game_mode.placement_cost(cost.0) as f32 * BULK_REMOVE_REFUND_FRACTION).round() as i32;
+                }
+                removed += 1;
+                // refunded, so Ctrl+Z bringing it back for free would be a money printer
+                commands.entity(tile.0).insert(NotUndoable);
+                removal_events.write(RemoveBuildingRequest { tile: entity });
+            }
+        }
+    }
+
+    if removed == 0 {
+        return;
+    }
+    player.money += refund;
+    info!("Bulk removed {} building(s) between {:?} and {:?}, refunded {}", removed, min, max, refund);
+    news_writer.write(crate::events::AddNewsfeedItemEvent {
+        faction: Faction::default(),
+        headline: format!("Cleared {} building{} for a ${} refund", removed, if removed == 1 { "" } else { "s" }, refund),
+        payload: None,
+    });
+}
+
+/// Tints the tiles red for a moment
+pub fn flash_undeletable(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut flashes: Query<(Entity, &mut CantRemoveFlash, Option<&mut Sprite>)>,
+) {
+    for (entity, mut flash, sprite) in flashes.iter_mut() {
+        flash.0 -= time.delta_secs();
+        let t = (flash.0 / FLASH_SECONDS).clamp(0.0, 1.0);
+        if let Some(mut sprite) = sprite {
+            sprite.color = Color::srgb(1.0, 1.0 - 0.7 * t, 1.0 - 0.7 * t);
+        }
+        if flash.0 <= 0.0 {
+            commands.entity(entity).remove::<CantRemoveFlash>();
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+use crate::factory::building_visuals::z_layers;
+use crate::factory::buildings::{Tile, Tiles};
+use crate::factory::physical::{ConnectionBroken, ConnectionEstablished};
+use crate::grid::{Grid, GridPosition};
+
+const CONNECT_FLASH_SECONDS: f32 = 0.4;
+/// Longer than the connect one, it's the one that matters
+const BREAK_FLASH_SECONDS: f32 = 0.9;
+/// More joins than this in a frame is a load or a paste, no point lighting up the whole map
+const MAX_CONNECT_FLASHES: usize = 32;
+
+/// Coloured square over one cell of a building that just got connected or cut off
+#[derive(Component)]
+pub struct ConnectionFlash {
+    remaining: f32,
+    duration: f32,
+    alpha: f32,
+}
+
+/// Every cell of the building an endpoint belongs to, a wire is just its own cell
+fn endpoint_cells(
+    entity: Entity,
+    tile_parent: &Query<&Tile>,
+    tiles_of: &Query<&Tiles>,
+    positions: &Query<&GridPosition>,
+) -> Vec<GridPosition> {
+    let building = tile_parent.get(entity).map_or(entity, |tile| tile.0);
+    let mut cells: Vec<GridPosition> = positions.get(building).ok().copied().into_iter().collect();
+    if let Ok(tiles) = tiles_of.get(building) {
+        cells.extend(tiles.iter().filter_map(|tile| positions.get(*tile).ok().copied()));
+    }
+    cells
+}
+
+fn spawn_flashes(commands: &mut Commands, grid: &Grid, cells: &HashSet<GridPosition>, color: Color, duration: f32) {
+    for cell in cells {
+        commands.spawn((
+            Sprite {
+                color,
+                custom_size: Some(Vec2::splat(grid.scale)),
+                ..default()
+            },
+            Transform::from_translation(grid.grid_to_world_center(cell).extend(z_layers::BADGE)),
+            ConnectionFlash {
+                remaining: duration,
+                duration,
+                alpha: color.alpha(),
+            },
+        ));
+    }
+}
+
+/// Click and a green pulse when things join up, a break sound and a red flash over both far
+/// ends when they come apart, so it's obvious what a deletion cut off
+pub fn announce_connection_changes(
+    mut commands: Commands,
+    mut established: MessageReader<ConnectionEstablished>,
+    mut broken: MessageReader<ConnectionBroken>,
+    (tile_parent, tiles_of, positions): (Query<&Tile>, Query<&Tiles>, Query<&GridPosition>),
+    grid: Res<Grid>,
+    mut sfx: MessageWriter<crate::audio::PlaySfx>,
+) {
+    let joined: Vec<_> = established.read().collect();
+    if !joined.is_empty() {
+        sfx.write(crate::audio::PlaySfx(crate::audio::Sfx::Connect));
+    }
+    if joined.len() <= MAX_CONNECT_FLASHES {
+        let cells: HashSet<GridPosition> = joined
+            .iter()
+            .flat_map(|c| [c.source, c.sink])
+            .flat_map(|e| endpoint_cells(e, &tile_parent, &tiles_of, &positions))
+            .collect();
+        spawn_flashes(&mut commands, &grid, &cells, Color::srgba(0.3, 1.0, 0.4, 0.45), CONNECT_FLASH_SECONDS);
+    }
+
+    // the removed wire itself is usually gone by now and just drops out here
+    let cells: HashSet<GridPosition> = broken
+        .read()
+        .flat_map(|c| [c.source, c.sink])
+        .flat_map(|e| endpoint_cells(e, &tile_parent, &tiles_of, &positions))
+        .collect();
+    if !cells.is_empty() {
+        sfx.write(crate::audio::PlaySfx(crate::audio::Sfx::Disconnect));
+        spawn_flashes(&mut commands, &grid, &cells, Color::srgba(1.0, 0.2, 0.2, 0.6), BREAK_FLASH_SECONDS);
+    }
+}
+
+pub fn fade_connection_flashes(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut flashes: Query<(Entity, &mut ConnectionFlash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in flashes.iter_mut() {
+        flash.remaining -= time.delta_secs();
+        if flash.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.color.set_alpha(flash.alpha * flash.remaining / flash.duration);
+    }
+}
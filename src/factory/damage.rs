@@ -0,0 +1,270 @@
+use crate::assets::GameAssets;
+use crate::factory::buildings::buildings::Building;
+use crate::factory::logical::LogicalLink;
+use crate::factory::physical::{walk_chain, PhysicalLink, PhysicalSink, PhysicalSource};
+use crate::grid::{Grid, WorldMap};
+use crate::ui::tooltip::ToggleOnHover;
+use crate::ui::BlocksWorldClicks;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Damaged wires fix themselves after this long
+pub const WIRE_SELF_REPAIR_SECONDS: f32 = 90.0;
+/// Instant repair costs this fraction of the wire price
+pub const WIRE_REPAIR_COST_FRACTION: f32 = 0.5;
+
+const CRACKED_TINT: Color = Color::srgb(0.85, 0.6, 0.45);
+const BROKEN_TINT: Color = Color::srgb(0.55, 0.3, 0.25);
+
+/// Damage on a wire segment. Severity 1 halves the segment's throughput,
+/// severity 2 zeroes it and breaks the logical chain until it's repaired.
+#[derive(Component, Debug, Clone)]
+pub struct Damaged {
+    pub severity: u8,
+    /// Seconds since the damage landed, for self repair
+    pub elapsed: f32,
+}
+
+impl Damaged {
+    pub fn new(severity: u8) -> Self {
+        Self {
+            severity: severity.clamp(1, 2),
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn throughput_factor(&self) -> f32 {
+        match self.severity {
+            1 => 0.5,
+            _ => 0.0,
+        }
+    }
+
+    pub fn breaks_chain(&self) -> bool {
+        self.severity >= 2
+    }
+}
+
+/// What a single segment contributes to its chain's capacity
+pub fn segment_throughput(link: &PhysicalLink, damage: Option<&Damaged>) -> f32 {
+    link.throughput * damage.map_or(1.0, Damaged::throughput_factor)
+}
+
+pub fn wire_repair_cost() -> i32 {
+    (PhysicalLink { throughput: 0.0 }.data().cost as f32 * WIRE_REPAIR_COST_FRACTION).ceil() as i32
+}
+
+/// Damage a segment instead of deleting it. Raids and other hazards go through here;
+/// repeated hits stack up to severity 2.
+pub fn damage_segment(commands: &mut Commands, segment: Entity, severity: u8, existing: Option<&Damaged>) {
+    let severity = existing.map_or(severity, |d| d.severity.saturating_add(severity));
+    commands.entity(segment).insert(Damaged::new(severity));
+}
+
+/// Pays for an instant repair of a damaged segment. Returns what it cost, or `None` if the
+/// player can't afford it and nothing changed
+pub fn repair_segment(
+    commands: &mut Commands,
+    segment: Entity,
+    player: &mut crate::player::Player,
+    game_mode: &crate::game_mode::GameMode,
+) -> Option<i32> {
+    let cost = game_mode.placement_cost(wire_repair_cost());
+    if !game_mode.can_afford(cost, player.money) {
+        info!("Can't afford wire repair ({} needed)", cost);
+        return None;
+    }
+    player.money -= cost;
+    commands.entity(segment).remove::<Damaged>();
+    info!("Repaired wire {:?} for {}", segment, cost);
+    Some(cost)
+}
+
+/// Smoke puff and repair prompt children, cleared on repair
+#[derive(Component)]
+pub struct DamageVisual;
+
+pub fn decorate_damaged_links(
+    mut commands: Commands,
+    mut damaged: Query<(Entity, Ref<Damaged>, Option<&mut Sprite>), With<PhysicalLink>>,
+    game_assets: Option<Res<GameAssets>>,
+) {
+    let Some(game_assets) = game_assets else { return };
+    for (entity, damage, sprite) in damaged.iter_mut() {
+        if !damage.is_changed() {
+            continue;
+        }
+        if let Some(mut sprite) = sprite {
+            sprite.color = if damage.breaks_chain() { BROKEN_TINT } else { CRACKED_TINT };
+        }
+        if !damage.is_added() {
+            continue;
+        }
+
+        let smoke = commands
+            .spawn((
+                Sprite {
+                    color: Color::srgba(0.35, 0.35, 0.35, 0.6),
+                    custom_size: Some(Vec2::splat(18.0)),
+                    ..default()
+                },
+                Transform::from_xyz(10.0, 14.0, crate::factory::building_visuals::z_layers::ORNAMENT),
+                DamageVisual,
+            ))
+            .id();
+        let prompt = commands
+            .spawn((
+                Text2d::new(format!("Click to repair (${})", wire_repair_cost())),
+                game_assets.text_font(14.0),
+                TextColor(Color::srgb(1.0, 0.85, 0.3)),
+                Transform::from_xyz(0.0, 40.0, crate::factory::building_visuals::z_layers::BADGE),
+                Visibility::Hidden,
+                DamageVisual,
+            ))
+            .id();
+        commands
+            .entity(entity)
+            .add_children(&[smoke, prompt])
+            .insert(ToggleOnHover(vec![prompt]));
+    }
+}
+
+pub fn self_repair_damaged_links(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut damaged: Query<(Entity, &mut Damaged)>,
+) {
+    for (entity, mut damage) in damaged.iter_mut() {
+        // bypass change detection so the tint isn't reapplied every frame
+        let damage = damage.bypass_change_detection();
+        damage.elapsed += time.delta_secs();
+        if damage.elapsed >= WIRE_SELF_REPAIR_SECONDS {
+            info!("Wire {:?} repaired itself", entity);
+            commands.entity(entity).remove::<Damaged>();
+        }
+    }
+}
+
+/// Restores the sprite and drops the damage children once a segment is repaired
+pub fn on_damage_removed(
+    trigger: On<Remove, Damaged>,
+    mut commands: Commands,
+    mut sprites: Query<&mut Sprite>,
+    children: Query<&Children>,
+    visuals: Query<(), With<DamageVisual>>,
+) {
+    let entity = trigger.entity;
+    if let Ok(mut sprite) = sprites.get_mut(entity) {
+        sprite.color = Color::WHITE;
+    }
+    if let Ok(children) = children.get(entity) {
+        for child in children.iter() {
+            if visuals.contains(child) {
+                commands.entity(child).despawn();
+            }
+        }
+    }
+    if let Ok(mut entity_commands) = commands.get_entity(entity) {
+        entity_commands.remove::<ToggleOnHover>();
+    }
+}
+
+/// Recomputes the logical link of every chain whose damage changed: severity 1
+/// lowers the capacity, severity 2 tears the link down, repairs rebuild it.
+pub fn refresh_links_on_damage(
+    mut commands: Commands,
+    changed: Query<Entity, Changed<Damaged>>,
+    mut repaired: RemovedComponents<Damaged>,
+    physical_sinks: Query<&PhysicalSink>,
+    physical_sources: Query<&PhysicalSource>,
+    physical_links: Query<(&PhysicalLink, Option<&Damaged>)>,
+    mut logical_links: Query<&mut LogicalLink>,
+) {
+    let affected: Vec<Entity> = changed.iter().chain(repaired.read()).collect();
+
+    for segment in affected {
+        let (Ok(sink), Ok(source)) = (physical_sinks.get(segment), physical_sources.get(segment)) else {
+            continue;
+        };
+        let Some((source_endpoint, sink_endpoint, chain)) =
+            walk_chain(segment, sink, source, &physical_sinks, &physical_sources)
+        else {
+            continue;
+        };
+
+        match chain_throughput(&chain, &physical_links) {
+            Some(throughput) => {
+                let logical_link = LogicalLink {
+                    links: chain,
+                    throughput,
+                    source: source_endpoint,
+                    sink: sink_endpoint,
+                };
+                if let Ok(mut existing) = logical_links.get_mut(sink_endpoint) {
+                    *existing = logical_link;
+                } else {
+                    commands.entity(sink_endpoint).insert(logical_link);
+                }
+            }
+            None => {
+                if logical_links.get(sink_endpoint).is_ok_and(|l| l.links.contains(&segment)) {
+                    commands.entity(sink_endpoint).remove::<LogicalLink>();
+                }
+            }
+        }
+    }
+}
+
+/// Capacity of a chain of segments, or `None` if a severity 2 segment breaks it
+pub fn chain_throughput(
+    chain: &[Entity],
+    physical_links: &Query<(&PhysicalLink, Option<&Damaged>)>,
+) -> Option<f32> {
+    let mut throughput = f32::INFINITY;
+    for (link, damage) in chain.iter().filter_map(|&e| physical_links.get(e).ok()) {
+        if damage.is_some_and(Damaged::breaks_chain) {
+            return None;
+        }
+        throughput = throughput.min(segment_throughput(link, damage));
+    }
+    Some(throughput)
+}
+
+/// Left click on a damaged wire (with nothing selected) pays for an instant repair
+pub fn handle_repair_click(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+    grid: Res<Grid>,
+    world_map: Res<WorldMap>,
+    damaged: Query<(), With<Damaged>>,
+    selected_building_type: Res<crate::ui::shop::SelectedBuildingType>,
+    ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    mut player: ResMut<crate::player::Player>,
+    game_mode: Res<crate::game_mode::GameMode>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || selected_building_type.0.is_some() {
+        return;
+    }
+    if ui_blocker_query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed)
+    {
+        return;
+    }
+
+    let Ok(window) = windows.single() else { return };
+    let Ok((camera, cam_xform)) = camera_q.single() else { return };
+    let Some(cursor_screen) = window.cursor_position() else { return };
+    let Ok(world_pos) = camera.viewport_to_world_2d(cam_xform, cursor_screen) else { return };
+
+    let Some(entities) = world_map.get(&grid.world_to_grid(world_pos)) else {
+        return;
+    };
+    let Some(&segment) = entities.iter().find(|&&e| damaged.contains(e)) else {
+        return;
+    };
+
+    repair_segment(&mut commands, segment, &mut player, &game_mode);
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::economics::BuildCost;
+use crate::factory::building_visuals::z_layers;
+use crate::factory::bulk_remove::cursor_cell;
+use crate::factory::physical::{PhysicalLink, PlainWire};
+use crate::factory::undo::NotUndoable;
+use crate::factory::MarkedForRemoval;
+use crate::grid::{Grid, GridPosition, WorldMap};
+use crate::ui::BlocksWorldClicks;
+
+/// How long the refund total stays up once the drag's over
+const COUNTER_LINGER_SECONDS: f32 = 1.5;
+
+/// Right button held from `last_cell`. It only starts taking wires once the cursor leaves the
+/// cell it was pressed on, that first cell is the normal single right-click's
+#[derive(Resource, Default)]
+pub struct WireRemoveDrag {
+    last_cell: Option<GridPosition>,
+    removed: u32,
+    refund: i32,
+}
+
+/// Running count by the cursor, left up for a moment with the refund after release
+#[derive(Component)]
+pub struct WireRemoveCounter {
+    linger: Option<f32>,
+}
+
+fn end_drag(drag: &mut WireRemoveDrag, counters: &mut Query<(&mut Text2d, &mut Transform, &mut WireRemoveCounter)>) {
+    if drag.removed > 0 {
+        info!("Drag removed {} wire(s), refunded {}", drag.removed, drag.refund);
+        for (mut text, _, mut counter) in counters.iter_mut() {
+            **text = format!("-{} wire{}, +${}", drag.removed, if drag.removed == 1 { "" } else { "s" }, drag.refund);
+            counter.linger = Some(COUNTER_LINGER_SECONDS);
+        }
+    }
+    *drag = WireRemoveDrag::default();
+}
+
+/// Right-drag takes out every plain wire the cursor crosses, cells skipped between frames
+/// included, each with the same refund the rectangle gives. Building tiles are never
+/// touched, and the drag ends the moment the cursor is over a panel
+pub fn drag_remove_wires(
+    mut commands: Commands,
+    mut drag: ResMut<WireRemoveDrag>,
+    (mouse, keyboard): (Res<ButtonInput<MouseButton>>, Res<ButtonInput<KeyCode>>),
+    (window, camera): (Single<&Window, With<PrimaryWindow>>, Single<(&Camera, &GlobalTransform)>),
+    (grid, world_map): (Res<Grid>, Res<WorldMap>),
+    ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    links: Query<Option<&BuildCost>, (PlainWire, Without<MarkedForRemoval>)>,
+    mut counters: Query<(&mut Text2d, &mut Transform, &mut WireRemoveCounter)>,
+    mut player: ResMut<crate::player::Player>,
+    (game_mode, game_assets): (Res<crate::game_mode::GameMode>, Res<crate::assets::GameAssets>),
+) {
+    let over_ui = ui_blocker_query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed);
+    let cell = cursor_cell(&window, &*camera, &grid);
+
+    let Some(last_cell) = drag.last_cell else {
+        // shift is the rectangle's, and a right press mid wire placement cancels that instead
+        let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        if mouse.just_pressed(MouseButton::Right) && !shift && !mouse.pressed(MouseButton::Left) && !over_ui {
+            drag.last_cell = cell;
+        }
+        return;
+    };
+    if !mouse.pressed(MouseButton::Right) || over_ui {
+        end_drag(&mut drag, &mut counters);
+        return;
+    }
+    let Some(cell) = cell else { return };
+    if cell == last_cell {
+        return;
+    }
+    drag.last_cell = Some(cell);
+
+    let mut refund = 0;
+    for step in crate::ui::shop::cells_along(*last_cell, *cell) {
+        let Some(entities) = world_map.get(&GridPosition(step)) else { continue };
+        for &entity in entities.iter() {
+            let Ok(cost) = links.get(entity) else { continue };
+            refund += crate::ui::shop::wire_refund(cost, &game_mode);
+            drag.removed += 1;
+            commands
+                .entity(entity)
+                .remove::<PhysicalLink>()
+                .insert((MarkedForRemoval, NotUndoable));
+        }
+    }
+    player.money += refund;
+    drag.refund += refund;
+
+    if drag.removed == 0 {
+        return;
+    }
+    let position = grid.grid_to_world_center(&cell).extend(z_layers::BADGE) + Vec3::new(40.0, 40.0, 10.0);
+    let text = format!("-{}", drag.removed);
+    match counters.single_mut() {
+        Ok((mut counter_text, mut transform, mut counter)) => {
+            **counter_text = text;
+            transform.translation = position;
+            counter.linger = None;
+        }
+        Err(_) => {
+            commands.spawn((
+                Text2d::new(text),
+                game_assets.text_font(20.0),
+                TextColor(Color::srgb(1.0, 0.45, 0.35)),
+                Transform::from_translation(position),
+                WireRemoveCounter { linger: None },
+            ));
+        }
+    }
+}
+
+pub fn fade_wire_remove_counter(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut counters: Query<(Entity, &mut WireRemoveCounter)>,
+) {
+    for (entity, mut counter) in counters.iter_mut() {
+        let Some(linger) = counter.linger.as_mut() else { continue };
+        *linger -= time.delta_secs();
+        if *linger <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
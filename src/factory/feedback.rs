@@ -0,0 +1,194 @@
+use crate::assets::GameAssets;
+use crate::factory::buildings::Tile;
+use crate::factory::logical::LogicalLink;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+/// Marks a building that is part of a circular configuration. Its processing is
+/// skipped and its outputs stay at zero until the loop is broken.
+#[derive(Component)]
+pub struct FeedbackLoop;
+
+/// The warning shown over a building in a feedback loop
+#[derive(Component)]
+pub struct FeedbackLoopWarning;
+
+/// Building-level graph: source building -> sink building, one edge per logical link
+pub type BuildingGraph = HashMap<Entity, HashSet<Entity>>;
+
+/// The building a port tile belongs to (or the entity itself for single-entity buildings)
+fn owning_building(entity: Entity, tiles: &Query<&Tile>) -> Entity {
+    tiles.get(entity).map_or(entity, |tile| tile.0)
+}
+
+/// All nodes weakly connected to `start`, i.e. everything a change at `start` could affect
+fn weak_component(start: &[Entity], forward: &BuildingGraph, backward: &BuildingGraph) -> HashSet<Entity> {
+    let mut seen: HashSet<Entity> = HashSet::new();
+    let mut stack: Vec<Entity> = start.to_vec();
+    while let Some(node) = stack.pop() {
+        if !seen.insert(node) {
+            continue;
+        }
+        for graph in [forward, backward] {
+            if let Some(next) = graph.get(&node) {
+                stack.extend(next.iter().copied().filter(|n| !seen.contains(n)));
+            }
+        }
+    }
+    seen
+}
+
+/// Nodes of `scope` that sit on a cycle: strongly connected components with more
+/// than one node, or a node with an edge to itself. Tarjan's algorithm.
+pub fn nodes_in_cycles(graph: &BuildingGraph, scope: &HashSet<Entity>) -> HashSet<Entity> {
+    struct Tarjan<'a> {
+        graph: &'a BuildingGraph,
+        scope: &'a HashSet<Entity>,
+        index: HashMap<Entity, usize>,
+        lowlink: HashMap<Entity, usize>,
+        on_stack: HashSet<Entity>,
+        stack: Vec<Entity>,
+        next_index: usize,
+        result: HashSet<Entity>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, node: Entity) {
+            self.index.insert(node, self.next_index);
+            self.lowlink.insert(node, self.next_index);
+            self.next_index += 1;
+            self.stack.push(node);
+            self.on_stack.insert(node);
+
+            let neighbours: Vec<Entity> = self
+                .graph
+                .get(&node)
+                .map(|n| n.iter().copied().filter(|e| self.scope.contains(e)).collect())
+                .unwrap_or_default();
+            for next in neighbours {
+                if !self.index.contains_key(&next) {
+                    self.visit(next);
+                    let low = self.lowlink[&node].min(self.lowlink[&next]);
+                    self.lowlink.insert(node, low);
+                } else if self.on_stack.contains(&next) {
+                    let low = self.lowlink[&node].min(self.index[&next]);
+                    self.lowlink.insert(node, low);
+                }
+            }
+
+            if self.lowlink[&node] == self.index[&node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(&member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                let self_loop = self.graph.get(&node).is_some_and(|n| n.contains(&node));
+                if component.len() > 1 || self_loop {
+                    self.result.extend(component);
+                }
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        graph,
+        scope,
+        index: HashMap::new(),
+        lowlink: HashMap::new(),
+        on_stack: HashSet::new(),
+        stack: Vec::new(),
+        next_index: 0,
+        result: HashSet::new(),
+    };
+    for &node in scope.iter() {
+        if !tarjan.index.contains_key(&node) {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.result
+}
+
+/// Rechecks the parts of the building graph touched by changed logical links
+/// and updates the `FeedbackLoop` markers there
+pub fn detect_feedback_loops(
+    mut commands: Commands,
+    links: Query<(Entity, Ref<LogicalLink>)>,
+    mut removed_links: RemovedComponents<LogicalLink>,
+    tiles: Query<&Tile>,
+    marked: Query<Entity, With<FeedbackLoop>>,
+) {
+    let mut touched: Vec<Entity> = links
+        .iter()
+        .filter(|(_, link)| link.is_changed())
+        .map(|(sink, _)| owning_building(sink, &tiles))
+        .collect();
+    touched.extend(removed_links.read().map(|sink| owning_building(sink, &tiles)));
+    if touched.is_empty() {
+        return;
+    }
+    // an edge that went away may have belonged to a loop we can no longer see from its
+    // despawned ends, so the currently marked buildings are always rechecked too
+    touched.extend(marked.iter());
+
+    let mut forward: BuildingGraph = HashMap::new();
+    let mut backward: BuildingGraph = HashMap::new();
+    for (sink, link) in links.iter() {
+        let from = owning_building(link.source, &tiles);
+        let to = owning_building(sink, &tiles);
+        forward.entry(from).or_default().insert(to);
+        backward.entry(to).or_default().insert(from);
+    }
+
+    let scope = weak_component(&touched, &forward, &backward);
+    let in_cycle = nodes_in_cycles(&forward, &scope);
+
+    for &building in scope.iter() {
+        let is_marked = marked.contains(building);
+        let Ok(mut entity_commands) = commands.get_entity(building) else {
+            continue;
+        };
+        if in_cycle.contains(&building) && !is_marked {
+            info!("Feedback loop detected through building {:?}", building);
+            entity_commands.insert(FeedbackLoop);
+        } else if !in_cycle.contains(&building) && is_marked {
+            info!("Feedback loop through building {:?} cleared", building);
+            entity_commands.remove::<FeedbackLoop>();
+        }
+    }
+}
+
+pub fn on_feedback_loop_added(
+    trigger: On<Add, FeedbackLoop>,
+    mut commands: Commands,
+    game_assets: Option<Res<GameAssets>>,
+) {
+    let Some(game_assets) = game_assets else { return };
+    let warning = commands
+        .spawn((
+            Text2d::new("Feedback loop - data cannot circulate"),
+            game_assets.text_font(16.0),
+            TextColor(Color::srgb(1.0, 0.35, 0.25)),
+            Transform::from_xyz(0.0, 48.0, crate::factory::building_visuals::z_layers::BADGE),
+            FeedbackLoopWarning,
+        ))
+        .id();
+    commands.entity(trigger.entity).add_child(warning);
+}
+
+pub fn on_feedback_loop_removed(
+    trigger: On<Remove, FeedbackLoop>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    warnings: Query<(), With<FeedbackLoopWarning>>,
+) {
+    if let Ok(children) = children.get(trigger.entity) {
+        for child in children.iter() {
+            if warnings.contains(child) {
+                commands.entity(child).despawn();
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::factory::buildings::{Tile, Tiles};
+use crate::factory::logical::LogicalLink;
+use crate::factory::physical::PhysicalLink;
+use crate::grid::{Grid, WorldMap};
+use crate::ui::BlocksWorldClicks;
+
+/// One hue per logical link through the hovered building, cycled past four
+const PATH_HUES: [Color; 4] = [
+    Color::srgb(0.3, 0.95, 1.0),
+    Color::srgb(1.0, 0.4, 0.95),
+    Color::srgb(0.6, 1.0, 0.3),
+    Color::srgb(1.0, 0.7, 0.2),
+];
+/// How much darker everything off the hovered paths gets
+const DIM_AMOUNT: f32 = 0.25;
+
+/// The sprite's colour from before a path highlight went on, put back when the cursor leaves
+#[derive(Component)]
+pub struct PathHighlightRestore(pub Color);
+
+/// Wires and buildings, the things that get tinted or dimmed
+type PathSprites<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static mut Sprite, Option<&'static PathHighlightRestore>),
+    Or<(With<PhysicalLink>, With<Tiles>)>,
+>;
+
+/// The building under the cursor, out in the world and not behind a panel
+fn hovered_building(
+    window: &Window,
+    camera: (&Camera, &GlobalTransform),
+    grid: &Grid,
+    world_map: &WorldMap,
+    tiles: &Query<&Tile>,
+) -> Option<Entity> {
+    let (camera, camera_transform) = camera;
+    let world_pos = window
+        .cursor_position()
+        .and_then(|p| camera.viewport_to_world_2d(camera_transform, p).ok())?;
+    world_map
+        .get(&grid.world_to_grid(world_pos))?
+        .iter()
+        .find_map(|e| tiles.get(*e).ok().map(|tile| tile.0))
+}
+
+/// Colour for every wire and endpoint building on the logical links that start or end at
+/// `building`. Links are sorted so a path keeps its hue while the cursor stays put
+fn path_colors(building: Entity, links: &Query<(Entity, &LogicalLink)>, tiles: &Query<&Tile>) -> HashMap<Entity, Color> {
+    let owner = |e: Entity| tiles.get(e).map_or(e, |tile| tile.0);
+    let mut through: Vec<_> = links
+        .iter()
+        .filter(|(_, link)| owner(link.source) == building || owner(link.sink) == building)
+        .collect();
+    through.sort_by_key(|(entity, _)| *entity);
+
+    let mut colors = HashMap::new();
+    for (i, (_, link)) in through.iter().enumerate() {
+        let hue = PATH_HUES[i % PATH_HUES.len()];
+        for wire in &link.links {
+            colors.insert(*wire, hue);
+        }
+        // a building shared by several paths takes the first one's hue
+        colors.entry(owner(link.source)).or_insert(hue);
+        colors.entry(owner(link.sink)).or_insert(hue);
+    }
+    colors
+}
+
+/// Hovering a building that's the end of a logical link lights up the whole chain, one hue per
+/// link, and dims everything else a little. Drops the moment the cursor moves off it
+pub fn highlight_hovered_link_paths(
+    mut commands: Commands,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    (grid, world_map): (Res<Grid>, Res<WorldMap>),
+    tiles: Query<&Tile>,
+    links: Query<(Entity, &LogicalLink)>,
+    (changed_links, mut removed_links): (Query<(), Changed<LogicalLink>>, RemovedComponents<LogicalLink>),
+    ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    mut sprites: PathSprites,
+    mut shown: Local<Option<Entity>>,
+) {
+    let over_ui = ui_blocker_query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed);
+    let hovered = if over_ui { None } else { hovered_building(&window, *camera, &grid, &world_map, &tiles) };
+    let colors = hovered.map(|building| path_colors(building, &links, &tiles)).filter(|c| !c.is_empty());
+    let hovered = colors.as_ref().and(hovered);
+
+    let links_changed = !changed_links.is_empty() || removed_links.read().count() > 0;
+    if hovered == *shown && !(hovered.is_some() && links_changed) {
+        return;
+    }
+    *shown = hovered;
+
+    let Some(colors) = colors else {
+        for (entity, mut sprite, restore) in sprites.iter_mut() {
+            if let Some(restore) = restore {
+                sprite.color = restore.0;
+                commands.entity(entity).remove::<PathHighlightRestore>();
+            }
+        }
+        return;
+    };
+    for (entity, mut sprite, restore) in sprites.iter_mut() {
+        let original = match restore {
+            Some(restore) => restore.0,
+            None => {
+                commands.entity(entity).insert(PathHighlightRestore(sprite.color));
+                sprite.color
+            }
+        };
+        sprite.color = colors.get(&entity).copied().unwrap_or_else(|| original.darker(DIM_AMOUNT));
+    }
+}
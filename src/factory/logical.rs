@@ -1,17 +1,20 @@
-use crate::factory::buildings::{TileThroughputData, Tiles};
+use crate::factory::buildings::{Tile, TileThroughputData, Tiles};
+use crate::factory::feedback::FeedbackLoop;
 use crate::grid::Direction;
-use bevy::prelude::{DetectChanges, Query, Ref, Res};
+use crate::factory::physical::PhysicalLink;
+use bevy::prelude::{Commands, DetectChanges, Query, Ref, Res, ResMut, Resource, With};
 use bevy::time::Time;
 use bevy::{
     ecs::{component::Component, entity::Entity},
     platform::collections::{HashMap, HashSet},
 };
 use core::fmt;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Display, Formatter};
 
 // The fundamental types of data
-#[derive(Component, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize)]
+#[derive(Component, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum BasicDataType {
     Biometric,   // A
     Economic,    // B
@@ -31,7 +34,7 @@ impl BasicDataType {
 }
 
 // Attributes that modify a data stream
-#[derive(Component, Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize, PartialOrd, Ord)]
+#[derive(Component, Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize, PartialOrd, Ord)]
 pub enum DataAttribute {
     Aggregated,
     DeIdentified,
@@ -50,7 +53,7 @@ impl DataAttribute {
     }
 }
 
-#[derive(Component, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Dataset {
     // The core of the data packet.
     // Maps each data type present in the packet to a set of its attributes.
@@ -106,6 +109,16 @@ impl Display for Dataset {
     }
 }
 
+/// What a delinker, trunker or combiner last made of its inputs, kept around for the
+/// hover panel. Outputs are in port order, each with the output tile it leaves from
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct PortOutputs {
+    pub inputs: Vec<Option<Dataset>>,
+    pub outputs: Vec<(Entity, Option<Dataset>)>,
+    /// Why nothing's coming out, when it isn't
+    pub stalled: Option<String>,
+}
+
 #[derive(Component, Debug)]
 pub struct DataSink {
     pub direction: Direction,
@@ -120,6 +133,17 @@ pub struct DataSource {
     pub(crate) limited: bool,
 }
 
+/// How many seconds of throughput a buffer holds before it stops taking more.
+/// This is what makes a saturated sink back up the chain instead of everything draining forever.
+pub const BACKLOG_SECONDS: f32 = 1.0;
+
+impl DataSource {
+    /// What still fits in the output buffer before the machine has to stop
+    pub(crate) fn headroom(&self) -> f32 {
+        (self.throughput * BACKLOG_SECONDS - self.buffer.value).max(0.)
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct DataBuffer {
     pub(crate) shape: Option<Dataset>,
@@ -227,6 +251,298 @@ pub fn calculate_throughput(
     }
 }
 
+/// How busy a wire segment is, summed over every logical link routed through it.
+/// Refreshed on the same timer as `calculate_throughput`.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct LinkUtilization {
+    /// Data per second passing through
+    pub flow: f32,
+    /// `flow` over the segment's capacity
+    pub utilization: f32,
+    /// Whether any logical link uses this segment at all
+    pub connected: bool,
+}
+
+/// Runs before `reset_delta`, the flow of a logical link is what its sink took in last second
+pub fn record_link_utilization(
+    mut commands: Commands,
+    logical_links: Query<(&LogicalLink, &DataSink)>,
+    physical_links: Query<(Entity, &PhysicalLink, Option<&LinkUtilization>)>,
+) {
+    let mut flows: HashMap<Entity, f32> = HashMap::new();
+    for (link, sink) in logical_links.iter() {
+        for segment in link.links.iter() {
+            *flows.entry(*segment).or_insert(0.) += sink.buffer.last_in;
+        }
+    }
+
+    for (entity, physical, previous) in physical_links.iter() {
+        let utilization = match flows.get(&entity) {
+            Some(flow) => LinkUtilization {
+                flow: *flow,
+                utilization: if physical.throughput > 0. { flow / physical.throughput } else { 0. },
+                connected: true,
+            },
+            None => LinkUtilization::default(),
+        };
+        // only touch it when it moved so the overlay can go off change detection
+        if previous != Some(&utilization) {
+            commands.entity(entity).insert(utilization);
+        }
+    }
+}
+
+/// What's arriving at a sink building, summed over its linked tiles. Refreshed once a second
+/// with the throughput numbers so the tooltip doesn't have to walk the tiles itself.
+#[derive(Component, Debug, Default, Clone)]
+pub struct SinkDeliveries {
+    /// Each distinct dataset coming in and its rate per second
+    pub datasets: Vec<(Dataset, f32)>,
+    /// Per data type: every attribute it arrives with and the combined rate of datasets carrying it
+    pub types: BTreeMap<BasicDataType, (HashSet<DataAttribute>, f32)>,
+}
+
+impl SinkDeliveries {
+    /// How the arriving data counts towards a contract asking for `required`, per requested type
+    pub fn contributions_for(&self, required: &Dataset) -> Vec<crate::contracts::TypeContribution> {
+        crate::contracts::sum_contributions(
+            self.datasets.iter().map(|(dataset, rate)| (dataset, *rate as f64)),
+            required,
+            crate::contracts::PARTIAL_ATTRIBUTE_CREDIT,
+        )
+    }
+
+    pub fn total(&self) -> f32 {
+        self.datasets.iter().map(|(_, rate)| *rate).sum()
+    }
+}
+
+/// Runs before `reset_delta`, while `last_in` still holds the last second's worth
+pub fn aggregate_sink_deliveries(
+    tiles: Query<(&DataSink, &Tile), With<LogicalLink>>,
+    mut sinks: Query<(Entity, &mut SinkDeliveries)>,
+) {
+    let mut arriving: HashMap<Entity, HashMap<Dataset, f32>> = HashMap::new();
+    for (sink, tile) in tiles.iter() {
+        if let Some(dataset) = &sink.buffer.shape {
+            *arriving
+                .entry(tile.0)
+                .or_default()
+                .entry(dataset.clone())
+                .or_insert(0.) += sink.buffer.last_in;
+        }
+    }
+
+    for (entity, mut deliveries) in sinks.iter_mut() {
+        let mut datasets: Vec<(Dataset, f32)> = arriving.remove(&entity).unwrap_or_default().into_iter().collect();
+        datasets.sort_by(|(a, _), (b, _)| a.to_string().cmp(&b.to_string()));
+        let mut types: BTreeMap<BasicDataType, (HashSet<DataAttribute>, f32)> = BTreeMap::new();
+        for (dataset, rate) in datasets.iter() {
+            for (data_type, attributes) in dataset.contents.iter() {
+                let entry = types.entry(*data_type).or_default();
+                entry.0.extend(attributes.iter().copied());
+                entry.1 += rate;
+            }
+        }
+        deliveries.datasets = datasets;
+        deliveries.types = types;
+    }
+}
+
+/// How long a data type/attribute combination counts as produced after it last went anywhere
+pub const RECENT_PRODUCTION_SECONDS: f64 = 30.0;
+
+/// What's been coming out of the player's sources and machines lately, each combination of
+/// data type and attributes with when it was last seen moving. Contract offers lean on this
+#[derive(Resource, Default, Debug)]
+pub struct ProductionSummary {
+    pub seen: HashMap<(BasicDataType, BTreeSet<DataAttribute>), f64>,
+    /// Each type's busiest outlet over the last second. The busiest rather than the total, data
+    /// leaving a machine already left the source before it
+    pub rates: HashMap<BasicDataType, f32>,
+}
+
+impl ProductionSummary {
+    pub fn rate(&self, data_type: BasicDataType) -> f32 {
+        self.rates.get(&data_type).copied().unwrap_or(0.0)
+    }
+
+    pub fn has_attribute(&self, attribute: DataAttribute) -> bool {
+        self.seen.keys().any(|(_, attributes)| attributes.contains(&attribute))
+    }
+
+    /// How much of a contract's dataset is already flowing, 0 to 1. A type that's flowing counts
+    /// half, the rest is for having the attributes on it
+    pub fn coverage(&self, dataset: &Dataset) -> f32 {
+        if dataset.contents.is_empty() {
+            return 1.0;
+        }
+        let total: f32 = dataset
+            .contents
+            .iter()
+            .map(|(data_type, attributes)| {
+                self.seen
+                    .keys()
+                    .filter(|(seen_type, _)| seen_type == data_type)
+                    .map(|(_, seen_attributes)| {
+                        let matched = attributes.iter().filter(|a| seen_attributes.contains(a)).count();
+                        let fraction = if attributes.is_empty() { 1.0 } else { matched as f32 / attributes.len() as f32 };
+                        0.5 + 0.5 * fraction
+                    })
+                    .fold(0.0, f32::max)
+            })
+            .sum();
+        total / dataset.contents.len() as f32
+    }
+}
+
+/// Runs before `reset_delta`, anything a source let out in the last second counts
+pub fn summarize_production(
+    sources: Query<&DataSource>,
+    mut summary: ResMut<ProductionSummary>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs_f64();
+    summary.rates.clear();
+    for source in sources.iter() {
+        let Some(shape) = &source.buffer.shape else { continue };
+        if source.buffer.last_out <= 0. {
+            continue;
+        }
+        for (data_type, attributes) in shape.contents.iter() {
+            summary.seen.insert((*data_type, attributes.iter().copied().collect()), now);
+            let rate = summary.rates.entry(*data_type).or_insert(0.0);
+            *rate = rate.max(source.buffer.last_out);
+        }
+    }
+    summary.seen.retain(|_, last_seen| now - *last_seen <= RECENT_PRODUCTION_SECONDS);
+}
+
+/// How a source building's output is split between the lines it feeds, one entry per
+/// linked side. Recomputed on the throughput tick
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct SourceAllocation {
+    pub consumers: Vec<ConsumerShare>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConsumerShare {
+    pub direction: Direction,
+    /// What's downstream would take per second, infinite for a machine that isn't backed up
+    pub demand: f32,
+    pub allocated: f32,
+}
+
+impl ConsumerShare {
+    pub fn starved(&self) -> bool {
+        self.allocated + 0.05 < self.demand
+    }
+}
+
+/// Splits `supply` between `demands`. While there's enough to go round everyone gets what they
+/// want and the rest is spread in proportion to demand (evenly if nobody wants anything), so
+/// new demand gets picked up before the next recompute. When there isn't, it goes round robin:
+/// each round everyone still wanting more gets an equal slice of what's left, capped at their want
+pub fn allocate_fairly(supply: f32, demands: &[f32]) -> Vec<f32> {
+    let mut allocated = vec![0.; demands.len()];
+    let mut left = supply.max(0.);
+    let mut wanting: Vec<usize> = (0..demands.len()).filter(|i| demands[*i] > 0.).collect();
+    while left > f32::EPSILON && !wanting.is_empty() {
+        let slice = left / wanting.len() as f32;
+        left = 0.;
+        wanting.retain(|&i| {
+            let take = slice.min(demands[i] - allocated[i]);
+            allocated[i] += take;
+            left += slice - take;
+            demands[i] - allocated[i] > f32::EPSILON
+        });
+    }
+    if left > f32::EPSILON && !demands.is_empty() {
+        let wanted: f32 = demands.iter().sum();
+        for (share, demand) in allocated.iter_mut().zip(demands) {
+            *share += if wanted > 0. { left * demand / wanted } else { left / demands.len() as f32 };
+        }
+    }
+    allocated
+}
+
+/// Runs before `reset_delta`. Shares each source building's throughput out over the logical
+/// links leaving it by what's at the other end wants: a sink building its capacity (nothing
+/// while disabled), a machine whatever it's given unless it's backed up, then what it took last
+/// second. Ports with nothing linked keep an even split so a new connection flows straight away
+pub fn allocate_source_output(
+    mut commands: Commands,
+    buildings: Query<
+        (Entity, &crate::factory::buildings::source::SourceBuilding, &Tiles, Option<&SourceAllocation>),
+        bevy::prelude::Without<crate::factory::buildings::source::Depleted>,
+    >,
+    mut sources: Query<&mut DataSource>,
+    links: Query<(Entity, &LogicalLink, &DataSink)>,
+    tiles: Query<&Tile>,
+    capacities: Query<&crate::factory::buildings::sink::SinkCapacity>,
+    disabled: Query<(), With<crate::factory::buildings::sink::SinkDisabled>>,
+) {
+    // what each source tile feeds, links live on their sink end
+    let mut fed: HashMap<Entity, f32> = HashMap::new();
+    for (sink_entity, link, sink) in links.iter() {
+        let sink_building = tiles.get(sink_entity).map_or(sink_entity, |tile| tile.0);
+        let demand = if disabled.contains(sink_building) {
+            0.
+        } else if let Ok(capacity) = capacities.get(sink_building) {
+            capacity.per_sec
+        } else {
+            let backed_up = sources
+                .get(link.source)
+                .is_ok_and(|source| sink.buffer.value >= source.throughput * BACKLOG_SECONDS * 0.95);
+            if backed_up { sink.buffer.last_out } else { f32::INFINITY }
+        };
+        fed.insert(link.source, demand);
+    }
+
+    for (entity, building, building_tiles, previous) in buildings.iter() {
+        let ports = building_tiles.iter().filter(|tile| sources.contains(**tile)).count();
+        let even_split = building.throughput / ports.max(1) as f32;
+        let mut linked: Vec<(Entity, Direction, f32)> = building_tiles
+            .iter()
+            .filter_map(|tile| {
+                let demand = fed.get(tile)?;
+                let source = sources.get(*tile).ok()?;
+                Some((*tile, source.direction, *demand))
+            })
+            .collect();
+        linked.sort_by_key(|(tile, _, _)| *tile);
+        let demands: Vec<f32> = linked.iter().map(|(_, _, demand)| *demand).collect();
+        let shares = allocate_fairly(building.throughput, &demands);
+
+        for tile in building_tiles.iter() {
+            let Ok(mut source) = sources.get_mut(*tile) else { continue };
+            let throughput = linked
+                .iter()
+                .position(|(linked_tile, _, _)| linked_tile == tile)
+                .map_or(even_split, |i| shares[i]);
+            if source.throughput != throughput {
+                source.throughput = throughput;
+            }
+        }
+
+        let allocation = SourceAllocation {
+            consumers: linked
+                .iter()
+                .zip(&shares)
+                .map(|((_, direction, demand), allocated)| ConsumerShare {
+                    direction: *direction,
+                    demand: *demand,
+                    allocated: *allocated,
+                })
+                .collect(),
+        };
+        // sources nothing was ever wired to don't need one, there's thousands of them
+        if previous != Some(&allocation) && (previous.is_some() || !allocation.consumers.is_empty()) {
+            commands.entity(entity).insert(allocation);
+        }
+    }
+}
+
 pub fn reset_delta(sinks: Query<&mut DataSink>, sources: Query<&mut DataSource>) {
     for mut sink in sinks {
         sink.buffer.reset_delta();
@@ -238,10 +554,26 @@ pub fn reset_delta(sinks: Query<&mut DataSink>, sources: Query<&mut DataSource>)
 
 pub fn pass_data_system(
     mut sources: Query<&mut DataSource>,
-    sinks: Query<(&mut DataSink, &LogicalLink)>,
+    sinks: Query<(Entity, &mut DataSink, &LogicalLink)>,
+    tiles: Query<&Tile>,
+    looped: Query<(), With<FeedbackLoop>>,
+    disabled: Query<(), With<crate::factory::buildings::sink::SinkDisabled>>,
+    mut capacities: Query<&mut crate::factory::buildings::sink::SinkCapacity>,
     time: Res<Time>,
 ) {
-    for (mut sink, link) in sinks {
+    // what each sink building can still take this frame, shared between its tiles
+    let mut budgets: HashMap<Entity, f32> = HashMap::new();
+    for (sink_entity, mut sink, link) in sinks {
+        // buildings in a feedback loop don't output anything until it's broken
+        let source_building = tiles.get(link.source).map_or(link.source, |tile| tile.0);
+        if looped.contains(source_building) {
+            continue;
+        }
+        // sinks switched off by an event don't take anything in
+        let sink_building = tiles.get(sink_entity).map_or(sink_entity, |tile| tile.0);
+        if disabled.contains(sink_building) {
+            continue;
+        }
         //         thread 'Compute Task Pool (4)' panicked at src\factory\logical.rs:245:55:
         // called `Result::unwrap()` on an `Err` value: QueryDoesNotMatch(7155v511, ArchetypeId(183))
         // note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
@@ -252,29 +584,46 @@ pub fn pass_data_system(
         // Use proper error handling instead of unwrap() to avoid panic
         // TOOD: debug more closely
         if let Ok(mut source) = sources.get_mut(link.source) {
-            pass_data_external(&mut *source, &mut *sink, time.delta_secs());
+            if let Ok(mut capacity) = capacities.get_mut(sink_building) {
+                // sink buildings take what their contracts need times the overflow factor
+                let budget = budgets
+                    .entry(sink_building)
+                    .or_insert(capacity.per_sec * time.delta_secs());
+                let before = sink.buffer.last_in;
+                let refused = pass_data_external(&mut *source, &mut *sink, time.delta_secs(), *budget);
+                *budget = (*budget - (sink.buffer.last_in - before)).max(0.);
+                capacity.refused += refused;
+            } else {
+                // machine inputs stop taking data once they're a full backlog behind
+                let room = (source.throughput * BACKLOG_SECONDS - sink.buffer.value).max(0.);
+                pass_data_external(&mut *source, &mut *sink, time.delta_secs(), room);
+            }
         } else {
             // Log warning if source entity doesn't exist or doesn't have DataSource component
             println!("Warning: LogicalLink references invalid source entity {:?}", link.source);
         }
     }
 }
-pub fn pass_data_external(source: &mut DataSource, sink: &mut DataSink, secs: f32) {
+/// Moves at most `max_accept` across the link, returns how much of what was on offer got turned away
+pub fn pass_data_external(source: &mut DataSource, sink: &mut DataSink, secs: f32, max_accept: f32) -> f32 {
     sink.buffer.set_shape(source.buffer.shape.as_ref());
 
     if let Some(ref shape) = source.buffer.shape {
-        let packet = if source.limited {
+        let offered = if source.limited {
             source.buffer.value.clamp(0., source.throughput * secs)
         } else {
             source.throughput * secs
         };
+        let packet = offered.min(max_accept.max(0.));
 
         sink.buffer.add(&shape, packet);
         source.buffer.remove(packet);
+        return offered - packet;
     }
+    0.
 }
 pub fn pass_data_internal(source: &mut DataSource, sink: &mut DataSink, amount: f32) {
-    let amount = amount.min(sink.buffer.value);
+    let amount = amount.min(sink.buffer.value).min(source.headroom());
 
     if let Some(ref shape) = sink.buffer.shape {
         source.buffer.add(&shape, amount);
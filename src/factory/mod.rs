@@ -1,4 +1,7 @@
 use crate::factory::buildings::aggregator::do_aggregation;
+use crate::factory::buildings::cleaner::do_cleaning;
+use crate::factory::buildings::filter_splitter::do_filter_splitting;
+use crate::factory::buildings::deidentifier::do_deidentifying;
 use crate::factory::buildings::buildings::Building;
 use crate::factory::buildings::combiner::do_combining;
 use crate::factory::buildings::delinker::do_delinking;
@@ -6,7 +9,8 @@ use crate::factory::buildings::splitter::do_splitting;
 use crate::factory::buildings::trunker::do_trunking;
 use crate::factory::buildings::Undeletable;
 use crate::factory::logical::{
-    calculate_throughput, debug_logical_links, pass_data_system, reset_delta,
+    aggregate_sink_deliveries, allocate_source_output, calculate_throughput, debug_logical_links, pass_data_system,
+    record_link_utilization, reset_delta, summarize_production, ProductionSummary,
 };
 use crate::factory::physical::{
     assemble_direct_logical_links, assemble_logical_links, detect_building_placement, detect_link_placement,
@@ -26,13 +30,27 @@ use physical::remove_physical_link_on_right_click;
 use std::sync::Arc;
 use std::time::Duration;
 
+pub mod building_visuals;
 pub mod buildings;
+pub mod bulk_remove;
+pub mod damage;
+pub mod drag_remove;
+pub mod connection_feedback;
+pub mod feedback;
+pub mod link_highlight;
 pub mod logical;
 pub mod physical;
 pub mod source_visuals;
+pub mod throughput_overlay;
+pub mod undo;
+pub mod upgrade;
 
 pub struct FactoryPlugin;
 
+/// Building visuals, overlays and the mouse/keyboard tools for editing the factory.
+/// Split from `FactoryPlugin` so the simulation can run without a window or `GameAssets`.
+pub struct FactoryInteractionPlugin;
+
 /// Component marking an entity for removal in PostUpdate
 #[derive(Component)]
 pub struct MarkedForRemoval;
@@ -43,6 +61,10 @@ pub struct ConstructBuildingEvent {
     pub building: Arc<dyn Building>,
     pub grid_position: I64Vec2,
     pub orientation: Orientation,
+    /// Wires under the footprint that get removed (and partly refunded) as part of this placement
+    pub replaces: Vec<Entity>,
+    /// Put back by an undo: no charge, and it doesn't count as building something
+    pub free: bool,
 }
 
 /// Event for removing a building when a tile is clicked
@@ -55,17 +77,26 @@ impl Plugin for FactoryPlugin {
     fn build(&self, app: &mut bevy::app::App) {
         use crate::pause::GameState;
         
-        app.add_plugins(source_visuals::SourceVisualsPlugin);
         app.add_message::<ConstructBuildingEvent>();
         app.add_message::<RemoveBuildingRequest>();
+        app.init_resource::<undo::UndoBuffer>();
+        app.init_resource::<ProductionSummary>();
+        app.init_resource::<physical::ConnectionPassStats>();
+        app.init_resource::<physical::OpenChains>();
 
         // Register new messages for the message-based physical connection system
         app.add_message::<EntityPlaced>();
         app.add_message::<ValidateConnections>();
+        app.add_message::<physical::ConnectionEstablished>();
+        app.add_message::<physical::ConnectionBroken>();
 
         app.add_observer(on_physical_link_removed);
         app.add_observer(on_data_source_removed);
         app.add_observer(on_data_sink_removed);
+        app.add_observer(damage::on_damage_removed);
+        app.add_observer(feedback::on_feedback_loop_added);
+        app.add_observer(feedback::on_feedback_loop_removed);
+        // the simulation itself stops in either pause
         app.add_systems(
             Update,
             (
@@ -75,43 +106,114 @@ impl Plugin for FactoryPlugin {
                     do_splitting,
                     do_combining,
                     do_trunking,
+                    do_deidentifying,
+                    do_cleaning,
+                    do_filter_splitting,
                 ),
+                buildings::sink::update_sink_capacity,
                 pass_data_system,
+                damage::self_repair_damaged_links,
+                buildings::sink::tick_disabled_sinks,
+            )
+                .chain()
+                .run_if(in_state(GameState::Running)),
+        );
+        // connections and visuals keep going in ManualPause so building while paused works
+        app.add_systems(
+            Update,
+            (
                 (
                     // New event-based connection system
                     detect_link_placement,
                     detect_building_placement,
                     validate_placed_entities,
-                    resolve_connections,
+                    physical::classify_connection_entities,
+                    crate::crash::guarded("resolve_connections", resolve_connections),
                     update_link_sprite_on_connection,
                     assemble_direct_logical_links,
                     assemble_logical_links,
+                    damage::refresh_links_on_damage,
+                    feedback::detect_feedback_loops,
                     debug_logical_links,
                 )
                     .chain(),
-                // update_sink_debug_text,
+                damage::decorate_damaged_links,
             )
                 .chain()
-                .run_if(in_state(GameState::Running)),
+                .after(pass_data_system)
+                .run_if(in_state(GameState::Running).or(in_state(GameState::ManualPause))),
         );
         app.add_systems(
             Update,
-            (handle_construction_event,
-                process_entity_removal)
+            (handle_construction_event, process_entity_removal)
 
                 .run_if(in_state(GameState::Running).or(in_state(GameState::ManualPause))),
         );
         app.add_systems(
             PostUpdate,
             (
-                (calculate_throughput, reset_delta)
+                (calculate_throughput, buildings::source::drain_source_pools, aggregate_sink_deliveries, buildings::sink::settle_sink_capacity, crate::ui::stats::sample_stats, record_link_utilization, summarize_production, allocate_source_output, reset_delta)
                     .chain()
-                    .run_if(on_timer(Duration::from_secs(1)).and(in_state(GameState::Running))),
+                    // state first so the timer doesn't keep ticking through a pause and fire
+                    // the moment it ends
+                    .run_if(in_state(GameState::Running).and(on_timer(Duration::from_secs(1)))),
             ),
         );
+        app.add_systems(Update, handle_building_removal);
         app.add_systems(
             Update,
-            (remove_physical_link_on_right_click, handle_building_removal),
+            buildings::sink::update_sink_access.run_if(resource_changed::<crate::grid::WorldMap>),
+        );
+    }
+}
+
+impl Plugin for FactoryInteractionPlugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        use crate::pause::GameState;
+
+        app.add_plugins(source_visuals::SourceVisualsPlugin);
+        app.add_plugins(building_visuals::BuildingVisualsPlugin);
+        app.init_resource::<bulk_remove::BulkRemoveDrag>();
+        app.init_resource::<drag_remove::WireRemoveDrag>();
+        app.init_resource::<throughput_overlay::ThroughputOverlay>();
+
+        app.add_systems(
+            Update,
+            (
+                upgrade::open_upgrade_menu.after(crate::ui::interaction::convert_input),
+                upgrade::handle_upgrade_option.before(handle_construction_event),
+                upgrade::fade_upgrade_blocked_flashes,
+                connection_feedback::announce_connection_changes.after(assemble_logical_links),
+                connection_feedback::fade_connection_flashes,
+                undo::undo_last_removal,
+            )
+                .run_if(in_state(GameState::Running).or(in_state(GameState::ManualPause))),
+        );
+        app.add_systems(
+            Update,
+            (remove_physical_link_on_right_click, damage::handle_repair_click),
+        );
+        app.add_systems(
+            Update,
+            (
+                bulk_remove::bulk_remove_drag
+                    .after(crate::ui::interaction::convert_input)
+                    .before(remove_physical_link_on_right_click)
+                    .before(handle_building_removal),
+                bulk_remove::flash_undeletable,
+                // after the single right-click, which gets the cell the drag starts on
+                drag_remove::drag_remove_wires.after(remove_physical_link_on_right_click),
+                drag_remove::fade_wire_remove_counter,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                throughput_overlay::toggle_throughput_overlay,
+                throughput_overlay::apply_throughput_overlay,
+                link_highlight::highlight_hovered_link_paths,
+            )
+                .chain(),
         );
     }
 }
@@ -120,14 +222,51 @@ impl Plugin for FactoryPlugin {
 pub fn handle_construction_event(
     mut construct_events: MessageReader<ConstructBuildingEvent>,
     mut commands: Commands,
-    game_assets: Res<crate::assets::GameAssets>,
+    mut player: ResMut<crate::player::Player>,
+    game_mode: Res<crate::game_mode::GameMode>,
+    mut counters: ResMut<crate::achievements::RunCounters>,
+    wires: Query<Option<&crate::economics::BuildCost>, physical::PlainWire>,
+    mut sfx: MessageWriter<crate::audio::PlaySfx>,
 ) {
     for event in construct_events.read() {
         let base_position = GridPosition(event.grid_position);
+        let data = event.building.data();
+
+        // all or nothing: if anything under the footprint stopped being a wire since the
+        // preview, the placement doesn't happen and no wire is touched
+        if !event.replaces.iter().all(|wire| wires.contains(*wire)) {
+            warn!("Placement of {} at {:?} no longer only replaces wires, skipping", data.name, event.grid_position);
+            continue;
+        }
+        if !event.replaces.is_empty() {
+            let mut refund = 0;
+            for &wire in event.replaces.iter() {
+                refund += crate::ui::shop::wire_refund(wires.get(wire).ok().flatten(), &game_mode);
+                commands.entity(wire).remove::<physical::PhysicalLink>().insert((MarkedForRemoval, undo::NotUndoable));
+            }
+            player.money += refund;
+            info!("{} replaced {} wire(s), refunded {}", data.name, event.replaces.len(), refund);
+        }
+
+        if !event.free {
+            player.money -= game_mode.placement_cost(data.cost);
+            counters.record_building(&data.name);
+            sfx.write(crate::audio::PlaySfx(crate::audio::Sfx::Place));
+        }
         // Extract sprite info for all buildings
-        event
+        let entity = event
             .building
             .spawn(&mut commands, base_position, event.orientation);
+        commands.entity(entity).insert((
+            crate::economics::BuildCost(data.cost),
+            crate::economics::BuildingInfo::from_data(&data),
+        ));
+        if let Some(descriptor) = event.building.descriptor() {
+            commands.entity(entity).insert(crate::save::PlacedBuilding {
+                descriptor,
+                orientation: event.orientation,
+            });
+        }
     }
 }
 
@@ -154,9 +293,25 @@ pub fn handle_building_removal(
 
 pub fn process_entity_removal(
     mut commands: Commands,
-    marked_entities: Query<Entity, With<MarkedForRemoval>>,
+    marked_entities: Query<(Entity, Option<&crate::save::PlacedBuilding>, Option<&GridPosition>, Has<undo::NotUndoable>), With<MarkedForRemoval>>,
+    mut undo_buffer: ResMut<undo::UndoBuffer>,
+    mut sfx: MessageWriter<crate::audio::PlaySfx>,
 ) {
-    for entity in marked_entities.iter() {
+    for (entity, placed, position, not_undoable) in marked_entities.iter() {
+        if placed.is_some() {
+            sfx.write(crate::audio::PlaySfx(crate::audio::Sfx::Remove));
+        }
+        // keep what the player removed so Ctrl+Z can bring it back
+        if let (Some(placed), Some(position), false) = (placed, position, not_undoable) {
+            let building = placed.descriptor.building();
+            undo_buffer.record(undo::RemovalRecord {
+                name: building.data().name,
+                position: *position,
+                orientation: placed.orientation,
+                is_link: matches!(placed.descriptor, crate::save::BuildingDescriptor::Link { .. }),
+                descriptor: placed.descriptor.clone(),
+            });
+        }
         if let Ok(mut entity_commands) = commands.get_entity(entity) {
             entity_commands.despawn();
         }
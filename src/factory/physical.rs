@@ -1,9 +1,10 @@
-use crate::factory::buildings::buildings::{Building, BuildingData, SpriteResource};
+use crate::factory::buildings::buildings::{Building, BuildingData, PortKind, SpriteResource};
 use crate::factory::buildings::Tile;
 use crate::factory::{MarkedForRemoval, RemoveBuildingRequest};
 use crate::grid::{Grid, GridAtlasSprite, WorldMap};
 use crate::ui::interaction::MouseButtonEvent;
 use crate::assets::{AtlasId, GameAssets};
+use crate::factory::damage::{chain_throughput, Damaged};
 use crate::{
     factory::logical::{DataSink, DataSource, LogicalLink},
     grid::{Direction, GridPosition, Orientation},
@@ -18,6 +19,7 @@ use bevy::input::gamepad;
 use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
+use bevy::math::I64Vec2;
 // ============================================================================
 // COMPONENTS
 // ============================================================================
@@ -33,6 +35,12 @@ pub struct PhysicalLink {
     pub throughput: f32,
 }
 
+/// What every placed wire carries, whatever its descriptor says
+pub const LINK_THROUGHPUT: f32 = 234.0;
+
+/// Standalone wires. Trunk segments are links too but belong to their building.
+pub type PlainWire = (With<PhysicalLink>, Without<Tile>);
+
 #[derive(Component)]
 pub struct Linked;
 
@@ -53,6 +61,31 @@ pub struct ValidateConnections {
     pub positions: HashSet<GridPosition>,
 }
 
+/// Two neighbours joined up, `source` now feeding `sink`. Wires count too, so dragging out a
+/// run gives one per joint.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ConnectionEstablished {
+    pub source: Entity,
+    pub sink: Entity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakCause {
+    /// This wire was taken out, either one of the two ends or a segment somewhere between them
+    LinkRemoved(Entity),
+    /// The source went away with its building
+    SourceRemoved,
+}
+
+/// A connection went away. When a whole logical link goes, `source` and `sink` are its far
+/// endpoints rather than the wire that was removed.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ConnectionBroken {
+    pub source: Entity,
+    pub sink: Entity,
+    pub cause: BreakCause,
+}
+
 // ============================================================================
 // BUILDING IMPLEMENTATION
 // ============================================================================
@@ -65,11 +98,16 @@ impl Building for PhysicalLink {
         _: Orientation,
     ) -> Entity {
         commands
-            .spawn((PhysicalLink { throughput: 234.0 }, position))
+            .spawn((PhysicalLink { throughput: LINK_THROUGHPUT }, position))
             .with_related::<Tile>(())
             .id()
     }
 
+    /// Wires join up on whatever side they touch, no fixed ports
+    fn ports(&self, _orientation: Orientation) -> Vec<(I64Vec2, Direction, PortKind)> {
+        Vec::new()
+    }
+
     fn spawn(
         &self,
         commands: &mut Commands,
@@ -118,6 +156,10 @@ impl Building for PhysicalLink {
         id
     }
 
+    fn descriptor(&self) -> Option<crate::save::BuildingDescriptor> {
+        Some(crate::save::BuildingDescriptor::Link { throughput: self.throughput })
+    }
+
     fn data(&self) -> BuildingData {
         BuildingData {
             sprite: Some(SpriteResource::Atlas (
@@ -127,6 +169,9 @@ impl Building for PhysicalLink {
             grid_height: 1,
             cost: 25,
             name: "Link".to_string(),
+            upkeep_per_sec: 0,
+            throughput: self.throughput,
+            description: "Carries data, drag to lay a run".to_string(),
         }
     }
 }
@@ -192,12 +237,102 @@ pub fn validate_placed_entities(
 // CONNECTION RESOLUTION SYSTEM
 // ============================================================================
 
-/// Represents what kind of entity is at a position
-#[derive(Debug)]
+/// Represents what kind of entity is at a position.
+/// The bool is whether it can meet a trunk: a trunk segment, or a Trunker's port.
+#[derive(Debug, Clone, Copy)]
 enum EntityType {
-    Link(Entity),
-    Source(Entity, Direction),
-    Sink(Entity, Direction),
+    Link(Entity, bool),
+    Source(Entity, Direction, bool),
+    Sink(Entity, Direction, bool),
+}
+
+impl EntityType {
+    fn entity(&self) -> Entity {
+        match self {
+            EntityType::Link(e, _) | EntityType::Source(e, _, _) | EntityType::Sink(e, _, _) => *e,
+        }
+    }
+
+    fn is_trunk_segment(&self) -> bool {
+        matches!(self, EntityType::Link(_, true))
+    }
+
+    fn trunk_compatible(&self) -> bool {
+        match self {
+            EntityType::Link(_, trunk) | EntityType::Source(_, _, trunk) | EntityType::Sink(_, _, trunk) => *trunk,
+        }
+    }
+}
+
+/// How an entity takes part in connections. Worked out once when it's placed so
+/// validation doesn't go through three queries for every entity and neighbour.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionKind {
+    Link { trunk: bool },
+    Source { direction: Direction, trunk: bool },
+    Sink { direction: Direction, trunk: bool },
+}
+
+/// Trunks only join trunks or Trunker ports. True when this pairing breaks that,
+/// and the plain side gets a red flash so it's clear why nothing connected.
+fn rejects_trunk_pairing(commands: &mut Commands, a: &EntityType, b: &EntityType) -> bool {
+    if !a.is_trunk_segment() && !b.is_trunk_segment() {
+        return false;
+    }
+    if a.trunk_compatible() && b.trunk_compatible() {
+        return false;
+    }
+    let plain = if a.trunk_compatible() { b } else { a };
+    commands
+        .entity(plain.entity())
+        .insert(crate::factory::bulk_remove::CantRemoveFlash(crate::factory::bulk_remove::FLASH_SECONDS));
+    true
+}
+
+/// Lookups for telling trunk-compatible entities apart, grouped so `classify_connection_entities` stays short
+#[derive(bevy::ecs::system::SystemParam)]
+pub struct TrunkLookup<'w, 's> {
+    segments: Query<'w, 's, (), With<crate::factory::buildings::trunk_link::TrunkSegment>>,
+    tiles: Query<'w, 's, &'static Tile>,
+    trunkers: Query<'w, 's, (), With<crate::factory::buildings::trunker::Trunker>>,
+}
+
+impl TrunkLookup<'_, '_> {
+    fn is_trunker_port(&self, entity: Entity) -> bool {
+        self.tiles.get(entity).is_ok_and(|tile| self.trunkers.contains(tile.0))
+    }
+}
+
+/// How much work the last connection pass did, for keeping an eye on big networks
+#[derive(Resource, Default, Debug)]
+pub struct ConnectionPassStats {
+    /// Entities `resolve_connections` tried to connect from
+    pub visited: usize,
+    /// Segments `assemble_logical_links` had to walk instead of picking up from a cached chain
+    pub chain_steps: usize,
+}
+
+/// Tags newly placed links, outputs and inputs with their `ConnectionKind`
+pub fn classify_connection_entities(
+    mut commands: Commands,
+    placed: Query<
+        (Entity, Has<PhysicalLink>, Option<&DataSource>, Option<&DataSink>),
+        Or<(Added<PhysicalLink>, Added<DataSource>, Added<DataSink>)>,
+    >,
+    trunks: TrunkLookup,
+) {
+    for (entity, is_link, data_source, data_sink) in placed.iter() {
+        let kind = if is_link {
+            ConnectionKind::Link { trunk: trunks.segments.contains(entity) }
+        } else if let Some(data_source) = data_source {
+            ConnectionKind::Source { direction: data_source.direction, trunk: trunks.is_trunker_port(entity) }
+        } else if let Some(data_sink) = data_sink {
+            ConnectionKind::Sink { direction: data_sink.direction, trunk: trunks.is_trunker_port(entity) }
+        } else {
+            continue;
+        };
+        commands.entity(entity).insert(kind);
+    }
 }
 
 /// Main connection resolution system - handles all connection logic
@@ -205,76 +340,101 @@ pub fn resolve_connections(
     mut validation_events: MessageReader<ValidateConnections>,
     world_map: Res<WorldMap>,
     mut commands: Commands,
+    kinds: Query<&ConnectionKind>,
     // Query for PhysicalLinks
     links: Query<(Entity, Option<&PhysicalSink>, Option<&PhysicalSource>), With<PhysicalLink>>,
-    // Query for DataSources (on buildings)
-    sources: Query<(Entity, &DataSource), (Without<PhysicalLink>, Without<PhysicalSource>)>,
-    // Query for DataSinks (on buildings)
-    sinks: Query<(Entity, &DataSink), (Without<PhysicalLink>, Without<PhysicalSink>)>,
+    // Whether building ports are already wired up
+    ports: Query<(Has<PhysicalSink>, Has<PhysicalSource>), Without<PhysicalLink>>,
+    mut stats: ResMut<ConnectionPassStats>,
 ) {
-    for event in validation_events.read() {
-        for &position in event.positions.iter() {
-            // Get all entities at this position using WorldMap
-            let Some(entities_at_pos) = world_map.get(&position) else {
+    // several placements in one frame overlap on their neighbours, only look at each cell once
+    let positions: HashSet<GridPosition> = validation_events
+        .read()
+        .flat_map(|event| event.positions.iter().copied())
+        .collect();
+    if positions.is_empty() {
+        return;
+    }
+
+    let mut classified: HashMap<Entity, Option<EntityType>> = HashMap::new();
+    let mut classify = |entity: Entity| {
+        *classified
+            .entry(entity)
+            .or_insert_with(|| classify_entity(entity, &kinds, &links, &ports))
+    };
+    stats.visited = 0;
+
+    for position in positions.iter() {
+        // Get all entities at this position using WorldMap
+        let Some(entities_at_pos) = world_map.get(position) else {
+            continue;
+        };
+
+        // Check all entities at this position
+        for &entity_at_pos in entities_at_pos.iter() {
+            // Classify the entity
+            let entity_type = classify(entity_at_pos);
+            if entity_type.is_none() || is_fully_linked(entity_at_pos, &links) {
                 continue;
-            };
+            }
+            stats.visited += 1;
 
-            // Check all entities at this position
-            for &entity_at_pos in entities_at_pos.iter() {
-                // Classify the entity
-                let entity_type = classify_entity(entity_at_pos, &links, &sources, &sinks);
+            // Check all neighbors and attempt connections
+            for (direction, neighbor_pos) in position.neighbours() {
+                let Some(neighbor_entities) = world_map.get(&neighbor_pos) else {
+                    continue;
+                };
 
-                // Check all neighbors and attempt connections
-                for (direction, neighbor_pos) in position.neighbours() {
-                    let Some(neighbor_entities) = world_map.get(&neighbor_pos) else {
+                // Try to connect to all entities at the neighbor position
+                for &neighbor_entity in neighbor_entities.iter() {
+                    if is_fully_linked(neighbor_entity, &links) {
                         continue;
-                    };
-
-                    // Try to connect to all entities at the neighbor position
-                    for &neighbor_entity in neighbor_entities.iter() {
-                        let neighbor_type =
-                            classify_entity(neighbor_entity, &links, &sources, &sinks);
-
-                        // Try to connect entity_at_pos -> neighbor
-                        attempt_connection(
-                            &mut commands,
-                            entity_at_pos,
-                            neighbor_entity,
-                            direction,
-                            &entity_type,
-                            &neighbor_type,
-                            &links,
-                        );
                     }
+                    let neighbor_type = classify(neighbor_entity);
+
+                    // Try to connect entity_at_pos -> neighbor
+                    attempt_connection(
+                        &mut commands,
+                        entity_at_pos,
+                        neighbor_entity,
+                        direction,
+                        &entity_type,
+                        &neighbor_type,
+                        &links,
+                    );
                 }
             }
         }
     }
 }
 
+/// A link with both an input and an output has nothing left to connect
+fn is_fully_linked(
+    entity: Entity,
+    links: &Query<(Entity, Option<&PhysicalSink>, Option<&PhysicalSource>), With<PhysicalLink>>,
+) -> bool {
+    links.get(entity).is_ok_and(|(_, sink, source)| sink.is_some() && source.is_some())
+}
+
 /// Classifies an entity into one of the connection types
 fn classify_entity(
     entity: Entity,
+    kinds: &Query<&ConnectionKind>,
     links: &Query<(Entity, Option<&PhysicalSink>, Option<&PhysicalSource>), With<PhysicalLink>>,
-    sources: &Query<(Entity, &DataSource), (Without<PhysicalLink>, Without<PhysicalSource>)>,
-    sinks: &Query<(Entity, &DataSink), (Without<PhysicalLink>, Without<PhysicalSink>)>,
+    ports: &Query<(Has<PhysicalSink>, Has<PhysicalSource>), Without<PhysicalLink>>,
 ) -> Option<EntityType> {
-    // Check if it's a PhysicalLink
-    if links.get(entity).is_ok() {
-        return Some(EntityType::Link(entity));
-    }
-
-    // Check if it's a DataSource (building output)
-    if let Ok((_, data_source)) = sources.get(entity) {
-        return Some(EntityType::Source(entity, data_source.direction));
-    }
-
-    // Check if it's a DataSink (building input)
-    if let Ok((_, data_sink)) = sinks.get(entity) {
-        return Some(EntityType::Sink(entity, data_sink.direction));
+    match *kinds.get(entity).ok()? {
+        ConnectionKind::Link { trunk } => links.contains(entity).then_some(EntityType::Link(entity, trunk)),
+        // a building output or input that's already wired up is taken
+        ConnectionKind::Source { direction, trunk } => {
+            let (_, connected) = ports.get(entity).ok()?;
+            (!connected).then_some(EntityType::Source(entity, direction, trunk))
+        }
+        ConnectionKind::Sink { direction, trunk } => {
+            let (connected, _) = ports.get(entity).ok()?;
+            (!connected).then_some(EntityType::Sink(entity, direction, trunk))
+        }
     }
-
-    None
 }
 
 /// Attempts to create a connection from source_entity to target_entity
@@ -293,7 +453,7 @@ fn attempt_connection(
 
     match (source_type, target_type) {
         // Building DataSource -> Building DataSink (direct connection)
-        (EntityType::Source(source, source_dir), EntityType::Sink(target, target_dir)) => {
+        (EntityType::Source(source, source_dir, _), EntityType::Sink(target, target_dir, _)) => {
             // Check directionality: source must output in direction, sink must input from opposite
             if *source_dir == direction_from_source
                 && *target_dir == direction_from_source.opposite()
@@ -303,11 +463,11 @@ fn attempt_connection(
         }
 
         // Building DataSource -> PhysicalLink
-        (EntityType::Source(source, source_dir), EntityType::Link(target)) => {
+        (EntityType::Source(source, source_dir, _), EntityType::Link(target, _)) => {
             if *source_dir == direction_from_source {
                 // Check if link doesn't already have an input
                 if let Ok((_, link_sink, _)) = links.get(*target) {
-                    if link_sink.is_none() {
+                    if link_sink.is_none() && !rejects_trunk_pairing(commands, source_type, target_type) {
                         insert_physical_connection(
                             commands,
                             *source,
@@ -320,11 +480,11 @@ fn attempt_connection(
         }
 
         // PhysicalLink -> Building DataSink
-        (EntityType::Link(source), EntityType::Sink(target, target_dir)) => {
+        (EntityType::Link(source, _), EntityType::Sink(target, target_dir, _)) => {
             if *target_dir == direction_from_source.opposite() {
                 // Check if link doesn't already have an output
                 if let Ok((_, _, link_source)) = links.get(*source) {
-                    if link_source.is_none() {
+                    if link_source.is_none() && !rejects_trunk_pairing(commands, source_type, target_type) {
                         insert_physical_connection(
                             commands,
                             *source,
@@ -337,7 +497,7 @@ fn attempt_connection(
         }
 
         // PhysicalLink -> PhysicalLink
-        (EntityType::Link(source), EntityType::Link(target)) => {
+        (EntityType::Link(source, _), EntityType::Link(target, _)) => {
             if let (Ok((_, source_in, source_out)), Ok((_, target_in, target_out))) =
                 (links.get(*source), links.get(*target))
             {
@@ -345,7 +505,8 @@ fn attempt_connection(
                 // Also check for cycles
                 if source_out.is_none()
                     && target_in.is_none()
-                    && !would_create_cycle(*target, *source, links)
+                    && !would_create_cycle(*source, *target, links)
+                    && !rejects_trunk_pairing(commands, source_type, target_type)
                 {
                     insert_physical_connection(commands, *source, *target, direction_from_source);
                 }
@@ -358,7 +519,7 @@ fn attempt_connection(
     }
 }
 
-/// Checks if adding a connection from -> to would create a cycle
+/// Checks if adding a connection from -> to would create a cycle, i.e. `to` already leads back to `from`
 fn would_create_cycle(
     from: Entity,
     to: Entity,
@@ -396,6 +557,7 @@ fn insert_physical_connection(
     commands
         .entity(sink)
         .insert(PhysicalSink(source, direction));
+    commands.write_message(ConnectionEstablished { source, sink });
 }
 
 // ============================================================================
@@ -449,25 +611,75 @@ pub fn assemble_direct_logical_links(
 //     let 
 // }
 
-/// Updates the sprite of PhysicalLinks when they get connected
+/// Re-picks wire sprites around anything that was placed, connected or disconnected, so
+/// chains keep their corners right after a neighbour goes away. Straight piece for a wire
+/// joined on one side only.
 pub fn update_link_sprite_on_connection(
+    mut validation_events: MessageReader<ValidateConnections>,
+    mut removed_sinks: RemovedComponents<PhysicalSink>,
+    mut removed_sources: RemovedComponents<PhysicalSource>,
+    changed: Query<Entity, (With<PhysicalLink>, Or<(Changed<PhysicalSink>, Changed<PhysicalSource>)>)>,
+    positions: Query<&GridPosition>,
+    world_map: Res<WorldMap>,
     mut links: Query<
-        (Entity, &mut GridAtlasSprite, &PhysicalSink, &PhysicalSource),
-        (
-            With<PhysicalLink>,
-            Or<(Added<PhysicalSink>, Added<PhysicalSource>)>,
-        ),
+        (Option<&PhysicalSink>, Option<&PhysicalSource>, &mut GridAtlasSprite, Option<&mut Sprite>, &mut Transform),
+        With<PhysicalLink>,
     >,
-    game_assets: Res<GameAssets>,
+    game_assets: Option<Res<GameAssets>>,
 ) {
-    for (_entity, mut sprite, sink, source) in links.iter_mut() {
-        // Update to wires atlas with appropriate index based on input/output directions
-        sprite.atlas_id = AtlasId::Wires;
-        sprite.atlas_index = game_assets.wire_index(sink.1, source.1);
+    // headless runs have no atlases to pick from
+    let Some(game_assets) = game_assets else { return };
+    let mut dirty: HashSet<GridPosition> = validation_events
+        .read()
+        .flat_map(|event| event.positions.iter().copied())
+        .collect();
+    for entity in changed.iter().chain(removed_sinks.read()).chain(removed_sources.read()) {
+        if let Ok(position) = positions.get(entity) {
+            dirty.insert(*position);
+        }
+    }
+
+    for position in dirty.iter() {
+        let Some(entities) = world_map.get(position) else { continue };
+        for &entity in entities.iter() {
+            let Ok((sink, source, mut atlas_sprite, sprite, mut transform)) = links.get_mut(entity) else { continue };
+            // the stored direction is the way data travels, it comes in on the opposite side
+            let sides: Vec<Direction> = sink
+                .map(|s| s.1.opposite())
+                .into_iter()
+                .chain(source.map(|s| s.1))
+                .collect();
+            let index = game_assets.wire_index_for_sides(&sides);
+            if atlas_sprite.atlas_index == index && atlas_sprite.atlas_id == AtlasId::Wires {
+                continue;
+            }
+            atlas_sprite.atlas_id = AtlasId::Wires;
+            atlas_sprite.atlas_index = index;
+            atlas_sprite.orientation = Orientation::default();
+            // the piece already points the right way, drop whatever rotation it was placed with
+            transform.rotation = Quat::IDENTITY;
+            if let Some(mut sprite) = sprite {
+                sprite.flip_x = false;
+                if let Some(atlas) = sprite.texture_atlas.as_mut() {
+                    atlas.index = index;
+                }
+            }
+        }
     }
 }
 
-/// Assembles logical links by walking complete physical chains
+/// Chains that reach a real source or sink at one end but are still open at the other, keyed by
+/// their last fully connected segment. Placing the next segment picks the chain up from here
+/// instead of walking it again. Anything disconnecting drops the lot, it's only a shortcut.
+#[derive(Resource, Default)]
+pub struct OpenChains {
+    /// Source endpoint and the segments from it up to and including the key
+    from_source: HashMap<Entity, (Entity, Vec<Entity>)>,
+    /// Sink endpoint and the segments from the key down to it
+    to_sink: HashMap<Entity, (Entity, Vec<Entity>)>,
+}
+
+/// Assembles logical links for physical chains, extending cached open chains where it can
 pub fn assemble_logical_links(
     mut commands: Commands,
     // PhysicalLinks that just became fully connected (have both input and output)
@@ -480,9 +692,17 @@ pub fn assemble_logical_links(
     >,
     physical_sinks: Query<&PhysicalSink>,
     physical_sources: Query<&PhysicalSource>,
-    physical_links: Query<&PhysicalLink>,
+    physical_links: Query<(&PhysicalLink, Option<&Damaged>)>,
     mut already_linked: Query<&mut LogicalLink>,
+    mut open_chains: ResMut<OpenChains>,
+    (mut removed_sinks, mut removed_sources): (RemovedComponents<PhysicalSink>, RemovedComponents<PhysicalSource>),
+    mut stats: ResMut<ConnectionPassStats>,
 ) {
+    if removed_sinks.read().count() + removed_sources.read().count() > 0 {
+        open_chains.from_source.clear();
+        open_chains.to_sink.clear();
+    }
+    stats.chain_steps = 0;
     let mut processed = HashSet::new();
 
     for (entity, sink, source) in newly_connected.iter() {
@@ -490,34 +710,59 @@ pub fn assemble_logical_links(
             continue;
         }
 
-        // Walk upstream to find the source endpoint
-        let (source_endpoint, mut upstream_chain) = walk_upstream(&physical_sinks, sink.0);
-
-        // Walk downstream to find the sink endpoint
-        let (sink_endpoint, mut downstream_chain) = walk_downstream(&physical_sources, source.0);
-
-        let (Some(source_endpoint), Some(sink_endpoint)) = (source_endpoint, sink_endpoint) else {
+        // Everything before this segment, from the cache when it was the last one placed
+        let upstream = open_chains.from_source.remove(&sink.0).or_else(|| {
+            let (source_endpoint, mut before) = walk_upstream(&physical_sinks, sink.0);
+            stats.chain_steps += before.len() + 1;
+            before.reverse();
+            source_endpoint.map(|endpoint| (endpoint, before))
+        });
+        // and everything after it
+        let downstream = open_chains.to_sink.remove(&source.0).or_else(|| {
+            let (sink_endpoint, after) = walk_downstream(&physical_sources, source.0);
+            stats.chain_steps += after.len() + 1;
+            sink_endpoint.map(|endpoint| (endpoint, after))
+        });
+        // Cycle detected
+        let (Some((source_endpoint, before)), Some((sink_endpoint, after))) = (upstream, downstream) else {
             continue;
         };
 
-        // Build the full chain
-        let mut full_chain = Vec::new();
-        full_chain.append(&mut upstream_chain);
-        full_chain.push(entity);
-        full_chain.append(&mut downstream_chain);
-
-        // Calculate minimum throughput
-        let throughput = full_chain
-            .iter()
-            .filter_map(|&e| physical_links.get(e).ok())
-            .map(|link| link.throughput)
-            .fold(f32::INFINITY, f32::min);
-
         // Mark all segments as linked
-        for &segment in full_chain.iter() {
-            processed.insert(segment);
+        processed.extend(before.iter().copied());
+        processed.insert(entity);
+        processed.extend(after.iter().copied());
+
+        // an end that's still a wire means the chain isn't finished yet
+        let source_open = physical_links.contains(source_endpoint);
+        let sink_open = physical_links.contains(sink_endpoint);
+        if source_open || sink_open {
+            if !source_open {
+                let key = after.last().copied().unwrap_or(entity);
+                let mut links = before;
+                links.push(entity);
+                links.extend(after);
+                open_chains.from_source.insert(key, (source_endpoint, links));
+            } else if !sink_open {
+                let key = before.first().copied().unwrap_or(entity);
+                let mut links = before;
+                links.push(entity);
+                links.extend(after);
+                open_chains.to_sink.insert(key, (sink_endpoint, links));
+            }
+            continue;
         }
 
+        let mut full_chain = before;
+        full_chain.push(entity);
+        full_chain.extend(after);
+
+        // Minimum throughput, with damaged segments counted at their reduced value.
+        // A broken (severity 2) segment means no link until it's repaired.
+        let Some(throughput) = chain_throughput(&full_chain, &physical_links) else {
+            continue;
+        };
+
         // Create or update the logical link on the sink endpoint
         let logical_link = LogicalLink {
             links: full_chain,
@@ -534,6 +779,32 @@ pub fn assemble_logical_links(
     }
 }
 
+/// Walks both ways from a fully connected segment, returning the source endpoint,
+/// sink endpoint and the full segment chain in order
+pub fn walk_chain(
+    entity: Entity,
+    sink: &PhysicalSink,
+    source: &PhysicalSource,
+    physical_sinks: &Query<&PhysicalSink>,
+    physical_sources: &Query<&PhysicalSource>,
+) -> Option<(Entity, Entity, Vec<Entity>)> {
+    // Walk upstream to find the source endpoint
+    let (source_endpoint, mut upstream_chain) = walk_upstream(physical_sinks, sink.0);
+
+    // Walk downstream to find the sink endpoint
+    let (sink_endpoint, mut downstream_chain) = walk_downstream(physical_sources, source.0);
+
+    let (Some(source_endpoint), Some(sink_endpoint)) = (source_endpoint, sink_endpoint) else {
+        return None;
+    };
+
+    let mut full_chain = Vec::new();
+    full_chain.append(&mut upstream_chain);
+    full_chain.push(entity);
+    full_chain.append(&mut downstream_chain);
+    Some((source_endpoint, sink_endpoint, full_chain))
+}
+
 /// Walks upstream (following PhysicalSink pointers) to find the source endpoint
 fn walk_upstream(sinks: &Query<&PhysicalSink>, start: Entity) -> (Option<Entity>, Vec<Entity>) {
     let mut chain = Vec::new();
@@ -584,6 +855,7 @@ pub fn on_data_source_removed(
     trigger: On<Remove, DataSource>,
     mut commands: Commands,
     logical_links: Query<(Entity, &LogicalLink)>,
+    mut broken: MessageWriter<ConnectionBroken>,
 ) {
     let removed_entity = trigger.entity;
 
@@ -594,6 +866,11 @@ pub fn on_data_source_removed(
             if let Ok(mut entity_commands) = commands.get_entity(sink_entity) {
                 entity_commands.remove::<LogicalLink>();
             }
+            broken.write(ConnectionBroken {
+                source: removed_entity,
+                sink: sink_entity,
+                cause: BreakCause::SourceRemoved,
+            });
         }
     }
 }
@@ -616,6 +893,7 @@ pub fn on_physical_link_removed(
     logical_links: Query<(Entity, &LogicalLink)>,
     positions: Query<&GridPosition>,
     mut validation_events: MessageWriter<ValidateConnections>,
+    mut broken: MessageWriter<ConnectionBroken>,
 ) {
     let removed_entity = trigger.entity;
     let mut positions_to_revalidate = HashSet::new();
@@ -634,6 +912,11 @@ pub fn on_physical_link_removed(
             if let Ok(mut entity_commands) = commands.get_entity(owner) {
                 entity_commands.remove::<PhysicalSource>();
             }
+            broken.write(ConnectionBroken {
+                source: owner,
+                sink: removed_entity,
+                cause: BreakCause::LinkRemoved(removed_entity),
+            });
             // Add to revalidation
             if let Ok(&pos) = positions.get(owner) {
                 positions_to_revalidate.insert(pos);
@@ -646,6 +929,11 @@ pub fn on_physical_link_removed(
             if let Ok(mut entity_commands) = commands.get_entity(owner) {
                 entity_commands.remove::<PhysicalSink>();
             }
+            broken.write(ConnectionBroken {
+                source: removed_entity,
+                sink: owner,
+                cause: BreakCause::LinkRemoved(removed_entity),
+            });
             // Add to revalidation
             if let Ok(&pos) = positions.get(owner) {
                 positions_to_revalidate.insert(pos);
@@ -660,6 +948,12 @@ pub fn on_physical_link_removed(
             if let Ok(mut entity_commands) = commands.get_entity(sink_entity) {
                 entity_commands.remove::<LogicalLink>();
             }
+            // the far ends, so the player sees everything the deletion cut off
+            broken.write(ConnectionBroken {
+                source: logical.source,
+                sink: sink_entity,
+                cause: BreakCause::LinkRemoved(removed_entity),
+            });
         }
     }
 
@@ -671,21 +965,43 @@ pub fn on_physical_link_removed(
     }
 }
 
+/// Repeat right-clicks on one cell within this many seconds carry on down its removal list
+const REMOVAL_CYCLE_SECONDS: f64 = 1.0;
+
+/// The cell last right-clicked and what was already taken out of it in this burst
+#[derive(Default)]
+pub struct RemovalCycle {
+    cell: Option<GridPosition>,
+    at: f64,
+    removed: Vec<Entity>,
+}
+
 pub fn remove_physical_link_on_right_click(
     mut commands: Commands,
     mut mouse: ResMut<MouseButtonEvent>,
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<crate::controls::InputBindings>),
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     grid: Res<Grid>,
     world_map: Res<WorldMap>,
-    links: Query<&PhysicalLink>,
-    tiles: Query<&Tile>,
+    links: Query<&PhysicalLink, (Without<MarkedForRemoval>, Without<Tile>)>,
+    tiles: Query<&Tile, Without<crate::world_gen::LockMarker>>,
+    removable_buildings: Query<(), (Without<crate::factory::buildings::Undeletable>, Without<MarkedForRemoval>)>,
+    ui_blocker_query: Query<&Interaction, With<crate::ui::BlocksWorldClicks>>,
+    selected_building_type: Res<crate::ui::shop::SelectedBuildingType>,
+    time: Res<Time<Real>>,
+    mut cycle: Local<RemovalCycle>,
     mut removal_events: MessageWriter<RemoveBuildingRequest>,
 ) {
-    let Some(mouse) = mouse.handle() else { return };
-
     // Only act on the press edge to avoid repeating every frame the button is held.
-    if !mouse.just_pressed(MouseButton::Right) {
+    if !mouse.handle_binding(bindings.get(crate::controls::InputAction::RemoveBuilding), &keys) {
+        return;
+    }
+    // right-clicking near the sidebar shouldn't eat the building under it
+    if ui_blocker_query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed)
+    {
         return;
     }
 
@@ -715,19 +1031,55 @@ pub fn remove_physical_link_on_right_click(
         return;
     };
 
-    // Check each entity at this position
+    let now = time.elapsed_secs_f64();
+    if cycle.cell != Some(grid_pos) || now - cycle.at > REMOVAL_CYCLE_SECONDS {
+        cycle.removed.clear();
+    }
+    cycle.cell = Some(grid_pos);
+    cycle.at = now;
+
+    // Building tiles win over wires, unless a wire is what's being placed.
+    // One entry per building, lock markers and undeletables never count.
+    let mut wires = Vec::new();
+    let mut buildings: Vec<(Entity, Entity)> = Vec::new();
     for &entity in entities.iter() {
-        // Check if it's a PhysicalLink
-        if links.get(entity).is_ok() {
-            commands.entity(entity).remove::<PhysicalLink>();
-            commands.entity(entity).insert(MarkedForRemoval);
-            return; // Stop after removing first PhysicalLink
+        if links.contains(entity) {
+            wires.push(entity);
+        } else if let Ok(tile) = tiles.get(entity)
+            && removable_buildings.contains(tile.0)
+            && !buildings.iter().any(|(_, building)| *building == tile.0)
+        {
+            buildings.push((entity, tile.0));
         }
+    }
+    // the WorldMap keeps cells in insertion order, sort so the same click picks the same thing
+    wires.sort();
+    buildings.sort_by_key(|(_, building)| *building);
+
+    let placing_wire = selected_building_type.0.as_ref().is_some_and(|b| b.data().name == "Link");
+    let wire_targets = wires.into_iter().map(|wire| (wire, None));
+    let building_targets = buildings.into_iter().map(|(tile, building)| (tile, Some(building)));
+    let targets: Vec<(Entity, Option<Entity>)> = if placing_wire {
+        wire_targets.chain(building_targets).collect()
+    } else {
+        building_targets.chain(wire_targets).collect()
+    };
 
-        // Check if it's a Tile (part of a building)
-        if tiles.get(entity).is_ok() {
+    // skip what this burst of clicks already took, so more clicks go to whatever is under it
+    let Some((entity, building)) = targets
+        .into_iter()
+        .find(|(entity, building)| !cycle.removed.contains(&building.unwrap_or(*entity)))
+    else {
+        return;
+    };
+    cycle.removed.push(building.unwrap_or(entity));
+    match building {
+        Some(_) => {
             removal_events.write(RemoveBuildingRequest { tile: entity });
-            return; // Stop after emitting first removal request
+        }
+        None => {
+            commands.entity(entity).remove::<PhysicalLink>();
+            commands.entity(entity).insert(MarkedForRemoval);
         }
     }
 }
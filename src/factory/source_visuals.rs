@@ -3,14 +3,29 @@ use bevy::platform::collections::HashSet; // Use Bevy's HashSet
 use bevy::render::render_resource::{FilterMode, SamplerDescriptor};
 use bevy::image::{ImageSampler, ImageSamplerDescriptor};
 use crate::factory::logical::{DataAttribute, BasicDataType};
-use crate::factory::buildings::source::SourceBuilding;
+use crate::factory::buildings::source::{Depleted, SourceBuilding, SourcePool};
 use crate::assets::{GameAssets, AtlasId};
 use crate::grid::GridPosition;
 use crate::factions::Faction;
 
-/// Component that marks a source building's background sprite
+/// Component that marks a source building's background sprite, and which building it's behind
 #[derive(Component)]
-pub struct SourceBackground;
+pub struct SourceBackground(pub Entity);
+
+/// Flat dot in the source's data type colour, stands in for the icons while they're hidden
+/// at lower detail
+#[derive(Component)]
+pub struct SourceLodDot;
+
+/// One colour per data type, shared by the stats graphs and the zoomed out source dots
+pub fn data_type_color(data_type: BasicDataType) -> Color {
+    match data_type {
+        BasicDataType::Biometric => Color::srgb(0.9, 0.35, 0.35),
+        BasicDataType::Economic => Color::srgb(0.35, 0.85, 0.4),
+        BasicDataType::Behavioural => Color::srgb(0.4, 0.6, 1.0),
+        BasicDataType::Telemetry => Color::srgb(0.95, 0.75, 0.3),
+    }
+}
 
 /// Component that marks a data type icon overlay on a source
 #[derive(Component)]
@@ -19,6 +34,21 @@ pub struct DataTypeIcon {
     pub parent_source: Entity,
 }
 
+/// Remaining-pool bar along the bottom of a limited source, the fill part
+#[derive(Component)]
+pub struct SourcePoolBar {
+    pub parent_source: Entity,
+    /// x of the bar's centre when full, the fill shrinks towards the left edge
+    base_x: f32,
+    width: f32,
+}
+
+/// The dark track behind a `SourcePoolBar`
+#[derive(Component)]
+pub struct SourcePoolTrack {
+    pub parent_source: Entity,
+}
+
 /// Component that marks augmentation visual effects
 #[derive(Component)]
 pub struct AugmentationEffect {
@@ -79,7 +109,7 @@ pub fn spawn_source_backgrounds(
     grid: Res<crate::grid::Grid>,
     _asset_server: Res<AssetServer>,
 ) {
-    for (_entity, source, grid_pos, faction) in query.iter() {
+    for (entity, source, grid_pos, faction) in query.iter() {
         // Determine background sprite index based on faction or data type
         let background_index = if let Some(faction) = faction {
             // Faction sources: use faction-specific background (indices 0-3)
@@ -119,9 +149,23 @@ pub fn spawn_source_backgrounds(
                 ..Default::default()
             },
             Transform::from_translation(position.extend(-1.0)),
-            SourceBackground,
+            SourceBackground(entity),
             Visibility::default(),
         ));
+
+        // hidden until zoomed out, the LOD systems flip it with the icons
+        let dot_color = source.shape.contents.keys().min().map_or(Color::WHITE, |t| data_type_color(*t));
+        let dot_size = source.size.x.min(source.size.y) as f32 * grid.scale * 0.45;
+        commands.spawn((
+            Sprite {
+                color: dot_color,
+                custom_size: Some(Vec2::splat(dot_size)),
+                ..Default::default()
+            },
+            Transform::from_translation(position.extend(1.0)),
+            SourceLodDot,
+            Visibility::Hidden,
+        ));
     }
 }
 
@@ -319,7 +363,7 @@ fn spawn_augmentation_effects(
 
 /// System to animate scanning flash effect for identified data (world sprites)
 pub fn animate_scanning_flash(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     mut flash_query: Query<(&mut ScanningFlashEffect, &mut Sprite), Without<ImageNode>>,
 ) {
     let delta = time.delta_secs();
@@ -372,7 +416,7 @@ pub struct FlashOverlay {
 /// System to animate scanning flash effect for identified data (UI elements)
 /// This system tints the ImageNode and manages a dedicated overlay child for each icon
 pub fn animate_scanning_flash_ui(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     mut commands: Commands,
     mut flash_query: Query<(Entity, &mut ScanningFlashEffect, &mut ImageNode), Without<FlashOverlay>>,
     children_query: Query<&Children>,
@@ -475,7 +519,7 @@ pub fn animate_scanning_flash_ui(
 
 /// System to animate floating icons (slow bounce up and down)
 pub fn animate_floating_icons(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     mut icon_query: Query<(&FloatingAnimation, &mut Transform), With<DataTypeIcon>>,
 ) {
     let t = time.elapsed_secs();
@@ -494,7 +538,7 @@ pub fn animate_floating_icons(
 
 /// System to animate pulse effect on augmented indicators (world sprites)
 pub fn animate_augmented_pulse(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     mut indicator_query: Query<(&AugmentedIndicator, &mut Transform), Without<Node>>,
 ) {
     for (indicator, mut transform) in indicator_query.iter_mut() {
@@ -512,7 +556,7 @@ pub fn animate_augmented_pulse(
 
 /// System to animate pulse effect on augmented indicators (UI elements)
 pub fn animate_augmented_pulse_ui(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     mut indicator_query: Query<(&AugmentedIndicator, &mut Node), With<ImageNode>>,
 ) {
     for (indicator, mut node) in indicator_query.iter_mut() {
@@ -812,6 +856,91 @@ pub fn spawn_data_type_with_augmentations(
     (icon_entity, augmented_entity)
 }
 
+/// Bar under each limited source showing how much is left
+pub fn spawn_source_pool_bars(
+    mut commands: Commands,
+    sources: Query<(Entity, &SourceBuilding, &GridPosition, &SourcePool), Added<SourcePool>>,
+    grid: Res<crate::grid::Grid>,
+) {
+    for (entity, source, grid_pos, pool) in sources.iter() {
+        let center = grid.calculate_building_sprite_position(
+            grid_pos,
+            source.size.x,
+            source.size.y,
+            crate::grid::Orientation::default(),
+        );
+        let width = source.size.x as f32 * grid.scale * 0.8;
+        let height = 4.0;
+        let y = center.y - source.size.y as f32 * grid.scale * 0.4;
+        commands.spawn((
+            Sprite {
+                color: Color::srgba(0.0, 0.0, 0.0, 0.7),
+                custom_size: Some(Vec2::new(width, height)),
+                ..default()
+            },
+            Transform::from_translation(Vec3::new(center.x, y, 1.1)),
+            SourcePoolTrack { parent_source: entity },
+        ));
+        let fraction = pool.fraction();
+        commands.spawn((
+            Sprite {
+                color: Color::srgb(0.3, 0.8, 0.9),
+                custom_size: Some(Vec2::new(width * fraction, height)),
+                ..default()
+            },
+            Transform::from_translation(Vec3::new(center.x - width * (1.0 - fraction) / 2.0, y, 1.2)),
+            SourcePoolBar { parent_source: entity, base_x: center.x, width },
+        ));
+    }
+}
+
+pub fn update_source_pool_bars(
+    pools: Query<&SourcePool, Changed<SourcePool>>,
+    mut bars: Query<(&SourcePoolBar, &mut Sprite, &mut Transform)>,
+) {
+    for (bar, mut sprite, mut transform) in bars.iter_mut() {
+        let Ok(pool) = pools.get(bar.parent_source) else { continue };
+        let fraction = pool.fraction();
+        sprite.custom_size = Some(Vec2::new(bar.width * fraction, sprite.custom_size.map_or(4.0, |s| s.y)));
+        transform.translation.x = bar.base_x - bar.width * (1.0 - fraction) / 2.0;
+        // running low goes amber, then red
+        sprite.color = if fraction > 0.5 {
+            Color::srgb(0.3, 0.8, 0.9)
+        } else if fraction > 0.2 {
+            Color::srgb(0.95, 0.7, 0.2)
+        } else {
+            Color::srgb(0.95, 0.3, 0.25)
+        };
+    }
+}
+
+/// Greys out a source that's run dry and takes its bar away
+pub fn show_depleted_sources(
+    mut commands: Commands,
+    depleted: Query<Entity, Added<Depleted>>,
+    mut icons: Query<(&DataTypeIcon, &mut Sprite)>,
+    bars: Query<(Entity, &SourcePoolBar)>,
+    tracks: Query<(Entity, &SourcePoolTrack)>,
+) {
+    for source in depleted.iter() {
+        for (icon, mut sprite) in icons.iter_mut() {
+            if icon.parent_source == source {
+                sprite.color = Color::srgba(0.35, 0.35, 0.35, 0.6);
+            }
+        }
+        for (entity, bar) in bars.iter() {
+            if bar.parent_source == source {
+                commands.entity(entity).despawn();
+            }
+        }
+        for (entity, track) in tracks.iter() {
+            if track.parent_source == source {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
 pub struct SourceVisualsPlugin;
 
 impl Plugin for SourceVisualsPlugin {
@@ -820,10 +949,11 @@ impl Plugin for SourceVisualsPlugin {
             .add_systems(Update, (
                 spawn_source_backgrounds,
                 update_source_data_icons,
-                animate_scanning_flash,
+                (spawn_source_pool_bars, update_source_pool_bars, show_depleted_sources).chain().after(update_source_data_icons),
+                animate_scanning_flash.run_if(crate::lod::decorations_visible),
                 animate_scanning_flash_ui,
-                animate_floating_icons,
-                animate_augmented_pulse,
+                animate_floating_icons.run_if(crate::lod::decorations_visible),
+                animate_augmented_pulse.run_if(crate::lod::decorations_visible),
                 animate_augmented_pulse_ui,
             ));
     }
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+use crate::factory::logical::LinkUtilization;
+use crate::factory::physical::PhysicalLink;
+use crate::palette::Palette;
+
+/// Utilization below this is comfortable, above `BUSY_UTILIZATION` it's close to full
+pub const COMFORTABLE_UTILIZATION: f32 = 0.5;
+pub const BUSY_UTILIZATION: f32 = 0.9;
+
+/// Wire colouring by how close each segment is to its capacity, toggled with T
+#[derive(Resource, Default)]
+pub struct ThroughputOverlay(pub bool);
+
+/// The wire's colour from before the overlay went on, put back when it goes off
+#[derive(Component)]
+pub struct OverlayRestoreColor(pub Color);
+
+pub fn utilization_color(utilization: &LinkUtilization, palette: &Palette) -> Color {
+    if !utilization.connected {
+        palette.inactive
+    } else if utilization.utilization < COMFORTABLE_UTILIZATION {
+        palette.success
+    } else if utilization.utilization < BUSY_UTILIZATION {
+        palette.warning
+    } else {
+        palette.danger
+    }
+}
+
+pub fn toggle_throughput_overlay(controls: crate::controls::Controls, mut overlay: ResMut<ThroughputOverlay>) {
+    if controls.just_pressed(crate::controls::InputAction::ToggleOverlay) {
+        overlay.0 = !overlay.0;
+        info!("Throughput overlay {}", if overlay.0 { "on" } else { "off" });
+    }
+}
+
+pub fn apply_throughput_overlay(
+    mut commands: Commands,
+    overlay: Res<ThroughputOverlay>,
+    palette: Res<Palette>,
+    mut links: Query<
+        (Entity, &mut Sprite, Option<Ref<LinkUtilization>>, Option<&OverlayRestoreColor>),
+        With<PhysicalLink>,
+    >,
+    mut restore: Query<(Entity, &mut Sprite, &OverlayRestoreColor), Without<PhysicalLink>>,
+) {
+    if !overlay.0 {
+        if !overlay.is_changed() {
+            return;
+        }
+        for (entity, mut sprite, _, original) in links.iter_mut() {
+            if let Some(original) = original {
+                sprite.color = original.0;
+                commands.entity(entity).remove::<OverlayRestoreColor>();
+            }
+        }
+        // wires being removed lose PhysicalLink before they go
+        for (entity, mut sprite, original) in restore.iter_mut() {
+            sprite.color = original.0;
+            commands.entity(entity).remove::<OverlayRestoreColor>();
+        }
+        return;
+    }
+
+    for (entity, mut sprite, utilization, original) in links.iter_mut() {
+        let fresh = original.is_none();
+        if fresh {
+            commands.entity(entity).insert(OverlayRestoreColor(sprite.color));
+        }
+        let changed = utilization.as_ref().is_some_and(|u| u.is_changed());
+        if fresh || changed || overlay.is_changed() || palette.is_changed() {
+            sprite.color = utilization.as_deref().map_or(palette.inactive, |u| utilization_color(u, &palette));
+        }
+    }
+}
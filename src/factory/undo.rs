@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use crate::factions::Faction;
+use crate::factory::ConstructBuildingEvent;
+use crate::grid::{are_positions_free, calculate_occupied_cells_rotated, GridPosition, Orientation, WorldMap};
+use crate::save::BuildingDescriptor;
+
+/// Removals older than this many are forgotten
+pub const UNDO_BUFFER_SIZE: usize = 20;
+
+/// Removed as a side effect of something else (built over, a cancelled drag), Ctrl+Z
+/// doesn't bring these back
+#[derive(Component)]
+pub struct NotUndoable;
+
+#[derive(Debug, Clone)]
+pub struct RemovalRecord {
+    pub name: String,
+    pub position: GridPosition,
+    pub orientation: Orientation,
+    pub is_link: bool,
+    pub descriptor: BuildingDescriptor,
+}
+
+/// The last few buildings the player removed, newest at the back
+#[derive(Resource, Default, Debug)]
+pub struct UndoBuffer(pub VecDeque<RemovalRecord>);
+
+impl UndoBuffer {
+    pub fn record(&mut self, record: RemovalRecord) {
+        if self.0.len() >= UNDO_BUFFER_SIZE {
+            self.0.pop_front();
+        }
+        self.0.push_back(record);
+    }
+}
+
+/// Ctrl+Z puts the last removed building back where it was, free of charge since removing
+/// it didn't refund anything. If something has been built there since, the record stays
+/// for another go once the cells are cleared.
+pub fn undo_last_removal(
+    controls: crate::controls::Controls,
+    mut buffer: ResMut<UndoBuffer>,
+    world_map: Res<WorldMap>,
+    bounds: Res<crate::world_gen::WorldBounds>,
+    mut construct_events: MessageWriter<ConstructBuildingEvent>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+) {
+    if !(controls.just_pressed(crate::controls::InputAction::Undo)
+        && controls.keys().any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]))
+    {
+        return;
+    }
+    let Some(record) = buffer.0.back() else {
+        info!("Nothing to undo");
+        return;
+    };
+
+    let building = record.descriptor.building();
+    let data = building.data();
+    let cells = calculate_occupied_cells_rotated(*record.position, data.grid_width, data.grid_height, record.orientation)
+        .into_iter()
+        .map(GridPosition)
+        .collect::<Vec<_>>();
+    if !are_positions_free(&world_map, &bounds, &cells) {
+        info!("Can't undo removal of {} at {:?}, the cells are taken", record.name, record.position);
+        news_writer.write(crate::events::AddNewsfeedItemEvent {
+            // the newsfeed wants a faction, this one is nobody's news
+            faction: Faction::default(),
+            headline: format!("Couldn't restore the {} - something is built where it stood", record.name),
+            payload: None,
+        });
+        return;
+    }
+
+    info!("Undoing removal of {} at {:?}", record.name, record.position);
+    construct_events.write(ConstructBuildingEvent {
+        building,
+        grid_position: *record.position,
+        orientation: record.orientation,
+        replaces: Vec::new(),
+        free: true,
+    });
+    buffer.0.pop_back();
+}
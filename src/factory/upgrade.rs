@@ -0,0 +1,254 @@
+use std::sync::Arc;
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::assets::GameAssets;
+use crate::factory::buildings::buildings::{layout_offset, Building};
+use crate::factory::buildings::combiner::Combiner;
+use crate::factory::buildings::splitter::Splitter;
+use crate::factory::buildings::Tile;
+use crate::factory::building_visuals::z_layers;
+use crate::factory::undo::NotUndoable;
+use crate::factory::{ConstructBuildingEvent, MarkedForRemoval};
+use crate::grid::{calculate_occupied_cells_rotated, Grid, GridPosition, Orientation, WorldMap};
+use crate::save::{BuildingDescriptor, PlacedBuilding};
+use crate::ui::interaction::MouseButtonEvent;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::BlocksWorldClicks;
+
+/// Biggest splitter/combiner there are sprites for
+pub const MAX_PORT_COUNT: i64 = 4;
+const BLOCKED_FLASH_SECONDS: f32 = 0.8;
+
+/// Alt+click menu on a placed splitter or combiner
+#[derive(Component)]
+pub struct UpgradeMenu;
+
+#[derive(Component)]
+pub struct UpgradeOption {
+    pub building: Entity,
+    pub ports: i64,
+}
+
+/// Red square over a cell that got in the way of an upgrade
+#[derive(Component)]
+pub struct UpgradeBlockedFlash(pub f32);
+
+/// Port count of an upgradable building, None for everything else
+fn port_count(descriptor: &BuildingDescriptor) -> Option<i64> {
+    match descriptor {
+        BuildingDescriptor::Splitter { source_count, .. } => Some(*source_count),
+        BuildingDescriptor::Combiner { sink_count, .. } => Some(*sink_count),
+        _ => None,
+    }
+}
+
+/// The same building with `ports` outputs (splitter) or inputs (combiner)
+fn with_ports(descriptor: &BuildingDescriptor, ports: i64) -> Option<Arc<dyn Building>> {
+    match *descriptor {
+        BuildingDescriptor::Splitter { throughput, .. } => Some(Arc::new(Splitter { throughput, source_count: ports })),
+        BuildingDescriptor::Combiner { throughput, .. } => Some(Arc::new(Combiner { throughput, sink_count: ports })),
+        _ => None,
+    }
+}
+
+/// Where the bigger footprint can go, best first. Growing forward keeps every port where it
+/// was; growing backward keeps the outputs (splitter) or inputs (combiner) but moves the shared
+/// port to the new anchor.
+fn upgrade_anchors(anchor: I64Vec2, orientation: Orientation, extra: i64) -> [I64Vec2; 2] {
+    [anchor, anchor - layout_offset(orientation, extra)]
+}
+
+/// Alt + left click on a splitter or combiner opens the menu, any other click closes it
+pub fn open_upgrade_menu(
+    mut commands: Commands,
+    (mouse, keyboard): (Res<ButtonInput<MouseButton>>, Res<ButtonInput<KeyCode>>),
+    mut mouse_event: ResMut<MouseButtonEvent>,
+    (window, camera): (Single<&Window, With<PrimaryWindow>>, Single<(&Camera, &GlobalTransform)>),
+    (grid, world_map): (Res<Grid>, Res<WorldMap>),
+    tiles: Query<&Tile>,
+    placed: Query<&PlacedBuilding>,
+    menus: Query<Entity, With<UpgradeMenu>>,
+    ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    selected_building_type: Res<crate::ui::shop::SelectedBuildingType>,
+    game_mode: Res<crate::game_mode::GameMode>,
+    game_assets: Res<GameAssets>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || selected_building_type.0.is_some() {
+        return;
+    }
+    if ui_blocker_query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed)
+    {
+        return;
+    }
+    for menu in menus.iter() {
+        commands.entity(menu).despawn();
+    }
+    if !keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+        return;
+    }
+
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = window.cursor_position() else { return };
+    let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor) else { return };
+    let Some((building, descriptor)) = world_map
+        .get(&grid.world_to_grid(world_pos))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| tiles.get(*e).ok())
+        .find_map(|tile| placed.get(tile.0).ok().map(|p| (tile.0, &p.descriptor)))
+    else {
+        return;
+    };
+    let Some(current) = port_count(descriptor) else { return };
+    mouse_event.handle();
+
+    let current_cost = descriptor.building().data().cost;
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(cursor.x + 8.0),
+                top: Val::Px(cursor.y + 8.0),
+                padding: UiRect::all(Val::Vw(0.4)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.3),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.92)),
+            GlobalZIndex(850),
+            UpgradeMenu,
+            BlocksWorldClicks,
+            Interaction::None,
+        ))
+        .with_children(|menu| {
+            if current >= MAX_PORT_COUNT {
+                menu.spawn((
+                    Text::new(format!("Already {}-way", current)),
+                    game_assets.text_font(14.0),
+                    ScalableText::from_vw(0.9),
+                    TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                ));
+            }
+            for ports in current + 1..=MAX_PORT_COUNT {
+                let Some(upgraded) = with_ports(descriptor, ports) else { continue };
+                let difference = game_mode.placement_cost(upgraded.data().cost - current_cost).max(0);
+                menu.spawn((
+                    Node {
+                        padding: UiRect::axes(Val::Vw(0.6), Val::Vw(0.25)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.35, 0.5)),
+                    Interaction::None,
+                    UpgradeOption { building, ports },
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new(format!("Upgrade to {}-way (${})", ports, difference)),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.9),
+                        TextColor(Color::WHITE),
+                    ));
+                });
+            }
+        });
+}
+
+/// Swaps the building for the bigger one in place, or flashes whatever's in the way
+pub fn handle_upgrade_option(
+    mut commands: Commands,
+    options: Query<(&Interaction, &UpgradeOption), Changed<Interaction>>,
+    menus: Query<Entity, With<UpgradeMenu>>,
+    buildings: Query<(&PlacedBuilding, &GridPosition)>,
+    (grid, world_map): (Res<Grid>, Res<WorldMap>),
+    tiles: Query<&Tile>,
+    mut player: ResMut<crate::player::Player>,
+    game_mode: Res<crate::game_mode::GameMode>,
+    mut construct_events: MessageWriter<ConstructBuildingEvent>,
+    mut sfx: MessageWriter<crate::audio::PlaySfx>,
+) {
+    let Some((_, option)) = options.iter().find(|(i, _)| **i == Interaction::Pressed) else { return };
+    for menu in menus.iter() {
+        commands.entity(menu).despawn();
+    }
+    let Ok((placed, position)) = buildings.get(option.building) else { return };
+    let (Some(current), Some(upgraded)) = (port_count(&placed.descriptor), with_ports(&placed.descriptor, option.ports))
+    else {
+        return;
+    };
+    let data = upgraded.data();
+    let difference = (data.cost - placed.descriptor.building().data().cost).max(0);
+    if !game_mode.can_afford(difference, player.money) {
+        info!("Can't afford upgrade to {} ({} needed, {} available)", data.name, difference, player.money);
+        return;
+    }
+
+    let orientation = placed.orientation;
+    // a cell is free if nothing's there, or only this building's own tiles
+    let blocked = |cell: I64Vec2| {
+        world_map.get(&GridPosition(cell)).is_some_and(|entities| {
+            entities
+                .iter()
+                .any(|e| tiles.get(*e).map_or(true, |tile| tile.0 != option.building))
+        })
+    };
+    let candidates = upgrade_anchors(position.0, orientation, option.ports - current);
+    let chosen = candidates.iter().find(|anchor| {
+        calculate_occupied_cells_rotated(**anchor, option.ports, 1, orientation)
+            .into_iter()
+            .all(|cell| !blocked(cell))
+    });
+    let Some(anchor) = chosen else {
+        // show what's in the way of growing forward, the layout that keeps every link
+        for cell in calculate_occupied_cells_rotated(candidates[0], option.ports, 1, orientation) {
+            if !blocked(cell) {
+                continue;
+            }
+            commands.spawn((
+                Sprite {
+                    color: Color::srgba(1.0, 0.15, 0.15, 0.6),
+                    custom_size: Some(Vec2::splat(grid.scale)),
+                    ..default()
+                },
+                Transform::from_translation(grid.grid_to_world_center(&GridPosition(cell)).extend(z_layers::BADGE)),
+                UpgradeBlockedFlash(BLOCKED_FLASH_SECONDS),
+            ));
+        }
+        info!("No room to upgrade to {} at {:?}", data.name, position);
+        return;
+    };
+
+    info!("Upgrading {:?} at {:?} to {} for {}", option.building, position, data.name, difference);
+    player.money -= game_mode.placement_cost(difference);
+    sfx.write(crate::audio::PlaySfx(crate::audio::Sfx::Place));
+    // the old one goes without an undo entry, undoing would put it back on top of the new one
+    commands.entity(option.building).insert((MarkedForRemoval, NotUndoable));
+    // paid above, so the construction itself is free. The new tiles fire EntityPlaced and
+    // the links around them re-resolve like any other placement
+    construct_events.write(ConstructBuildingEvent {
+        building: upgraded,
+        grid_position: *anchor,
+        orientation,
+        replaces: Vec::new(),
+        free: true,
+    });
+}
+
+pub fn fade_upgrade_blocked_flashes(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut flashes: Query<(Entity, &mut UpgradeBlockedFlash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in flashes.iter_mut() {
+        flash.0 -= time.delta_secs();
+        if flash.0 <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.color.set_alpha(0.6 * flash.0 / BLOCKED_FLASH_SECONDS);
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How the current run was started. Consulted at the specific decision points
+/// (placement cost, world gen locking, reputation init, contract scheduling)
+/// instead of forking code paths.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameMode {
+    /// Free building, everything unlocked, excluded from records
+    pub sandbox: bool,
+    /// Sandbox runs can switch contract generation off
+    pub contract_generation: bool,
+}
+
+impl Default for GameMode {
+    fn default() -> Self {
+        Self {
+            sandbox: false,
+            contract_generation: true,
+        }
+    }
+}
+
+impl GameMode {
+    pub fn sandbox() -> Self {
+        Self {
+            sandbox: true,
+            contract_generation: true,
+        }
+    }
+
+    /// What the setup screen's mode selector starts on, `--sandbox` preselects sandbox
+    pub fn from_args() -> Self {
+        if std::env::args().any(|arg| arg == "--sandbox") {
+            Self::sandbox()
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn can_afford(&self, cost: i32, money: i32) -> bool {
+        self.sandbox || money >= cost
+    }
+
+    /// What a placement actually charges
+    pub fn placement_cost(&self, cost: i32) -> i32 {
+        if self.sandbox { 0 } else { cost }
+    }
+
+    pub fn upkeep_enabled(&self) -> bool {
+        !self.sandbox
+    }
+
+    pub fn starts_locked(&self) -> bool {
+        !self.sandbox
+    }
+
+    pub fn contracts_enabled(&self) -> bool {
+        !self.sandbox || self.contract_generation
+    }
+
+    /// Sandbox runs never count towards statistics and records
+    pub fn counts_for_records(&self) -> bool {
+        !self.sandbox
+    }
+}
+
+pub struct GameModePlugin;
+
+impl Plugin for GameModePlugin {
+    fn build(&self, app: &mut App) {
+        let mode = GameMode::from_args();
+        if mode.sandbox {
+            info!("Starting in sandbox mode");
+        }
+        app.insert_resource(mode);
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::controls::{Controls, InputAction};
+use crate::pause::GameState;
+
+/// Sim speeds the player can step through, slowest first
+pub const SPEED_STEPS: [f32; 3] = [1.0, 2.0, 4.0];
+
+/// How fast the player wants the sim to run. Goes into `Time<Virtual>`, so everything on
+/// plain `Time` (data passing, contract timers, event cooldowns, the throughput timer) scales
+/// together; UI animations read `Time<Real>` and keep their pace.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GameSpeed {
+    step: usize,
+}
+
+impl GameSpeed {
+    pub fn multiplier(&self) -> f32 {
+        SPEED_STEPS[self.step]
+    }
+
+    pub fn faster(&mut self) {
+        self.step = (self.step + 1).min(SPEED_STEPS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}x", self.multiplier())
+    }
+}
+
+fn handle_speed_input(controls: Controls, mut speed: ResMut<GameSpeed>) {
+    // set only on an actual step so the display doesn't refresh every press at the limit
+    let mut next = *speed;
+    if controls.just_pressed(InputAction::SpeedUp) {
+        next.faster();
+    }
+    if controls.just_pressed(InputAction::SlowDown) {
+        next.slower();
+    }
+    if next != *speed {
+        *speed = next;
+        info!("Game speed {}", speed.label());
+    }
+}
+
+/// The speed the clock should be at: 1x while an event modal is up so reading it costs nothing.
+/// Pause is separate (it's a state), so the chosen speed comes back when it ends.
+pub fn effective_speed(speed: &GameSpeed, state: &GameState) -> f32 {
+    if *state == GameState::EventModal {
+        1.0
+    } else {
+        speed.multiplier()
+    }
+}
+
+fn apply_game_speed(
+    speed: Res<GameSpeed>,
+    state: Res<State<GameState>>,
+    ceremonies: Res<crate::ui::cluster_unlock::UnlockCeremonies>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    // the unlock ceremony slows the clock itself and puts it back afterwards,
+    // this catches up on any speed change made meanwhile the frame after
+    if ceremonies.is_running() {
+        return;
+    }
+    let target = effective_speed(&speed, state.get());
+    if virtual_time.relative_speed() != target {
+        virtual_time.set_relative_speed(target);
+    }
+}
+
+pub struct GameSpeedPlugin;
+
+impl Plugin for GameSpeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameSpeed>()
+            .add_systems(Update, (
+                handle_speed_input.run_if(not(in_state(GameState::Setup))),
+                apply_game_speed,
+            ).chain());
+    }
+}
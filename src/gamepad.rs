@@ -0,0 +1,239 @@
+use bevy::input::mouse::AccumulatedMouseMotion;
+use bevy::input::InputSystems;
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::ui::UiSystems;
+use bevy::window::PrimaryWindow;
+
+use crate::factory::building_visuals::z_layers;
+use crate::grid::{Grid, GridPosition};
+use crate::ui::contracts::ContractAcceptButton;
+use crate::ui::interactive_event::EventChoiceButton;
+use crate::ui::widgets::Focusable;
+
+/// A held direction waits this long before repeating, then steps every `CURSOR_REPEAT_INTERVAL`
+const CURSOR_REPEAT_DELAY: f32 = 0.3;
+const CURSOR_REPEAT_INTERVAL: f32 = 0.08;
+/// How far the right stick has to go before it counts as a push
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// The pad's virtual cursor. While the pad's the last thing used, the real mouse cursor
+/// is kept on the cell (or focused button), and A/B are fed in as left/right clicks so
+/// everything downstream of `MouseButtonEvent` treats them like the mouse.
+#[derive(Resource, Default)]
+pub struct GamepadCursor {
+    pub cell: Option<GridPosition>,
+    pub active: bool,
+    /// Walking menu buttons with the d-pad instead of the grid
+    pub menu_focus: bool,
+    pub focused: Option<Entity>,
+    held: I64Vec2,
+    repeat: f32,
+}
+
+/// Outline on the cell under the pad cursor
+#[derive(Component)]
+pub struct GamepadCursorHighlight;
+
+/// Outline on the button the pad has focused
+#[derive(Component)]
+pub struct GamepadFocused;
+
+fn pad_direction(gamepad: &Gamepad) -> I64Vec2 {
+    let mut direction = I64Vec2::ZERO;
+    if gamepad.pressed(GamepadButton::DPadUp) {
+        direction.y += 1;
+    }
+    if gamepad.pressed(GamepadButton::DPadDown) {
+        direction.y -= 1;
+    }
+    if gamepad.pressed(GamepadButton::DPadLeft) {
+        direction.x -= 1;
+    }
+    if gamepad.pressed(GamepadButton::DPadRight) {
+        direction.x += 1;
+    }
+    if direction == I64Vec2::ZERO {
+        let stick = gamepad.right_stick();
+        if stick.x.abs() > STICK_THRESHOLD {
+            direction.x = stick.x.signum() as i64;
+        }
+        if stick.y.abs() > STICK_THRESHOLD {
+            direction.y = stick.y.signum() as i64;
+        }
+    }
+    direction
+}
+
+/// Moves the pad cursor a cell (or a button) per step, warps the mouse there and turns A/B
+/// into clicks. Runs between input collection and UI focus so buttons see the click this frame.
+fn drive_gamepad_cursor(
+    mut cursor: ResMut<GamepadCursor>,
+    gamepads: Query<&Gamepad>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    grid: Res<Grid>,
+    time: Res<Time<Real>>,
+    (choices, accept_buttons, widgets): (
+        Query<(Entity, &UiGlobalTransform, &ComputedNode), With<EventChoiceButton>>,
+        Query<(Entity, &UiGlobalTransform, &ComputedNode), With<ContractAcceptButton>>,
+        Query<&ComputedNode, With<Focusable>>,
+    ),
+) {
+    // the real mouse takes over again, the pad picks up from wherever it leaves the cursor
+    if mouse_motion.delta != Vec2::ZERO {
+        cursor.active = false;
+        cursor.cell = None;
+    }
+    // settings widgets already do their own d-pad navigation
+    if widgets.iter().any(|node| node.size() != Vec2::ZERO) {
+        return;
+    }
+    let Some(gamepad) = gamepads.iter().next() else { return };
+
+    let direction = pad_direction(gamepad);
+    let used = direction != I64Vec2::ZERO
+        || gamepad.get_just_pressed().next().is_some()
+        || gamepad.left_stick() != Vec2::ZERO;
+    if used {
+        cursor.active = true;
+    }
+    if !cursor.active {
+        return;
+    }
+
+    // an event popup takes the pad until it's answered, Select toggles the contract buttons
+    let modal = !choices.is_empty();
+    if gamepad.just_pressed(GamepadButton::Select) && !modal {
+        cursor.menu_focus = !cursor.menu_focus;
+    }
+    let menu_focus = modal || cursor.menu_focus;
+
+    let step = if direction == I64Vec2::ZERO {
+        cursor.repeat = 0.0;
+        None
+    } else if direction != cursor.held {
+        cursor.repeat = CURSOR_REPEAT_DELAY;
+        Some(direction)
+    } else {
+        cursor.repeat -= time.delta_secs();
+        (cursor.repeat <= 0.0).then(|| {
+            cursor.repeat = CURSOR_REPEAT_INTERVAL;
+            direction
+        })
+    };
+    cursor.held = direction;
+
+    let (camera, camera_transform) = *camera;
+    let target = if menu_focus {
+        // top to bottom, then left to right, as they are on screen
+        let candidates: Vec<_> = if modal { choices.iter().collect() } else { accept_buttons.iter().collect() };
+        let mut buttons: Vec<(Entity, Vec2)> = candidates
+            .into_iter()
+            .filter(|(_, _, node)| node.size() != Vec2::ZERO)
+            .map(|(entity, transform, node)| (entity, transform.translation * node.inverse_scale_factor()))
+            .collect();
+        buttons.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+        let current = cursor.focused.and_then(|f| buttons.iter().position(|(e, _)| *e == f));
+        let index = match (current, step) {
+            (Some(i), Some(step)) => {
+                // down and right go forward, the y axis points up on the pad
+                let delta = if step.y < 0 || step.x > 0 { 1 } else { -1 };
+                Some((i as i64 + delta).rem_euclid(buttons.len() as i64) as usize)
+            }
+            (Some(i), None) => Some(i),
+            (None, _) => (!buttons.is_empty()).then_some(0),
+        };
+        cursor.focused = index.map(|i| buttons[i].0);
+        index.map(|i| buttons[i].1)
+    } else {
+        cursor.focused = None;
+        let cell = *cursor.cell.get_or_insert_with(|| {
+            // starts wherever the mouse was, or the middle of the screen
+            let screen = window.cursor_position().unwrap_or(window.size() / 2.0);
+            camera
+                .viewport_to_world_2d(camera_transform, screen)
+                .map(|world| grid.world_to_grid(world))
+                .unwrap_or(GridPosition(I64Vec2::ZERO))
+        });
+        let cell = GridPosition(cell.0 + step.unwrap_or(I64Vec2::ZERO));
+        cursor.cell = Some(cell);
+        camera
+            .world_to_viewport(camera_transform, grid.grid_to_world_center(&cell).extend(0.0))
+            .ok()
+    };
+    if let Some(target) = target
+        && window.cursor_position() != Some(target)
+    {
+        window.set_cursor_position(Some(target));
+    }
+
+    for (button, mouse_button) in [(GamepadButton::South, MouseButton::Left), (GamepadButton::East, MouseButton::Right)] {
+        if gamepad.just_pressed(button) {
+            mouse.press(mouse_button);
+        }
+        if gamepad.just_released(button) {
+            mouse.release(mouse_button);
+        }
+    }
+}
+
+/// Keeps the highlight on the pad's cell and the outline on its focused button
+fn show_gamepad_cursor(
+    mut commands: Commands,
+    cursor: Res<GamepadCursor>,
+    grid: Res<Grid>,
+    mut highlight: Query<(&mut Transform, &mut Visibility), With<GamepadCursorHighlight>>,
+    focused: Query<Entity, With<GamepadFocused>>,
+) {
+    let show_cell = cursor.active && !cursor.menu_focus && cursor.focused.is_none();
+    match (highlight.single_mut(), cursor.cell) {
+        (Ok((mut transform, mut visibility)), Some(cell)) => {
+            transform.translation = grid.grid_to_world_center(&cell).extend(z_layers::BADGE + 1.0);
+            visibility.set_if_neq(if show_cell { Visibility::Visible } else { Visibility::Hidden });
+        }
+        (Err(_), Some(cell)) => {
+            commands.spawn((
+                Sprite {
+                    color: Color::srgba(0.3, 0.8, 1.0, 0.35),
+                    custom_size: Some(Vec2::splat(grid.scale)),
+                    ..default()
+                },
+                Transform::from_translation(grid.grid_to_world_center(&cell).extend(z_layers::BADGE + 1.0)),
+                GamepadCursorHighlight,
+            ));
+        }
+        (Ok((_, mut visibility)), None) => {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+        (Err(_), None) => {}
+    }
+
+    let wanted = cursor.focused.filter(|_| cursor.active);
+    for entity in focused.iter() {
+        if Some(entity) != wanted {
+            commands.entity(entity).remove::<(GamepadFocused, Outline)>();
+        }
+    }
+    if let Some(entity) = wanted
+        && !focused.contains(entity)
+        && let Ok(mut entity_commands) = commands.get_entity(entity)
+    {
+        entity_commands.insert((
+            GamepadFocused,
+            Outline::new(Val::Px(2.0), Val::Px(1.0), Color::srgb(0.3, 0.8, 1.0)),
+        ));
+    }
+}
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadCursor>()
+            .add_systems(PreUpdate, drive_gamepad_cursor.after(InputSystems).before(UiSystems::Focus))
+            .add_systems(Update, show_gamepad_cursor);
+    }
+}
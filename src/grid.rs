@@ -1,7 +1,7 @@
 use bevy::ecs::lifecycle::HookContext;
 use bevy::ecs::world::DeferredWorld;
 use bevy::math::I64Vec2;
-use bevy::prelude::{Changed, DerefMut};
+use bevy::prelude::{info, Camera, Changed, DerefMut, Projection, With};
 use bevy::{
     app::{Plugin, PostUpdate, Startup},
     asset::{Asset, Assets},
@@ -12,6 +12,7 @@ use bevy::{
         query::Added,
         resource::Resource,
         system::{Commands, Query, Res, ResMut},
+        message::MessageReader,
     },
     math::{Vec2, Vec3, Vec4, primitives::Rectangle},
     mesh::{Mesh, Mesh2d},
@@ -23,12 +24,22 @@ use bevy::{
     sprite::Sprite,
     sprite_render::{AlphaMode2d, Material2d, Material2dPlugin, MeshMaterial2d},
     transform::components::Transform,
-    window::Window,
+    window::{Window, WindowResized},
 };
 
 const GRID_SHADER_ASSET_PATH: &str = "shaders/grid_shader.wgsl";
+/// The grid quad spans this many windows each way, so zoomed all the way out it still fills the screen
+const GRID_QUAD_WINDOWS: f32 = 100.0;
+const GRID_INTENSITY: f32 = 1.0;
+/// Camera scale the lines start fading at, and where they're gone
+const GRID_FADE_START_SCALE: f32 = 3.0;
+const GRID_FADE_END_SCALE: f32 = 9.0;
 pub struct GridPlugin;
 
+/// Background shader and sprites for grid entities. Needs a window and `GameAssets`,
+/// `GridPlugin` on its own is enough for the simulation.
+pub struct GridVisualsPlugin;
+
 // World map resource to track which grid positions are occupied by which entities
 #[derive(Resource, Default, Deref, DerefMut)]
 pub struct WorldMap(pub HashMap<GridPosition, Vec<Entity>>);
@@ -41,7 +52,7 @@ pub struct WorldMap(pub HashMap<GridPosition, Vec<Entity>>);
 #[derive(Debug)]
 pub struct GridPosition(pub I64Vec2);
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Direction {
     Right,
     Down,
@@ -59,7 +70,7 @@ impl Direction {
 }
 
 /// Represents the orientation of a building (direction + flip state)
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Orientation {
     pub direction: Direction,
     pub flipped: bool,
@@ -223,6 +234,10 @@ pub struct GridMaterial {
     pub grid_intensity: f32,
 }
 
+/// The background quad the grid shader draws on
+#[derive(Component)]
+pub struct GridBackground;
+
 impl Plugin for GridPlugin {
     fn build(&self, app: &mut bevy::app::App) {
         app.insert_resource(Grid {
@@ -230,12 +245,18 @@ impl Plugin for GridPlugin {
             base_offset: 0.,
         });
         app.insert_resource(WorldMap::default());
+        app.add_systems(PostUpdate, transform_to_grid);
+    }
+}
+
+impl Plugin for GridVisualsPlugin {
+    fn build(&self, app: &mut bevy::app::App) {
         app.add_plugins(Material2dPlugin::<GridMaterial>::default());
         app.add_systems(Startup, setup_grid);
+        app.add_systems(bevy::app::Update, (resize_grid_background, fade_grid_with_zoom));
         app.add_systems(
             PostUpdate,
             (
-                transform_to_grid,
                 spawn_grid_sprite_system,
                 spawn_grid_atlas_sprite_system,
             ),
@@ -460,33 +481,79 @@ fn setup_grid(
 ) {
     let window = query.single().unwrap();
 
-    let width = window.width() * 100.;
-    let height = window.height() * 100.;
+    let half_size = grid_half_size(Vec2::new(window.width(), window.height()), &grid);
 
     // quad
     commands.spawn((
-        Mesh2d(meshes.add(Rectangle {
-            half_size: Vec2 {
-                x: width,
-                y: height,
-            },
-        })),
+        Mesh2d(meshes.add(Rectangle { half_size })),
         MeshMaterial2d(materials.add(GridMaterial {
             line_colour: Vec4::new(1.0, 1.0, 1.0, 0.1),
             line_width: 0.5,
             grid_size: grid.scale / 2.0,
             offset: Vec2::splat(grid.scale / 4.0),
-            resolution: Vec2::new(width, height), // Match your quad size
-            grid_intensity: 0.7,
+            resolution: half_size, // Match your quad size
+            grid_intensity: GRID_INTENSITY,
         })),
         Transform::from_translation(Vec3 {
             x: grid.base_offset + grid.scale / 2.0,
             y: grid.base_offset + grid.scale / 2.0,
             z: 1.,
         }),
+        GridBackground,
     ));
 }
 
+/// Rounded up to whole coarse squares, the quad's edge is where the shader counts lines from,
+/// so anything else puts them off the tile edges
+fn grid_half_size(window_size: Vec2, grid: &Grid) -> Vec2 {
+    let step = grid.scale * 5.0;
+    (window_size * GRID_QUAD_WINDOWS / step).ceil() * step
+}
+
+/// Window resized or went fullscreen, the quad and the shader's resolution follow it
+fn resize_grid_background(
+    mut resized: MessageReader<WindowResized>,
+    grid: Res<Grid>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GridMaterial>>,
+    mut backgrounds: Query<(&mut Mesh2d, &MeshMaterial2d<GridMaterial>), With<GridBackground>>,
+) {
+    let Some(event) = resized.read().last() else { return };
+    if event.width <= 0.0 || event.height <= 0.0 {
+        // minimised
+        return;
+    }
+    let half_size = grid_half_size(Vec2::new(event.width, event.height), &grid);
+    for (mut mesh, material) in backgrounds.iter_mut() {
+        let Some(material) = materials.get_mut(&material.0) else { continue };
+        if material.resolution == half_size {
+            continue;
+        }
+        material.resolution = half_size;
+        mesh.0 = meshes.add(Rectangle { half_size });
+        info!("Grid background resized to {}x{}", half_size.x * 2.0, half_size.y * 2.0);
+    }
+}
+
+/// Lines fade out as the camera pulls back, zoomed far out they'd just moiré
+fn fade_grid_with_zoom(
+    camera: Query<&Projection, (With<Camera>, Changed<Projection>)>,
+    mut materials: ResMut<Assets<GridMaterial>>,
+    backgrounds: Query<&MeshMaterial2d<GridMaterial>, With<GridBackground>>,
+) {
+    let Some(Projection::Orthographic(orthographic)) = camera.iter().next() else {
+        return;
+    };
+    let t = ((orthographic.scale - GRID_FADE_START_SCALE) / (GRID_FADE_END_SCALE - GRID_FADE_START_SCALE)).clamp(0.0, 1.0);
+    let intensity = GRID_INTENSITY * (1.0 - t);
+    for material in backgrounds.iter() {
+        let Some(material) = materials.get_mut(&material.0) else { continue };
+        if material.grid_intensity != intensity {
+            material.grid_intensity = intensity;
+        }
+    }
+}
+
 fn transform_to_grid(
     query: Query<(&mut Transform, &GridPosition), Changed<GridPosition>>,
     grid: Res<Grid>,
@@ -602,6 +669,7 @@ pub fn calculate_occupied_cells_rotated(
 
     cells
 }
-pub fn are_positions_free(world_map: &WorldMap, positions: &[GridPosition]) -> bool {
-    positions.iter().all(|pos| !world_map.0.contains_key(pos))
+/// Nothing built there and inside the world
+pub fn are_positions_free(world_map: &WorldMap, bounds: &crate::world_gen::WorldBounds, positions: &[GridPosition]) -> bool {
+    positions.iter().all(|pos| bounds.contains(pos.0) && !world_map.0.contains_key(pos))
 }
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy_prng::WyRand;
+use bevy_rand::prelude::EntropyPlugin;
+use std::time::Duration;
+
+use crate::{
+    contracts::ContractsPlugin,
+    factions::FactionsPlugin,
+    factory::FactoryPlugin,
+    grid::GridPlugin,
+    market::MarketPlugin,
+    pause::GameState,
+    player::PlayerPlugin,
+    trace::TracePlugin,
+    world_gen::WorldSeed,
+};
+
+/// Game time each `App::update` advances by when running headless
+pub const HEADLESS_TICK: Duration = Duration::from_millis(100);
+
+/// The simulation on `MinimalPlugins`: grid, factory, contracts, factions and money, with no
+/// window, assets, audio or world gen. Starts straight in `Running` with the default game mode
+/// and difficulty, and every update is one `HEADLESS_TICK` so timers fire on a fixed count.
+pub struct HeadlessPlugin {
+    pub seed: u64,
+}
+
+impl Default for HeadlessPlugin {
+    fn default() -> Self {
+        Self { seed: 58 }
+    }
+}
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(HEADLESS_TICK))
+            .insert_resource(WorldSeed(self.seed))
+            .add_plugins(EntropyPlugin::<WyRand>::with_seed(self.seed.to_le_bytes()))
+            .insert_state(GameState::Running)
+            // what the windowed app gets from plugins that also bring UI or input along
+            .init_resource::<crate::game_mode::GameMode>()
+            .init_resource::<crate::difficulty::Difficulty>()
+            .init_resource::<crate::achievements::RunCounters>()
+            .init_resource::<crate::ui::stats::StatsHistory>()
+            .init_resource::<crate::events::DebtState>()
+            .init_resource::<crate::crash::DisabledSystems>()
+            .init_resource::<crate::world_gen::WorldBounds>()
+            .add_message::<crate::audio::PlaySfx>()
+            .add_message::<crate::events::AddNewsfeedItemEvent>()
+            .add_plugins((
+                TracePlugin,
+                MarketPlugin,
+                GridPlugin,
+                FactoryPlugin,
+                ContractsPlugin,
+                FactionsPlugin,
+                PlayerPlugin,
+            ));
+    }
+}
+
+/// A headless app ready to `update`, startup systems run on the first one
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(HeadlessPlugin::default());
+    app
+}
@@ -0,0 +1,204 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_prng::WyRand;
+use bevy_rand::prelude::GlobalRng;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+
+use crate::contracts::{AssociatedWithSink, ContractStatus, RaiseContractThreshold, ThresholdRaised};
+use crate::events::{AddNewsfeedItemEvent, InteractiveEventLibrary, NewsPayload, ShowInteractiveEvent};
+use crate::factions::{Faction, FactionReputations, ReputationLevel};
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::buildings::source::SourceBuilding;
+use crate::factory::buildings::{Tile, Undeletable};
+use crate::factory::damage::{damage_segment, Damaged};
+use crate::factory::physical::PhysicalLink;
+use crate::grid::GridPosition;
+use crate::pause::GameState;
+use crate::save::PlacedBuilding;
+use crate::trace::TraceLog;
+use crate::trace_sim;
+
+/// Seconds between sabotages at the start of a hostile spell, rolled fresh each time
+pub const SABOTAGE_INTERVAL_SECONDS: std::ops::RangeInclusive<f32> = 90.0..=180.0;
+/// Every this many seconds of hostility the sabotage comes one interval's worth faster
+pub const ESCALATION_SECONDS: f32 = 300.0;
+/// Sabotage never comes more than this many times as often as at the start
+pub const MAX_ESCALATION: f32 = 3.0;
+/// The wire cut is one of this many closest to the faction's buildings
+const WIRE_CANDIDATES: usize = 5;
+/// A cut breaks the chain outright, it heals or gets repaired like any other damage
+const WIRE_CUT_SEVERITY: u8 = 2;
+/// Camera trauma when a wire's cut
+const WIRE_CUT_SHAKE: f32 = 0.5;
+const RAISED_THRESHOLD_FACTOR: f64 = 1.5;
+const RAISED_THRESHOLD_SECONDS: f32 = 60.0;
+
+/// How long a faction has been hostile and when it strikes next
+#[derive(Debug, Clone, Copy)]
+pub struct HostileSpell {
+    pub elapsed: f32,
+    pub next_sabotage: f32,
+}
+
+/// Factions at `ReputationLevel::Hostile` and the ones due to sabotage something this frame.
+/// A faction climbing back to Untrusted drops out straight away, along with anything it had due.
+#[derive(Resource, Default, Debug)]
+pub struct Hostility {
+    spells: HashMap<Faction, HostileSpell>,
+    due: Vec<Faction>,
+}
+
+impl Hostility {
+    pub fn spell(&self, faction: Faction) -> Option<&HostileSpell> {
+        self.spells.get(&faction)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sabotage {
+    CutWire,
+    RaiseThreshold,
+    Shakedown,
+}
+
+/// The longer the hostility lasts, the shorter the wait
+pub fn roll_interval(elapsed: f32, rng: &mut WyRand) -> f32 {
+    let escalation = (1.0 + elapsed / ESCALATION_SECONDS).min(MAX_ESCALATION);
+    rng.random_range(SABOTAGE_INTERVAL_SECONDS) / escalation
+}
+
+fn shakedown_event_id(faction: Faction) -> &'static str {
+    match faction {
+        Faction::Corporate => "corp_hostile_shakedown",
+        Faction::Academia => "acad_hostile_shakedown",
+        Faction::Government => "gov_hostile_shakedown",
+        Faction::Criminal => "crim_hostile_shakedown",
+    }
+}
+
+fn track_hostility(
+    time: Res<Time>,
+    reputations: Res<FactionReputations>,
+    mut hostility: ResMut<Hostility>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+) {
+    for faction in [Faction::Corporate, Faction::Academia, Faction::Government, Faction::Criminal] {
+        if reputations.get_level(faction) != ReputationLevel::Hostile {
+            if hostility.spells.remove(&faction).is_some() {
+                hostility.due.retain(|f| *f != faction);
+                info!("{:?} no longer hostile, sabotage stops", faction);
+            }
+            continue;
+        }
+        let spell = hostility.spells.entry(faction).or_insert_with(|| {
+            info!("{:?} turned hostile", faction);
+            HostileSpell { elapsed: 0.0, next_sabotage: roll_interval(0.0, &mut rng) }
+        });
+        spell.elapsed += time.delta_secs();
+        spell.next_sabotage -= time.delta_secs();
+        if spell.next_sabotage > 0.0 {
+            continue;
+        }
+        spell.next_sabotage = roll_interval(spell.elapsed, &mut rng);
+        hostility.due.push(faction);
+    }
+}
+
+/// Carries out the due sabotage, picked at random from what there's something to hit with
+fn sabotage_player(
+    mut commands: Commands,
+    mut hostility: ResMut<Hostility>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+    wires: Query<(Entity, &GridPosition, Option<&Damaged>), (With<PhysicalLink>, With<PlacedBuilding>, Without<Tile>, Without<Undeletable>)>,
+    territory: Query<(&Faction, &GridPosition), Or<(With<SinkBuilding>, With<SourceBuilding>)>>,
+    contracts: Query<(Entity, &ContractStatus, &Faction, &AssociatedWithSink), Without<ThresholdRaised>>,
+    sink_positions: Query<&GridPosition, With<SinkBuilding>>,
+    library: Res<InteractiveEventLibrary>,
+    mut raises: MessageWriter<RaiseContractThreshold>,
+    mut show_event: MessageWriter<ShowInteractiveEvent>,
+    mut news_writer: MessageWriter<AddNewsfeedItemEvent>,
+    mut shake: MessageWriter<crate::camera::ShakeScreen>,
+    mut trace: ResMut<TraceLog>,
+) {
+    for faction in std::mem::take(&mut hostility.due) {
+        let rivals: Vec<_> = contracts
+            .iter()
+            .filter(|(_, status, contract_faction, _)| **status == ContractStatus::Active && **contract_faction != faction)
+            .collect();
+        let shakedown = library.get_event_by_id(shakedown_event_id(faction));
+
+        let mut options = Vec::new();
+        if !wires.is_empty() {
+            options.push(Sabotage::CutWire);
+        }
+        if !rivals.is_empty() {
+            options.push(Sabotage::RaiseThreshold);
+        }
+        if shakedown.is_some() {
+            options.push(Sabotage::Shakedown);
+        }
+        let Some(&sabotage) = options.choose(&mut rng) else { continue };
+
+        match sabotage {
+            Sabotage::CutWire => {
+                let home: Vec<_> = territory.iter().filter(|(f, _)| **f == faction).map(|(_, p)| p.0).collect();
+                let mut candidates: Vec<_> = wires
+                    .iter()
+                    .map(|(wire, position, damage)| {
+                        let distance = home.iter().map(|h| (*h - position.0).abs().element_sum()).min().unwrap_or(0);
+                        (distance, *position, wire, damage)
+                    })
+                    .collect();
+                candidates.sort_by_key(|(distance, position, ..)| (*distance, position.x, position.y));
+                candidates.truncate(WIRE_CANDIDATES);
+                let Some((_, position, wire, damage)) = candidates.choose(&mut rng) else { continue };
+                damage_segment(&mut commands, *wire, WIRE_CUT_SEVERITY, *damage);
+                shake.write(crate::camera::ShakeScreen(WIRE_CUT_SHAKE));
+                warn!("{:?} sabotage: wire cut at {:?}", faction, position);
+                trace_sim!(trace, Raid, [*wire], "{:?} cut wire {:?} at {:?}", faction, wire, position.0);
+                news_writer.write(AddNewsfeedItemEvent {
+                    faction,
+                    headline: format!("Saboteurs cut one of your lines, {:?} denies everything", faction),
+                    payload: Some(NewsPayload::Position(*position)),
+                });
+            }
+            Sabotage::RaiseThreshold => {
+                let Some(&(contract, _, rival, sink)) = rivals.choose(&mut rng) else { continue };
+                raises.write(RaiseContractThreshold {
+                    contract,
+                    factor: RAISED_THRESHOLD_FACTOR,
+                    seconds: RAISED_THRESHOLD_SECONDS,
+                });
+                warn!("{:?} sabotage: {:?} contract {:?} threshold raised", faction, rival, contract);
+                trace_sim!(trace, Raid, [contract, sink.0], "{:?} raised the threshold on {:?} contract {:?}", faction, rival, contract);
+                news_writer.write(AddNewsfeedItemEvent {
+                    faction,
+                    headline: format!("{:?} whispers to {:?} buyers, they want more data for the same money", faction, rival),
+                    payload: Some(match sink_positions.get(sink.0) {
+                        Ok(position) => NewsPayload::Position(*position),
+                        Err(_) => NewsPayload::Faction(*rival),
+                    }),
+                });
+            }
+            Sabotage::Shakedown => {
+                let Some(event) = shakedown else { continue };
+                warn!("{:?} sabotage: shakedown", faction);
+                trace_sim!(trace, Raid, [], "{:?} shakedown, event {}", faction, event.id);
+                let mut data: crate::events::InteractiveEventData = event.into();
+                // demands don't wait in a bubble
+                data.popup_urgency = true;
+                show_event.write(ShowInteractiveEvent(data));
+            }
+        }
+    }
+}
+
+pub struct HostilityPlugin;
+
+impl Plugin for HostilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hostility>()
+            .add_systems(Update, (track_hostility, sabotage_player).chain().run_if(in_state(GameState::Running)));
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::ecs::lifecycle::HookContext;
+use bevy::ecs::world::DeferredWorld;
+use bevy::prelude::*;
+
+pub mod achievements;
+pub mod assets;
+pub mod audio;
+pub mod changelog;
+pub mod camera;
+pub mod contracts;
+pub mod controls;
+pub mod crash;
+pub mod dev;
+pub mod difficulty;
+pub mod economics;
+pub mod emissary;
+pub mod game_mode;
+pub mod game_speed;
+pub mod events;
+pub mod factions;
+pub mod factory;
+pub mod gamepad;
+pub mod grid;
+pub mod headless;
+pub mod hostility;
+pub mod lod;
+pub mod market;
+pub mod palette;
+pub mod photo_mode;
+pub mod player;
+pub mod profile;
+pub mod test;
+pub mod trace;
+pub mod tutorial;
+pub mod user_config;
+pub mod ui;
+pub mod world_gen;
+pub mod pause;
+pub mod save;
+pub mod settings;
+
+#[derive(Component, Deref)]
+#[component(on_remove = cleanup_linked_spawn)]
+pub struct LinkedSpawn(Vec<Entity>);
+
+fn cleanup_linked_spawn(mut world: DeferredWorld, context: HookContext) {
+    let entity = context.entity;
+
+    // Get the LinkedSpawn component data before it's removed
+    if let Some(linked_spawn) = world.get::<LinkedSpawn>(entity) {
+        // Clone the entity list before we drop the borrow
+        let entities_to_despawn = linked_spawn.0.clone();
+
+        // Despawn all linked entities using commands
+        for &linked_entity in &entities_to_despawn {
+            world.commands().entity(linked_entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use bevy::math::I64Vec2;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+
+use crate::assets::GameAssets;
+use crate::factions::{Faction, FactionReputations, Locked, ReputationLevel};
+use crate::factory::building_visuals::z_layers;
+use crate::factory::physical::PhysicalLink;
+use crate::factory::source_visuals::{
+    AugmentationEffect, AugmentedIndicator, DataTypeIcon, FloatingAnimation, GlowSprite, ScanningFlashEffect,
+    SourceBackground, SourceLodDot,
+};
+use crate::grid::{Grid, GridAtlasSprite, GridPosition};
+use crate::ui::interactive_event::ScalableText;
+use crate::world_gen::{ClusterIconMarker, LockMarker};
+
+/// Camera scale past which source icons and their effects are hidden
+pub const REDUCED_DETAIL_SCALE: f32 = 3.0;
+/// Camera scale past which buildings become flat quads and lock overlays merge per cluster
+pub const MINIMAL_DETAIL_SCALE: f32 = 6.0;
+/// Zooming back in has to go this much past a threshold before detail returns, so
+/// sitting right on the line doesn't flicker
+const LOD_HYSTERESIS: f32 = 0.9;
+
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LodLevel {
+    #[default]
+    Full,
+    Reduced,
+    Minimal,
+}
+
+impl LodLevel {
+    pub fn for_scale(scale: f32, current: LodLevel) -> LodLevel {
+        let threshold = |level: LodLevel, base: f32| {
+            if current >= level { base * LOD_HYSTERESIS } else { base }
+        };
+        if scale >= threshold(LodLevel::Minimal, MINIMAL_DETAIL_SCALE) {
+            LodLevel::Minimal
+        } else if scale >= threshold(LodLevel::Reduced, REDUCED_DETAIL_SCALE) {
+            LodLevel::Reduced
+        } else {
+            LodLevel::Full
+        }
+    }
+
+    pub fn shows_decorations(&self) -> bool {
+        *self == LodLevel::Full
+    }
+}
+
+/// Run condition for the icon animations, no point moving what nobody can see
+pub fn decorations_visible(lod: Res<LodLevel>) -> bool {
+    lod.shows_decorations()
+}
+
+/// Cell bounds of each locked cluster, worked out at world gen so the merged overlay
+/// doesn't have to be
+#[derive(Resource, Default, Debug)]
+pub struct ClusterBounds(pub HashMap<i64, ClusterBound>);
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClusterBound {
+    pub min: I64Vec2,
+    pub max: I64Vec2,
+    pub faction: Faction,
+    pub reputation: ReputationLevel,
+}
+
+impl ClusterBound {
+    pub fn include(&mut self, cell: I64Vec2) {
+        self.min = self.min.min(cell);
+        self.max = self.max.max(cell);
+    }
+}
+
+/// One lock overlay covering a whole cluster, shown instead of the per-cell ones at minimal detail
+#[derive(Component)]
+pub struct ClusterLockQuad;
+
+/// On a building drawn as a flat quad, pointing at the quad. The building is hidden rather than
+/// having its sprite swapped out, so wire pieces and tints changed while zoomed out stay current
+#[derive(Component)]
+pub struct LodFlattened(pub Entity);
+
+/// Child quad standing in for its hidden building at minimal detail
+#[derive(Component)]
+pub struct LodFlatQuad;
+
+/// Dev overlay line with what each LOD layer is currently showing
+#[derive(Component)]
+pub struct LodDebugText;
+
+/// Entities left visible at each level, as last measured while the camera was at it, so
+/// the overlay can compare levels without zooming back and forth
+#[derive(Resource, Default, Debug)]
+pub struct LodVisibilityCounts(pub HashMap<LodLevel, usize>);
+
+type DecorationFilter = (
+    Or<(
+        With<DataTypeIcon>,
+        With<AugmentationEffect>,
+        With<AugmentedIndicator>,
+        With<GlowSprite>,
+        With<ScanningFlashEffect>,
+        With<FloatingAnimation>,
+    )>,
+    Without<Node>,
+);
+
+type LodBuildingFilter = (Or<(With<GridAtlasSprite>, With<SourceBackground>)>, Without<LodFlattened>);
+
+type CellLockFilter = (With<LockMarker>, Without<ClusterIconMarker>, Without<ClusterLockQuad>);
+
+fn update_lod_level(
+    camera: Query<&Projection, (With<Camera>, Changed<Projection>)>,
+    mut lod: ResMut<LodLevel>,
+) {
+    let Some(Projection::Orthographic(orthographic)) = camera.iter().next() else {
+        return;
+    };
+    let level = LodLevel::for_scale(orthographic.scale, *lod);
+    if lod.set_if_neq(level) {
+        info!("LOD level now {:?} (scale {:.2})", level, orthographic.scale);
+    }
+}
+
+fn dot_visibility(lod: &LodLevel) -> Visibility {
+    if lod.shows_decorations() { Visibility::Hidden } else { Visibility::Inherited }
+}
+
+/// Hides/restores source icons, augmentation indicators and the animated effects, with
+/// the flat source dots going the other way
+fn apply_decoration_lod(
+    lod: Res<LodLevel>,
+    mut decorations: Query<&mut Visibility, (DecorationFilter, Without<SourceLodDot>)>,
+    mut dots: Query<&mut Visibility, With<SourceLodDot>>,
+) {
+    let visibility = if lod.shows_decorations() { Visibility::Inherited } else { Visibility::Hidden };
+    for mut current in decorations.iter_mut() {
+        current.set_if_neq(visibility);
+    }
+    for mut current in dots.iter_mut() {
+        current.set_if_neq(dot_visibility(&lod));
+    }
+}
+
+fn lod_quad_color(faction: Option<&Faction>, is_link: bool, game_assets: &GameAssets) -> Color {
+    match faction {
+        Some(faction) => game_assets.faction_color(*faction),
+        None if is_link => Color::srgb(0.45, 0.45, 0.5),
+        None => Color::srgb(0.35, 0.55, 0.85),
+    }
+}
+
+/// Hides the building and shows a flat quad of the same size over it. The quad is `Visible`
+/// rather than `Inherited` so it still draws under its hidden parent.
+fn flatten(commands: &mut Commands, entity: Entity, sprite: &Sprite, color: Color) {
+    let quad = commands
+        .spawn((
+            Sprite {
+                color,
+                custom_size: sprite.custom_size,
+                ..default()
+            },
+            Transform::default(),
+            Visibility::Visible,
+            LodFlatQuad,
+        ))
+        .id();
+    commands
+        .entity(entity)
+        .insert((Visibility::Hidden, LodFlattened(quad)))
+        .add_child(quad);
+}
+
+/// Draws buildings as flat quads at minimal detail and puts them back again
+fn apply_building_lod(
+    mut commands: Commands,
+    lod: Res<LodLevel>,
+    game_assets: Res<GameAssets>,
+    detailed: Query<(Entity, &Sprite, Option<&Faction>, Has<PhysicalLink>), LodBuildingFilter>,
+    flattened: Query<(Entity, &LodFlattened)>,
+) {
+    if *lod == LodLevel::Minimal {
+        for (entity, sprite, faction, is_link) in detailed.iter() {
+            flatten(&mut commands, entity, sprite, lod_quad_color(faction, is_link, &game_assets));
+        }
+    } else {
+        for (entity, flattened) in flattened.iter() {
+            commands.entity(flattened.0).try_despawn();
+            commands.entity(entity).insert(Visibility::Inherited).remove::<LodFlattened>();
+        }
+    }
+}
+
+/// Things spawned while zoomed out start at the current level of detail
+fn apply_lod_to_new_entities(
+    mut commands: Commands,
+    lod: Res<LodLevel>,
+    game_assets: Res<GameAssets>,
+    mut new_decorations: Query<&mut Visibility, (DecorationFilter, Without<SourceLodDot>, Added<Visibility>)>,
+    mut new_dots: Query<&mut Visibility, (With<SourceLodDot>, Added<Visibility>)>,
+    added: Query<(Entity, &Sprite, Option<&Faction>, Has<PhysicalLink>), (LodBuildingFilter, Added<Sprite>)>,
+) {
+    if !lod.shows_decorations() {
+        for mut visibility in new_decorations.iter_mut() {
+            *visibility = Visibility::Hidden;
+        }
+        for mut visibility in new_dots.iter_mut() {
+            *visibility = Visibility::Inherited;
+        }
+    }
+    if *lod != LodLevel::Minimal {
+        return;
+    }
+    for (entity, sprite, faction, is_link) in added.iter() {
+        flatten(&mut commands, entity, sprite, lod_quad_color(faction, is_link, &game_assets));
+    }
+}
+
+/// Per-cell lock overlays at normal detail, one quad per cluster at minimal
+fn apply_lock_overlay_lod(
+    mut commands: Commands,
+    lod: Res<LodLevel>,
+    bounds: Res<ClusterBounds>,
+    grid: Res<Grid>,
+    game_assets: Res<GameAssets>,
+    mut cells: Query<&mut Visibility, (CellLockFilter, With<Locked>)>,
+    reputations: Res<FactionReputations>,
+    mut quads: Query<&mut Visibility, With<ClusterLockQuad>>,
+) {
+    let minimal = *lod == LodLevel::Minimal;
+    for mut visibility in cells.iter_mut() {
+        visibility.set_if_neq(if minimal { Visibility::Hidden } else { Visibility::Inherited });
+    }
+
+    // the quads are spawned the first time they're needed and just toggled after that
+    // (again after a load, which clears them). they carry Locked like the cells so
+    // unlocking a cluster cleans them up the same way
+    if minimal && quads.is_empty() {
+        for bound in bounds.0.values() {
+            if bound.reputation <= reputations.get_level(bound.faction) {
+                continue;
+            }
+            let min = grid.grid_to_world_corner(&GridPosition(bound.min));
+            let max = grid.grid_to_world_corner(&GridPosition(bound.max + I64Vec2::ONE));
+            let mut color = game_assets.faction_color(bound.faction);
+            color.set_alpha(0.5);
+            commands.spawn((
+                Sprite {
+                    color,
+                    custom_size: Some(max - min),
+                    ..default()
+                },
+                Transform::from_translation(((min + max) / 2.0).extend(z_layers::LOCK_OVERLAY)),
+                Visibility::Visible,
+                bound.faction,
+                bound.reputation,
+                Locked,
+                LockMarker,
+                ClusterLockQuad,
+            ));
+        }
+    }
+    for mut visibility in quads.iter_mut() {
+        visibility.set_if_neq(if minimal { Visibility::Visible } else { Visibility::Hidden });
+    }
+}
+
+fn spawn_lod_debug_text(mut commands: Commands, dev_mode: Res<crate::dev::DevMode>, game_assets: Res<GameAssets>) {
+    if !dev_mode.0 {
+        return;
+    }
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(50.0),
+            ..default()
+        },
+        Text::new(""),
+        game_assets.text_font(12.0),
+        ScalableText::from_vw(0.7),
+        TextColor(Color::srgb(0.7, 0.9, 0.7)),
+        LodDebugText,
+    ));
+}
+
+fn count_visible<'a>(visibilities: impl Iterator<Item = &'a Visibility>) -> (usize, usize) {
+    visibilities.fold((0, 0), |(shown, total), v| {
+        (shown + usize::from(*v != Visibility::Hidden), total + 1)
+    })
+}
+
+fn update_lod_debug_text(
+    lod: Res<LodLevel>,
+    mut counts: ResMut<LodVisibilityCounts>,
+    decorations: Query<&Visibility, DecorationFilter>,
+    dots: Query<&Visibility, With<SourceLodDot>>,
+    detailed: Query<(), LodBuildingFilter>,
+    flattened: Query<(), With<LodFlattened>>,
+    cells: Query<&Visibility, CellLockFilter>,
+    quads: Query<&Visibility, With<ClusterLockQuad>>,
+    mut text_query: Query<&mut Text, With<LodDebugText>>,
+) {
+    let (decorations_shown, decorations_total) = count_visible(decorations.iter());
+    let (dots_shown, _) = count_visible(dots.iter());
+    let (cells_shown, cells_total) = count_visible(cells.iter());
+    let (quads_shown, quads_total) = count_visible(quads.iter());
+    let (sprites, flat) = (detailed.iter().count(), flattened.iter().count());
+    counts.0.insert(*lod, decorations_shown + dots_shown + sprites + flat + cells_shown + quads_shown);
+
+    let per_level = [LodLevel::Full, LodLevel::Reduced, LodLevel::Minimal]
+        .iter()
+        .map(|level| match counts.0.get(level) {
+            Some(count) => format!("{:?} {}", level, count),
+            None => format!("{:?} -", level),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let label = format!(
+        "LOD {:?} | decorations {}/{} | sprites {} quads {} | lock cells {}/{} cluster quads {}/{}\nvisible per level: {}",
+        *lod,
+        decorations_shown,
+        decorations_total,
+        sprites,
+        flat,
+        cells_shown,
+        cells_total,
+        quads_shown,
+        quads_total,
+        per_level
+    );
+    for mut text in text_query.iter_mut() {
+        if **text != label {
+            **text = label.clone();
+        }
+    }
+}
+
+pub struct LodPlugin;
+
+impl Plugin for LodPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LodLevel>()
+            .init_resource::<ClusterBounds>()
+            .init_resource::<LodVisibilityCounts>()
+            .add_systems(OnExit(crate::pause::GameState::Setup), spawn_lod_debug_text.after(crate::dev::follow_game_mode))
+            .add_systems(Update, (
+                update_lod_level,
+                (apply_decoration_lod, apply_building_lod, apply_lock_overlay_lod)
+                    .run_if(resource_changed::<LodLevel>),
+                apply_lod_to_new_entities,
+            ).chain())
+            .add_systems(Update, update_lod_debug_text
+                .run_if(|dev_mode: Res<crate::dev::DevMode>| dev_mode.0)
+                .run_if(on_timer(std::time::Duration::from_millis(500))));
+    }
+}
@@ -3,55 +3,91 @@ extern crate core;
 use bevy_prng::WyRand;
 use bevy_rand::prelude::*;
 
-use crate::player::PlayerPlugin;
-use crate::ui::interaction::CustomInteractionPlugin;
-use crate::ui::tooltip::inherit_translation;
-use crate::world_gen::WorldGenPlugin;
-use crate::{
+use ld58::player::PlayerPlugin;
+use ld58::ui::interaction::CustomInteractionPlugin;
+use ld58::ui::tooltip::inherit_translation;
+use ld58::world_gen::{WorldGenPlugin, WorldSeed};
+use ld58::{
     assets::AssetPlugin,
+    audio::MusicPlugin,
     camera::GameCameraPlugin,
+    controls::ControlsPlugin,
+    gamepad::GamepadPlugin,
     contracts::ContractsPlugin,
     events::EventsPlugin,
     factions::FactionsPlugin,
-    factory::FactoryPlugin,
-    grid::{GridPlugin, GridPosition},
+    factory::{FactoryInteractionPlugin, FactoryPlugin},
+    grid::{GridPlugin, GridVisualsPlugin},
     ui::UIPlugin,
     pause::PausePlugin,
+    settings::SettingsPlugin,
+    dev::DevPlugin,
+    difficulty::DifficultyPlugin,
+    game_mode::GameModePlugin,
+    game_speed::GameSpeedPlugin,
+    crash::CrashPlugin,
+    user_config::UserConfigPlugin,
+    achievements::AchievementsPlugin,
+    profile::ProfilePlugin,
+    palette::PalettePlugin,
+    photo_mode::PhotoModePlugin,
+    trace::TracePlugin,
+    market::MarketPlugin,
+    economics::EconomicsPlugin,
+    lod::LodPlugin,
+    changelog::ChangelogPlugin,
+    emissary::EmissaryPlugin,
+    hostility::HostilityPlugin,
+    save::SaveGamePlugin,
+    tutorial::TutorialPlugin,
 };
-use bevy::ecs::lifecycle::HookContext;
-use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 
-mod assets;
-mod camera;
-mod contracts;
-mod events;
-mod factions;
-mod factory;
-mod grid;
-mod player;
-mod test;
-mod ui;
-mod world_gen;
-mod pause;
-
 fn main() {    
+    // one seed for the global rng and the world gen noise, so a run can be reproduced
+    let seed = WorldSeed::from_env();
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
+        .insert_resource(seed)
         .add_plugins(AssetPlugin)
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
-        .add_plugins(EntropyPlugin::<WyRand>::default())
+        .add_plugins(EntropyPlugin::<WyRand>::with_seed(seed.0.to_le_bytes()))
         .add_plugins(PausePlugin)
+        .add_plugins(GameSpeedPlugin)
+        .add_plugins(SettingsPlugin)
+        .add_plugins(PalettePlugin)
+        .add_plugins(ControlsPlugin)
+        .add_plugins(GamepadPlugin)
+        .add_plugins(DevPlugin)
+        .add_plugins(GameModePlugin)
+        .add_plugins(DifficultyPlugin)
+        .add_plugins(CrashPlugin)
+        .add_plugins(UserConfigPlugin)
+        .add_plugins(AchievementsPlugin)
+        .add_plugins(ProfilePlugin)
+        .add_plugins(ChangelogPlugin)
+        .add_plugins(TracePlugin)
+        .add_plugins(MusicPlugin)
         .add_plugins(EventsPlugin)
         .add_plugins(ContractsPlugin)
+        .add_plugins(EconomicsPlugin)
+        .add_plugins(EmissaryPlugin)
+        .add_plugins(HostilityPlugin)
+        .add_plugins(SaveGamePlugin)
+        .add_plugins(MarketPlugin)
         .add_plugins(GameCameraPlugin)
+        .add_plugins(PhotoModePlugin)
+        .add_plugins(LodPlugin)
         .add_plugins(WorldGenPlugin)
         .add_plugins(UIPlugin)
         .add_plugins(GridPlugin)
+        .add_plugins(GridVisualsPlugin)
         .add_plugins(FactoryPlugin)
+        .add_plugins(FactoryInteractionPlugin)
         .add_plugins(FactionsPlugin)
         .add_plugins(PlayerPlugin)
         .add_plugins(CustomInteractionPlugin)
+        .add_plugins(TutorialPlugin)
         .add_systems(Startup, startup)
         .add_systems(PostUpdate, inherit_translation)
         .run();
@@ -63,23 +99,6 @@ fn startup(_commands: Commands) {
     //test::spawn_combiner_test(&mut commands);
     //test::spawn_trunking_test(&mut commands);
     //test::spawn_sized_sink_test(&mut commands);
-}
-
-#[derive(Component, Deref)]
-#[component(on_remove = cleanup_linked_spawn)]
-pub struct LinkedSpawn(Vec<Entity>);
-
-fn cleanup_linked_spawn(mut world: DeferredWorld, context: HookContext) {
-    let entity = context.entity;
-
-    // Get the LinkedSpawn component data before it's removed
-    if let Some(linked_spawn) = world.get::<LinkedSpawn>(entity) {
-        // Clone the entity list before we drop the borrow
-        let entities_to_despawn = linked_spawn.0.clone();
-
-        // Despawn all linked entities using commands
-        for &linked_entity in &entities_to_despawn {
-            world.commands().entity(linked_entity).despawn();
-        }
-    }
+    //test::spawn_deidentifier_test(&mut commands);
+    //test::spawn_cleaner_test(&mut commands);
 }
@@ -0,0 +1,156 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_prng::WyRand;
+use bevy_rand::prelude::GlobalRng;
+use rand::Rng;
+
+use crate::events::AddNewsfeedItemEvent;
+use crate::factions::Faction;
+use crate::factory::logical::{BasicDataType, Dataset};
+
+pub const MIN_MARKET_MULTIPLIER: f32 = 0.6;
+pub const MAX_MARKET_MULTIPLIER: f32 = 1.6;
+/// How often the market takes a random walk step
+pub const MARKET_STEP_SECONDS: f32 = 120.0;
+/// Largest normal move per step, as a fraction of the current price
+const MARKET_STEP_SIZE: f32 = 0.1;
+/// Chance per data type per step of a bigger jolt
+const MARKET_JOLT_CHANCE: f64 = 0.05;
+const MARKET_JOLT_SIZE: f32 = 0.3;
+/// Relative moves bigger than this make the news
+const SIGNIFICANT_SWING: f32 = 0.15;
+
+const ALL_DATA_TYPES: [BasicDataType; 4] = [
+    BasicDataType::Biometric,
+    BasicDataType::Economic,
+    BasicDataType::Behavioural,
+    BasicDataType::Telemetry,
+];
+
+/// Global price multiplier per data type, applied to contract income at payout
+#[derive(Resource, Debug, Clone)]
+pub struct MarketPrices {
+    pub multipliers: HashMap<BasicDataType, f32>,
+    /// Multipliers before the last change, for the up/down arrows
+    pub previous: HashMap<BasicDataType, f32>,
+}
+
+impl Default for MarketPrices {
+    fn default() -> Self {
+        let multipliers: HashMap<_, _> = ALL_DATA_TYPES.iter().map(|t| (*t, 1.0)).collect();
+        Self {
+            previous: multipliers.clone(),
+            multipliers,
+        }
+    }
+}
+
+impl MarketPrices {
+    pub fn get(&self, data_type: BasicDataType) -> f32 {
+        *self.multipliers.get(&data_type).unwrap_or(&1.0)
+    }
+
+    pub fn previous(&self, data_type: BasicDataType) -> f32 {
+        *self.previous.get(&data_type).unwrap_or(&1.0)
+    }
+
+    /// Set a multiplier (clamped), keeping the old one around for trend display
+    pub fn set(&mut self, data_type: BasicDataType, value: f32) {
+        let old = self.get(data_type);
+        self.previous.insert(data_type, old);
+        self.multipliers
+            .insert(data_type, value.clamp(MIN_MARKET_MULTIPLIER, MAX_MARKET_MULTIPLIER));
+    }
+
+    /// Apply an additive shock from an interactive event
+    pub fn shock(&mut self, data_type: BasicDataType, delta: f32) {
+        self.set(data_type, self.get(data_type) + delta);
+    }
+
+    /// Average multiplier of the data types in a dataset, each type weighing the same
+    pub fn dataset_multiplier(&self, dataset: &Dataset) -> f32 {
+        if dataset.contents.is_empty() {
+            return 1.0;
+        }
+        let total: f32 = dataset.contents.keys().map(|t| self.get(*t)).sum();
+        total / dataset.contents.len() as f32
+    }
+
+    /// What a contract pays this tick at current prices
+    pub fn payout(&self, income: f64, dataset: &Dataset) -> f64 {
+        income * self.dataset_multiplier(dataset) as f64
+    }
+
+    /// One random walk step over all data types. Returns the types that swung significantly.
+    pub fn step(&mut self, rng: &mut impl Rng) -> Vec<(BasicDataType, f32)> {
+        let mut swings = Vec::new();
+        for data_type in ALL_DATA_TYPES {
+            let old = self.get(data_type);
+            let size = if rng.random_bool(MARKET_JOLT_CHANCE) {
+                MARKET_JOLT_SIZE
+            } else {
+                MARKET_STEP_SIZE
+            };
+            self.set(data_type, old * (1.0 + rng.random_range(-size..=size)));
+
+            let change = (self.get(data_type) - old) / old;
+            if change.abs() > SIGNIFICANT_SWING {
+                swings.push((data_type, change));
+            }
+        }
+        swings
+    }
+}
+
+#[derive(Resource)]
+pub struct MarketTimer(pub Timer);
+
+impl Default for MarketTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(MARKET_STEP_SECONDS, TimerMode::Repeating))
+    }
+}
+
+/// Ticks only while the game is running, so the market freezes when paused
+fn market_random_walk_system(
+    time: Res<Time>,
+    mut timer: ResMut<MarketTimer>,
+    mut prices: ResMut<MarketPrices>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+    mut news_writer: MessageWriter<AddNewsfeedItemEvent>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    for (data_type, change) in prices.step(&mut **rng) {
+        let direction = if change > 0.0 { "soar" } else { "crash" };
+        news_writer.write(AddNewsfeedItemEvent {
+            faction: Faction::Corporate,
+            headline: format!(
+                "{:?} data prices {} {:.0}% as brokers scramble",
+                data_type,
+                direction,
+                change.abs() * 100.0
+            ),
+            payload: None,
+        });
+    }
+    info!("Market prices updated: {:?}", prices.multipliers);
+}
+
+pub struct MarketPlugin;
+
+impl Plugin for MarketPlugin {
+    fn build(&self, app: &mut App) {
+        use crate::pause::GameState;
+
+        app.init_resource::<MarketPrices>()
+            .init_resource::<MarketTimer>()
+            .add_systems(
+                Update,
+                market_random_walk_system.run_if(in_state(GameState::Running)),
+            );
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::assets::GameAssets;
+use crate::factions::Faction;
+use crate::settings::Settings;
+
+/// Named colour roles for everything that tells the player how things are going. Swapped
+/// wholesale by the colorblind option, so nothing should pick these colours inline
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Palette {
+    /// Meeting a contract, a free port, a wire with room to spare
+    pub success: Color,
+    /// Exceeding a contract
+    pub exceeding: Color,
+    /// Offers waiting on the player, a busy wire or port
+    pub warning: Color,
+    /// Failing, blocked, about to expire
+    pub danger: Color,
+    /// Not connected, no data
+    pub inactive: Color,
+    /// Darker card backgrounds for the same roles
+    pub success_card: Color,
+    pub exceeding_card: Color,
+    pub warning_card: Color,
+    pub danger_card: Color,
+    factions: [(Faction, Color); 4],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Palette {
+    pub fn standard() -> Self {
+        Self {
+            success: Color::srgb(0.3, 0.9, 0.3),
+            exceeding: Color::srgb(0.45, 0.65, 1.0),
+            warning: Color::srgb(0.95, 0.85, 0.25),
+            danger: Color::srgb(1.0, 0.3, 0.3),
+            inactive: Color::srgb(0.5, 0.5, 0.5),
+            success_card: Color::srgb(0.18, 0.45, 0.18),
+            exceeding_card: Color::srgb(0.18, 0.32, 0.60),
+            warning_card: Color::srgb(0.25, 0.22, 0.10),
+            danger_card: Color::srgb(0.45, 0.18, 0.18),
+            factions: [
+                (Faction::Academia, Color::srgb(0.2, 0.8, 1.0)),   // Cyan
+                (Faction::Corporate, Color::srgb(0.9, 0.9, 0.3)),  // Yellow
+                (Faction::Government, Color::srgb(0.3, 1.0, 0.3)), // Green
+                (Faction::Criminal, Color::srgb(1.0, 0.3, 0.3)),   // Red
+            ],
+        }
+    }
+
+    /// Okabe-Ito hues, no red against green anywhere
+    pub fn colorblind() -> Self {
+        Self {
+            success: Color::srgb(0.0, 0.62, 0.45),
+            exceeding: Color::srgb(0.34, 0.71, 0.91),
+            warning: Color::srgb(0.9, 0.62, 0.0),
+            danger: Color::srgb(0.84, 0.37, 0.0),
+            inactive: Color::srgb(0.5, 0.5, 0.5),
+            success_card: Color::srgb(0.0, 0.3, 0.22),
+            exceeding_card: Color::srgb(0.12, 0.3, 0.42),
+            warning_card: Color::srgb(0.3, 0.22, 0.02),
+            danger_card: Color::srgb(0.4, 0.17, 0.0),
+            factions: [
+                (Faction::Academia, Color::srgb(0.34, 0.71, 0.91)),  // Sky blue
+                (Faction::Corporate, Color::srgb(0.94, 0.89, 0.26)), // Yellow
+                (Faction::Government, Color::srgb(0.0, 0.45, 0.7)),  // Blue
+                (Faction::Criminal, Color::srgb(0.8, 0.47, 0.65)),   // Reddish purple
+            ],
+        }
+    }
+
+    pub fn faction(&self, faction: Faction) -> Color {
+        self.factions
+            .iter()
+            .find(|(f, _)| *f == faction)
+            .map_or(Color::WHITE, |(_, color)| *color)
+    }
+
+    pub fn factions(&self) -> impl Iterator<Item = (Faction, Color)> + '_ {
+        self.factions.iter().copied()
+    }
+}
+
+/// Swaps the palette when the setting flips. Faction colours are still read through
+/// `GameAssets::faction_color`, so they're copied over there too
+fn sync_palette(settings: Res<Settings>, mut palette: ResMut<Palette>, mut game_assets: ResMut<GameAssets>) {
+    let wanted = if settings.colorblind_palette { Palette::colorblind() } else { Palette::standard() };
+    if *palette == wanted {
+        return;
+    }
+    info!("Switching to the {} palette", if settings.colorblind_palette { "colorblind" } else { "standard" });
+    for (faction, color) in wanted.factions() {
+        game_assets.faction_colors.insert(faction, color);
+    }
+    *palette = wanted;
+}
+
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Palette>()
+            .add_systems(Update, sync_palette.run_if(resource_changed::<Settings>));
+    }
+}
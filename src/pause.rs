@@ -3,8 +3,10 @@ use bevy::prelude::*;
 /// Game state for pause management
 #[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum GameState {
-    /// Game is running normally - all systems active
+    /// Difficulty screen before world gen, nothing's been spawned yet
     #[default]
+    Setup,
+    /// Game is running normally - all systems active
     Running,
     /// Paused by event modal - only modal interaction allowed
     /// Time stops, no building placement, no other UI interaction
@@ -23,11 +25,13 @@ impl GameState {
 
 /// System to handle manual pause toggling
 pub fn handle_pause_input(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    controls: crate::controls::Controls,
+    gamepads: Query<&Gamepad>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if keyboard.just_pressed(KeyCode::Space) {
+    let start = gamepads.iter().any(|g| g.just_pressed(GamepadButton::Start));
+    if controls.just_pressed(crate::controls::InputAction::Pause) || start {
         match current_state.get() {
             GameState::Running => {
                 next_state.set(GameState::ManualPause);
@@ -37,8 +41,8 @@ pub fn handle_pause_input(
                 next_state.set(GameState::Running);
                 info!("Game resumed");
             }
-            GameState::EventModal => {
-                // Can't unpause modal with spacebar
+            GameState::EventModal | GameState::Setup => {
+                // Can't unpause modal with spacebar, and there's nothing to pause before the run starts
             }
         }
     }
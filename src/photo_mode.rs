@@ -0,0 +1,259 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{save_to_disk, Screenshot, ScreenshotCaptured};
+use bevy::ui::UiSystems;
+
+use crate::assets::GameAssets;
+use crate::controls::{Controls, InputAction};
+use crate::pause::GameState;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::shop::{SelectedBuilding, SelectedBuildingType};
+use crate::ui::BlocksWorldClicks;
+use crate::world_gen::LockMarker;
+
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+/// UI-free view for screenshots, F12 or the pause menu. The camera goes unclamped while it's on
+#[derive(Resource, Default)]
+pub struct PhotoMode {
+    pub active: bool,
+    pub hide_locks: bool,
+    pub hide_labels: bool,
+    /// Hint's hidden, the screenshot goes out next frame
+    capture_queued: bool,
+    /// A screenshot's been asked for and hasn't come back yet, the hint stays hidden
+    capturing: bool,
+}
+
+/// Full screen, so clicks don't reach the world, and never hidden by photo mode itself
+#[derive(Component)]
+pub struct PhotoModeOverlay;
+
+/// The controls line, the only thing that's left out of a capture
+#[derive(Component)]
+pub struct PhotoModeHint;
+
+/// What `Node::display` was before photo mode hid it
+#[derive(Component)]
+pub struct PhotoModeHiddenNode(Display);
+
+/// Same for lock markers and world text
+#[derive(Component)]
+pub struct PhotoModeHiddenSprite(Visibility);
+
+fn spawn_photo_overlay(commands: &mut Commands, game_assets: &GameAssets) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::FlexEnd,
+                padding: UiRect::bottom(Val::Vh(3.0)),
+                ..default()
+            },
+            GlobalZIndex(950),
+            Interaction::None,
+            BlocksWorldClicks,
+            PhotoModeOverlay,
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    padding: UiRect::all(Val::Vw(0.6)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                PhotoModeHint,
+            ))
+            .with_children(|hint| {
+                hint.spawn((
+                    Text::new("Photo mode - Enter: capture   L: lock markers   H: labels   WASD/middle drag/scroll: move   Esc/F12: exit"),
+                    game_assets.text_font(16.0),
+                    ScalableText::from_vw(0.9),
+                    TextColor(Color::srgb(0.85, 0.85, 0.85)),
+                ));
+            });
+        });
+}
+
+pub fn enter_photo_mode(
+    commands: &mut Commands,
+    photo_mode: &mut PhotoMode,
+    game_assets: &GameAssets,
+) {
+    if photo_mode.active {
+        return;
+    }
+    photo_mode.active = true;
+    spawn_photo_overlay(commands, game_assets);
+    info!("Photo mode on");
+}
+
+/// Puts every hidden node and sprite back the way photo mode found it
+pub fn exit_photo_mode(
+    commands: &mut Commands,
+    photo_mode: &mut PhotoMode,
+    overlays: &Query<Entity, With<PhotoModeOverlay>>,
+) {
+    if !photo_mode.active {
+        return;
+    }
+    *photo_mode = PhotoMode::default();
+    for overlay in overlays.iter() {
+        commands.entity(overlay).despawn();
+    }
+    info!("Photo mode off");
+}
+
+fn toggle_photo_mode(
+    mut commands: Commands,
+    controls: Controls,
+    mut photo_mode: ResMut<PhotoMode>,
+    state: Res<State<GameState>>,
+    game_assets: Res<GameAssets>,
+    overlays: Query<Entity, With<PhotoModeOverlay>>,
+    (ghosts, mut selected_building_type): (Query<Entity, With<SelectedBuilding>>, ResMut<SelectedBuildingType>),
+) {
+    if !controls.just_pressed(InputAction::PhotoMode) {
+        return;
+    }
+    if photo_mode.active {
+        exit_photo_mode(&mut commands, &mut photo_mode, &overlays);
+        return;
+    }
+    // an event popup has to be answered first, and the setup screen's not a picture
+    if matches!(state.get(), GameState::EventModal | GameState::Setup) {
+        return;
+    }
+    // the ghost would end up in the picture
+    for ghost in ghosts.iter() {
+        commands.entity(ghost).despawn();
+    }
+    selected_building_type.0 = None;
+    enter_photo_mode(&mut commands, &mut photo_mode, &game_assets);
+}
+
+fn photo_mode_keys(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut hint: Query<&mut Node, With<PhotoModeHint>>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyL) {
+        photo_mode.hide_locks = !photo_mode.hide_locks;
+    }
+    if keyboard.just_pressed(KeyCode::KeyH) {
+        photo_mode.hide_labels = !photo_mode.hide_labels;
+    }
+
+    if photo_mode.capture_queued {
+        photo_mode.capture_queued = false;
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis());
+        let path = std::path::PathBuf::from(SCREENSHOT_DIR).join(format!("photo_{}.png", millis));
+        if let Err(e) = std::fs::create_dir_all(SCREENSHOT_DIR) {
+            warn!("Couldn't create {}: {}", SCREENSHOT_DIR, e);
+            photo_mode.capturing = false;
+        } else {
+            info!("Saving screenshot to {}", path.display());
+            commands
+                .spawn(Screenshot::primary_window())
+                .observe(save_to_disk(path))
+                .observe(|_: On<ScreenshotCaptured>, mut photo_mode: ResMut<PhotoMode>| {
+                    photo_mode.capturing = false;
+                });
+        }
+    } else if keyboard.just_pressed(KeyCode::Enter) && !photo_mode.capturing {
+        // hidden a frame ahead so the layout's caught up by the time it's captured
+        photo_mode.capture_queued = true;
+        photo_mode.capturing = true;
+    }
+
+    for mut node in hint.iter_mut() {
+        let display = if photo_mode.capturing { Display::None } else { Display::Flex };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+/// Hides every UI root but the overlay, including ones that turn up while photo mode's on.
+/// Anything that switched itself back on since (the paused indicator does every frame) gets
+/// that remembered as what to restore instead. Runs before layout so none of it ever draws
+fn hide_ui_roots(
+    mut commands: Commands,
+    photo_mode: Res<PhotoMode>,
+    mut roots: Query<(Entity, &mut Node, Option<&mut PhotoModeHiddenNode>), (Without<ChildOf>, Without<PhotoModeOverlay>)>,
+) {
+    for (entity, mut node, hidden) in roots.iter_mut() {
+        match (photo_mode.active, hidden) {
+            (true, None) => {
+                commands.entity(entity).insert(PhotoModeHiddenNode(node.display));
+                node.display = Display::None;
+            }
+            (true, Some(mut hidden)) => {
+                if node.display != Display::None {
+                    hidden.0 = node.display;
+                    node.display = Display::None;
+                }
+            }
+            (false, Some(hidden)) => {
+                node.display = hidden.0;
+                commands.entity(entity).remove::<PhotoModeHiddenNode>();
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+/// Lock markers and world text, each behind its own toggle, same remembering as the UI
+fn hide_world_extras(
+    mut commands: Commands,
+    photo_mode: Res<PhotoMode>,
+    mut extras: Query<
+        (Entity, &mut Visibility, Option<&mut PhotoModeHiddenSprite>, Has<LockMarker>),
+        Or<(With<LockMarker>, With<Text2d>)>,
+    >,
+) {
+    for (entity, mut visibility, hidden, is_lock) in extras.iter_mut() {
+        let hide = photo_mode.active && if is_lock { photo_mode.hide_locks } else { photo_mode.hide_labels };
+        match (hide, hidden) {
+            (true, None) => {
+                commands.entity(entity).insert(PhotoModeHiddenSprite(*visibility));
+                *visibility = Visibility::Hidden;
+            }
+            (true, Some(mut hidden)) => {
+                if *visibility != Visibility::Hidden {
+                    hidden.0 = *visibility;
+                    *visibility = Visibility::Hidden;
+                }
+            }
+            (false, Some(hidden)) => {
+                *visibility = hidden.0;
+                commands.entity(entity).remove::<PhotoModeHiddenSprite>();
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+pub fn photo_mode_active(photo_mode: Res<PhotoMode>) -> bool {
+    photo_mode.active
+}
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>()
+            .add_systems(Update, (toggle_photo_mode, photo_mode_keys.run_if(photo_mode_active)).chain())
+            .add_systems(
+                PostUpdate,
+                (hide_ui_roots, hide_world_extras)
+                    .run_if(photo_mode_active.or(resource_changed::<PhotoMode>))
+                    .before(UiSystems::Layout),
+            );
+    }
+}
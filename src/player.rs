@@ -1,12 +1,18 @@
 use bevy::ecs::relationship::Relationship;
 use bevy::prelude::*;
 use bevy::time::common_conditions::on_timer;
-use crate::contracts::{AssociatedWithSink, ContractFulfillment, ContractStatus};
+use crate::contracts::{AssociatedWithSink, ContractFulfillment, ContractFulfillmentStatus, ContractStatus};
 use std::time::Duration;
 use crate::factory::logical::DataSink;
 use crate::factory::buildings::Tile;
 use bevy::platform::collections::HashMap;
 use crate::factory::logical::Dataset;
+use crate::market::MarketPrices;
+use crate::trace::TraceLog;
+use crate::trace_sim;
+
+/// What a Normal run starts with, `Difficulty` scales it
+pub const STARTING_MONEY: i32 = 1000;
 
 /// Player game state
 #[derive(Resource, Debug)]
@@ -14,40 +20,61 @@ pub struct Player {
     pub money: i32,
     pub current_year: u32,
     pub net_income: i32,
-    // Bankruptcy system
+    // Bankruptcy system, how long it's been going is in `DebtState`
     pub bankruptcy_stage: u32,
-    pub bankruptcy_timer: f32, // seconds spent bankrupt in current stage
 }
 
 impl Default for Player {
     fn default() -> Self {
         Self {
-            money: 1000,
+            money: STARTING_MONEY,
             current_year: 0,
             net_income: 10,
             bankruptcy_stage: 0,
-            bankruptcy_timer: 0.0,
         }
     }
 }
 
+/// One income tick's worth of money from every active contract on a sink, summed
+#[derive(Message, Debug, Clone, Copy)]
+pub struct SinkPayout {
+    pub sink: Entity,
+    pub amount: f64,
+    /// Best fulfillment among the contracts that paid
+    pub status: ContractFulfillmentStatus,
+}
+
+/// What reached the sinks over the last income tick, one per data type that arrived
+#[derive(Message, Debug, Clone, Copy)]
+pub struct DataShipped {
+    pub data_type: crate::factory::logical::BasicDataType,
+    pub amount: f32,
+}
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Player>()
+            .add_message::<SinkPayout>()
+            .add_message::<DataShipped>()
             .add_systems(Update, (
                 update_contract_fulfillment,
                 update_money,
-            ).chain().run_if(on_timer(Duration::from_secs(1))));
+                pay_upkeep,
+                crate::events::repay_loans,
+            ).chain().run_if(in_state(crate::pause::GameState::Running).and(on_timer(Duration::from_secs(1)))));
     }
 }
 
 /// System that runs every 1 second to update contract fulfillment status
 /// TODO: smooth out the throughput calculation over time if necessary
 fn update_contract_fulfillment(
-    mut contract_query: Query<(&mut ContractFulfillment, &mut Dataset, &AssociatedWithSink, &mut ContractStatus)>,
+    mut contract_query: Query<(Entity, &mut ContractFulfillment, &mut Dataset, &AssociatedWithSink, &mut ContractStatus)>,
     mut sink_tile_query: Query<(&mut DataSink, &Tile)>,
+    mut trace: ResMut<TraceLog>,
+    mut counters: ResMut<crate::achievements::RunCounters>,
+    mut shipped: MessageWriter<DataShipped>,
 ) {
     // calculate the throughput per (SinkBuilding entity, dataset) pair
     let mut dataset_sink_throughputs: HashMap<(Entity, Dataset), f32> = HashMap::new();
@@ -61,16 +88,48 @@ fn update_contract_fulfillment(
         }
     }
 
+    let total_delivered: f32 = dataset_sink_throughputs.values().sum();
+    if total_delivered > counters.max_throughput {
+        counters.max_throughput = total_delivered;
+    }
+    // a mixed dataset counts towards every type in it
+    let mut per_type: HashMap<crate::factory::logical::BasicDataType, f32> = HashMap::new();
+    for ((_, dataset), amount) in dataset_sink_throughputs.iter() {
+        for data_type in dataset.contents.keys() {
+            *per_type.entry(*data_type).or_insert(0.0) += amount;
+        }
+    }
+    for (data_type, amount) in per_type {
+        if amount > 0.0 {
+            shipped.write(DataShipped { data_type, amount });
+        }
+    }
+
     // update each contract's fulfillment based on the calculated throughputs
-    for (mut fulfillment, dataset, associated_sink, status) in contract_query.iter_mut() {
+    for (contract_entity, mut fulfillment, dataset, associated_sink, status) in contract_query.iter_mut() {
         if *status != ContractStatus::Active{
             continue; // Only update active contracts
         }
         let sink_building_entity = associated_sink.0;
-        if let Some(throughput) = dataset_sink_throughputs.get(&(sink_building_entity, dataset.clone())) {
-            fulfillment.update_throughput(*throughput as f64);
-        } else {
-            fulfillment.update_throughput(0.0);
+        let previous_status = fulfillment.status;
+        // everything arriving at the sink, matched type by type against what the contract asks for
+        let arriving = dataset_sink_throughputs
+            .iter()
+            .filter(|((sink, _), _)| *sink == sink_building_entity)
+            .map(|((_, incoming), amount)| (incoming, *amount as f64));
+        fulfillment.update_contributions(crate::contracts::sum_contributions(
+            arriving,
+            &dataset,
+            crate::contracts::PARTIAL_ATTRIBUTE_CREDIT,
+        ));
+        // runs once a second so the rate is also what came in since last time
+        fulfillment.delivered += fulfillment.throughput;
+        if fulfillment.status != previous_status {
+            trace_sim!(
+                trace, Fulfillment, [contract_entity, sink_building_entity],
+                "contract {:?} {:?} -> {:?} at throughput {:.2} (threshold {:.2})",
+                contract_entity, previous_status, fulfillment.status, fulfillment.throughput, fulfillment.base_threshold
+            );
         }
     }
 
@@ -82,27 +141,92 @@ fn update_contract_fulfillment(
 // System that runs every 1 second to update player money based on active contracts
 fn update_money(
     mut player: ResMut<Player>,
-    contract_query: Query<(&ContractStatus, &ContractFulfillment)>,
+    mut contract_query: Query<(&ContractStatus, &ContractFulfillment, &Dataset, &mut crate::economics::ContractEarnings, Option<&AssociatedWithSink>)>,
+    market: Res<MarketPrices>,
+    mut trace: ResMut<TraceLog>,
+    mut counters: ResMut<crate::achievements::RunCounters>,
+    mut payouts: MessageWriter<SinkPayout>,
 ) {
     let mut total_income = 0.0;
+    // one popup per sink, however many contracts it has
+    let mut per_sink: HashMap<Entity, (f64, ContractFulfillmentStatus)> = HashMap::new();
     
-    // Calculate income from all active contracts
-    for (status, fulfillment) in contract_query.iter() {
+    // Calculate income from all active contracts, scaled by the current market prices
+    for (status, fulfillment, dataset, mut earnings, sink) in contract_query.iter_mut() {
         if *status == ContractStatus::Active {
-            total_income += fulfillment.get_income();
+            let income = market.payout(fulfillment.get_income(), dataset);
+            earnings.0 += income;
+            total_income += income;
+            if let Some(sink) = sink
+                && income > 0.0
+            {
+                let entry = per_sink.entry(sink.0).or_insert((0.0, fulfillment.status));
+                entry.0 += income;
+                if fulfillment.status == ContractFulfillmentStatus::Exceeding {
+                    entry.1 = ContractFulfillmentStatus::Exceeding;
+                }
+            }
         }
     }
-
-    // TODO: subtract factory upkeep from total_income
-    
+    for (sink, (amount, status)) in per_sink {
+        payouts.write(SinkPayout { sink, amount, status });
+    }
 
     // Update player money and net income
     player.money += (total_income as i32).max(0);
+    if total_income > 0.0 {
+        counters.money_earned += total_income as i64;
+    }
     player.net_income = total_income as i32;
-
-    if player.money == 0 && player.net_income < 0 {
-        player.bankruptcy_timer += 1.0;
+    if total_income != 0.0 {
+        trace_sim!(trace, Payout, [], "payout {:.2}, balance {}", total_income, player.money);
     }
-    
+
     info!("Player money updated: {} (income: {})", player.money, total_income);
+}
+
+/// Runs after `update_money`, takes every placed building's upkeep out of the balance.
+/// Short on money, the cheapest buildings lose power until what's left can be paid for,
+/// and get it back on a later tick once money recovers.
+fn pay_upkeep(
+    mut commands: Commands,
+    mut player: ResMut<Player>,
+    buildings: Query<(Entity, &crate::economics::BuildingInfo, Has<crate::economics::Unpowered>)>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+) {
+    let mut charged: Vec<_> = buildings.iter().filter(|(_, info, _)| info.upkeep > 0).collect();
+    let mut total: i32 = charged.iter().map(|(_, info, _)| info.upkeep).sum();
+    if total == 0 {
+        return;
+    }
+    let was_unpowered = charged.iter().filter(|(_, _, unpowered)| *unpowered).count();
+
+    // most expensive stay on the longest
+    charged.sort_by_key(|(_, info, _)| info.cost);
+    let available = player.money.max(0);
+    let mut unpowered = 0;
+    for (entity, info, has_unpowered) in charged.iter() {
+        if total > available {
+            total -= info.upkeep;
+            unpowered += 1;
+            if !has_unpowered {
+                commands.entity(*entity).insert(crate::economics::Unpowered);
+            }
+        } else if *has_unpowered {
+            commands.entity(*entity).remove::<crate::economics::Unpowered>();
+        }
+    }
+
+    player.money -= total;
+    player.net_income -= total;
+    if unpowered > was_unpowered {
+        warn!("Can't cover upkeep, {} building(s) unpowered", unpowered);
+        news_writer.write(crate::events::AddNewsfeedItemEvent {
+            faction: crate::factions::Faction::default(),
+            headline: format!("Upkeep unpaid, {} building{} lost power", unpowered, if unpowered == 1 { "" } else { "s" }),
+            payload: None,
+        });
+    } else if unpowered < was_unpowered {
+        info!("Upkeep covered again, {} building(s) still unpowered", unpowered);
+    }
 }
\ No newline at end of file
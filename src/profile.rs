@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::contracts::ContractDelivered;
+use crate::events::WentBankrupt;
+use crate::factions::{Faction, FactionReputations, ReputationChanged, ReputationLevel, ReputationSource};
+use crate::factory::logical::BasicDataType;
+use crate::pause::GameState;
+use crate::player::{DataShipped, Player};
+
+/// Lifetime stats over every run, next to the user config
+pub const PROFILE_PATH: &str = "profile.ron";
+
+/// Light meta-progression, added to by every run that counts for records. Loaded at startup,
+/// written when the game ends, on saves, and on the autosave interval.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Profile {
+    pub contracts_completed: HashMap<Faction, u32>,
+    pub highest_money: i32,
+    pub data_shipped: HashMap<BasicDataType, f64>,
+    pub bankruptcies: u32,
+    /// Seconds into a run before any faction first reached Trusted, the best run's
+    pub fastest_trusted_seconds: Option<f32>,
+}
+
+impl Profile {
+    /// A missing or broken file is a fresh profile, never a crash
+    pub fn load() -> Self {
+        match std::fs::read_to_string(PROFILE_PATH) {
+            Ok(ron_str) => ron::from_str(&ron_str).unwrap_or_else(|e| {
+                warn!("Failed to parse {}, starting a fresh profile: {}", PROFILE_PATH, e);
+                Self::default()
+            }),
+            Err(e) => {
+                warn!("Couldn't read {} ({}), starting a fresh profile", PROFILE_PATH, e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|s| std::fs::write(PROFILE_PATH, s).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to save {}: {}", PROFILE_PATH, e);
+        }
+    }
+
+    pub fn total_contracts(&self) -> u32 {
+        self.contracts_completed.values().sum()
+    }
+}
+
+/// This run's side of the profile, never saved
+#[derive(Resource, Default)]
+pub struct ProfileRun {
+    /// Seconds played, pauses and event popups don't count
+    pub elapsed: f32,
+    reached_trusted: bool,
+    since_write: f32,
+    game_over_written: bool,
+}
+
+fn count_profile_time(time: Res<Time<Virtual>>, mut run: ResMut<ProfileRun>) {
+    run.elapsed += time.delta_secs();
+    run.since_write += time.delta_secs();
+}
+
+/// Hooks on the same contract, money and event messages the stats and achievements use
+fn update_profile(
+    mut profile: ResMut<Profile>,
+    mut run: ResMut<ProfileRun>,
+    player: Res<Player>,
+    reputations: Res<FactionReputations>,
+    mut delivered: MessageReader<ContractDelivered>,
+    mut shipped: MessageReader<DataShipped>,
+    mut bankrupt: MessageReader<WentBankrupt>,
+    mut reputation_changes: MessageReader<ReputationChanged>,
+) {
+    for contract in delivered.read() {
+        *profile.contracts_completed.entry(contract.faction).or_insert(0) += 1;
+    }
+    for data in shipped.read() {
+        *profile.data_shipped.entry(data.data_type).or_insert(0.0) += data.amount as f64;
+    }
+    let bankruptcies = bankrupt.read().count() as u32;
+    if bankruptcies > 0 {
+        profile.bankruptcies += bankruptcies;
+    }
+    if player.money > profile.highest_money {
+        profile.highest_money = player.money;
+    }
+
+    for change in reputation_changes.read() {
+        if run.reached_trusted || reputations.get_level(change.faction) < ReputationLevel::Trusted {
+            continue;
+        }
+        run.reached_trusted = true;
+        // a loaded save that was already there doesn't get to set a record
+        if change.source == ReputationSource::Setup {
+            continue;
+        }
+        let seconds = run.elapsed;
+        if profile.fastest_trusted_seconds.is_none_or(|best| seconds < best) {
+            info!("{:?} reached Trusted after {:.0}s, a new profile best", change.faction, seconds);
+            profile.fastest_trusted_seconds = Some(seconds);
+        }
+    }
+}
+
+/// Writes profile.ron on quitting, a save, the run being lost, or when the autosave interval
+/// comes round
+fn write_profile(
+    profile: Res<Profile>,
+    mut run: ResMut<ProfileRun>,
+    player: Res<Player>,
+    settings: Res<crate::settings::Settings>,
+    mut exits: MessageReader<AppExit>,
+    mut saves: MessageReader<crate::save::SaveGameRequest>,
+) {
+    let quitting = exits.read().count() > 0;
+    let saved = saves.read().count() > 0;
+    let game_over = player.bankruptcy_stage >= crate::audio::GAME_OVER_BANKRUPTCY_STAGE && !run.game_over_written;
+    let autosave = settings.autosave_minutes() > 0 && run.since_write >= settings.autosave_minutes() as f32 * 60.0;
+    if !(quitting || saved || game_over || autosave) {
+        return;
+    }
+    if game_over {
+        run.game_over_written = true;
+    }
+    run.since_write = 0.0;
+    profile.save();
+}
+
+fn counts_for_records(game_mode: Res<crate::game_mode::GameMode>) -> bool {
+    game_mode.counts_for_records()
+}
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Profile::load())
+            .init_resource::<ProfileRun>()
+            .add_systems(Update, count_profile_time.run_if(in_state(GameState::Running)))
+            .add_systems(Update, update_profile.run_if(counts_for_records))
+            // Last, so an AppExit sent this frame is still around to be read
+            .add_systems(Last, write_profile.run_if(counts_for_records));
+    }
+}
@@ -0,0 +1,698 @@
+//! Save schema. Nothing in here stores an `Entity`: a save is grid positions, building
+//! descriptors and contract data, and loading goes through the same runtime paths that
+//! built everything the first time. Where each id-holding component comes back from:
+//!
+//! - `Tile` / `Tiles`: spawned together by `Building::spawn`
+//! - `PhysicalSink` / `PhysicalSource`: placement detection (`EntityPlaced` -> `ValidateConnections`)
+//! - `LogicalLink { source, sink, links }`: `assemble_logical_links` once the physical chain is back
+//! - `AssociatedWithSink`: `find_sink`, matching the sink's grid position + faction
+//! - `parent_source` / `parent_icon` in source visuals: regenerated from their `Added<>` queries
+//! - `FeedbackLoop`, `SinkInfraCost`: redetected / recomputed from the rebuilt links
+//!
+//! Anything new that holds an `Entity` needs a line here and a way back.
+//!
+//! `SaveGamePlugin` writes the whole run to `saves/<slot>.ron` (F5 quicksaves, F9 loads
+//! the quicksave). World gen is seeded (`WorldSeed`), but events add sources and pools run
+//! dry after it, so sources, sinks and the lock overlay are saved as they are instead of
+//! being regenerated.
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::contracts::{AssociatedWithSink, Contract, ContractBundle, ContractDescription, ContractFulfillment, ContractStatus, ContractTimeout};
+use crate::economics::ContractEarnings;
+use crate::emissary::Emissary;
+use crate::factions::{Faction, FactionReputations, Locked, ReputationChanges, ReputationLevel, ReputationSource, Unlocked};
+use crate::factory::building_visuals::StarterSink;
+use crate::factory::buildings::aggregator::Aggregator;
+use crate::factory::buildings::cleaner::Cleaner;
+use crate::factory::buildings::deidentifier::DeIdentifier;
+use crate::factory::buildings::buildings::Building;
+use crate::factory::buildings::combiner::Combiner;
+use crate::factory::buildings::delinker::Delinker;
+use crate::factory::buildings::filter_splitter::FilterSplitter;
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::buildings::source::{SourceBuilding, SourcePool};
+use crate::factory::buildings::splitter::Splitter;
+use crate::factory::buildings::trunker::Trunker;
+use crate::factory::buildings::Undeletable;
+use crate::factory::logical::{BasicDataType, Dataset};
+use crate::factory::physical::PhysicalLink;
+use crate::factory::source_visuals::{
+    AugmentationEffect, AugmentedIndicator, DataTypeIcon, GlowSprite, ScanningFlashEffect, SourceBackground, SourceLodDot,
+    SourcePoolBar, SourcePoolTrack,
+};
+use crate::grid::{Direction, GridPosition, Orientation};
+use crate::lod::{ClusterBound, ClusterBounds, ClusterLockQuad};
+use crate::player::Player;
+use crate::world_gen::{ClusterIconMarker, LockMarker};
+
+pub const SAVE_DIR: &str = "saves";
+pub const QUICKSAVE_SLOT: &str = "quicksave";
+/// Written by "Save and quit" on the crash overlay, kept apart so it can't clobber a good quicksave
+pub const CRASH_SAVE_SLOT: &str = "crash";
+
+/// Everything needed to spawn a player-placed building again
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum BuildingDescriptor {
+    Link { throughput: f32 },
+    Aggregator {
+        throughput: f32,
+        #[serde(default = "default_min_aggregate_types")]
+        min_types: usize,
+    },
+    Splitter { throughput: f32, source_count: i64 },
+    Combiner { throughput: f32, sink_count: i64 },
+    Delinker { throughput: f32, source_count: i64 },
+    Trunker { throughput_per_sink: f32, sink_count: i64 },
+    TrunkLink { throughput: f32 },
+    DeIdentifier { throughput: f32 },
+    Cleaner { throughput: f32 },
+    /// Filters in port order, the overflow port isn't listed
+    FilterSplitter { throughput: f32, source_count: i64, filters: Vec<Option<BasicDataType>> },
+}
+
+/// Saves from before aggregators cared about their input
+fn default_min_aggregate_types() -> usize {
+    crate::factory::buildings::aggregator::DEFAULT_MIN_AGGREGATE_TYPES
+}
+
+impl BuildingDescriptor {
+    pub fn building(&self) -> Arc<dyn Building> {
+        match *self {
+            BuildingDescriptor::Link { throughput } => Arc::new(PhysicalLink { throughput }),
+            BuildingDescriptor::Aggregator { throughput, min_types } => Arc::new(Aggregator { throughput, min_types }),
+            BuildingDescriptor::Splitter { throughput, source_count } => Arc::new(Splitter { throughput, source_count }),
+            BuildingDescriptor::Combiner { throughput, sink_count } => Arc::new(Combiner { throughput, sink_count }),
+            BuildingDescriptor::Delinker { throughput, source_count } => Arc::new(Delinker { throughput, source_count }),
+            BuildingDescriptor::Trunker { throughput_per_sink, sink_count } => {
+                Arc::new(Trunker { throughput_per_sink, sink_count })
+            }
+            BuildingDescriptor::TrunkLink { throughput } => Arc::new(crate::factory::buildings::trunk_link::TrunkLink { throughput }),
+            BuildingDescriptor::DeIdentifier { throughput } => Arc::new(DeIdentifier { throughput }),
+            BuildingDescriptor::Cleaner { throughput } => Arc::new(Cleaner { throughput }),
+            BuildingDescriptor::FilterSplitter { throughput, source_count, ref filters } => {
+                let mut splitter = FilterSplitter::new(throughput, source_count);
+                splitter.filters = filters.iter().copied().enumerate().collect();
+                Arc::new(splitter)
+            }
+        }
+    }
+}
+
+/// Put on buildings the player constructed, so saving doesn't have to work out what they were
+#[derive(Component, Debug, Clone)]
+pub struct PlacedBuilding {
+    pub descriptor: BuildingDescriptor,
+    pub orientation: Orientation,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedBuilding {
+    pub descriptor: BuildingDescriptor,
+    pub position: (i64, i64),
+    pub orientation: Orientation,
+}
+
+/// A contract keyed by the sink it belongs to rather than the sink's entity
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedContract {
+    pub sink_position: (i64, i64),
+    pub faction: Faction,
+    pub status: ContractStatus,
+    pub dataset: Dataset,
+    pub description: ContractDescription,
+    /// None once accepted, only pending offers count down
+    pub timeout: Option<f32>,
+    pub base_threshold: f64,
+    pub base_money: f64,
+    pub earned: f64,
+    #[serde(default)]
+    pub delivered: f64,
+    #[serde(default = "crate::contracts::ongoing_volume")]
+    pub total_required: f64,
+}
+
+pub fn save_building(position: &GridPosition, placed: &PlacedBuilding) -> SavedBuilding {
+    SavedBuilding {
+        descriptor: placed.descriptor.clone(),
+        position: (position.x, position.y),
+        orientation: placed.orientation,
+    }
+}
+
+/// Spawns a saved building back through `Building::spawn`, the links get resolved by the
+/// usual placement detection afterwards
+pub fn restore_building(commands: &mut Commands, saved: &SavedBuilding) -> Entity {
+    let building = saved.descriptor.building();
+    let entity = building.spawn(
+        commands,
+        GridPosition(I64Vec2::new(saved.position.0, saved.position.1)),
+        saved.orientation,
+    );
+    let data = building.data();
+    commands.entity(entity).insert((
+        crate::economics::BuildCost(data.cost),
+        crate::economics::BuildingInfo::from_data(&data),
+        PlacedBuilding {
+            descriptor: saved.descriptor.clone(),
+            orientation: saved.orientation,
+        },
+    ));
+    entity
+}
+
+/// The sink a saved contract belongs to: same position, same faction
+pub fn find_sink(
+    sink_position: (i64, i64),
+    faction: Faction,
+    sinks: &Query<(Entity, &GridPosition, &Faction), With<SinkBuilding>>,
+) -> Option<Entity> {
+    let position = I64Vec2::new(sink_position.0, sink_position.1);
+    sinks
+        .iter()
+        .find(|(_, grid_position, sink_faction)| grid_position.0 == position && **sink_faction == faction)
+        .map(|(entity, _, _)| entity)
+}
+
+pub fn save_contract(
+    sink_position: &GridPosition,
+    faction: Faction,
+    status: ContractStatus,
+    dataset: &Dataset,
+    description: &ContractDescription,
+    timeout: Option<&ContractTimeout>,
+    fulfillment: &ContractFulfillment,
+    earned: f64,
+) -> SavedContract {
+    SavedContract {
+        sink_position: (sink_position.x, sink_position.y),
+        faction,
+        status,
+        dataset: dataset.clone(),
+        description: description.clone(),
+        timeout: timeout.map(|t| t.0),
+        base_threshold: fulfillment.base_threshold,
+        base_money: fulfillment.base_money,
+        earned,
+        delivered: fulfillment.delivered,
+        total_required: fulfillment.total_required,
+    }
+}
+
+/// Respawns a saved contract attached to `sink`. Throughput starts at zero and comes back
+/// on the next fulfillment tick once the links are rebuilt.
+pub fn restore_contract(commands: &mut Commands, saved: &SavedContract, sink: Entity) -> Entity {
+    let mut contract = commands.spawn((
+        ContractBundle {
+            contract: Contract,
+            status: saved.status,
+            dataset: saved.dataset.clone(),
+            faction: saved.faction,
+            timeout: ContractTimeout(saved.timeout.unwrap_or_default()),
+            description: saved.description.clone(),
+            fulfillment_info: ContractFulfillment {
+                delivered: saved.delivered,
+                ..ContractFulfillment::new(saved.base_threshold, saved.base_money).with_total_required(saved.total_required)
+            },
+        },
+        crate::economics::ContractEarnings(saved.earned),
+        AssociatedWithSink(sink),
+    ));
+    if saved.timeout.is_none() {
+        contract.remove::<ContractTimeout>();
+    }
+    // already paid for when it failed the first time
+    if saved.status == ContractStatus::Failed {
+        contract.insert(crate::contracts::FailurePenalized);
+    }
+    contract.id()
+}
+
+/// Whether a world building is gated behind faction reputation, and if so which side of it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockState {
+    Ungated,
+    Locked,
+    Unlocked,
+}
+
+impl LockState {
+    fn from_components(locked: bool, unlocked: bool) -> LockState {
+        match (locked, unlocked) {
+            (true, _) => LockState::Locked,
+            (_, true) => LockState::Unlocked,
+            _ => LockState::Ungated,
+        }
+    }
+
+    fn insert(&self, commands: &mut EntityCommands) {
+        match self {
+            LockState::Locked => {
+                commands.insert(Locked);
+            }
+            LockState::Unlocked => {
+                commands.insert(Unlocked);
+            }
+            LockState::Ungated => {}
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedSource {
+    pub position: (i64, i64),
+    pub directions: Vec<Direction>,
+    pub throughput: f32,
+    pub limited: bool,
+    /// Remaining and capacity of a limited source's pool
+    #[serde(default)]
+    pub pool: Option<(f32, f32)>,
+    pub size: (i64, i64),
+    pub shape: Dataset,
+    pub faction: Option<Faction>,
+    pub reputation: Option<ReputationLevel>,
+    pub lock: LockState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedSink {
+    pub position: (i64, i64),
+    pub size: (i64, i64),
+    pub faction: Option<Faction>,
+    pub reputation: Option<ReputationLevel>,
+    pub lock: LockState,
+    pub starter: bool,
+}
+
+/// A still-locked overlay cell, or the icon in the middle of its cluster
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedLockCell {
+    pub position: (i64, i64),
+    pub faction: Faction,
+    pub reputation: ReputationLevel,
+    pub icon: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedCluster {
+    pub id: i64,
+    pub min: (i64, i64),
+    pub max: (i64, i64),
+    pub faction: Faction,
+    pub reputation: ReputationLevel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SaveGame {
+    pub version: String,
+    pub money: i32,
+    pub current_year: u32,
+    pub bankruptcy_stage: u32,
+    /// Insolvency clock and loans, older saves start debt free
+    #[serde(default)]
+    pub debt: crate::events::DebtState,
+    /// Older saves were all Normal
+    #[serde(default)]
+    pub difficulty: crate::difficulty::Difficulty,
+    /// Older saves were all normal runs
+    #[serde(default)]
+    pub mode: crate::game_mode::GameMode,
+    /// The seed world gen ran with, older saves didn't keep it
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Market multipliers per data type, older saves start the market flat
+    #[serde(default)]
+    pub market: Vec<(BasicDataType, f32)>,
+    pub reputations: Vec<(Faction, i32)>,
+    pub buildings: Vec<SavedBuilding>,
+    pub sources: Vec<SavedSource>,
+    pub sinks: Vec<SavedSink>,
+    pub lock_cells: Vec<SavedLockCell>,
+    pub clusters: Vec<SavedCluster>,
+    pub contracts: Vec<SavedContract>,
+}
+
+#[derive(Message, Debug, Clone)]
+pub struct SaveGameRequest {
+    pub slot: String,
+}
+
+#[derive(Message, Debug, Clone)]
+pub struct LoadGameRequest {
+    pub slot: String,
+}
+
+/// Save read and the old factory cleared, waiting for the despawns to go through before
+/// the saved one is spawned in its place
+#[derive(Resource)]
+struct PendingLoad(SaveGame);
+
+/// Contracts wait for their sinks to exist so `find_sink` can match them up
+#[derive(Resource)]
+struct PendingContracts(Vec<SavedContract>);
+
+pub fn save_path(slot: &str) -> std::path::PathBuf {
+    std::path::Path::new(SAVE_DIR).join(format!("{}.ron", slot))
+}
+
+fn to_pair(vec: I64Vec2) -> (i64, i64) {
+    (vec.x, vec.y)
+}
+
+fn from_pair(pair: (i64, i64)) -> I64Vec2 {
+    I64Vec2::new(pair.0, pair.1)
+}
+
+const FACTIONS: [Faction; 4] = [Faction::Corporate, Faction::Academia, Faction::Government, Faction::Criminal];
+
+fn quicksave_keys(
+    controls: crate::controls::Controls,
+    mut save_writer: MessageWriter<SaveGameRequest>,
+    mut load_writer: MessageWriter<LoadGameRequest>,
+) {
+    if controls.just_pressed(crate::controls::InputAction::QuickSave) {
+        save_writer.write(SaveGameRequest { slot: QUICKSAVE_SLOT.to_string() });
+    }
+    if controls.just_pressed(crate::controls::InputAction::QuickLoad) {
+        load_writer.write(LoadGameRequest { slot: QUICKSAVE_SLOT.to_string() });
+    }
+}
+
+type SavedSourceQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static GridPosition, &'static SourceBuilding, Option<&'static Faction>, Option<&'static ReputationLevel>, Has<Locked>, Has<Unlocked>, Option<&'static SourcePool>),
+    With<Undeletable>,
+>;
+
+type SavedSinkQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static GridPosition, &'static SinkBuilding, Option<&'static Faction>, Option<&'static ReputationLevel>, Has<Locked>, Has<Unlocked>, Has<StarterSink>),
+    With<Undeletable>,
+>;
+
+type SavedContractQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static ContractStatus, &'static Dataset, &'static Faction, &'static ContractDescription, Option<&'static ContractTimeout>, &'static ContractFulfillment, &'static ContractEarnings, &'static AssociatedWithSink),
+    With<Contract>,
+>;
+
+fn save_game(
+    mut requests: MessageReader<SaveGameRequest>,
+    player: Res<Player>,
+    (debt, difficulty): (Res<crate::events::DebtState>, Res<crate::difficulty::Difficulty>),
+    (mode, seed, market): (Res<crate::game_mode::GameMode>, Res<crate::world_gen::WorldSeed>, Res<crate::market::MarketPrices>),
+    reputations: Res<FactionReputations>,
+    bounds: Res<ClusterBounds>,
+    buildings: Query<(&GridPosition, &PlacedBuilding)>,
+    sources: SavedSourceQuery,
+    sinks: SavedSinkQuery,
+    lock_cells: Query<(&GridPosition, &Faction, &ReputationLevel, Has<ClusterIconMarker>), (With<LockMarker>, With<Locked>)>,
+    contracts: SavedContractQuery,
+    sink_positions: Query<&GridPosition, With<SinkBuilding>>,
+) {
+    for request in requests.read() {
+        let save = SaveGame {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            money: player.money,
+            current_year: player.current_year,
+            bankruptcy_stage: player.bankruptcy_stage,
+            debt: debt.clone(),
+            difficulty: *difficulty,
+            mode: mode.clone(),
+            seed: Some(seed.0),
+            market: {
+                let mut market: Vec<_> = market.multipliers.iter().map(|(t, m)| (*t, *m)).collect();
+                market.sort_by_key(|(t, _)| *t);
+                market
+            },
+            reputations: FACTIONS.iter().map(|f| (*f, reputations.get(*f))).collect(),
+            buildings: buildings.iter().map(|(position, placed)| save_building(position, placed)).collect(),
+            sources: sources
+                .iter()
+                .map(|(position, source, faction, reputation, locked, unlocked, pool)| SavedSource {
+                    position: to_pair(position.0),
+                    directions: source.directions.clone(),
+                    throughput: source.throughput,
+                    limited: source.limited,
+                    pool: pool.map(|p| (p.remaining, p.capacity)),
+                    size: to_pair(source.size),
+                    shape: source.shape.clone(),
+                    faction: faction.copied(),
+                    reputation: reputation.copied(),
+                    lock: LockState::from_components(locked, unlocked),
+                })
+                .collect(),
+            sinks: sinks
+                .iter()
+                .map(|(position, sink, faction, reputation, locked, unlocked, starter)| SavedSink {
+                    position: to_pair(position.0),
+                    size: to_pair(sink.size),
+                    faction: faction.copied(),
+                    reputation: reputation.copied(),
+                    lock: LockState::from_components(locked, unlocked),
+                    starter,
+                })
+                .collect(),
+            lock_cells: lock_cells
+                .iter()
+                .map(|(position, faction, reputation, icon)| SavedLockCell {
+                    position: to_pair(position.0),
+                    faction: *faction,
+                    reputation: *reputation,
+                    icon,
+                })
+                .collect(),
+            clusters: bounds
+                .0
+                .iter()
+                .map(|(id, bound)| SavedCluster {
+                    id: *id,
+                    min: to_pair(bound.min),
+                    max: to_pair(bound.max),
+                    faction: bound.faction,
+                    reputation: bound.reputation,
+                })
+                .collect(),
+            // finished and turned down offers are history, only the live ones come back
+            contracts: contracts
+                .iter()
+                .filter(|(status, ..)| matches!(status, ContractStatus::Pending | ContractStatus::Active))
+                .filter_map(|(status, dataset, faction, description, timeout, fulfillment, earnings, sink)| {
+                    let sink_position = sink_positions.get(sink.0).ok()?;
+                    Some(save_contract(sink_position, *faction, *status, dataset, description, timeout, fulfillment, earnings.0))
+                })
+                .collect(),
+        };
+
+        let path = save_path(&request.slot);
+        let result = std::fs::create_dir_all(SAVE_DIR)
+            .map_err(|e| e.to_string())
+            .and_then(|_| ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default()).map_err(|e| e.to_string()))
+            .and_then(|s| std::fs::write(&path, s).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => info!(
+                "Saved to {} ({} buildings, {} contracts)",
+                path.display(),
+                save.buildings.len(),
+                save.contracts.len()
+            ),
+            Err(e) => warn!("Failed to save {}: {}", path.display(), e),
+        }
+    }
+}
+
+type ClearOnLoadFilter = Or<(
+    With<PlacedBuilding>,
+    With<SourceBuilding>,
+    With<SinkBuilding>,
+    With<LockMarker>,
+    With<Contract>,
+    With<Emissary>,
+)>;
+
+/// Source decorations aren't children of their source, they go separately
+type SourceDecorationFilter = (
+    Or<(
+        With<DataTypeIcon>,
+        With<SourceBackground>,
+        With<SourceLodDot>,
+        With<AugmentationEffect>,
+        With<AugmentedIndicator>,
+        With<GlowSprite>,
+        With<ScanningFlashEffect>,
+        With<SourcePoolBar>,
+        With<SourcePoolTrack>,
+    )>,
+    Without<Node>,
+);
+
+/// Reads the save and clears the current factory. Nothing is touched if the file is
+/// missing or doesn't parse.
+fn load_game(
+    mut commands: Commands,
+    mut requests: MessageReader<LoadGameRequest>,
+    cleared: Query<Entity, ClearOnLoadFilter>,
+    decorations: Query<Entity, SourceDecorationFilter>,
+    mut undo_buffer: ResMut<crate::factory::undo::UndoBuffer>,
+) {
+    let Some(request) = requests.read().last() else {
+        return;
+    };
+    let path = save_path(&request.slot);
+    let Ok(ron_str) = std::fs::read_to_string(&path) else {
+        warn!("No save at {}", path.display());
+        return;
+    };
+    let save = match ron::from_str::<SaveGame>(&ron_str) {
+        Ok(save) => save,
+        Err(e) => {
+            warn!("Failed to parse {}: {}", path.display(), e);
+            return;
+        }
+    };
+    info!("Loading {} (saved by v{})", path.display(), save.version);
+
+    for entity in cleared.iter().chain(decorations.iter()) {
+        commands.entity(entity).try_despawn();
+    }
+    // removals from the run being replaced would undo into the loaded one
+    undo_buffer.0.clear();
+    commands.insert_resource(PendingLoad(save));
+}
+
+/// Spawns the saved world back in. Buildings go through `Building::spawn` like at world gen
+/// and placement, so the physical and logical links resolve themselves again.
+fn respawn_saved_world(
+    mut commands: Commands,
+    pending: Res<PendingLoad>,
+    game_assets: Res<crate::assets::GameAssets>,
+    mut player: ResMut<Player>,
+    mut reputations: ReputationChanges,
+) {
+    let save = &pending.0;
+    commands.remove_resource::<PendingLoad>();
+
+    player.money = save.money;
+    player.current_year = save.current_year;
+    player.bankruptcy_stage = save.bankruptcy_stage;
+    commands.insert_resource(save.debt.clone());
+    commands.insert_resource(save.difficulty);
+    commands.insert_resource(save.mode.clone());
+    if let Some(seed) = save.seed {
+        commands.insert_resource(crate::world_gen::WorldSeed(seed));
+    }
+    // no trend arrows straight after a load, previous starts equal to current
+    let mut market = crate::market::MarketPrices::default();
+    market.multipliers.extend(save.market.iter().copied());
+    market.previous = market.multipliers.clone();
+    commands.insert_resource(market);
+    for (faction, value) in save.reputations.iter() {
+        reputations.set(*faction, *value, ReputationSource::Setup);
+    }
+    commands.insert_resource(ClusterBounds(
+        save.clusters
+            .iter()
+            .map(|c| (c.id, ClusterBound { min: from_pair(c.min), max: from_pair(c.max), faction: c.faction, reputation: c.reputation }))
+            .collect(),
+    ));
+
+    // only what's still locked survives a save, which is all the minimap tints strongly anyway
+    commands.insert_resource(crate::world_gen::ClusterCells(
+        save.lock_cells
+            .iter()
+            .map(|c| (from_pair(c.position), (c.faction, c.reputation)))
+            .collect(),
+    ));
+    for cell in save.lock_cells.iter() {
+        if cell.icon {
+            crate::world_gen::spawn_cluster_icon(from_pair(cell.position), cell.faction, cell.reputation, &game_assets, &mut commands);
+        } else {
+            crate::world_gen::spawn_lock_cell(from_pair(cell.position), cell.faction, cell.reputation, &game_assets, &mut commands);
+        }
+    }
+
+    for saved in save.sources.iter() {
+        let entity = SourceBuilding {
+            directions: saved.directions.clone(),
+            throughput: saved.throughput,
+            limited: saved.limited,
+            size: from_pair(saved.size),
+            shape: saved.shape.clone(),
+        }
+        .spawn(&mut commands, GridPosition(from_pair(saved.position)), Orientation::default());
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert((ZIndex(3), Undeletable));
+        if let (Some(faction), Some(reputation)) = (saved.faction, saved.reputation) {
+            entity_commands.insert((faction, reputation));
+        }
+        // a dry one goes straight back to depleted on the next drain pass
+        if let Some((remaining, capacity)) = saved.pool {
+            entity_commands.insert(SourcePool { remaining, capacity });
+        }
+        saved.lock.insert(&mut entity_commands);
+    }
+
+    for saved in save.sinks.iter() {
+        let entity = SinkBuilding { size: from_pair(saved.size) }
+            .spawn(&mut commands, GridPosition(from_pair(saved.position)), Orientation::default());
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(Undeletable);
+        if let (Some(faction), Some(reputation)) = (saved.faction, saved.reputation) {
+            entity_commands.insert((faction, reputation));
+        }
+        if saved.starter {
+            entity_commands.insert(StarterSink);
+        }
+        saved.lock.insert(&mut entity_commands);
+    }
+
+    for saved in save.buildings.iter() {
+        restore_building(&mut commands, saved);
+    }
+
+    commands.insert_resource(PendingContracts(save.contracts.clone()));
+    info!(
+        "Loaded {} buildings, {} sources, {} sinks",
+        save.buildings.len(),
+        save.sources.len(),
+        save.sinks.len()
+    );
+}
+
+fn reattach_saved_contracts(
+    mut commands: Commands,
+    pending: Res<PendingContracts>,
+    sinks: Query<(Entity, &GridPosition, &Faction), With<SinkBuilding>>,
+) {
+    commands.remove_resource::<PendingContracts>();
+    for saved in pending.0.iter() {
+        match find_sink(saved.sink_position, saved.faction, &sinks) {
+            Some(sink) => {
+                restore_contract(&mut commands, saved, sink);
+            }
+            None => warn!("No {:?} sink at {:?} for a saved contract, dropping it", saved.faction, saved.sink_position),
+        }
+    }
+}
+
+pub struct SaveGamePlugin;
+
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<SaveGameRequest>()
+            .add_message::<LoadGameRequest>()
+            .add_systems(Update, (
+                // nothing to save or load over before the run starts
+                quicksave_keys.run_if(not(in_state(crate::pause::GameState::Setup))),
+                save_game,
+                load_game,
+                respawn_saved_world.run_if(resource_exists::<PendingLoad>),
+                reattach_saved_contracts.run_if(resource_exists::<PendingContracts>),
+            ).chain());
+    }
+}
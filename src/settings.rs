@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const MIN_UI_TEXT_SCALE: f32 = 0.8;
+pub const MAX_UI_TEXT_SCALE: f32 = 1.5;
+pub const UI_TEXT_SCALE_STEP: f32 = 0.1;
+pub const SCREEN_SHAKE_LEVELS: [&str; 3] = ["Off", "Low", "Full"];
+/// How hard the camera shakes at each of `SCREEN_SHAKE_LEVELS`
+const SCREEN_SHAKE_STRENGTHS: [f32; 3] = [0.0, 0.4, 1.0];
+/// Autosave interval options in minutes, 0 meaning off
+pub const AUTOSAVE_INTERVALS: [u32; 5] = [0, 1, 2, 5, 10];
+
+/// Volumes, all 0..1. The only settings kept between launches, in the user config.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub music_muted: bool,
+    pub sfx_volume: f32,
+    /// Silences everything, music and sound effects
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 0.8,
+            music_volume: 0.5,
+            music_muted: false,
+            sfx_volume: 0.7,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn music_level(&self) -> f32 {
+        if self.muted || self.music_muted { 0.0 } else { self.master_volume * self.music_volume }
+    }
+
+    pub fn sfx_level(&self) -> f32 {
+        if self.muted { 0.0 } else { self.master_volume * self.sfx_volume }
+    }
+}
+
+/// Player preferences, applied live without a restart
+#[derive(Resource, Debug, Clone)]
+pub struct Settings {
+    /// Multiplier on top of the window-relative `ScalableText` size
+    pub ui_text_scale: f32,
+    pub audio: AudioSettings,
+    /// Index into `SCREEN_SHAKE_LEVELS`
+    pub screen_shake: usize,
+    pub reduce_motion: bool,
+    /// Index into `AUTOSAVE_INTERVALS`
+    pub autosave_interval: usize,
+    pub edge_scroll: bool,
+    /// Contracts sidebar folded down to its edge tab
+    pub sidebar_collapsed: bool,
+    /// Faction emissaries walking some offers over in person
+    pub emissaries: bool,
+    /// Refuse placements that leave a port facing something it can't connect to
+    pub block_facing_ports: bool,
+    /// Swap the `Palette` for one that doesn't lean on red against green
+    pub colorblind_palette: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ui_text_scale: 1.0,
+            audio: AudioSettings::default(),
+            screen_shake: 2,
+            reduce_motion: false,
+            autosave_interval: 3,
+            edge_scroll: false,
+            sidebar_collapsed: false,
+            emissaries: true,
+            block_facing_ports: false,
+            colorblind_palette: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn set_ui_text_scale(&mut self, scale: f32) {
+        // round to the step so repeated +/- doesn't drift
+        let stepped = (scale / UI_TEXT_SCALE_STEP).round() * UI_TEXT_SCALE_STEP;
+        self.ui_text_scale = stepped.clamp(MIN_UI_TEXT_SCALE, MAX_UI_TEXT_SCALE);
+    }
+
+    /// Multiplier on camera shake, reduce motion switches it off whatever the level
+    pub fn screen_shake_strength(&self) -> f32 {
+        if self.reduce_motion {
+            return 0.0;
+        }
+        SCREEN_SHAKE_STRENGTHS.get(self.screen_shake).copied().unwrap_or(0.0)
+    }
+
+    pub fn autosave_minutes(&self) -> u32 {
+        AUTOSAVE_INTERVALS.get(self.autosave_interval).copied().unwrap_or(0)
+    }
+}
+
+/// Audio comes back from the user config before the settings panel is built
+fn load_audio_settings(config: Res<crate::user_config::UserConfig>, mut settings: ResMut<Settings>) {
+    if let Some(audio) = config.audio {
+        settings.audio = audio;
+    }
+}
+
+fn persist_audio_settings(settings: Res<Settings>, mut config: ResMut<crate::user_config::UserConfig>) {
+    // only touch the config when audio changed, every write saves the file
+    if config.audio != Some(settings.audio) {
+        config.audio = Some(settings.audio);
+    }
+}
+
+/// The sidebar opens the way it was left last session
+fn load_sidebar_settings(config: Res<crate::user_config::UserConfig>, mut settings: ResMut<Settings>) {
+    if let Some(collapsed) = config.sidebar_collapsed {
+        settings.sidebar_collapsed = collapsed;
+    }
+}
+
+fn persist_sidebar_settings(settings: Res<Settings>, mut config: ResMut<crate::user_config::UserConfig>) {
+    if config.sidebar_collapsed != Some(settings.sidebar_collapsed) {
+        config.sidebar_collapsed = Some(settings.sidebar_collapsed);
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>()
+            .add_systems(PreStartup, (load_audio_settings, load_sidebar_settings))
+            .add_systems(
+                Update,
+                (persist_audio_settings, persist_sidebar_settings)
+                    .run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+            );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::factory::buildings::aggregator::Aggregator;
+use crate::factory::buildings::cleaner::Cleaner;
 use crate::factory::buildings::buildings::Building;
 use crate::factory::buildings::combiner::Combiner;
+use crate::factory::buildings::deidentifier::DeIdentifier;
 use crate::factory::buildings::delinker::Delinker;
 use crate::factory::buildings::sink::SinkBuilding;
 use crate::factory::buildings::source::SourceBuilding;
@@ -11,7 +13,7 @@ use crate::factory::physical::PhysicalLink;
 use crate::grid::{Direction, GridPosition, Orientation};
 use bevy::math::I64Vec2;
 use bevy::platform::collections::{HashMap, HashSet};
-use bevy::prelude::Commands;
+use bevy::prelude::*;
 
 pub fn spawn_combiner_test(commands: &mut Commands) {
     SourceBuilding {
@@ -189,7 +191,7 @@ pub fn spawn_delinker_test(commands: &mut Commands) {
         Orientation::new(Direction::Up, false),
     );
 
-    Aggregator { throughput: 5.0 }.spawn(
+    Aggregator::new(5.0).spawn(
         commands,
         GridPosition(I64Vec2 { x: 1, y: 1 + 5 }),
         Orientation::new(Direction::Right, false),
@@ -261,7 +263,7 @@ pub fn spawn_splitter_test(commands: &mut Commands) {
         Orientation::default(),
     );
 
-    Aggregator { throughput: 1.0 }.spawn(
+    Aggregator::new(1.0).spawn(
         commands,
         GridPosition(I64Vec2 { x: 1, y: 1 }),
         Orientation::new(Direction::Right, false),
@@ -313,3 +315,82 @@ pub fn spawn_splitter_test(commands: &mut Commands) {
         Orientation::new(Direction::Right, false),
     );
 }
+
+/// Source -> de-identifier -> sink, the sink's tooltip should show the "-" attribute
+pub fn spawn_deidentifier_test(commands: &mut Commands) {
+    SourceBuilding {
+        directions: vec![Direction::Right],
+        throughput: 5.0,
+        limited: false,
+        size: I64Vec2::new(1, 1),
+        shape: Dataset {
+            contents: HashMap::from([(BasicDataType::Biometric, HashSet::<DataAttribute>::new())]),
+        },
+    }
+    .spawn(
+        commands,
+        GridPosition(I64Vec2 { x: 0, y: 10 }),
+        Orientation::default(),
+    );
+
+    // input on its first tile, output one tile up
+    DeIdentifier { throughput: 5.0 }.spawn(
+        commands,
+        GridPosition(I64Vec2 { x: 1, y: 10 }),
+        Orientation::new(Direction::Right, false),
+    );
+
+    PhysicalLink { throughput: 234.0 }.spawn(
+        commands,
+        GridPosition(I64Vec2 { x: 2, y: 11 }),
+        Orientation::new(Direction::Right, false),
+    );
+
+    SinkBuilding {
+        size: I64Vec2::new(1, 1),
+    }
+    .spawn(
+        commands,
+        GridPosition(I64Vec2 { x: 3, y: 11 }),
+        Orientation::new(Direction::Right, false),
+    );
+}
+
+/// Source -> cleaner -> sink, the sink should get "*" data at 70% of the source rate
+pub fn spawn_cleaner_test(commands: &mut Commands) {
+    SourceBuilding {
+        directions: vec![Direction::Right],
+        throughput: 5.0,
+        limited: false,
+        size: I64Vec2::new(1, 1),
+        shape: Dataset {
+            contents: HashMap::from([(BasicDataType::Economic, HashSet::<DataAttribute>::new())]),
+        },
+    }
+    .spawn(
+        commands,
+        GridPosition(I64Vec2 { x: 0, y: 14 }),
+        Orientation::default(),
+    );
+
+    Cleaner { throughput: 5.0 }.spawn(
+        commands,
+        GridPosition(I64Vec2 { x: 1, y: 14 }),
+        Orientation::new(Direction::Right, false),
+    );
+
+    PhysicalLink { throughput: 234.0 }.spawn(
+        commands,
+        GridPosition(I64Vec2 { x: 2, y: 14 }),
+        Orientation::new(Direction::Right, false),
+    );
+
+    SinkBuilding {
+        size: I64Vec2::new(1, 1),
+    }
+    .spawn(
+        commands,
+        GridPosition(I64Vec2 { x: 3, y: 14 }),
+        Orientation::new(Direction::Right, false),
+    );
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+
+use crate::factory::physical::PhysicalSink;
+
+/// How many entries the trace keeps before dropping the oldest
+pub const TRACE_CAPACITY: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceCategory {
+    Contract,
+    Fulfillment,
+    Connection,
+    Reputation,
+    Consequence,
+    Raid,
+    Payout,
+}
+
+impl TraceCategory {
+    pub const ALL: [TraceCategory; 7] = [
+        TraceCategory::Contract,
+        TraceCategory::Fulfillment,
+        TraceCategory::Connection,
+        TraceCategory::Reputation,
+        TraceCategory::Consequence,
+        TraceCategory::Raid,
+        TraceCategory::Payout,
+    ];
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    /// Seconds since startup
+    pub time: f64,
+    pub category: TraceCategory,
+    pub message: String,
+    pub entities: Vec<Entity>,
+}
+
+/// Ring buffer of significant simulation events, for reconstructing bug reports
+#[derive(Resource)]
+pub struct TraceLog {
+    entries: VecDeque<TraceEntry>,
+    capacity: usize,
+    /// Updated every frame so call sites don't need `Time`
+    pub now: f64,
+}
+
+impl Default for TraceLog {
+    fn default() -> Self {
+        Self::with_capacity(TRACE_CAPACITY)
+    }
+}
+
+impl TraceLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            now: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, category: TraceCategory, message: String, entities: Vec<Entity>) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(TraceEntry {
+            time: self.now,
+            category,
+            message,
+            entities,
+        });
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &TraceEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Entries matching the filter, oldest first
+    pub fn filtered<'a>(&'a self, filter: &'a TraceFilter) -> impl Iterator<Item = &'a TraceEntry> {
+        self.entries.iter().filter(move |e| filter.matches(e))
+    }
+
+    /// Plain text dump of the filtered entries, for pasting into bug reports
+    pub fn format_filtered(&self, filter: &TraceFilter) -> String {
+        let mut out = String::new();
+        for entry in self.filtered(filter) {
+            let _ = writeln!(
+                out,
+                "[{:>9.2}] {:?}: {} {:?}",
+                entry.time, entry.category, entry.message, entry.entities
+            );
+        }
+        out
+    }
+}
+
+#[derive(Resource, Default, Clone)]
+pub struct TraceFilter {
+    pub category: Option<TraceCategory>,
+    pub entity: Option<Entity>,
+}
+
+impl TraceFilter {
+    pub fn matches(&self, entry: &TraceEntry) -> bool {
+        self.category.is_none_or(|c| c == entry.category)
+            && self.entity.is_none_or(|e| entry.entities.contains(&e))
+    }
+}
+
+/// Records a simulation trace entry: `trace_sim!(log, Category, [entities], "fmt", args..)`.
+/// Compiles to nothing without the `sim_trace` feature.
+#[macro_export]
+macro_rules! trace_sim {
+    ($log:expr, $category:ident, [$($entity:expr),* $(,)?], $($arg:tt)+) => {{
+        #[cfg(feature = "sim_trace")]
+        {
+            $log.push(
+                $crate::trace::TraceCategory::$category,
+                format!($($arg)+),
+                vec![$($entity),*],
+            );
+        }
+        #[cfg(not(feature = "sim_trace"))]
+        {
+            let _ = &$log;
+        }
+    }};
+}
+
+fn update_trace_clock(time: Res<Time>, mut log: ResMut<TraceLog>) {
+    log.now = time.elapsed_secs_f64();
+}
+
+fn trace_connection_formed(
+    trigger: On<Add, PhysicalSink>,
+    sinks: Query<&PhysicalSink>,
+    mut log: ResMut<TraceLog>,
+) {
+    if let Ok(sink) = sinks.get(trigger.entity) {
+        trace_sim!(log, Connection, [sink.0, trigger.entity], "connection formed {:?} -> {:?}", sink.0, trigger.entity);
+    }
+}
+
+fn trace_connection_broken(
+    trigger: On<Remove, PhysicalSink>,
+    sinks: Query<&PhysicalSink>,
+    mut log: ResMut<TraceLog>,
+) {
+    if let Ok(sink) = sinks.get(trigger.entity) {
+        trace_sim!(log, Connection, [sink.0, trigger.entity], "connection broken {:?} -> {:?}", sink.0, trigger.entity);
+    }
+}
+
+pub struct TracePlugin;
+
+impl Plugin for TracePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TraceLog>()
+            .init_resource::<TraceFilter>()
+            .add_systems(First, update_trace_clock)
+            .add_observer(trace_connection_formed)
+            .add_observer(trace_connection_broken);
+    }
+}
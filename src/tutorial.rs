@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+use bevy::platform::collections::HashSet;
+
+use crate::achievements::RunCounters;
+use crate::contracts::ContractStatus;
+use crate::events::{PlayerChoiceEvent, TriggerInteractiveEvent};
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::buildings::Tile;
+use crate::factory::logical::LogicalLink;
+use crate::factory::ConstructBuildingEvent;
+use crate::ui::contracts::ContractAcceptButton;
+use crate::ui::shop::UIBuilding;
+use crate::user_config::UserConfig;
+
+/// Seconds into the run before the welcome popup, so the world has drawn first
+const TUTORIAL_START_DELAY: f32 = 1.5;
+const WELCOME_EVENT: &str = "tutorial_welcome";
+/// Choice on the welcome event that turns the whole chain off
+const SKIP_CHOICE: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Milestone {
+    WirePlaced,
+    SinkConnected,
+    ContractAccepted,
+    FirstIncome,
+}
+
+/// Where the player is in the tutorial. Each step waits on one milestone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TutorialStep {
+    #[default]
+    NotStarted,
+    Welcome,
+    PlaceWire,
+    ConnectSink,
+    AcceptContract,
+    EarnIncome,
+    Done,
+}
+
+impl TutorialStep {
+    /// The popup explaining this step
+    fn event_id(&self) -> Option<&'static str> {
+        match self {
+            TutorialStep::Welcome => Some(WELCOME_EVENT),
+            TutorialStep::PlaceWire => Some("tutorial_wires"),
+            TutorialStep::ConnectSink => Some("tutorial_connect"),
+            TutorialStep::AcceptContract => Some("tutorial_contracts"),
+            TutorialStep::EarnIncome => Some("tutorial_income"),
+            TutorialStep::Done => Some("tutorial_done"),
+            TutorialStep::NotStarted => None,
+        }
+    }
+
+    fn milestone(&self) -> Option<Milestone> {
+        match self {
+            TutorialStep::PlaceWire => Some(Milestone::WirePlaced),
+            TutorialStep::ConnectSink => Some(Milestone::SinkConnected),
+            TutorialStep::AcceptContract => Some(Milestone::ContractAccepted),
+            TutorialStep::EarnIncome => Some(Milestone::FirstIncome),
+            _ => None,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            TutorialStep::NotStarted => TutorialStep::Welcome,
+            TutorialStep::Welcome => TutorialStep::PlaceWire,
+            TutorialStep::PlaceWire => TutorialStep::ConnectSink,
+            TutorialStep::ConnectSink => TutorialStep::AcceptContract,
+            TutorialStep::AcceptContract => TutorialStep::EarnIncome,
+            TutorialStep::EarnIncome | TutorialStep::Done => TutorialStep::Done,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Tutorial {
+    pub step: TutorialStep,
+    /// Milestones hit so far, including ones reached ahead of their step
+    reached: HashSet<Milestone>,
+    started_after: f32,
+}
+
+/// Pulsing outline on the UI element the current step is about
+#[derive(Component)]
+pub struct TutorialHighlight;
+
+/// Picks up milestones from the messages and changes that already exist
+fn detect_milestones(
+    mut tutorial: ResMut<Tutorial>,
+    mut constructions: MessageReader<ConstructBuildingEvent>,
+    new_links: Query<&Tile, Added<LogicalLink>>,
+    sinks: Query<(), With<SinkBuilding>>,
+    statuses: Query<&ContractStatus, Changed<ContractStatus>>,
+    counters: Res<RunCounters>,
+) {
+    if constructions.read().any(|event| event.building.data().name == "Link") {
+        tutorial.reached.insert(Milestone::WirePlaced);
+    }
+    // LogicalLinks live on the receiving tile, a sink building's tile means data is arriving
+    if new_links.iter().any(|tile| sinks.contains(tile.0)) {
+        tutorial.reached.insert(Milestone::SinkConnected);
+    }
+    if statuses.iter().any(|status| *status == ContractStatus::Active) {
+        tutorial.reached.insert(Milestone::ContractAccepted);
+    }
+    if counters.is_changed() && counters.money_earned > 0 {
+        tutorial.reached.insert(Milestone::FirstIncome);
+    }
+}
+
+/// Moves the tutorial along and fires the popup for each new step
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    mut choices: MessageReader<PlayerChoiceEvent>,
+    mut trigger: MessageWriter<TriggerInteractiveEvent>,
+    mut user_config: ResMut<UserConfig>,
+    time: Res<Time>,
+) {
+    let mut step = tutorial.step;
+    match step {
+        TutorialStep::NotStarted => {
+            tutorial.started_after += time.delta_secs();
+            if tutorial.started_after < TUTORIAL_START_DELAY {
+                return;
+            }
+            step = step.next();
+        }
+        TutorialStep::Welcome => {
+            let Some(choice) = choices.read().find(|c| c.event_id == WELCOME_EVENT) else { return };
+            if choice.choice_index == SKIP_CHOICE {
+                info!("Tutorial skipped");
+                tutorial.step = TutorialStep::Done;
+                user_config.tutorial_finished = true;
+                return;
+            }
+            step = step.next();
+        }
+        TutorialStep::Done => return,
+        _ => {}
+    }
+    // anything already done gets skipped over rather than explained
+    while let Some(milestone) = step.milestone()
+        && tutorial.reached.contains(&milestone)
+    {
+        step = step.next();
+    }
+    if step == tutorial.step {
+        return;
+    }
+
+    info!("Tutorial step {:?} -> {:?}", tutorial.step, step);
+    tutorial.step = step;
+    if let Some(event_id) = step.event_id() {
+        trigger.write(TriggerInteractiveEvent { event_id: event_id.to_string() });
+    }
+    if step == TutorialStep::Done {
+        user_config.tutorial_finished = true;
+    }
+}
+
+/// Keeps the outline on the shop's wire slot or the first accept button, and pulses it
+fn update_tutorial_highlight(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    shop_slots: Query<(Entity, &UIBuilding)>,
+    accept_buttons: Query<Entity, With<ContractAcceptButton>>,
+    mut highlighted: Query<(Entity, &mut Outline), With<TutorialHighlight>>,
+    time: Res<Time<Real>>,
+) {
+    let target = match tutorial.step {
+        TutorialStep::PlaceWire => shop_slots
+            .iter()
+            .find(|(_, slot)| slot.building_type.data().name == "Link")
+            .map(|(e, _)| e),
+        TutorialStep::AcceptContract => accept_buttons.iter().next(),
+        _ => None,
+    };
+
+    let pulse = 0.5 + 0.5 * (time.elapsed_secs() * 4.0).sin();
+    let mut target_done = false;
+    for (entity, mut outline) in highlighted.iter_mut() {
+        if Some(entity) == target {
+            outline.color = Color::srgba(1.0, 0.85, 0.2, pulse);
+            target_done = true;
+        } else {
+            commands.entity(entity).remove::<(TutorialHighlight, Outline)>();
+        }
+    }
+    if let Some(target) = target
+        && !target_done
+    {
+        commands.entity(target).insert((
+            TutorialHighlight,
+            Outline::new(Val::Px(3.0), Val::Px(2.0), Color::srgba(1.0, 0.85, 0.2, pulse)),
+        ));
+    }
+}
+
+/// Once is enough, and sandbox players know what they're after
+fn skip_tutorial_if_seen(
+    mut tutorial: ResMut<Tutorial>,
+    user_config: Res<UserConfig>,
+    game_mode: Res<crate::game_mode::GameMode>,
+) {
+    if user_config.tutorial_finished || game_mode.sandbox {
+        tutorial.step = TutorialStep::Done;
+    }
+}
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        use crate::pause::GameState;
+
+        app.init_resource::<Tutorial>()
+            .add_systems(Startup, skip_tutorial_if_seen)
+            .add_systems(Update, (
+                detect_milestones,
+                advance_tutorial.run_if(in_state(GameState::Running)),
+                update_tutorial_highlight,
+            ).chain());
+    }
+}
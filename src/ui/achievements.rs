@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use crate::achievements::{AchievementEarned, AchievementLibrary, RunCounters};
+use crate::assets::GameAssets;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::toast::spawn_toast;
+use crate::ui::{BlocksWorldClicks, BlocksWorldScroll};
+use crate::user_config::UserConfig;
+
+/// Opens/closes the achievements screen
+#[derive(Component)]
+pub struct AchievementsButton;
+
+#[derive(Component)]
+pub struct AchievementsPanel;
+
+#[derive(Component)]
+pub struct AchievementsList;
+
+pub fn spawn_achievements_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(20.0),
+                left: Val::Px(130.0),
+                padding: UiRect::all(Val::Vw(0.45)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+            ZIndex(100),
+            AchievementsButton,
+            BlocksWorldClicks,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new("Achievements"),
+                game_assets.text_font(14.0),
+                ScalableText::from_vw(0.9),
+                TextColor(Color::WHITE),
+            ));
+        });
+
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                top: Val::Percent(15.0),
+                left: Val::Percent(30.0),
+                width: Val::Percent(40.0),
+                max_height: Val::Percent(65.0),
+                padding: UiRect::all(Val::Vw(0.8)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.4),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.95)),
+            GlobalZIndex(700),
+            AchievementsPanel,
+            BlocksWorldClicks,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new("Achievements"),
+                game_assets.text_font(20.0),
+                ScalableText::from_vw(1.4),
+                TextColor(Color::srgb(0.95, 0.85, 0.25)),
+            ));
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Vw(0.3),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                AchievementsList,
+                BlocksWorldScroll,
+            ));
+        });
+}
+
+pub fn toggle_achievements_panel(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<AchievementsButton>)>,
+    mut panel_query: Query<&mut Node, With<AchievementsPanel>>,
+) {
+    if !button_query.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    for mut node in panel_query.iter_mut() {
+        node.display = match node.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+    }
+}
+
+/// Rebuilds the earned/unearned list while the panel is open
+pub fn update_achievements_list(
+    mut commands: Commands,
+    panel_query: Query<&Node, With<AchievementsPanel>>,
+    list_query: Query<Entity, With<AchievementsList>>,
+    library: Res<AchievementLibrary>,
+    counters: Res<RunCounters>,
+    user_config: Res<UserConfig>,
+    game_assets: Res<GameAssets>,
+) {
+    let Ok(panel) = panel_query.single() else { return; };
+    let opened = panel.is_changed();
+    if panel.display == Display::None || !(opened || counters.is_changed() || user_config.is_changed()) {
+        return;
+    }
+    let Ok(list) = list_query.single() else { return; };
+
+    commands.entity(list).despawn_children().with_children(|list| {
+        for achievement in library.achievements.iter() {
+            let earned = user_config.earned_achievements.contains(&achievement.id);
+            let status = if earned {
+                "Earned".to_string()
+            } else {
+                achievement.condition.progress_text(&counters).unwrap_or("Locked".to_string())
+            };
+            list.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Vw(0.4)),
+                    ..default()
+                },
+                BackgroundColor(if earned { Color::srgb(0.15, 0.3, 0.15) } else { Color::srgb(0.15, 0.15, 0.18) }),
+            ))
+            .with_children(|row| {
+                row.spawn((
+                    Text::new(format!("{} - {}", achievement.name, status)),
+                    game_assets.text_font(16.0),
+                    ScalableText::from_vw(1.0),
+                    TextColor(if earned { Color::srgb(0.3, 0.9, 0.3) } else { Color::WHITE }),
+                ));
+                row.spawn((
+                    Text::new(achievement.description.clone()),
+                    game_assets.text_font(12.0),
+                    ScalableText::from_vw(0.8),
+                    TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                ));
+            });
+        }
+    });
+}
+
+pub fn show_achievement_toasts(
+    mut commands: Commands,
+    mut earned: MessageReader<AchievementEarned>,
+    game_assets: Res<GameAssets>,
+) {
+    for achievement in earned.read() {
+        spawn_toast(
+            &mut commands,
+            &game_assets,
+            &format!("Achievement: {}", achievement.name),
+            &achievement.description,
+            Color::srgb(0.95, 0.85, 0.25),
+        );
+    }
+}
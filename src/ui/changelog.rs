@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::changelog::{Changelog, WhatsNew};
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::{BlocksWorldClicks, BlocksWorldScroll};
+
+#[derive(Component)]
+pub struct ChangelogPanel;
+
+#[derive(Component)]
+pub struct ChangelogTitle;
+
+#[derive(Component)]
+pub struct ChangelogList;
+
+#[derive(Component)]
+pub struct ChangelogCloseButton;
+
+/// "What's new" button in the settings panel, opens the full changelog
+#[derive(Component)]
+pub struct OpenChangelogButton;
+
+pub fn spawn_changelog_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                top: Val::Percent(15.0),
+                left: Val::Percent(30.0),
+                width: Val::Percent(40.0),
+                max_height: Val::Percent(65.0),
+                padding: UiRect::all(Val::Vw(0.8)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.4),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.95)),
+            GlobalZIndex(700),
+            ChangelogPanel,
+            BlocksWorldClicks,
+        ))
+        .with_children(|panel| {
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::SpaceBetween,
+                    ..default()
+                })
+                .with_children(|header| {
+                    header.spawn((
+                        Text::new("What's new"),
+                        game_assets.text_font(20.0),
+                        ScalableText::from_vw(1.4),
+                        TextColor(Color::srgb(0.95, 0.85, 0.25)),
+                        ChangelogTitle,
+                    ));
+                    header
+                        .spawn((
+                            Node {
+                                padding: UiRect::axes(Val::Vw(0.5), Val::Vw(0.2)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                            Interaction::None,
+                            ChangelogCloseButton,
+                        ))
+                        .with_children(|button| {
+                            button.spawn((
+                                Text::new("Close"),
+                                game_assets.text_font(14.0),
+                                ScalableText::from_vw(0.9),
+                                TextColor(Color::WHITE),
+                            ));
+                        });
+                });
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Vw(0.5),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                ChangelogList,
+                BlocksWorldScroll,
+            ));
+        });
+}
+
+pub fn handle_changelog_buttons(
+    open_query: Query<&Interaction, (Changed<Interaction>, With<OpenChangelogButton>)>,
+    close_query: Query<&Interaction, (Changed<Interaction>, With<ChangelogCloseButton>)>,
+    mut whats_new: ResMut<WhatsNew>,
+) {
+    if open_query.iter().any(|i| *i == Interaction::Pressed) {
+        whats_new.versions.clear();
+        whats_new.open = true;
+    }
+    if close_query.iter().any(|i| *i == Interaction::Pressed) {
+        whats_new.open = false;
+    }
+}
+
+/// Shows/hides the panel and fills it with the releases asked for
+pub fn update_changelog_panel(
+    mut commands: Commands,
+    whats_new: Res<WhatsNew>,
+    changelog: Option<Res<Changelog>>,
+    game_assets: Res<GameAssets>,
+    mut panel_query: Query<&mut Node, With<ChangelogPanel>>,
+    mut title_query: Query<&mut Text, With<ChangelogTitle>>,
+    list_query: Query<Entity, With<ChangelogList>>,
+) {
+    // no changelog file, nothing to show
+    let open = whats_new.open && changelog.is_some();
+    for mut node in panel_query.iter_mut() {
+        node.display = if open { Display::Flex } else { Display::None };
+    }
+    let (Some(changelog), true) = (changelog, open) else { return };
+    let Ok(list) = list_query.single() else { return };
+
+    for mut title in title_query.iter_mut() {
+        **title = if whats_new.versions.is_empty() { "Changelog" } else { "What's new" }.to_string();
+    }
+
+    let releases: Vec<_> = changelog
+        .releases
+        .iter()
+        .filter(|r| whats_new.versions.is_empty() || whats_new.versions.contains(&r.version))
+        .cloned()
+        .collect();
+    commands.entity(list).despawn_children().with_children(|list| {
+        for release in releases.iter() {
+            list.spawn((
+                Text::new(format!("v{} - {}", release.version, release.date)),
+                game_assets.text_font(16.0),
+                ScalableText::from_vw(1.0),
+                TextColor(Color::WHITE),
+            ));
+            for entry in release.entries.iter() {
+                list.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Vw(0.4),
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn((
+                        Text::new(entry.category.icon()),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.9),
+                        TextColor(entry.category.color()),
+                    ));
+                    row.spawn((
+                        Text::new(entry.text.clone()),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.9),
+                        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                    ));
+                });
+            }
+        }
+    });
+}
@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::camera::focus_camera_on_grid_pos;
+use crate::factions::ClusterUnlocked;
+use crate::grid::Grid;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::BlocksWorldClicks;
+
+/// How long the camera takes to glide over to an unlocked cluster
+const PAN_SECONDS: f32 = 1.2;
+/// The sim runs this fast while a ceremony is up, so nothing's missed but it's not frozen either
+const CEREMONY_TIME_SPEED: f32 = 0.25;
+
+/// Unlocks waiting their turn, so two at once don't fight over the camera
+#[derive(Resource, Default)]
+pub struct UnlockCeremonies {
+    queue: VecDeque<ClusterUnlocked>,
+    active: Option<ActiveCeremony>,
+}
+
+impl UnlockCeremonies {
+    /// A ceremony has the sim slowed down, speed controls leave the clock alone until it's over
+    pub fn is_running(&self) -> bool {
+        self.active.is_some()
+    }
+}
+
+struct ActiveCeremony {
+    unlock: ClusterUnlocked,
+    from: Vec3,
+    from_scale: f32,
+    elapsed: f32,
+    banner: Option<Entity>,
+    /// Sim speed from before the ceremony slowed it
+    restore_speed: f32,
+}
+
+/// Banner listing what a cluster unlock opened up
+#[derive(Component)]
+pub struct UnlockBanner;
+
+#[derive(Component)]
+pub struct UnlockBannerDismiss;
+
+pub fn queue_cluster_unlocks(
+    mut unlocks: MessageReader<ClusterUnlocked>,
+    mut ceremonies: ResMut<UnlockCeremonies>,
+    game_mode: Res<crate::game_mode::GameMode>,
+) {
+    for unlock in unlocks.read() {
+        // sandbox opens everything on the first frame, nobody wants a dozen banners
+        if game_mode.sandbox {
+            continue;
+        }
+        ceremonies.queue.push_back(unlock.clone());
+    }
+}
+
+/// Starts the next queued ceremony, pans the camera over and puts the banner up when it arrives
+pub fn run_unlock_ceremony(
+    mut commands: Commands,
+    mut ceremonies: ResMut<UnlockCeremonies>,
+    camera: Single<(&mut Transform, &mut Projection), With<Camera>>,
+    grid: Res<Grid>,
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut sfx: MessageWriter<crate::audio::PlaySfx>,
+    game_assets: Res<GameAssets>,
+) {
+    let (mut camera_transform, mut projection) = camera.into_inner();
+    let Projection::Orthographic(orthographic) = projection.as_mut() else { return };
+
+    if ceremonies.active.is_none() {
+        let Some(unlock) = ceremonies.queue.pop_front() else { return };
+        let restore_speed = virtual_time.relative_speed();
+        virtual_time.set_relative_speed(CEREMONY_TIME_SPEED);
+        sfx.write(crate::audio::PlaySfx(crate::audio::Sfx::Fanfare));
+        ceremonies.active = Some(ActiveCeremony {
+            unlock,
+            from: camera_transform.translation,
+            from_scale: orthographic.scale,
+            elapsed: 0.0,
+            banner: None,
+            restore_speed,
+        });
+    }
+    let Some(active) = ceremonies.active.as_mut() else { return };
+    if active.banner.is_some() {
+        return;
+    }
+
+    // where focus_camera_on_grid_pos would jump to, eased towards instead
+    let mut target_transform = *camera_transform;
+    let mut target_projection = orthographic.clone();
+    focus_camera_on_grid_pos(&active.unlock.center, &grid, &mut target_transform, &mut target_projection);
+
+    active.elapsed += real_time.delta_secs();
+    let t = (active.elapsed / PAN_SECONDS).clamp(0.0, 1.0);
+    let eased = t * t * (3.0 - 2.0 * t);
+    camera_transform.translation = active.from.lerp(target_transform.translation, eased);
+    orthographic.scale = active.from_scale + (target_projection.scale - active.from_scale) * eased;
+
+    if t >= 1.0 {
+        active.banner = Some(spawn_unlock_banner(&mut commands, &game_assets, &active.unlock));
+    }
+}
+
+fn spawn_unlock_banner(commands: &mut Commands, game_assets: &GameAssets, unlock: &ClusterUnlocked) -> Entity {
+    let mut lines = vec![format!(
+        "{} new source{}",
+        unlock.source_count,
+        if unlock.source_count == 1 { "" } else { "s" }
+    )];
+    if unlock.sink_count > 0 {
+        lines.push(format!("The {:?} sink is open for contracts", unlock.faction));
+    }
+    if !unlock.data_types.is_empty() {
+        let types: Vec<String> = unlock.data_types.iter().map(|t| format!("{:?}", t)).collect();
+        lines.push(format!("Expect: {}", types.join(", ")));
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Vh(12.0),
+                left: Val::Vw(35.0),
+                width: Val::Vw(30.0),
+                padding: UiRect::all(Val::Vw(1.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Vh(0.8),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.08, 0.08, 0.12, 0.95)),
+            BorderColor::all(game_assets.faction_color(unlock.faction)),
+            GlobalZIndex(800),
+            UnlockBanner,
+            BlocksWorldClicks,
+            Interaction::None,
+        ))
+        .with_children(|banner| {
+            banner.spawn((
+                Text::new(format!("{:?} territory unlocked!", unlock.faction)),
+                game_assets.text_font(24.0),
+                ScalableText::from_vw(1.8),
+                TextColor(Color::srgb(1.0, 0.85, 0.3)),
+            ));
+            for line in lines {
+                banner.spawn((
+                    Text::new(line),
+                    game_assets.text_font(16.0),
+                    ScalableText::from_vw(1.1),
+                    TextColor(Color::WHITE),
+                ));
+            }
+            banner.spawn((
+                Node {
+                    padding: UiRect::axes(Val::Vw(1.2), Val::Vw(0.4)),
+                    margin: UiRect::top(Val::Vh(0.6)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.2, 0.5, 0.2)),
+                Interaction::None,
+                UnlockBannerDismiss,
+            )).with_children(|button| {
+                button.spawn((
+                    Text::new("Nice"),
+                    game_assets.text_font(16.0),
+                    ScalableText::from_vw(1.1),
+                    TextColor(Color::WHITE),
+                ));
+            });
+        })
+        .id()
+}
+
+/// Closing the banner gives time back and lets the next ceremony go
+pub fn dismiss_unlock_banner(
+    mut commands: Commands,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<UnlockBannerDismiss>)>,
+    mut ceremonies: ResMut<UnlockCeremonies>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if !buttons.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    let Some(active) = ceremonies.active.take() else { return };
+    if let Some(banner) = active.banner {
+        commands.entity(banner).despawn();
+    }
+    virtual_time.set_relative_speed(active.restore_speed);
+}
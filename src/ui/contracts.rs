@@ -4,16 +4,23 @@ use crate::{
     grid::GridPosition,
     grid::Grid,
     ui::BlocksWorldScroll,
-    factory::buildings::sink::SinkBuilding,
+    factory::buildings::sink::{PortAccess, SinkAccess, SinkBuilding},
     ui::interactive_event::ScalableText,
     assets::GameAssets,
     factory::logical::Dataset,
+    market::MarketPrices,
+    palette::Palette,
 };
 use bevy::{
+    ecs::system::SystemParam,
     input::mouse::{MouseScrollUnit, MouseWheel},
     picking::hover::HoverMap,
+    platform::collections::HashMap,
 };
 use crate::camera::focus_camera_on_grid_pos;
+use crate::ui::sidebar_tabs::{spawn_tab_bar, tab_pane_node, SidebarTab, SidebarTabPane};
+use crate::trace::TraceLog;
+use crate::trace_sim;
 
 #[derive(Component)]
 pub struct ContractAcceptButton;
@@ -24,12 +31,45 @@ pub struct ContractRejectButton;
 #[derive(Component)]
 pub struct ViewSinkButton;
 
+/// On active cards, asks for confirmation before walking away from the contract
 #[derive(Component)]
-pub struct ContractEntityLink(Entity);
+pub struct ContractCancelButton;
+
+/// The "are you sure" popup for cancelling one contract
+#[derive(Component)]
+pub struct ContractCancelPopup(pub Entity);
+
+#[derive(Component)]
+pub struct ContractCancelConfirm;
+
+#[derive(Component)]
+pub struct ContractCancelDismiss;
+
+#[derive(Component)]
+pub struct ContractEntityLink(pub(crate) Entity);
+
+/// On an active contract the player pinned, its place among the pinned cards at the top of
+/// the sidebar, lowest first
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Pinned(pub u32);
+
+/// Pins or unpins the linked contract
+#[derive(Component)]
+pub struct ContractPinButton;
+
+/// Moves a pinned card one place up or down among the pinned ones
+#[derive(Component)]
+pub struct ContractPinMoveButton {
+    pub up: bool,
+}
 
 #[derive(Component)]
 pub struct ContractsSidebarRoot;
 
+/// The Contracts tab's pane, the cards are its only children
+#[derive(Component)]
+pub struct ContractsList;
+
 /// Marker component for data icons that need to be resized without replacing their Node component
 #[derive(Component)]
 pub struct NeedsContractResize;
@@ -48,10 +88,14 @@ fn get_contract_sort_priority(status: &ContractStatus, fulfillment: &ContractFul
             ContractFulfillmentStatus::Exceeding => 3,  // Fourth
         },
         ContractStatus::Pending => 1,                  // Second
+        ContractStatus::Completed => -1,               // on top while it's celebrating
         _ => 4,                                        // Last
     }
 }
 
+/// A contract whose priority changed has to keep it this long before its card moves
+const SORT_HOLD_SECONDS: f32 = 3.0;
+
 const LINE_HEIGHT: f32 = 21.;
 
 /// Injects scroll events into the UI hierarchy.
@@ -137,27 +181,41 @@ pub fn on_scroll_handler(
 }
 
 pub fn spawn_contracts_sidebar_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
-    // Right sidebar root node
-    commands.spawn((
-        Node {
-            position_type: PositionType::Absolute,
-            right: Val::Px(0.0),
-            left: Val::Auto,
-            top: Val::Px(45.0), // Start below the newsfeed (which is 64px tall)
-            bottom: Val::Percent(15.0), // Stop above the bottom bar (12% height)
-            width: Val::Vw(25.0),
-            flex_direction: FlexDirection::Column,
-            align_items: AlignItems::FlexStart,
-            justify_content: JustifyContent::FlexStart,
-            overflow: Overflow::scroll_y(),
-            align_self: AlignSelf::Stretch,
-            ..default()
-        },
-        BackgroundColor(Color::srgb(0.08, 0.08, 0.12)),
-        ContractsSidebarRoot,
-        BlocksWorldScroll
-    ));
-    
+    // Right sidebar root node, the tab bar over one pane per tab
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                left: Val::Auto,
+                top: Val::Px(crate::ui::sidebar::SIDEBAR_TOP_PX), // Start below the newsfeed (which is 64px tall)
+                bottom: Val::Percent(crate::ui::sidebar::SIDEBAR_BOTTOM_PCT), // Stop above the bottom bar (12% height)
+                width: Val::Vw(crate::ui::sidebar::SIDEBAR_WIDTH_VW),
+                flex_direction: FlexDirection::Column,
+                align_self: AlignSelf::Stretch,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.08, 0.08, 0.12)),
+            ContractsSidebarRoot,
+            BlocksWorldScroll,
+        ))
+        .with_children(|sidebar| {
+            spawn_tab_bar(sidebar, &game_assets);
+            sidebar.spawn((
+                tab_pane_node(SidebarTab::Contracts),
+                SidebarTabPane(SidebarTab::Contracts),
+                ContractsList,
+            ));
+            sidebar.spawn((
+                tab_pane_node(SidebarTab::Factions),
+                SidebarTabPane(SidebarTab::Factions),
+            ));
+            sidebar.spawn((
+                tab_pane_node(SidebarTab::EventLog),
+                SidebarTabPane(SidebarTab::EventLog),
+            ));
+        });
+
     // Spawn tooltip that will be shown on hover
     commands.spawn((
         Node {
@@ -182,406 +240,1040 @@ pub fn spawn_contracts_sidebar_ui(mut commands: Commands, game_assets: Res<GameA
     });
 }
 
+/// Which sidebar card belongs to which contract, so cards get patched rather than rebuilt
+#[derive(Default)]
+pub struct ContractCards {
+    cards: HashMap<Entity, ContractCardEntry>,
+    sort: HashMap<Entity, SortHold>,
+}
+
+/// The priority a card's sorted by, and the one it's been showing since when, if different
+struct SortHold {
+    shown: i32,
+    candidate: Option<(i32, f32)>,
+}
+
+impl SortHold {
+    /// Fulfillment flapping between Meeting and Failing waits out `SORT_HOLD_SECONDS`
+    /// before the card moves. Accepting or completing moves it straight away
+    fn update(&mut self, priority: i32, status: &ContractStatus, now: f32) -> i32 {
+        if priority == self.shown || *status != ContractStatus::Active {
+            self.shown = priority;
+            self.candidate = None;
+            return self.shown;
+        }
+        match self.candidate {
+            Some((candidate, since)) if candidate == priority => {
+                if now - since >= SORT_HOLD_SECONDS {
+                    self.shown = priority;
+                    self.candidate = None;
+                }
+            }
+            _ => self.candidate = Some((priority, now)),
+        }
+        self.shown
+    }
+}
+
+struct ContractCardEntry {
+    card: Entity,
+    layout: CardLayout,
+}
+
+/// The parts of a contract that decide what a card is made of. When it changes the card
+/// is respawned, everything else is edited in place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CardLayout {
+    Completed,
+    Pending { access: Option<SinkAccess>, expires: bool },
+    Active { volume_goal: bool, pinned: bool },
+}
+
+/// Root node of one contract's card
+#[derive(Component)]
+pub struct ContractCard;
+
+/// A line of card text that's kept up to date in place
+#[derive(Component)]
+pub struct ContractCardText {
+    pub(crate) contract: Entity,
+    pub(crate) field: CardField,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CardField {
+    Status,
+    Expires,
+    Fulfillment,
+    BaseIncome,
+    Income,
+    Earned,
+    Delivered,
+    ReputationFlash,
+    CompletedName,
+    CompletedBonus,
+}
+
+impl CardField {
+    /// Fields that move on their own timers rather than when status or fulfillment change
+    fn ticks(&self) -> bool {
+        matches!(
+            self,
+            CardField::Expires | CardField::Earned | CardField::ReputationFlash | CardField::CompletedName | CardField::CompletedBonus
+        )
+    }
+}
+
+/// Fill of a card's progress bar, resized in place
+#[derive(Component)]
+pub struct ContractCardBar {
+    pub(crate) contract: Entity,
+    pub(crate) volume: bool,
+}
+
+/// Everything a card reads besides the contract's own components
+#[derive(SystemParam)]
+pub struct ContractCardLookups<'w, 's> {
+    pub(crate) game_assets: Res<'w, GameAssets>,
+    asset_server: Res<'w, AssetServer>,
+    market: Res<'w, MarketPrices>,
+    associated_sink_query: Query<'w, 's, &'static AssociatedWithSink>,
+    sink_access_query: Query<'w, 's, &'static SinkAccess>,
+    earnings_query: Query<'w, 's, &'static crate::economics::ContractEarnings>,
+    infra_query: Query<'w, 's, &'static crate::economics::SinkInfraCost>,
+    share_query: Query<'w, 's, (Entity, &'static ContractStatus, &'static ContractFulfillment, &'static AssociatedWithSink)>,
+    highlight_query: Query<'w, 's, &'static crate::emissary::ContractHighlight>,
+    timeout_query: Query<'w, 's, &'static crate::contracts::ContractTimeout>,
+    reputation_query: Query<'w, 's, (&'static crate::factions::Faction, &'static crate::contracts::ContractReputation)>,
+    completed_query: Query<'w, 's, &'static crate::contracts::ContractCompleted>,
+    pinned_query: Query<'w, 's, &'static Pinned>,
+    pub(crate) palette: Res<'w, Palette>,
+}
+
+impl ContractCardLookups<'_, '_> {
+    pub(crate) fn sink_access(&self, contract: Entity) -> Option<SinkAccess> {
+        self.associated_sink_query
+            .get(contract)
+            .ok()
+            .and_then(|sink| self.sink_access_query.get(sink.0).ok())
+            .copied()
+    }
+
+    fn layout(&self, contract: Entity, status: &ContractStatus, fulfillment: &ContractFulfillment) -> CardLayout {
+        if self.completed_query.contains(contract) {
+            return CardLayout::Completed;
+        }
+        match status {
+            ContractStatus::Pending => CardLayout::Pending {
+                access: self.sink_access(contract),
+                expires: self.timeout_query.contains(contract),
+            },
+            _ => CardLayout::Active {
+                volume_goal: fulfillment.has_volume_goal(),
+                pinned: self.pinned_query.contains(contract),
+            },
+        }
+    }
+}
+
+/// One contract as the card sees it
+#[derive(Clone, Copy)]
+pub(crate) struct CardContract<'a> {
+    pub(crate) entity: Entity,
+    pub(crate) status: &'a ContractStatus,
+    pub(crate) desc: &'a ContractDescription,
+    pub(crate) fulfillment: &'a ContractFulfillment,
+    pub(crate) dataset: &'a Dataset,
+}
+
+pub(crate) fn card_color(status: &ContractStatus, fulfillment: &ContractFulfillment, palette: &Palette) -> Color {
+    match status {
+        ContractStatus::Pending => palette.warning_card,
+        ContractStatus::Active => match fulfillment.status {
+            ContractFulfillmentStatus::Exceeding => palette.exceeding_card,
+            ContractFulfillmentStatus::Meeting => palette.success_card,
+            ContractFulfillmentStatus::Failing => palette.danger_card,
+        },
+        _ => Color::srgb(0.15, 0.15, 0.18),
+    }
+}
+
+fn status_text_color(status: &ContractStatus, fulfillment: &ContractFulfillment, palette: &Palette) -> Color {
+    match status {
+        ContractStatus::Pending => palette.warning,
+        ContractStatus::Active => match fulfillment.status {
+            ContractFulfillmentStatus::Exceeding => palette.exceeding,
+            ContractFulfillmentStatus::Meeting => palette.success,
+            ContractFulfillmentStatus::Failing => palette.danger,
+        },
+        _ => Color::WHITE,
+    }
+}
+
+/// Shape next to the status so it doesn't only come across as a colour. Plain ASCII, the
+/// pixel font has nothing fancier
+fn fulfillment_symbol(status: ContractFulfillmentStatus) -> &'static str {
+    match status {
+        ContractFulfillmentStatus::Exceeding => "(+)",
+        ContractFulfillmentStatus::Meeting => "(=)",
+        ContractFulfillmentStatus::Failing => "/!\\",
+    }
+}
+
+/// Completed cards fade out over the last second before they're removed
+fn completed_alpha(contract: Entity, lookups: &ContractCardLookups) -> f32 {
+    lookups.completed_query.get(contract).map_or(1.0, |c| c.display.clamp(0.0, 1.0))
+}
+
+/// Text and color for one line of a card, shared by spawning and in-place updates
+fn card_field_text(field: CardField, contract: CardContract, lookups: &ContractCardLookups) -> (String, Color) {
+    let CardContract { entity, status, desc, fulfillment, dataset } = contract;
+    let market_multiplier = lookups.market.dataset_multiplier(dataset);
+    match field {
+        CardField::Status => {
+            let symbol = match status {
+                ContractStatus::Active => format!(" {}", fulfillment_symbol(fulfillment.status)),
+                _ => String::new(),
+            };
+            (format!("Status: {:?}{}", status, symbol), status_text_color(status, fulfillment, &lookups.palette))
+        }
+        CardField::Expires => {
+            let seconds = lookups.timeout_query.get(entity).map_or(0.0, |t| t.0.max(0.0).ceil());
+            (
+                format!("Expires in {:.0}s", seconds),
+                if seconds < 15.0 { lookups.palette.danger } else { Color::srgb(0.8, 0.8, 0.8) },
+            )
+        }
+        CardField::Fulfillment => (
+            format!("Fulfillment: {} {:?}", fulfillment_symbol(fulfillment.status), fulfillment.status),
+            status_text_color(status, fulfillment, &lookups.palette),
+        ),
+        CardField::BaseIncome => match status {
+            ContractStatus::Active => (
+                format!("Base income: {:.2} | Required: {:.2}", fulfillment.base_money, fulfillment.base_threshold),
+                Color::WHITE,
+            ),
+            _ => (
+                format!(
+                    "Base income: {:.2} (x{:.2} market) | Required: {:.2}",
+                    fulfillment.base_money, market_multiplier, fulfillment.base_threshold
+                ),
+                Color::WHITE,
+            ),
+        },
+        CardField::Income => (
+            format!(
+                "Income: {:.2} (x{:.2} market) | Throughput: {:.2}{}",
+                fulfillment.get_income() * market_multiplier as f64,
+                market_multiplier,
+                fulfillment.throughput,
+                fulfillment.shortfall_reason().map_or(String::new(), |why| format!(" ({})", why))
+            ),
+            Color::WHITE,
+        ),
+        CardField::Earned => {
+            // Lifetime earnings against this contract's share of the sink's infrastructure
+            let earned = lookups.earnings_query.get(entity).map_or(0.0, |e| e.0);
+            let infra = lookups.associated_sink_query.get(entity).ok().map_or(0.0, |sink| {
+                let sink_cost = lookups.infra_query.get(sink.0).map_or(0, |c| c.0);
+                crate::economics::contract_infra_share(entity, sink.0, sink_cost, &lookups.share_query)
+            });
+            let ratio = crate::economics::return_ratio(earned, infra);
+            (
+                format!(
+                    "Earned ${:.0} · est. infra ${:.0} · {}",
+                    earned,
+                    infra,
+                    ratio.map_or("-".to_string(), |r| format!("{:.1}x return", r))
+                ),
+                crate::economics::return_ratio_color(ratio),
+            )
+        }
+        CardField::Delivered => (
+            format!(
+                "Delivered: {:.0} / {:.0}",
+                fulfillment.delivered.min(fulfillment.total_required),
+                fulfillment.total_required
+            ),
+            Color::srgb(0.8, 0.8, 0.8),
+        ),
+        // always there, just empty and see-through between gains
+        CardField::ReputationFlash => match lookups.reputation_query.get(entity).ok().filter(|(_, r)| r.flash > 0.0) {
+            Some((_, reputation)) => {
+                let mut color = lookups.palette.success;
+                color.set_alpha((reputation.flash / crate::contracts::REPUTATION_FLASH_SECONDS).clamp(0.0, 1.0));
+                (format!("+{} rep", reputation.last_gain), color)
+            }
+            None => (String::new(), Color::NONE),
+        },
+        CardField::CompletedName => (desc.name.clone(), Color::srgba(1.0, 1.0, 1.0, completed_alpha(entity, lookups))),
+        CardField::CompletedBonus => (
+            format!("Completed! +${} bonus", lookups.completed_query.get(entity).map_or(0, |c| c.bonus)),
+            Color::srgba(1.0, 0.85, 0.3, completed_alpha(entity, lookups)),
+        ),
+    }
+}
+
+pub(crate) fn card_field(field: CardField, contract: CardContract, lookups: &ContractCardLookups) -> (Text, TextColor, ContractCardText) {
+    let (text, color) = card_field_text(field, contract, lookups);
+    (Text::new(text), TextColor(color), ContractCardText { contract: contract.entity, field })
+}
+
+/// Throughput over twice the threshold, and share of the volume goal delivered
+pub(crate) fn card_bar_width(volume: bool, fulfillment: &ContractFulfillment) -> Val {
+    let progress = if volume {
+        fulfillment.volume_progress()
+    } else {
+        (fulfillment.throughput / (fulfillment.base_threshold * 2.0)).min(1.0).max(0.0)
+    };
+    Val::Vw(13.5 * progress as f32)
+}
+
+fn card_border(contract: Entity, layout: CardLayout, lookups: &ContractCardLookups) -> (UiRect, Color) {
+    if layout == CardLayout::Completed {
+        return (UiRect::default(), Color::NONE);
+    }
+    let highlighted = lookups.highlight_query.contains(contract);
+    let highlight_phase = lookups.highlight_query.get(contract).map_or(0.0, |h| h.0);
+    (
+        UiRect::all(Val::Px(if highlighted { 3.0 } else { 0.0 })),
+        // pulsing gold edge on an offer an emissary just handed over
+        Color::srgba(1.0, 0.85, 0.3, 0.5 + 0.5 * (highlight_phase * 6.0).sin().abs()),
+    )
+}
+
+fn card_background(contract: CardContract, layout: CardLayout, lookups: &ContractCardLookups) -> Color {
+    match layout {
+        CardLayout::Completed => Color::srgba(0.45, 0.38, 0.10, completed_alpha(contract.entity, lookups)),
+        _ => card_color(contract.status, contract.fulfillment, &lookups.palette),
+    }
+}
+
+/// Keeps one card per shown contract: spawns cards for new contracts, despawns the ones that
+/// left, patches text, colors and bars in place and only reorders when the order changes.
+/// Pinned cards go first in their pinned order, the rest by priority with a hold on changes.
+/// The card list is never rebuilt, so its scroll position stays put.
 pub fn update_contracts_sidebar_ui(
     mut commands: Commands,
-    sidebar_query: Query<Entity, With<ContractsSidebarRoot>>,
-    contract_query: Query<(Entity, &Contract, &ContractStatus, &ContractDescription, &ContractFulfillment, &Dataset)>,
+    time: Res<Time<Real>>,
+    mut cards: Local<ContractCards>,
+    sidebar_query: Query<Entity, With<ContractsList>>,
+    contract_query: Query<
+        (Entity, Ref<ContractStatus>, &ContractDescription, Ref<ContractFulfillment>, &Dataset),
+        (With<Contract>, Without<crate::emissary::AwaitingEmissary>),
+    >,
     children_query: Query<&Children>,
-    game_assets: Res<GameAssets>,
-    asset_server: Res<AssetServer>,
+    lookups: ContractCardLookups,
+    mut card_query: Query<(&ContractEntityLink, &mut BackgroundColor, &mut BorderColor, &mut Node), With<ContractCard>>,
+    mut text_query: Query<(&ContractCardText, &mut Text, &mut TextColor)>,
+    mut bar_query: Query<(&ContractCardBar, &mut Node), Without<ContractCard>>,
 ) {
     let Ok(sidebar) = sidebar_query.single() else { return; };
 
-    // Remove all children (cards) from sidebar before re-adding
-    if let Ok(children) = children_query.get(sidebar) {
-        let children_vec: Vec<Entity> = children.to_vec();
-        for child in children_vec {
-            commands.entity(child).despawn();
-        }
-    }
-
     // Collect and sort contracts by priority
     let mut contracts: Vec<_> = contract_query.iter()
-        .filter(|(_, _, status, _, _, _)| matches!(status, ContractStatus::Pending | ContractStatus::Active))
+        .filter(|(entity, status, _, _, _)| {
+            matches!(**status, ContractStatus::Pending | ContractStatus::Active)
+                || (**status == ContractStatus::Completed && lookups.completed_query.contains(*entity))
+        })
         .collect();
-    contracts.sort_by_key(|(_, _, status, _, fulfillment, _)| get_contract_sort_priority(status, fulfillment));
-
-    // Add a card for each sorted contract
-    for (contract_entity, _contract, status, desc, fulfillment, dataset) in contracts {
-        if matches!(status, ContractStatus::Pending | ContractStatus::Active) {
-            // Card background color
-            let card_color = match status {
-                ContractStatus::Pending => Color::srgb(0.25, 0.22, 0.10), // gold-brown for pending
-                ContractStatus::Active => match fulfillment.status {
-                    ContractFulfillmentStatus::Exceeding => Color::srgb(0.18, 0.32, 0.60), // blue for exceeding
-                    ContractFulfillmentStatus::Meeting => Color::srgb(0.18, 0.45, 0.18),   // green for meeting
-                    ContractFulfillmentStatus::Failing => Color::srgb(0.45, 0.18, 0.18),   // red for failing
+    let now = time.elapsed_secs();
+    let mut sort_keys = HashMap::new();
+    for (entity, status, _, fulfillment, _) in contracts.iter() {
+        let priority = get_contract_sort_priority(status, fulfillment);
+        let held = cards
+            .sort
+            .entry(*entity)
+            .or_insert(SortHold { shown: priority, candidate: None })
+            .update(priority, status, now);
+        let key = match lookups.pinned_query.get(*entity) {
+            Ok(pinned) if **status == ContractStatus::Active => (0, pinned.0 as i32),
+            _ => (1, held),
+        };
+        sort_keys.insert(*entity, key);
+    }
+    contracts.sort_by_key(|(entity, ..)| sort_keys[entity]);
+
+    // Cards whose contract is gone or no longer shown
+    let shown: Vec<Entity> = contracts.iter().map(|(entity, ..)| *entity).collect();
+    cards.cards.retain(|contract, entry| {
+        let keep = shown.contains(contract);
+        if !keep {
+            commands.entity(entry.card).despawn();
+        }
+        keep
+    });
+    cards.sort.retain(|contract, _| shown.contains(contract));
+
+    let mut ordered = Vec::with_capacity(contracts.len());
+    for (entity, status, desc, fulfillment, dataset) in contracts.iter() {
+        let contract = CardContract { entity: *entity, status, desc, fulfillment, dataset };
+        let layout = lookups.layout(*entity, status, fulfillment);
+        let entry = cards.cards.get(entity);
+        let card = match entry {
+            Some(entry) if entry.layout == layout => entry.card,
+            // new, or changed shape (accepted, completed, sink access moved): rebuild just this one
+            _ => {
+                if let Some(entry) = entry {
+                    commands.entity(entry.card).despawn();
+                }
+                let card = spawn_contract_card(&mut commands, &lookups, contract, layout);
+                cards.cards.insert(*entity, ContractCardEntry { card, layout });
+                card
+            }
+        };
+        ordered.push(card);
+    }
+
+    let current: &[Entity] = children_query.get(sidebar).map_or(&[][..], |c| &**c);
+    if current != ordered.as_slice() {
+        commands.entity(sidebar).replace_children(&ordered);
+    }
+
+    let status_changed = |contract: Entity| {
+        contract_query
+            .get(contract)
+            .is_ok_and(|(_, status, _, fulfillment, _)| status.is_changed() || fulfillment.is_changed())
+            || lookups.market.is_changed()
+            || lookups.palette.is_changed()
+    };
+
+    for (link, mut background, mut border_color, mut node) in card_query.iter_mut() {
+        let Ok((entity, status, desc, fulfillment, dataset)) = contract_query.get(link.0) else { continue };
+        let Some(entry) = cards.cards.get(&entity) else { continue };
+        let contract = CardContract { entity, status: &status, desc, fulfillment: &fulfillment, dataset };
+        background.set_if_neq(BackgroundColor(card_background(contract, entry.layout, &lookups)));
+        let (border, color) = card_border(entity, entry.layout, &lookups);
+        if node.border != border {
+            node.border = border;
+        }
+        border_color.set_if_neq(BorderColor::all(color));
+    }
+
+    for (part, mut text, mut color) in text_query.iter_mut() {
+        if !part.field.ticks() && !status_changed(part.contract) {
+            continue;
+        }
+        let Ok((entity, status, desc, fulfillment, dataset)) = contract_query.get(part.contract) else { continue };
+        let contract = CardContract { entity, status: &status, desc, fulfillment: &fulfillment, dataset };
+        let (new_text, new_color) = card_field_text(part.field, contract, &lookups);
+        if text.0 != new_text {
+            text.0 = new_text;
+        }
+        color.set_if_neq(TextColor(new_color));
+    }
+
+    for (bar, mut node) in bar_query.iter_mut() {
+        if !status_changed(bar.contract) {
+            continue;
+        }
+        let Ok((_, _, _, fulfillment, _)) = contract_query.get(bar.contract) else { continue };
+        let width = card_bar_width(bar.volume, &fulfillment);
+        if node.width != width {
+            node.width = width;
+        }
+    }
+}
+
+/// Gold head on a short stem, next to the name of a pinned contract
+fn spawn_pin_icon(parent: &mut ChildSpawnerCommands) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        })
+        .with_children(|pin| {
+            pin.spawn((
+                Node {
+                    width: Val::Vw(0.7),
+                    height: Val::Vw(0.7),
+                    ..default()
                 },
-                _ => Color::srgb(0.15, 0.15, 0.18),
-            };
-            // Status text color
-            let status_text_color = match status {
-                ContractStatus::Pending => Color::srgb(0.95, 0.85, 0.25), // yellow for pending
-                ContractStatus::Active => match fulfillment.status {
-                    ContractFulfillmentStatus::Exceeding => Color::srgb(0.45, 0.65, 1.0), // light blue
-                    ContractFulfillmentStatus::Meeting => Color::srgb(0.3, 0.9, 0.3),     // bright green
-                    ContractFulfillmentStatus::Failing => Color::srgb(1.0, 0.3, 0.3),     // bright red
+                BorderRadius::MAX,
+                BackgroundColor(Color::srgb(0.95, 0.8, 0.25)),
+            ));
+            pin.spawn((
+                Node {
+                    width: Val::Px(2.0),
+                    height: Val::Vw(0.5),
+                    ..default()
                 },
-                _ => Color::WHITE,
-            };
+                BackgroundColor(Color::srgb(0.75, 0.75, 0.75)),
+            ));
+        });
+}
+
+/// A small button in a card header, same look as View Sink
+fn spawn_card_button(
+    parent: &mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    label: &str,
+    color: Color,
+    bundle: impl Bundle,
+) {
+    parent
+        .spawn((
+            Node {
+                padding: UiRect::all(Val::Vw(0.45)),
+                ..default()
+            },
+            BackgroundColor(color),
+            Interaction::None,
+            bundle,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(1.5),
+                TextColor(Color::WHITE),
+                Node::default(),
+            ));
+        });
+}
+
+fn spawn_contract_card(commands: &mut Commands, lookups: &ContractCardLookups, contract: CardContract, layout: CardLayout) -> Entity {
+    let CardContract { entity: contract_entity, status, desc, dataset, .. } = contract;
+    let game_assets = &lookups.game_assets;
+
+    if layout == CardLayout::Completed {
+        return commands.spawn((
+            Node {
+                margin: UiRect::new(Val::Vw(0.3), Val::Vw(0.3), Val::Vw(0.15), Val::Vw(0.15)),
+                padding: UiRect::all(Val::Vw(1.2)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexStart,
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(card_background(contract, layout, lookups)),
+            BorderColor::all(Color::NONE),
+            ContractCard,
+            ContractEntityLink(contract_entity),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                card_field(CardField::CompletedName, contract, lookups),
+                game_assets.text_font(20.0),
+                ScalableText::from_vw(2.2),
+            ));
+            parent.spawn((
+                card_field(CardField::CompletedBonus, contract, lookups),
+                game_assets.text_font(14.0),
+                ScalableText::from_vw(1.6),
+            ));
+        })
+        .id();
+    }
+
+    let sink_access = lookups.sink_access(contract_entity);
+    // unknown access (not computed yet) shouldn't block accepting
+    let accessible = sink_access.is_none_or(|access| access.has_access());
+
+    // First, spawn all data type icons with augmentation effects BEFORE creating the card
+    let mut data_types: Vec<_> = dataset.contents.keys().cloned().collect();
+    data_types.sort();
+    
+    let mut data_icon_entities = Vec::new();
+    let mut aug_indicator_entities = Vec::new();
+    use crate::factory::source_visuals::spawn_data_type_with_augmentations;
+    
+    for (index, data_type) in data_types.iter().enumerate() {
+        if let Some(attributes) = dataset.contents.get(data_type) {
+            // Calculate position for this icon
+            let x_offset = index as f32 * 22.0;
+            let position = Vec3::new(x_offset, 0.0, 0.0);
             
-            // First, spawn all data type icons with augmentation effects BEFORE creating the card
-            let mut data_types: Vec<_> = dataset.contents.keys().cloned().collect();
-            data_types.sort();
+            // This function handles BOTH augmented indicator AND scanning flash effect!
+            let (icon_entity, aug_entity) = spawn_data_type_with_augmentations(
+                commands,
+                *data_type,
+                attributes.clone(),
+                position,
+                true, // is_ui = true
+                game_assets,
+                &lookups.asset_server,
+            );
             
-            let mut data_icon_entities = Vec::new();
-            let mut aug_indicator_entities = Vec::new();
-            use crate::factory::source_visuals::spawn_data_type_with_augmentations;
+            // Mark this icon for resizing - we'll resize it after spawn without replacing Node
+            commands.entity(icon_entity).insert(NeedsContractResize);
             
-            for (index, data_type) in data_types.iter().enumerate() {
-                if let Some(attributes) = dataset.contents.get(data_type) {
-                    // Calculate position for this icon
-                    let x_offset = index as f32 * 22.0;
-                    let position = Vec3::new(x_offset, 0.0, 0.0);
-                    
-                    // This function handles BOTH augmented indicator AND scanning flash effect!
-                    let (icon_entity, aug_entity) = spawn_data_type_with_augmentations(
-                        &mut commands,
-                        *data_type,
-                        attributes.clone(),
-                        position,
-                        true, // is_ui = true
-                        &game_assets,
-                        &asset_server,
-                    );
-                    
-                    // Mark this icon for resizing - we'll resize it after spawn without replacing Node
-                    commands.entity(icon_entity).insert(NeedsContractResize);
-                    
-                    data_icon_entities.push(icon_entity);
-                    
-                    // Collect augmented indicator entities if they exist
-                    if let Some(aug_indicator) = aug_entity {
-                        aug_indicator_entities.push((icon_entity, aug_indicator));
-                    }
-                }
-            }
+            data_icon_entities.push(icon_entity);
             
-            // Now create the card and add the icons to it
-            let card = commands.spawn((
+            // Collect augmented indicator entities if they exist
+            if let Some(aug_indicator) = aug_entity {
+                aug_indicator_entities.push((icon_entity, aug_indicator));
+            }
+        }
+    }
+
+    let (border, border_color) = card_border(contract_entity, layout, lookups);
+    let pinned = matches!(layout, CardLayout::Active { pinned: true, .. });
+
+    // Now create the card and add the icons to it
+    commands.spawn((
+        Node {
+            margin: UiRect::new(Val::Vw(0.3), Val::Vw(0.3), Val::Vw(0.15), Val::Vw(0.15)),
+            padding: UiRect::all(Val::Vw(1.2)),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexStart,
+            width: Val::Percent(100.0), // take full width of sidebar
+            position_type: PositionType::Relative,
+            border,
+            ..default()
+        },
+        BackgroundColor(card_background(contract, layout, lookups)),
+        BorderColor::all(border_color),
+        ContractCard,
+        ContractEntityLink(contract_entity),
+    ))
+    .with_children(|parent| {
+        
+        if let ContractStatus::Active = status {
+            // Create a horizontal container for the title and view sink button
+            parent.spawn((
                 Node {
-                    margin: UiRect::new(Val::Vw(0.3), Val::Vw(0.3), Val::Vw(0.15), Val::Vw(0.15)),
-                    padding: UiRect::all(Val::Vw(1.2)),
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::FlexStart,
-                    width: Val::Percent(100.0), // take full width of sidebar
-                    position_type: PositionType::Relative,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    width: Val::Percent(100.0),
+                    margin: UiRect::bottom(Val::Vw(0.6)),
                     ..default()
                 },
-                BackgroundColor(card_color),
-            ))
-            .with_children(|parent| {
-                
-                if let ContractStatus::Active = status {
-                    // Create a horizontal container for the title and view sink button
-                    parent.spawn((
+                BackgroundColor(Color::NONE),
+            )).with_children(|header| {
+                // Left side: icons + contract name
+                header.spawn((
+                    Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Vw(0.4),
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                )).with_children(|left_container| {
+                    if pinned {
+                        spawn_pin_icon(left_container);
+                    }
+
+                    // Dataset icons container
+                    let dataset_container = left_container.spawn((
                         Node {
                             display: Display::Flex,
                             flex_direction: FlexDirection::Row,
-                            justify_content: JustifyContent::SpaceBetween,
-                            align_items: AlignItems::Center,
-                            width: Val::Percent(100.0),
-                            margin: UiRect::bottom(Val::Vw(0.6)),
+                            column_gap: Val::Vw(0.2),
                             ..default()
                         },
                         BackgroundColor(Color::NONE),
-                    )).with_children(|header| {
-                        // Left side: icons + contract name
-                        header.spawn((
-                            Node {
-                                display: Display::Flex,
-                                flex_direction: FlexDirection::Row,
-                                align_items: AlignItems::Center,
-                                column_gap: Val::Vw(0.4),
-                                ..default()
-                            },
-                            BackgroundColor(Color::NONE),
-                        )).with_children(|left_container| {
-                            // Dataset icons container
-                            let dataset_container = left_container.spawn((
-                                Node {
-                                    display: Display::Flex,
-                                    flex_direction: FlexDirection::Row,
-                                    column_gap: Val::Vw(0.2),
-                                    ..default()
-                                },
-                                BackgroundColor(Color::NONE),
-                                Interaction::None,
-                                DatasetTooltip {
-                                    dataset: dataset.clone(),
-                                },
-                            )).id();
-                            
-                            // Add all the pre-spawned data icons as children
-                            for icon_entity in &data_icon_entities {
-                                left_container.commands().entity(dataset_container).add_child(*icon_entity);
-                            }
-                            
-                            // Add augmented indicators as children of their respective icons
-                            for (icon_entity, aug_indicator) in &aug_indicator_entities {
-                                left_container.commands().entity(*icon_entity).add_child(*aug_indicator);
-                            }
-                            
-                            // Contract name
-                            left_container.spawn((
-                                Text::new(&desc.name),
-                                game_assets.text_font(20.0),
-                                ScalableText::from_vw(2.2),
-                                TextColor(Color::WHITE),
-                                Node { ..default() },
-                            ));
-                        });
-                        
-                        // View Sink button on the right
-                        header.spawn((
+                        Interaction::None,
+                        DatasetTooltip {
+                            dataset: dataset.clone(),
+                        },
+                    )).id();
+                    
+                    // Add all the pre-spawned data icons as children
+                    for icon_entity in &data_icon_entities {
+                        left_container.commands().entity(dataset_container).add_child(*icon_entity);
+                    }
+                    
+                    // Add augmented indicators as children of their respective icons
+                    for (icon_entity, aug_indicator) in &aug_indicator_entities {
+                        left_container.commands().entity(*icon_entity).add_child(*aug_indicator);
+                    }
+
+                    // who this builds reputation with
+                    if let Some((atlas_id, index)) = lookups.reputation_query
+                        .get(contract_entity)
+                        .ok()
+                        .and_then(|(faction, _)| game_assets.faction_icon(*faction, crate::assets::IconSize::Small))
+                    {
+                        let (texture, layout) = game_assets.get_atlas(atlas_id);
+                        left_container.spawn((
+                            ImageNode::from_atlas_image(texture, TextureAtlas { layout, index }),
                             Node {
-                                padding: UiRect::all(Val::Vw(0.45)),
+                                width: Val::Vw(1.3),
+                                height: Val::Vw(1.3),
                                 ..default()
                             },
-                            BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
-                            ViewSinkButton,
-                            ContractEntityLink(contract_entity),
-                            Interaction::None,
-                        )).with_children(|button| {
-                            button.spawn((
-                                Text::new("View Sink"),
-                                game_assets.text_font(12.0),
-                                ScalableText::from_vw(1.5),
-                                TextColor(Color::WHITE),
-                                Node::default()
+                        ));
+                    }
+
+                    // Contract name
+                    left_container.spawn((
+                        Text::new(&desc.name),
+                        game_assets.text_font(20.0),
+                        ScalableText::from_vw(2.2),
+                        TextColor(Color::WHITE),
+                        // allow long names to wrap instead of pushing the button off the card
+                        Node { flex_shrink: 1.0, min_width: Val::Px(0.0), ..default() },
+                    ));
+
+                    left_container.spawn((
+                        card_field(CardField::ReputationFlash, contract, lookups),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(1.4),
+                    ));
+                });
+                
+                // View Sink and Cancel buttons on the right
+                header.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Vw(0.3),
+                    ..default()
+                }).with_children(|buttons| {
+                    if pinned {
+                        for (label, up) in [("^", true), ("v", false)] {
+                            spawn_card_button(buttons, game_assets, label, Color::srgb(0.3, 0.3, 0.3), (
+                                ContractPinMoveButton { up },
+                                ContractEntityLink(contract_entity),
                             ));
-                        });
+                        }
+                    }
+                    spawn_card_button(
+                        buttons,
+                        game_assets,
+                        if pinned { "Unpin" } else { "Pin" },
+                        Color::srgb(0.45, 0.38, 0.15),
+                        (ContractPinButton, ContractEntityLink(contract_entity)),
+                    );
+                    buttons.spawn((
+                        Node {
+                            padding: UiRect::all(Val::Vw(0.45)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                        ViewSinkButton,
+                        ContractEntityLink(contract_entity),
+                        Interaction::None,
+                    )).with_children(|button| {
+                        button.spawn((
+                            Text::new("View Sink"),
+                            game_assets.text_font(12.0),
+                            ScalableText::from_vw(1.5),
+                            TextColor(Color::WHITE),
+                            Node::default()
+                        ));
                     });
-                } else {
-                    // Pending contracts: icons + name in horizontal row
-                    parent.spawn((
+                    buttons.spawn((
                         Node {
-                            display: Display::Flex,
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            column_gap: Val::Vw(0.4),
-                            margin: UiRect::bottom(Val::Vw(0.6)),
+                            padding: UiRect::all(Val::Vw(0.45)),
                             ..default()
                         },
-                        BackgroundColor(Color::NONE),
-                    )).with_children(|left_container| {
-                        // Dataset icons container
-                        let dataset_container = left_container.spawn((
-                            Node {
-                                display: Display::Flex,
-                                flex_direction: FlexDirection::Row,
-                                column_gap: Val::Vw(0.2),
-                                ..default()
-                            },
-                            BackgroundColor(Color::NONE),
-                            Interaction::None,
-                            DatasetTooltip {
-                                dataset: dataset.clone(),
-                            },
-                        )).id();
-                        
-                        // Add all the pre-spawned data icons as children
-                        for icon_entity in &data_icon_entities {
-                            left_container.commands().entity(dataset_container).add_child(*icon_entity);
-                        }
-                        
-                        // Add augmented indicators as children of their respective icons
-                        for (icon_entity, aug_indicator) in &aug_indicator_entities {
-                            left_container.commands().entity(*icon_entity).add_child(*aug_indicator);
-                        }
-                        
-                        // Contract name
-                        left_container.spawn((
-                            Text::new(&desc.name),
-                            game_assets.text_font(20.0),
-                            ScalableText::from_vw(2.2),
+                        BackgroundColor(Color::srgb(0.5, 0.2, 0.2)),
+                        ContractCancelButton,
+                        ContractEntityLink(contract_entity),
+                        Interaction::None,
+                    )).with_children(|button| {
+                        button.spawn((
+                            Text::new("Cancel"),
+                            game_assets.text_font(12.0),
+                            ScalableText::from_vw(1.5),
                             TextColor(Color::WHITE),
-                            Node { ..default() },
+                            Node::default()
                         ));
                     });
+                });
+            });
+        } else {
+            // Pending contracts: icons + name in horizontal row
+            parent.spawn((
+                Node {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Vw(0.4),
+                    margin: UiRect::bottom(Val::Vw(0.6)),
+                    ..default()
+                },
+                BackgroundColor(Color::NONE),
+            )).with_children(|left_container| {
+                // Dataset icons container
+                let dataset_container = left_container.spawn((
+                    Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Vw(0.2),
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    Interaction::None,
+                    DatasetTooltip {
+                        dataset: dataset.clone(),
+                    },
+                )).id();
+                
+                // Add all the pre-spawned data icons as children
+                for icon_entity in &data_icon_entities {
+                    left_container.commands().entity(dataset_container).add_child(*icon_entity);
                 }
+                
+                // Add augmented indicators as children of their respective icons
+                for (icon_entity, aug_indicator) in &aug_indicator_entities {
+                    left_container.commands().entity(*icon_entity).add_child(*aug_indicator);
+                }
+                
+                // Contract name
+                left_container.spawn((
+                    Text::new(&desc.name),
+                    game_assets.text_font(20.0),
+                    ScalableText::from_vw(2.2),
+                    TextColor(Color::WHITE),
+                    Node { flex_shrink: 1.0, min_width: Val::Px(0.0), ..default() },
+                ));
+            });
+        }
+        parent.spawn((
+            card_field(CardField::Status, contract, lookups),
+            game_assets.text_font(12.0),
+            ScalableText::from_vw(1.5),
+            Node { ..default() },
+        ));
+        if let CardLayout::Pending { expires: true, .. } = layout {
+            parent.spawn((
+                card_field(CardField::Expires, contract, lookups),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(1.5),
+                Node { ..default() },
+            ));
+        }
+        if let CardLayout::Active { volume_goal, .. } = layout {
+            parent.spawn((
+                card_field(CardField::Fulfillment, contract, lookups),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(1.5),
+                Node { ..default() },
+            ));
+
+            // Add base money and throughput info
+            parent.spawn((
+                card_field(CardField::BaseIncome, contract, lookups),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.7),
+                Node { ..default() },
+            ));
+
+            // Add current money and throughput info
+            parent.spawn((
+                card_field(CardField::Income, contract, lookups),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(1.5),
+                Node { ..default() },
+            ));
+
+            parent.spawn((
+                card_field(CardField::Earned, contract, lookups),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.7),
+                Node { ..default() },
+            ));
+
+            // Progress bar for throughput over threshold
+            parent.spawn((
+                Node {
+                    width: Val::Vw(13.5),
+                    height: Val::Vh(1.5),
+                    position_type: PositionType::Relative,
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.18, 0.18, 0.18)),
+            ))
+            .with_children(|bar| {
+                // Progress fill
+                bar.spawn((
+                    Node {
+                        width: card_bar_width(false, contract.fulfillment),
+                        height: Val::Vh(1.5),
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.7, 0.3)),
+                    ContractCardBar { contract: contract_entity, volume: false },
+                ));
+                
+                // Threshold line
+                bar.spawn((
+                    Node {
+                        width: Val::Px(1.0),
+                        height: Val::Vh(1.5),
+                        position_type: PositionType::Absolute,
+                        left: Val::Vw(6.75), // 50% of 13.5vw
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(1., 1., 1., 0.4)), // Semi-transparent white
+                ));
+            });
+
+            // Cumulative volume, only for contracts that actually end
+            if volume_goal {
                 parent.spawn((
-                    Text::new(format!("Status: {:?}", status)),
+                    card_field(CardField::Delivered, contract, lookups),
                     game_assets.text_font(12.0),
-                    ScalableText::from_vw(1.5),
-                    TextColor(status_text_color),
-                    Node { ..default() },
+                    ScalableText::from_vw(1.2),
+                    Node { margin: UiRect::top(Val::Vw(0.4)), ..default() },
                 ));
-                if let ContractStatus::Active = status {
-                    parent.spawn((
-                        Text::new(format!("Fulfillment: {:?}", fulfillment.status)),
-                        game_assets.text_font(12.0),
-                        ScalableText::from_vw(1.5),
-                        TextColor(status_text_color),
-                        Node { ..default() },
+                parent.spawn((
+                    Node {
+                        width: Val::Vw(13.5),
+                        height: Val::Vh(0.8),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.18, 0.18, 0.18)),
+                ))
+                .with_children(|bar| {
+                    bar.spawn((
+                        Node {
+                            width: card_bar_width(true, contract.fulfillment),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.95, 0.8, 0.3)),
+                        ContractCardBar { contract: contract_entity, volume: true },
                     ));
+                });
+            }
+        } else {
+            // Add base money and throughput info
+            parent.spawn((
+                card_field(CardField::BaseIncome, contract, lookups),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(1.5),
+                Node { ..default() },
+            ));
 
-                    // Add base money and throughput info
-                    parent.spawn((
-                        Text::new(format!(
-                            "Base income: {:.2} | Required: {:.2}",
-                            fulfillment.base_money, fulfillment.base_threshold
-                        )),
-                        game_assets.text_font(12.0),
-                        ScalableText::from_vw(0.7),
+            // Which sides of the sink can actually be routed to
+            if let Some(access) = sink_access {
+                parent.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Vw(0.4),
+                    margin: UiRect::top(Val::Vw(0.4)),
+                    ..default()
+                }).with_children(|row| {
+                    spawn_sink_access_schematic(row, &access, &lookups.palette);
+                    if !accessible {
+                        row.spawn((
+                            Text::new("No access - every side of this sink is blocked"),
+                            game_assets.text_font(12.0),
+                            ScalableText::from_vw(1.2),
+                            TextColor(Color::srgb(1.0, 0.3, 0.3)),
+                            Node { flex_shrink: 1.0, min_width: Val::Px(0.0), ..default() },
+                        ));
+                    }
+                });
+            }
+            // Add accept/reject buttons
+            parent.spawn((
+                Node {
+                    margin: UiRect::top(Val::Vw(0.6)),
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::SpaceBetween,
+                    width: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::NONE),
+            )).with_children(|buttons| {
+                // Accept button
+                buttons.spawn((
+                    Node {
+                        padding: UiRect::all(Val::Vw(0.6)),
+                        margin: UiRect::right(Val::Vw(0.6)),
+                        ..default()
+                    },
+                    BackgroundColor(if accessible {
+                        Color::srgb(0.2, 0.6, 0.2)
+                    } else {
+                        Color::srgb(0.3, 0.3, 0.3)
+                    }),
+                    ContractAcceptButton,
+                    ContractEntityLink(contract_entity),
+                    Interaction::None,
+                )).with_children(|button| {
+                    button.spawn((
+                        Text::new("Y"),
+                        game_assets.text_font(16.0),
+                        ScalableText::from_vw(2.0),
                         TextColor(Color::WHITE),
-                        Node { ..default() },
+                        Node::default()
                     ));
+                });
 
-                    // Add current money and throughput info
-                    parent.spawn((
-                        Text::new(format!(
-                            "Income: {:.2} | Throughput: {:.2}",
-                            fulfillment.get_income(), fulfillment.throughput
-                        )),
-                        game_assets.text_font(12.0),
-                        ScalableText::from_vw(1.5),
+                // View Sink button
+                buttons.spawn((
+                    Node {
+                        padding: UiRect::all(Val::Vw(0.6)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                    ViewSinkButton,
+                    ContractEntityLink(contract_entity),
+                    Interaction::None,
+                )).with_children(|button| {
+                    button.spawn((
+                        Text::new("View Sink"),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(1.8),
                         TextColor(Color::WHITE),
-                        Node { ..default() },
+                        Node::default()
                     ));
+                });
 
-                    // Progress bar for throughput over threshold
-                    let progress = (fulfillment.throughput / (fulfillment.base_threshold * 2.0)).min(1.0).max(0.0);
-                    parent.spawn((
-                        Node {
-                            width: Val::Vw(13.5),
-                            height: Val::Vh(1.5),
-                            position_type: PositionType::Relative,
-                            ..default()
-                        },
-                        BackgroundColor(Color::srgb(0.18, 0.18, 0.18)),
-                    ))
-                    .with_children(|bar| {
-                        // Progress fill
-                        bar.spawn((
-                            Node {
-                                width: Val::Vw(13.5 * progress as f32),
-                                height: Val::Vh(1.5),
-                                position_type: PositionType::Absolute,
-                                left: Val::Px(0.0),
-                                ..default()
-                            },
-                            BackgroundColor(Color::srgb(0.3, 0.7, 0.3)),
-                        ));
-                        
-                        // Threshold line
-                        bar.spawn((
-                            Node {
-                                width: Val::Px(1.0),
-                                height: Val::Vh(1.5),
-                                position_type: PositionType::Absolute,
-                                left: Val::Vw(6.75), // 50% of 13.5vw
-                                ..default()
-                            },
-                            BackgroundColor(Color::srgba(1., 1., 1., 0.4)), // Semi-transparent white
-                        ));
-                    });
-                } else {
-                    // Add base money and throughput info
-                    parent.spawn((
-                        Text::new(format!(
-                            "Base income: {:.2} | Required: {:.2}",
-                            fulfillment.base_money, fulfillment.base_threshold
-                        )),
-                        game_assets.text_font(12.0),
-                        ScalableText::from_vw(1.5),
+                // Reject button
+                buttons.spawn((
+                    Node {
+                        padding: UiRect::all(Val::Vw(0.6)),
+                        margin: UiRect::right(Val::Vw(0.6)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.6, 0.2, 0.2)),
+                    ContractRejectButton,
+                    ContractEntityLink(contract_entity),
+                    Interaction::None,
+                )).with_children(|button| {
+                    button.spawn((
+                        Text::new("N"),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(2.0),
                         TextColor(Color::WHITE),
-                        Node { ..default() },
+                        Node::default()
                     ));
-
-                    // Add accept/reject buttons
-                    parent.spawn((
-                        Node {
-                            margin: UiRect::top(Val::Vw(0.6)),
-                            display: Display::Flex,
-                            flex_direction: FlexDirection::Row,
-                            justify_content: JustifyContent::SpaceBetween,
-                            width: Val::Percent(100.0),
-                            ..default()
-                        },
-                        BackgroundColor(Color::NONE),
-                    )).with_children(|buttons| {
-                        // Accept button
-                        buttons.spawn((
-                            Node {
-                                padding: UiRect::all(Val::Vw(0.6)),
-                                margin: UiRect::right(Val::Vw(0.6)),
-                                ..default()
-                            },
-                            BackgroundColor(Color::srgb(0.2, 0.6, 0.2)),
-                            ContractAcceptButton,
-                            ContractEntityLink(contract_entity),
-                            Interaction::None,
-                        )).with_children(|button| {
-                            button.spawn((
-                                Text::new("Y"),
-                                game_assets.text_font(16.0),
-                                ScalableText::from_vw(2.0),
-                                TextColor(Color::WHITE),
-                                Node::default()
-                            ));
-                        });
-
-                        // View Sink button
-                        buttons.spawn((
-                            Node {
-                                padding: UiRect::all(Val::Vw(0.6)),
-                                ..default()
-                            },
-                            BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
-                            ViewSinkButton,
-                            ContractEntityLink(contract_entity),
-                            Interaction::None,
-                        )).with_children(|button| {
-                            button.spawn((
-                                Text::new("View Sink"),
-                                game_assets.text_font(14.0),
-                                ScalableText::from_vw(1.8),
-                                TextColor(Color::WHITE),
-                                Node::default()
-                            ));
-                        });
-
-                        // Reject button
-                        buttons.spawn((
-                            Node {
-                                padding: UiRect::all(Val::Vw(0.6)),
-                                margin: UiRect::right(Val::Vw(0.6)),
-                                ..default()
-                            },
-                            BackgroundColor(Color::srgb(0.6, 0.2, 0.2)),
-                            ContractRejectButton,
-                            ContractEntityLink(contract_entity),
-                            Interaction::None,
-                        )).with_children(|button| {
-                            button.spawn((
-                                Text::new("N"),
-                                game_assets.text_font(14.0),
-                                ScalableText::from_vw(2.0),
-                                TextColor(Color::WHITE),
-                                Node::default()
-                            ));
-                        });
-                    });
-                }
-            })
-            .id();
-            commands.entity(sidebar).add_child(card);
+                });
+            });
         }
-    }
+    })
+    .id()
+}
+
+/// Tiny square whose borders show which sides of a sink are reachable
+fn spawn_sink_access_schematic(parent: &mut ChildSpawnerCommands, access: &SinkAccess, palette: &Palette) {
+    let color = |side: PortAccess| match side {
+        PortAccess::Open => palette.success,
+        PortAccess::Occupied => palette.warning,
+        PortAccess::Blocked => palette.danger,
+    };
+    parent.spawn((
+        Node {
+            width: Val::Vw(1.6),
+            height: Val::Vw(1.6),
+            border: UiRect::all(Val::Vw(0.25)),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.12, 0.12, 0.12)),
+        BorderColor {
+            top: color(access.up),
+            right: color(access.right),
+            bottom: color(access.down),
+            left: color(access.left),
+        },
+    ));
 }
 
 pub fn handle_contract_buttons(
+    mut commands: Commands,
     mut contract_query: Query<&mut ContractStatus>,
     accept_query: Query<(&Interaction, &ContractEntityLink), (Changed<Interaction>, With<ContractAcceptButton>)>,
     reject_query: Query<(&Interaction, &ContractEntityLink), (Changed<Interaction>, With<ContractRejectButton>)>,
@@ -590,12 +1282,27 @@ pub fn handle_contract_buttons(
     camera_query: Single<(&mut Transform, &mut Projection), With<Camera>>,
     sink_query: Query<&GridPosition, With<SinkBuilding>>, // Assuming SinkBuilding is a marker component for sink entities
     grid: Res<Grid>,
+    mut trace: ResMut<TraceLog>,
+    sink_access_query: Query<&SinkAccess>,
+    mut sfx: MessageWriter<crate::audio::PlaySfx>,
 ) {
     // Handle accept button clicks
     for (interaction, link) in accept_query.iter() {
         if *interaction == Interaction::Pressed {
+            let blocked = associated_sink_query
+                .get(link.0)
+                .ok()
+                .and_then(|sink| sink_access_query.get(sink.0).ok())
+                .is_some_and(|access| !access.has_access());
+            if blocked {
+                continue;
+            }
             if let Ok(mut status) = contract_query.get_mut(link.0) {
+                trace_sim!(trace, Contract, [link.0], "contract {:?} {:?} -> Active (accepted)", link.0, *status);
                 *status = ContractStatus::Active;
+                // accepted offers don't expire
+                commands.entity(link.0).remove::<crate::contracts::ContractTimeout>();
+                sfx.write(crate::audio::PlaySfx(crate::audio::Sfx::ContractAccepted));
             }
         }
     }
@@ -604,6 +1311,7 @@ pub fn handle_contract_buttons(
     for (interaction, link) in reject_query.iter() {
         if *interaction == Interaction::Pressed {
             if let Ok(mut status) = contract_query.get_mut(link.0) {
+                trace_sim!(trace, Contract, [link.0], "contract {:?} {:?} -> Rejected", link.0, *status);
                 *status = ContractStatus::Rejected;
             }
         }
@@ -627,6 +1335,45 @@ pub fn handle_contract_buttons(
     }
 }
 
+/// Pin toggles and the up/down arrows. New pins go to the bottom of the pinned group
+pub fn handle_contract_pin_buttons(
+    mut commands: Commands,
+    pin_query: Query<(&Interaction, &ContractEntityLink), (Changed<Interaction>, With<ContractPinButton>)>,
+    move_query: Query<(&Interaction, &ContractEntityLink, &ContractPinMoveButton), Changed<Interaction>>,
+    mut pinned_query: Query<(Entity, &mut Pinned)>,
+) {
+    for (interaction, link) in pin_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if pinned_query.contains(link.0) {
+            commands.entity(link.0).remove::<Pinned>();
+        } else {
+            let next = pinned_query.iter().map(|(_, pinned)| pinned.0 + 1).max().unwrap_or(0);
+            commands.entity(link.0).insert(Pinned(next));
+        }
+    }
+
+    for (interaction, link, button) in move_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let mut order: Vec<(Entity, u32)> = pinned_query.iter().map(|(entity, pinned)| (entity, pinned.0)).collect();
+        order.sort_by_key(|(_, rank)| *rank);
+        let Some(index) = order.iter().position(|(entity, _)| *entity == link.0) else { continue };
+        let Some(other) = (if button.up { index.checked_sub(1) } else { Some(index + 1) }).and_then(|i| order.get(i)) else {
+            continue;
+        };
+        let (this_rank, other_rank) = (order[index].1, other.1);
+        if let Ok((_, mut pinned)) = pinned_query.get_mut(other.0) {
+            pinned.0 = this_rank;
+        }
+        if let Ok((_, mut pinned)) = pinned_query.get_mut(link.0) {
+            pinned.0 = other_rank;
+        }
+    }
+}
+
 /// System to resize data icons in contracts without replacing their Node component
 /// This is crucial because replacing Node breaks the ScanningFlashEffect overlay system
 pub fn resize_contract_data_icons(
@@ -687,3 +1434,219 @@ pub fn show_dataset_tooltip(
 #[derive(Component)]
 pub struct DatasetTooltipText;
 
+/// Hover text on a greyed out accept button, tied to the button it explains
+#[derive(Component)]
+pub struct AcceptBlockedTooltip {
+    button: Entity,
+}
+
+/// Which sides of the sink are shut, so the player knows what to unlock
+fn blocked_access_reason(access: &SinkAccess, position: &GridPosition) -> String {
+    let sides: Vec<String> = crate::grid::Direction::ALL
+        .iter()
+        .filter(|direction| access.side(**direction) == PortAccess::Blocked)
+        .map(|direction| format!("{:?}", direction).to_lowercase())
+        .collect();
+    format!(
+        "Can't accept: the sink at ({}, {}) has no access.\nIts {} sides are locked territory or off the map",
+        position.0.x, position.0.y, sides.join(", ")
+    )
+}
+
+/// Explains a greyed out accept button while it's hovered, like the event choice tooltips
+pub fn show_accept_blocked_tooltip(
+    mut commands: Commands,
+    button_query: Query<(Entity, &Interaction, &ContractEntityLink), With<ContractAcceptButton>>,
+    mut tooltip_query: Query<(Entity, &AcceptBlockedTooltip, &mut Node, &ComputedNode)>,
+    associated_sink_query: Query<&AssociatedWithSink>,
+    sink_query: Query<(&SinkAccess, &GridPosition), With<SinkBuilding>>,
+    window: Single<&Window, With<bevy::window::PrimaryWindow>>,
+    game_assets: Res<GameAssets>,
+) {
+    let reason = |contract: Entity| {
+        let sink = associated_sink_query.get(contract).ok()?;
+        let (access, position) = sink_query.get(sink.0).ok()?;
+        (!access.has_access()).then(|| blocked_access_reason(access, position))
+    };
+    let hovered = button_query
+        .iter()
+        .find(|(_, interaction, _)| **interaction != Interaction::None)
+        .and_then(|(button, _, link)| Some((button, reason(link.0)?)));
+    let cursor = window.cursor_position();
+
+    let mut tooltip_exists = false;
+    for (tooltip_entity, tooltip, mut node, computed) in tooltip_query.iter_mut() {
+        if hovered.as_ref().is_some_and(|(button, _)| *button == tooltip.button) {
+            tooltip_exists = true;
+            if let Some(cursor) = cursor {
+                crate::ui::shop_tooltip::place_by_cursor(&mut node, computed, cursor, &window);
+            }
+        } else {
+            commands.entity(tooltip_entity).despawn();
+        }
+    }
+
+    let Some((button, reason)) = hovered else { return };
+    if tooltip_exists {
+        return;
+    }
+    // first frame sits right by the cursor, place_by_cursor tidies it once it has a size
+    let cursor = cursor.unwrap_or(Vec2::splat(100.0));
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(cursor.x + 15.0),
+            top: Val::Px(cursor.y + 15.0),
+            padding: UiRect::all(Val::Vw(0.8)),
+            max_width: Val::Vw(20.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.15, 0.95)),
+        GlobalZIndex(1000),
+        Pickable::IGNORE,
+        AcceptBlockedTooltip { button },
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new(reason),
+            game_assets.text_font(14.0),
+            ScalableText::from_vw(1.1),
+            TextColor(Color::srgb(1.0, 0.8, 0.8)),
+        ));
+    });
+}
+
+
+/// Cancel on an active card opens the confirmation with the penalty spelled out
+pub fn open_contract_cancel_popup(
+    mut commands: Commands,
+    cancel_query: Query<(&Interaction, &ContractEntityLink), (Changed<Interaction>, With<ContractCancelButton>)>,
+    contract_query: Query<(&ContractDescription, &ContractFulfillment, &crate::factions::Faction)>,
+    popups: Query<Entity, With<ContractCancelPopup>>,
+    game_assets: Res<GameAssets>,
+) {
+    let Some((_, link)) = cancel_query.iter().find(|(i, _)| **i == Interaction::Pressed) else { return };
+    let Ok((description, fulfillment, faction)) = contract_query.get(link.0) else { return };
+    for popup in popups.iter() {
+        commands.entity(popup).despawn();
+    }
+
+    let (fee, reputation) = fulfillment.cancellation_penalty();
+    let failing = fulfillment.status == ContractFulfillmentStatus::Failing;
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Vh(35.0),
+                left: Val::Vw(35.0),
+                width: Val::Vw(24.0),
+                padding: UiRect::all(Val::Vw(1.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.6),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.08, 0.08, 0.12, 0.97)),
+            BorderColor::all(Color::srgb(0.7, 0.25, 0.25)),
+            GlobalZIndex(1100),
+            ContractCancelPopup(link.0),
+            crate::ui::BlocksWorldClicks,
+        ))
+        .with_children(|popup| {
+            popup.spawn((
+                Text::new(format!("Cancel \"{}\"?", description.name)),
+                game_assets.text_font(18.0),
+                ScalableText::from_vw(1.3),
+                TextColor(Color::WHITE),
+            ));
+            popup.spawn((
+                Text::new(format!(
+                    "Costs ${} and {} reputation with {:?}{}",
+                    fee,
+                    reputation,
+                    faction,
+                    if failing { " (doubled, it's already failing)" } else { "" },
+                )),
+                game_assets.text_font(14.0),
+                ScalableText::from_vw(1.0),
+                TextColor(Color::srgb(1.0, 0.6, 0.5)),
+            ));
+            popup.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceBetween,
+                ..default()
+            }).with_children(|buttons| {
+                for (label, color, confirm) in [
+                    ("Cancel contract", Color::srgb(0.6, 0.2, 0.2), true),
+                    ("Keep it (Esc)", Color::srgb(0.3, 0.3, 0.3), false),
+                ] {
+                    let mut button = buttons.spawn((
+                        Node {
+                            padding: UiRect::all(Val::Vw(0.5)),
+                            ..default()
+                        },
+                        BackgroundColor(color),
+                        Interaction::None,
+                    ));
+                    if confirm {
+                        button.insert(ContractCancelConfirm);
+                    } else {
+                        button.insert(ContractCancelDismiss);
+                    }
+                    button.with_children(|button| {
+                        button.spawn((
+                            Text::new(label),
+                            game_assets.text_font(14.0),
+                            ScalableText::from_vw(1.0),
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+                }
+            });
+        });
+}
+
+/// Confirm rejects the contract, takes the fee and reputation and frees its sink slot.
+/// The other button just closes the popup, Escape is handled in `pause_menu::handle_escape`.
+pub fn handle_contract_cancel_popup(
+    mut commands: Commands,
+    popups: Query<(Entity, &ContractCancelPopup)>,
+    confirm_query: Query<&Interaction, (Changed<Interaction>, With<ContractCancelConfirm>)>,
+    dismiss_query: Query<&Interaction, (Changed<Interaction>, With<ContractCancelDismiss>)>,
+    mut contract_query: Query<(&mut ContractStatus, &ContractFulfillment, &ContractDescription, &crate::factions::Faction, Option<&AssociatedWithSink>)>,
+    mut player: ResMut<crate::player::Player>,
+    mut reputations: crate::factions::ReputationChanges,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+    mut trace: ResMut<TraceLog>,
+) {
+    let Ok((popup_entity, popup)) = popups.single() else { return };
+    let dismissed = dismiss_query.iter().any(|i| *i == Interaction::Pressed)
+        // contract went away while the popup was up
+        || !contract_query.contains(popup.0);
+    if dismissed {
+        commands.entity(popup_entity).despawn();
+        return;
+    }
+    if !confirm_query.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+
+    commands.entity(popup_entity).despawn();
+    let Ok((mut status, fulfillment, description, faction, sink)) = contract_query.get_mut(popup.0) else { return };
+    if *status != ContractStatus::Active {
+        return;
+    }
+    let (fee, reputation) = fulfillment.cancellation_penalty();
+    trace_sim!(trace, Contract, [popup.0], "contract {:?} {:?} -> Rejected (cancelled, -${} -{} rep)", popup.0, *status, fee, reputation);
+    *status = ContractStatus::Rejected;
+    player.money -= fee;
+    reputations.add(*faction, -reputation, crate::factions::ReputationSource::Contract(sink.map(|s| s.0)));
+    // off the sink so the slot opens back up
+    commands.entity(popup.0).remove::<AssociatedWithSink>();
+    info!("Cancelled contract {} for ${} and {} {:?} reputation", description.name, fee, reputation, faction);
+    news_writer.write(crate::events::AddNewsfeedItemEvent {
+        faction: *faction,
+        headline: format!("Contract \"{}\" cancelled, {:?} won't forget it", description.name, faction),
+        payload: Some(crate::events::NewsPayload::Faction(*faction)),
+    });
+}
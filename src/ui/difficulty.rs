@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+
+use crate::assets::GameAssets;
+use crate::difficulty::{Difficulty, DifficultyMultipliers, SkipSetupScreen};
+use crate::game_mode::GameMode;
+use crate::pause::GameState;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::widgets::{spawn_cycler, spawn_slider, Cycler, Slider, WidgetChanged, WidgetValue};
+use crate::ui::{BlocksWorldClicks, BlocksWorldScroll};
+
+const MIN_MULTIPLIER: f32 = 0.25;
+const MAX_MULTIPLIER: f32 = 3.0;
+const MULTIPLIER_STEP: f32 = 0.05;
+
+/// Shown before world gen, the run doesn't start until Start's pressed
+#[derive(Component)]
+pub struct DifficultyScreen;
+
+#[derive(Component)]
+pub struct DifficultyStartButton;
+
+/// Which part of the run setup a widget on the setup screen edits
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DifficultyBinding {
+    /// Normal or sandbox, goes to `GameMode` rather than the difficulty
+    Mode,
+    Preset,
+    ContractThreshold,
+    ContractMoney,
+    EventCooldown,
+    FailingTime,
+    StartingMoney,
+    SourceThroughput,
+    ContractPacing,
+}
+
+impl DifficultyBinding {
+    const SLIDERS: [(DifficultyBinding, &'static str); 7] = [
+        (DifficultyBinding::ContractThreshold, "Contract demand"),
+        (DifficultyBinding::ContractMoney, "Contract pay"),
+        (DifficultyBinding::EventCooldown, "Event cooldown"),
+        (DifficultyBinding::FailingTime, "Time before failing"),
+        (DifficultyBinding::StartingMoney, "Starting money"),
+        (DifficultyBinding::SourceThroughput, "Source output"),
+        (DifficultyBinding::ContractPacing, "Early offer gap"),
+    ];
+
+    fn field(self, multipliers: &mut DifficultyMultipliers) -> Option<&mut f32> {
+        match self {
+            DifficultyBinding::Mode | DifficultyBinding::Preset => None,
+            DifficultyBinding::ContractThreshold => Some(&mut multipliers.contract_threshold),
+            DifficultyBinding::ContractMoney => Some(&mut multipliers.contract_money),
+            DifficultyBinding::EventCooldown => Some(&mut multipliers.event_cooldown),
+            DifficultyBinding::FailingTime => Some(&mut multipliers.failing_time),
+            DifficultyBinding::StartingMoney => Some(&mut multipliers.starting_money),
+            DifficultyBinding::SourceThroughput => Some(&mut multipliers.source_throughput),
+            DifficultyBinding::ContractPacing => Some(&mut multipliers.contract_pacing),
+        }
+    }
+}
+
+fn multiplier(value: f32) -> String {
+    format!("{:.2}x", value)
+}
+
+/// The presets, then Custom
+fn preset_index(difficulty: &Difficulty) -> usize {
+    Difficulty::PRESETS
+        .iter()
+        .position(|preset| preset == difficulty)
+        .unwrap_or(Difficulty::PRESETS.len())
+}
+
+pub fn spawn_difficulty_screen(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    difficulty: Res<Difficulty>,
+    game_mode: Res<GameMode>,
+    skip: Option<Res<SkipSetupScreen>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if skip.is_some() {
+        info!("Difficulty {} from the command line, skipping setup", difficulty.name());
+        next_state.set(GameState::Running);
+        return;
+    }
+    let mut multipliers = difficulty.multipliers();
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.05, 0.05, 0.07)),
+            GlobalZIndex(1000),
+            DifficultyScreen,
+            BlocksWorldClicks,
+            BlocksWorldScroll,
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Vh(0.8),
+                    padding: UiRect::all(Val::Vw(1.5)),
+                    min_width: Val::Vw(24.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.12, 0.12, 0.15)),
+            ))
+            .with_children(|panel| {
+                panel.spawn((
+                    Text::new("New run"),
+                    game_assets.text_font(28.0),
+                    ScalableText::from_vw(2.0),
+                    TextColor(Color::WHITE),
+                    Node {
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                ));
+
+                let modes = vec!["Normal".to_string(), "Sandbox".to_string()];
+                let mode = spawn_cycler(panel, &game_assets, "Mode", modes, game_mode.sandbox as usize);
+                panel.commands().entity(mode).insert(DifficultyBinding::Mode);
+
+                let mut options: Vec<String> = Difficulty::PRESETS.iter().map(|d| d.name().to_string()).collect();
+                options.push("Custom".to_string());
+                let preset = spawn_cycler(panel, &game_assets, "Difficulty", options, preset_index(&difficulty));
+                panel.commands().entity(preset).insert(DifficultyBinding::Preset);
+
+                for (binding, name) in DifficultyBinding::SLIDERS {
+                    let value = binding.field(&mut multipliers).map_or(1.0, |v| *v);
+                    let slider = spawn_slider(
+                        panel,
+                        &game_assets,
+                        name,
+                        value,
+                        MIN_MULTIPLIER,
+                        MAX_MULTIPLIER,
+                        Some(MULTIPLIER_STEP),
+                        multiplier,
+                    );
+                    panel.commands().entity(slider).insert(binding);
+                }
+
+                panel
+                    .spawn((
+                        Node {
+                            padding: UiRect::all(Val::Vw(0.6)),
+                            justify_content: JustifyContent::Center,
+                            margin: UiRect::top(Val::Vh(1.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.2, 0.45, 0.25)),
+                        Interaction::None,
+                        DifficultyStartButton,
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            Text::new("Start"),
+                            game_assets.text_font(18.0),
+                            ScalableText::from_vw(1.3),
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+            });
+        });
+}
+
+/// Picking a preset moves the sliders to it, moving a slider makes it Custom. The mode
+/// cycler only flips sandbox, contract generation stays a settings panel toggle
+pub fn apply_difficulty_widgets(
+    mut changes: MessageReader<WidgetChanged>,
+    mut difficulty: ResMut<Difficulty>,
+    mut game_mode: ResMut<GameMode>,
+    mut sliders: Query<(&DifficultyBinding, &mut Slider)>,
+    mut cyclers: Query<(&DifficultyBinding, &mut Cycler)>,
+) {
+    for change in changes.read() {
+        if let Ok((DifficultyBinding::Mode, _)) = cyclers.get(change.entity) {
+            let WidgetValue::Index(index) = change.value else { continue };
+            game_mode.sandbox = index == 1;
+            continue;
+        }
+        if let Ok((DifficultyBinding::Preset, _)) = cyclers.get(change.entity) {
+            let WidgetValue::Index(index) = change.value else { continue };
+            *difficulty = Difficulty::PRESETS
+                .get(index)
+                .copied()
+                .unwrap_or(Difficulty::Custom(difficulty.multipliers()));
+            let mut multipliers = difficulty.multipliers();
+            for (binding, mut slider) in sliders.iter_mut() {
+                if let Some(value) = binding.field(&mut multipliers) {
+                    slider.set_value(*value);
+                }
+            }
+            continue;
+        }
+
+        let (Ok((binding, _)), WidgetValue::Float(value)) = (sliders.get(change.entity), change.value) else {
+            continue;
+        };
+        let mut multipliers = difficulty.multipliers();
+        let Some(field) = binding.field(&mut multipliers) else { continue };
+        *field = value;
+        *difficulty = Difficulty::Custom(multipliers);
+        for (binding, mut cycler) in cyclers.iter_mut() {
+            if *binding == DifficultyBinding::Preset {
+                cycler.index = Difficulty::PRESETS.len();
+            }
+        }
+    }
+}
+
+/// Leaving Setup is what runs world gen
+pub fn handle_difficulty_start(
+    mut commands: Commands,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<DifficultyStartButton>)>,
+    screens: Query<Entity, With<DifficultyScreen>>,
+    difficulty: Res<Difficulty>,
+    game_mode: Res<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !buttons.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    for screen in screens.iter() {
+        commands.entity(screen).despawn();
+    }
+    if game_mode.sandbox {
+        info!("Starting a sandbox run");
+    }
+    info!("Starting on {} ({:?})", difficulty.name(), difficulty.multipliers());
+    next_state.set(GameState::Running);
+}
@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::assets::{GameAssets, IconSize};
+use crate::factory::buildings::buildings::Building;
+use crate::factory::buildings::filter_splitter::FilterSplitter;
+use crate::factory::buildings::Tile;
+use crate::grid::{Grid, WorldMap};
+use crate::save::PlacedBuilding;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::BlocksWorldClicks;
+
+/// Screen panel for configuring one filter splitter's ports
+#[derive(Component)]
+pub struct FilterPanel {
+    pub building: Entity,
+}
+
+/// Cycles the data type assigned to `port` on click
+#[derive(Component)]
+pub struct FilterPortButton {
+    pub building: Entity,
+    pub port: usize,
+}
+
+#[derive(Component)]
+pub struct FilterPanelClose;
+
+fn spawn_filter_panel(commands: &mut Commands, game_assets: &GameAssets, building: Entity, splitter: &FilterSplitter) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Vh(30.0),
+                left: Val::Vw(40.0),
+                padding: UiRect::all(Val::Vw(0.6)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.4),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            ZIndex(150),
+            FilterPanel { building },
+            BlocksWorldClicks,
+        ))
+        .with_children(|panel| {
+            panel.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceBetween,
+                column_gap: Val::Vw(1.0),
+                ..default()
+            }).with_children(|header| {
+                header.spawn((
+                    Text::new("Filter Splitter"),
+                    game_assets.text_font(16.0),
+                    ScalableText::from_vw(1.1),
+                    TextColor(Color::WHITE),
+                ));
+                header.spawn((
+                    Node {
+                        padding: UiRect::axes(Val::Vw(0.4), Val::Vw(0.1)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.5, 0.2, 0.2)),
+                    Interaction::None,
+                    FilterPanelClose,
+                )).with_children(|button| {
+                    button.spawn((
+                        Text::new("X"),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.9),
+                        TextColor(Color::WHITE),
+                    ));
+                });
+            });
+
+            for port in 0..splitter.overflow_port() {
+                panel.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Vw(0.6),
+                    ..default()
+                }).with_children(|row| {
+                    row.spawn((
+                        Text::new(format!("Port {}", port + 1)),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.9),
+                        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                    ));
+                    row.spawn((
+                        Node {
+                            width: Val::Vw(1.8),
+                            height: Val::Vw(1.8),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                        Interaction::None,
+                        FilterPortButton { building, port },
+                    )).with_children(|button| {
+                        match splitter.filter(port).and_then(|t| game_assets.data_type_icon(t, IconSize::Small)) {
+                            Some((atlas_id, index)) => {
+                                let (texture, layout) = game_assets.get_atlas(atlas_id);
+                                button.spawn((
+                                    ImageNode::from_atlas_image(texture, TextureAtlas { layout, index }),
+                                    Node {
+                                        width: Val::Vw(1.4),
+                                        height: Val::Vw(1.4),
+                                        ..default()
+                                    },
+                                ));
+                            }
+                            None => {
+                                button.spawn((
+                                    Text::new("-"),
+                                    game_assets.text_font(14.0),
+                                    ScalableText::from_vw(0.9),
+                                    TextColor(Color::WHITE),
+                                ));
+                            }
+                        }
+                    });
+                    row.spawn((
+                        Text::new(splitter.filter(port).map_or("unassigned".to_string(), |t| format!("{:?}", t))),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.9),
+                        TextColor(Color::WHITE),
+                    ));
+                });
+            }
+
+            panel.spawn((
+                Text::new(format!("Port {}: overflow, gets everything unassigned", splitter.overflow_port() + 1)),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.8),
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Left click on a placed filter splitter (with nothing selected) opens its panel
+pub fn open_filter_panel_on_click(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    grid: Res<Grid>,
+    world_map: Res<WorldMap>,
+    tiles: Query<&Tile>,
+    splitters: Query<&FilterSplitter>,
+    panels: Query<Entity, With<FilterPanel>>,
+    selected_building_type: Res<crate::ui::shop::SelectedBuildingType>,
+    ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    game_assets: Res<GameAssets>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || selected_building_type.0.is_some() {
+        return;
+    }
+    if ui_blocker_query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed)
+    {
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(world_pos) = window
+        .cursor_position()
+        .and_then(|p| camera.viewport_to_world_2d(camera_transform, p).ok())
+    else {
+        return;
+    };
+    let Some(entities) = world_map.get(&grid.world_to_grid(world_pos)) else { return };
+    let Some((building, splitter)) = entities
+        .iter()
+        .filter_map(|e| tiles.get(*e).ok())
+        .find_map(|tile| splitters.get(tile.0).ok().map(|s| (tile.0, s)))
+    else {
+        return;
+    };
+
+    for panel in panels.iter() {
+        commands.entity(panel).despawn();
+    }
+    spawn_filter_panel(&mut commands, &game_assets, building, splitter);
+}
+
+pub fn handle_filter_panel_buttons(
+    mut commands: Commands,
+    port_buttons: Query<(&Interaction, &FilterPortButton), Changed<Interaction>>,
+    close_buttons: Query<&Interaction, (Changed<Interaction>, With<FilterPanelClose>)>,
+    mut splitters: Query<(&mut FilterSplitter, Option<&mut PlacedBuilding>)>,
+    panels: Query<Entity, With<FilterPanel>>,
+    game_assets: Res<GameAssets>,
+) {
+    if close_buttons.iter().any(|i| *i == Interaction::Pressed) {
+        for panel in panels.iter() {
+            commands.entity(panel).despawn();
+        }
+        return;
+    }
+    let Some((_, button)) = port_buttons.iter().find(|(i, _)| **i == Interaction::Pressed) else { return };
+    let Ok((mut splitter, placed)) = splitters.get_mut(button.building) else { return };
+    splitter.cycle_filter(button.port);
+    info!("Filter splitter {:?} port {} now takes {:?}", button.building, button.port, splitter.filter(button.port));
+    // saves go off the descriptor, keep it in step
+    if let (Some(mut placed), Some(descriptor)) = (placed, splitter.descriptor()) {
+        placed.descriptor = descriptor;
+    }
+
+    for panel in panels.iter() {
+        commands.entity(panel).despawn();
+    }
+    spawn_filter_panel(&mut commands, &game_assets, button.building, &splitter);
+}
+
+/// The panel goes when its splitter is removed
+pub fn close_orphaned_filter_panel(
+    mut commands: Commands,
+    panels: Query<(Entity, &FilterPanel)>,
+    splitters: Query<(), With<FilterSplitter>>,
+) {
+    for (entity, panel) in panels.iter() {
+        if !splitters.contains(panel.building) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use crate::factory::physical::remove_physical_link_on_right_click;
 use bevy::app::App;
 use bevy::input::ButtonInput;
+use crate::controls::Binding;
 use bevy::prelude::{
-    resource_changed, DetectChanges, IntoScheduleConfigs, MouseButton, Plugin, Res, ResMut, Resource,
+    resource_changed, DetectChanges, IntoScheduleConfigs, KeyCode, MouseButton, Plugin, Res, ResMut, Resource,
     Update,
 };
 
@@ -20,6 +21,8 @@ impl Plugin for CustomInteractionPlugin {
     }
 }
 
+/// Gamepad A/B come through here too, `crate::gamepad` presses them into
+/// `ButtonInput<MouseButton>` before this is filled
 #[derive(Resource, Default)]
 pub struct MouseButtonEvent {
     event: Option<ButtonInput<MouseButton>>,
@@ -35,6 +38,14 @@ impl MouseButtonEvent {
             None
         }
     }
+
+    /// Takes the press like `handle` for mouse bindings, key bindings just read the keyboard
+    pub(crate) fn handle_binding(&mut self, binding: Binding, keys: &ButtonInput<KeyCode>) -> bool {
+        match binding {
+            Binding::Key(key) => keys.just_pressed(key),
+            Binding::Mouse(button) => self.handle().is_some_and(|mouse| mouse.just_pressed(button)),
+        }
+    }
 }
 
 pub fn convert_input(
@@ -1,4 +1,4 @@
-use crate::events::{InteractiveEventData, PlayerChoiceEvent, ShowInteractiveEvent, GameContext, EventState, Requirements};
+use crate::events::{EventIgnored, InteractiveEventData, PlayerChoiceEvent, ShowInteractiveEvent, GameContext, EventState, Requirements};
 use crate::assets::GameAssets;
 use crate::factions::{FactionReputations, reputation_level_name};
 use crate::player::Player;
@@ -11,6 +11,9 @@ const BUBBLE_SIZE: f32 = 90.0;
 const BUBBLE_SPACING: f32 = 10.0;
 const BUBBLE_LEFT_OFFSET: f32 = 20.0;
 const BUBBLE_BOTTOM_OFFSET: f32 = 20.0;
+const BUBBLE_RING_WIDTH: f32 = 5.0;
+/// Bubbles wobble harder and more often once they're this close to expiring
+const BUBBLE_URGENT_SECONDS: f32 = 20.0;
 
 /// Helper function to check choice requirements and generate disabled reason
 fn check_choice_requirements(requirements: &[Requirements], context: &GameContext) -> (bool, Option<String>) {    
@@ -77,6 +80,22 @@ fn check_choice_requirements(requirements: &[Requirements], context: &GameContex
                 // TODO: Implement contract checking when contract system is ready
                 return (true, Some(format!("Contract {} must be fulfilled", contract_id)));
             }
+            Requirements::MinBuildingCount { building_name, count } => {
+                let have = context.buildings.get(building_name);
+                if have < *count {
+                    return (true, Some(format!("Need {} {} ({} placed)", count, building_name, have)));
+                }
+            }
+            Requirements::ProducesDataType { data_type, min_rate } => {
+                if context.production.rate(*data_type) < *min_rate {
+                    return (true, Some(format!("Need {:?} data at {:.0}/s", data_type, min_rate)));
+                }
+            }
+            Requirements::HasAttributeInProduction(attribute) => {
+                if !context.production.has_attribute(*attribute) {
+                    return (true, Some(format!("Need {:?} data moving", attribute)));
+                }
+            }
             Requirements::AllOf(reqs) => {
                 let (disabled, reason) = check_choice_requirements(reqs, context);
                 if disabled {
@@ -221,6 +240,16 @@ fn spawn_choice_button(
     // Add visual indicators for all consequences
     let mut indicators = Vec::new();
     for consequence in consequences {
+        if consequence.is_hidden() {
+            let indicator = spawn_text_consequence_indicator(
+                commands,
+                "???",
+                Color::srgb(0.7, 0.7, 0.7),
+                game_assets,
+            );
+            indicators.push(indicator);
+            continue;
+        }
         match consequence {
             ConsequenceType::ModifyReputation { faction, amount } => {
                 // Create faction icon + arrow indicator
@@ -241,6 +270,74 @@ fn spawn_choice_button(
                 );
                 indicators.push(indicator);
             }
+            ConsequenceType::DestroyRandomBuilding { count } => {
+                let indicator = spawn_text_consequence_indicator(
+                    commands,
+                    &format!("-{} bldg", count),
+                    Color::srgb(0.9, 0.3, 0.3),
+                    game_assets,
+                );
+                indicators.push(indicator);
+            }
+            ConsequenceType::SpawnSource { faction, dataset, .. } => {
+                let indicator = spawn_faction_label_indicator(
+                    commands,
+                    *faction,
+                    "+source",
+                    Color::srgb(0.3, 0.85, 0.3),
+                    game_assets,
+                );
+                // the data it'll put out
+                let mut data_types: Vec<_> = dataset.contents.keys().copied().collect();
+                data_types.sort();
+                for data_type in data_types {
+                    if let Some((atlas_id, index)) = game_assets.data_type_icon(data_type, crate::assets::IconSize::Small) {
+                        let (texture, layout) = game_assets.get_atlas(atlas_id);
+                        let icon = commands
+                            .spawn((
+                                ImageNode::from_atlas_image(texture, TextureAtlas { layout, index }),
+                                Node {
+                                    width: Val::Vw(1.2),
+                                    height: Val::Vw(1.2),
+                                    ..default()
+                                },
+                            ))
+                            .id();
+                        commands.entity(indicator).add_child(icon);
+                    }
+                }
+                indicators.push(indicator);
+            }
+            ConsequenceType::DisableSinksForSeconds { faction, seconds } => {
+                let indicator = spawn_faction_label_indicator(
+                    commands,
+                    *faction,
+                    &format!("sinks off {:.0}s", seconds),
+                    Color::srgb(0.9, 0.3, 0.3),
+                    game_assets,
+                );
+                indicators.push(indicator);
+            }
+            ConsequenceType::SellRandomBuildings { count } => {
+                let indicator = spawn_text_consequence_indicator(
+                    commands,
+                    &format!("sell {} bldg", count),
+                    Color::srgb(0.95, 0.75, 0.3),
+                    game_assets,
+                );
+                indicators.push(indicator);
+            }
+            ConsequenceType::TakeLoan { principal, per_second } => {
+                let indicator = spawn_money_consequence_indicator(commands, *principal, game_assets);
+                indicators.push(indicator);
+                let repayment = spawn_text_consequence_indicator(
+                    commands,
+                    &format!("-${}/s", per_second),
+                    Color::srgb(0.9, 0.3, 0.3),
+                    game_assets,
+                );
+                indicators.push(repayment);
+            }
             _ => {
                 // Other consequence types can be added here   
             }
@@ -384,6 +481,54 @@ fn spawn_money_consequence_indicator(
     container
 }
 
+/// Faction icon followed by a short label, for consequences that hit a faction's buildings
+fn spawn_faction_label_indicator(
+    commands: &mut Commands,
+    faction: crate::factions::Faction,
+    label: &str,
+    color: Color,
+    game_assets: &crate::assets::GameAssets,
+) -> Entity {
+    let container = commands
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Vw(0.2),
+            ..default()
+        })
+        .id();
+
+    let faction_icon_index = game_assets.faction_icon(faction, crate::assets::IconSize::Small).map(|(_, idx)| idx).unwrap_or(0);
+    let faction_icon = commands
+        .spawn((
+            ImageNode::from_atlas_image(
+                game_assets.small_sprites_texture.clone(),
+                TextureAtlas {
+                    layout: game_assets.small_sprites_layout.clone(),
+                    index: faction_icon_index,
+                },
+            ),
+            Node {
+                width: Val::Vw(1.5),
+                height: Val::Vw(1.5),
+                ..default()
+            },
+        ))
+        .id();
+
+    let text = commands
+        .spawn((
+            Text::new(label),
+            game_assets.text_font(14.0),
+            TextColor(color),
+            ScalableText::from_vw(0.9),
+        ))
+        .id();
+
+    commands.entity(container).add_children(&[faction_icon, text]);
+    container
+}
+
 /// Spawn a simple text-based consequence indicator
 fn spawn_text_consequence_indicator(
     commands: &mut Commands,
@@ -429,9 +574,21 @@ pub fn handle_choice_button_interaction(
     }
 }
 
-/// System to scale text based on window size
+/// Smallest and largest font size any `ScalableText` may end up with,
+/// so the 1.5x preference can't blow modal titles out of their cards
+pub const MIN_SCALED_FONT_SIZE: f32 = 10.0;
+pub const MAX_SCALED_FONT_SIZE: f32 = 100.0;
+
+/// Font size for a given vw size, window width and player text scale preference
+pub fn scaled_font_size(base_vw: f32, window_width: f32, text_scale: f32) -> f32 {
+    ((base_vw / 100.0) * window_width * text_scale)
+        .clamp(MIN_SCALED_FONT_SIZE, MAX_SCALED_FONT_SIZE)
+}
+
+/// System to scale text based on window size and the text size preference
 pub fn scale_text_system(
     windows: Query<&Window>,
+    settings: Res<crate::settings::Settings>,
     mut text_query: Query<(&ScalableText, &mut TextFont)>,
 ) {
     let Ok(window) = windows.single() else {
@@ -441,10 +598,7 @@ pub fn scale_text_system(
     let window_width = window.width();
     
     for (scalable, mut font) in text_query.iter_mut() {
-        // Calculate font size as percentage of window width
-        let new_size = (scalable.base_vw / 100.0) * window_width;
-        // Clamp to reasonable values
-        let new_size = new_size.clamp(10.0, 100.0);
+        let new_size = scaled_font_size(scalable.base_vw, window_width, settings.ui_text_scale);
         
         if (font.font_size - new_size).abs() > 0.5 {
             font.font_size = new_size;
@@ -464,73 +618,135 @@ pub struct ChoiceTooltip {
     parent_button: Entity,
 }
 
-/// System to show/hide tooltips on hover for disabled choices
+/// "$2,000" style, with the sign in front
+fn format_money_change(amount: i32) -> String {
+    let digits = amount.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{}${}", if amount < 0 { "-" } else { "+" }, grouped)
+}
+
+/// One tooltip line per consequence, exact numbers and all
+pub(crate) fn consequence_line(consequence: &crate::events::ConsequenceType) -> String {
+    use crate::events::ConsequenceType;
+
+    match consequence {
+        ConsequenceType::UnlockEvent(event_id) => format!("Triggers: {}", event_id),
+        ConsequenceType::ModifyMoney(amount) => format!("Money {}", format_money_change(*amount)),
+        ConsequenceType::ModifyReputation { faction, amount } => format!("{:?} reputation {:+}", faction, amount),
+        ConsequenceType::CompleteEvent(event_id) => format!("Closes: {}", event_id),
+        ConsequenceType::Bankruptcy => "Bankruptcy, the run is over".to_string(),
+        ConsequenceType::UnlockContract(contract_id) => format!("Unlocks contract #{}", contract_id),
+        ConsequenceType::MarketShock { data_type, delta } => format!("{:?} market price {:+.2}", data_type, delta),
+        ConsequenceType::DestroyRandomBuilding { count } => format!("Destroys {} random building(s)", count),
+        ConsequenceType::SpawnSource { faction, dataset, near_player } => format!(
+            "New {:?} source: {}{}",
+            faction,
+            dataset,
+            if *near_player { " (near you)" } else { "" }
+        ),
+        ConsequenceType::DisableSinksForSeconds { faction, seconds } => {
+            format!("{:?} sinks offline for {:.0}s", faction, seconds)
+        }
+        ConsequenceType::SellRandomBuildings { count } => format!(
+            "Sells {} random building(s) for {:.0}% of their price",
+            count,
+            crate::events::SELL_REFUND_FRACTION * 100.0
+        ),
+        ConsequenceType::TakeLoan { principal, per_second } => format!(
+            "Loan {}, repaid at ${}/s with {}% interest",
+            format_money_change(*principal),
+            per_second,
+            crate::events::LOAN_INTEREST_PERCENT
+        ),
+        ConsequenceType::Hidden(_) => "???".to_string(),
+    }
+}
+
+/// Hover tooltip on every choice, listing exactly what it does and why it's disabled if it is.
+/// Lives under the modal so it goes when the modal does, even mid-hover
 pub fn handle_choice_tooltip(
     mut commands: Commands,
     button_query: Query<(Entity, &Interaction, &EventChoiceButton)>,
-    tooltip_query: Query<(Entity, &ChoiceTooltip)>,
-    windows: Query<&Window>,
+    mut tooltip_query: Query<(Entity, &ChoiceTooltip, &mut Node, &ComputedNode)>,
+    modal_query: Query<(Entity, &StoredEventData), With<InteractiveEventModal>>,
+    window: Single<&Window, With<bevy::window::PrimaryWindow>>,
     game_assets: Res<GameAssets>
 ) {
-    // Remove tooltips for buttons that are no longer hovered
-    for (tooltip_entity, tooltip) in tooltip_query.iter() {
-        if let Ok((_, interaction, _)) = button_query.get(tooltip.parent_button) {
-            if *interaction != Interaction::Hovered {
-                commands.entity(tooltip_entity).despawn();
+    let hovered = button_query
+        .iter()
+        .find(|(_, interaction, _)| **interaction == Interaction::Hovered);
+    let cursor = window.cursor_position();
+
+    // Drop tooltips for buttons that aren't hovered (or are gone), keep the rest by the cursor
+    let mut tooltip_exists = false;
+    for (tooltip_entity, tooltip, mut node, computed) in tooltip_query.iter_mut() {
+        if hovered.is_some_and(|(entity, _, _)| entity == tooltip.parent_button) {
+            tooltip_exists = true;
+            if let Some(cursor) = cursor {
+                crate::ui::shop_tooltip::place_by_cursor(&mut node, computed, cursor, &window);
             }
         } else {
-            // Button no longer exists
             commands.entity(tooltip_entity).despawn();
         }
     }
-    
-    // Show tooltip when hovering over disabled button
-    for (button_entity, interaction, button) in button_query.iter() {
-        if *interaction == Interaction::Hovered && button.is_disabled
-            && let Some(reason) = &button.disabled_reason {
-                // Check if tooltip already exists for this button
-                let tooltip_exists = tooltip_query.iter().any(|(_, t)| t.parent_button == button_entity);
-                
-                if !tooltip_exists {
-                    // Get cursor position if available
-                    let (cursor_x, cursor_y) = if let Ok(window) = windows.single() {
-                        if let Some(cursor_pos) = window.cursor_position() {
-                            (cursor_pos.x, cursor_pos.y)
-                        } else {
-                            (100.0, 100.0)
-                        }
-                    } else {
-                        (100.0, 100.0)
-                    };
-                    
-                    // Spawn tooltip at cursor position (not as a child)
-                    commands.spawn((
-                        Node {
-                            position_type: PositionType::Absolute,
-                            left: Val::Px(cursor_x + 15.0), // Slight offset from cursor
-                            top: Val::Px(cursor_y + 15.0),
-                            padding: UiRect::all(Val::Vw(0.8)),
-                            ..default()
-                        },
-                        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.95)),
-                        BorderColor::all(Color::srgb(0.9, 0.4, 0.4)),
-                        BorderRadius::all(Val::Px(4.0)),
-                        ZIndex(2000),
-                        ChoiceTooltip {
-                            parent_button: button_entity,
-                        },
-                    ))
-                    .with_children(|parent| {
-                        parent.spawn((
-                            Text::new(reason),
-                            game_assets.text_font(14.0),
-                            TextColor(Color::srgb(1.0, 0.8, 0.8)),
-                            ScalableText::from_vw(1.1),
-                        ));
-                    });
-                }
-            }
+
+    let Some((button_entity, _, button)) = hovered else { return };
+    if tooltip_exists {
+        return;
+    }
+    let Some((modal, stored)) = modal_query.iter().next() else { return };
+    let Some(choice) = stored.event_data.choices.get(button.choice_index) else { return };
+
+    let mut lines: Vec<String> = choice.consequences.iter().map(consequence_line).collect();
+    if lines.is_empty() {
+        lines.push("No effect".to_string());
     }
+    // first frame sits right by the cursor, place_by_cursor tidies it once it has a size
+    let cursor = cursor.unwrap_or(Vec2::splat(100.0));
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(cursor.x + 15.0),
+            top: Val::Px(cursor.y + 15.0),
+            padding: UiRect::all(Val::Vw(0.8)),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Vh(0.4),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.95)),
+        BorderColor::all(if button.is_disabled { Color::srgb(0.9, 0.4, 0.4) } else { Color::srgb(0.5, 0.5, 0.5) }),
+        BorderRadius::all(Val::Px(4.0)),
+        ZIndex(2000),
+        Pickable::IGNORE,
+        ChoiceTooltip {
+            parent_button: button_entity,
+        },
+        ChildOf(modal),
+    ))
+    .with_children(|parent| {
+        if button.is_disabled
+            && let Some(reason) = &button.disabled_reason
+        {
+            parent.spawn((
+                Text::new(reason),
+                game_assets.text_font(14.0),
+                TextColor(Color::srgb(1.0, 0.8, 0.8)),
+                ScalableText::from_vw(1.1),
+            ));
+        }
+        parent.spawn((
+            Text::new(lines.join("\n")),
+            game_assets.text_font(14.0),
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ScalableText::from_vw(1.1),
+        ));
+    });
 }
 
 /// OLD system - replaced by route_events_by_urgency
@@ -545,6 +761,8 @@ pub fn show_interactive_event_system_old(
     player: Res<Player>,
     factions: Res<FactionReputations>,
     event_state: Res<EventState>,
+    buildings: Res<crate::events::BuildingCounts>,
+    production: Res<crate::factory::logical::ProductionSummary>,
 ) {
     // Get the first event (if any)
     if let Some(event) = events.read().next() {
@@ -561,6 +779,8 @@ pub fn show_interactive_event_system_old(
             player: &player,
             factions: &factions,
             event_state: &event_state,
+            buildings: &buildings,
+            production: &production,
         };
         
         spawn_event_modal(&mut commands, event.0.clone(), &game_assets, &context);
@@ -755,23 +975,28 @@ pub fn handle_choice_click(
 }
 
 pub fn test_trigger_random_event(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    controls: crate::controls::Controls,
     time: Res<Time>,
     event_library: Res<crate::events::InteractiveEventLibrary>,
     player: Res<crate::player::Player>,
     factions: Res<crate::factions::FactionReputations>,
     event_state: Res<crate::events::EventState>,
+    buildings: Res<crate::events::BuildingCounts>,
+    production: Res<crate::factory::logical::ProductionSummary>,
     queued_events: Res<QueuedEvents>,
+    difficulty: Res<crate::difficulty::Difficulty>,
     mut show_event: MessageWriter<ShowInteractiveEvent>,
 ) {
     use rand::prelude::*;
 
-    if keyboard.just_pressed(KeyCode::KeyE) {
+    if controls.just_pressed(crate::controls::InputAction::TestEvent) {
         // Build game context (same as random_event_trigger_system)
         let context = crate::events::GameContext {
             player: &player,
             factions: &factions,
             event_state: &event_state,
+            buildings: &buildings,
+            production: &production,
         };
 
         // Get queued event IDs to filter them out
@@ -780,7 +1005,12 @@ pub fn test_trigger_random_event(
             .collect();
 
         // Get all eligible random events with their weights (filters by requirements and cooldown)
-        let eligible = event_library.get_eligible_random_events(&context, time.elapsed_secs_f64(), &queued_ids);
+        let eligible = event_library.get_eligible_random_events(
+            &context,
+            time.elapsed_secs_f64(),
+            &queued_ids,
+            difficulty.multipliers().event_cooldown,
+        );
         
         if eligible.is_empty() {
             warn!("No eligible random events found!");
@@ -807,12 +1037,45 @@ pub fn test_trigger_random_event(
 
 // ============== Event Bubble Queue System ==============
 
-/// Resource to store queued non-urgent events
+/// Resource to store queued non-urgent events, plus urgent ones waiting on the modal in
+/// their way. The urgent ones always sit at the front
 #[derive(Resource, Default, Debug)]
 pub struct QueuedEvents {
     pub events: Vec<InteractiveEventData>,
 }
 
+impl QueuedEvents {
+    /// An event whose modal was closed under it by an urgent one goes back to the very
+    /// front, urgent, so it opens again as soon as that's answered
+    pub fn requeue_displaced(&mut self, mut event: InteractiveEventData) {
+        self.events.retain(|e| e.event_id != event.event_id);
+        event.popup_urgency = true;
+        self.events.insert(0, event);
+    }
+
+    /// An urgent event that can't open yet waits behind the other urgent ones
+    pub fn queue_urgent(&mut self, event: InteractiveEventData) {
+        if self.events.iter().any(|e| e.event_id == event.event_id) {
+            return;
+        }
+        let behind = self.events.iter().take_while(|e| e.popup_urgency).count();
+        self.events.insert(behind, event);
+    }
+
+    /// The urgent event to open next, if one's waiting
+    pub fn pop_urgent(&mut self) -> Option<InteractiveEventData> {
+        if self.events.first().is_some_and(|e| e.popup_urgency) {
+            Some(self.events.remove(0))
+        } else {
+            None
+        }
+    }
+
+    fn urgent_at_front(&self) -> bool {
+        self.events.iter().skip_while(|e| e.popup_urgency).all(|e| !e.popup_urgency)
+    }
+}
+
 /// Component marking an event bubble in the bottom left
 #[derive(Component, Debug)]
 pub struct EventBubble {
@@ -831,24 +1094,38 @@ pub struct BubbleWobble {
     pub amplitude: f32,
 }
 
-/// System that handles event routing based on urgency
+/// System that handles event routing based on urgency. Only one modal is ever open: an urgent
+/// event closes the one in its way and puts it back at the front of the queue, and any more
+/// urgent events the same frame queue up behind it
 pub fn route_events_by_urgency(
     mut show_events: MessageReader<ShowInteractiveEvent>,
     mut queued_events: ResMut<QueuedEvents>,
     mut commands: Commands,
-    existing_modals: Query<Entity, With<InteractiveEventModal>>,
+    existing_modals: Query<(Entity, &StoredEventData), With<InteractiveEventModal>>,
     mut cooldown: ResMut<ModalSpawnCooldown>,
     game_assets: Res<GameAssets>,
     player: Res<Player>,
     factions: Res<FactionReputations>,
     event_state: Res<EventState>,
+    buildings: Res<crate::events::BuildingCounts>,
+    production: Res<crate::factory::logical::ProductionSummary>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut sfx: MessageWriter<crate::audio::PlaySfx>,
 ) {
+    let mut opened = false;
     for event in show_events.read() {
+        sfx.write(crate::audio::PlaySfx(crate::audio::Sfx::EventPopup));
         if event.0.popup_urgency {
+            // the modal spawned a moment ago isn't in the query yet, this one waits for it
+            if opened {
+                info!("Urgent event {} waits for the open modal", event.0.event_id);
+                queued_events.queue_urgent(event.0.clone());
+                continue;
+            }
             // Urgent event - show immediately
-            // Only show one modal at a time - dismiss any existing ones
-            for entity in existing_modals.iter() {
+            for (entity, stored) in existing_modals.iter() {
+                info!("Event {} displaced by urgent {}", stored.event_data.event_id, event.0.event_id);
+                queued_events.requeue_displaced(stored.event_data.clone());
                 commands.entity(entity).despawn();
             }
 
@@ -858,12 +1135,15 @@ pub fn route_events_by_urgency(
                 player: &player,
                 factions: &factions,
                 event_state: &event_state,
+                buildings: &buildings,
+                production: &production,
             };
             
             // Pause the game when showing modal
             next_state.set(GameState::EventModal);
             
             spawn_event_modal(&mut commands, event.0.clone(), &game_assets, &context);
+            opened = true;
         } else {
             // Non-urgent event - add to queue only if not already queued
             if !queued_events.events.iter().any(|e| e.event_id == event.0.event_id) {
@@ -871,6 +1151,38 @@ pub fn route_events_by_urgency(
             }
         }
     }
+    debug_assert!(queued_events.urgent_at_front(), "urgent events must wait at the front of the queue");
+}
+
+/// Once the modal in their way is answered, queued urgent events open one at a time
+pub fn open_waiting_urgent_event(
+    mut commands: Commands,
+    mut queued_events: ResMut<QueuedEvents>,
+    existing_modals: Query<(), With<InteractiveEventModal>>,
+    mut cooldown: ResMut<ModalSpawnCooldown>,
+    game_assets: Res<GameAssets>,
+    player: Res<Player>,
+    factions: Res<FactionReputations>,
+    event_state: Res<EventState>,
+    buildings: Res<crate::events::BuildingCounts>,
+    production: Res<crate::factory::logical::ProductionSummary>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !existing_modals.is_empty() || !queued_events.events.first().is_some_and(|e| e.popup_urgency) {
+        return;
+    }
+    let Some(event) = queued_events.pop_urgent() else { return };
+    info!("Reopening urgent event {}", event.event_id);
+    cooldown.just_spawned();
+    let context = GameContext {
+        player: &player,
+        factions: &factions,
+        event_state: &event_state,
+        buildings: &buildings,
+        production: &production,
+    };
+    next_state.set(GameState::EventModal);
+    spawn_event_modal(&mut commands, event, &game_assets, &context);
 }
 
 /// System that spawns/updates event bubbles in the bottom left
@@ -921,7 +1233,7 @@ fn spawn_event_bubble(
                 bottom: Val::Px(bottom_position),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
-                border: UiRect::all(Val::Px(3.0)),
+                border: UiRect::all(Val::Px(BUBBLE_RING_WIDTH)),
                 overflow: Overflow::clip(), // Clip children to circular shape
                 ..default()
             },
@@ -976,12 +1288,14 @@ pub fn handle_bubble_clicks(
     mut commands: Commands,
     interaction_query: Query<(&Interaction, &EventBubble), Changed<Interaction>>,
     mut queued_events: ResMut<QueuedEvents>,
-    existing_modals: Query<Entity, With<InteractiveEventModal>>,
+    existing_modals: Query<(Entity, &StoredEventData), With<InteractiveEventModal>>,
     mut cooldown: ResMut<ModalSpawnCooldown>,
     game_assets: Res<GameAssets>,
     player: Res<Player>,
     factions: Res<FactionReputations>,
     event_state: Res<EventState>,
+    buildings: Res<crate::events::BuildingCounts>,
+    production: Res<crate::factory::logical::ProductionSummary>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     for (interaction, bubble) in interaction_query.iter() {
@@ -989,8 +1303,12 @@ pub fn handle_bubble_clicks(
             // Remove this event from the queue
             queued_events.events.retain(|e| e.event_id != bubble.event_data.event_id);
             
-            // Close any existing modals
-            for entity in existing_modals.iter() {
+            // Close any existing modal, its event goes back to being a bubble
+            for (entity, stored) in existing_modals.iter() {
+                let mut displaced = stored.event_data.clone();
+                displaced.popup_urgency = false;
+                let behind = queued_events.events.iter().take_while(|e| e.popup_urgency).count();
+                queued_events.events.insert(behind, displaced);
                 commands.entity(entity).despawn();
             }
             
@@ -1000,6 +1318,8 @@ pub fn handle_bubble_clicks(
                 player: &player,
                 factions: &factions,
                 event_state: &event_state,
+                buildings: &buildings,
+                production: &production,
             };
             
             // Pause the game when showing modal
@@ -1011,17 +1331,83 @@ pub fn handle_bubble_clicks(
     }
 }
 
+/// Counts down queued events, the ones that run out are missed
+pub fn expire_queued_events(
+    time: Res<Time>,
+    mut queued_events: ResMut<QueuedEvents>,
+    mut ignored_writer: MessageWriter<EventIgnored>,
+    mut news_writer: MessageWriter<crate::events::AddNewsfeedItemEvent>,
+) {
+    // ticking every frame shouldn't count as a change, or the bubbles get rebuilt every frame
+    for event in queued_events.bypass_change_detection().events.iter_mut() {
+        event.expires_in -= time.delta_secs();
+    }
+    if !queued_events.events.iter().any(|e| e.expires_in <= 0.0) {
+        return;
+    }
+
+    let (expired, kept): (Vec<_>, Vec<_>) = queued_events.events.drain(..).partition(|e| e.expires_in <= 0.0);
+    queued_events.events = kept;
+    for event in expired {
+        info!("Queued event {} expired", event.event_id);
+        ignored_writer.write(EventIgnored { event_id: event.event_id.clone() });
+        news_writer.write(crate::events::AddNewsfeedItemEvent {
+            faction: event.faction.unwrap_or_default(),
+            headline: format!("Missed opportunity: {}", event.title),
+            payload: event.faction.map(crate::events::NewsPayload::Faction),
+        });
+    }
+}
+
+/// Border of each bubble drains clockwise as its time runs out, going red near the end
+pub fn update_bubble_countdown(
+    queued_events: Res<QueuedEvents>,
+    mut bubbles: Query<(&EventBubble, &mut BorderColor)>,
+    game_assets: Res<GameAssets>,
+) {
+    for (bubble, mut border) in bubbles.iter_mut() {
+        let Some(event) = queued_events.events.iter().find(|e| e.event_id == bubble.event_data.event_id) else { continue };
+        let fraction = (event.expires_in / event.expiry_seconds.max(0.01)).clamp(0.0, 1.0);
+        let color = if event.expires_in <= BUBBLE_URGENT_SECONDS {
+            Color::srgb(0.95, 0.25, 0.2)
+        } else {
+            event.faction
+                .map(|f| game_assets.faction_color(f))
+                .unwrap_or(Color::srgba(0.2, 0.6, 0.9, 1.0))
+        };
+        // a quarter of the ring per side, left goes first and top last
+        let side = |k: f32| color.with_alpha((fraction * 4.0 - k).clamp(0.0, 1.0));
+        border.top = side(0.0);
+        border.right = side(1.0);
+        border.bottom = side(2.0);
+        border.left = side(3.0);
+    }
+}
+
 /// System that animates event bubbles with a wobble effect
 pub fn animate_bubble_wobble(
-    time: Res<Time>,
-    mut bubbles: Query<(&mut BubbleWobble, &mut Node), With<EventBubble>>,
+    time: Res<Time<Real>>,
+    queued_events: Res<QueuedEvents>,
+    mut bubbles: Query<(&mut BubbleWobble, &mut Node, &EventBubble)>,
 ) {
-    for (mut wobble, mut node) in bubbles.iter_mut() {
+    for (mut wobble, mut node, bubble) in bubbles.iter_mut() {
+        // running out: wobble harder and more often
+        let urgent = queued_events
+            .events
+            .iter()
+            .find(|e| e.event_id == bubble.event_data.event_id)
+            .is_some_and(|e| e.expires_in <= BUBBLE_URGENT_SECONDS);
+        let (cycle_duration, amplitude) = if urgent {
+            (wobble.cycle_duration * 0.4, wobble.amplitude * 2.5)
+        } else {
+            (wobble.cycle_duration, wobble.amplitude)
+        };
+
         // Update cycle timer
         wobble.cycle_timer += time.delta_secs();
         
         // Check if it's time to start a new wobble
-        if wobble.cycle_timer >= wobble.cycle_duration && !wobble.is_wobbling {
+        if wobble.cycle_timer >= cycle_duration && !wobble.is_wobbling {
             wobble.is_wobbling = true;
             wobble.timer = 0.0;
             wobble.cycle_timer = 0.0;
@@ -1046,8 +1432,8 @@ pub fn animate_bubble_wobble(
             let decay = 1.0 - ease; // Amplitude decays as wobble progresses
             
             let angle = wobble.timer * wobble.frequency * std::f32::consts::TAU;
-            let x = angle.sin() * wobble.amplitude * decay;
-            let y = (angle * 1.5).cos() * wobble.amplitude * 0.5 * decay;
+            let x = angle.sin() * amplitude * decay;
+            let y = (angle * 1.5).cos() * amplitude * 0.5 * decay;
             (x, y)
         } else {
             (0.0, 0.0)
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use crate::assets::{GameAssets, IconSize};
+use crate::factory::logical::BasicDataType;
+use crate::market::MarketPrices;
+use crate::ui::interactive_event::ScalableText;
+
+/// Marker for the market ticker strip
+#[derive(Component)]
+pub struct MarketTicker;
+
+/// Multiplier text for one data type in the ticker
+#[derive(Component)]
+pub struct MarketTickerText(pub BasicDataType);
+
+/// Trend arrow for one data type in the ticker
+#[derive(Component)]
+pub struct MarketTickerArrow(pub BasicDataType);
+
+/// Spawns the market ticker strip next to the money display
+pub fn spawn_market_ticker_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    let data_types = [
+        BasicDataType::Biometric,
+        BasicDataType::Economic,
+        BasicDataType::Behavioural,
+        BasicDataType::Telemetry,
+    ];
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(65.0),
+                left: Val::Percent(30.0),
+                padding: UiRect::all(Val::Vw(0.5)),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Vw(1.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            ZIndex(100),
+            MarketTicker,
+        ))
+        .with_children(|parent| {
+            for data_type in data_types {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Vw(0.2),
+                        ..default()
+                    })
+                    .with_children(|entry| {
+                        if let Some((_, index)) = game_assets.data_type_icon(data_type, IconSize::Small) {
+                            entry.spawn((
+                                ImageNode::from_atlas_image(
+                                    game_assets.small_sprites_texture.clone(),
+                                    TextureAtlas {
+                                        layout: game_assets.small_sprites_layout.clone(),
+                                        index,
+                                    },
+                                ),
+                                Node {
+                                    width: Val::Vw(1.5),
+                                    height: Val::Vw(1.5),
+                                    ..default()
+                                },
+                            ));
+                        }
+                        entry.spawn((
+                            Text::new("x1.00"),
+                            game_assets.text_font(16.0),
+                            ScalableText::from_vw(0.9),
+                            TextColor(Color::WHITE),
+                            MarketTickerText(data_type),
+                        ));
+                        entry.spawn((
+                            ImageNode::from_atlas_image(
+                                game_assets.small_sprites_texture.clone(),
+                                TextureAtlas {
+                                    layout: game_assets.small_sprites_layout.clone(),
+                                    index: game_assets.utility_icons.arrow_up,
+                                },
+                            ),
+                            Node {
+                                width: Val::Vw(1.0),
+                                height: Val::Vw(1.0),
+                                ..default()
+                            },
+                            Visibility::Hidden,
+                            MarketTickerArrow(data_type),
+                        ));
+                    });
+            }
+        });
+}
+
+/// Refreshes the ticker whenever prices change
+pub fn update_market_ticker(
+    market: Res<MarketPrices>,
+    game_assets: Res<GameAssets>,
+    mut text_query: Query<(&MarketTickerText, &mut Text, &mut TextColor)>,
+    mut arrow_query: Query<(&MarketTickerArrow, &mut ImageNode, &mut Visibility)>,
+) {
+    for (ticker, mut text, mut color) in text_query.iter_mut() {
+        let value = market.get(ticker.0);
+        **text = format!("x{:.2}", value);
+        *color = if value > 1.0 {
+            TextColor(Color::srgb(0.7, 0.9, 0.7))
+        } else if value < 1.0 {
+            TextColor(Color::srgb(0.9, 0.5, 0.5))
+        } else {
+            TextColor(Color::WHITE)
+        };
+    }
+
+    for (arrow, mut image, mut visibility) in arrow_query.iter_mut() {
+        let change = market.get(arrow.0) - market.previous(arrow.0);
+        if change.abs() < f32::EPSILON {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        if let Some(atlas) = image.texture_atlas.as_mut() {
+            atlas.index = if change > 0.0 {
+                game_assets.utility_icons.arrow_up
+            } else {
+                game_assets.utility_icons.arrow_down
+            };
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::ui::RelativeCursorPosition;
+use crate::assets::GameAssets;
+use crate::factions::Locked;
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::buildings::source::SourceBuilding;
+use crate::grid::{Grid, GridPosition};
+use crate::save::PlacedBuilding;
+use crate::ui::BlocksWorldClicks;
+use crate::world_gen::{ClusterCells, LockMarker, WorldBounds};
+
+/// Side of the square map, fits in the corner under the sidebar and right of the shop bar
+pub const MINIMAP_SIZE_VH: f32 = 14.0;
+const BACKGROUND: [u8; 4] = [20, 22, 28, 255];
+
+/// Low resolution image of the world, one pixel per cell
+#[derive(Resource)]
+pub struct MinimapImage(pub Handle<Image>);
+
+#[derive(Component)]
+pub struct Minimap;
+
+/// Holds the source/sink/building dots, rebuilt when the map changes
+#[derive(Component)]
+pub struct MinimapMarkers;
+
+#[derive(Component)]
+pub struct MinimapViewport;
+
+/// Percent across/down the minimap for a grid cell, y flipped since UI goes down
+fn cell_to_percent(cell: Vec2, bounds: &WorldBounds) -> Vec2 {
+    let size = bounds.size().as_vec2();
+    Vec2::new(
+        (cell.x - bounds.min.x as f32) / size.x * 100.0,
+        (bounds.max.y as f32 + 1.0 - cell.y) / size.y * 100.0,
+    )
+}
+
+/// One pixel per cell of the world
+fn image_extent(bounds: &WorldBounds) -> Extent3d {
+    let size = bounds.size();
+    Extent3d {
+        width: size.x as u32,
+        height: size.y as u32,
+        depth_or_array_layers: 1,
+    }
+}
+
+pub fn spawn_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>, bounds: Res<WorldBounds>) {
+    let mut image = Image::new_fill(
+        image_extent(&bounds),
+        TextureDimension::D2,
+        &BACKGROUND,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    // keep the cells crisp when scaled up
+    image.sampler = ImageSampler::nearest();
+    let handle = images.add(image);
+    commands.insert_resource(MinimapImage(handle.clone()));
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Vh(0.5),
+                right: Val::Vh(0.5),
+                width: Val::Vh(MINIMAP_SIZE_VH),
+                height: Val::Vh(MINIMAP_SIZE_VH),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            ImageNode::new(handle),
+            Outline::new(Val::Px(1.0), Val::ZERO, Color::srgb(0.4, 0.4, 0.45)),
+            ZIndex(100),
+            Interaction::None,
+            RelativeCursorPosition::default(),
+            Minimap,
+            BlocksWorldClicks,
+        ))
+        .with_children(|minimap| {
+            minimap.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                MinimapMarkers,
+            ));
+            minimap.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                Outline::new(Val::Px(1.0), Val::ZERO, Color::WHITE),
+                Pickable::IGNORE,
+                MinimapViewport,
+            ));
+        });
+}
+
+/// Tints cluster cells, strongly while still locked and faintly once opened up
+pub fn paint_minimap(
+    minimap: Res<MinimapImage>,
+    mut images: ResMut<Assets<Image>>,
+    cluster_cells: Res<ClusterCells>,
+    game_assets: Res<GameAssets>,
+    bounds: Res<WorldBounds>,
+    locked_cells: Query<&GridPosition, (With<LockMarker>, With<Locked>)>,
+    mut removed_locked: RemovedComponents<Locked>,
+    mut painted: Local<bool>,
+) {
+    let unlocked_any = removed_locked.read().count() > 0;
+    if *painted && !unlocked_any && !cluster_cells.is_changed() && !bounds.is_changed() {
+        return;
+    }
+    // first frames the lock cells might not be spawned yet
+    if locked_cells.is_empty() && !cluster_cells.0.is_empty() {
+        return;
+    }
+    let Some(image) = images.get_mut(&minimap.0) else { return };
+    *painted = true;
+    if image.texture_descriptor.size != image_extent(&bounds) {
+        image.resize(image_extent(&bounds));
+    }
+
+    let locked: bevy::platform::collections::HashSet<I64Vec2> = locked_cells.iter().map(|p| p.0).collect();
+    let background = Color::srgba_u8(BACKGROUND[0], BACKGROUND[1], BACKGROUND[2], BACKGROUND[3]);
+    let size = bounds.size();
+    for x in 0..size.x {
+        for y in 0..size.y {
+            let cell = I64Vec2::new(bounds.min.x + x, bounds.max.y - y);
+            let color = match cluster_cells.0.get(&cell) {
+                Some((faction, _)) if locked.contains(&cell) => game_assets.faction_color(*faction).mix(&background, 0.2),
+                Some((faction, _)) => game_assets.faction_color(*faction).mix(&background, 0.8),
+                None => background,
+            };
+            let _ = image.set_color_at(x as u32, y as u32, color);
+        }
+    }
+}
+
+fn spawn_dot(markers: &mut ChildSpawnerCommands, bounds: &WorldBounds, cell: I64Vec2, size_px: f32, color: Color) {
+    let percent = cell_to_percent(cell.as_vec2() + Vec2::splat(0.5), bounds);
+    markers.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(percent.x),
+            top: Val::Percent(percent.y),
+            width: Val::Px(size_px),
+            height: Val::Px(size_px),
+            margin: UiRect::all(Val::Px(-size_px / 2.0)),
+            ..default()
+        },
+        BackgroundColor(color),
+        Pickable::IGNORE,
+    ));
+}
+
+/// Sources, sinks and the player's buildings as dots. Runs when the map changes or a cluster unlocks.
+pub fn update_minimap_markers(
+    mut commands: Commands,
+    markers: Single<Entity, With<MinimapMarkers>>,
+    sources: Query<(&GridPosition, Has<Locked>), With<SourceBuilding>>,
+    sinks: Query<(&GridPosition, Has<Locked>), With<SinkBuilding>>,
+    buildings: Query<&GridPosition, With<PlacedBuilding>>,
+    bounds: Res<WorldBounds>,
+) {
+    commands.entity(*markers).despawn_children().with_children(|markers| {
+        for position in buildings.iter() {
+            spawn_dot(markers, &bounds, position.0, 2.0, Color::srgb(0.75, 0.75, 0.75));
+        }
+        for (position, locked) in sources.iter() {
+            let alpha = if locked { 0.4 } else { 1.0 };
+            spawn_dot(markers, &bounds, position.0, 4.0, Color::srgba(0.3, 0.7, 1.0, alpha));
+        }
+        for (position, locked) in sinks.iter() {
+            let alpha = if locked { 0.4 } else { 1.0 };
+            spawn_dot(markers, &bounds, position.0, 5.0, Color::srgba(1.0, 0.85, 0.2, alpha));
+        }
+    });
+}
+
+/// Keeps the rectangle over whatever the camera is looking at
+pub fn update_minimap_viewport(
+    camera: Single<(&Transform, &Projection), With<Camera>>,
+    grid: Res<Grid>,
+    bounds: Res<WorldBounds>,
+    mut viewport: Single<&mut Node, With<MinimapViewport>>,
+) {
+    let (transform, projection) = *camera;
+    let Projection::Orthographic(orthographic) = projection else { return };
+    let center = transform.translation.xy();
+    let to_cell = |world: Vec2| (world - Vec2::splat(grid.base_offset)) / grid.scale;
+    let top_left = cell_to_percent(to_cell(center + Vec2::new(orthographic.area.min.x, orthographic.area.max.y)), &bounds);
+    let bottom_right =
+        cell_to_percent(to_cell(center + Vec2::new(orthographic.area.max.x, orthographic.area.min.y)), &bounds);
+
+    viewport.left = Val::Percent(top_left.x);
+    viewport.top = Val::Percent(top_left.y);
+    viewport.width = Val::Percent((bottom_right.x - top_left.x).max(0.0));
+    viewport.height = Val::Percent((bottom_right.y - top_left.y).max(0.0));
+}
+
+/// Clicking the map jumps the camera to that spot
+pub fn handle_minimap_click(
+    minimap: Query<(&Interaction, &RelativeCursorPosition), (Changed<Interaction>, With<Minimap>)>,
+    camera: Single<(&mut Transform, &mut Projection), With<Camera>>,
+    grid: Res<Grid>,
+    bounds: Res<WorldBounds>,
+) {
+    let Some((_, cursor)) = minimap.iter().find(|(i, _)| **i == Interaction::Pressed) else { return };
+    // normalized is relative to the node's center, -0.5..0.5
+    let Some(normalized) = cursor.normalized else { return };
+    let size = bounds.size().as_vec2();
+    let cell = I64Vec2::new(
+        bounds.min.x + ((normalized.x + 0.5) * size.x) as i64,
+        bounds.max.y - ((normalized.y + 0.5) * size.y) as i64,
+    );
+    let (mut transform, mut projection) = camera.into_inner();
+    if let Projection::Orthographic(ref mut orthographic) = *projection {
+        info!("Minimap jump to {:?}", cell);
+        crate::camera::focus_camera_on_grid_pos(&GridPosition(cell), &grid, &mut transform, orthographic);
+    }
+}
@@ -4,12 +4,32 @@ use crate::{assets::GameAssets, ui::tooltip::TooltipPlugin};
 use crate::player::Player;
 use bevy::{color::palettes::css::BROWN, prelude::*};
 
+pub mod achievements;
+pub mod changelog;
+pub mod cluster_unlock;
 pub mod contracts;
+pub mod difficulty;
+pub mod filter_panel;
 pub mod interactive_event;
 pub mod newsfeed;
+pub mod overlay_legend;
+pub mod pause_menu;
+pub mod profile;
 pub mod shop;
+pub mod shop_tooltip;
+pub mod sink_panel;
 pub mod tooltip;
 pub mod money;
+pub mod reputation;
+pub mod market;
+pub mod minimap;
+pub mod settings;
+pub mod sidebar;
+pub mod sidebar_tabs;
+pub mod stats;
+pub mod toast;
+pub mod trace_viewer;
+pub mod widgets;
 
 pub mod interaction;
 
@@ -43,6 +63,11 @@ impl Plugin for UIPlugin {
         use crate::pause::GameState;
         
         app.insert_resource(shop::SelectedBuildingType(None))
+            .init_resource::<shop::PlacementState>()
+            .init_resource::<shop_tooltip::ShopHover>()
+            .init_resource::<sidebar::UiOccludedMargins>()
+            .init_resource::<sidebar::SidebarSlide>()
+            .init_resource::<sidebar_tabs::SidebarTab>()
             .insert_resource(newsfeed::RecentNewsIds::new(5))
             .insert_resource(interactive_event::ModalSpawnCooldown::default())
             .insert_resource(interactive_event::QueuedEvents::default())
@@ -51,26 +76,172 @@ impl Plugin for UIPlugin {
                 (
                     contracts::send_scroll_events,
                     contracts::handle_contract_buttons,
+                    contracts::handle_contract_pin_buttons,
+                    contracts::open_contract_cancel_popup,
+                    contracts::handle_contract_cancel_popup,
                     contracts::update_contracts_sidebar_ui,
                     contracts::resize_contract_data_icons,
                     contracts::show_dataset_tooltip,
+                    contracts::show_accept_blocked_tooltip,
                 )
                     .chain(),
             )
             .add_observer(contracts::on_scroll_handler)
             .add_systems(Startup, spawn_paused_indicator)
             .add_systems(Startup, shop::spawn_building_shop)
+            .add_systems(Startup, shop::spawn_placement_chain_counter)
+            .add_systems(Startup, shop::spawn_placement_replace_label)
+            .add_systems(Startup, shop_tooltip::spawn_shop_tooltip)
             .add_systems(Startup, newsfeed::spawn_newsfeed_ui)
             .add_systems(Startup, contracts::spawn_contracts_sidebar_ui)
             .add_systems(Startup, money::spawn_money_display_ui)
-            .add_systems(Update, money::update_money_display.run_if(resource_changed::<Player>))
+            .add_systems(Startup, (
+                reputation::spawn_reputation_panel,
+                sidebar_tabs::spawn_event_log_placeholder,
+            ).after(contracts::spawn_contracts_sidebar_ui))
+            .add_systems(Startup, settings::spawn_settings_ui)
+            .add_systems(Startup, settings::spawn_controls_ui)
+            .add_systems(Startup, sidebar::spawn_sidebar_collapse_ui)
+            .add_systems(Startup, achievements::spawn_achievements_ui)
+            .add_systems(Startup, changelog::spawn_changelog_ui)
+            .add_systems(Startup, difficulty::spawn_difficulty_screen)
+            .add_systems(Update, (
+                difficulty::apply_difficulty_widgets.after(widgets::update_widget_visuals),
+                difficulty::handle_difficulty_start,
+            ).run_if(in_state(GameState::Setup)))
+            .add_systems(Update, (
+                changelog::handle_changelog_buttons,
+                changelog::update_changelog_panel.run_if(resource_changed::<crate::changelog::WhatsNew>),
+            ).chain())
+            .add_systems(Update, (
+                achievements::toggle_achievements_panel,
+                achievements::update_achievements_list,
+                achievements::show_achievement_toasts,
+                toast::update_toasts,
+            ).chain())
+            .add_systems(Startup, minimap::spawn_minimap)
+            .add_systems(Startup, overlay_legend::spawn_overlay_legend)
+            .add_systems(Update, overlay_legend::update_overlay_legend.run_if(
+                resource_changed::<crate::factory::throughput_overlay::ThroughputOverlay>.or(resource_changed::<crate::palette::Palette>),
+            ))
+            .add_systems(Update, (
+                minimap::paint_minimap,
+                minimap::update_minimap_markers.run_if(
+                    resource_changed::<crate::grid::WorldMap>
+                        .or(resource_changed::<crate::factions::FactionReputations>)
+                        .or(resource_changed::<crate::world_gen::WorldBounds>),
+                ),
+                minimap::update_minimap_viewport,
+                minimap::handle_minimap_click,
+            ).chain())
+            .add_systems(Update, (
+                filter_panel::close_orphaned_filter_panel,
+                filter_panel::handle_filter_panel_buttons,
+                filter_panel::open_filter_panel_on_click,
+            ).chain().run_if(in_state(GameState::Running).or(in_state(GameState::ManualPause))))
+            .add_systems(Update, (
+                sink_panel::refresh_sink_panel,
+                sink_panel::open_sink_panel_on_click,
+                sink_panel::follow_sink_panel,
+            ).chain().after(contracts::handle_contract_buttons))
+            .init_resource::<cluster_unlock::UnlockCeremonies>()
+            .add_systems(Update, (
+                cluster_unlock::queue_cluster_unlocks,
+                cluster_unlock::dismiss_unlock_banner,
+                cluster_unlock::run_unlock_ceremony,
+            ).chain())
+            .add_systems(Startup, market::spawn_market_ticker_ui)
+            .init_resource::<stats::StatsHistory>()
+            .add_systems(Startup, stats::spawn_stats_panel)
+            .add_systems(Update, (
+                stats::toggle_stats_panel,
+                stats::update_stats_difficulty.run_if(resource_changed::<crate::difficulty::Difficulty>),
+                stats::handle_stats_toggles,
+                stats::redraw_stats_graphs,
+                stats::update_stats_contracts,
+            ).chain())
+            .add_systems(Startup, trace_viewer::spawn_trace_viewer_ui)
+            .add_systems(Update, (
+                trace_viewer::toggle_trace_viewer,
+                trace_viewer::handle_trace_viewer_buttons,
+                trace_viewer::update_trace_viewer_list,
+            ).chain())
+            .add_systems(Update, market::update_market_ticker.run_if(resource_changed::<crate::market::MarketPrices>))
+            .add_systems(Update, (
+                // closing first so a menu opened this frame isn't taken down before the pause lands
+                pause_menu::close_pause_menu_when_unpaused,
+                pause_menu::handle_escape,
+                pause_menu::handle_pause_menu_buttons,
+                profile::handle_profile_close,
+            ).chain())
+            .add_systems(Update, (
+                settings::handle_settings_buttons,
+                settings::apply_setting_widgets.after(widgets::update_widget_visuals),
+                (
+                    settings::handle_controls_buttons,
+                    settings::update_rebind_labels
+                        .run_if(resource_changed::<crate::controls::InputBindings>.or(resource_changed::<settings::RebindState>)),
+                ).chain(),
+            ))
+            .init_resource::<settings::RebindState>()
+            .add_systems(PreUpdate, settings::capture_rebind.after(bevy::input::InputSystems))
+            .add_systems(Update, (
+                sidebar::handle_sidebar_collapse_input,
+                sidebar_tabs::handle_sidebar_tab_input,
+                sidebar::animate_sidebar_slide,
+                sidebar::update_sidebar_chevron.run_if(resource_changed::<crate::settings::Settings>),
+                sidebar::update_collapsed_tab,
+                sidebar::update_ui_occluded_margins,
+                sidebar_tabs::update_sidebar_tabs.run_if(resource_changed::<sidebar_tabs::SidebarTab>),
+                sidebar_tabs::log_resolved_events,
+            ).chain())
+            .init_resource::<money::DisplayedMoney>()
+            .add_systems(Update, (
+                (money::update_money_display, money::spawn_money_drop_popups).run_if(resource_changed::<Player>),
+                money::animate_money_display,
+                money::update_money_drop_popups,
+                money::spawn_sink_income_popups,
+                money::update_sink_income_popups,
+                money::update_debt_display
+                    .run_if(resource_changed::<crate::events::DebtState>.or(resource_changed::<Player>)),
+                money::handle_speed_buttons,
+                money::update_speed_display
+                    .run_if(resource_changed::<crate::game_speed::GameSpeed>.or(state_changed::<crate::pause::GameState>)),
+            ).chain())
+            .add_systems(Update, (
+                reputation::update_reputation_panel.run_if(resource_changed::<crate::factions::FactionReputations>),
+                reputation::flash_changed_reputation_rows,
+                reputation::flash_reputation_rows,
+                reputation::spawn_reputation_popups,
+                reputation::update_reputation_popups,
+                reputation::show_reputation_tooltip,
+            ).chain())
             .add_systems(Update, (update_paused_indicator, animate_paused_fade))
             // Shop systems should work in Running and ManualPause (allow building placement while paused)
             .add_systems(Update, (
-                shop::handle_building_click,
-                shop::update_selected_building_position,
-                shop::handle_placement_click,
-                shop::handle_building_rotate,
+                // ghost snaps first so placement reads the same cell, and a placing
+                // press is consumed before the shop sees it
+                (
+                    shop::update_selected_building_position,
+                    shop::update_placement_cell_highlights,
+                    shop::handle_placement_click,
+                    shop::handle_building_click,
+                    shop::update_placement_chain_counter,
+                    shop::show_out_of_money_warning,
+                    shop::update_placement_warnings,
+                ).chain(),
+                (
+                    shop::handle_building_rotate,
+                    shop::handle_building_flip,
+                    shop::update_ghost_port_arrows,
+                    shop::tint_blocked_ghost_ports,
+                ).chain(),
+                shop::handle_building_hotkeys,
+                shop::handle_gamepad_shop_cycle,
+                shop::handle_pipette,
+                shop_tooltip::update_shop_tooltip,
+                shop::update_shop_locks.run_if(resource_changed::<crate::factions::FactionReputations>),
+                shop::animate_shop_unlock_pulse,
             ).run_if(in_state(GameState::Running).or(in_state(GameState::ManualPause))))
             // Newsfeed only during gameplay
             .add_systems(Update, (
@@ -78,13 +249,26 @@ impl Plugin for UIPlugin {
                 newsfeed::add_newsfeed_item_system,
                 newsfeed::scroll_newsfeed_items,
             ).run_if(in_state(GameState::Running)))
-            // Event routing and bubbles work in all non-modal states
+            // links stay clickable while paused, the ticker just stops moving
+            .add_systems(Update, (
+                newsfeed::update_newsfeed_link_hover,
+                newsfeed::handle_newsfeed_clicks,
+            ))
+            // Routing runs under an open modal too, so an urgent event can take its place
+            .add_systems(Update, interactive_event::route_events_by_urgency.run_if(not(in_state(GameState::Setup))))
+            // urgent events that had to wait open once the game's going again
+            .add_systems(Update, interactive_event::open_waiting_urgent_event
+                .after(interactive_event::route_events_by_urgency)
+                .run_if(in_state(GameState::Running)))
+            // Bubbles work in all non-modal states
             .add_systems(Update, (
-                interactive_event::route_events_by_urgency,
                 interactive_event::manage_event_bubbles,
                 interactive_event::handle_bubble_clicks,
+                interactive_event::update_bubble_countdown,
                 interactive_event::animate_bubble_wobble,
             ).run_if(in_state(GameState::Running).or(in_state(GameState::ManualPause))))
+            // bubbles only run out while the game is actually going
+            .add_systems(Update, interactive_event::expire_queued_events.run_if(in_state(GameState::Running)))
             // Modal interaction always runs (needed when modal is open)
             .add_systems(
                 Update,
@@ -103,6 +287,7 @@ impl Plugin for UIPlugin {
                 clear_selection.before(remove_physical_link_on_right_click),
             );
         app.add_plugins(TooltipPlugin);
+        app.add_plugins(widgets::WidgetsPlugin);
     }
 }
 
@@ -154,7 +339,7 @@ fn update_paused_indicator(
 }
 
 fn animate_paused_fade(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     state: Res<State<crate::pause::GameState>>,
     mut query: Query<(&mut PausedFadeAnimation, &mut TextColor)>,
 ) {
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use crate::player::Player;
 use crate::ui::interactive_event::ScalableText;
 use crate::assets::GameAssets;
+use crate::ui::BlocksWorldClicks;
 
 #[derive(Component)]
 pub struct MoneyDisplay;
@@ -12,6 +13,56 @@ pub struct MoneyText;
 #[derive(Component)]
 pub struct IncomeText;
 
+/// Under the balance while there's a loan to pay or the creditors' clock is running
+#[derive(Component)]
+pub struct DebtText;
+
+/// "-" and "+" either side of the speed readout
+#[derive(Component, Clone, Copy)]
+pub enum SpeedButton {
+    Slower,
+    Faster,
+}
+
+#[derive(Component)]
+pub struct SpeedText;
+
+/// Seconds the main number takes to count up or down to a new balance
+const MONEY_COUNT_SECONDS: f32 = 0.5;
+/// Drops at least this big in one go get a floating "-$x"
+const MONEY_DROP_POPUP_MIN: i32 = 50;
+const MONEY_POPUP_SECONDS: f32 = 1.5;
+
+/// What the money text is showing right now, counting towards `Player.money`
+#[derive(Resource, Default)]
+pub struct DisplayedMoney {
+    pub from: f32,
+    pub shown: f32,
+    pub target: i32,
+    pub elapsed: f32,
+    /// Balance last frame, for spotting sudden drops
+    pub last_seen: Option<i32>,
+}
+
+/// Floating "-$250" next to the money display, drifts up and fades out
+#[derive(Component)]
+pub struct MoneyDeltaPopup {
+    pub age: f32,
+}
+
+/// Floating "+$120" over a sink when its contracts pay out
+#[derive(Component)]
+pub struct SinkIncomePopup {
+    pub age: f32,
+    /// World units per second, straight up
+    pub velocity: f32,
+}
+
+const SINK_INCOME_POPUP_SECONDS: f32 = 1.5;
+const SINK_INCOME_POPUP_SPEED: f32 = 40.0;
+/// Zoomed out past this the text would be a few pixels at most, not worth spawning
+const SINK_INCOME_POPUP_MAX_SCALE: f32 = 4.0;
+
 /// Spawns the money display UI below the newsfeed with scaling support
 pub fn spawn_money_display_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
     commands.spawn((
@@ -55,33 +106,149 @@ pub fn spawn_money_display_ui(mut commands: Commands, game_assets: Res<GameAsset
                 Node::default(),
                 MoneyText,
             ));
+
+            // Income rate, "(+$87/s)"
+            money_row.spawn((
+                Text::new("(+$0/s)"),
+                game_assets.text_font(20.0),
+                ScalableText::from_vw(0.95),
+                TextColor(Color::srgb(0.7, 0.9, 0.7)), // Light green for income
+                Node::default(),
+                IncomeText,
+            ));
         });
-        
-        // Income display
+
+        // "- 2x +"
+        parent.spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Vw(0.4),
+            ..default()
+        })
+        .with_children(|speed_row| {
+            spawn_speed_button(speed_row, &game_assets, SpeedButton::Slower, "-");
+            speed_row.spawn((
+                Text::new("1x"),
+                game_assets.text_font(16.0),
+                ScalableText::from_vw(0.8),
+                TextColor(Color::WHITE),
+                Node::default(),
+                SpeedText,
+            ));
+            spawn_speed_button(speed_row, &game_assets, SpeedButton::Faster, "+");
+        });
+
+        // "Loan: -$20/s ($1,800 left)", "Creditors call in 12s"
         parent.spawn((
-            Text::new("Income: $0/s"),
-            game_assets.text_font(20.0),
-            ScalableText::from_vw(0.95),
-            TextColor(Color::srgb(0.7, 0.9, 0.7)), // Light green for income
+            Text::new(""),
+            game_assets.text_font(16.0),
+            ScalableText::from_vw(0.8),
+            TextColor(Color::srgb(0.9, 0.5, 0.5)),
             Node {
-                margin: UiRect::top(Val::Vw(0.3)),
+                display: Display::None,
                 ..default()
             },
-            IncomeText,
+            DebtText,
         ));
     });
 }
 
-/// Updates the money display text when player money changes
+fn spawn_speed_button(parent: &mut ChildSpawnerCommands, game_assets: &GameAssets, button: SpeedButton, label: &str) {
+    parent
+        .spawn((
+            Node {
+                padding: UiRect::axes(Val::Vw(0.4), Val::Vw(0.1)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.25, 0.25, 0.3)),
+            BlocksWorldClicks,
+            button,
+        ))
+        .with_children(|b| {
+            b.spawn((
+                Text::new(label),
+                game_assets.text_font(16.0),
+                ScalableText::from_vw(0.8),
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+pub fn handle_speed_buttons(
+    buttons: Query<(&Interaction, &SpeedButton), Changed<Interaction>>,
+    mut speed: ResMut<crate::game_speed::GameSpeed>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            SpeedButton::Slower => speed.slower(),
+            SpeedButton::Faster => speed.faster(),
+        }
+    }
+}
+
+/// Shows the speed the clock is actually at, greyed out while something is holding it
+pub fn update_speed_display(
+    speed: Res<crate::game_speed::GameSpeed>,
+    state: Res<State<crate::pause::GameState>>,
+    mut text: Single<(&mut Text, &mut TextColor), With<SpeedText>>,
+) {
+    let effective = crate::game_speed::effective_speed(&speed, state.get());
+    let (text, color) = &mut *text;
+    text.0 = format!("{}x", effective);
+    color.0 = if state.get().is_paused() { Color::srgb(0.6, 0.6, 0.6) } else { Color::WHITE };
+}
+
+fn debt_lines(debt: &crate::events::DebtState, bankruptcy_stage: u32, failing_time: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !debt.loans.is_empty() {
+        lines.push(format!(
+            "Loan: -${}/s (${} left)",
+            format_number_with_commas(debt.repayment_per_second()),
+            format_number_with_commas(debt.total_owed())
+        ));
+    }
+    match (bankruptcy_stage, debt.next_stage_in(bankruptcy_stage, failing_time)) {
+        (0, Some(seconds)) => lines.push(format!("Creditors call in {:.0}s", seconds.ceil())),
+        (1, Some(seconds)) => lines.push(format!("Final notice in {:.0}s", seconds.ceil())),
+        _ => {}
+    }
+    lines
+}
+
+/// Shows what the loans are taking and how long until the creditors act, hidden otherwise
+pub fn update_debt_display(
+    player: Res<Player>,
+    debt: Res<crate::events::DebtState>,
+    difficulty: Res<crate::difficulty::Difficulty>,
+    mut debt_text: Single<(&mut Text, &mut Node), With<DebtText>>,
+) {
+    let lines = debt_lines(&debt, player.bankruptcy_stage, difficulty.multipliers().failing_time);
+    let (text, node) = &mut *debt_text;
+    if lines.is_empty() {
+        node.display = Display::None;
+        return;
+    }
+    let joined = lines.join("\n");
+    if text.0 != joined {
+        text.0 = joined;
+    }
+    node.display = Display::Flex;
+}
+
+/// Updates the income rate and starts the count towards the new balance when player money changes.
+/// `net_income` is refreshed on the fulfillment tick so the rate follows the same cadence.
 pub fn update_money_display(
     player: Res<Player>,
-    mut money_text_query: Query<&mut Text, With<MoneyText>>,
+    mut displayed: ResMut<DisplayedMoney>,
     mut income_query: Query<(&mut Text, &mut TextColor), (With<IncomeText>, Without<MoneyText>)>,
 ) {
-    // Update money display
-    for mut text in money_text_query.iter_mut() {
-        let formatted_money = format!("${}", format_number_with_commas(player.money));
-        **text = formatted_money;
+    if displayed.target != player.money {
+        displayed.from = displayed.shown;
+        displayed.target = player.money;
+        displayed.elapsed = 0.0;
     }
     
     // Update income display with dynamic color
@@ -94,7 +261,7 @@ pub fn update_money_display(
             "" 
         };
         
-        let formatted_income = format!("{}${}/s", 
+        let formatted_income = format!("({}${}/s)", 
             income_prefix, 
             format_number_with_commas(player.net_income));
         **text = formatted_income;
@@ -110,8 +277,76 @@ pub fn update_money_display(
     }
 }
 
+/// Counts the shown balance towards the real one, eased so big jumps don't look jittery
+pub fn animate_money_display(
+    time: Res<Time<Real>>,
+    mut displayed: ResMut<DisplayedMoney>,
+    mut money_text_query: Query<&mut Text, With<MoneyText>>,
+) {
+    if displayed.shown == displayed.target as f32 {
+        return;
+    }
+    displayed.elapsed += time.delta_secs();
+    let t = (displayed.elapsed / MONEY_COUNT_SECONDS).clamp(0.0, 1.0);
+    let eased = 1.0 - (1.0 - t).powi(3);
+    displayed.shown = if t >= 1.0 {
+        displayed.target as f32
+    } else {
+        displayed.from + (displayed.target as f32 - displayed.from) * eased
+    };
+    for mut text in money_text_query.iter_mut() {
+        **text = format!("${}", format_number_with_commas(displayed.shown.round() as i32));
+    }
+}
+
+/// Purchases, event fines and the like get a floating "-$x" by the money display
+pub fn spawn_money_drop_popups(
+    mut commands: Commands,
+    player: Res<Player>,
+    mut displayed: ResMut<DisplayedMoney>,
+    game_assets: Res<GameAssets>,
+) {
+    let Some(last) = displayed.last_seen.replace(player.money) else { return };
+    let drop = last - player.money;
+    if drop < MONEY_DROP_POPUP_MIN {
+        return;
+    }
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(80.0),
+            left: Val::Vw(10.0),
+            ..default()
+        },
+        Text::new(format!("-${}", format_number_with_commas(drop))),
+        game_assets.text_font(24.0),
+        ScalableText::from_vw(1.1),
+        TextColor(Color::srgb(1.0, 0.35, 0.35)),
+        ZIndex(101),
+        Pickable::IGNORE,
+        MoneyDeltaPopup { age: 0.0 },
+    ));
+}
+
+pub fn update_money_drop_popups(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut popups: Query<(Entity, &mut MoneyDeltaPopup, &mut Node, &mut TextColor)>,
+) {
+    for (entity, mut popup, mut node, mut color) in popups.iter_mut() {
+        popup.age += time.delta_secs();
+        if popup.age >= MONEY_POPUP_SECONDS {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = popup.age / MONEY_POPUP_SECONDS;
+        node.top = Val::Px(80.0 - 30.0 * t);
+        color.0.set_alpha(1.0 - t);
+    }
+}
+
 /// Helper function to format numbers with commas (e.g., 1000 -> "1,000")
-fn format_number_with_commas(mut num: i32) -> String {
+pub fn format_number_with_commas(mut num: i32) -> String {
     if num == 0 {
         return "0".to_string();
     }
@@ -136,4 +371,53 @@ fn format_number_with_commas(mut num: i32) -> String {
     }
     
     result.chars().rev().collect()
-}
\ No newline at end of file
+}
+pub fn spawn_sink_income_popups(
+    mut commands: Commands,
+    mut payouts: MessageReader<crate::player::SinkPayout>,
+    sinks: Query<&crate::grid::GridPosition>,
+    grid: Res<crate::grid::Grid>,
+    projection: Single<&Projection, With<Camera>>,
+    game_assets: Res<GameAssets>,
+) {
+    let scale = if let Projection::Orthographic(orthographic) = *projection { orthographic.scale } else { 1.0 };
+    if scale > SINK_INCOME_POPUP_MAX_SCALE {
+        payouts.clear();
+        return;
+    }
+    for payout in payouts.read() {
+        let Ok(position) = sinks.get(payout.sink) else { continue };
+        let color = match payout.status {
+            crate::contracts::ContractFulfillmentStatus::Exceeding => Color::srgb(0.35, 0.65, 1.0),
+            _ => Color::srgb(0.3, 0.9, 0.3),
+        };
+        let world = grid.grid_to_world_center(position);
+        commands.spawn((
+            Text2d::new(format!("+${:.0}", payout.amount)),
+            game_assets.text_font(28.0),
+            TextColor(color),
+            Transform::from_translation(world.extend(crate::factory::building_visuals::z_layers::BADGE + 2.0) + Vec3::Y * grid.scale),
+            SinkIncomePopup {
+                age: 0.0,
+                velocity: SINK_INCOME_POPUP_SPEED,
+            },
+        ));
+    }
+}
+
+/// Rises, fades, and goes
+pub fn update_sink_income_popups(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut popups: Query<(Entity, &mut SinkIncomePopup, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut popup, mut transform, mut color) in popups.iter_mut() {
+        popup.age += time.delta_secs();
+        if popup.age >= SINK_INCOME_POPUP_SECONDS {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += popup.velocity * time.delta_secs();
+        color.0.set_alpha(1.0 - popup.age / SINK_INCOME_POPUP_SECONDS);
+    }
+}
@@ -1,8 +1,14 @@
 use bevy::prelude::*;
-use crate::events::newsfeed_events::{AddNewsfeedItemEvent, get_news_headline};
+use crate::events::newsfeed_events::{AddNewsfeedItemEvent, NewsPayload, get_news_headline};
 use crate::events::NewsLibrary;
 use crate::factions::{Faction, FactionReputations};
 use crate::assets::GameAssets;
+use crate::ui::interactive_event::ScalableText;
+use crate::camera::focus_camera_on_grid_pos;
+use crate::events::TriggerInteractiveEvent;
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::grid::{Grid, GridPosition};
+use crate::ui::BlocksWorldClicks;
 use rand::prelude::IndexedRandom;
 
 /// Component to mark the root entity of the newsfeed UI.
@@ -13,6 +19,12 @@ pub struct NewsfeedRoot;
 #[derive(Component)]
 pub struct NewsfeedItem;
 
+/// On newsfeed items that lead somewhere when clicked
+#[derive(Component)]
+pub struct NewsfeedLink(pub NewsPayload);
+
+const NEWS_LINK_HOVER_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.12);
+
 /// Resource to track recently used news event IDs to avoid repetition.
 #[derive(Resource, Default)]
 pub struct RecentNewsIds {
@@ -114,6 +126,14 @@ pub fn add_newsfeed_item_system(
                 NewsfeedItem,
             ))
             .id();
+        if let Some(payload) = &event.payload {
+            commands.entity(news_item).insert((
+                NewsfeedLink(payload.clone()),
+                Interaction::None,
+                BlocksWorldClicks,
+                BackgroundColor(Color::NONE),
+            ));
+        }
 
         // Add faction icon with fixed size and maintain aspect ratio
         let icon_index = game_assets.faction_icon(event.faction, crate::assets::IconSize::Small).map(|(_, idx)| idx).unwrap_or(0);
@@ -137,7 +157,8 @@ pub fn add_newsfeed_item_system(
         let text = commands
             .spawn((
                 Text::new(&event.headline),
-                game_assets.text_font(24.0), 
+                game_assets.text_font(24.0),
+                ScalableText::from_vw(1.25),
                 TextColor(faction_color),
                 Node {
                     ..default()
@@ -150,6 +171,7 @@ pub fn add_newsfeed_item_system(
             .spawn((
                 Text::new(" | "),
                 game_assets.text_font(24.0),
+                ScalableText::from_vw(1.25),
                 TextColor(Color::srgb(0.5, 0.5, 0.5)),
                 Node {
                     ..default()
@@ -167,8 +189,14 @@ pub fn add_newsfeed_item_system(
 pub fn scroll_newsfeed_items(
     mut commands: Commands,
     mut item_query: Query<(Entity, &mut Node), With<NewsfeedItem>>,
-    time: Res<Time>,
+    links: Query<&Interaction, With<NewsfeedLink>>,
+    time: Res<Time<Real>>,
 ) {
+    // hold the whole ticker while a link is under the mouse, stopping just the one would
+    // let the next item slide over it
+    if links.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
     let scroll_speed = 50.0;
     let delta = scroll_speed * time.delta_secs();
 
@@ -206,13 +234,59 @@ pub fn generate_news(
         let rep = reputations.get(faction).clamp(0, 100) as u32;
 
         // get_news_headline handles the loading check internally
-        if let Some((id, headline)) = get_news_headline(faction, rep, &news_library, &mut recent_ids.ids) {
+        if let Some(item) = get_news_headline(faction, rep, &news_library, &mut recent_ids.ids) {
+            let (id, headline, event_id) = (item.id, item.text.clone(), item.event_id.clone());
             recent_ids.add(id);
             
             events.write(AddNewsfeedItemEvent {
                 faction,
                 headline,
+                payload: event_id.map(NewsPayload::Event),
             });
         }
     }
-}
\ No newline at end of file
+}
+/// Hover highlight on the items that do something, decorative ones are left alone
+pub fn update_newsfeed_link_hover(
+    mut links: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<NewsfeedLink>)>,
+) {
+    for (interaction, mut background) in links.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => Color::NONE,
+            _ => NEWS_LINK_HOVER_COLOR,
+        };
+    }
+}
+
+/// Positions and factions move the camera there, events get triggered
+pub fn handle_newsfeed_clicks(
+    links: Query<(&Interaction, &NewsfeedLink), Changed<Interaction>>,
+    sinks: Query<(&GridPosition, &Faction), With<SinkBuilding>>,
+    camera: Single<(&mut Transform, &mut Projection), With<Camera>>,
+    grid: Res<Grid>,
+    mut trigger: MessageWriter<TriggerInteractiveEvent>,
+) {
+    let Some((_, link)) = links.iter().find(|(i, _)| **i == Interaction::Pressed) else { return };
+    let target = match &link.0 {
+        NewsPayload::Event(event_id) => {
+            trigger.write(TriggerInteractiveEvent { event_id: event_id.clone() });
+            return;
+        }
+        NewsPayload::Position(position) => *position,
+        NewsPayload::Faction(faction) => {
+            // their sink closest to the start area stands in for the faction
+            let Some((position, _)) = sinks
+                .iter()
+                .filter(|(_, f)| *f == faction)
+                .min_by_key(|(p, _)| p.0.length_squared())
+            else {
+                return;
+            };
+            *position
+        }
+    };
+    let (mut transform, mut projection) = camera.into_inner();
+    if let Projection::Orthographic(orthographic) = projection.as_mut() {
+        focus_camera_on_grid_pos(&target, &grid, &mut transform, orthographic);
+    }
+}
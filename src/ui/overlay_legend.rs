@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+
+use crate::assets::GameAssets;
+use crate::factory::throughput_overlay::{ThroughputOverlay, BUSY_UTILIZATION, COMFORTABLE_UTILIZATION};
+use crate::palette::Palette;
+use crate::ui::interactive_event::ScalableText;
+
+/// Key for the throughput overlay, above the minimap while the overlay's on
+#[derive(Component)]
+pub struct OverlayLegend;
+
+/// One swatch, coloured from whichever palette is current
+#[derive(Component, Clone, Copy)]
+pub enum LegendSwatch {
+    Inactive,
+    Comfortable,
+    Busy,
+    Full,
+}
+
+impl LegendSwatch {
+    const ALL: [LegendSwatch; 4] = [LegendSwatch::Comfortable, LegendSwatch::Busy, LegendSwatch::Full, LegendSwatch::Inactive];
+
+    fn color(self, palette: &Palette) -> Color {
+        match self {
+            LegendSwatch::Inactive => palette.inactive,
+            LegendSwatch::Comfortable => palette.success,
+            LegendSwatch::Busy => palette.warning,
+            LegendSwatch::Full => palette.danger,
+        }
+    }
+
+    fn label(self) -> String {
+        let percent = |fraction: f32| (fraction * 100.0).round() as i32;
+        match self {
+            LegendSwatch::Inactive => "Not connected".to_string(),
+            LegendSwatch::Comfortable => format!("Under {}%", percent(COMFORTABLE_UTILIZATION)),
+            LegendSwatch::Busy => format!("{}-{}%", percent(COMFORTABLE_UTILIZATION), percent(BUSY_UTILIZATION)),
+            LegendSwatch::Full => format!("Over {}%", percent(BUSY_UTILIZATION)),
+        }
+    }
+}
+
+pub fn spawn_overlay_legend(mut commands: Commands, game_assets: Res<GameAssets>, palette: Res<Palette>) {
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                bottom: Val::Vh(crate::ui::minimap::MINIMAP_SIZE_VH + 1.5),
+                right: Val::Vh(0.5),
+                padding: UiRect::all(Val::Vw(0.5)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.25),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            Pickable::IGNORE,
+            OverlayLegend,
+        ))
+        .with_children(|legend| {
+            legend.spawn((
+                Text::new("Wire load (T)"),
+                game_assets.text_font(13.0),
+                ScalableText::from_vw(0.85),
+                TextColor(Color::WHITE),
+            ));
+            for swatch in LegendSwatch::ALL {
+                legend
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Vw(0.4),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            Node {
+                                width: Val::Vw(0.8),
+                                height: Val::Vw(0.8),
+                                ..default()
+                            },
+                            BackgroundColor(swatch.color(&palette)),
+                            swatch,
+                        ));
+                        row.spawn((
+                            Text::new(swatch.label()),
+                            game_assets.text_font(12.0),
+                            ScalableText::from_vw(0.8),
+                            TextColor(Color::srgb(0.85, 0.85, 0.85)),
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn update_overlay_legend(
+    overlay: Res<ThroughputOverlay>,
+    palette: Res<Palette>,
+    mut legend: Single<&mut Node, With<OverlayLegend>>,
+    mut swatches: Query<(&LegendSwatch, &mut BackgroundColor)>,
+) {
+    legend.display = if overlay.0 { Display::Flex } else { Display::None };
+    for (swatch, mut background) in swatches.iter_mut() {
+        background.set_if_neq(BackgroundColor(swatch.color(&palette)));
+    }
+}
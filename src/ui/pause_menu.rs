@@ -0,0 +1,247 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::pause::GameState;
+use crate::ui::contracts::ContractCancelPopup;
+use crate::ui::interactive_event::{InteractiveEventModal, QueuedEvents, ScalableText, StoredEventData};
+use crate::ui::shop::{SelectedBuilding, SelectedBuildingType};
+use crate::ui::sink_panel::SinkContractsPanel;
+use crate::ui::BlocksWorldClicks;
+
+/// Root of the Escape menu
+#[derive(Component)]
+pub struct PauseMenu;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuButton {
+    Resume,
+    Settings,
+    Profile,
+    PhotoMode,
+    Quit,
+}
+
+fn spawn_pause_menu(commands: &mut Commands, game_assets: &GameAssets, seed: crate::world_gen::WorldSeed) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            GlobalZIndex(900),
+            PauseMenu,
+            BlocksWorldClicks,
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Stretch,
+                    row_gap: Val::Vh(1.5),
+                    padding: UiRect::all(Val::Vw(1.5)),
+                    min_width: Val::Vw(18.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.12, 0.12, 0.15)),
+            ))
+            .with_children(|menu| {
+                menu.spawn((
+                    Text::new("Paused"),
+                    game_assets.text_font(28.0),
+                    ScalableText::from_vw(2.0),
+                    TextColor(Color::WHITE),
+                    Node {
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                ));
+                // for sharing a map or reporting a weird one
+                menu.spawn((
+                    Text::new(format!("Seed {}", seed.0)),
+                    game_assets.text_font(14.0),
+                    ScalableText::from_vw(0.9),
+                    TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                    Node {
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                ));
+                for (label, button) in [
+                    ("Resume", PauseMenuButton::Resume),
+                    ("Settings", PauseMenuButton::Settings),
+                    ("Profile", PauseMenuButton::Profile),
+                    ("Photo mode", PauseMenuButton::PhotoMode),
+                    ("Quit", PauseMenuButton::Quit),
+                ] {
+                    menu.spawn((
+                        Node {
+                            padding: UiRect::all(Val::Vw(0.6)),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                        Interaction::None,
+                        button,
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            Text::new(label),
+                            game_assets.text_font(18.0),
+                            ScalableText::from_vw(1.3),
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+                }
+            });
+        });
+}
+
+/// The one place Escape is read, first match wins:
+/// photo mode, focused widget, profile screen, cancel-contract popup, sink contracts panel, event modal (back to a bubble), placement ghost, pause menu
+pub fn handle_escape(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cancel_popups: Query<Entity, With<ContractCancelPopup>>,
+    sink_panels: Query<Entity, With<SinkContractsPanel>>,
+    modals: Query<(Entity, &StoredEventData), With<InteractiveEventModal>>,
+    mut queued_events: ResMut<QueuedEvents>,
+    ghosts: Query<Entity, With<SelectedBuilding>>,
+    mut selected_building_type: ResMut<SelectedBuildingType>,
+    menus: Query<Entity, With<PauseMenu>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    game_assets: Res<GameAssets>,
+    seed: Res<crate::world_gen::WorldSeed>,
+    profile_screens: Query<Entity, With<crate::ui::profile::ProfileScreen>>,
+    (mut photo_mode, photo_overlays): (ResMut<crate::photo_mode::PhotoMode>, Query<Entity, With<crate::photo_mode::PhotoModeOverlay>>),
+    mut widget_focus: ResMut<crate::ui::widgets::WidgetFocus>,
+) {
+    if !keyboard.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    if photo_mode.active {
+        crate::photo_mode::exit_photo_mode(&mut commands, &mut photo_mode, &photo_overlays);
+        return;
+    }
+
+    // drop keyboard focus first, the next press closes whatever the widget sits in
+    if widget_focus.0.is_some() {
+        widget_focus.0 = None;
+        return;
+    }
+
+    if !profile_screens.is_empty() {
+        for screen in profile_screens.iter() {
+            commands.entity(screen).despawn();
+        }
+        return;
+    }
+
+    if !cancel_popups.is_empty() {
+        for popup in cancel_popups.iter() {
+            commands.entity(popup).despawn();
+        }
+        return;
+    }
+
+    if !sink_panels.is_empty() {
+        for panel in sink_panels.iter() {
+            commands.entity(panel).despawn();
+        }
+        return;
+    }
+
+    if let Some((modal, stored)) = modals.iter().next() {
+        // put off rather than dismissed, it waits as a bubble like a non-urgent event
+        let mut event = stored.event_data.clone();
+        event.popup_urgency = false;
+        if !queued_events.events.iter().any(|e| e.event_id == event.event_id) {
+            info!("Event {} put off to the queue", event.event_id);
+            queued_events.events.push(event);
+        }
+        commands.entity(modal).despawn();
+        next_state.set(GameState::Running);
+        return;
+    }
+
+    if !ghosts.is_empty() {
+        for ghost in ghosts.iter() {
+            commands.entity(ghost).despawn();
+        }
+        selected_building_type.0 = None;
+        return;
+    }
+
+    if !menus.is_empty() {
+        for menu in menus.iter() {
+            commands.entity(menu).despawn();
+        }
+        if *state.get() == GameState::ManualPause {
+            next_state.set(GameState::Running);
+        }
+        return;
+    }
+    match state.get() {
+        GameState::Running | GameState::ManualPause => {
+            next_state.set(GameState::ManualPause);
+            spawn_pause_menu(&mut commands, &game_assets, *seed);
+            info!("Pause menu opened");
+        }
+        GameState::EventModal | GameState::Setup => {}
+    }
+}
+
+pub fn handle_pause_menu_buttons(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+    menus: Query<Entity, With<PauseMenu>>,
+    mut settings_panel: Query<&mut Node, With<crate::ui::settings::SettingsPanel>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: MessageWriter<AppExit>,
+    (game_assets, profile): (Res<GameAssets>, Res<crate::profile::Profile>),
+    mut photo_mode: ResMut<crate::photo_mode::PhotoMode>,
+) {
+    let Some((_, button)) = buttons.iter().find(|(i, _)| **i == Interaction::Pressed) else { return };
+    for menu in menus.iter() {
+        commands.entity(menu).despawn();
+    }
+    match button {
+        PauseMenuButton::Resume => next_state.set(GameState::Running),
+        // stays paused while they fiddle with settings
+        PauseMenuButton::Settings => {
+            for mut node in settings_panel.iter_mut() {
+                node.display = Display::Flex;
+            }
+        }
+        PauseMenuButton::Profile => {
+            crate::ui::profile::spawn_profile_screen(&mut commands, &game_assets, &profile);
+        }
+        // stays paused, the world holds still for the picture
+        PauseMenuButton::PhotoMode => {
+            crate::photo_mode::enter_photo_mode(&mut commands, &mut photo_mode, &game_assets);
+        }
+        PauseMenuButton::Quit => {
+            info!("Quit from the pause menu");
+            exit.write(AppExit::Success);
+        }
+    }
+}
+
+/// Space unpausing (or an event modal taking over) shouldn't leave the menu up
+pub fn close_pause_menu_when_unpaused(
+    mut commands: Commands,
+    state: Res<State<GameState>>,
+    menus: Query<Entity, With<PauseMenu>>,
+) {
+    if *state.get() == GameState::ManualPause {
+        return;
+    }
+    for menu in menus.iter() {
+        commands.entity(menu).despawn();
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::factions::Faction;
+use crate::factory::logical::BasicDataType;
+use crate::profile::Profile;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::money::format_number_with_commas;
+use crate::ui::{BlocksWorldClicks, BlocksWorldScroll};
+
+/// Lifetime stats screen, opened from the pause menu
+#[derive(Component)]
+pub struct ProfileScreen;
+
+#[derive(Component)]
+pub struct ProfileCloseButton;
+
+fn format_duration(seconds: f32) -> String {
+    let total = seconds.round() as u32;
+    format!("{}m {:02}s", total / 60, total % 60)
+}
+
+fn profile_lines(profile: &Profile) -> Vec<String> {
+    let mut lines = vec![format!("Contracts completed: {}", profile.total_contracts())];
+    for faction in [Faction::Corporate, Faction::Academia, Faction::Government, Faction::Criminal] {
+        let count = profile.contracts_completed.get(&faction).copied().unwrap_or(0);
+        lines.push(format!("    {:?}: {}", faction, count));
+    }
+    lines.push(format!("Highest money: ${}", format_number_with_commas(profile.highest_money)));
+    lines.push("Data shipped:".to_string());
+    for data_type in [BasicDataType::Biometric, BasicDataType::Economic, BasicDataType::Behavioural, BasicDataType::Telemetry] {
+        let amount = profile.data_shipped.get(&data_type).copied().unwrap_or(0.0);
+        lines.push(format!("    {:?}: {}", data_type, format_number_with_commas(amount.min(i32::MAX as f64) as i32)));
+    }
+    lines.push(format!("Bankruptcies: {}", profile.bankruptcies));
+    lines.push(format!(
+        "Fastest to Trusted: {}",
+        profile.fastest_trusted_seconds.map_or("not yet".to_string(), format_duration)
+    ));
+    lines
+}
+
+/// A snapshot of the profile as it is now, it doesn't update while open
+pub fn spawn_profile_screen(commands: &mut Commands, game_assets: &GameAssets, profile: &Profile) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            GlobalZIndex(900),
+            ProfileScreen,
+            BlocksWorldClicks,
+            BlocksWorldScroll,
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Vh(0.8),
+                    padding: UiRect::all(Val::Vw(1.5)),
+                    min_width: Val::Vw(22.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.12, 0.12, 0.15)),
+            ))
+            .with_children(|panel| {
+                panel.spawn((
+                    Text::new("Profile"),
+                    game_assets.text_font(28.0),
+                    ScalableText::from_vw(2.0),
+                    TextColor(Color::WHITE),
+                    Node {
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                ));
+                panel.spawn((
+                    Text::new(profile_lines(profile).join("\n")),
+                    game_assets.text_font(16.0),
+                    ScalableText::from_vw(1.0),
+                    TextColor(Color::srgb(0.85, 0.85, 0.85)),
+                ));
+                panel
+                    .spawn((
+                        Node {
+                            padding: UiRect::all(Val::Vw(0.6)),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                        Interaction::None,
+                        ProfileCloseButton,
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            Text::new("Close"),
+                            game_assets.text_font(18.0),
+                            ScalableText::from_vw(1.3),
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+            });
+        });
+}
+
+pub fn handle_profile_close(
+    mut commands: Commands,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<ProfileCloseButton>)>,
+    screens: Query<Entity, With<ProfileScreen>>,
+) {
+    if !buttons.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    for screen in screens.iter() {
+        commands.entity(screen).despawn();
+    }
+}
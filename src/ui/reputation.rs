@@ -0,0 +1,354 @@
+use bevy::prelude::*;
+use crate::assets::{GameAssets, IconSize};
+use crate::factions::{
+    reputation_level_min_score, reputation_level_name, Faction, FactionReputations, Locked, ReputationChanged,
+    ReputationLevel, ReputationSource, REPUTATION_BANDS,
+};
+use crate::grid::{Grid, GridPosition};
+use bevy::platform::collections::HashMap;
+use bevy::window::PrimaryWindow;
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::buildings::source::SourceBuilding;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::sidebar_tabs::{SidebarTab, SidebarTabPane};
+use crate::ui::BlocksWorldClicks;
+
+const FACTIONS: [Faction; 4] = [Faction::Corporate, Faction::Academia, Faction::Government, Faction::Criminal];
+const FLASH_SECONDS: f32 = 0.8;
+const BAR_WIDTH_VW: f32 = 6.0;
+const ROW_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.0);
+const POPUP_SECONDS: f32 = 2.0;
+/// Changes this soon after a popup went up are added onto it instead of getting their own
+const POPUP_COALESCE_SECONDS: f32 = 1.0;
+/// How far a popup rises over its life, logical px
+const POPUP_RISE: f32 = 60.0;
+
+#[derive(Component)]
+pub struct ReputationPanel;
+
+/// One faction's line in the panel, flashes when a `ReputationChanged` comes in for it
+#[derive(Component)]
+pub struct ReputationRow {
+    pub faction: Faction,
+    /// Seconds of flash left, and whether it went up
+    pub flash: f32,
+    pub rose: bool,
+}
+
+#[derive(Component)]
+pub struct ReputationScoreText(pub Faction);
+
+#[derive(Component)]
+pub struct ReputationBarFill(pub Faction);
+
+#[derive(Component)]
+pub struct ReputationTooltipText;
+
+/// Fraction of the way from the start of the current band to the next one, full at the top band
+pub fn progress_to_next_level(score: i32) -> f32 {
+    let score = score.clamp(0, 100) as u32;
+    let current = REPUTATION_BANDS.iter().rev().find(|(_, min)| score >= *min);
+    let next = REPUTATION_BANDS.iter().find(|(_, min)| *min > score);
+    match (current, next) {
+        (Some((_, from)), Some((_, to))) => (score - from) as f32 / (to - from) as f32,
+        _ => 1.0,
+    }
+}
+
+/// Lives in the sidebar's Factions tab
+pub fn spawn_reputation_panel(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    reputations: Res<FactionReputations>,
+    panes: Query<(Entity, &SidebarTabPane)>,
+) {
+    let Some((pane, _)) = panes.iter().find(|(_, pane)| pane.0 == SidebarTab::Factions) else { return };
+    commands
+        .spawn((
+            Node {
+                padding: UiRect::all(Val::Vw(0.6)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.3),
+                ..default()
+            },
+            ReputationPanel,
+            BlocksWorldClicks,
+            ChildOf(pane),
+        ))
+        .with_children(|panel| {
+            for faction in FACTIONS {
+                let score = reputations.get(faction);
+                panel
+                    .spawn((
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Vw(0.4),
+                            padding: UiRect::axes(Val::Vw(0.2), Val::Vw(0.1)),
+                            ..default()
+                        },
+                        BackgroundColor(ROW_COLOR),
+                        Interaction::None,
+                        ReputationRow {
+                            faction,
+                            flash: 0.0,
+                            rose: false,
+                        },
+                    ))
+                    .with_children(|row| {
+                        if let Some((atlas_id, index)) = game_assets.faction_icon(faction, IconSize::Small) {
+                            let (texture, layout) = game_assets.get_atlas(atlas_id);
+                            row.spawn((
+                                ImageNode::from_atlas_image(texture, TextureAtlas { layout, index }),
+                                Node {
+                                    width: Val::Vw(1.4),
+                                    height: Val::Vw(1.4),
+                                    ..default()
+                                },
+                            ));
+                        }
+                        row.spawn(Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Vw(0.15),
+                            ..default()
+                        })
+                        .with_children(|column| {
+                            column.spawn((
+                                Text::new(score_label(faction, score, &reputations)),
+                                game_assets.text_font(14.0),
+                                ScalableText::from_vw(0.75),
+                                TextColor(game_assets.faction_color(faction)),
+                                ReputationScoreText(faction),
+                            ));
+                            column
+                                .spawn((
+                                    Node {
+                                        width: Val::Vw(BAR_WIDTH_VW),
+                                        height: Val::Vw(0.25),
+                                        ..default()
+                                    },
+                                    BackgroundColor(Color::srgb(0.18, 0.18, 0.18)),
+                                ))
+                                .with_children(|bar| {
+                                    bar.spawn((
+                                        Node {
+                                            width: Val::Vw(BAR_WIDTH_VW * progress_to_next_level(score)),
+                                            height: Val::Percent(100.0),
+                                            ..default()
+                                        },
+                                        BackgroundColor(game_assets.faction_color(faction)),
+                                        ReputationBarFill(faction),
+                                    ));
+                                });
+                        });
+                    });
+            }
+            panel.spawn((
+                Node {
+                    display: Display::None,
+                    max_width: Val::Vw(14.0),
+                    ..default()
+                },
+                Text::new(""),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.65),
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                ReputationTooltipText,
+            ));
+        });
+}
+
+fn score_label(faction: Faction, score: i32, reputations: &FactionReputations) -> String {
+    format!("{:?} {} · {}", faction, score, reputation_level_name(reputations.get_level(faction)))
+}
+
+/// Refreshes scores and bars
+pub fn update_reputation_panel(
+    reputations: Res<FactionReputations>,
+    mut texts: Query<(&mut Text, &ReputationScoreText)>,
+    mut fills: Query<(&mut Node, &ReputationBarFill)>,
+) {
+    for (mut text, faction) in texts.iter_mut() {
+        **text = score_label(faction.0, reputations.get(faction.0), &reputations);
+    }
+    for (mut node, faction) in fills.iter_mut() {
+        node.width = Val::Vw(BAR_WIDTH_VW * progress_to_next_level(reputations.get(faction.0)));
+    }
+}
+
+/// Starts the flash on a row when its faction's score moves, setup and loads stay quiet
+pub fn flash_changed_reputation_rows(
+    mut changes: MessageReader<ReputationChanged>,
+    mut rows: Query<&mut ReputationRow>,
+) {
+    for change in changes.read() {
+        if change.source == ReputationSource::Setup {
+            continue;
+        }
+        for mut row in rows.iter_mut().filter(|row| row.faction == change.faction) {
+            row.rose = change.delta > 0;
+            row.flash = FLASH_SECONDS;
+        }
+    }
+}
+
+pub fn flash_reputation_rows(time: Res<Time<Real>>, mut rows: Query<(&mut ReputationRow, &mut BackgroundColor)>) {
+    for (mut row, mut background) in rows.iter_mut() {
+        if row.flash <= 0.0 {
+            continue;
+        }
+        row.flash = (row.flash - time.delta_secs()).max(0.0);
+        let alpha = 0.6 * row.flash / FLASH_SECONDS;
+        background.0 = if row.rose {
+            Color::srgba(0.2, 0.8, 0.2, alpha)
+        } else {
+            Color::srgba(0.9, 0.2, 0.2, alpha)
+        };
+    }
+}
+
+/// Hovering a row says what the next locked tier for that faction needs
+pub fn show_reputation_tooltip(
+    rows: Query<(&Interaction, &ReputationRow)>,
+    changed: Query<(), (Changed<Interaction>, With<ReputationRow>)>,
+    locked: Query<(&Faction, &ReputationLevel, Has<SinkBuilding>, Has<SourceBuilding>), With<Locked>>,
+    reputations: Res<FactionReputations>,
+    mut tooltip_query: Query<(&mut Text, &mut Node), With<ReputationTooltipText>>,
+) {
+    if changed.is_empty() {
+        return;
+    }
+    let Ok((mut text, mut node)) = tooltip_query.single_mut() else { return };
+    let Some((_, row)) = rows.iter().find(|(interaction, _)| **interaction == Interaction::Hovered) else {
+        node.display = Display::None;
+        return;
+    };
+
+    let current = reputations.get_level(row.faction);
+    let next_tier = locked
+        .iter()
+        .filter(|(faction, level, is_sink, is_source)| **faction == row.faction && **level > current && (*is_sink || *is_source))
+        .map(|(_, level, _, _)| *level)
+        .min();
+    **text = match next_tier {
+        Some(level) => {
+            let (sinks, sources) = locked
+                .iter()
+                .filter(|(faction, tier, _, _)| **faction == row.faction && **tier == level)
+                .fold((0, 0), |(sinks, sources), (_, _, is_sink, is_source)| {
+                    (sinks + usize::from(is_sink), sources + usize::from(is_source))
+                });
+            format!(
+                "{} ({}) with {:?} unlocks {} sink{} and {} source{}",
+                reputation_level_name(level),
+                reputation_level_min_score(level),
+                row.faction,
+                sinks,
+                if sinks == 1 { "" } else { "s" },
+                sources,
+                if sources == 1 { "" } else { "s" },
+            )
+        }
+        None => format!("Nothing left to unlock with {:?}", row.faction),
+    };
+    node.display = Display::Flex;
+}
+
+/// Floating "+3 Government" over the sink a change came from, or mid-screen for events
+#[derive(Component)]
+pub struct ReputationPopup {
+    pub faction: Faction,
+    pub delta: i32,
+    /// World point it rises from, None for the middle of the screen
+    pub anchor: Option<Vec2>,
+    pub age: f32,
+}
+
+fn reputation_popup_text(faction: Faction, delta: i32) -> String {
+    format!("{:+} {:?}", delta, faction)
+}
+
+/// Turns reputation changes into popups, summing a faction's changes into one while it's fresh
+pub fn spawn_reputation_popups(
+    mut commands: Commands,
+    mut changes: MessageReader<ReputationChanged>,
+    mut popups: Query<(&mut ReputationPopup, &mut Text)>,
+    sinks: Query<(&GridPosition, &SinkBuilding)>,
+    grid: Res<Grid>,
+    game_assets: Res<GameAssets>,
+) {
+    // one frame's worth grouped first, several contracts ticking together is one popup
+    let mut pending: HashMap<Faction, (i32, Option<Vec2>)> = HashMap::new();
+    for change in changes.read() {
+        let anchor = match change.source {
+            ReputationSource::Setup => continue,
+            ReputationSource::Event | ReputationSource::Contract(None) => None,
+            ReputationSource::Contract(Some(sink)) => sinks.get(sink).ok().map(|(position, sink)| {
+                let low = grid.grid_to_world_corner(position);
+                let high = grid.grid_to_world_corner(&GridPosition(position.0 + sink.size));
+                Vec2::new((low.x + high.x) / 2.0, low.y.max(high.y))
+            }),
+        };
+        let entry = pending.entry(change.faction).or_insert((0, anchor));
+        entry.0 += change.delta;
+    }
+
+    for (faction, (delta, anchor)) in pending {
+        if delta == 0 {
+            continue;
+        }
+        let fresh = popups
+            .iter_mut()
+            .find(|(popup, _)| popup.faction == faction && popup.age < POPUP_COALESCE_SECONDS);
+        if let Some((mut popup, mut text)) = fresh {
+            popup.delta += delta;
+            popup.age = 0.0;
+            **text = reputation_popup_text(faction, popup.delta);
+            continue;
+        }
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Text::new(reputation_popup_text(faction, delta)),
+            game_assets.text_font(22.0),
+            ScalableText::from_vw(1.3),
+            TextColor(game_assets.faction_color(faction)),
+            // hidden until the first update places it
+            Visibility::Hidden,
+            ZIndex(101),
+            Pickable::IGNORE,
+            ReputationPopup { faction, delta, anchor, age: 0.0 },
+        ));
+    }
+}
+
+pub fn update_reputation_popups(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut popups: Query<(Entity, &mut ReputationPopup, &mut Node, &mut TextColor, &mut Visibility, &ComputedNode)>,
+) {
+    let (camera, camera_transform) = *camera;
+    let center = Vec2::new(window.width(), window.height()) / 2.0;
+    for (entity, mut popup, mut node, mut color, mut visibility, computed) in popups.iter_mut() {
+        popup.age += time.delta_secs();
+        if popup.age >= POPUP_SECONDS {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // a sink that's panned off screen just falls back to the middle
+        let origin = popup
+            .anchor
+            .and_then(|anchor| camera.world_to_viewport(camera_transform, anchor.extend(0.0)).ok())
+            .unwrap_or(center);
+        let t = popup.age / POPUP_SECONDS;
+        let size = computed.size() * computed.inverse_scale_factor();
+        node.left = Val::Px(origin.x - size.x / 2.0);
+        node.top = Val::Px(origin.y - size.y - POPUP_RISE * t);
+        color.0.set_alpha(1.0 - t * t);
+        *visibility = Visibility::Inherited;
+    }
+}
@@ -0,0 +1,482 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::controls::{Binding, InputAction, InputBindings};
+use crate::settings::{Settings, AUTOSAVE_INTERVALS, MAX_UI_TEXT_SCALE, MIN_UI_TEXT_SCALE, SCREEN_SHAKE_LEVELS, UI_TEXT_SCALE_STEP};
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::widgets::{spawn_cycler, spawn_slider, spawn_toggle, WidgetChanged, WidgetValue};
+use crate::ui::BlocksWorldClicks;
+
+/// Button that opens/closes the settings panel
+#[derive(Component)]
+pub struct SettingsToggleButton;
+
+/// Root of the settings panel
+#[derive(Component)]
+pub struct SettingsPanel;
+
+/// Opens the key bindings sub-panel
+#[derive(Component)]
+pub struct ControlsToggleButton;
+
+/// Key bindings sub-panel, next to the settings panel
+#[derive(Component)]
+pub struct ControlsPanel;
+
+/// Click to rebind this action, the next key or button pressed takes it
+#[derive(Component)]
+pub struct RebindButton(pub InputAction);
+
+#[derive(Component)]
+pub struct RebindLabel(pub InputAction);
+
+#[derive(Component)]
+pub struct RebindMessage;
+
+#[derive(Component)]
+pub struct ResetBindingsButton;
+
+/// The action waiting for a key, and why the last attempt didn't take
+#[derive(Resource, Default)]
+pub struct RebindState {
+    pub listening: Option<InputAction>,
+    message: Option<String>,
+}
+
+/// Which setting a widget in the panel controls
+#[derive(Component, Debug, Clone, Copy)]
+pub enum SettingBinding {
+    SoundEnabled,
+    MasterVolume,
+    MusicEnabled,
+    MusicVolume,
+    SfxVolume,
+    TextScale,
+    ScreenShake,
+    ReduceMotion,
+    AutosaveInterval,
+    EdgeScroll,
+    Emissaries,
+    BlockFacingPorts,
+    ColorblindPalette,
+    /// Sandbox only
+    ContractGeneration,
+}
+
+fn percent(value: f32) -> String {
+    format!("{:.0}%", value * 100.0)
+}
+
+fn multiplier(value: f32) -> String {
+    format!("{:.1}x", value)
+}
+
+pub fn spawn_settings_ui(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    settings: Res<Settings>,
+    game_mode: Res<crate::game_mode::GameMode>,
+) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(20.0),
+                left: Val::Px(20.0),
+                flex_direction: FlexDirection::ColumnReverse,
+                row_gap: Val::Vw(0.4),
+                ..default()
+            },
+            ZIndex(100),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Vw(0.45)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                    SettingsToggleButton,
+                    BlocksWorldClicks,
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new("Settings"),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.9),
+                        TextColor(Color::WHITE),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Node {
+                        display: Display::None,
+                        padding: UiRect::all(Val::Vw(0.6)),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Vw(0.4),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                    SettingsPanel,
+                    BlocksWorldClicks,
+                ))
+                .with_children(|panel| {
+                    let sound = spawn_toggle(panel, &game_assets, "Sound", !settings.audio.muted);
+                    let master_volume = spawn_slider(panel, &game_assets, "Master volume", settings.audio.master_volume, 0.0, 1.0, Some(0.05), percent);
+                    let music = spawn_toggle(panel, &game_assets, "Music", !settings.audio.music_muted);
+                    let music_volume = spawn_slider(panel, &game_assets, "Music volume", settings.audio.music_volume, 0.0, 1.0, Some(0.05), percent);
+                    let sfx_volume = spawn_slider(panel, &game_assets, "SFX volume", settings.audio.sfx_volume, 0.0, 1.0, Some(0.05), percent);
+                    let text_scale = spawn_slider(
+                        panel,
+                        &game_assets,
+                        "Text size",
+                        settings.ui_text_scale,
+                        MIN_UI_TEXT_SCALE,
+                        MAX_UI_TEXT_SCALE,
+                        Some(UI_TEXT_SCALE_STEP),
+                        multiplier,
+                    );
+                    let screen_shake = spawn_cycler(
+                        panel,
+                        &game_assets,
+                        "Screen shake",
+                        SCREEN_SHAKE_LEVELS.iter().map(|s| s.to_string()).collect(),
+                        settings.screen_shake,
+                    );
+                    let reduce_motion = spawn_toggle(panel, &game_assets, "Reduce motion", settings.reduce_motion);
+                    let autosave = spawn_cycler(
+                        panel,
+                        &game_assets,
+                        "Autosave",
+                        AUTOSAVE_INTERVALS
+                            .iter()
+                            .map(|m| if *m == 0 { "Off".to_string() } else { format!("{} min", m) })
+                            .collect(),
+                        settings.autosave_interval,
+                    );
+                    let edge_scroll = spawn_toggle(panel, &game_assets, "Edge scroll", settings.edge_scroll);
+                    let emissaries = spawn_toggle(panel, &game_assets, "Emissaries", settings.emissaries);
+                    let block_ports = spawn_toggle(panel, &game_assets, "Block dead ports", settings.block_facing_ports);
+                    let colorblind = spawn_toggle(panel, &game_assets, "Colorblind palette", settings.colorblind_palette);
+
+                    let mut commands = panel.commands();
+                    commands.entity(sound).insert(SettingBinding::SoundEnabled);
+                    commands.entity(master_volume).insert(SettingBinding::MasterVolume);
+                    commands.entity(music).insert(SettingBinding::MusicEnabled);
+                    commands.entity(music_volume).insert(SettingBinding::MusicVolume);
+                    commands.entity(sfx_volume).insert(SettingBinding::SfxVolume);
+                    commands.entity(text_scale).insert(SettingBinding::TextScale);
+                    commands.entity(screen_shake).insert(SettingBinding::ScreenShake);
+                    commands.entity(reduce_motion).insert(SettingBinding::ReduceMotion);
+                    commands.entity(autosave).insert(SettingBinding::AutosaveInterval);
+                    commands.entity(edge_scroll).insert(SettingBinding::EdgeScroll);
+                    commands.entity(emissaries).insert(SettingBinding::Emissaries);
+                    commands.entity(block_ports).insert(SettingBinding::BlockFacingPorts);
+                    commands.entity(colorblind).insert(SettingBinding::ColorblindPalette);
+
+                    if game_mode.sandbox {
+                        let contracts = spawn_toggle(panel, &game_assets, "Contracts", game_mode.contract_generation);
+                        panel.commands().entity(contracts).insert(SettingBinding::ContractGeneration);
+                    }
+
+                    panel
+                        .spawn((
+                            Node {
+                                padding: UiRect::all(Val::Vw(0.3)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                            Interaction::None,
+                            crate::ui::changelog::OpenChangelogButton,
+                        ))
+                        .with_children(|button| {
+                            button.spawn((
+                                Text::new("What's new"),
+                                game_assets.text_font(14.0),
+                                ScalableText::from_vw(0.9),
+                                TextColor(Color::WHITE),
+                            ));
+                        });
+
+                    panel
+                        .spawn((
+                            Node {
+                                padding: UiRect::all(Val::Vw(0.3)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                            Interaction::None,
+                            ControlsToggleButton,
+                        ))
+                        .with_children(|button| {
+                            button.spawn((
+                                Text::new("Controls"),
+                                game_assets.text_font(14.0),
+                                ScalableText::from_vw(0.9),
+                                TextColor(Color::WHITE),
+                            ));
+                        });
+                });
+        });
+}
+
+pub fn handle_settings_buttons(
+    toggle_query: Query<&Interaction, (Changed<Interaction>, With<SettingsToggleButton>)>,
+    mut panel_query: Query<&mut Node, (With<SettingsPanel>, Without<ControlsPanel>)>,
+    mut controls_panel: Query<&mut Node, (With<ControlsPanel>, Without<SettingsPanel>)>,
+    mut rebind: ResMut<RebindState>,
+) {
+    for interaction in toggle_query.iter() {
+        if *interaction == Interaction::Pressed {
+            for mut node in panel_query.iter_mut() {
+                node.display = match node.display {
+                    Display::None => Display::Flex,
+                    _ => Display::None,
+                };
+                // the controls go away with the panel they hang off
+                if node.display == Display::None {
+                    for mut controls in controls_panel.iter_mut() {
+                        controls.display = Display::None;
+                    }
+                    rebind.listening = None;
+                }
+            }
+        }
+    }
+}
+
+/// Writes widget changes from the settings panel back into the resources they're bound to
+pub fn apply_setting_widgets(
+    mut changes: MessageReader<WidgetChanged>,
+    bindings: Query<&SettingBinding>,
+    mut settings: ResMut<Settings>,
+    mut game_mode: ResMut<crate::game_mode::GameMode>,
+) {
+    for change in changes.read() {
+        let Ok(binding) = bindings.get(change.entity) else { continue };
+        match (*binding, change.value) {
+            (SettingBinding::SoundEnabled, WidgetValue::Bool(on)) => settings.audio.muted = !on,
+            (SettingBinding::MasterVolume, WidgetValue::Float(v)) => settings.audio.master_volume = v,
+            (SettingBinding::MusicEnabled, WidgetValue::Bool(on)) => settings.audio.music_muted = !on,
+            (SettingBinding::MusicVolume, WidgetValue::Float(v)) => settings.audio.music_volume = v,
+            (SettingBinding::SfxVolume, WidgetValue::Float(v)) => settings.audio.sfx_volume = v,
+            (SettingBinding::TextScale, WidgetValue::Float(v)) => {
+                settings.set_ui_text_scale(v);
+                info!("UI text scale set to {:.1}x", settings.ui_text_scale);
+            }
+            (SettingBinding::ScreenShake, WidgetValue::Index(i)) => settings.screen_shake = i,
+            (SettingBinding::ReduceMotion, WidgetValue::Bool(on)) => settings.reduce_motion = on,
+            (SettingBinding::AutosaveInterval, WidgetValue::Index(i)) => settings.autosave_interval = i,
+            (SettingBinding::EdgeScroll, WidgetValue::Bool(on)) => settings.edge_scroll = on,
+            (SettingBinding::Emissaries, WidgetValue::Bool(on)) => settings.emissaries = on,
+            (SettingBinding::BlockFacingPorts, WidgetValue::Bool(on)) => settings.block_facing_ports = on,
+            (SettingBinding::ColorblindPalette, WidgetValue::Bool(on)) => settings.colorblind_palette = on,
+            (SettingBinding::ContractGeneration, WidgetValue::Bool(on)) => {
+                game_mode.contract_generation = on;
+                info!("Sandbox contract generation: {}", on);
+            }
+            (binding, value) => warn!("Setting {:?} got unexpected widget value {:?}", binding, value),
+        }
+    }
+}
+
+pub fn spawn_controls_ui(mut commands: Commands, game_assets: Res<GameAssets>, bindings: Res<InputBindings>) {
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(20.0),
+                left: Val::Vw(18.0),
+                padding: UiRect::all(Val::Vw(0.6)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.3),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            ZIndex(100),
+            ControlsPanel,
+            BlocksWorldClicks,
+            Interaction::None,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new("Controls"),
+                game_assets.text_font(16.0),
+                ScalableText::from_vw(1.1),
+                TextColor(Color::WHITE),
+            ));
+            for action in InputAction::ALL {
+                panel
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Vw(0.5),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn(Node { width: Val::Vw(9.0), ..default() }).with_children(|n| {
+                            n.spawn((
+                                Text::new(action.label()),
+                                game_assets.text_font(14.0),
+                                ScalableText::from_vw(0.9),
+                                TextColor(Color::WHITE),
+                            ));
+                        });
+                        row.spawn((
+                            Node {
+                                width: Val::Vw(6.0),
+                                padding: UiRect::axes(Val::Vw(0.4), Val::Vw(0.15)),
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                            Interaction::None,
+                            RebindButton(action),
+                        ))
+                        .with_children(|button| {
+                            button.spawn((
+                                Text::new(bindings.get(action).to_string()),
+                                game_assets.text_font(14.0),
+                                ScalableText::from_vw(0.9),
+                                TextColor(Color::WHITE),
+                                RebindLabel(action),
+                            ));
+                        });
+                    });
+            }
+            panel.spawn((
+                Text::new(""),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.8),
+                TextColor(Color::srgb(1.0, 0.6, 0.4)),
+                RebindMessage,
+            ));
+            panel
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Vw(0.3)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                    Interaction::None,
+                    ResetBindingsButton,
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new("Reset to defaults"),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.9),
+                        TextColor(Color::WHITE),
+                    ));
+                });
+        });
+}
+
+pub fn handle_controls_buttons(
+    toggle_query: Query<&Interaction, (Changed<Interaction>, With<ControlsToggleButton>)>,
+    rebind_buttons: Query<(&Interaction, &RebindButton), Changed<Interaction>>,
+    reset_buttons: Query<&Interaction, (Changed<Interaction>, With<ResetBindingsButton>)>,
+    mut panel_query: Query<&mut Node, With<ControlsPanel>>,
+    mut rebind: ResMut<RebindState>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    if toggle_query.iter().any(|i| *i == Interaction::Pressed) {
+        for mut node in panel_query.iter_mut() {
+            node.display = match node.display {
+                Display::None => Display::Flex,
+                _ => Display::None,
+            };
+        }
+        rebind.listening = None;
+    }
+    for (interaction, button) in rebind_buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // clicking the one that's listening again cancels it
+        rebind.listening = if rebind.listening == Some(button.0) { None } else { Some(button.0) };
+        rebind.message = None;
+    }
+    if reset_buttons.iter().any(|i| *i == Interaction::Pressed) {
+        *bindings = InputBindings::default();
+        rebind.listening = None;
+        rebind.message = None;
+        info!("Key bindings reset to defaults");
+    }
+}
+
+/// Takes the next key or button for the listening action. Runs before Update and eats the
+/// press, so rebinding to Space doesn't also pause the game.
+pub fn capture_rebind(
+    mut rebind: ResMut<RebindState>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    let Some(action) = rebind.listening else { return };
+
+    let pressed = keys
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        // left click stays for the UI and placing, it's how you got here
+        .or_else(|| mouse.get_just_pressed().find(|b| **b != MouseButton::Left).map(|b| Binding::Mouse(*b)));
+    let Some(binding) = pressed else { return };
+    match binding {
+        Binding::Key(key) => keys.clear_just_pressed(key),
+        Binding::Mouse(button) => mouse.clear_just_pressed(button),
+    };
+
+    if binding == Binding::Key(KeyCode::Escape) {
+        rebind.listening = None;
+        return;
+    }
+    if binding.name().is_none() {
+        rebind.message = Some(format!("{:?} can't be bound", binding));
+        return;
+    }
+    match bindings.rebind(action, binding) {
+        Ok(()) => {
+            info!("{:?} bound to {}", action, binding);
+            rebind.listening = None;
+            rebind.message = None;
+        }
+        Err(other) => {
+            rebind.message = Some(format!("{} is already used for {}", binding, other.label()));
+        }
+    }
+}
+
+pub fn update_rebind_labels(
+    bindings: Res<InputBindings>,
+    rebind: Res<RebindState>,
+    mut labels: Query<(&RebindLabel, &mut Text), Without<RebindMessage>>,
+    mut messages: Query<&mut Text, With<RebindMessage>>,
+    mut buttons: Query<(&RebindButton, &mut BackgroundColor)>,
+) {
+    for (label, mut text) in labels.iter_mut() {
+        let wanted = if rebind.listening == Some(label.0) {
+            "Press a key...".to_string()
+        } else {
+            bindings.get(label.0).to_string()
+        };
+        if text.0 != wanted {
+            text.0 = wanted;
+        }
+    }
+    for mut text in messages.iter_mut() {
+        let wanted = rebind.message.clone().unwrap_or_default();
+        if text.0 != wanted {
+            text.0 = wanted;
+        }
+    }
+    for (button, mut background) in buttons.iter_mut() {
+        background.set_if_neq(BackgroundColor(if rebind.listening == Some(button.0) {
+            Color::srgb(0.45, 0.4, 0.15)
+        } else {
+            Color::srgb(0.25, 0.25, 0.25)
+        }));
+    }
+}
@@ -1,19 +1,28 @@
 use crate::assets::GameAssets;
 use crate::factory::buildings::aggregator::Aggregator;
-use crate::factory::buildings::buildings::{Building, SpriteResource};
+use crate::factory::buildings::cleaner::Cleaner;
+use crate::factory::buildings::filter_splitter::FilterSplitter;
+use crate::factory::buildings::deidentifier::DeIdentifier;
+use crate::factory::buildings::buildings::{Building, PortKind, SpriteResource};
 use crate::factory::buildings::combiner::Combiner;
 use crate::factory::buildings::delinker::Delinker;
 use crate::factory::buildings::splitter::Splitter;
+use crate::factory::buildings::trunk_link::TrunkLink;
 use crate::factory::buildings::trunker::Trunker;
 use crate::factory::physical::PhysicalLink;
 use crate::factory::ConstructBuildingEvent;
 use crate::grid::{
     are_positions_free, calculate_occupied_cells_rotated, Grid, GridPosition, Orientation, WorldMap,
 };
+use crate::controls::{Controls, InputAction, InputBindings};
+use crate::factions::{Faction, FactionReputations, ReputationLevel};
 use crate::ui::interaction::MouseButtonEvent;
 use crate::ui::interactive_event::ScalableText;
 use crate::ui::BlocksWorldClicks;
+use crate::world_gen::WorldBounds;
 use bevy::color::palettes::css::DIM_GRAY;
+use bevy::diagnostic::FrameCount;
+use bevy::math::I64Vec2;
 use bevy::prelude::*;
 use std::sync::Arc;
 
@@ -24,49 +33,242 @@ const BUILDING_TILE_SIZE: i64 = 64;
 #[derive(Component, Clone)]
 pub struct UIBuilding {
     pub building_type: Arc<dyn Building>,
+    /// Standing needed with a faction before this can be bought, None for always
+    pub required_reputation: Option<(Faction, ReputationLevel)>,
 }
 
+impl UIBuilding {
+    pub fn is_available(&self, reputations: &FactionReputations) -> bool {
+        self.required_reputation
+            .is_none_or(|(faction, level)| reputations.get_level(faction) >= level)
+    }
+}
+
+/// Shop slot still waiting on its reputation gate
+#[derive(Component)]
+pub struct ShopLocked;
+
+/// Lock and faction icon in the corner of a gated slot
+#[derive(Component)]
+pub struct ShopLockBadge;
+
+/// Gold outline flash on a slot that just unlocked, seconds left
+#[derive(Component)]
+pub struct ShopUnlockPulse(pub f32);
+
+const SHOP_UNLOCK_PULSE_SECONDS: f32 = 1.5;
+const LOCKED_SLOT_TINT: Color = Color::srgba(0.35, 0.35, 0.35, 0.8);
+
 #[derive(Component)]
 pub struct SelectedBuilding;
 
+/// Number key that picks this shop entry, from its place in the bar
+#[derive(Component)]
+pub struct ShopHotkey(pub usize);
+
+const SHOP_HOTKEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
 #[derive(Component)]
 pub struct BuildingOrientation(pub Orientation);
 
 #[derive(Resource)]
 pub struct SelectedBuildingType(pub Option<Arc<dyn Building>>);
 
+/// Share of a wire's cost given back when a building is placed over it
+pub const WIRE_REPLACE_REFUND_FRACTION: f32 = 0.6;
+
+/// Refund for a replaced wire, from what it cost (wires from before costs were recorded
+/// count at the shop price)
+pub fn wire_refund(build_cost: Option<&crate::economics::BuildCost>, game_mode: &crate::game_mode::GameMode) -> i32 {
+    let cost = build_cost.map_or_else(|| PhysicalLink { throughput: 0.0 }.data().cost, |c| c.0);
+    (game_mode.placement_cost(cost) as f32 * WIRE_REPLACE_REFUND_FRACTION).round() as i32
+}
+
+/// What placing a footprint would do
+pub enum PlacementCheck {
+    Free,
+    /// Only wires are in the way, the placement removes them
+    ReplacesWires(Vec<Entity>),
+    Blocked,
+}
+
+/// Cells holding nothing but wires can be built over by anything except another wire.
+/// Nothing goes past the edge of the world
+pub fn check_placement(
+    world_map: &WorldMap,
+    bounds: &WorldBounds,
+    cells: &[GridPosition],
+    wires: &Query<Option<&crate::economics::BuildCost>, crate::factory::physical::PlainWire>,
+    placing_wire: bool,
+) -> PlacementCheck {
+    let mut replaced = Vec::new();
+    for cell in cells {
+        if !bounds.contains(cell.0) {
+            return PlacementCheck::Blocked;
+        }
+        let Some(entities) = world_map.get(cell) else { continue };
+        if placing_wire || !entities.iter().all(|e| wires.contains(*e)) {
+            return PlacementCheck::Blocked;
+        }
+        replaced.extend(entities.iter().copied());
+    }
+    if replaced.is_empty() {
+        PlacementCheck::Free
+    } else {
+        PlacementCheck::ReplacesWires(replaced)
+    }
+}
+
+/// "will replace 3 wires, refund $45" next to the ghost
+#[derive(Component)]
+pub struct PlacementReplaceLabel;
+
+/// Frames within which a second placement is treated as the same click
+const PLACEMENT_DEBOUNCE_FRAMES: u32 = 2;
+
+/// Snapshot of where the ghost snapped this frame, shared with the placement click
+/// so both agree on the target cell
+#[derive(Resource, Default)]
+pub struct PlacementState {
+    pub snapped_cell: Option<GridPosition>,
+    last_placement: Option<(GridPosition, u32)>,
+    released_since_placement: bool,
+    /// The press that placed a building, so the shop doesn't also treat it as a click
+    consumed_press: bool,
+    /// Placements in the current shift-held chain, reset when the selection changes
+    pub chain_count: u32,
+    /// Wire run being dragged out with the left button held
+    wire_drag: Option<WireDrag>,
+    /// Last cell of a 1x1 building sweep, while the left button's held
+    sweep: Option<GridPosition>,
+    /// Whether the current sweep has put anything down, a blocked click keeps the selection
+    sweep_placed: bool,
+    /// Where a placement just failed for want of money, picked up by `show_out_of_money_warning`
+    pub out_of_money: Option<GridPosition>,
+}
+
+/// Cells laid in the current wire drag, so a right-click can take them all back
+struct WireDrag {
+    last_cell: GridPosition,
+    placed: Vec<GridPosition>,
+}
+
+/// Cells stepped through going from `from` to `to`, not including `from`. Only ever moves
+/// along one axis at a time, so consecutive cells are always neighbours a wire can join.
+pub fn cells_along(from: I64Vec2, to: I64Vec2) -> Vec<I64Vec2> {
+    let delta = to - from;
+    let (nx, ny) = (delta.x.abs(), delta.y.abs());
+    let step = delta.signum();
+    let mut current = from;
+    let mut cells = Vec::new();
+    let (mut ix, mut iy) = (0, 0);
+    while ix < nx || iy < ny {
+        // step whichever axis is further behind the straight line between the two
+        if (1 + 2 * ix) * ny < (1 + 2 * iy) * nx {
+            current.x += step.x;
+            ix += 1;
+        } else {
+            current.y += step.y;
+            iy += 1;
+        }
+        cells.push(current);
+    }
+    cells
+}
+
+impl PlacementState {
+    /// Whether a placement at `cell` on `frame` should go through
+    pub fn can_place(&self, cell: GridPosition, frame: u32) -> bool {
+        match self.last_placement {
+            Some((last_cell, last_frame))
+                if frame.wrapping_sub(last_frame) <= PLACEMENT_DEBOUNCE_FRAMES =>
+            {
+                last_cell != cell && self.released_since_placement
+            }
+            _ => true,
+        }
+    }
+
+    pub fn record_placement(&mut self, cell: GridPosition, frame: u32) {
+        self.last_placement = Some((cell, frame));
+        self.released_since_placement = false;
+        self.consumed_press = true;
+    }
+
+    pub fn on_release(&mut self) {
+        self.released_since_placement = true;
+        self.consumed_press = false;
+    }
+}
+
 /// Spawns the building shop UI bar at the bottom of the screen
-pub fn spawn_building_shop(mut commands: Commands, assets: Res<GameAssets>) {
+pub fn spawn_building_shop(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    reputations: Res<FactionReputations>,
+) {
     let buildings = [
         UIBuilding {
             building_type: Arc::new(PhysicalLink { throughput: 50.0 }),
+            required_reputation: None,
+        },
+        UIBuilding {
+            building_type: Arc::new(TrunkLink::default()),
+            required_reputation: None,
         },
         UIBuilding {
-            building_type: Arc::new(Aggregator { throughput: 5.0 }),
+            building_type: Arc::new(Aggregator::new(5.0)),
+            required_reputation: None,
         },
         UIBuilding {
             building_type: Arc::new(Splitter {
                 source_count: 2,
                 throughput: 5.0,
             }),
+            required_reputation: None,
+        },
+        UIBuilding {
+            building_type: Arc::new(FilterSplitter::new(5.0, 3)),
+            required_reputation: None,
         },
         UIBuilding {
             building_type: Arc::new(Combiner {
                 sink_count: 2,
                 throughput: 5.0,
             }),
+            required_reputation: None,
         },
         UIBuilding {
             building_type: Arc::new(Delinker {
                 throughput: 5.0,
                 source_count: 2,
             }),
+            required_reputation: Some((Faction::Criminal, ReputationLevel::Friendly)),
         },
         UIBuilding {
             building_type: Arc::new(Trunker {
                 sink_count: 2,
                 throughput_per_sink: 5.0,
             }),
+            required_reputation: Some((Faction::Corporate, ReputationLevel::Friendly)),
+        },
+        UIBuilding {
+            building_type: Arc::new(DeIdentifier { throughput: 5.0 }),
+            required_reputation: Some((Faction::Government, ReputationLevel::Friendly)),
+        },
+        UIBuilding {
+            building_type: Arc::new(Cleaner { throughput: 5.0 }),
+            required_reputation: None,
         },
     ];
 
@@ -90,8 +292,10 @@ pub fn spawn_building_shop(mut commands: Commands, assets: Res<GameAssets>) {
             BlocksWorldClicks,
         ))
         .with_children(|parent| {
-            for building in &buildings {
+            for (index, building) in buildings.iter().enumerate() {
                 let data = building.building_type.data();
+                // 1-9 in bar order, anything past that just doesn't get a key
+                let hotkey = (index < SHOP_HOTKEYS.len()).then_some(index + 1);
                 let mut image_node = match &data.sprite {
                     Some(SpriteResource::Atlas(atlas_id, index)) => {
                         let (texture, layout) = assets.get_atlas(*atlas_id);
@@ -122,15 +326,19 @@ pub fn spawn_building_shop(mut commands: Commands, assets: Res<GameAssets>) {
                 };
                 // Use Auto mode to maintain aspect ratio
                 image_node.image_mode = NodeImageMode::Auto;
+                let locked = !building.is_available(&reputations);
+                if locked {
+                    image_node.color = LOCKED_SLOT_TINT;
+                }
 
-                parent.spawn((
-                    Node {
+                parent
+                    .spawn(Node {
                         flex_direction: FlexDirection::Column,
                         align_items: AlignItems::Center,
                         ..default()
-                    },
-                    children![
-                        (
+                    })
+                    .with_children(|entry| {
+                        let mut tile = entry.spawn((
                             Node {
                                 width: Val::Vh(8.0 * data.grid_width as f32),
                                 height: Val::Vh(8.0),
@@ -142,18 +350,134 @@ pub fn spawn_building_shop(mut commands: Commands, assets: Res<GameAssets>) {
                             building.clone(),
                             Interaction::None,
                             Button,
-                        ),
-                        (
+                        ));
+                        if let Some((faction, _)) = building.required_reputation {
+                            if locked {
+                                tile.insert(ShopLocked);
+                            }
+                            tile.with_children(|slot| {
+                                spawn_shop_lock_badge(slot, &assets, faction, locked);
+                            });
+                        }
+                        if let Some(hotkey) = hotkey {
+                            tile.insert(ShopHotkey(hotkey)).with_child((
+                                Node {
+                                    position_type: PositionType::Absolute,
+                                    top: Val::Px(2.0),
+                                    left: Val::Px(4.0),
+                                    ..default()
+                                },
+                                Text::new(hotkey.to_string()),
+                                assets.text_font(10.0),
+                                ScalableText::from_vw(0.6),
+                                TextColor(Color::srgba(1.0, 1.0, 1.0, 0.7)),
+                            ));
+                        }
+                        entry.spawn((
                             Text(data.name),
                             assets.text_font(12.0),
                             ScalableText::from_vw(0.7),
-                        ),
-                    ],
-                ));
+                        ));
+                    });
             }
         });
 }
 
+fn spawn_shop_lock_badge(
+    slot: &mut ChildSpawnerCommands,
+    assets: &GameAssets,
+    faction: Faction,
+    locked: bool,
+) {
+    let (texture, layout) = assets.get_atlas(crate::assets::AtlasId::SmallSprites);
+    let faction_index = assets
+        .faction_icon(faction, crate::assets::IconSize::Small)
+        .map(|(_, index)| index)
+        .unwrap_or(0);
+    slot.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(2.0),
+            right: Val::Px(2.0),
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(1.0),
+            ..default()
+        },
+        if locked { Visibility::Inherited } else { Visibility::Hidden },
+        ShopLockBadge,
+    ))
+    .with_children(|badge| {
+        for index in [assets.utility_icons.lock, faction_index] {
+            badge.spawn((
+                Node {
+                    width: Val::Px(16.0),
+                    height: Val::Px(16.0),
+                    ..default()
+                },
+                ImageNode::from_atlas_image(
+                    texture.clone(),
+                    TextureAtlas {
+                        layout: layout.clone(),
+                        index,
+                    },
+                ),
+            ));
+        }
+    });
+}
+
+/// Greys out shop slots whose reputation gate isn't met and lights them back up when it is
+pub fn update_shop_locks(
+    mut commands: Commands,
+    reputations: Res<FactionReputations>,
+    mut slots: Query<(Entity, &UIBuilding, &mut ImageNode, &Children, Has<ShopLocked>)>,
+    mut badges: Query<&mut Visibility, With<ShopLockBadge>>,
+) {
+    for (entity, building, mut image, children, was_locked) in slots.iter_mut() {
+        let locked = !building.is_available(&reputations);
+        if locked == was_locked {
+            continue;
+        }
+        for child in children.iter() {
+            if let Ok(mut visibility) = badges.get_mut(child) {
+                *visibility = if locked { Visibility::Inherited } else { Visibility::Hidden };
+            }
+        }
+        if locked {
+            info!("{} locked again, reputation dropped", building.building_type.data().name);
+            image.color = LOCKED_SLOT_TINT;
+            commands.entity(entity).insert(ShopLocked);
+        } else {
+            info!("{} unlocked in the shop", building.building_type.data().name);
+            image.color = Color::WHITE;
+            commands
+                .entity(entity)
+                .remove::<ShopLocked>()
+                .insert(ShopUnlockPulse(SHOP_UNLOCK_PULSE_SECONDS));
+        }
+    }
+}
+
+pub fn animate_shop_unlock_pulse(
+    mut commands: Commands,
+    mut pulses: Query<(Entity, &mut ShopUnlockPulse)>,
+    time: Res<Time<Real>>,
+) {
+    for (entity, mut pulse) in pulses.iter_mut() {
+        pulse.0 -= time.delta_secs();
+        if pulse.0 <= 0.0 {
+            commands.entity(entity).remove::<(ShopUnlockPulse, Outline)>();
+            continue;
+        }
+        let alpha = pulse.0 / SHOP_UNLOCK_PULSE_SECONDS * (0.5 + 0.5 * (pulse.0 * 10.0).sin());
+        commands.entity(entity).insert(Outline::new(
+            Val::Px(3.0),
+            Val::Px(2.0),
+            Color::srgba(1.0, 0.85, 0.2, alpha),
+        ));
+    }
+}
+
 fn get_mouse_world_position(
     windows: &Query<&Window>,
     camera_query: &Query<(&Camera, &GlobalTransform)>,
@@ -176,6 +500,7 @@ pub fn handle_building_click(
         (Entity, &Interaction, &UIBuilding),
         (Changed<Interaction>, With<UIBuilding>),
     >,
+    reputations: Res<FactionReputations>,
     selected_query: Query<Entity, With<SelectedBuilding>>,
     windows: Query<&Window>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
@@ -183,72 +508,280 @@ pub fn handle_building_click(
     asset_server: Res<AssetServer>,
     assets: Res<GameAssets>,
     mut selected_building_type: ResMut<SelectedBuildingType>,
+    placement_state: Res<PlacementState>,
 ) {
+    if placement_state.consumed_press {
+        return;
+    }
     for (_entity, interaction, building) in &mut interaction_query {
-        if *interaction == Interaction::Pressed {
-            // Remove any existing selected building
-            for selected_entity in selected_query.iter() {
-                commands.entity(selected_entity).despawn();
-            }
-            // Set the selected building type
-            selected_building_type.0 = Some(building.building_type.clone());
-
+        // locked slots only get their tooltip, checked here too rather than trusting the tint
+        if *interaction == Interaction::Pressed && building.is_available(&reputations) {
             // Get initial mouse position
             let initial_position =
                 get_mouse_world_position(&windows, &camera_query).unwrap_or(Vec3::ZERO);
-
-            // Spawn a dragged building sprite at mouse position
-            let data = building.building_type.data();
-            let sprite_size = Vec2::new(
-                data.grid_width as f32 * grid.scale,
-                data.grid_height as f32 * grid.scale,
+            select_building(
+                &mut commands,
+                &building.building_type,
+                Orientation::default(),
+                &selected_query,
+                initial_position,
+                &grid,
+                &assets,
+                &mut selected_building_type,
             );
+        }
+    }
+}
 
-            // Create sprite based on SpriteResource type
-            let sprite = match &data.sprite {
-                Some(SpriteResource::Atlas(atlas_id, index)) => {
-                    let (texture, layout) = assets.get_atlas(*atlas_id);
-                    Sprite {
-                        image: texture,
-                        custom_size: Some(sprite_size),
-                        texture_atlas: Some(TextureAtlas {
-                            layout,
-                            index: *index,
-                        }),
-                        ..default()
-                    }
-                },
-                Some(SpriteResource::Machine(machine_type, variant)) => {
-                    if let Some((atlas_id, index)) = assets.machine_sprite(*machine_type, *variant) {
-                        let (texture, layout) = assets.get_atlas(atlas_id);
-                        Sprite {
-                            image: texture,
-                            custom_size: Some(sprite_size),
-                            texture_atlas: Some(TextureAtlas {
-                                layout,
-                                index,
-                            }),
-                            ..default()
-                        }
-                    } else {
-                        Sprite::default()
-                    }
-                },
-                Some(SpriteResource::Sprite(image)) => Sprite {
-                    image: image.clone(),
+/// Selects a building and spawns its ghost at `initial_position`, replacing any
+/// current selection
+fn select_building(
+    commands: &mut Commands,
+    building_type: &Arc<dyn Building>,
+    orientation: Orientation,
+    selected_query: &Query<Entity, With<SelectedBuilding>>,
+    initial_position: Vec3,
+    grid: &Grid,
+    assets: &GameAssets,
+    selected_building_type: &mut SelectedBuildingType,
+) {
+    // Remove any existing selected building
+    for selected_entity in selected_query.iter() {
+        commands.entity(selected_entity).despawn();
+    }
+    // Set the selected building type
+    selected_building_type.0 = Some(building_type.clone());
+
+    // Spawn a dragged building sprite at mouse position
+    let data = building_type.data();
+    let sprite_size = Vec2::new(
+        data.grid_width as f32 * grid.scale,
+        data.grid_height as f32 * grid.scale,
+    );
+
+    // Create sprite based on SpriteResource type
+    let sprite = match &data.sprite {
+        Some(SpriteResource::Atlas(atlas_id, index)) => {
+            let (texture, layout) = assets.get_atlas(*atlas_id);
+            Sprite {
+                image: texture,
+                custom_size: Some(sprite_size),
+                texture_atlas: Some(TextureAtlas {
+                    layout,
+                    index: *index,
+                }),
+                ..default()
+            }
+        },
+        Some(SpriteResource::Machine(machine_type, variant)) => {
+            if let Some((atlas_id, index)) = assets.machine_sprite(*machine_type, *variant) {
+                let (texture, layout) = assets.get_atlas(atlas_id);
+                Sprite {
+                    image: texture,
                     custom_size: Some(sprite_size),
+                    texture_atlas: Some(TextureAtlas {
+                        layout,
+                        index,
+                    }),
                     ..default()
-                },
-                None => Sprite::default(),
-            };
+                }
+            } else {
+                Sprite::default()
+            }
+        },
+        Some(SpriteResource::Sprite(image)) => Sprite {
+            image: image.clone(),
+            custom_size: Some(sprite_size),
+            ..default()
+        },
+        None => Sprite::default(),
+    };
 
-            commands.spawn((
-                SelectedBuilding,
-                BuildingOrientation(Orientation::default()),
-                sprite,
-                Transform::from_xyz(initial_position.x, initial_position.y, 100.0),
-                ZIndex(10), // Ensure it renders above UI
-            ));
+    let sprite = Sprite {
+        flip_x: orientation.flipped,
+        ..sprite
+    };
+
+    commands.spawn((
+        SelectedBuilding,
+        BuildingOrientation(orientation),
+        sprite,
+        Transform::from_xyz(initial_position.x, initial_position.y, 100.0)
+            .with_rotation(Quat::from_rotation_z(orientation.rotation_angle())),
+        ZIndex(10), // Ensure it renders above UI
+    ));
+}
+
+/// 1-9 pick the shop entries in bar order, pressing the selected one's key again drops it
+pub fn handle_building_hotkeys(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    shop_query: Query<(&UIBuilding, &ShopHotkey)>,
+    selected_query: Query<Entity, With<SelectedBuilding>>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    grid: Res<Grid>,
+    assets: Res<GameAssets>,
+    mut selected_building_type: ResMut<SelectedBuildingType>,
+    reputations: Res<FactionReputations>,
+) {
+    let Some(pressed) = SHOP_HOTKEYS.iter().position(|key| key_input.just_pressed(*key)) else {
+        return;
+    };
+    let Some((building, _)) = shop_query.iter().find(|(_, hotkey)| hotkey.0 == pressed + 1) else {
+        return;
+    };
+    if !building.is_available(&reputations) {
+        info!("{} is still locked", building.building_type.data().name);
+        return;
+    }
+
+    let already_selected = selected_building_type
+        .0
+        .as_ref()
+        .is_some_and(|selected| Arc::ptr_eq(selected, &building.building_type));
+    if already_selected {
+        for selected_entity in selected_query.iter() {
+            commands.entity(selected_entity).despawn();
+        }
+        selected_building_type.0 = None;
+        return;
+    }
+
+    let initial_position = get_mouse_world_position(&windows, &camera_query).unwrap_or(Vec3::ZERO);
+    select_building(
+        &mut commands,
+        &building.building_type,
+        Orientation::default(),
+        &selected_query,
+        initial_position,
+        &grid,
+        &assets,
+        &mut selected_building_type,
+    );
+}
+
+/// Shoulder buttons step through the shop in hotkey order, skipping anything still locked
+pub fn handle_gamepad_shop_cycle(
+    mut commands: Commands,
+    gamepads: Query<&Gamepad>,
+    shop_query: Query<(&UIBuilding, &ShopHotkey)>,
+    selected_query: Query<Entity, With<SelectedBuilding>>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    grid: Res<Grid>,
+    assets: Res<GameAssets>,
+    mut selected_building_type: ResMut<SelectedBuildingType>,
+    reputations: Res<FactionReputations>,
+) {
+    let step = gamepads.iter().find_map(|g| {
+        if g.just_pressed(GamepadButton::RightTrigger) {
+            Some(1)
+        } else if g.just_pressed(GamepadButton::LeftTrigger) {
+            Some(-1)
+        } else {
+            None
+        }
+    });
+    let Some(step) = step else { return };
+
+    let mut entries: Vec<_> = shop_query
+        .iter()
+        .filter(|(building, _)| building.is_available(&reputations))
+        .collect();
+    if entries.is_empty() {
+        return;
+    }
+    entries.sort_by_key(|(_, hotkey)| hotkey.0);
+    let current = selected_building_type.0.as_ref().and_then(|selected| {
+        entries
+            .iter()
+            .position(|(building, _)| Arc::ptr_eq(selected, &building.building_type))
+    });
+    let next = match current {
+        Some(i) => (i as i64 + step).rem_euclid(entries.len() as i64) as usize,
+        None if step > 0 => 0,
+        None => entries.len() - 1,
+    };
+
+    let initial_position = get_mouse_world_position(&windows, &camera_query).unwrap_or(Vec3::ZERO);
+    select_building(
+        &mut commands,
+        &entries[next].0.building_type,
+        Orientation::default(),
+        &selected_query,
+        initial_position,
+        &grid,
+        &assets,
+        &mut selected_building_type,
+    );
+}
+
+/// Q copies the building under the cursor, type, parameters and orientation. Q over
+/// anything that isn't a player building clears the selection instead.
+pub fn handle_pipette(
+    mut commands: Commands,
+    controls: Controls,
+    selected_query: Query<Entity, With<SelectedBuilding>>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    grid: Res<Grid>,
+    assets: Res<GameAssets>,
+    world_map: Res<WorldMap>,
+    placed_query: Query<&crate::save::PlacedBuilding>,
+    tile_query: Query<&crate::factory::buildings::Tile>,
+    mut selected_building_type: ResMut<SelectedBuildingType>,
+    (shop_query, reputations): (Query<&UIBuilding>, Res<FactionReputations>),
+) {
+    if !controls.just_pressed(InputAction::Pipette) {
+        return;
+    }
+    let Some(world_position) = get_mouse_world_position(&windows, &camera_query) else {
+        return;
+    };
+    let cell = grid.world_to_grid(world_position.xy());
+
+    // wires carry PlacedBuilding themselves, anything bigger has it on the tile's parent
+    let placed = world_map.get(&cell).and_then(|entities| {
+        entities.iter().find_map(|entity| {
+            placed_query.get(*entity).ok().or_else(|| {
+                tile_query
+                    .get(*entity)
+                    .ok()
+                    .and_then(|tile| placed_query.get(tile.0).ok())
+            })
+        })
+    });
+
+    match placed {
+        Some(placed) => {
+            let building = placed.descriptor.building();
+            // copying one built before the reputation dropped doesn't get round the gate
+            let name = building.data().name;
+            if shop_query
+                .iter()
+                .any(|slot| slot.building_type.data().name == name && !slot.is_available(&reputations))
+            {
+                info!("Pipette can't pick {}, it's locked", name);
+                return;
+            }
+            info!("Pipette picked {} at {:?}", name, cell);
+            select_building(
+                &mut commands,
+                &building,
+                placed.orientation,
+                &selected_query,
+                world_position,
+                &grid,
+                &assets,
+                &mut selected_building_type,
+            );
+        }
+        None => {
+            for selected_entity in selected_query.iter() {
+                commands.entity(selected_entity).despawn();
+            }
+            selected_building_type.0 = None;
         }
     }
 }
@@ -263,10 +796,19 @@ pub fn update_selected_building_position(
     camera_query: Query<(&Camera, &GlobalTransform)>,
     grid: Res<crate::grid::Grid>,
     world_map: Res<WorldMap>,
+    bounds: Res<WorldBounds>,
+    mut placement_state: ResMut<PlacementState>,
+    wires: Query<Option<&crate::economics::BuildCost>, crate::factory::physical::PlainWire>,
+    game_mode: Res<crate::game_mode::GameMode>,
+    mut label_query: Query<(&mut Text2d, &mut Transform, &mut Visibility), (With<PlacementReplaceLabel>, Without<SelectedBuilding>)>,
 ) {
+    placement_state.snapped_cell = None;
+    let mut replace_label: Option<(String, Vec3)> = None;
     if let Some(world_position) = get_mouse_world_position(&windows, &camera_query)
         && let Some(building_type) = &selected_building_type.0
     {
+        placement_state.snapped_cell = Some(grid.world_to_grid(world_position.xy()));
+
         let data = building_type.data();
 
         for (mut transform, mut sprite, orientation) in selected_query.iter_mut() {
@@ -296,26 +838,226 @@ pub fn update_selected_building_position(
             .map(GridPosition)
             .collect::<Vec<_>>();
 
-            if are_positions_free(&world_map, &occupied_positions) {
+            match check_placement(&world_map, &bounds, &occupied_positions, &wires, data.name == "Link") {
                 // Valid placement - normal color
-                sprite.color = Color::WHITE;
-            } else {
+                PlacementCheck::Free => sprite.color = Color::WHITE,
+                // Builds over wires - tint yellow and say what it costs them
+                PlacementCheck::ReplacesWires(replaced) => {
+                    sprite.color = Color::srgb(1.0, 0.9, 0.4);
+                    let refund: i32 = replaced
+                        .iter()
+                        .map(|w| wire_refund(wires.get(*w).ok().flatten(), &game_mode))
+                        .sum();
+                    replace_label = Some((
+                        format!("will replace {} wire{}, refund ${}", replaced.len(), if replaced.len() == 1 { "" } else { "s" }, refund),
+                        snapped_position,
+                    ));
+                }
                 // Invalid placement - tint red
-                sprite.color = Color::srgb(1.0, 0.5, 0.5);
+                PlacementCheck::Blocked => sprite.color = Color::srgb(1.0, 0.5, 0.5),
+            }
+        }
+    }
+
+    for (mut text, mut transform, mut visibility) in label_query.iter_mut() {
+        match &replace_label {
+            Some((label, position)) => {
+                if **text != *label {
+                    **text = label.clone();
+                }
+                transform.translation = *position + Vec3::new(0.0, -48.0, 10.0);
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+/// A cell under the ghost, tinted by whether that one cell is free
+#[derive(Component)]
+pub struct PlacementCellHighlight;
+
+const CELL_HIGHLIGHT_ALPHA: f32 = 0.35;
+
+/// Per-cell squares over the ghost's footprint so long buildings show which cell is blocked.
+/// The anchor cell also gets a white dot, handy for seeing where it sits after a rotate.
+/// Reuses the quads from last frame and drops them all once the ghost is gone.
+pub fn update_placement_cell_highlights(
+    mut commands: Commands,
+    selected_building_type: Res<SelectedBuildingType>,
+    placement_state: Res<PlacementState>,
+    ghost_query: Query<&BuildingOrientation, With<SelectedBuilding>>,
+    grid: Res<Grid>,
+    world_map: Res<WorldMap>,
+    bounds: Res<WorldBounds>,
+    wires: Query<Option<&crate::economics::BuildCost>, crate::factory::physical::PlainWire>,
+    mut highlights: Query<(Entity, &mut Transform, &mut Sprite), With<PlacementCellHighlight>>,
+) {
+    // (center, size, color) for every quad wanted this frame
+    let mut wanted: Vec<(Vec2, f32, Color)> = Vec::new();
+    if let (Some(building_type), Some(anchor), Some(orientation)) = (
+        &selected_building_type.0,
+        placement_state.snapped_cell,
+        ghost_query.iter().next(),
+    ) {
+        let data = building_type.data();
+        let placing_wire = data.name == "Link";
+        for cell in calculate_occupied_cells_rotated(*anchor, data.grid_width, data.grid_height, orientation.0) {
+            let cell = GridPosition(cell);
+            let color = match check_placement(&world_map, &bounds, &[cell], &wires, placing_wire) {
+                PlacementCheck::Free => Color::srgba(0.2, 1.0, 0.3, CELL_HIGHLIGHT_ALPHA),
+                PlacementCheck::ReplacesWires(_) => Color::srgba(1.0, 0.9, 0.3, CELL_HIGHLIGHT_ALPHA),
+                PlacementCheck::Blocked => Color::srgba(1.0, 0.2, 0.2, CELL_HIGHLIGHT_ALPHA),
+            };
+            wanted.push((grid.grid_to_world_center(&cell), grid.scale * 0.9, color));
+        }
+        wanted.push((grid.grid_to_world_center(&anchor), grid.scale * 0.25, Color::srgba(1.0, 1.0, 1.0, 0.8)));
+    }
+
+    let mut existing = highlights.iter_mut();
+    for (i, (center, size, color)) in wanted.iter().enumerate() {
+        // anchor dot goes last so it draws over its cell
+        let z = 101.0 + i as f32 * 0.01;
+        match existing.next() {
+            Some((_, mut transform, mut sprite)) => {
+                transform.translation = center.extend(z);
+                sprite.custom_size = Some(Vec2::splat(*size));
+                sprite.color = *color;
+            }
+            None => {
+                commands.spawn((
+                    Sprite {
+                        color: *color,
+                        custom_size: Some(Vec2::splat(*size)),
+                        ..default()
+                    },
+                    Transform::from_translation(center.extend(z)),
+                    PlacementCellHighlight,
+                ));
             }
         }
     }
+    for (entity, _, _) in existing {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn spawn_placement_replace_label(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        Text2d::new(""),
+        assets.text_font(16.0),
+        TextColor(Color::srgb(1.0, 0.9, 0.4)),
+        Transform::from_xyz(0.0, 0.0, 110.0),
+        Visibility::Hidden,
+        PlacementReplaceLabel,
+    ));
+}
+
+fn end_placement(
+    commands: &mut Commands,
+    selected_query: &Query<(Entity, &BuildingOrientation, &mut Sprite), With<SelectedBuilding>>,
+    selected_building_type: &mut SelectedBuildingType,
+) {
+    for (entity, _, _) in selected_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    selected_building_type.0 = None;
+}
+
+/// The "xN" label next to the ghost while shift-chaining placements
+#[derive(Component)]
+pub struct PlacementChainCounter;
+
+pub fn spawn_placement_chain_counter(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        Text2d::new(""),
+        assets.text_font(20.0),
+        TextColor(Color::srgb(0.95, 0.85, 0.25)),
+        Transform::from_xyz(0.0, 0.0, 110.0),
+        Visibility::Hidden,
+        PlacementChainCounter,
+    ));
+}
+
+pub fn update_placement_chain_counter(
+    selected_building_type: Res<SelectedBuildingType>,
+    mut placement_state: ResMut<PlacementState>,
+    ghost_query: Query<&Transform, (With<SelectedBuilding>, Without<PlacementChainCounter>)>,
+    mut counter_query: Query<(&mut Text2d, &mut Transform, &mut Visibility), With<PlacementChainCounter>>,
+) {
+    if selected_building_type.is_changed() {
+        placement_state.chain_count = 0;
+    }
+    let Ok((mut text, mut transform, mut visibility)) = counter_query.single_mut() else { return; };
+    match ghost_query.iter().next() {
+        Some(ghost) if placement_state.chain_count > 0 => {
+            transform.translation = ghost.translation + Vec3::new(40.0, 40.0, 10.0);
+            **text = format!("x{}", placement_state.chain_count);
+            *visibility = Visibility::Visible;
+        }
+        _ => *visibility = Visibility::Hidden,
+    }
+}
+
+const WARNING_SECONDS: f32 = 1.5;
+
+/// "Not enough money" rising off the cell a placement failed on
+#[derive(Component)]
+pub struct PlacementWarning {
+    age: f32,
+    origin: Vec3,
+}
+
+pub fn show_out_of_money_warning(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    grid: Res<Grid>,
+    mut placement_state: ResMut<PlacementState>,
+    existing: Query<Entity, With<PlacementWarning>>,
+) {
+    let Some(cell) = placement_state.out_of_money.take() else { return };
+    // one at a time, holding the button against an empty wallet shouldn't stack them
+    for entity in existing.iter() {
+        commands.entity(entity).despawn();
+    }
+    let origin = grid.grid_to_world_center(&cell).extend(110.0);
+    commands.spawn((
+        Text2d::new("Not enough money"),
+        assets.text_font(20.0),
+        TextColor(Color::srgb(1.0, 0.35, 0.35)),
+        Transform::from_translation(origin),
+        PlacementWarning { age: 0.0, origin },
+    ));
+}
+
+pub fn update_placement_warnings(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut warnings: Query<(Entity, &mut PlacementWarning, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut warning, mut transform, mut color) in warnings.iter_mut() {
+        warning.age += time.delta_secs();
+        if warning.age >= WARNING_SECONDS {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = warning.age / WARNING_SECONDS;
+        transform.translation = warning.origin + Vec3::new(0.0, 40.0 * t, 0.0);
+        color.0.set_alpha(1.0 - t * t);
+    }
 }
 
 pub fn handle_building_rotate(
-    key_input: Res<ButtonInput<KeyCode>>,
+    controls: Controls,
     mut selected_query: Query<
         (Entity, &mut Transform, &mut BuildingOrientation),
         With<SelectedBuilding>,
     >,
     selected_building_type: Res<SelectedBuildingType>,
 ) {
-    if key_input.just_pressed(KeyCode::KeyR)
+    // Ctrl+R is the dev event reload
+    if controls.just_pressed(InputAction::RotateBuilding)
+        && !controls.keys().any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
         && let Some(_building_type) = &selected_building_type.0
     {
         for (_entity, mut building_transform, mut orientation) in &mut selected_query {
@@ -326,14 +1068,14 @@ pub fn handle_building_rotate(
 }
 
 pub fn handle_building_flip(
-    key_input: Res<ButtonInput<KeyCode>>,
+    controls: Controls,
     mut selected_query: Query<
         (Entity, &mut Sprite, &mut BuildingOrientation),
         With<SelectedBuilding>,
     >,
     selected_building_type: Res<SelectedBuildingType>,
 ) {
-    if key_input.just_pressed(KeyCode::KeyF)
+    if controls.just_pressed(InputAction::FlipBuilding)
         && let Some(_building_type) = &selected_building_type.0
     {
         for (_entity, mut sprite, mut orientation) in &mut selected_query {
@@ -346,17 +1088,163 @@ pub fn handle_building_flip(
     }
 }
 
+/// Arrow over each port of the ghost, pointing the way data goes through it
+#[derive(Component)]
+pub struct GhostPortArrow {
+    /// Port tile relative to the anchor, already rotated and flipped
+    offset: I64Vec2,
+    side: crate::grid::Direction,
+    kind: PortKind,
+}
+
+fn port_arrow_color(kind: PortKind) -> Color {
+    match kind {
+        PortKind::Input => Color::srgb(0.4, 0.9, 1.0),
+        PortKind::Output => Color::srgb(1.0, 0.7, 0.2),
+    }
+}
+
+/// Whatever might be on the cell a port looks at
+pub type PortNeighbours<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static crate::factory::logical::DataSink>,
+        Option<&'static crate::factory::logical::DataSource>,
+        Has<PhysicalLink>,
+    ),
+>;
+
+/// True when the cell a port faces holds something it can never connect to. Empty cells,
+/// links and a neighbour's port facing straight back (a sink for an output, a source for an
+/// input) are all fine.
+pub fn port_faces_wall(
+    anchor: GridPosition,
+    offset: I64Vec2,
+    side: crate::grid::Direction,
+    kind: PortKind,
+    world_map: &WorldMap,
+    neighbours: &PortNeighbours,
+) -> bool {
+    let faced = GridPosition(anchor.0 + offset).offset(side, 1);
+    let Some(entities) = world_map.get(&faced) else { return false };
+    !entities.iter().any(|entity| {
+        let Ok((sink, source, is_link)) = neighbours.get(*entity) else { return false };
+        is_link
+            || match kind {
+                PortKind::Output => sink.is_some_and(|sink| sink.direction == side.opposite()),
+                PortKind::Input => source.is_some_and(|source| source.direction == side.opposite()),
+            }
+    })
+}
+
+/// The ports of a building placed at `anchor` that would face a dead end
+pub fn blocked_ports(
+    building: &Arc<dyn Building>,
+    anchor: GridPosition,
+    orientation: Orientation,
+    world_map: &WorldMap,
+    neighbours: &PortNeighbours,
+) -> usize {
+    building
+        .ports(orientation)
+        .into_iter()
+        .filter(|(offset, side, kind)| port_faces_wall(anchor, *offset, *side, *kind, world_map, neighbours))
+        .count()
+}
+
+/// One screen-space step along a direction, +y up
+fn direction_vec(direction: crate::grid::Direction) -> Vec2 {
+    GridPosition(I64Vec2::ZERO).offset(direction, 1).0.as_vec2()
+}
+
+/// Respawns the ghost's port arrows whenever it's selected, rotated or flipped. The ghost's own
+/// rotation is undone on the children so the arrows can be laid out in world terms.
+pub fn update_ghost_port_arrows(
+    mut commands: Commands,
+    selected_query: Query<(Entity, Ref<BuildingOrientation>, &Transform), With<SelectedBuilding>>,
+    selected_building_type: Res<SelectedBuildingType>,
+    arrows: Query<(Entity, &ChildOf), With<GhostPortArrow>>,
+    grid: Res<Grid>,
+    assets: Res<GameAssets>,
+) {
+    let Some(building_type) = &selected_building_type.0 else { return };
+    for (ghost, orientation, transform) in selected_query.iter() {
+        if !orientation.is_changed() {
+            continue;
+        }
+        for (arrow, parent) in arrows.iter() {
+            if parent.parent() == ghost {
+                commands.entity(arrow).despawn();
+            }
+        }
+
+        let data = building_type.data();
+        let ghost_center = grid.calculate_building_sprite_position(
+            &GridPosition(I64Vec2::ZERO),
+            data.grid_width,
+            data.grid_height,
+            orientation.0,
+        );
+        let undo_rotation = transform.rotation.inverse();
+        for (offset, side, kind) in building_type.ports(orientation.0) {
+            let side_vec = direction_vec(side);
+            let tile_center = grid.grid_to_world_center(&GridPosition(offset));
+            // on the tile edge the port faces, pointing in for inputs and out for outputs
+            let position = tile_center - ghost_center + side_vec * grid.scale * 0.4;
+            let pointing = match kind {
+                PortKind::Input => -side_vec,
+                PortKind::Output => side_vec,
+            };
+            // the arrow sprite points up
+            let angle = pointing.y.atan2(pointing.x) - std::f32::consts::FRAC_PI_2;
+            commands.entity(ghost).with_child((
+                Sprite {
+                    image: assets.small_sprites_texture.clone(),
+                    texture_atlas: Some(TextureAtlas {
+                        layout: assets.small_sprites_layout.clone(),
+                        index: assets.utility_icons.arrow_up,
+                    }),
+                    custom_size: Some(Vec2::splat(grid.scale * 0.35)),
+                    color: port_arrow_color(kind),
+                    ..default()
+                },
+                Transform::from_translation(undo_rotation * position.extend(1.0))
+                    .with_rotation(undo_rotation * Quat::from_rotation_z(angle)),
+                GhostPortArrow { offset, side, kind },
+            ));
+        }
+    }
+}
+
+/// Yellow arrow on any ghost port that would face something it can't connect to
+pub fn tint_blocked_ghost_ports(
+    placement_state: Res<PlacementState>,
+    world_map: Res<WorldMap>,
+    neighbours: PortNeighbours,
+    mut arrows: Query<(&GhostPortArrow, &mut Sprite)>,
+) {
+    let Some(anchor) = placement_state.snapped_cell else { return };
+    for (arrow, mut sprite) in arrows.iter_mut() {
+        let color = if port_faces_wall(anchor, arrow.offset, arrow.side, arrow.kind, &world_map, &neighbours) {
+            Color::srgb(1.0, 0.9, 0.1)
+        } else {
+            port_arrow_color(arrow.kind)
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+
 pub fn clear_selection(
     mut commands: Commands,
     mut mouse_button_event: ResMut<MouseButtonEvent>,
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<InputBindings>),
     mut selected_building: ResMut<SelectedBuildingType>,
     selected_query: Single<Option<(Entity, &BuildingOrientation)>, With<SelectedBuilding>>,
 ) {
-    let Some(mouse) = mouse_button_event.handle() else {
-        return;
-    };
-
-    if !mouse.just_pressed(MouseButton::Right) {
+    if !mouse_button_event.handle_binding(bindings.get(InputAction::ClearSelection), &keys) {
         return;
     };
 
@@ -365,24 +1253,67 @@ pub fn clear_selection(
         return;
     };
     commands.entity(entity).despawn();
-    if mouse.pressed(MouseButton::Right) {
-        selected_building.0 = None;
-    }
+    selected_building.0 = None;
 }
 
 pub fn handle_placement_click(
     mut commands: Commands,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
-    selected_query: Query<(Entity, &BuildingOrientation), With<SelectedBuilding>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut selected_query: Query<(Entity, &BuildingOrientation, &mut Sprite), With<SelectedBuilding>>,
     mut selected_building_type: ResMut<SelectedBuildingType>,
     mut construct_events: MessageWriter<ConstructBuildingEvent>,
-    windows: Query<&Window>,
-    camera_query: Query<(&Camera, &GlobalTransform)>,
-    grid: Res<crate::grid::Grid>,
     world_map: Res<WorldMap>,
     ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    mut placement_state: ResMut<PlacementState>,
+    frame_count: Res<FrameCount>,
+    mut player: ResMut<crate::player::Player>,
+    game_mode: Res<crate::game_mode::GameMode>,
+    wires: Query<Option<&crate::economics::BuildCost>, crate::factory::physical::PlainWire>,
+    links: Query<(), With<PhysicalLink>>,
+    (settings, neighbours, bounds): (Res<crate::settings::Settings>, PortNeighbours, Res<WorldBounds>),
 ) {
-    if mouse_button_input.just_pressed(MouseButton::Left) {
+    if mouse_button_input.just_released(MouseButton::Left) {
+        placement_state.on_release();
+        // the link stays selected for the next run
+        placement_state.wire_drag = None;
+    }
+
+    if let Some(building_type) = selected_building_type.0.clone()
+        && building_type.data().name == "Link"
+    {
+        drag_place_wires(
+            &building_type,
+            &mouse_button_input,
+            &mut commands,
+            &mut construct_events,
+            &world_map,
+            &bounds,
+            &ui_blocker_query,
+            &mut placement_state,
+            &mut player,
+            &game_mode,
+            &links,
+        );
+        return;
+    }
+
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    // a sweep keeps the selection while the button's down, letting go ends it like a plain click
+    if mouse_button_input.just_released(MouseButton::Left) && placement_state.sweep.take().is_some() {
+        if std::mem::take(&mut placement_state.sweep_placed) && !shift {
+            end_placement(&mut commands, &selected_query, &mut selected_building_type);
+        }
+        return;
+    }
+
+    let Some(building_type) = selected_building_type.0.clone() else { return };
+    // Use the cell the ghost snapped to this frame rather than re-reading the cursor
+    let Some(snapped_grid_pos) = placement_state.snapped_cell else { return };
+    let data = building_type.data();
+    let sweeps = data.grid_width == 1 && data.grid_height == 1;
+
+    let cells = if mouse_button_input.just_pressed(MouseButton::Left) {
         // Check if cursor is over any BlocksWorldClicks UI panel
         for interaction in ui_blocker_query.iter() {
             if *interaction == Interaction::Hovered || *interaction == Interaction::Pressed {
@@ -390,57 +1321,191 @@ pub fn handle_placement_click(
                 return;
             }
         }
+        if !placement_state.can_place(snapped_grid_pos, frame_count.0) {
+            return;
+        }
+        if sweeps {
+            placement_state.sweep = Some(snapped_grid_pos);
+            placement_state.sweep_placed = false;
+        }
+        vec![snapped_grid_pos]
+    } else if mouse_button_input.pressed(MouseButton::Left)
+        && let Some(last_cell) = placement_state.sweep
+        && last_cell != snapped_grid_pos
+    {
+        // 1x1 buildings only, so every cell passed over is one footprint further on
+        placement_state.sweep = Some(snapped_grid_pos);
+        cells_along(*last_cell, *snapped_grid_pos).into_iter().map(GridPosition).collect()
+    } else {
+        return;
+    };
 
-        // Check if we have a selected building
-        if let Some(building_type) = &selected_building_type.0 {
-            // Get mouse position
-            if let Some(world_position) = get_mouse_world_position(&windows, &camera_query) {
-                // Get building data and orientation
-                let data = building_type.data();
-                let orientation = selected_query
-                    .iter()
-                    .next()
-                    .map(|(_, o)| o.0)
-                    .unwrap_or_default();
-
-                // Convert mouse position to grid coordinates - this is the anchor cell
-                let snapped_grid_pos = grid.world_to_grid(world_position.xy());
-
-                // The snapped grid position IS the anchor
-                let base_position = *snapped_grid_pos;
-
-                // Calculate occupied positions
-                let occupied_positions = calculate_occupied_cells_rotated(
-                    base_position,
-                    data.grid_width,
-                    data.grid_height,
-                    orientation,
-                )
-                .into_iter()
-                .map(GridPosition)
-                .collect::<Vec<_>>();
-
-                // Only place if positions are free
-                if are_positions_free(&world_map, &occupied_positions) {
-                    // Send construction event with:
-                    // - rotation: The actual rotation direction (not flipped)
-                    // - flipped: The flip state - building spawn system will handle the flip
-                    construct_events.write(ConstructBuildingEvent {
-                        building: building_type.clone(),
-                        grid_position: base_position,
-                        orientation,
-                    });
+    // the ghost keeps its orientation across the whole chain
+    let orientation = selected_query
+        .iter()
+        .next()
+        .map(|(_, o, _)| o.0)
+        .unwrap_or_default();
+    // the money only comes off when the events are handled, so keep count of this frame's
+    let mut committed = 0;
+    let mut placed_any = false;
+    for cell in cells {
+        // The snapped grid position IS the anchor
+        let base_position = *cell;
 
-                    // // Despawn the dragged building
-                    // for (entity, _) in selected_query.iter() {
-                    //     commands.entity(entity).despawn();
-                    // }
+        // off by default, the arrows going yellow is usually warning enough
+        if settings.block_facing_ports {
+            let blocked = blocked_ports(&building_type, base_position, orientation, &world_map, &neighbours);
+            if blocked > 0 {
+                info!("{} would have {} port(s) facing a dead end at {:?}", data.name, blocked, base_position);
+                continue;
+            }
+        }
 
-                    // Clear selection
-                    // selected_building_type.0 = None;
-                }
-                // If occupied, do nothing - building stays selected and tinted red
+        if !game_mode.can_afford(data.cost, player.money - committed) {
+            info!("Can't afford {} ({} needed, {} available)", data.name, data.cost, player.money - committed);
+            placement_state.out_of_money = Some(cell);
+            placement_state.sweep = None;
+            // running out of money ends a repeat chain or a sweep
+            if placement_state.chain_count > 0 {
+                end_placement(&mut commands, &selected_query, &mut selected_building_type);
             }
+            return;
+        }
+
+        // Calculate occupied positions
+        let occupied_positions = calculate_occupied_cells_rotated(
+            base_position,
+            data.grid_width,
+            data.grid_height,
+            orientation,
+        )
+        .into_iter()
+        .map(GridPosition)
+        .collect::<Vec<_>>();
+
+        // Only place if positions are free, or only hold wires we can build over.
+        // If occupied, do nothing - building stays selected and tinted red
+        let replaces = match check_placement(&world_map, &bounds, &occupied_positions, &wires, data.name == "Link") {
+            PlacementCheck::Free => Vec::new(),
+            PlacementCheck::ReplacesWires(replaced) => replaced,
+            PlacementCheck::Blocked => continue,
+        };
+        // Send construction event with:
+        // - rotation: The actual rotation direction (not flipped)
+        // - flipped: The flip state - building spawn system will handle the flip
+        construct_events.write(ConstructBuildingEvent {
+            building: building_type.clone(),
+            grid_position: base_position,
+            orientation,
+            replaces,
+            free: false,
+        });
+        committed += game_mode.placement_cost(data.cost);
+        placement_state.record_placement(cell, frame_count.0);
+        placement_state.chain_count += 1;
+        placed_any = true;
+    }
+
+    if !placed_any {
+        return;
+    }
+    if placement_state.sweep.is_some() {
+        placement_state.sweep_placed = true;
+    }
+    if shift || placement_state.sweep.is_some() {
+        // Keep the ghost for another copy. It's sitting on the cells we just
+        // filled, so show it as blocked now rather than waiting a frame for the map.
+        for (_, _, mut sprite) in selected_query.iter_mut() {
+            sprite.color = Color::srgb(1.0, 0.5, 0.5);
         }
+    } else {
+        end_placement(&mut commands, &selected_query, &mut selected_building_type);
+    }
+}
+
+/// Wires go down belt-style: hold left and every free cell the cursor passes through gets
+/// one, including the ones skipped over between frames. Right-click mid-drag takes the
+/// whole run back with a full refund.
+fn drag_place_wires(
+    building_type: &Arc<dyn Building>,
+    mouse_button_input: &ButtonInput<MouseButton>,
+    commands: &mut Commands,
+    construct_events: &mut MessageWriter<ConstructBuildingEvent>,
+    world_map: &WorldMap,
+    bounds: &WorldBounds,
+    ui_blocker_query: &Query<&Interaction, With<BlocksWorldClicks>>,
+    placement_state: &mut PlacementState,
+    player: &mut ResMut<crate::player::Player>,
+    game_mode: &crate::game_mode::GameMode,
+    links: &Query<(), With<PhysicalLink>>,
+) {
+    let data = building_type.data();
+
+    if mouse_button_input.just_pressed(MouseButton::Right)
+        && let Some(drag) = placement_state.wire_drag.take()
+    {
+        let mut refund = 0;
+        for cell in drag.placed.iter() {
+            let Some(entities) = world_map.get(cell) else { continue };
+            if let Some(&wire) = entities.iter().find(|e| links.contains(**e)) {
+                commands
+                    .entity(wire)
+                    .remove::<PhysicalLink>()
+                    .insert((crate::factory::MarkedForRemoval, crate::factory::undo::NotUndoable));
+                refund += game_mode.placement_cost(data.cost);
+            }
+        }
+        player.money += refund;
+        info!("Cancelled wire drag, removed {} wire(s), refunded {}", drag.placed.len(), refund);
+        return;
+    }
+
+    let Some(cell) = placement_state.snapped_cell else { return };
+
+    let path = if mouse_button_input.just_pressed(MouseButton::Left) {
+        // Check if cursor is over any BlocksWorldClicks UI panel
+        if ui_blocker_query
+            .iter()
+            .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed)
+        {
+            return;
+        }
+        placement_state.consumed_press = true;
+        placement_state.wire_drag = Some(WireDrag { last_cell: cell, placed: Vec::new() });
+        vec![cell]
+    } else if mouse_button_input.pressed(MouseButton::Left)
+        && let Some(drag) = placement_state.wire_drag.as_mut()
+        && drag.last_cell != cell
+    {
+        let from = drag.last_cell;
+        drag.last_cell = cell;
+        cells_along(*from, *cell).into_iter().map(GridPosition).collect()
+    } else {
+        return;
+    };
+
+    let Some(drag) = placement_state.wire_drag.as_mut() else { return };
+    // the money only comes off when the events are handled, so keep count of this frame's
+    let mut committed = 0;
+    for cell in path {
+        if drag.placed.contains(&cell) || !are_positions_free(world_map, bounds, &[cell]) {
+            continue;
+        }
+        if !game_mode.can_afford(data.cost, player.money - committed) {
+            info!("Can't afford another {} ({} needed)", data.name, data.cost);
+            placement_state.out_of_money = Some(cell);
+            break;
+        }
+        construct_events.write(ConstructBuildingEvent {
+            building: building_type.clone(),
+            grid_position: *cell,
+            orientation: Orientation::default(),
+            replaces: Vec::new(),
+            free: false,
+        });
+        committed += game_mode.placement_cost(data.cost);
+        drag.placed.push(cell);
+        placement_state.chain_count += 1;
     }
 }
@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::assets::GameAssets;
+use crate::factions::{reputation_level_min_score, reputation_level_name, FactionReputations};
+use crate::factory::buildings::buildings::PortKind;
+use crate::grid::Orientation;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::shop::UIBuilding;
+
+/// Hover this long on a shop slot before the tooltip comes up
+const SHOP_TOOLTIP_DELAY: f32 = 0.4;
+/// Gap between the cursor and the panel's corner, logical px
+const CURSOR_OFFSET: Vec2 = Vec2::new(16.0, 12.0);
+
+#[derive(Component)]
+pub struct ShopTooltip;
+
+#[derive(Component)]
+pub struct ShopTooltipTitle;
+
+#[derive(Component)]
+pub struct ShopTooltipBody;
+
+/// Red line at the bottom saying why it can't be bought right now
+#[derive(Component)]
+pub struct ShopTooltipReason;
+
+/// Which slot the cursor's on and for how long
+#[derive(Resource, Default)]
+pub struct ShopHover {
+    slot: Option<Entity>,
+    elapsed: f32,
+}
+
+pub fn spawn_shop_tooltip(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                padding: UiRect::all(Val::Vw(0.6)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vh(0.4),
+                max_width: Val::Vw(20.0),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.92)),
+            BorderColor::all(Color::srgb(0.4, 0.4, 0.45)),
+            GlobalZIndex(900),
+            // never catches the hover itself, or it'd flicker over the slot it's describing
+            Pickable::IGNORE,
+            ShopTooltip,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new(""),
+                game_assets.text_font(16.0),
+                ScalableText::from_vw(1.1),
+                TextColor(Color::WHITE),
+                ShopTooltipTitle,
+            ));
+            panel.spawn((
+                Text::new(""),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.85),
+                TextColor(Color::srgb(0.85, 0.85, 0.85)),
+                ShopTooltipBody,
+            ));
+            panel.spawn((
+                Text::new(""),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.85),
+                TextColor(Color::srgb(1.0, 0.4, 0.35)),
+                Node { display: Display::None, ..default() },
+                ShopTooltipReason,
+            ));
+        });
+}
+
+/// Why a shop entry can't be bought right now, None when it can
+pub fn shop_unavailable_reason(
+    building: &UIBuilding,
+    reputations: &FactionReputations,
+    player: &crate::player::Player,
+    game_mode: &crate::game_mode::GameMode,
+) -> Option<String> {
+    if let Some((faction, level)) = building.required_reputation
+        && !building.is_available(reputations)
+    {
+        return Some(format!(
+            "Locked: needs {} ({}) with {:?}",
+            reputation_level_name(level),
+            reputation_level_min_score(level),
+            faction
+        ));
+    }
+    let cost = building.building_type.data().cost;
+    if !game_mode.can_afford(cost, player.money) {
+        return Some(format!("Can't afford: ${} more needed", cost - player.money));
+    }
+    None
+}
+
+fn shop_tooltip_body(building: &UIBuilding) -> String {
+    let data = building.building_type.data();
+    let mut lines = vec![format!("Footprint: {}x{}", data.grid_width, data.grid_height)];
+    if data.upkeep_per_sec > 0 {
+        lines.push(format!("Upkeep: ${}/s", data.upkeep_per_sec));
+    }
+    if data.throughput > 0.0 {
+        lines.push(format!("Throughput: {}/s", data.throughput));
+    }
+    let ports = building.building_type.ports(Orientation::default());
+    let inputs = ports.iter().filter(|(_, _, kind)| *kind == PortKind::Input).count();
+    let outputs = ports.len() - inputs;
+    if !ports.is_empty() {
+        lines.push(format!("Ports: {} in, {} out", inputs, outputs));
+    }
+    if !data.description.is_empty() {
+        lines.push(data.description.clone());
+    }
+    lines.join("\n")
+}
+
+/// Shows the tooltip for the hovered shop slot after a short delay and keeps it by the cursor
+pub fn update_shop_tooltip(
+    slots: Query<(Entity, &Interaction, &UIBuilding)>,
+    mut hover: ResMut<ShopHover>,
+    time: Res<Time<Real>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    (player, game_mode, reputations): (
+        Res<crate::player::Player>,
+        Res<crate::game_mode::GameMode>,
+        Res<FactionReputations>,
+    ),
+    mut panel: Single<(&mut Node, &ComputedNode), With<ShopTooltip>>,
+    mut title: Single<&mut Text, (With<ShopTooltipTitle>, Without<ShopTooltipBody>, Without<ShopTooltipReason>)>,
+    mut body: Single<&mut Text, (With<ShopTooltipBody>, Without<ShopTooltipTitle>, Without<ShopTooltipReason>)>,
+    mut reason: Single<(&mut Text, &mut Node), (With<ShopTooltipReason>, Without<ShopTooltip>, Without<ShopTooltipTitle>, Without<ShopTooltipBody>)>,
+) {
+    let hovered = slots
+        .iter()
+        .find(|(_, interaction, _)| **interaction != Interaction::None);
+    let Some((slot, interaction, building)) = hovered else {
+        hover.slot = None;
+        panel.0.display = Display::None;
+        return;
+    };
+    if hover.slot != Some(slot) {
+        hover.slot = Some(slot);
+        hover.elapsed = 0.0;
+    }
+    hover.elapsed += time.delta_secs();
+    // clicking a locked slot is asking why, no point making them wait
+    if *interaction == Interaction::Pressed && !building.is_available(&reputations) {
+        hover.elapsed = hover.elapsed.max(SHOP_TOOLTIP_DELAY);
+    }
+    let Some(cursor) = window.cursor_position() else { return };
+    if hover.elapsed < SHOP_TOOLTIP_DELAY {
+        panel.0.display = Display::None;
+        return;
+    }
+
+    let data = building.building_type.data();
+    let title_text = format!("{} - ${}", data.name, data.cost);
+    if title.0 != title_text {
+        title.0 = title_text;
+    }
+    let body_text = shop_tooltip_body(building);
+    if body.0 != body_text {
+        body.0 = body_text;
+    }
+    let (reason_text, reason_node) = &mut *reason;
+    match shop_unavailable_reason(building, &reputations, &player, &game_mode) {
+        Some(why) => {
+            if reason_text.0 != why {
+                reason_text.0 = why;
+            }
+            reason_node.display = Display::Flex;
+        }
+        None => reason_node.display = Display::None,
+    }
+
+    let (node, computed) = &mut *panel;
+    place_by_cursor(node, computed, cursor, &window);
+    node.display = Display::Flex;
+}
+
+/// Puts an absolute tooltip up and to the right of the cursor, pushed back in at the edges.
+/// ComputedNode lags a frame behind the text, close enough for a tooltip
+pub fn place_by_cursor(node: &mut Node, computed: &ComputedNode, cursor: Vec2, window: &Window) {
+    let size = computed.size() * computed.inverse_scale_factor();
+    let max = (Vec2::new(window.width(), window.height()) - size).max(Vec2::ZERO);
+    let corner = Vec2::new(cursor.x + CURSOR_OFFSET.x, cursor.y - size.y - CURSOR_OFFSET.y).clamp(Vec2::ZERO, max);
+    node.left = Val::Px(corner.x);
+    node.top = Val::Px(corner.y);
+}
@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::assets::GameAssets;
+use crate::contracts::{ContractFulfillment, ContractFulfillmentStatus, ContractStatus};
+use crate::settings::Settings;
+use crate::ui::contracts::ContractsSidebarRoot;
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::{BlocksWorldClicks, BlocksWorldScroll};
+
+pub const SIDEBAR_WIDTH_VW: f32 = 25.0;
+pub const SIDEBAR_TOP_PX: f32 = 45.0;
+pub const SIDEBAR_BOTTOM_PCT: f32 = 15.0;
+pub const COLLAPSED_TAB_WIDTH_PX: f32 = 32.0;
+const SLIDE_SECONDS: f32 = 0.15;
+const PULSE_SECONDS: f32 = 3.0;
+
+/// Screen space (logical px) covered by fixed UI on each edge. Anything that frames
+/// the camera around the visible world should inset by these instead of hardcoding the sidebar.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct UiOccludedMargins {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+/// Slide animation for the sidebar, 0 = expanded, 1 = collapsed
+#[derive(Resource, Default)]
+pub struct SidebarSlide {
+    pub t: f32,
+    /// Seconds left of the collapsed tab pulsing for attention
+    pub pulse: f32,
+    last_failing: usize,
+    last_pending: usize,
+}
+
+/// Chevron on the sidebar's left edge
+#[derive(Component)]
+pub struct SidebarCollapseButton;
+
+#[derive(Component)]
+pub struct SidebarCollapseChevron;
+
+/// Thin tab shown while the sidebar is collapsed
+#[derive(Component)]
+pub struct CollapsedSidebarTab;
+
+#[derive(Component, Clone, Copy)]
+pub enum SummaryChip {
+    Meeting,
+    Failing,
+    Pending,
+}
+
+pub fn spawn_sidebar_collapse_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Vw(SIDEBAR_WIDTH_VW),
+                top: Val::Px(SIDEBAR_TOP_PX),
+                width: Val::Px(20.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.08, 0.08, 0.12)),
+            SidebarCollapseButton,
+            BlocksWorldClicks,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(">"),
+                game_assets.text_font(16.0),
+                ScalableText::from_vw(1.0),
+                TextColor(Color::WHITE),
+                SidebarCollapseChevron,
+            ));
+        });
+
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                top: Val::Px(SIDEBAR_TOP_PX),
+                bottom: Val::Percent(SIDEBAR_BOTTOM_PCT),
+                width: Val::Px(COLLAPSED_TAB_WIDTH_PX),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::vertical(Val::Px(8.0)),
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.08, 0.08, 0.12)),
+            CollapsedSidebarTab,
+            BlocksWorldClicks,
+            BlocksWorldScroll,
+        ))
+        .with_children(|tab| {
+            tab.spawn((
+                Text::new("<"),
+                game_assets.text_font(16.0),
+                ScalableText::from_vw(1.0),
+                TextColor(Color::WHITE),
+            ));
+            for (chip, color) in [
+                (SummaryChip::Meeting, Color::srgb(0.3, 0.9, 0.3)),
+                (SummaryChip::Failing, Color::srgb(1.0, 0.3, 0.3)),
+                (SummaryChip::Pending, Color::srgb(0.95, 0.85, 0.25)),
+            ] {
+                tab.spawn((
+                    Text::new("0"),
+                    game_assets.text_font(14.0),
+                    ScalableText::from_vw(0.9),
+                    TextColor(color),
+                    chip,
+                ));
+            }
+        });
+}
+
+/// Chevron, tab click and the sidebar key (C by default) flip the persisted collapsed state
+pub fn handle_sidebar_collapse_input(
+    controls: crate::controls::Controls,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<SidebarCollapseButton>)>,
+    tab_query: Query<&Interaction, (Changed<Interaction>, With<CollapsedSidebarTab>)>,
+    mut settings: ResMut<Settings>,
+) {
+    let clicked = button_query
+        .iter()
+        .chain(tab_query.iter())
+        .any(|i| *i == Interaction::Pressed);
+    if clicked || controls.just_pressed(crate::controls::InputAction::ToggleSidebar) {
+        settings.sidebar_collapsed = !settings.sidebar_collapsed;
+    }
+}
+
+/// Slides the sidebar towards the collapsed state and swaps in the tab at the end
+pub fn animate_sidebar_slide(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut slide: ResMut<SidebarSlide>,
+    mut sidebar_query: Query<&mut Node, (With<ContractsSidebarRoot>, Without<SidebarCollapseButton>, Without<CollapsedSidebarTab>)>,
+    mut button_query: Query<&mut Node, (With<SidebarCollapseButton>, Without<CollapsedSidebarTab>)>,
+    mut tab_query: Query<&mut Node, With<CollapsedSidebarTab>>,
+) {
+    let target = if settings.sidebar_collapsed { 1.0 } else { 0.0 };
+    let rate = if settings.reduce_motion { 1.0 } else { time.delta_secs() / SLIDE_SECONDS };
+    let stepped = if slide.t < target {
+        (slide.t + rate).min(target)
+    } else {
+        (slide.t - rate).max(target)
+    };
+    if stepped == slide.t && !slide.is_added() {
+        return;
+    }
+    slide.t = stepped;
+
+    let collapsed = slide.t >= 1.0;
+    for mut node in sidebar_query.iter_mut() {
+        node.right = Val::Vw(-SIDEBAR_WIDTH_VW * slide.t);
+        node.display = if collapsed { Display::None } else { Display::Flex };
+    }
+    for mut node in button_query.iter_mut() {
+        node.right = Val::Vw(SIDEBAR_WIDTH_VW * (1.0 - slide.t));
+        node.display = if collapsed { Display::None } else { Display::Flex };
+    }
+    for mut node in tab_query.iter_mut() {
+        node.display = if collapsed { Display::Flex } else { Display::None };
+    }
+}
+
+pub fn update_sidebar_chevron(
+    settings: Res<Settings>,
+    mut chevron_query: Query<&mut Text, With<SidebarCollapseChevron>>,
+) {
+    for mut text in chevron_query.iter_mut() {
+        **text = if settings.sidebar_collapsed { "<" } else { ">" }.to_string();
+    }
+}
+
+/// Keeps the tab's meeting/failing/pending counts current, and pulses it when something
+/// new needs attention while collapsed
+pub fn update_collapsed_tab(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut slide: ResMut<SidebarSlide>,
+    contracts: Query<(&ContractStatus, &ContractFulfillment)>,
+    mut chips: Query<(&SummaryChip, &mut Text)>,
+    mut tab_query: Query<&mut BackgroundColor, With<CollapsedSidebarTab>>,
+) {
+    let (mut meeting, mut failing, mut pending) = (0, 0, 0);
+    for (status, fulfillment) in contracts.iter() {
+        match (status, fulfillment.status) {
+            (ContractStatus::Pending, _) => pending += 1,
+            (ContractStatus::Active, ContractFulfillmentStatus::Failing) => failing += 1,
+            (ContractStatus::Active, _) => meeting += 1,
+            _ => {}
+        }
+    }
+
+    // a count going up means a new pending offer or a contract that just started failing
+    if settings.sidebar_collapsed {
+        if failing > slide.last_failing || pending > slide.last_pending {
+            slide.pulse = PULSE_SECONDS;
+        }
+    } else {
+        slide.pulse = 0.0;
+    }
+    slide.last_failing = failing;
+    slide.last_pending = pending;
+    for (chip, mut text) in chips.iter_mut() {
+        let count = match chip {
+            SummaryChip::Meeting => meeting,
+            SummaryChip::Failing => failing,
+            SummaryChip::Pending => pending,
+        };
+        let label = count.to_string();
+        if **text != label {
+            **text = label;
+        }
+    }
+
+    slide.pulse = (slide.pulse - time.delta_secs()).max(0.0);
+    let glow = if slide.pulse > 0.0 {
+        (slide.pulse * std::f32::consts::TAU).sin().abs() * 0.35
+    } else {
+        0.0
+    };
+    for mut background in tab_query.iter_mut() {
+        background.0 = Color::srgb(0.08 + glow, 0.08 + glow * 0.8, 0.12);
+    }
+}
+
+pub fn update_ui_occluded_margins(
+    window: Single<&Window, With<PrimaryWindow>>,
+    slide: Res<SidebarSlide>,
+    mut margins: ResMut<UiOccludedMargins>,
+) {
+    let width = window.width();
+    let sidebar = if slide.t >= 1.0 {
+        COLLAPSED_TAB_WIDTH_PX
+    } else {
+        width * SIDEBAR_WIDTH_VW / 100.0 * (1.0 - slide.t)
+    };
+    let updated = UiOccludedMargins {
+        left: 0.0,
+        right: sidebar,
+        top: SIDEBAR_TOP_PX,
+        bottom: window.height() * crate::ui::shop::BUILDING_BAR_HEIGHT_PCT / 100.0,
+    };
+    if updated.right != margins.right || updated.bottom != margins.bottom {
+        *margins = updated;
+    }
+}
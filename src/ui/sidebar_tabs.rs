@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::controls::{Controls, InputAction};
+use crate::events::{InteractiveEventLibrary, PlayerChoiceEvent};
+use crate::settings::Settings;
+use crate::ui::interactive_event::{consequence_line, ScalableText};
+use crate::ui::BlocksWorldClicks;
+
+/// The log drops its oldest entries past this many
+const MAX_LOG_ENTRIES: usize = 50;
+const TAB_COLOR: Color = Color::srgb(0.12, 0.12, 0.17);
+const ACTIVE_TAB_COLOR: Color = Color::srgb(0.25, 0.25, 0.35);
+
+/// Which pane the sidebar is showing
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidebarTab {
+    #[default]
+    Contracts,
+    Factions,
+    EventLog,
+}
+
+impl SidebarTab {
+    const ALL: [SidebarTab; 3] = [SidebarTab::Contracts, SidebarTab::Factions, SidebarTab::EventLog];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SidebarTab::Contracts => "Contracts",
+            SidebarTab::Factions => "Factions",
+            SidebarTab::EventLog => "Log",
+        }
+    }
+
+    fn action(&self) -> InputAction {
+        match self {
+            SidebarTab::Contracts => InputAction::ContractsTab,
+            SidebarTab::Factions => InputAction::FactionsTab,
+            SidebarTab::EventLog => InputAction::EventLogTab,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SidebarTabButton(pub SidebarTab);
+
+/// A tab's content, built once and shown or hidden with `Display`
+#[derive(Component)]
+pub struct SidebarTabPane(pub SidebarTab);
+
+/// "Nothing yet" line in the log, goes with the first entry
+#[derive(Component)]
+pub struct EventLogPlaceholder;
+
+#[derive(Component)]
+pub struct EventLogEntry;
+
+pub fn spawn_tab_bar(sidebar: &mut ChildSpawnerCommands, game_assets: &GameAssets) {
+    sidebar
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Row,
+            flex_shrink: 0.0,
+            ..default()
+        })
+        .with_children(|bar| {
+            for tab in SidebarTab::ALL {
+                bar.spawn((
+                    Node {
+                        flex_grow: 1.0,
+                        justify_content: JustifyContent::Center,
+                        padding: UiRect::vertical(Val::Vw(0.3)),
+                        ..default()
+                    },
+                    BackgroundColor(if tab == SidebarTab::default() { ACTIVE_TAB_COLOR } else { TAB_COLOR }),
+                    Interaction::None,
+                    BlocksWorldClicks,
+                    SidebarTabButton(tab),
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new(tab.label()),
+                        game_assets.text_font(14.0),
+                        ScalableText::from_vw(0.85),
+                        TextColor(Color::WHITE),
+                    ));
+                });
+            }
+        });
+}
+
+/// Scrolls on its own, so the wheel moves whichever pane is showing
+pub fn tab_pane_node(tab: SidebarTab) -> Node {
+    Node {
+        display: if tab == SidebarTab::default() { Display::Flex } else { Display::None },
+        width: Val::Percent(100.0),
+        flex_grow: 1.0,
+        min_height: Val::Px(0.0),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::FlexStart,
+        justify_content: JustifyContent::FlexStart,
+        overflow: Overflow::scroll_y(),
+        ..default()
+    }
+}
+
+/// Tab clicks and shortcuts (F1-F3 by default). A shortcut while the sidebar's collapsed opens it too
+pub fn handle_sidebar_tab_input(
+    controls: Controls,
+    buttons: Query<(&Interaction, &SidebarTabButton), Changed<Interaction>>,
+    mut tab: ResMut<SidebarTab>,
+    mut settings: ResMut<Settings>,
+) {
+    let clicked = buttons
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button.0);
+    let pressed = SidebarTab::ALL.into_iter().find(|t| controls.just_pressed(t.action()));
+    if pressed.is_some() && settings.sidebar_collapsed {
+        settings.sidebar_collapsed = false;
+    }
+    if let Some(next) = clicked.or(pressed) {
+        if *tab != next {
+            *tab = next;
+        }
+    }
+}
+
+pub fn update_sidebar_tabs(
+    tab: Res<SidebarTab>,
+    mut panes: Query<(&SidebarTabPane, &mut Node)>,
+    mut buttons: Query<(&SidebarTabButton, &mut BackgroundColor)>,
+) {
+    for (pane, mut node) in panes.iter_mut() {
+        node.display = if pane.0 == *tab { Display::Flex } else { Display::None };
+    }
+    for (button, mut background) in buttons.iter_mut() {
+        background.0 = if button.0 == *tab { ACTIVE_TAB_COLOR } else { TAB_COLOR };
+    }
+}
+
+pub fn spawn_event_log_placeholder(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    panes: Query<(Entity, &SidebarTabPane)>,
+) {
+    let Some((pane, _)) = panes.iter().find(|(_, pane)| pane.0 == SidebarTab::EventLog) else { return };
+    commands.spawn((
+        Node {
+            margin: UiRect::all(Val::Vw(0.6)),
+            ..default()
+        },
+        Text::new("No events resolved yet"),
+        game_assets.text_font(14.0),
+        ScalableText::from_vw(0.8),
+        TextColor(Color::srgb(0.6, 0.6, 0.6)),
+        EventLogPlaceholder,
+        ChildOf(pane),
+    ));
+}
+
+/// One entry per resolved event, newest on top: when, what, the choice and what it did.
+/// Hidden consequences have happened by now, so they're listed as what they were
+pub fn log_resolved_events(
+    mut commands: Commands,
+    mut choices: MessageReader<PlayerChoiceEvent>,
+    time: Res<Time>,
+    library: Res<InteractiveEventLibrary>,
+    game_assets: Res<GameAssets>,
+    panes: Query<(Entity, &SidebarTabPane)>,
+    placeholder: Query<Entity, With<EventLogPlaceholder>>,
+    entries: Query<(), With<EventLogEntry>>,
+    children: Query<&Children>,
+) {
+    let Some((pane, _)) = panes.iter().find(|(_, pane)| pane.0 == SidebarTab::EventLog) else { return };
+    let mut logged = 0;
+    for choice in choices.read() {
+        let Some(event) = library.get_event_by_id(&choice.event_id) else { continue };
+        let Some(picked) = event.choices.get(choice.choice_index) else { continue };
+        let elapsed = time.elapsed_secs().round() as u32;
+        let title_color = event.faction.map(|f| game_assets.faction_color(f)).unwrap_or(Color::WHITE);
+
+        let entry = commands
+            .spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    flex_shrink: 0.0,
+                    row_gap: Val::Vw(0.2),
+                    padding: UiRect::all(Val::Vw(0.6)),
+                    border: UiRect::bottom(Val::Px(1.0)),
+                    ..default()
+                },
+                BorderColor::all(Color::srgb(0.2, 0.2, 0.28)),
+                EventLogEntry,
+            ))
+            .with_children(|entry| {
+                entry.spawn((
+                    Text::new(format!("{}:{:02}  {}", elapsed / 60, elapsed % 60, event.title)),
+                    game_assets.text_font(14.0),
+                    ScalableText::from_vw(0.85),
+                    TextColor(title_color),
+                ));
+                entry.spawn((
+                    Text::new(format!("> {}", picked.text)),
+                    game_assets.text_font(13.0),
+                    ScalableText::from_vw(0.75),
+                    TextColor(Color::WHITE),
+                ));
+                for consequence in &picked.consequences {
+                    entry.spawn((
+                        Text::new(consequence_line(consequence.revealed())),
+                        game_assets.text_font(12.0),
+                        ScalableText::from_vw(0.7),
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                }
+            })
+            .id();
+        commands.entity(pane).insert_children(0, &[entry]);
+        logged += 1;
+    }
+    if logged == 0 {
+        return;
+    }
+
+    for entity in placeholder.iter() {
+        commands.entity(entity).despawn();
+    }
+    // existing entries sit after the new ones, drop from the bottom
+    let existing: Vec<Entity> = children
+        .get(pane)
+        .map(|c| c.iter().filter(|e| entries.contains(*e)).collect())
+        .unwrap_or_default();
+    for entity in existing.into_iter().skip(MAX_LOG_ENTRIES.saturating_sub(logged)) {
+        commands.entity(entity).despawn();
+    }
+}
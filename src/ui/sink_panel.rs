@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::contracts::{Contract, ContractDescription, ContractFulfillment, ContractStatus, SinkContracts};
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::buildings::Tile;
+use crate::factory::logical::Dataset;
+use crate::grid::{Grid, GridPosition, WorldMap};
+use crate::ui::contracts::{
+    card_bar_width, card_color, card_field, CardContract, CardField, ContractAcceptButton, ContractCardBar,
+    ContractCardLookups, ContractEntityLink,
+};
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::BlocksWorldClicks;
+
+/// Small panel over a sink listing the contracts it serves
+#[derive(Component)]
+pub struct SinkContractsPanel {
+    pub sink: Entity,
+}
+
+type ContractData<'a> = (
+    Entity,
+    &'a ContractStatus,
+    &'a ContractDescription,
+    &'a ContractFulfillment,
+    &'a Dataset,
+);
+
+fn spawn_sink_contracts_panel(
+    commands: &mut Commands,
+    lookups: &ContractCardLookups,
+    sink: Entity,
+    contracts: &[ContractData],
+) {
+    let game_assets = &lookups.game_assets;
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Vw(0.6)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.4),
+                min_width: Val::Vw(14.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            // off screen until the first follow pass places it
+            Visibility::Hidden,
+            ZIndex(150),
+            SinkContractsPanel { sink },
+            BlocksWorldClicks,
+            Interaction::None,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new("Sink contracts"),
+                game_assets.text_font(16.0),
+                ScalableText::from_vw(1.1),
+                TextColor(Color::WHITE),
+            ));
+            if contracts.is_empty() {
+                panel.spawn((
+                    Text::new("Nothing contracted here yet"),
+                    game_assets.text_font(12.0),
+                    ScalableText::from_vw(0.9),
+                    TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                ));
+            }
+            for &(entity, status, desc, fulfillment, dataset) in contracts {
+                let contract = CardContract { entity, status, desc, fulfillment, dataset };
+                panel.spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Vw(0.4)),
+                        row_gap: Val::Vw(0.2),
+                        ..default()
+                    },
+                    BackgroundColor(card_color(status, fulfillment, &lookups.palette)),
+                )).with_children(|row| {
+                    row.spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Vw(0.6),
+                        ..default()
+                    }).with_children(|header| {
+                        header.spawn((
+                            Text::new(&desc.name),
+                            game_assets.text_font(14.0),
+                            ScalableText::from_vw(1.0),
+                            TextColor(Color::WHITE),
+                            Node { flex_shrink: 1.0, min_width: Val::Px(0.0), ..default() },
+                        ));
+                        if *status == ContractStatus::Pending {
+                            // same button as the sidebar's, so handle_contract_buttons takes it
+                            let accessible = lookups.sink_access(entity).is_none_or(|access| access.has_access());
+                            header.spawn((
+                                Node {
+                                    padding: UiRect::axes(Val::Vw(0.4), Val::Vw(0.1)),
+                                    ..default()
+                                },
+                                BackgroundColor(if accessible {
+                                    Color::srgb(0.2, 0.6, 0.2)
+                                } else {
+                                    Color::srgb(0.3, 0.3, 0.3)
+                                }),
+                                ContractAcceptButton,
+                                ContractEntityLink(entity),
+                                Interaction::None,
+                            )).with_children(|button| {
+                                button.spawn((
+                                    Text::new("Accept"),
+                                    game_assets.text_font(12.0),
+                                    ScalableText::from_vw(0.9),
+                                    TextColor(Color::WHITE),
+                                ));
+                            });
+                        }
+                    });
+                    row.spawn((
+                        card_field(CardField::Status, contract, lookups),
+                        game_assets.text_font(12.0),
+                        ScalableText::from_vw(0.9),
+                    ));
+                    if *status == ContractStatus::Active {
+                        row.spawn((
+                            Node {
+                                width: Val::Vw(13.5),
+                                height: Val::Vh(1.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.18, 0.18, 0.18)),
+                        )).with_children(|bar| {
+                            bar.spawn((
+                                Node {
+                                    width: card_bar_width(false, fulfillment),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                BackgroundColor(Color::srgb(0.3, 0.7, 0.3)),
+                                ContractCardBar { contract: entity, volume: false },
+                            ));
+                        });
+                    }
+                });
+            }
+        });
+}
+
+/// Left click on a sink (with nothing selected) opens its contracts; anywhere else closes it
+pub fn open_sink_panel_on_click(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    (grid, world_map): (Res<Grid>, Res<WorldMap>),
+    tiles: Query<&Tile>,
+    sinks: Query<&SinkContracts, With<SinkBuilding>>,
+    contract_query: Query<(Entity, &ContractStatus, &ContractDescription, &ContractFulfillment, &Dataset), With<Contract>>,
+    panels: Query<Entity, With<SinkContractsPanel>>,
+    selected_building_type: Res<crate::ui::shop::SelectedBuildingType>,
+    ui_blocker_query: Query<&Interaction, With<BlocksWorldClicks>>,
+    lookups: ContractCardLookups,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || selected_building_type.0.is_some() {
+        return;
+    }
+    if ui_blocker_query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed)
+    {
+        return;
+    }
+    // any click out in the world closes the current one
+    for panel in panels.iter() {
+        commands.entity(panel).despawn();
+    }
+
+    let (camera, camera_transform) = *camera;
+    let Some(world_pos) = window
+        .cursor_position()
+        .and_then(|p| camera.viewport_to_world_2d(camera_transform, p).ok())
+    else {
+        return;
+    };
+    let Some(entities) = world_map.get(&grid.world_to_grid(world_pos)) else { return };
+    let Some((sink, sink_contracts)) = entities
+        .iter()
+        .filter_map(|e| tiles.get(*e).ok())
+        .find_map(|tile| sinks.get(tile.0).ok().map(|c| (tile.0, c)))
+    else {
+        return;
+    };
+
+    let contracts: Vec<_> = sink_contracts
+        .contracts()
+        .iter()
+        .filter_map(|c| contract_query.get(*c).ok())
+        .filter(|(_, status, ..)| matches!(status, ContractStatus::Pending | ContractStatus::Active))
+        .collect();
+    spawn_sink_contracts_panel(&mut commands, &lookups, sink, &contracts);
+}
+
+/// Rebuilds the panel when one of its contracts is accepted, dropped or added
+pub fn refresh_sink_panel(
+    mut commands: Commands,
+    panels: Query<(Entity, &SinkContractsPanel)>,
+    sinks: Query<Ref<SinkContracts>, With<SinkBuilding>>,
+    changed_statuses: Query<(), Changed<ContractStatus>>,
+    contract_query: Query<(Entity, &ContractStatus, &ContractDescription, &ContractFulfillment, &Dataset), With<Contract>>,
+    lookups: ContractCardLookups,
+) {
+    for (panel_entity, panel) in panels.iter() {
+        // sink gone, panel goes with it
+        let Ok(sink_contracts) = sinks.get(panel.sink) else {
+            commands.entity(panel_entity).despawn();
+            continue;
+        };
+        let stale = sink_contracts.is_changed()
+            || sink_contracts.contracts().iter().any(|c| changed_statuses.contains(*c));
+        if !stale {
+            continue;
+        }
+        let contracts: Vec<_> = sink_contracts
+            .contracts()
+            .iter()
+            .filter_map(|c| contract_query.get(*c).ok())
+            .filter(|(_, status, ..)| matches!(status, ContractStatus::Pending | ContractStatus::Active))
+            .collect();
+        commands.entity(panel_entity).despawn();
+        spawn_sink_contracts_panel(&mut commands, &lookups, panel.sink, &contracts);
+    }
+}
+
+/// Keeps the panel pinned just above its sink as the camera pans and zooms
+pub fn follow_sink_panel(
+    camera: Single<(&Camera, &GlobalTransform)>,
+    grid: Res<Grid>,
+    sinks: Query<(&GridPosition, &SinkBuilding)>,
+    mut panels: Query<(&SinkContractsPanel, &mut Node, &mut Visibility, &ComputedNode)>,
+) {
+    let (camera, camera_transform) = *camera;
+    for (panel, mut node, mut visibility, computed) in panels.iter_mut() {
+        let Ok((position, sink)) = sinks.get(panel.sink) else { continue };
+        let low = grid.grid_to_world_corner(position);
+        let high = grid.grid_to_world_corner(&GridPosition(position.0 + sink.size));
+        let anchor = Vec2::new((low.x + high.x) / 2.0, low.y.max(high.y));
+        let Ok(screen) = camera.world_to_viewport(camera_transform, anchor.extend(0.0)) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        // centred on the sink, sitting on top of it
+        let size = computed.size() * computed.inverse_scale_factor();
+        node.left = Val::Px(screen.x - size.x / 2.0);
+        node.top = Val::Px(screen.y - size.y - 8.0);
+        *visibility = Visibility::Inherited;
+    }
+}
@@ -0,0 +1,475 @@
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use crate::assets::GameAssets;
+use crate::contracts::{ContractDescription, ContractStatus};
+use crate::economics::ContractEarnings;
+use crate::factions::{Faction, FactionReputations};
+use crate::factory::buildings::sink::SinkBuilding;
+use crate::factory::logical::{BasicDataType, SinkDeliveries};
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::widgets::Focusable;
+use crate::ui::{BlocksWorldClicks, BlocksWorldScroll};
+
+/// The throughput timer ticks once a second, take a sample every this many ticks
+pub const STATS_SAMPLE_EVERY_TICKS: u32 = 5;
+/// 10 minutes of samples
+pub const STATS_HISTORY_SAMPLES: usize = 120;
+const DOT_PX: f32 = 3.0;
+
+const DATA_TYPES: [BasicDataType; 4] = [
+    BasicDataType::Biometric,
+    BasicDataType::Economic,
+    BasicDataType::Behavioural,
+    BasicDataType::Telemetry,
+];
+const FACTIONS: [Faction; 4] = [Faction::Corporate, Faction::Academia, Faction::Government, Faction::Criminal];
+
+/// Which of the four graphs a series is drawn on
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatGraph {
+    Throughput,
+    Income,
+    Money,
+    Reputation,
+}
+
+impl StatGraph {
+    const ALL: [StatGraph; 4] = [StatGraph::Throughput, StatGraph::Income, StatGraph::Money, StatGraph::Reputation];
+
+    fn title(&self) -> &'static str {
+        match self {
+            StatGraph::Throughput => "Delivered throughput /s",
+            StatGraph::Income => "Contract income /s",
+            StatGraph::Money => "Money",
+            StatGraph::Reputation => "Reputation",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatSeries {
+    Throughput(BasicDataType),
+    Income,
+    Money,
+    Reputation(Faction),
+}
+
+impl StatSeries {
+    fn all() -> Vec<StatSeries> {
+        let mut all: Vec<StatSeries> = DATA_TYPES.iter().map(|t| StatSeries::Throughput(*t)).collect();
+        all.push(StatSeries::Income);
+        all.push(StatSeries::Money);
+        all.extend(FACTIONS.iter().map(|f| StatSeries::Reputation(*f)));
+        all
+    }
+
+    fn graph(&self) -> StatGraph {
+        match self {
+            StatSeries::Throughput(_) => StatGraph::Throughput,
+            StatSeries::Income => StatGraph::Income,
+            StatSeries::Money => StatGraph::Money,
+            StatSeries::Reputation(_) => StatGraph::Reputation,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            StatSeries::Throughput(data_type) => format!("{:?}", data_type),
+            StatSeries::Income => "Income".to_string(),
+            StatSeries::Money => "Money".to_string(),
+            StatSeries::Reputation(faction) => format!("{:?}", faction),
+        }
+    }
+
+    fn color(&self, game_assets: &GameAssets) -> Color {
+        match self {
+            StatSeries::Throughput(data_type) => crate::factory::source_visuals::data_type_color(*data_type),
+            StatSeries::Income => Color::srgb(0.4, 0.9, 0.4),
+            StatSeries::Money => Color::srgb(1.0, 0.85, 0.2),
+            StatSeries::Reputation(faction) => game_assets.faction_color(*faction),
+        }
+    }
+}
+
+/// Ring buffers of the last 10 minutes, sampled off the throughput timer so pausing stops it too
+#[derive(Resource, Default)]
+pub struct StatsHistory {
+    pub series: HashMap<StatSeries, VecDeque<f32>>,
+    /// Series toggled off in the panel
+    pub hidden: HashSet<StatSeries>,
+    ticks: u32,
+    last_money_earned: i64,
+}
+
+impl StatsHistory {
+    fn push(&mut self, series: StatSeries, value: f32) {
+        let samples = self.series.entry(series).or_default();
+        samples.push_back(value);
+        while samples.len() > STATS_HISTORY_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    /// Min and max over the shown series of a graph, widened a bit when flat so the line sits in the middle
+    fn range(&self, graph: StatGraph) -> Option<(f32, f32)> {
+        let values: Vec<f32> = self
+            .series
+            .iter()
+            .filter(|(series, _)| series.graph() == graph && !self.hidden.contains(*series))
+            .flat_map(|(_, samples)| samples.iter().copied())
+            .collect();
+        let min = values.iter().copied().reduce(f32::min)?;
+        let max = values.iter().copied().reduce(f32::max)?;
+        if max - min < f32::EPSILON {
+            Some((min - 1.0, max + 1.0))
+        } else {
+            Some((min, max))
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct StatsPanel;
+
+/// Which difficulty the run's on, under the title
+#[derive(Component)]
+pub struct StatsDifficultyText;
+
+/// Best and worst earner among the accepted contracts, under the difficulty
+#[derive(Component)]
+pub struct StatsContractsText;
+
+#[derive(Component)]
+pub struct StatsPlot(pub StatGraph);
+
+#[derive(Component)]
+pub struct StatsMaxLabel(pub StatGraph);
+
+#[derive(Component)]
+pub struct StatsMinLabel(pub StatGraph);
+
+#[derive(Component)]
+pub struct StatsSeriesToggle(pub StatSeries);
+
+/// Chained after `aggregate_sink_deliveries`, reads what it already summed instead of walking the links again
+pub fn sample_stats(
+    mut history: ResMut<StatsHistory>,
+    sinks: Query<&SinkDeliveries, With<SinkBuilding>>,
+    player: Res<crate::player::Player>,
+    reputations: Res<FactionReputations>,
+    counters: Res<crate::achievements::RunCounters>,
+) {
+    history.ticks += 1;
+    if history.ticks < STATS_SAMPLE_EVERY_TICKS {
+        return;
+    }
+    history.ticks = 0;
+
+    let mut per_type: HashMap<BasicDataType, f32> = HashMap::new();
+    for deliveries in sinks.iter() {
+        for (data_type, (_, rate)) in deliveries.types.iter() {
+            *per_type.entry(*data_type).or_insert(0.0) += rate;
+        }
+    }
+    for data_type in DATA_TYPES {
+        history.push(StatSeries::Throughput(data_type), per_type.get(&data_type).copied().unwrap_or(0.0));
+    }
+
+    let earned = counters.money_earned - history.last_money_earned;
+    history.last_money_earned = counters.money_earned;
+    history.push(StatSeries::Income, earned as f32 / STATS_SAMPLE_EVERY_TICKS as f32);
+    history.push(StatSeries::Money, player.money as f32);
+    for faction in FACTIONS {
+        history.push(StatSeries::Reputation(faction), reputations.get(faction) as f32);
+    }
+}
+
+pub fn spawn_stats_panel(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Vw(1.5)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.8),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.03, 0.03, 0.05, 0.94)),
+            GlobalZIndex(850),
+            StatsPanel,
+            BlocksWorldClicks,
+            BlocksWorldScroll,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new("Statistics (Tab to close)"),
+                game_assets.text_font(20.0),
+                ScalableText::from_vw(1.4),
+                TextColor(Color::WHITE),
+            ));
+            panel.spawn((
+                Text::new(""),
+                game_assets.text_font(14.0),
+                ScalableText::from_vw(0.9),
+                TextColor(Color::srgb(0.7, 0.7, 0.75)),
+                StatsDifficultyText,
+            ));
+            panel.spawn((
+                Text::new(""),
+                game_assets.text_font(14.0),
+                ScalableText::from_vw(0.9),
+                TextColor(Color::srgb(0.7, 0.7, 0.75)),
+                StatsContractsText,
+            ));
+
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Vw(0.4),
+                    row_gap: Val::Vw(0.3),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    for series in StatSeries::all() {
+                        bar.spawn((
+                            Node {
+                                padding: UiRect::axes(Val::Vw(0.5), Val::Vw(0.2)),
+                                border: UiRect::bottom(Val::Px(3.0)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.22, 0.22, 0.25)),
+                            BorderColor::all(series.color(&game_assets)),
+                            Interaction::None,
+                            StatsSeriesToggle(series),
+                        ))
+                        .with_children(|button| {
+                            button.spawn((
+                                Text::new(series.label()),
+                                game_assets.text_font(13.0),
+                                ScalableText::from_vw(0.85),
+                                TextColor(Color::WHITE),
+                            ));
+                        });
+                    }
+                });
+
+            // 2x2 grid of graphs
+            panel
+                .spawn(Node {
+                    flex_grow: 1.0,
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::flex(2, 1.0),
+                    grid_template_rows: RepeatedGridTrack::flex(2, 1.0),
+                    column_gap: Val::Vw(1.2),
+                    row_gap: Val::Vw(1.2),
+                    ..default()
+                })
+                .with_children(|grid| {
+                    for graph in StatGraph::ALL {
+                        spawn_graph(grid, &game_assets, graph);
+                    }
+                });
+        });
+}
+
+fn spawn_graph(grid: &mut ChildSpawnerCommands, game_assets: &GameAssets, graph: StatGraph) {
+    grid.spawn(Node {
+        flex_direction: FlexDirection::Column,
+        row_gap: Val::Vw(0.3),
+        ..default()
+    })
+    .with_children(|column| {
+        column.spawn((
+            Text::new(graph.title()),
+            game_assets.text_font(15.0),
+            ScalableText::from_vw(1.0),
+            TextColor(Color::srgb(0.85, 0.85, 0.85)),
+        ));
+        column
+            .spawn(Node {
+                flex_grow: 1.0,
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Vw(0.4),
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::SpaceBetween,
+                    min_width: Val::Vw(3.5),
+                    ..default()
+                })
+                .with_children(|labels| {
+                    for (text, marker_is_max) in [("-", true), ("-", false)] {
+                        let mut label = labels.spawn((
+                            Text::new(text),
+                            game_assets.text_font(12.0),
+                            ScalableText::from_vw(0.75),
+                            TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                        ));
+                        if marker_is_max {
+                            label.insert(StatsMaxLabel(graph));
+                        } else {
+                            label.insert(StatsMinLabel(graph));
+                        }
+                    }
+                });
+                row.spawn((
+                    Node {
+                        flex_grow: 1.0,
+                        height: Val::Percent(100.0),
+                        border: UiRect::new(Val::Px(1.0), Val::ZERO, Val::ZERO, Val::Px(1.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.03)),
+                    BorderColor::all(Color::srgb(0.35, 0.35, 0.4)),
+                    StatsPlot(graph),
+                ));
+            });
+    });
+}
+
+/// Tab opens and closes the panel, unless Tab is busy moving focus around a menu
+pub fn toggle_stats_panel(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    focusable: Query<&ComputedNode, With<Focusable>>,
+    mut panel: Single<&mut Node, With<StatsPanel>>,
+    mut history: ResMut<StatsHistory>,
+) {
+    if !keyboard.just_pressed(KeyCode::Tab) || focusable.iter().any(|node| node.size() != Vec2::ZERO) {
+        return;
+    }
+    panel.display = match panel.display {
+        Display::None => {
+            // redraw with whatever came in while it was closed
+            history.set_changed();
+            Display::Flex
+        }
+        _ => Display::None,
+    };
+}
+
+/// Set once the run starts, and again if a load brings a different one
+pub fn update_stats_difficulty(
+    difficulty: Res<crate::difficulty::Difficulty>,
+    mut text: Single<&mut Text, With<StatsDifficultyText>>,
+) {
+    text.0 = format!("Difficulty: {}", difficulty.name());
+}
+
+/// Refreshed with the graphs, from what each accepted contract has been credited so far
+pub fn update_stats_contracts(
+    history: Res<StatsHistory>,
+    panel: Single<&Node, With<StatsPanel>>,
+    contracts: Query<(&ContractDescription, &ContractStatus, &ContractEarnings)>,
+    mut text: Single<&mut Text, With<StatsContractsText>>,
+) {
+    if panel.display == Display::None || !history.is_changed() {
+        return;
+    }
+    let accepted: Vec<_> = contracts
+        .iter()
+        .filter(|(_, status, _)| matches!(status, ContractStatus::Active | ContractStatus::Completed | ContractStatus::Failed))
+        .map(|(description, _, earnings)| (description.name.as_str(), earnings.0))
+        .collect();
+    let most = accepted.iter().max_by(|a, b| a.1.total_cmp(&b.1));
+    let least = accepted.iter().min_by(|a, b| a.1.total_cmp(&b.1));
+    text.0 = match (most, least) {
+        (Some((most_name, most_earned)), Some((least_name, least_earned))) => format!(
+            "Most profitable contract: {} (${:.0})\nLeast profitable contract: {} (${:.0})",
+            most_name, most_earned, least_name, least_earned
+        ),
+        _ => "No contracts accepted yet".to_string(),
+    };
+}
+
+pub fn handle_stats_toggles(
+    toggles: Query<(&Interaction, &StatsSeriesToggle), Changed<Interaction>>,
+    mut history: ResMut<StatsHistory>,
+) {
+    for (interaction, toggle) in toggles.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if !history.hidden.remove(&toggle.0) {
+            history.hidden.insert(toggle.0);
+        }
+    }
+}
+
+fn format_stat(graph: StatGraph, value: f32) -> String {
+    match graph {
+        StatGraph::Money | StatGraph::Income => format!("${:.0}", value),
+        StatGraph::Throughput => format!("{:.1}", value),
+        StatGraph::Reputation => format!("{:.0}", value),
+    }
+}
+
+/// Rebuilds the dots of every graph, one per sample with the newest on the right edge
+pub fn redraw_stats_graphs(
+    mut commands: Commands,
+    history: Res<StatsHistory>,
+    panel: Single<&Node, With<StatsPanel>>,
+    plots: Query<(Entity, &StatsPlot)>,
+    mut max_labels: Query<(&mut Text, &StatsMaxLabel), Without<StatsMinLabel>>,
+    mut min_labels: Query<(&mut Text, &StatsMinLabel), Without<StatsMaxLabel>>,
+    mut toggles: Query<(&StatsSeriesToggle, &mut BackgroundColor)>,
+    game_assets: Res<GameAssets>,
+) {
+    if panel.display == Display::None || !history.is_changed() {
+        return;
+    }
+
+    for (toggle, mut background) in toggles.iter_mut() {
+        background.0 = if history.hidden.contains(&toggle.0) {
+            Color::srgb(0.1, 0.1, 0.12)
+        } else {
+            Color::srgb(0.22, 0.22, 0.25)
+        };
+    }
+
+    for (entity, plot) in plots.iter() {
+        let range = history.range(plot.0);
+        for (mut text, label) in max_labels.iter_mut().filter(|(_, l)| l.0 == plot.0) {
+            **text = range.map_or("-".to_string(), |(_, max)| format_stat(label.0, max));
+        }
+        for (mut text, label) in min_labels.iter_mut().filter(|(_, l)| l.0 == plot.0) {
+            **text = range.map_or("-".to_string(), |(min, _)| format_stat(label.0, min));
+        }
+
+        commands.entity(entity).despawn_children();
+        let Some((min, max)) = range else { continue };
+        commands.entity(entity).with_children(|plot_area| {
+            for (series, samples) in history.series.iter() {
+                if series.graph() != plot.0 || history.hidden.contains(series) {
+                    continue;
+                }
+                let color = series.color(&game_assets);
+                // pad on the left until the buffer fills up
+                let offset = STATS_HISTORY_SAMPLES - samples.len();
+                for (i, value) in samples.iter().enumerate() {
+                    let x = (offset + i) as f32 / (STATS_HISTORY_SAMPLES - 1) as f32 * 100.0;
+                    let y = (1.0 - (value - min) / (max - min)) * 100.0;
+                    plot_area.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent(x),
+                            top: Val::Percent(y),
+                            width: Val::Px(DOT_PX),
+                            height: Val::Px(DOT_PX),
+                            margin: UiRect::all(Val::Px(-DOT_PX / 2.0)),
+                            ..default()
+                        },
+                        BackgroundColor(color),
+                        Pickable::IGNORE,
+                    ));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::ui::interactive_event::ScalableText;
+
+const TOAST_SECONDS: f32 = 4.0;
+const TOAST_FADE_SECONDS: f32 = 0.5;
+
+/// A banner at the top of the screen that fades out on its own
+#[derive(Component)]
+pub struct Toast {
+    pub remaining: f32,
+}
+
+/// Shows a banner with a title and a smaller line underneath. Newer toasts stack below older ones.
+pub fn spawn_toast(commands: &mut Commands, game_assets: &GameAssets, title: &str, body: &str, accent: Color) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(110.0),
+                left: Val::Percent(35.0),
+                width: Val::Percent(30.0),
+                padding: UiRect::all(Val::Vw(0.6)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.92)),
+            BorderColor::all(accent),
+            GlobalZIndex(800),
+            Toast { remaining: TOAST_SECONDS },
+        ))
+        .with_children(|toast| {
+            toast.spawn((
+                Text::new(title),
+                game_assets.text_font(20.0),
+                ScalableText::from_vw(1.4),
+                TextColor(accent),
+            ));
+            toast.spawn((
+                Text::new(body),
+                game_assets.text_font(14.0),
+                ScalableText::from_vw(0.9),
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+pub fn update_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(Entity, &mut Toast, &mut Node, &mut BackgroundColor)>,
+) {
+    let mut index = 0;
+    for (entity, mut toast, mut node, mut background) in toasts.iter_mut() {
+        toast.remaining -= time.delta_secs();
+        if toast.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        node.top = Val::Px(110.0 + index as f32 * 70.0);
+        index += 1;
+        let alpha = (toast.remaining / TOAST_FADE_SECONDS).min(1.0);
+        background.0.set_alpha(0.92 * alpha);
+    }
+}
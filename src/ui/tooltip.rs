@@ -1,18 +1,35 @@
-use crate::assets::GameAssets;
-use crate::factory::buildings::TileThroughputData;
-use crate::factory::logical::calculate_throughput;
+use crate::assets::{GameAssets, IconSize};
+use crate::contracts::{ContractDescription, ContractStatus, SinkContracts};
+use crate::factory::building_visuals::z_layers;
+use crate::factory::buildings::aggregator::{AggregationActive, Aggregator, PASSTHROUGH_RATE};
+use crate::factory::buildings::filter_splitter::FilterSplitter;
+use crate::factory::buildings::sink::{PortAccess, SinkAccess, SinkBuilding};
+use crate::factory::buildings::{TileThroughputData, Tiles};
+use crate::factory::buildings::source::SourceBuilding;
+use crate::factory::logical::{
+    calculate_throughput, BasicDataType, DataAttribute, Dataset, LogicalLink, PortOutputs, SinkDeliveries, SourceAllocation,
+};
+use crate::factory::source_visuals::SourceBackground;
+use crate::grid::Grid;
+use crate::palette::Palette;
+use crate::ui::interactive_event::ScalableText;
 use crate::LinkedSpawn;
 use bevy::app::{App, Plugin, Update};
-use bevy::color::Color;
-use bevy::math::Vec3;
+use bevy::color::{Alpha, Color};
+use bevy::math::{Vec2, Vec3};
 use bevy::picking::Pickable;
 use bevy::prelude::{
-    default, Commands, Component, Deref, DetectChanges, Entity, GlobalTransform, IntoScheduleConfigs,
-    On, Out, Over, Pointer, Query, Ref, TextFont, Transform, Visibility,
+    default, info, Added, ButtonInput, Camera, Changed, ChildSpawnerCommands, Children, Commands, Component, Deref,
+    DetectChanges, Entity, GlobalTransform, IntoScheduleConfigs, KeyCode, On, Out, Over, Pointer, Projection, Query, Ref,
+    Res, ResMut, Resource, TextFont, TextureAtlas, Transform, Visibility, With, Without,
 };
-use bevy::sprite::Text2d;
+use bevy::sprite::{Sprite, Text2d};
 use bevy::text::TextColor;
 
+/// Hover text sizes in vw, so the text size preference reaches them too
+const THROUGHPUT_TEXT_VW: f32 = 2.1;
+const TOOLTIP_TEXT_VW: f32 = 1.15;
+
 #[derive(Component, Deref)]
 pub struct ToggleOnHover(pub Vec<Entity>);
 #[derive(Component)]
@@ -43,6 +60,20 @@ impl Plugin for TooltipPlugin {
         );
 
         app.add_systems(Update, update_tooltip.after(calculate_throughput));
+        app.init_resource::<VerboseSinkBadges>();
+        // badge attach after the breakdown's so both land in LinkedSpawn
+        app.add_systems(Update, (
+            attach_sink_breakdown,
+            attach_sink_badge,
+            update_sink_breakdowns,
+            toggle_verbose_sink_badges,
+            update_sink_badges,
+            fade_sink_badges,
+        ).chain());
+        app.add_systems(Update, (attach_filter_tooltip, update_filter_tooltips).chain());
+        app.add_systems(Update, (attach_aggregator_tooltip, update_aggregator_tooltips).chain());
+        app.add_systems(Update, (attach_source_allocation_tooltip, update_source_allocation_tooltips).chain());
+        app.add_systems(Update, (attach_port_outputs_tooltip, update_port_outputs_tooltips).chain());
     }
 }
 
@@ -77,6 +108,7 @@ pub fn attach_tooltip(commands: &mut Commands, id: Entity) {
                 Transform::from_translation(Vec3::new(-64., 0., 0.)),
                 Text2d::default(),
                 text_font.clone(),
+                ScalableText::from_vw(THROUGHPUT_TEXT_VW),
                 TextColor(Color::linear_rgba(0., 1.0, 0., 1.0)),
             ))
             .id();
@@ -86,6 +118,7 @@ pub fn attach_tooltip(commands: &mut Commands, id: Entity) {
                 Transform::from_translation(Vec3::new(64., 0., 0.)),
                 Text2d::default(),
                 text_font,
+                ScalableText::from_vw(THROUGHPUT_TEXT_VW),
                 TextColor(Color::linear_rgba(1.0, 0.0, 0., 1.0)),
             ))
             .id();
@@ -118,3 +151,764 @@ pub fn inherit_translation(
         }
     }
 }
+
+const BREAKDOWN_LINE_HEIGHT: f32 = 30.0;
+const BREAKDOWN_WIDTH: f32 = 440.0;
+const MISSING_COLOR: Color = Color::srgb(1.0, 0.3, 0.3);
+/// Side of the access schematic in the panel's corner, and its edge thickness
+const SCHEMATIC_SIZE: f32 = 22.0;
+const SCHEMATIC_EDGE: f32 = 4.0;
+
+/// On a sink building, points at the hover panel listing what's arriving
+#[derive(Component)]
+pub struct SinkBreakdownTooltip(pub Entity);
+
+/// Gives each new sink a hidden breakdown panel above it, shown while any of its tiles is hovered
+pub fn attach_sink_breakdown(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    sinks: Query<(Entity, &SinkBuilding, &Tiles, Option<&LinkedSpawn>), Added<SinkBuilding>>,
+) {
+    for (entity, sink, tiles, linked) in sinks.iter() {
+        let height = sink.size.y.max(1) as f32 * grid.scale;
+        let panel = commands
+            .spawn((
+                Visibility::Hidden,
+                Transform::from_xyz(0.0, height, z_layers::LOCK_ICON + 5.0),
+            ))
+            .id();
+        let anchor = commands.spawn(InheritTranslation(entity)).add_child(panel).id();
+        for tile in tiles.iter() {
+            commands.entity(*tile).insert((Pickable::default(), ToggleOnHover(vec![panel])));
+        }
+        // keep the throughput tooltip's anchor linked too
+        let mut linked = linked.map(|l| l.to_vec()).unwrap_or_default();
+        linked.push(anchor);
+        commands
+            .entity(entity)
+            .insert((SinkBreakdownTooltip(panel), LinkedSpawn(linked)));
+    }
+}
+
+fn attribute_names(attributes: &bevy::platform::collections::HashSet<DataAttribute>) -> String {
+    if attributes.is_empty() {
+        return "raw".to_string();
+    }
+    let mut attributes: Vec<_> = attributes.iter().collect();
+    attributes.sort();
+    attributes.iter().map(|a| format!("{:?}", a)).collect::<Vec<_>>().join(", ")
+}
+
+/// One line of the panel, with the data type's icon in front when there is one
+struct BreakdownLine {
+    icon: Option<BasicDataType>,
+    text: String,
+    color: Color,
+}
+
+/// Rebuilds the panel contents whenever the sink's deliveries are refreshed
+pub fn update_sink_breakdowns(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    palette: Res<Palette>,
+    sinks: Query<(
+        &SinkBreakdownTooltip,
+        Ref<SinkDeliveries>,
+        &SinkContracts,
+        &crate::factory::buildings::sink::SinkCapacity,
+        Option<Ref<SinkAccess>>,
+    )>,
+    contracts: Query<(&Dataset, &ContractStatus, &ContractDescription)>,
+) {
+    for (tooltip, deliveries, sink_contracts, capacity, access) in sinks.iter() {
+        let access_changed = access.as_ref().is_some_and(|a| a.is_changed());
+        if !deliveries.is_changed() && !access_changed {
+            continue;
+        }
+        let mut lines = vec![BreakdownLine {
+            icon: None,
+            text: format!("Arriving {:.1}/s", deliveries.total()),
+            color: Color::WHITE,
+        }];
+        lines.push(if capacity.capped {
+            BreakdownLine {
+                icon: None,
+                text: format!("Capped at {:.1}/s, the rest backs up", capacity.per_sec),
+                color: Color::srgb(1.0, 0.6, 0.2),
+            }
+        } else {
+            BreakdownLine {
+                icon: None,
+                text: format!("Takes up to {:.1}/s", capacity.per_sec),
+                color: Color::srgb(0.7, 0.7, 0.7),
+            }
+        });
+        if access.as_ref().is_some_and(|a| !a.has_access()) {
+            lines.push(BreakdownLine {
+                icon: None,
+                text: "No access, every side is locked or off the map".to_string(),
+                color: MISSING_COLOR,
+            });
+        }
+        for (data_type, (attributes, rate)) in deliveries.types.iter() {
+            lines.push(BreakdownLine {
+                icon: Some(*data_type),
+                text: format!("{:?} ({}) {:.1}/s", data_type, attribute_names(attributes), rate),
+                color: Color::srgb(0.85, 0.85, 0.85),
+            });
+        }
+
+        for contract in sink_contracts.contracts() {
+            let Ok((dataset, status, description)) = contracts.get(*contract) else { continue };
+            if !matches!(status, ContractStatus::Active | ContractStatus::Pending) {
+                continue;
+            }
+            let contributions = deliveries.contributions_for(dataset);
+            let counted: f64 = contributions.iter().map(|c| c.counted).sum();
+            let exact = contributions.iter().all(|c| c.arriving > 0.0 && c.missing.is_empty());
+            lines.push(BreakdownLine {
+                icon: None,
+                text: format!("{}: needs {}, {:.1}/s counted", description.name, dataset, counted),
+                color: if counted > 0.0 && exact {
+                    Color::srgb(0.4, 0.9, 0.4)
+                } else {
+                    Color::srgb(0.95, 0.85, 0.25)
+                },
+            });
+            for contribution in contributions {
+                let data_type = contribution.data_type;
+                if contribution.arriving <= 0.0 {
+                    lines.push(BreakdownLine {
+                        icon: Some(data_type),
+                        text: format!("  {:?} ({}) not arriving", data_type, attribute_names(&dataset.contents[&data_type])),
+                        color: MISSING_COLOR,
+                    });
+                } else if !contribution.missing.is_empty() {
+                    lines.push(BreakdownLine {
+                        icon: Some(data_type),
+                        text: format!(
+                            "  {:?} missing {}, {:.1} of {:.1}/s counted",
+                            data_type,
+                            attribute_names(&contribution.missing),
+                            contribution.counted,
+                            contribution.arriving
+                        ),
+                        color: Color::srgb(1.0, 0.6, 0.2),
+                    });
+                }
+            }
+        }
+
+        let text_font = game_assets.text_font(22.0);
+        let height = lines.len() as f32 * BREAKDOWN_LINE_HEIGHT;
+        commands.entity(tooltip.0).despawn_children().with_children(|panel| {
+            panel.spawn((
+                Sprite {
+                    color: Color::srgba(0.0, 0.0, 0.0, 0.8),
+                    custom_size: Some(Vec2::new(BREAKDOWN_WIDTH, height + 12.0)),
+                    ..default()
+                },
+                Transform::from_xyz(0.0, height / 2.0, 0.0),
+            ));
+            if let Some(access) = &access {
+                let corner = Vec2::new(
+                    BREAKDOWN_WIDTH / 2.0 - BREAKDOWN_LINE_HEIGHT / 2.0,
+                    height - BREAKDOWN_LINE_HEIGHT / 2.0,
+                );
+                spawn_sink_access_sprites(panel, access, &palette, corner);
+            }
+            for (i, line) in lines.iter().enumerate() {
+                let y = height - (i as f32 + 0.5) * BREAKDOWN_LINE_HEIGHT;
+                if let Some((atlas_id, index)) = line.icon.and_then(|t| game_assets.data_type_icon(t, IconSize::Small)) {
+                    let (image, layout) = game_assets.get_atlas(atlas_id);
+                    panel.spawn((
+                        Sprite {
+                            image,
+                            texture_atlas: Some(TextureAtlas { layout, index }),
+                            custom_size: Some(Vec2::splat(BREAKDOWN_LINE_HEIGHT - 6.0)),
+                            ..default()
+                        },
+                        Transform::from_xyz(-BREAKDOWN_WIDTH / 2.0 + BREAKDOWN_LINE_HEIGHT / 2.0, y, 0.1),
+                    ));
+                }
+                panel.spawn((
+                    Text2d(line.text.clone()),
+                    text_font.clone(),
+                    ScalableText::from_vw(TOOLTIP_TEXT_VW),
+                    TextColor(line.color),
+                    Transform::from_xyz(BREAKDOWN_LINE_HEIGHT / 2.0, y, 0.1),
+                ));
+            }
+        });
+    }
+}
+
+/// World-space twin of the contract card's access schematic: a dark square with each edge
+/// coloured by whether that side of the sink can be wired into
+fn spawn_sink_access_sprites(
+    panel: &mut ChildSpawnerCommands,
+    access: &SinkAccess,
+    palette: &Palette,
+    center: Vec2,
+) {
+    let color = |side: PortAccess| match side {
+        PortAccess::Open => palette.success,
+        PortAccess::Occupied => palette.warning,
+        PortAccess::Blocked => palette.danger,
+    };
+    panel.spawn((
+        Sprite {
+            color: Color::srgb(0.12, 0.12, 0.12),
+            custom_size: Some(Vec2::splat(SCHEMATIC_SIZE)),
+            ..default()
+        },
+        Transform::from_translation(center.extend(0.1)),
+    ));
+    let offset = (SCHEMATIC_SIZE - SCHEMATIC_EDGE) / 2.0;
+    let horizontal = Vec2::new(SCHEMATIC_SIZE, SCHEMATIC_EDGE);
+    let vertical = Vec2::new(SCHEMATIC_EDGE, SCHEMATIC_SIZE);
+    for (side, size, at) in [
+        (access.up, horizontal, Vec2::new(0.0, offset)),
+        (access.down, horizontal, Vec2::new(0.0, -offset)),
+        (access.left, vertical, Vec2::new(-offset, 0.0)),
+        (access.right, vertical, Vec2::new(offset, 0.0)),
+    ] {
+        panel.spawn((
+            Sprite { color: color(side), custom_size: Some(size), ..default() },
+            Transform::from_translation((center + at).extend(0.2)),
+        ));
+    }
+}
+
+/// On a filter splitter, points at the hover text listing its port assignments
+#[derive(Component)]
+pub struct FilterAssignmentsTooltip(pub Entity);
+
+pub fn attach_filter_tooltip(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    game_assets: Res<GameAssets>,
+    splitters: Query<(Entity, &Tiles, Option<&LinkedSpawn>), Added<FilterSplitter>>,
+) {
+    for (entity, tiles, linked) in splitters.iter() {
+        let text = commands
+            .spawn((
+                Visibility::Hidden,
+                Text2d::default(),
+                game_assets.text_font(22.0),
+                ScalableText::from_vw(TOOLTIP_TEXT_VW),
+                TextColor(Color::WHITE),
+                Transform::from_xyz(0.0, grid.scale, z_layers::LOCK_ICON + 5.0),
+            ))
+            .id();
+        let anchor = commands.spawn(InheritTranslation(entity)).add_child(text).id();
+        for tile in tiles.iter() {
+            commands.entity(*tile).insert((Pickable::default(), ToggleOnHover(vec![text])));
+        }
+        let mut linked = linked.map(|l| l.to_vec()).unwrap_or_default();
+        linked.push(anchor);
+        commands
+            .entity(entity)
+            .insert((FilterAssignmentsTooltip(text), LinkedSpawn(linked)));
+    }
+}
+
+pub fn filter_assignments_text(splitter: &FilterSplitter) -> String {
+    let mut lines: Vec<String> = (0..splitter.overflow_port())
+        .map(|port| match splitter.filter(port) {
+            Some(data_type) => format!("Port {}: {:?}", port + 1, data_type),
+            None => format!("Port {}: unassigned", port + 1),
+        })
+        .collect();
+    lines.push(format!("Port {}: overflow", splitter.overflow_port() + 1));
+    lines.join("\n")
+}
+
+pub fn update_filter_tooltips(
+    mut commands: Commands,
+    splitters: Query<(&FilterSplitter, &FilterAssignmentsTooltip), Changed<FilterSplitter>>,
+) {
+    for (splitter, tooltip) in splitters.iter() {
+        commands.entity(tooltip.0).insert(Text2d(filter_assignments_text(splitter)));
+    }
+}
+
+/// On an aggregator, points at the hover text saying whether it's actually aggregating
+#[derive(Component)]
+pub struct AggregatorStatusTooltip(pub Entity);
+
+pub fn attach_aggregator_tooltip(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    game_assets: Res<GameAssets>,
+    aggregators: Query<(Entity, &Aggregator, &Tiles, Option<&LinkedSpawn>), Added<Aggregator>>,
+) {
+    for (entity, aggregator, tiles, linked) in aggregators.iter() {
+        let text = commands
+            .spawn((
+                Visibility::Hidden,
+                Text2d(aggregator_status_text(aggregator, None)),
+                game_assets.text_font(22.0),
+                ScalableText::from_vw(TOOLTIP_TEXT_VW),
+                TextColor(Color::WHITE),
+                Transform::from_xyz(0.0, grid.scale, z_layers::LOCK_ICON + 5.0),
+            ))
+            .id();
+        let anchor = commands.spawn(InheritTranslation(entity)).add_child(text).id();
+        for tile in tiles.iter() {
+            commands.entity(*tile).insert((Pickable::default(), ToggleOnHover(vec![text])));
+        }
+        let mut linked = linked.map(|l| l.to_vec()).unwrap_or_default();
+        linked.push(anchor);
+        commands
+            .entity(entity)
+            .insert((AggregatorStatusTooltip(text), LinkedSpawn(linked)));
+    }
+}
+
+pub fn aggregator_status_text(aggregator: &Aggregator, active: Option<&AggregationActive>) -> String {
+    match active {
+        Some(AggregationActive(true)) => "Aggregating".to_string(),
+        Some(AggregationActive(false)) => format!(
+            "Needs {}+ data types mixed in\nPassing through at {:.0}%",
+            aggregator.min_types,
+            PASSTHROUGH_RATE * 100.0
+        ),
+        None => "No input".to_string(),
+    }
+}
+
+pub fn update_aggregator_tooltips(
+    mut commands: Commands,
+    aggregators: Query<(&Aggregator, &AggregationActive, &AggregatorStatusTooltip), Changed<AggregationActive>>,
+) {
+    for (aggregator, active, tooltip) in aggregators.iter() {
+        commands.entity(tooltip.0).insert(Text2d(aggregator_status_text(aggregator, Some(active))));
+    }
+}
+
+/// On a source building, points at the hover text saying how its output is split
+#[derive(Component)]
+pub struct SourceAllocationTooltip(pub Entity);
+
+/// The source's own tiles aren't drawn, so the text shows while its background is hovered
+pub fn attach_source_allocation_tooltip(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    game_assets: Res<GameAssets>,
+    sources: Query<(Entity, &SourceBuilding, Option<&LinkedSpawn>), Added<SourceAllocation>>,
+    backgrounds: Query<(Entity, &SourceBackground)>,
+) {
+    if sources.is_empty() {
+        return;
+    }
+    let backgrounds: bevy::platform::collections::HashMap<Entity, Entity> =
+        backgrounds.iter().map(|(background, of)| (of.0, background)).collect();
+    for (entity, source, linked) in sources.iter() {
+        let text = commands
+            .spawn((
+                Visibility::Hidden,
+                Text2d::default(),
+                game_assets.text_font(22.0),
+                ScalableText::from_vw(TOOLTIP_TEXT_VW),
+                TextColor(Color::WHITE),
+                Transform::from_xyz(0.0, source.size.y.max(1) as f32 * grid.scale, z_layers::LOCK_ICON + 5.0),
+            ))
+            .id();
+        let anchor = commands.spawn(InheritTranslation(entity)).add_child(text).id();
+        if let Some(background) = backgrounds.get(&entity) {
+            commands.entity(*background).insert((Pickable::default(), ToggleOnHover(vec![text])));
+        }
+        let mut linked = linked.map(|l| l.to_vec()).unwrap_or_default();
+        linked.push(anchor);
+        commands
+            .entity(entity)
+            .insert((SourceAllocationTooltip(text), LinkedSpawn(linked)));
+    }
+}
+
+/// One line per linked port: what it gets, what's at the other end wants and whether it's short
+pub fn source_allocation_text(source: &SourceBuilding, allocation: &SourceAllocation) -> String {
+    let count = allocation.consumers.len();
+    if count == 0 {
+        return format!("{:.1}/s, nothing linked", source.throughput);
+    }
+    let mut lines = vec![format!("{:.1}/s over {} line{}", source.throughput, count, if count == 1 { "" } else { "s" })];
+    for (i, share) in allocation.consumers.iter().enumerate() {
+        let wanted = if share.demand.is_finite() {
+            format!("{:.1}/s wanted", share.demand)
+        } else {
+            "takes what it gets".to_string()
+        };
+        lines.push(format!(
+            "{} {:?}: {:.1}/s, {}{}",
+            i + 1,
+            share.direction,
+            share.allocated,
+            wanted,
+            if share.starved() { " (starved)" } else { "" }
+        ));
+    }
+    lines.join("\n")
+}
+
+pub fn update_source_allocation_tooltips(
+    mut commands: Commands,
+    sources: Query<(&SourceBuilding, &SourceAllocation, &SourceAllocationTooltip), Changed<SourceAllocation>>,
+) {
+    for (source, allocation, tooltip) in sources.iter() {
+        commands.entity(tooltip.0).insert(Text2d(source_allocation_text(source, allocation)));
+    }
+}
+
+const PORT_PANEL_LINE_HEIGHT: f32 = 30.0;
+const PORT_PANEL_LABEL_WIDTH: f32 = 90.0;
+/// Room for one data type icon and its attribute letters
+const PORT_PANEL_ENTRY_WIDTH: f32 = 64.0;
+const PORT_PANEL_NOTE_WIDTH: f32 = 170.0;
+
+/// On a delinker, trunker or combiner, the hover panel with what goes in and what comes out
+/// of each port. Remembers what it last drew so it only rebuilds when that changes
+#[derive(Component)]
+pub struct PortOutputsTooltip {
+    pub panel: Entity,
+    shown: Option<(PortOutputs, Vec<bool>)>,
+}
+
+pub fn attach_port_outputs_tooltip(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    buildings: Query<(Entity, &Tiles, Option<&LinkedSpawn>), Added<PortOutputs>>,
+) {
+    for (entity, tiles, linked) in buildings.iter() {
+        let panel = commands
+            .spawn((
+                Visibility::Hidden,
+                Transform::from_xyz(0.0, grid.scale, z_layers::LOCK_ICON + 5.0),
+            ))
+            .id();
+        let anchor = commands.spawn(InheritTranslation(entity)).add_child(panel).id();
+        for tile in tiles.iter() {
+            commands.entity(*tile).insert((Pickable::default(), ToggleOnHover(vec![panel])));
+        }
+        let mut linked = linked.map(|l| l.to_vec()).unwrap_or_default();
+        linked.push(anchor);
+        commands
+            .entity(entity)
+            .insert((PortOutputsTooltip { panel, shown: None }, LinkedSpawn(linked)));
+    }
+}
+
+fn attribute_letters(attributes: &bevy::platform::collections::HashSet<DataAttribute>) -> String {
+    let mut attributes: Vec<_> = attributes.iter().collect();
+    attributes.sort();
+    attributes.iter().map(|a| a.to_shorthand()).collect()
+}
+
+/// One row of the port panel: which port, the dataset as icons, and a note after it
+struct PortLine {
+    label: String,
+    dataset: Option<Dataset>,
+    note: Option<&'static str>,
+    color: Color,
+}
+
+pub fn update_port_outputs_tooltips(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    mut buildings: Query<(&PortOutputs, &mut PortOutputsTooltip)>,
+    links: Query<&LogicalLink>,
+) {
+    let linked: bevy::platform::collections::HashSet<Entity> = links.iter().map(|link| link.source).collect();
+    for (port_outputs, mut tooltip) in buildings.iter_mut() {
+        let connected: Vec<bool> = port_outputs.outputs.iter().map(|(port, _)| linked.contains(port)).collect();
+        if tooltip
+            .shown
+            .as_ref()
+            .is_some_and(|(shown, shown_connected)| shown == port_outputs && *shown_connected == connected)
+        {
+            continue;
+        }
+        tooltip.shown = Some((port_outputs.clone(), connected.clone()));
+
+        let numbered = |prefix: &str, i: usize, count: usize| {
+            if count > 1 { format!("{} {}", prefix, i + 1) } else { prefix.to_string() }
+        };
+        let mut lines = Vec::new();
+        for (i, input) in port_outputs.inputs.iter().enumerate() {
+            lines.push(PortLine {
+                label: numbered("In", i, port_outputs.inputs.len()),
+                dataset: input.clone(),
+                note: input.is_none().then_some("(empty)"),
+                color: Color::srgb(0.85, 0.85, 0.85),
+            });
+        }
+        for (i, ((_, output), connected)) in port_outputs.outputs.iter().zip(&connected).enumerate() {
+            lines.push(PortLine {
+                label: numbered("Out", i, port_outputs.outputs.len()),
+                dataset: output.clone(),
+                note: if !connected {
+                    Some("(unconnected)")
+                } else if output.is_none() {
+                    Some("(nothing)")
+                } else {
+                    None
+                },
+                color: if *connected { Color::WHITE } else { Color::srgb(0.6, 0.6, 0.6) },
+            });
+        }
+
+        let most_types = lines
+            .iter()
+            .map(|line| line.dataset.as_ref().map_or(0, |d| d.contents.len()))
+            .max()
+            .unwrap_or(0);
+        let width = PORT_PANEL_LABEL_WIDTH + most_types as f32 * PORT_PANEL_ENTRY_WIDTH + PORT_PANEL_NOTE_WIDTH;
+        let rows = lines.len() + port_outputs.stalled.is_some() as usize;
+        let height = rows as f32 * PORT_PANEL_LINE_HEIGHT;
+        let left = -width / 2.0;
+        let text_font = game_assets.text_font(22.0);
+
+        commands.entity(tooltip.panel).despawn_children().with_children(|panel| {
+            panel.spawn((
+                Sprite {
+                    color: Color::srgba(0.0, 0.0, 0.0, 0.8),
+                    custom_size: Some(Vec2::new(width, height + 12.0)),
+                    ..default()
+                },
+                Transform::from_xyz(0.0, height / 2.0, 0.0),
+            ));
+            for (row, line) in lines.iter().enumerate() {
+                let y = height - (row as f32 + 0.5) * PORT_PANEL_LINE_HEIGHT;
+                panel.spawn((
+                    Text2d(line.label.clone()),
+                    text_font.clone(),
+                    TextColor(line.color),
+                    Transform::from_xyz(left + PORT_PANEL_LABEL_WIDTH / 2.0, y, 0.1),
+                ));
+                let mut x = left + PORT_PANEL_LABEL_WIDTH;
+                let mut entries: Vec<_> = line.dataset.iter().flat_map(|d| d.contents.iter()).collect();
+                entries.sort_by_key(|(data_type, _)| **data_type);
+                for (data_type, attributes) in entries {
+                    if let Some((atlas_id, index)) = game_assets.data_type_icon(*data_type, IconSize::Small) {
+                        let (image, layout) = game_assets.get_atlas(atlas_id);
+                        panel.spawn((
+                            Sprite {
+                                image,
+                                texture_atlas: Some(TextureAtlas { layout, index }),
+                                custom_size: Some(Vec2::splat(PORT_PANEL_LINE_HEIGHT - 6.0)),
+                                ..default()
+                            },
+                            Transform::from_xyz(x + PORT_PANEL_LINE_HEIGHT / 2.0, y, 0.1),
+                        ));
+                    }
+                    panel.spawn((
+                        Text2d(attribute_letters(attributes)),
+                        text_font.clone(),
+                        TextColor(line.color),
+                        Transform::from_xyz(x + PORT_PANEL_LINE_HEIGHT + 12.0, y, 0.1),
+                    ));
+                    x += PORT_PANEL_ENTRY_WIDTH;
+                }
+                if let Some(note) = line.note {
+                    panel.spawn((
+                        Text2d(note.to_string()),
+                        text_font.clone(),
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        Transform::from_xyz(x + PORT_PANEL_NOTE_WIDTH / 2.0, y, 0.1),
+                    ));
+                }
+            }
+            if let Some(stalled) = &port_outputs.stalled {
+                panel.spawn((
+                    Text2d(stalled.clone()),
+                    text_font.clone(),
+                    TextColor(Color::srgb(1.0, 0.6, 0.2)),
+                    Transform::from_xyz(0.0, PORT_PANEL_LINE_HEIGHT / 2.0, 0.1),
+                ));
+            }
+        });
+    }
+}
+
+/// Past this camera scale sink badges start fading, gone completely by `BADGE_HIDE_SCALE`
+const BADGE_FADE_SCALE: f32 = 2.5;
+const BADGE_HIDE_SCALE: f32 = 4.0;
+const BADGE_ICON_SIZE: f32 = 24.0;
+/// Badges scale with the camera up to this much so they stay readable zoomed out
+const BADGE_MAX_SCALE: f32 = 2.0;
+
+/// On a sink, the small always-on badge above it with arriving types and the total rate
+#[derive(Component)]
+pub struct SinkBadge {
+    pub panel: Entity,
+    /// Has an active contract or something arriving, otherwise it stays hidden
+    pub shown: bool,
+}
+
+/// Sprites and text in a sink badge, faded together with the zoom
+#[derive(Component)]
+pub struct SinkBadgePart {
+    alpha: f32,
+}
+
+/// Dev toggle (F8 with --dev) that swaps the badges for the full per-dataset numbers
+#[derive(Resource, Default)]
+pub struct VerboseSinkBadges(pub bool);
+
+pub fn attach_sink_badge(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    sinks: Query<(Entity, &SinkBuilding, Option<&LinkedSpawn>), Added<SinkBuilding>>,
+) {
+    for (entity, sink, linked) in sinks.iter() {
+        let height = sink.size.y.max(1) as f32 * grid.scale;
+        let panel = commands
+            .spawn((
+                Visibility::Hidden,
+                Transform::from_xyz(0.0, height + BADGE_ICON_SIZE, z_layers::LOCK_ICON + 4.0),
+            ))
+            .id();
+        let anchor = commands.spawn(InheritTranslation(entity)).add_child(panel).id();
+        let mut linked = linked.map(|l| l.to_vec()).unwrap_or_default();
+        linked.push(anchor);
+        commands
+            .entity(entity)
+            .insert((SinkBadge { panel, shown: false }, LinkedSpawn(linked)));
+    }
+}
+
+/// Rebuilds a badge when its sink's deliveries are refreshed, which only happens on the throughput tick
+pub fn update_sink_badges(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    verbose: Res<VerboseSinkBadges>,
+    mut sinks: Query<(&mut SinkBadge, Ref<SinkDeliveries>, Ref<SinkContracts>)>,
+    statuses: Query<&ContractStatus>,
+) {
+    for (mut badge, deliveries, sink_contracts) in sinks.iter_mut() {
+        if !deliveries.is_changed() && !sink_contracts.is_changed() && !verbose.is_changed() {
+            continue;
+        }
+        let active = sink_contracts
+            .contracts()
+            .iter()
+            .any(|c| statuses.get(*c).is_ok_and(|s| *s == ContractStatus::Active));
+        let total = deliveries.total();
+        badge.shown = active || total > 0.0;
+
+        let text_font = game_assets.text_font(22.0);
+        commands.entity(badge.panel).despawn_children().with_children(|panel| {
+            if verbose.0 {
+                // the old debug dump, every dataset and its exact rate
+                let lines: Vec<String> = deliveries
+                    .datasets
+                    .iter()
+                    .map(|(dataset, rate)| format!("{} {:.2}/s", dataset, rate))
+                    .chain(std::iter::once(format!("total {:.2}/s", total)))
+                    .collect();
+                panel.spawn((
+                    Text2d(lines.join("\n")),
+                    text_font.clone(),
+                    TextColor(Color::WHITE),
+                    SinkBadgePart { alpha: 1.0 },
+                ));
+                return;
+            }
+            let icons: Vec<_> = deliveries
+                .types
+                .keys()
+                .filter_map(|t| game_assets.data_type_icon(*t, IconSize::Small))
+                .collect();
+            // icons then the number, centred on the sink as one row
+            let rate_width = BADGE_ICON_SIZE * 2.5;
+            let width = icons.len() as f32 * BADGE_ICON_SIZE + rate_width;
+            panel.spawn((
+                Sprite {
+                    color: Color::srgba(0.0, 0.0, 0.0, 0.6),
+                    custom_size: Some(Vec2::new(width + 8.0, BADGE_ICON_SIZE + 6.0)),
+                    ..default()
+                },
+                SinkBadgePart { alpha: 0.6 },
+            ));
+            for (i, (atlas_id, index)) in icons.into_iter().enumerate() {
+                let (image, layout) = game_assets.get_atlas(atlas_id);
+                panel.spawn((
+                    Sprite {
+                        image,
+                        texture_atlas: Some(TextureAtlas { layout, index }),
+                        custom_size: Some(Vec2::splat(BADGE_ICON_SIZE)),
+                        ..default()
+                    },
+                    Transform::from_xyz(-width / 2.0 + (i as f32 + 0.5) * BADGE_ICON_SIZE, 0.0, 0.1),
+                    SinkBadgePart { alpha: 1.0 },
+                ));
+            }
+            panel.spawn((
+                Text2d(format!("{:.1}/s", total)),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+                Transform::from_xyz(width / 2.0 - rate_width / 2.0, 0.0, 0.1),
+                SinkBadgePart { alpha: 1.0 },
+            ));
+        });
+    }
+}
+
+/// Keeps badges a readable size as the camera zooms and fades them out when it's far enough away.
+/// Also steps aside while the sink's hover breakdown is up since they share the spot
+pub fn fade_sink_badges(
+    camera: Query<&Projection, With<Camera>>,
+    verbose: Res<VerboseSinkBadges>,
+    sinks: Query<(&SinkBadge, Option<&SinkBreakdownTooltip>)>,
+    mut panels: Query<(&mut Transform, &mut Visibility, Option<&Children>), Without<SinkBadgePart>>,
+    mut parts: Query<(&SinkBadgePart, Option<&mut Sprite>, Option<&mut TextColor>)>,
+) {
+    let Some(Projection::Orthographic(projection)) = camera.iter().next() else { return };
+    let scale = projection.scale;
+    let fade = if verbose.0 {
+        1.0
+    } else {
+        1.0 - ((scale - BADGE_FADE_SCALE) / (BADGE_HIDE_SCALE - BADGE_FADE_SCALE)).clamp(0.0, 1.0)
+    };
+    for (badge, breakdown) in sinks.iter() {
+        let breakdown_up = breakdown
+            .and_then(|b| panels.get(b.0).ok())
+            .is_some_and(|(_, v, _)| *v == Visibility::Visible);
+        let Ok((mut transform, mut visibility, children)) = panels.get_mut(badge.panel) else { continue };
+        let show = badge.shown && fade > 0.0 && !breakdown_up;
+        visibility.set_if_neq(if show { Visibility::Inherited } else { Visibility::Hidden });
+        if !show {
+            continue;
+        }
+        let size = scale.clamp(1.0, BADGE_MAX_SCALE);
+        if transform.scale.x != size {
+            transform.scale = Vec3::splat(size);
+        }
+        for child in children.iter().flat_map(|c| c.iter()) {
+            let Ok((part, sprite, text_color)) = parts.get_mut(child) else { continue };
+            let alpha = part.alpha * fade;
+            if let Some(mut sprite) = sprite
+                && sprite.color.alpha() != alpha
+            {
+                sprite.color.set_alpha(alpha);
+            }
+            if let Some(mut text_color) = text_color
+                && text_color.0.alpha() != alpha
+            {
+                text_color.0.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+pub fn toggle_verbose_sink_badges(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    dev_mode: Res<crate::dev::DevMode>,
+    mut verbose: ResMut<VerboseSinkBadges>,
+) {
+    if dev_mode.0 && keyboard.just_pressed(KeyCode::F8) {
+        verbose.0 = !verbose.0;
+        info!("Verbose sink badges {}", if verbose.0 { "on" } else { "off" });
+    }
+}
@@ -0,0 +1,230 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::dev::DevMode;
+use crate::trace::{TraceCategory, TraceFilter, TraceLog};
+use crate::ui::interactive_event::ScalableText;
+use crate::ui::{BlocksWorldClicks, BlocksWorldScroll};
+
+/// Only the newest entries are shown in the viewer; dumps contain everything
+const MAX_VIEWER_ROWS: usize = 200;
+const TRACE_DUMP_PATH: &str = "trace_dump.txt";
+
+#[derive(Component)]
+pub struct TraceViewerRoot;
+
+#[derive(Component)]
+pub struct TraceViewerList;
+
+/// Category filter button, `None` meaning all categories
+#[derive(Component)]
+pub struct TraceCategoryButton(pub Option<TraceCategory>);
+
+#[derive(Component)]
+pub struct TraceClearEntityButton;
+
+#[derive(Component)]
+pub struct TraceDumpButton;
+
+#[derive(Component)]
+pub struct TraceCopyButton;
+
+/// A row in the list; clicking it filters by the first entity it touches
+#[derive(Component)]
+pub struct TraceEntryRow(pub Option<Entity>);
+
+#[derive(Component)]
+pub struct TraceFilterLabel;
+
+pub fn spawn_trace_viewer_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                top: Val::Percent(10.0),
+                left: Val::Percent(15.0),
+                width: Val::Percent(50.0),
+                height: Val::Percent(70.0),
+                padding: UiRect::all(Val::Vw(0.6)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Vw(0.4),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.95)),
+            GlobalZIndex(900),
+            TraceViewerRoot,
+            BlocksWorldClicks,
+        ))
+        .with_children(|root| {
+            root.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                column_gap: Val::Vw(0.3),
+                row_gap: Val::Vw(0.3),
+                ..default()
+            })
+            .with_children(|bar| {
+                spawn_viewer_button(bar, &game_assets, "All", TraceCategoryButton(None));
+                for category in TraceCategory::ALL {
+                    spawn_viewer_button(
+                        bar,
+                        &game_assets,
+                        &format!("{:?}", category),
+                        TraceCategoryButton(Some(category)),
+                    );
+                }
+                spawn_viewer_button(bar, &game_assets, "Clear entity", TraceClearEntityButton);
+                spawn_viewer_button(bar, &game_assets, "Dump to file", TraceDumpButton);
+                spawn_viewer_button(bar, &game_assets, "Copy", TraceCopyButton);
+            });
+
+            root.spawn((
+                Text::new(""),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.8),
+                TextColor(Color::srgb(0.95, 0.85, 0.25)),
+                TraceFilterLabel,
+            ));
+
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    flex_grow: 1.0,
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                TraceViewerList,
+                BlocksWorldScroll,
+            ));
+        });
+}
+
+fn spawn_viewer_button(
+    parent: &mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    label: &str,
+    marker: impl Bundle,
+) {
+    parent
+        .spawn((
+            Node {
+                padding: UiRect::axes(Val::Vw(0.4), Val::Vw(0.15)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+            Interaction::None,
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.8),
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+pub fn toggle_trace_viewer(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    dev_mode: Res<DevMode>,
+    mut root_query: Query<&mut Node, With<TraceViewerRoot>>,
+) {
+    if !dev_mode.0 || !keyboard.just_pressed(KeyCode::F10) {
+        return;
+    }
+    for mut node in root_query.iter_mut() {
+        node.display = match node.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+    }
+}
+
+pub fn handle_trace_viewer_buttons(
+    category_query: Query<(&Interaction, &TraceCategoryButton), Changed<Interaction>>,
+    clear_query: Query<&Interaction, (Changed<Interaction>, With<TraceClearEntityButton>)>,
+    dump_query: Query<&Interaction, (Changed<Interaction>, With<TraceDumpButton>)>,
+    copy_query: Query<&Interaction, (Changed<Interaction>, With<TraceCopyButton>)>,
+    row_query: Query<(&Interaction, &TraceEntryRow), Changed<Interaction>>,
+    mut filter: ResMut<TraceFilter>,
+    log: Res<TraceLog>,
+) {
+    for (interaction, button) in category_query.iter() {
+        if *interaction == Interaction::Pressed {
+            filter.category = button.0;
+        }
+    }
+    for interaction in clear_query.iter() {
+        if *interaction == Interaction::Pressed {
+            filter.entity = None;
+        }
+    }
+    for (interaction, row) in row_query.iter() {
+        if *interaction == Interaction::Pressed && row.0.is_some() {
+            filter.entity = row.0;
+        }
+    }
+    for interaction in dump_query.iter() {
+        if *interaction == Interaction::Pressed {
+            match std::fs::write(TRACE_DUMP_PATH, log.format_filtered(&filter)) {
+                Ok(()) => info!("Trace written to {}", TRACE_DUMP_PATH),
+                Err(e) => warn!("Failed to write trace dump: {}", e),
+            }
+        }
+    }
+    for interaction in copy_query.iter() {
+        if *interaction == Interaction::Pressed {
+            let text = log.format_filtered(&filter);
+            match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+                Ok(()) => info!("Trace copied to the clipboard"),
+                Err(e) => warn!("Failed to copy trace: {}", e),
+            }
+        }
+    }
+}
+
+/// Rebuilds the list while the viewer is open and the log or filter changed
+pub fn update_trace_viewer_list(
+    mut commands: Commands,
+    root_query: Query<&Node, With<TraceViewerRoot>>,
+    list_query: Query<Entity, With<TraceViewerList>>,
+    mut label_query: Query<&mut Text, With<TraceFilterLabel>>,
+    log: Res<TraceLog>,
+    filter: Res<TraceFilter>,
+    game_assets: Res<GameAssets>,
+) {
+    let Ok(root) = root_query.single() else { return; };
+    if root.display == Display::None || !(log.is_changed() || filter.is_changed()) {
+        return;
+    }
+    let Ok(list) = list_query.single() else { return; };
+
+    for mut text in label_query.iter_mut() {
+        **text = format!(
+            "Category: {} | Entity: {} | {} entries total",
+            filter.category.map_or("All".to_string(), |c| format!("{:?}", c)),
+            filter.entity.map_or("any".to_string(), |e| format!("{:?}", e)),
+            log.len()
+        );
+    }
+
+    let entries: Vec<_> = log.filtered(&filter).collect();
+    let skip = entries.len().saturating_sub(MAX_VIEWER_ROWS);
+
+    commands.entity(list).despawn_children().with_children(|list| {
+        for entry in entries.into_iter().skip(skip) {
+            list.spawn((
+                Text::new(format!(
+                    "[{:>8.1}] {:?}: {}",
+                    entry.time, entry.category, entry.message
+                )),
+                game_assets.text_font(12.0),
+                ScalableText::from_vw(0.75),
+                TextColor(Color::srgb(0.85, 0.85, 0.85)),
+                Interaction::None,
+                TraceEntryRow(entry.entities.first().copied()),
+            ));
+        }
+    });
+}
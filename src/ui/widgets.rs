@@ -0,0 +1,455 @@
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use crate::assets::GameAssets;
+use crate::ui::interactive_event::ScalableText;
+
+// Small reusable input widgets. Each spawn function returns the widget's root entity,
+// which holds the current value and is the `entity` of the `WidgetChanged` messages it sends.
+// Feature code tags the root with its own binding component and reads the messages.
+
+const TRACK_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const FILL_COLOR: Color = Color::srgb(0.9, 0.9, 0.1);
+const BUTTON_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+const TOGGLE_ON_COLOR: Color = Color::srgb(0.2, 0.6, 0.3);
+const FOCUS_COLOR: Color = Color::srgb(0.95, 0.85, 0.25);
+/// Keyboard nudge for sliders without a step
+const DEFAULT_KEY_STEP_FRACTION: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WidgetValue {
+    Float(f32),
+    Bool(bool),
+    Index(usize),
+}
+
+/// Sent whenever the player changes a widget's value
+#[derive(Message, Debug, Clone, Copy)]
+pub struct WidgetChanged {
+    pub entity: Entity,
+    pub value: WidgetValue,
+}
+
+#[derive(Component)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Values snap to multiples of this (from `min`) if set
+    pub step: Option<f32>,
+    pub format: fn(f32) -> String,
+    fill: Entity,
+    label: Entity,
+}
+
+impl Slider {
+    pub fn normalized(&self) -> f32 {
+        if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Set from a 0..1 track position, snapping to the step. Returns true if the value changed.
+    pub fn set_normalized(&mut self, t: f32) -> bool {
+        let raw = self.min + t.clamp(0.0, 1.0) * (self.max - self.min);
+        self.set_value(raw)
+    }
+
+    pub fn set_value(&mut self, raw: f32) -> bool {
+        let snapped = snap_value(raw, self.min, self.max, self.step);
+        if (snapped - self.value).abs() > f32::EPSILON {
+            self.value = snapped;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// One keyboard/gamepad nudge
+    pub fn key_step(&self) -> f32 {
+        self.step.unwrap_or((self.max - self.min) * DEFAULT_KEY_STEP_FRACTION)
+    }
+}
+
+/// Clamp to the range and snap to the step (counted from `min`)
+pub fn snap_value(raw: f32, min: f32, max: f32, step: Option<f32>) -> f32 {
+    let clamped = raw.clamp(min, max);
+    match step {
+        Some(step) if step > 0.0 => {
+            let snapped = min + ((clamped - min) / step).round() * step;
+            snapped.clamp(min, max)
+        }
+        _ => clamped,
+    }
+}
+
+/// The clickable track of a slider
+#[derive(Component)]
+pub struct SliderTrack {
+    pub slider: Entity,
+}
+
+#[derive(Component)]
+pub struct Toggle {
+    pub on: bool,
+    label: Entity,
+}
+
+/// Cycles through a fixed list of options, like a compact dropdown
+#[derive(Component)]
+pub struct Cycler {
+    pub options: Vec<String>,
+    pub index: usize,
+    label: Entity,
+}
+
+impl Cycler {
+    pub fn cycle(&mut self, delta: i32) {
+        let len = self.options.len() as i32;
+        if len > 0 {
+            self.index = (self.index as i32 + delta).rem_euclid(len) as usize;
+        }
+    }
+}
+
+/// Widgets that take part in keyboard/gamepad navigation
+#[derive(Component)]
+pub struct Focusable;
+
+/// The widget currently focused by keyboard/gamepad navigation
+#[derive(Resource, Default)]
+pub struct WidgetFocus(pub Option<Entity>);
+
+fn widget_label(game_assets: &GameAssets, text: String) -> impl Bundle {
+    (
+        Text::new(text),
+        game_assets.text_font(14.0),
+        ScalableText::from_vw(0.9),
+        TextColor(Color::WHITE),
+    )
+}
+
+/// A labelled row holding one widget
+fn spawn_widget_row<'a>(
+    parent: &'a mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    name: &str,
+) -> EntityCommands<'a> {
+    let mut row = parent.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Vw(0.5),
+        ..default()
+    });
+    row.with_children(|row| {
+        row.spawn((
+            Node { width: Val::Vw(7.0), ..default() },
+        ))
+        .with_children(|n| {
+            n.spawn(widget_label(game_assets, name.to_string()));
+        });
+    });
+    row
+}
+
+pub fn spawn_slider(
+    parent: &mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    name: &str,
+    value: f32,
+    min: f32,
+    max: f32,
+    step: Option<f32>,
+    format: fn(f32) -> String,
+) -> Entity {
+    let mut root = Entity::PLACEHOLDER;
+    spawn_widget_row(parent, game_assets, name).with_children(|row| {
+        let mut fill = Entity::PLACEHOLDER;
+        let mut label = Entity::PLACEHOLDER;
+        root = row
+            .spawn((
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Vw(0.4),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BorderColor::all(Color::NONE),
+                Focusable,
+            ))
+            .with_children(|widget| {
+                let track = widget
+                    .spawn((
+                        Node {
+                            width: Val::Vw(8.0),
+                            height: Val::Vw(0.6),
+                            ..default()
+                        },
+                        BackgroundColor(TRACK_COLOR),
+                        Interaction::None,
+                        RelativeCursorPosition::default(),
+                    ))
+                    .with_children(|track| {
+                        fill = track
+                            .spawn((
+                                Node {
+                                    width: Val::Percent(0.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                BackgroundColor(FILL_COLOR),
+                            ))
+                            .id();
+                    })
+                    .id();
+                label = widget.spawn(widget_label(game_assets, format(value))).id();
+                widget.commands().entity(track).insert(SliderTrack { slider: widget.target_entity() });
+            })
+            .id();
+        row.commands().entity(root).insert(Slider {
+            value: snap_value(value, min, max, step),
+            min,
+            max,
+            step,
+            format,
+            fill,
+            label,
+        });
+    });
+    root
+}
+
+pub fn spawn_toggle(
+    parent: &mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    name: &str,
+    on: bool,
+) -> Entity {
+    let mut root = Entity::PLACEHOLDER;
+    spawn_widget_row(parent, game_assets, name).with_children(|row| {
+        let mut label = Entity::PLACEHOLDER;
+        root = row
+            .spawn((
+                Node {
+                    width: Val::Vw(3.5),
+                    padding: UiRect::axes(Val::Vw(0.4), Val::Vw(0.15)),
+                    justify_content: JustifyContent::Center,
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(if on { TOGGLE_ON_COLOR } else { BUTTON_COLOR }),
+                BorderColor::all(Color::NONE),
+                Interaction::None,
+                Focusable,
+            ))
+            .with_children(|button| {
+                label = button.spawn(widget_label(game_assets, on_off(on).to_string())).id();
+            })
+            .id();
+        row.commands().entity(root).insert(Toggle { on, label });
+    });
+    root
+}
+
+pub fn spawn_cycler(
+    parent: &mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    name: &str,
+    options: Vec<String>,
+    index: usize,
+) -> Entity {
+    let index = index.min(options.len().saturating_sub(1));
+    let current = options.get(index).cloned().unwrap_or_default();
+    let mut root = Entity::PLACEHOLDER;
+    spawn_widget_row(parent, game_assets, name).with_children(|row| {
+        let mut label = Entity::PLACEHOLDER;
+        root = row
+            .spawn((
+                Node {
+                    padding: UiRect::axes(Val::Vw(0.4), Val::Vw(0.15)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+                BorderColor::all(Color::NONE),
+                Interaction::None,
+                Focusable,
+            ))
+            .with_children(|button| {
+                label = button.spawn(widget_label(game_assets, format!("< {} >", current))).id();
+            })
+            .id();
+        row.commands().entity(root).insert(Cycler { options, index, label });
+    });
+    root
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on { "On" } else { "Off" }
+}
+
+/// Dragging (or clicking) a slider track sets the value from the cursor position
+pub fn drag_sliders(
+    tracks: Query<(&Interaction, &RelativeCursorPosition, &SliderTrack)>,
+    mut sliders: Query<&mut Slider>,
+    mut changed: MessageWriter<WidgetChanged>,
+    mut focus: ResMut<WidgetFocus>,
+) {
+    for (interaction, cursor, track) in tracks.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // normalized is relative to the node centre, -0.5..0.5
+        let Some(position) = cursor.normalized else { continue };
+        if let Ok(mut slider) = sliders.get_mut(track.slider) {
+            focus.0 = Some(track.slider);
+            if slider.set_normalized(position.x + 0.5) {
+                changed.write(WidgetChanged { entity: track.slider, value: WidgetValue::Float(slider.value) });
+            }
+        }
+    }
+}
+
+pub fn click_buttons(
+    mut toggles: Query<(Entity, &Interaction, &mut Toggle), (Changed<Interaction>, Without<Cycler>)>,
+    mut cyclers: Query<(Entity, &Interaction, &mut Cycler), Changed<Interaction>>,
+    mut changed: MessageWriter<WidgetChanged>,
+    mut focus: ResMut<WidgetFocus>,
+) {
+    for (entity, interaction, mut toggle) in toggles.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            toggle.on = !toggle.on;
+            focus.0 = Some(entity);
+            changed.write(WidgetChanged { entity, value: WidgetValue::Bool(toggle.on) });
+        }
+    }
+    for (entity, interaction, mut cycler) in cyclers.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            cycler.cycle(1);
+            focus.0 = Some(entity);
+            changed.write(WidgetChanged { entity, value: WidgetValue::Index(cycler.index) });
+        }
+    }
+}
+
+/// Tab / d-pad up-down moves focus between visible widgets, left/right adjusts, enter/south activates
+pub fn navigate_widgets(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    focusable: Query<(Entity, &ComputedNode), With<Focusable>>,
+    mut sliders: Query<&mut Slider>,
+    mut toggles: Query<&mut Toggle>,
+    mut cyclers: Query<&mut Cycler>,
+    mut focus: ResMut<WidgetFocus>,
+    mut changed: MessageWriter<WidgetChanged>,
+) {
+    let pad = |button: GamepadButton| gamepads.iter().any(|g| g.just_pressed(button));
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let next = (keyboard.just_pressed(KeyCode::Tab) && !shift) || pad(GamepadButton::DPadDown);
+    let previous = (keyboard.just_pressed(KeyCode::Tab) && shift) || pad(GamepadButton::DPadUp);
+    let left = keyboard.just_pressed(KeyCode::ArrowLeft) || pad(GamepadButton::DPadLeft);
+    let right = keyboard.just_pressed(KeyCode::ArrowRight) || pad(GamepadButton::DPadRight);
+    let activate = keyboard.just_pressed(KeyCode::Enter) || pad(GamepadButton::South);
+
+    // hidden panels have no size, so they drop out of the focus order
+    let mut visible: Vec<Entity> = focusable
+        .iter()
+        .filter(|(_, node)| node.size() != Vec2::ZERO)
+        .map(|(e, _)| e)
+        .collect();
+    visible.sort();
+
+    if focus.0.is_some_and(|e| !visible.contains(&e)) {
+        focus.0 = None;
+    }
+
+    if (next || previous) && !visible.is_empty() {
+        let len = visible.len() as i32;
+        let current = focus.0.and_then(|e| visible.iter().position(|&v| v == e));
+        let index = match current {
+            Some(i) => (i as i32 + if next { 1 } else { -1 }).rem_euclid(len),
+            None if next => 0,
+            None => len - 1,
+        };
+        focus.0 = Some(visible[index as usize]);
+        return;
+    }
+
+    let Some(entity) = focus.0 else { return };
+    let direction = if right { 1 } else if left { -1 } else { 0 };
+
+    if let Ok(mut slider) = sliders.get_mut(entity)
+        && direction != 0
+    {
+        let target = slider.value + slider.key_step() * direction as f32;
+        if slider.set_value(target) {
+            changed.write(WidgetChanged { entity, value: WidgetValue::Float(slider.value) });
+        }
+    } else if let Ok(mut toggle) = toggles.get_mut(entity)
+        && (direction != 0 || activate)
+    {
+        toggle.on = !toggle.on;
+        changed.write(WidgetChanged { entity, value: WidgetValue::Bool(toggle.on) });
+    } else if let Ok(mut cycler) = cyclers.get_mut(entity)
+        && (direction != 0 || activate)
+    {
+        cycler.cycle(if direction == 0 { 1 } else { direction });
+        changed.write(WidgetChanged { entity, value: WidgetValue::Index(cycler.index) });
+    }
+}
+
+/// Keeps fills, labels and the focus border in sync with widget state
+pub fn update_widget_visuals(
+    sliders: Query<&Slider, Changed<Slider>>,
+    toggles: Query<(Entity, &Toggle), Changed<Toggle>>,
+    cyclers: Query<&Cycler, Changed<Cycler>>,
+    mut nodes: Query<&mut Node>,
+    mut texts: Query<&mut Text>,
+    mut backgrounds: Query<&mut BackgroundColor>,
+    mut borders: Query<(Entity, &mut BorderColor), With<Focusable>>,
+    focus: Res<WidgetFocus>,
+) {
+    for slider in sliders.iter() {
+        if let Ok(mut node) = nodes.get_mut(slider.fill) {
+            node.width = Val::Percent(slider.normalized() * 100.0);
+        }
+        if let Ok(mut text) = texts.get_mut(slider.label) {
+            **text = (slider.format)(slider.value);
+        }
+    }
+    for (entity, toggle) in toggles.iter() {
+        if let Ok(mut text) = texts.get_mut(toggle.label) {
+            **text = on_off(toggle.on).to_string();
+        }
+        if let Ok(mut background) = backgrounds.get_mut(entity) {
+            background.0 = if toggle.on { TOGGLE_ON_COLOR } else { BUTTON_COLOR };
+        }
+    }
+    for cycler in cyclers.iter() {
+        if let (Ok(mut text), Some(option)) = (texts.get_mut(cycler.label), cycler.options.get(cycler.index)) {
+            **text = format!("< {} >", option);
+        }
+    }
+    if focus.is_changed() {
+        for (entity, mut border) in borders.iter_mut() {
+            *border = BorderColor::all(if focus.0 == Some(entity) { FOCUS_COLOR } else { Color::NONE });
+        }
+    }
+}
+
+pub struct WidgetsPlugin;
+
+impl Plugin for WidgetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<WidgetChanged>()
+            .init_resource::<WidgetFocus>()
+            .add_systems(Update, (
+                drag_sliders,
+                click_buttons,
+                navigate_widgets,
+                update_widget_visuals,
+            ).chain());
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where per-user data that outlives a run is stored (achievements, and later run history)
+pub const USER_CONFIG_PATH: &str = "user_config.ron";
+
+/// Persistent per-user data, loaded at startup and written back whenever it changes
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UserConfig {
+    /// Ids of earned achievements, in the order they were earned
+    pub earned_achievements: Vec<String>,
+    /// Crate version of the last launch, for the "What's new" panel
+    pub last_seen_version: Option<String>,
+    /// Volumes and mutes from the settings panel
+    pub audio: Option<crate::settings::AudioSettings>,
+    /// Contracts sidebar left folded at the end of the last session
+    pub sidebar_collapsed: Option<bool>,
+    /// Key and button for every rebindable action
+    pub bindings: Option<Vec<(crate::controls::InputAction, crate::controls::Binding)>>,
+    /// Tutorial played through or skipped, it doesn't come back
+    pub tutorial_finished: bool,
+}
+
+impl UserConfig {
+    pub fn load() -> Self {
+        match std::fs::read_to_string(USER_CONFIG_PATH) {
+            Ok(ron_str) => ron::from_str(&ron_str).unwrap_or_else(|e| {
+                warn!("Failed to parse {}, starting fresh: {}", USER_CONFIG_PATH, e);
+                Self::default()
+            }),
+            // no file yet on a first run
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|s| std::fs::write(USER_CONFIG_PATH, s).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to save {}: {}", USER_CONFIG_PATH, e);
+        }
+    }
+}
+
+fn save_user_config(config: Res<UserConfig>) {
+    config.save();
+}
+
+pub struct UserConfigPlugin;
+
+impl Plugin for UserConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(UserConfig::load())
+            .add_systems(Last, save_user_config.run_if(resource_changed::<UserConfig>.and(not(resource_added::<UserConfig>))));
+    }
+}
@@ -14,12 +14,18 @@ use rand::Rng;
 
 use crate::factory::logical::{BasicDataType, DataAttribute, Dataset};
 
-use crate::factions::{Faction, Locked, ReputationLevel};
+use crate::events::AddNewsfeedItemEvent;
+use crate::factions::{
+    reputation_level_min_score, reputation_level_name, reputation_progress_fraction, Faction,
+    FactionReputations, Locked, ReputationLevel,
+};
 use crate::factory::buildings::buildings::Building;
 use crate::factory::buildings::sink::SinkBuilding;
-use crate::factory::buildings::source::SourceBuilding;
+use crate::factory::buildings::source::{SourceBuilding, SourcePool};
 use crate::factory::buildings::Undeletable;
-use crate::grid::{Direction, GridSprite, Orientation};
+use crate::factory::building_visuals::{z_layers, StarterSink};
+use crate::grid::{are_positions_free, Direction, GridSprite, Orientation, WorldMap};
+use crate::pause::GameState;
 use bevy_prng::WyRand;
 use bevy_rand::prelude::GlobalRng;
 use rand::prelude::IndexedRandom;
@@ -46,10 +52,91 @@ pub struct ClusterID(i64);
 #[derive(Component)]
 pub struct LockMarker;
 
+/// Sprites outlining the world and shading what's past it
+#[derive(Component)]
+pub struct WorldBorder;
+
+/// Which faction (and tier) each locked cluster cell was generated for, for the minimap.
+/// Cells stay in here after they unlock.
+#[derive(Resource, Default, Debug)]
+pub struct ClusterCells(pub HashMap<I64Vec2, (Faction, ReputationLevel)>);
+
+/// The central faction icon of a locked cluster
+#[derive(Component)]
+pub struct ClusterIconMarker;
+
+/// Progress bar under a cluster icon showing how close the player is to unlocking it
+#[derive(Component)]
+pub struct UnlockProgressBar {
+    pub faction: Faction,
+    pub required: ReputationLevel,
+    /// Direction of the cluster from the start area, for the newsfeed nudge
+    pub compass: &'static str,
+    /// The cluster icon's cell, where clicking the nudge goes
+    pub center: GridPosition,
+    pub nudged: bool,
+}
+
+impl UnlockProgressBar {
+    /// True the first time `score` gets within `UNLOCK_NUDGE_POINTS` of unlocking, never again after
+    pub fn take_nudge(&mut self, score: i32) -> bool {
+        let remaining = reputation_level_min_score(self.required) as i32 - score;
+        if self.nudged || remaining <= 0 || remaining > UNLOCK_NUDGE_POINTS {
+            return false;
+        }
+        self.nudged = true;
+        true
+    }
+}
+
+/// Fill sprite of an `UnlockProgressBar`
+#[derive(Component)]
+pub struct UnlockProgressFill;
+
+const UNLOCK_BAR_WIDTH: f32 = 112.0;
+const UNLOCK_BAR_HEIGHT: f32 = 10.0;
+/// Within this many points of unlocking the bar pulses and a nudge is sent
+const UNLOCK_NUDGE_POINTS: i32 = 5;
+
 // might need to change min/max logic a bit if not even lol
-const WORLD_SIZE: i64 = 80;
-const WORLD_MIN: i64 = -(WORLD_SIZE / 2);
-const WORLD_MAX: i64 = (WORLD_SIZE / 2) - 1;
+pub(crate) const WORLD_SIZE: i64 = 80;
+pub(crate) const WORLD_MIN: i64 = -(WORLD_SIZE / 2);
+pub(crate) const WORLD_MAX: i64 = (WORLD_SIZE / 2) - 1;
+
+/// The square of cells world gen fills and the player may build in. Grid placement, the camera
+/// clamp and the border drawn around the world all read this
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorldBounds {
+    pub min: I64Vec2,
+    pub max: I64Vec2,
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        Self {
+            min: I64Vec2::splat(WORLD_MIN),
+            max: I64Vec2::splat(WORLD_MAX),
+        }
+    }
+}
+
+impl WorldBounds {
+    pub fn contains(&self, cell: I64Vec2) -> bool {
+        cell.cmpge(self.min).all() && cell.cmple(self.max).all()
+    }
+
+    /// Cells across and up, both edges included
+    pub fn size(&self) -> I64Vec2 {
+        self.max - self.min + I64Vec2::ONE
+    }
+}
+
+/// Line drawn along the world's edge, px
+const BORDER_THICKNESS: f32 = 6.0;
+const BORDER_COLOR: Color = Color::srgba(0.9, 0.9, 1.0, 0.6);
+/// The dimmed strip past the edge, wide enough to fill the view at full zoom out
+const OUTSIDE_SHADE_TILES: i64 = 80;
+const OUTSIDE_SHADE_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.55);
 
 const STARTING_AREA_SIZE: i64 = 8;
 const INITIAL_FACTION_SINKS: [(I64Vec2, Faction); 4] = [
@@ -62,6 +149,10 @@ const INITIAL_FACTION_SINKS: [(I64Vec2, Faction); 4] = [
 // basic sources per 1000 unlocked tiles
 const BASIC_SOURCE_DENSITY: i32 = 10;
 const SOURCES_PER_FACTION_CLUSTER: RangeInclusive<i32> = 2..=3;
+/// Share of basic sources that run dry eventually
+const LIMITED_SOURCE_CHANCE: f64 = 0.3;
+/// A limited source's pool, in seconds of its full throughput
+const LIMITED_SOURCE_POOL_SECONDS: RangeInclusive<f32> = 180.0..=600.0;
 
 const FACTION_CLUSTER_THRESHOLD: f32 = 0.30;
 // check to stop broken clusters from spawning because of start area cutting through them
@@ -69,25 +160,69 @@ const MIN_CLUSTER_SIZE: i32 = 32;
 
 impl Plugin for WorldGenPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, startup)
-            .add_systems(Update, cleanup_unlocked_markers);
+        app.init_resource::<ClusterCells>()
+            .insert_resource(WorldBounds::default())
+            // waits for the difficulty, which scales the sources
+            .add_systems(OnExit(GameState::Setup), (startup, spawn_world_border))
+            .add_systems(Update, (cleanup_unlocked_markers, fade_unlocked_markers).chain())
+            .add_systems(Update, (
+                spawn_unlock_progress_bars,
+                update_unlock_progress_bars,
+                pulse_unlock_progress_bars,
+            ).chain());
     }
 }
 
-fn startup(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    game_assets: Res<GameAssets>,
-    mut rng: Single<&mut WyRand, With<GlobalRng>>,
-) {
-    let _startup_span = info_span!("startup_span", name = "startup_span").entered();
+/// Seed for everything world gen rolls, from `--seed <n>` or `LD58_SEED`, random otherwise.
+/// The same seed gives the same clusters, sinks and sources
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorldSeed(pub u64);
+
+impl WorldSeed {
+    pub fn from_env() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        let from_args = args.iter().enumerate().find_map(|(i, arg)| {
+            arg.strip_prefix("--seed=")
+                .map(str::to_string)
+                .or_else(|| (arg == "--seed").then(|| args.get(i + 1).cloned()).flatten())
+        });
+        let given = from_args.or_else(|| std::env::var("LD58_SEED").ok());
+        match given.map(|s| s.trim().parse::<u64>()) {
+            Some(Ok(seed)) => Self(seed),
+            Some(Err(e)) => {
+                warn!("Ignoring bad world seed ({}), rolling a random one", e);
+                Self(rand::random())
+            }
+            None => Self(rand::random()),
+        }
+    }
+
+    /// Offset into the cluster noise, rolled from its own stream so it doesn't shift when
+    /// something else draws from the global rng first
+    pub fn noise_offset(&self) -> f32 {
+        use rand::SeedableRng;
+        WyRand::seed_from_u64(self.0).random_range(-1000.0..1000.0)
+    }
+}
+
+/// Which cells start locked and how they group into clusters, before anything's spawned
+pub struct ClusterLayout {
+    pub unlocked_cells: Vec<I64Vec2>,
+    pub locked_cells: Vec<I64Vec2>,
+    /// grid_pos -> cluster_id
+    pub cluster_map: HashMap<I64Vec2, i64>,
+    /// cluster_id -> grid_pos
+    pub center_map: HashMap<i64, I64Vec2>,
+    /// defines free spots to spawn faction sources
+    pub faction_source_locations: HashMap<i64, HashSet<I64Vec2>>,
+}
+
+/// Splits the world into unlocked cells and faction clusters from the noise alone, no rng
+pub fn generate_clusters(noise_offset: f32) -> ClusterLayout {
     // apply logic to determine which ones start locked
     let mut unlocked_cells: Vec<I64Vec2> = Vec::new();
     let mut locked_cells: Vec<I64Vec2> = Vec::new();
 
-    // let mut rng = rand::rng();
-    let noise_offset: f32 = rng.random_range(-1000.0..1000.0);
-
     for i in WORLD_MIN..=WORLD_MAX {
         for j in WORLD_MIN..=WORLD_MAX {
             let cell_vec = I64Vec2::new(i, j);
@@ -107,13 +242,8 @@ fn startup(
     let valid_nodes: HashSet<I64Vec2> = locked_cells.iter().cloned().collect();
     let mut visited: HashSet<I64Vec2> = HashSet::new();
 
-    // grid_pos -> cluster_id
     let mut cluster_map: HashMap<I64Vec2, i64> = HashMap::new();
-
-    // defines free spots to spawn faction sources
     let mut faction_source_locations: HashMap<i64, HashSet<I64Vec2>> = HashMap::new();
-
-    // cluster_id -> grid_pos
     let mut center_map: HashMap<i64, I64Vec2> = HashMap::new();
 
     let mut cluster_id: i64 = 0;
@@ -165,6 +295,36 @@ fn startup(
         }
     }
 
+    ClusterLayout {
+        unlocked_cells,
+        locked_cells,
+        cluster_map,
+        center_map,
+        faction_source_locations,
+    }
+}
+
+fn startup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    game_mode: Res<crate::game_mode::GameMode>,
+    difficulty: Res<crate::difficulty::Difficulty>,
+    seed: Res<WorldSeed>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+) {
+    let source_multiplier = difficulty.multipliers().source_throughput;
+    let _startup_span = info_span!("startup_span", name = "startup_span").entered();
+    info!("Generating world with seed {}", seed.0);
+
+    let ClusterLayout {
+        unlocked_cells,
+        locked_cells: _,
+        cluster_map,
+        center_map,
+        mut faction_source_locations,
+    } = generate_clusters(seed.noise_offset());
+
     // println!("cluster map: {:?}", cluster_map);
     // println!("center map: {:?}", center_map);
 
@@ -187,20 +347,41 @@ fn startup(
             .collect::<HashMap<i64, ReputationLevel>>(),
     );
 
+    // cluster bounding boxes for the merged lock overlay at minimal LOD
+    if game_mode.starts_locked() {
+        let mut bounds: HashMap<i64, crate::lod::ClusterBound> = HashMap::new();
+        for (cell_vec, cluster_id) in cluster_map.iter() {
+            if let (Some(faction), Some(reputation)) = (cluster_faction.get(cluster_id), cluster_reputation.get(cluster_id)) {
+                bounds
+                    .entry(*cluster_id)
+                    .or_insert(crate::lod::ClusterBound {
+                        min: *cell_vec,
+                        max: *cell_vec,
+                        faction: *faction,
+                        reputation: *reputation,
+                    })
+                    .include(*cell_vec);
+            }
+        }
+        commands.insert_resource(crate::lod::ClusterBounds(bounds));
+        commands.insert_resource(ClusterCells(
+            cluster_map
+                .iter()
+                .filter_map(|(cell_vec, cluster_id)| {
+                    Some((*cell_vec, (*cluster_faction.get(cluster_id)?, *cluster_reputation.get(cluster_id)?)))
+                })
+                .collect(),
+        ));
+    }
+
+    // sandbox skips the lock overlay, sinks/sources still get unlocked by the maxed reputation
     for (cell_vec, cluster_id) in cluster_map.iter().collect::<Vec<(&I64Vec2, &i64)>>()
     {
+        if !game_mode.starts_locked() {
+            break;
+        }
         if let (Some(faction), Some(reputation)) = (cluster_faction.get(cluster_id), cluster_reputation.get(cluster_id)) {
-            let mut faction_color = game_assets.faction_color(*faction);
-            faction_color.set_alpha(0.5);
-            commands.spawn((
-                Locked,
-                GridPosition(*cell_vec),
-                GridSprite(faction_color),
-                *faction,
-                *reputation,
-                Transform::from_xyz(0.0, 0.0, 50.0), // Higher Z coordinate to appear above other sprites
-                LockMarker
-            ));
+            spawn_lock_cell(*cell_vec, *faction, *reputation, &game_assets, &mut commands);
         } else {
             panic!("cluster {cluster_id} is missing from a hashmap");
         }
@@ -234,33 +415,10 @@ fn startup(
             cluster_faction.get(cluster_id),
             cluster_reputation.get(cluster_id),
         ) {
-            // spawn faction icon
-            let icon_index = game_assets.faction_icon(*faction, crate::assets::IconSize::Large).map(|(_, idx)| idx).unwrap_or(0);
-            commands.spawn((
-                GridPosition(*cell_vec),
-                Sprite {
-                    image: game_assets.small_sprites_texture.clone(),
-                    texture_atlas: Some(TextureAtlas { layout: game_assets.small_sprites_layout.clone(), index: icon_index }),
-                    custom_size: Some(Vec2::splat(128.0)), // Upscale the sprite (default grid size is 64.0)
-                    ..Default::default()
-                },
-                Transform::from_xyz(0.0, 0.0, 60.0), // Higher Z coordinate to appear above other sprites
-                *faction,
-                *reputation,
-                Locked,
-                LockMarker,
-                // Reputation level text indicator
-                Text2d::new(format!("{:?}", reputation)),
-                TextFont {
-                    font_size: 16.0,
-                    ..default()
-                },
-                TextColor(Color::srgb(1.0, 1.0, 1.0)), // White text
-                ZIndex(70), // Above the sprite
-            ));
+            spawn_cluster_icon(*cell_vec, *faction, *reputation, &game_assets, &mut commands);
 
             if let Some(cluster_allowable_spawns) = faction_source_locations.get_mut(cluster_id) {
-                spawn_faction_sink(
+                let _ = spawn_faction_sink(
                     *cell_vec,
                     *faction,
                     *reputation,
@@ -276,7 +434,7 @@ fn startup(
 
     // spawn intitial faction sinks
     for (position, faction) in INITIAL_FACTION_SINKS {
-        spawn_faction_sink(
+        let sink = spawn_faction_sink(
             position,
             faction,
             ReputationLevel::Hostile,
@@ -284,6 +442,7 @@ fn startup(
             Option::None,
             &mut commands,
         );
+        commands.entity(sink).insert(StarterSink);
     }
 
     let basic_source_amount = (unlocked_cells.length() as i32 / 1000) * BASIC_SOURCE_DENSITY;
@@ -305,12 +464,18 @@ fn startup(
         }
 
         if !sink_locs.contains(cell_vec) {
+            let throughput = get_basic_source_throughput(*cell_vec) * source_multiplier;
+            // some basic sources dry up, faction ones never do
+            let pool = rng
+                .random_bool(LIMITED_SOURCE_CHANCE)
+                .then(|| throughput * rng.random_range(LIMITED_SOURCE_POOL_SECONDS));
             spawn_source(
                 *cell_vec,
-                get_basic_source_throughput(*cell_vec),
+                throughput,
                 get_basic_source_dataset(&mut rng),
                 Option::None,
                 Option::None,
+                pool,
                 &mut commands,
             );
         }
@@ -330,6 +495,7 @@ fn startup(
                 *reputation,
                 *faction,
                 available_spawns,
+                source_multiplier,
                 &mut rng,
                 &mut commands,
             );
@@ -345,11 +511,12 @@ fn spawn_cluster_sources(
     reputation: ReputationLevel,
     faction: Faction,
     available_spawns: &HashSet<I64Vec2>,
+    throughput_multiplier: f32,
     rng: &mut WyRand,
     commands: &mut Commands,
 ) {
     let dataset = get_faction_source_dataset(faction, reputation, rng);
-    let throughput = get_faction_source_throughput(reputation);
+    let throughput = get_faction_source_throughput(reputation) * throughput_multiplier;
 
     for cell_vec in available_spawns
         .into_iter()
@@ -363,6 +530,7 @@ fn spawn_cluster_sources(
             dataset.clone(),
             Some(faction),
             Some(reputation),
+            None,
             commands,
         );
     }
@@ -443,16 +611,20 @@ fn spawn_source(
     dataset: Dataset,
     faction: Option<Faction>,
     reputation: Option<ReputationLevel>,
+    pool: Option<f32>,
     commands: &mut Commands,
-) {
+) -> Entity {
     let entity = SourceBuilding {
         shape: dataset.clone(),
         size: I64Vec2 { x: 1, y: 1 },
         directions: Direction::ALL.to_vec(),
         throughput,
-        limited: false,
+        limited: pool.is_some(),
     }
     .spawn(commands, GridPosition(vec), Orientation::default());
+    if let Some(capacity) = pool {
+        commands.entity(entity).insert(SourcePool::new(capacity));
+    }
 
     commands.entity(entity).insert((
         ZIndex(3),
@@ -473,6 +645,134 @@ fn spawn_source(
         }
         _ => { /* do nothing */ }
     }
+    entity
+}
+
+/// A free cell for a source dropped in by an event. Near the player means around the start area,
+/// otherwise anywhere outside the faction clusters so it isn't stuck behind a lock.
+pub(crate) fn find_event_source_cell(
+    near_player: bool,
+    world_map: &WorldMap,
+    cluster_cells: &ClusterCells,
+    rng: &mut impl Rng,
+) -> Option<I64Vec2> {
+    let (min, max) = if near_player {
+        (-(STARTING_AREA_SIZE + 4), STARTING_AREA_SIZE + 4)
+    } else {
+        (WORLD_MIN, WORLD_MAX)
+    };
+    // plenty of tries, the world is mostly empty
+    for _ in 0..200 {
+        let vec = I64Vec2::new(rng.random_range(min..=max), rng.random_range(min..=max));
+        if !in_world_bounds(vec) || cluster_cells.0.contains_key(&vec) {
+            continue;
+        }
+        if are_positions_free(world_map, &WorldBounds::default(), &[GridPosition(vec)]) {
+            return Some(vec);
+        }
+    }
+    None
+}
+
+/// Source from an interactive event, goes through the same path as generated ones but starts unlocked
+pub(crate) fn spawn_event_source(
+    vec: I64Vec2,
+    dataset: Dataset,
+    faction: Faction,
+    reputation: ReputationLevel,
+    commands: &mut Commands,
+) {
+    // spawn_source only tags the faction on locked sources
+    let entity = spawn_source(vec, get_faction_source_throughput(reputation), dataset, None, None, None, commands);
+    commands.entity(entity).insert((faction, reputation, crate::factions::Unlocked));
+}
+
+/// Faction-coloured overlay on one locked cell, goes away when the cluster unlocks
+pub(crate) fn spawn_lock_cell(
+    vec: I64Vec2,
+    faction: Faction,
+    reputation: ReputationLevel,
+    game_assets: &GameAssets,
+    commands: &mut Commands,
+) {
+    let mut faction_color = game_assets.faction_color(faction);
+    faction_color.set_alpha(0.5);
+    commands.spawn((
+        Locked,
+        GridPosition(vec),
+        GridSprite(faction_color),
+        faction,
+        reputation,
+        Transform::from_xyz(0.0, 0.0, z_layers::LOCK_OVERLAY),
+        LockMarker
+    ));
+}
+
+/// Faction icon in the middle of a locked cluster, the progress bar gets added on top
+pub(crate) fn spawn_cluster_icon(
+    vec: I64Vec2,
+    faction: Faction,
+    reputation: ReputationLevel,
+    game_assets: &GameAssets,
+    commands: &mut Commands,
+) {
+    let icon_index = game_assets.faction_icon(faction, crate::assets::IconSize::Large).map(|(_, idx)| idx).unwrap_or(0);
+    commands.spawn((
+        GridPosition(vec),
+        Sprite {
+            image: game_assets.small_sprites_texture.clone(),
+            texture_atlas: Some(TextureAtlas { layout: game_assets.small_sprites_layout.clone(), index: icon_index }),
+            custom_size: Some(Vec2::splat(128.0)), // Upscale the sprite (default grid size is 64.0)
+            ..Default::default()
+        },
+        Transform::from_xyz(0.0, 0.0, z_layers::LOCK_ICON),
+        faction,
+        reputation,
+        Locked,
+        LockMarker,
+        ClusterIconMarker,
+        // Reputation level text indicator
+        Text2d::new(format!("{:?}", reputation)),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 1.0, 1.0)), // White text
+        ZIndex(70), // Above the sprite
+    ));
+}
+
+/// Whether a cell lies inside the generated world square, for gen code without the resource at hand
+pub fn in_world_bounds(vec: I64Vec2) -> bool {
+    WorldBounds::default().contains(vec)
+}
+
+/// A line around the world and everything past it dimmed, so the edge reads from any zoom
+fn spawn_world_border(mut commands: Commands, grid: Res<crate::grid::Grid>, bounds: Res<WorldBounds>) {
+    let min = grid.grid_to_world_corner(&GridPosition(bounds.min));
+    let max = grid.grid_to_world_corner(&GridPosition(bounds.max + I64Vec2::ONE));
+    let size = max - min;
+    let center = (min + max) / 2.0;
+    let shade = OUTSIDE_SHADE_TILES as f32 * grid.scale;
+
+    // (center, size, color): left and right strips run the full height, top and bottom fill between
+    let rects = [
+        (Vec2::new(min.x - shade / 2.0, center.y), Vec2::new(shade, size.y + 2.0 * shade), OUTSIDE_SHADE_COLOR),
+        (Vec2::new(max.x + shade / 2.0, center.y), Vec2::new(shade, size.y + 2.0 * shade), OUTSIDE_SHADE_COLOR),
+        (Vec2::new(center.x, min.y - shade / 2.0), Vec2::new(size.x, shade), OUTSIDE_SHADE_COLOR),
+        (Vec2::new(center.x, max.y + shade / 2.0), Vec2::new(size.x, shade), OUTSIDE_SHADE_COLOR),
+        (Vec2::new(min.x, center.y), Vec2::new(BORDER_THICKNESS, size.y + BORDER_THICKNESS), BORDER_COLOR),
+        (Vec2::new(max.x, center.y), Vec2::new(BORDER_THICKNESS, size.y + BORDER_THICKNESS), BORDER_COLOR),
+        (Vec2::new(center.x, min.y), Vec2::new(size.x + BORDER_THICKNESS, BORDER_THICKNESS), BORDER_COLOR),
+        (Vec2::new(center.x, max.y), Vec2::new(size.x + BORDER_THICKNESS, BORDER_THICKNESS), BORDER_COLOR),
+    ];
+    for (position, size, color) in rects {
+        commands.spawn((
+            Sprite::from_color(color, size),
+            Transform::from_translation(position.extend(z_layers::WORLD_BORDER)),
+            WorldBorder,
+        ));
+    }
 }
 
 fn in_start_area(vec: I64Vec2) -> bool {
@@ -488,7 +788,7 @@ fn spawn_faction_sink(
     cluster_map: Option<&HashMap<I64Vec2, i64>>,
     cluster_hash_set: Option<&mut HashSet<I64Vec2>>,
     commands: &mut Commands,
-) {
+) -> Entity {
     let mut sink_vecs: Vec<I64Vec2> = Vec::new();
     for x in position.x..=position.x + 1 {
         for y in position.y..=position.y + 1 {
@@ -518,6 +818,7 @@ fn spawn_faction_sink(
     commands
         .entity(sink_building)
         .insert((faction, reputation, Locked, Undeletable));
+    sink_building
 }
 
 fn map_grid_pos_to_faction(vec: I64Vec2) -> Faction {
@@ -549,7 +850,17 @@ fn get_locked_tile_noise(vec: I64Vec2, offset: f32) -> f32 {
     .x + (0.1 * normalised_simplex_noise.powf(BIAS_EXPONENT));
 }
 
-/// System that checks all entities with LockMarker and removes them when the Locked component is removed
+/// How long an unlocked cluster's overlay takes to fade away
+const UNLOCK_FADE_SECONDS: f32 = 0.8;
+
+/// On a lock marker fading out after its cluster unlocked, despawned when it hits zero
+#[derive(Component)]
+pub struct UnlockFade {
+    remaining: f32,
+    start_alpha: f32,
+}
+
+/// System that checks all entities with LockMarker and fades them out when the Locked component is removed
 /// Only runs when entities actually lose their Locked component (optimized with change detection)
 fn cleanup_unlocked_markers(
     mut commands: Commands,
@@ -557,13 +868,141 @@ fn cleanup_unlocked_markers(
     // RemovedComponents<Locked> tracks entities that had Locked removed this frame
     mut removed_locked: RemovedComponents<Locked>,
     // Check if the entity still has LockMarker (so we know it's a lock marker to clean up)
-    lock_markers: Query<(), With<LockMarker>>,
+    lock_markers: Query<Option<&Sprite>, With<LockMarker>>,
 ) {
     // Only process entities that just had their Locked component removed this frame
     for entity in removed_locked.read() {
         // If the entity still has LockMarker, it's a visual marker that should be cleaned up
-        if lock_markers.contains(entity) {
+        if let Ok(sprite) = lock_markers.get(entity) {
+            let start_alpha = sprite.map_or(1.0, |s| s.color.alpha());
+            commands.entity(entity).insert(UnlockFade { remaining: UNLOCK_FADE_SECONDS, start_alpha });
+        }
+    }
+}
+
+/// Fades unlocked overlays (and the icon's label) out rather than popping them
+fn fade_unlocked_markers(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut fading: Query<(Entity, &mut UnlockFade, Option<&mut Sprite>, Option<&mut TextColor>)>,
+) {
+    for (entity, mut fade, sprite, text_color) in fading.iter_mut() {
+        fade.remaining -= time.delta_secs();
+        if fade.remaining <= 0.0 {
             commands.entity(entity).despawn();
+            continue;
+        }
+        let fraction = fade.remaining / UNLOCK_FADE_SECONDS;
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_alpha(fade.start_alpha * fraction);
+        }
+        if let Some(mut text_color) = text_color {
+            text_color.0.set_alpha(fraction);
+        }
+    }
+}
+
+fn compass_name(vec: I64Vec2) -> &'static str {
+    if vec.x.abs() >= vec.y.abs() {
+        if vec.x >= 0 { "east" } else { "west" }
+    } else if vec.y >= 0 {
+        "north"
+    } else {
+        "south"
+    }
+}
+
+/// Adds a progress bar as a child of each cluster icon, so unlock cleanup removes it too
+fn spawn_unlock_progress_bars(
+    mut commands: Commands,
+    query: Query<(Entity, &Faction, &ReputationLevel, &GridPosition), Added<ClusterIconMarker>>,
+    reputations: Res<FactionReputations>,
+) {
+    for (entity, faction, required, grid_pos) in query.iter() {
+        let fraction = reputation_progress_fraction(reputations.get(*faction), *required);
+        let bar = commands
+            .spawn((
+                Sprite {
+                    color: Color::srgba(0.0, 0.0, 0.0, 0.7),
+                    custom_size: Some(Vec2::new(UNLOCK_BAR_WIDTH, UNLOCK_BAR_HEIGHT)),
+                    ..default()
+                },
+                Transform::from_xyz(0.0, -76.0, 1.0),
+                UnlockProgressBar {
+                    faction: *faction,
+                    required: *required,
+                    compass: compass_name(grid_pos.0),
+                    center: *grid_pos,
+                    nudged: false,
+                },
+            ))
+            .with_children(|bar| {
+                bar.spawn((
+                    Sprite {
+                        color: Color::srgb(0.3, 0.8, 0.3),
+                        custom_size: Some(Vec2::new(UNLOCK_BAR_WIDTH * fraction, UNLOCK_BAR_HEIGHT)),
+                        ..default()
+                    },
+                    Transform::from_xyz(-UNLOCK_BAR_WIDTH * (1.0 - fraction) / 2.0, 0.0, 1.0),
+                    UnlockProgressFill,
+                ));
+            })
+            .id();
+        commands.entity(entity).add_child(bar);
+    }
+}
+
+fn update_unlock_progress_bars(
+    mut bar_query: Query<(&mut UnlockProgressBar, &Children)>,
+    mut fill_query: Query<(&mut Sprite, &mut Transform), With<UnlockProgressFill>>,
+    reputations: Res<FactionReputations>,
+    mut news_writer: MessageWriter<AddNewsfeedItemEvent>,
+) {
+    if !reputations.is_changed() {
+        return;
+    }
+    for (mut bar, children) in bar_query.iter_mut() {
+        let score = reputations.get(bar.faction);
+        let fraction = reputation_progress_fraction(score, bar.required);
+        for child in children.iter() {
+            if let Ok((mut sprite, mut transform)) = fill_query.get_mut(child) {
+                sprite.custom_size = Some(Vec2::new(UNLOCK_BAR_WIDTH * fraction, UNLOCK_BAR_HEIGHT));
+                transform.translation.x = -UNLOCK_BAR_WIDTH * (1.0 - fraction) / 2.0;
+            }
+        }
+
+        if bar.take_nudge(score) {
+            news_writer.write(AddNewsfeedItemEvent {
+                faction: bar.faction,
+                headline: format!(
+                    "Almost {} with {:?} - a cluster to the {} will open",
+                    reputation_level_name(bar.required),
+                    bar.faction,
+                    bar.compass
+                ),
+                payload: Some(crate::events::NewsPayload::Position(bar.center)),
+            });
+        }
+    }
+}
+
+fn pulse_unlock_progress_bars(
+    time: Res<Time<Real>>,
+    bar_query: Query<(&UnlockProgressBar, &Children)>,
+    mut fill_query: Query<&mut Sprite, With<UnlockProgressFill>>,
+    reputations: Res<FactionReputations>,
+) {
+    for (bar, children) in bar_query.iter() {
+        let remaining = reputation_level_min_score(bar.required) as i32 - reputations.get(bar.faction);
+        let alpha = if remaining > 0 && remaining <= UNLOCK_NUDGE_POINTS {
+            0.6 + 0.4 * (time.elapsed_secs() * 4.0).sin().abs()
+        } else {
+            1.0
+        };
+        for child in children.iter() {
+            if let Ok(mut sprite) = fill_query.get_mut(child) {
+                sprite.color.set_alpha(alpha);
+            }
         }
     }
 }
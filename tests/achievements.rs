@@ -0,0 +1,82 @@
+use ld58::achievements::{AchievementDef, Comparison, Condition, Counter, RunCounters};
+
+fn parse(ron_str: &str) -> Condition {
+    ron::from_str(ron_str).expect("condition parses")
+}
+
+#[test]
+fn conditions_parse_from_ron() {
+    assert_eq!(
+        parse("Compare(counter: WiresPlaced, op: AtLeast, value: 1.0)"),
+        Condition::Compare { counter: Counter::WiresPlaced, op: Comparison::AtLeast, value: 1.0 }
+    );
+    assert_eq!(
+        parse(r#"All([
+            Compare(counter: BuildingsPlaced("Combiner"), op: AtLeast, value: 1.0),
+            Compare(counter: MoneyEarned, op: AtMost, value: 0.0),
+        ])"#),
+        Condition::All(vec![
+            Condition::Compare {
+                counter: Counter::BuildingsPlaced("Combiner".to_string()),
+                op: Comparison::AtLeast,
+                value: 1.0,
+            },
+            Condition::Compare { counter: Counter::MoneyEarned, op: Comparison::AtMost, value: 0.0 },
+        ])
+    );
+    assert!(ron::from_str::<Condition>("Compare(counter: Wires, op: AtLeast, value: 1.0)").is_err());
+}
+
+#[test]
+fn shipped_achievements_all_parse() {
+    let ron_str = std::fs::read_to_string("assets/text/achievements.ron").unwrap();
+    let achievements: Vec<AchievementDef> = ron::from_str(&ron_str).unwrap();
+    assert!(!achievements.is_empty());
+}
+
+#[test]
+fn comparisons_evaluate_against_the_counters() {
+    let mut counters = RunCounters::default();
+    let at_least = parse("Compare(counter: WiresPlaced, op: AtLeast, value: 2.0)");
+    let at_most = parse("Compare(counter: WiresPlaced, op: AtMost, value: 1.0)");
+    let equal = parse("Compare(counter: WiresPlaced, op: Equal, value: 1.0)");
+
+    assert!(!at_least.evaluate(&counters));
+    assert!(at_most.evaluate(&counters));
+    assert!(!equal.evaluate(&counters));
+
+    counters.record_building("Link");
+    assert!(!at_least.evaluate(&counters));
+    assert!(at_most.evaluate(&counters), "the bound is inclusive");
+    assert!(equal.evaluate(&counters));
+
+    counters.record_building("Link");
+    assert!(at_least.evaluate(&counters));
+    assert!(!at_most.evaluate(&counters));
+    assert!(!equal.evaluate(&counters));
+}
+
+#[test]
+fn all_needs_every_part() {
+    let mut counters = RunCounters::default();
+    let pipeline = parse(r#"All([
+        Compare(counter: BuildingsPlaced("Combiner"), op: AtLeast, value: 1.0),
+        Compare(counter: BuildingsPlaced("Splitter"), op: AtLeast, value: 1.0),
+    ])"#);
+
+    counters.record_building("Combiner 2x1");
+    assert!(!pipeline.evaluate(&counters));
+    counters.record_building("Splitter 3x1");
+    assert!(pipeline.evaluate(&counters), "sizes fold into one kind");
+    assert!(Condition::All(vec![]).evaluate(&RunCounters::default()), "nothing to satisfy");
+}
+
+#[test]
+fn progress_only_for_at_least_targets() {
+    let mut counters = RunCounters::default();
+    counters.money_earned = 2_500;
+    let earn = parse("Compare(counter: MoneyEarned, op: AtLeast, value: 1000.0)");
+    assert_eq!(earn.progress(&counters), Some((1000.0, 1000.0)), "capped at the target");
+    assert_eq!(parse("Compare(counter: MoneyEarned, op: AtMost, value: 10.0)").progress(&counters), None);
+    assert_eq!(Condition::All(vec![earn]).progress(&counters), None);
+}
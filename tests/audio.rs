@@ -0,0 +1,65 @@
+use ld58::audio::{desired_music_phase, MusicDirector, MusicPhase, MusicTrack, GAME_OVER_BANKRUPTCY_STAGE};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn track(phase: MusicPhase, level: f32, target: f32) -> MusicTrack {
+    MusicTrack { phase, level, target, fade_seconds: 3.0 }
+}
+
+#[test]
+fn phase_follows_the_game_state() {
+    assert_eq!(desired_music_phase(false, false, 0), MusicPhase::Calm);
+    assert_eq!(desired_music_phase(true, false, 0), MusicPhase::Tense);
+    assert_eq!(desired_music_phase(false, true, 1), MusicPhase::Tense);
+    // game over wins over everything else
+    assert_eq!(desired_music_phase(true, true, GAME_OVER_BANKRUPTCY_STAGE), MusicPhase::GameOver);
+    assert_eq!(desired_music_phase(false, false, GAME_OVER_BANKRUPTCY_STAGE + 1), MusicPhase::GameOver);
+}
+
+#[test]
+fn director_starts_each_phase_once() {
+    let mut director = MusicDirector::default();
+    assert_eq!(director.begin_transition(), None, "nothing wanted yet");
+
+    director.desired = Some(MusicPhase::Calm);
+    assert_eq!(director.begin_transition(), Some((MusicPhase::Calm, true)), "first track fades in");
+    assert_eq!(director.begin_transition(), None, "already playing");
+
+    director.desired = Some(MusicPhase::Tense);
+    assert_eq!(director.begin_transition(), Some((MusicPhase::Tense, false)), "later ones crossfade");
+    assert_eq!(director.playing, Some(MusicPhase::Tense));
+
+    director.desired = Some(MusicPhase::Calm);
+    assert_eq!(director.begin_transition(), Some((MusicPhase::Calm, false)));
+}
+
+#[test]
+fn crossfade_swaps_levels_and_ends_the_old_track() {
+    let mut old = track(MusicPhase::Calm, 1.0, 0.0);
+    let mut new = track(MusicPhase::Tense, 0.0, 1.0);
+
+    // halfway through both are at half
+    for _ in 0..90 {
+        assert!(!old.step(DT));
+        new.step(DT);
+    }
+    assert!((old.level - 0.5).abs() < 0.02, "old at {}", old.level);
+    assert!((new.level - 0.5).abs() < 0.02, "new at {}", new.level);
+
+    let mut finished = false;
+    for _ in 0..100 {
+        finished |= old.step(DT);
+        new.step(DT);
+    }
+    assert!(finished, "the faded out track reports it's done");
+    assert_eq!(old.level, 0.0);
+    assert_eq!(new.level, 1.0, "the new track settles at full, not past it");
+    assert!(!new.step(DT), "a playing track never counts as finished");
+}
+
+#[test]
+fn zero_length_fade_jumps() {
+    let mut t = MusicTrack { phase: MusicPhase::GameOver, level: 0.0, target: 1.0, fade_seconds: 0.0 };
+    t.step(DT);
+    assert_eq!(t.level, 1.0);
+}
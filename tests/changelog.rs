@@ -0,0 +1,85 @@
+use ld58::changelog::{ChangeCategory, Changelog, ChangelogEntry, ChangelogRelease, Version};
+
+fn version(s: &str) -> Version {
+    Version::parse(s).unwrap_or_else(|| panic!("{s} parses"))
+}
+
+fn changelog(versions: &[&str]) -> Changelog {
+    Changelog {
+        releases: versions
+            .iter()
+            .map(|v| ChangelogRelease {
+                version: v.to_string(),
+                date: String::new(),
+                entries: vec![ChangelogEntry { category: ChangeCategory::Fix, text: format!("fixed in {v}") }],
+            })
+            .collect(),
+    }
+}
+
+fn shown(changelog: &Changelog, last_seen: Option<&str>, current: &str) -> Vec<String> {
+    changelog
+        .unseen(last_seen.map(version), version(current))
+        .iter()
+        .map(|r| r.version.clone())
+        .collect()
+}
+
+#[test]
+fn versions_parse_with_optional_parts() {
+    assert_eq!(version("1.2.3"), Version::new(1, 2, 3));
+    assert_eq!(version("v0.4"), Version::new(0, 4, 0));
+    assert_eq!(version("2"), Version::new(2, 0, 0));
+    assert_eq!(version("1.0.0+build.5"), Version::new(1, 0, 0), "build metadata is dropped");
+    assert_eq!(version("1.0.0-beta.2").pre, ["beta", "2"]);
+    assert_eq!(Version::parse("one.two"), None);
+    assert_eq!(Version::parse("1.0.0-"), None);
+}
+
+#[test]
+fn pre_releases_order_before_their_release() {
+    assert!(version("1.0.0-beta") < version("1.0.0"));
+    assert!(version("0.9.9") < version("1.0.0-alpha"));
+    // the order from the semver spec
+    let ordered = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0",
+    ];
+    for pair in ordered.windows(2) {
+        assert!(version(pair[0]) < version(pair[1]), "{} < {}", pair[0], pair[1]);
+    }
+}
+
+#[test]
+fn upgrade_shows_everything_since_last_seen() {
+    let log = changelog(&["1.2.0", "1.1.0", "1.0.0", "0.9.0"]);
+    assert_eq!(shown(&log, Some("1.0.0"), "1.2.0"), ["1.2.0", "1.1.0"]);
+    // a release further on than the build isn't out yet
+    assert_eq!(shown(&log, Some("1.0.0"), "1.1.0"), ["1.1.0"]);
+    assert!(shown(&log, Some("1.2.0"), "1.2.0").is_empty(), "nothing new on a relaunch");
+}
+
+#[test]
+fn upgrade_from_a_pre_release_shows_the_release() {
+    let log = changelog(&["1.0.0", "1.0.0-beta"]);
+    assert_eq!(shown(&log, Some("1.0.0-beta"), "1.0.0"), ["1.0.0"]);
+}
+
+#[test]
+fn fresh_install_shows_nothing() {
+    let log = changelog(&["1.2.0", "1.1.0"]);
+    assert!(shown(&log, None, "1.2.0").is_empty());
+}
+
+#[test]
+fn downgrade_shows_nothing() {
+    let log = changelog(&["1.2.0", "1.1.0", "1.0.0"]);
+    assert!(shown(&log, Some("1.2.0"), "1.1.0").is_empty());
+    assert!(shown(&log, Some("1.0.0"), "1.0.0-rc.1").is_empty(), "back to a pre-release is a downgrade too");
+}
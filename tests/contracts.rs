@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use bevy::math::I64Vec2;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use ld58::contracts::{match_contribution, sum_contributions, Contract, ContractPacing, ContractStatus};
+use ld58::difficulty::Difficulty;
+use ld58::factions::{Faction, ReputationLevel, Unlocked};
+use ld58::factory::buildings::sink::SinkBuilding;
+use ld58::factory::logical::{BasicDataType, DataAttribute, Dataset};
+use ld58::grid::GridPosition;
+use ld58::headless::headless_app;
+
+/// Checked half a second after each whole second, away from where a beat can land
+fn checkpoints() -> impl Iterator<Item = f32> {
+    (0..=60).map(|second| second as f32 + 0.5)
+}
+
+/// Beats due at each checkpoint, stepping time the way a game running at `fps` would
+fn beats_at_checkpoints(pacing: &ContractPacing, fps: f32) -> Vec<u32> {
+    let dt = 1.0 / fps;
+    let mut elapsed = 0.0;
+    checkpoints()
+        .map(|checkpoint| {
+            while elapsed + dt <= checkpoint {
+                elapsed += dt;
+            }
+            pacing.beats_due(elapsed)
+        })
+        .collect()
+}
+
+#[test]
+fn beats_come_due_the_same_at_any_frame_rate() {
+    let pacing = ContractPacing::default();
+    let fast = beats_at_checkpoints(&pacing, 120.0);
+    assert_eq!(fast, beats_at_checkpoints(&pacing, 20.0));
+    assert_eq!(fast[4], 0, "nothing before the first interval");
+    assert_eq!(fast[5], 1);
+    assert_eq!(*fast.last().unwrap(), 11, "one every five seconds strictly inside the minute");
+}
+
+#[test]
+fn difficulty_spreads_or_packs_the_beats() {
+    let beats = |difficulty: Difficulty| {
+        let pacing = ContractPacing::for_difficulty(&difficulty.multipliers());
+        *beats_at_checkpoints(&pacing, 60.0).last().unwrap()
+    };
+    assert_eq!(beats(Difficulty::Normal), 11);
+    assert!(beats(Difficulty::Easy) > beats(Difficulty::Normal));
+    assert!(beats(Difficulty::Hard) < beats(Difficulty::Normal));
+}
+
+/// Headless at `fps`, with plenty of open sinks and every offer taken as soon as it's made
+/// so the pending cap never holds a beat back. Contracts made by each checkpoint.
+fn offers_at_checkpoints(fps: f64, difficulty: Difficulty) -> Vec<usize> {
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / fps)))
+        .insert_resource(difficulty);
+    for x in 0..40 {
+        app.world_mut().spawn((
+            SinkBuilding { size: I64Vec2::ONE },
+            GridPosition(I64Vec2::new(x * 2, 0)),
+            Faction::Academia,
+            ReputationLevel::Neutral,
+            Unlocked,
+        ));
+    }
+    app.update();
+
+    let mut elapsed = 0.0;
+    let mut counts = Vec::new();
+    for checkpoint in checkpoints() {
+        while elapsed + 1.0 / fps <= checkpoint as f64 {
+            app.update();
+            elapsed += 1.0 / fps;
+            let mut statuses = app.world_mut().query::<&mut ContractStatus>();
+            for mut status in statuses.iter_mut(app.world_mut()) {
+                if *status == ContractStatus::Pending {
+                    *status = ContractStatus::Active;
+                }
+            }
+        }
+        counts.push(app.world_mut().query_filtered::<(), With<Contract>>().iter(app.world()).count());
+    }
+    counts
+}
+
+#[test]
+fn first_minute_offers_match_at_120_and_20_fps() {
+    let fast = offers_at_checkpoints(120.0, Difficulty::Normal);
+    assert_eq!(fast, offers_at_checkpoints(20.0, Difficulty::Normal));
+    assert!(fast[5] >= 1, "first beat by five and a half seconds, got {:?}", fast);
+    assert!(fast.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn first_minute_offers_follow_the_difficulty() {
+    let normal = offers_at_checkpoints(20.0, Difficulty::Normal);
+    let hard = offers_at_checkpoints(20.0, Difficulty::Hard);
+    // same 20s random offers either way, the difference is the first-minute beats
+    assert!(hard.last() < normal.last(), "hard {:?} normal {:?}", hard, normal);
+}
+
+/// A small matrix of what arrives against what's asked for: exact and extra attributes count
+/// in full, missing attributes at the partial rate, other types not at all
+#[test]
+fn contract_matching_credits_attributes() {
+    let dataset = |types: &[(BasicDataType, &[DataAttribute])]| Dataset {
+        contents: types
+            .iter()
+            .map(|(data_type, attributes)| (*data_type, attributes.iter().copied().collect::<HashSet<_>>()))
+            .collect(),
+    };
+    let counted = |incoming: &Dataset, required: &Dataset| -> f64 {
+        match_contribution(incoming, 10.0, required, 0.5).iter().map(|c| c.counted).sum()
+    };
+
+    let cleaned_economic = dataset(&[(BasicDataType::Economic, &[DataAttribute::Cleaned])]);
+    let plain_economic = dataset(&[(BasicDataType::Economic, &[])]);
+    let extra_economic = dataset(&[(BasicDataType::Economic, &[DataAttribute::Cleaned, DataAttribute::DeIdentified])]);
+    let biometric = dataset(&[(BasicDataType::Biometric, &[])]);
+    let mixed = dataset(&[(BasicDataType::Economic, &[DataAttribute::Cleaned]), (BasicDataType::Biometric, &[])]);
+
+    let cases = [
+        (&cleaned_economic, &cleaned_economic, 10.0, "exact match"),
+        (&extra_economic, &cleaned_economic, 10.0, "extra attributes"),
+        (&plain_economic, &cleaned_economic, 5.0, "missing Cleaned"),
+        (&biometric, &cleaned_economic, 0.0, "wrong type"),
+        (&mixed, &cleaned_economic, 10.0, "unrequested type alongside"),
+        (&cleaned_economic, &plain_economic, 10.0, "raw asked, cleaned sent"),
+        (&mixed, &mixed, 10.0, "both types exact"),
+        (&cleaned_economic, &mixed, 5.0, "half the types"),
+        (&plain_economic, &mixed, 2.5, "half the types, short an attribute"),
+    ];
+    for (incoming, required, expected, label) in cases {
+        let got = counted(incoming, required);
+        assert!((got - expected).abs() < 1e-9, "{}: {} counted {} for {}, expected {}", label, incoming, got, required, expected);
+    }
+
+    let missing = match_contribution(&plain_economic, 10.0, &cleaned_economic, 0.5);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].arriving, 10.0);
+    assert!(missing[0].missing.contains(&DataAttribute::Cleaned));
+    // the partial rate is a parameter, not baked in
+    assert_eq!(counted(&plain_economic, &cleaned_economic), 5.0);
+    assert_eq!(match_contribution(&plain_economic, 10.0, &cleaned_economic, 0.0)[0].counted, 0.0);
+
+    let summed = sum_contributions([(&cleaned_economic, 4.0), (&plain_economic, 6.0), (&biometric, 8.0)], &cleaned_economic, 0.5);
+    assert_eq!(summed.len(), 1);
+    assert!((summed[0].counted - 7.0).abs() < 1e-9, "4 exact + 6 at half should be 7, got {}", summed[0].counted);
+    assert_eq!(summed[0].arriving, 10.0);
+    assert!(sum_contributions([], &cleaned_economic, 0.5).iter().all(|c| c.counted == 0.0 && c.arriving == 0.0));
+}
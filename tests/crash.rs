@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use ld58::crash::{format_crash_report, guarded, CrashSnapshot, DisabledSystems};
+
+#[test]
+fn report_has_the_panic_and_the_state() {
+    let snapshot = CrashSnapshot {
+        game_state: "Running".to_string(),
+        money: 1250,
+        contract_count: 3,
+        world_seed: Some(58),
+        trace_tail: vec!["[    12.00] Contracts: accepted".to_string(), "[    13.50] Grid: wire placed".to_string()],
+    };
+    let report = format_crash_report(1_700_000_000, "index out of bounds", "src/grid.rs:42", "main", "<backtrace>", Some(&snapshot));
+    let lines: Vec<_> = report.lines().collect();
+
+    assert_eq!(lines[0], "LD58 crash report");
+    for expected in [
+        "time: 1700000000",
+        "thread: main",
+        "location: src/grid.rs:42",
+        "panic: index out of bounds",
+        "state: Running",
+        "money: 1250",
+        "contracts: 3",
+        "world seed: 58",
+        "last 2 trace entries:",
+        "  [    13.50] Grid: wire placed",
+        "<backtrace>",
+    ] {
+        assert!(lines.contains(&expected), "missing {expected:?} in\n{report}");
+    }
+    let panic = lines.iter().position(|l| l.starts_with("panic:")).unwrap();
+    let backtrace = lines.iter().position(|l| *l == "backtrace:").unwrap();
+    assert!(panic < backtrace, "the panic comes first, the backtrace last");
+}
+
+#[test]
+fn report_without_a_snapshot_says_so() {
+    let report = format_crash_report(0, "boom", "unknown", "unnamed", "", None);
+    assert!(report.contains("no state snapshot (crashed during startup)"));
+    assert!(!report.contains("money:"));
+
+    let unseeded = CrashSnapshot { world_seed: None, ..default() };
+    let report = format_crash_report(0, "boom", "unknown", "unnamed", "", Some(&unseeded));
+    assert!(report.contains("world seed: unknown"));
+    assert!(report.contains("last 0 trace entries:"));
+}
+
+#[derive(Resource, Default)]
+struct Ticks(u32);
+
+fn always_panics() {
+    panic!("deliberate test panic");
+}
+
+fn counts_ticks(mut ticks: ResMut<Ticks>) {
+    ticks.0 += 1;
+}
+
+#[cfg(feature = "crash_guard")]
+#[test]
+fn guarded_panic_disables_only_that_system() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<DisabledSystems>()
+        .init_resource::<Ticks>()
+        .add_systems(Update, (guarded("always_panics", always_panics), guarded("counts_ticks", counts_ticks)));
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(app.world().resource::<DisabledSystems>().0, vec!["always_panics"], "disabled once, not every frame");
+    assert_eq!(app.world().resource::<Ticks>().0, 3, "the other system kept running");
+}
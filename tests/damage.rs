@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::math::I64Vec2;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use ld58::factory::buildings::buildings::Building;
+use ld58::factory::buildings::sink::SinkBuilding;
+use ld58::factory::buildings::source::SourceBuilding;
+use ld58::factory::damage::{repair_segment, wire_repair_cost, Damaged, WIRE_SELF_REPAIR_SECONDS};
+use ld58::factory::logical::{BasicDataType, Dataset, LogicalLink};
+use ld58::factory::physical::{PhysicalLink, LINK_THROUGHPUT};
+use ld58::factory::ConstructBuildingEvent;
+use ld58::game_mode::GameMode;
+use ld58::grid::{Direction, GridPosition, Orientation};
+use ld58::headless::headless_app;
+use ld58::player::Player;
+
+fn construct(app: &mut App, building: Arc<dyn Building>, x: i64) {
+    app.world_mut().write_message(ConstructBuildingEvent {
+        building,
+        grid_position: I64Vec2::new(x, 0),
+        orientation: Orientation::new(Direction::Up, false),
+        replaces: Vec::new(),
+        free: false,
+    });
+}
+
+/// Source at x=0, wires on 1..=2, sink at 3. Returns the app and the wire at x=1
+fn chain() -> (App, Entity) {
+    let mut app = headless_app();
+    app.update();
+    construct(
+        &mut app,
+        Arc::new(SourceBuilding {
+            directions: vec![Direction::Right],
+            throughput: 10.0,
+            limited: false,
+            size: I64Vec2::new(1, 1),
+            shape: Dataset { contents: HashMap::from([(BasicDataType::Behavioural, HashSet::new())]) },
+        }),
+        0,
+    );
+    for x in 1..=2 {
+        construct(&mut app, Arc::new(PhysicalLink { throughput: LINK_THROUGHPUT }), x);
+    }
+    construct(&mut app, Arc::new(SinkBuilding { size: I64Vec2::new(1, 1) }), 3);
+    run(&mut app, 5);
+    let wire = app
+        .world_mut()
+        .query_filtered::<(Entity, &GridPosition), With<PhysicalLink>>()
+        .iter(app.world())
+        .find(|(_, position)| position.0 == I64Vec2::new(1, 0))
+        .map(|(entity, _)| entity)
+        .expect("wire built");
+    (app, wire)
+}
+
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+/// Capacity of the logical link running over `wire`, if the chain's whole
+fn chain_capacity(app: &mut App, wire: Entity) -> Option<f32> {
+    app.world_mut()
+        .query::<&LogicalLink>()
+        .iter(app.world())
+        .find(|link| link.links.contains(&wire))
+        .map(|link| link.throughput)
+}
+
+#[test]
+fn cracked_segment_halves_the_chain() {
+    let (mut app, wire) = chain();
+    assert_eq!(chain_capacity(&mut app, wire), Some(LINK_THROUGHPUT));
+
+    app.world_mut().entity_mut(wire).insert(Damaged::new(1));
+    run(&mut app, 2);
+    assert_eq!(chain_capacity(&mut app, wire), Some(LINK_THROUGHPUT / 2.0), "the min takes the halved segment");
+}
+
+#[test]
+fn broken_segment_breaks_the_chain_and_heals() {
+    let (mut app, wire) = chain();
+    app.world_mut().entity_mut(wire).insert(Damaged::new(2));
+    run(&mut app, 2);
+    assert_eq!(chain_capacity(&mut app, wire), None, "severity 2 tears the link down");
+
+    app.world_mut().entity_mut(wire).remove::<Damaged>();
+    run(&mut app, 2);
+    assert_eq!(chain_capacity(&mut app, wire), Some(LINK_THROUGHPUT), "repair rebuilds it without replacing the wire");
+}
+
+#[test]
+fn broken_segment_repairs_itself() {
+    let (mut app, wire) = chain();
+    app.world_mut()
+        .entity_mut(wire)
+        .insert(Damaged { severity: 2, elapsed: WIRE_SELF_REPAIR_SECONDS - 0.05 });
+    run(&mut app, 3);
+    assert!(app.world().get::<Damaged>(wire).is_none());
+    assert_eq!(chain_capacity(&mut app, wire), Some(LINK_THROUGHPUT));
+}
+
+#[test]
+fn hits_stack_up_to_severity_two() {
+    let (mut app, wire) = chain();
+    for _ in 0..3 {
+        app.world_mut()
+            .run_system_once(move |mut commands: Commands, damaged: Query<&Damaged>| {
+                ld58::factory::damage::damage_segment(&mut commands, wire, 1, damaged.get(wire).ok());
+            })
+            .unwrap();
+    }
+    assert_eq!(app.world().get::<Damaged>(wire).map(|d| d.severity), Some(2));
+}
+
+fn repair(app: &mut App, wire: Entity) -> Option<i32> {
+    app.world_mut()
+        .run_system_once(move |mut commands: Commands, mut player: ResMut<Player>, mode: Res<GameMode>| {
+            repair_segment(&mut commands, wire, &mut player, &mode)
+        })
+        .unwrap()
+}
+
+#[test]
+fn instant_repair_charges_the_repair_cost() {
+    let (mut app, wire) = chain();
+    app.world_mut().entity_mut(wire).insert(Damaged::new(2));
+    app.update();
+    let before = app.world().resource::<Player>().money;
+
+    assert_eq!(repair(&mut app, wire), Some(wire_repair_cost()));
+    assert_eq!(app.world().resource::<Player>().money, before - wire_repair_cost());
+    assert!(app.world().get::<Damaged>(wire).is_none());
+}
+
+#[test]
+fn unaffordable_repair_leaves_the_damage() {
+    let (mut app, wire) = chain();
+    app.world_mut().entity_mut(wire).insert(Damaged::new(2));
+    app.world_mut().resource_mut::<Player>().money = 0;
+
+    assert_eq!(repair(&mut app, wire), None);
+    assert_eq!(app.world().resource::<Player>().money, 0);
+    assert!(app.world().get::<Damaged>(wire).is_some());
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_prng::WyRand;
+use bevy_rand::prelude::EntropyPlugin;
+use ld58::assets::GameAssets;
+use ld58::contracts::{AssociatedWithSink, Contract, ContractStatus};
+use ld58::emissary::{
+    dispatch_emissaries, walk_emissaries, AwaitingEmissary, ContractHighlight, Emissary, EmissaryState,
+};
+use ld58::factions::Faction;
+use ld58::grid::{Grid, WorldMap};
+use ld58::settings::Settings;
+
+const TICK: Duration = Duration::from_millis(100);
+
+/// Dispatch and walking on `MinimalPlugins`, a tenth of a second a frame
+fn emissary_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(EntropyPlugin::<WyRand>::with_seed(58u64.to_le_bytes()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .init_resource::<Settings>()
+        .init_resource::<GameAssets>()
+        .init_resource::<WorldMap>()
+        .insert_resource(Grid { scale: 64.0, base_offset: 0.0 })
+        .add_systems(Update, (dispatch_emissaries, walk_emissaries).chain());
+    app.update();
+    app
+}
+
+fn pending_offer(app: &mut App, faction: Faction) -> Entity {
+    let sink = app.world_mut().spawn_empty().id();
+    app.world_mut()
+        .spawn((Contract, ContractStatus::Pending, faction, AssociatedWithSink(sink)))
+        .id()
+}
+
+fn emissary(app: &mut App) -> Option<Entity> {
+    app.world_mut().query_filtered::<Entity, With<Emissary>>().iter(app.world()).next()
+}
+
+fn state(app: &App, emissary: Entity) -> EmissaryState {
+    app.world().get::<Emissary>(emissary).unwrap().state
+}
+
+#[test]
+fn emissary_walks_in_and_opens_the_offer() {
+    let mut app = emissary_app();
+    // only some offers get walked over, keep making them until one does
+    let mut contract = None;
+    for _ in 0..50 {
+        let offer = pending_offer(&mut app, Faction::Academia);
+        app.update();
+        if emissary(&mut app).is_some() {
+            contract = Some(offer);
+            break;
+        }
+    }
+    let contract = contract.expect("an emissary set out");
+    let walker = emissary(&mut app).unwrap();
+    assert_eq!(app.world().get::<Emissary>(walker).unwrap().contract, contract);
+    assert!(app.world().get::<AwaitingEmissary>(contract).is_some(), "hidden until it arrives");
+
+    let start = app.world().get::<Emissary>(walker).unwrap().cell;
+    // 50 seconds, short of the minute it'd give up at
+    for _ in 0..500 {
+        app.update();
+        if state(&app, walker) == EmissaryState::Departing {
+            break;
+        }
+    }
+    assert_eq!(state(&app, walker), EmissaryState::Departing, "arrived before giving up");
+    let walker_state = app.world().get::<Emissary>(walker).unwrap();
+    assert_ne!(walker_state.cell, start, "it walked");
+    assert!((walker_state.cell - walker_state.target).abs().max_element() <= 2, "stopped next to the factory");
+
+    assert!(app.world().get::<AwaitingEmissary>(contract).is_none(), "the offer's open");
+    assert!(app.world().get::<ContractHighlight>(contract).is_some(), "and its card pulses");
+    // fades out and goes
+    for _ in 0..20 {
+        app.update();
+    }
+    assert!(app.world().get_entity(walker).is_err());
+}
+
+#[test]
+fn ignored_emissary_delivers_quietly() {
+    let mut app = emissary_app();
+    let contract = pending_offer(&mut app, Faction::Criminal);
+    app.world_mut().entity_mut(contract).insert(AwaitingEmissary);
+    // so slow it never gets anywhere
+    let walker = app
+        .world_mut()
+        .spawn((
+            Sprite::default(),
+            Transform::default(),
+            Emissary {
+                faction: Faction::Criminal,
+                contract,
+                cell: I64Vec2::new(30, 0),
+                previous: None,
+                target: I64Vec2::ZERO,
+                step_seconds: 1_000.0,
+                step_progress: 0.0,
+                age: 0.0,
+                state: EmissaryState::Walking,
+                depart_timer: 1.5,
+            },
+        ))
+        .id();
+
+    // 59.5 seconds in it's still waiting
+    for _ in 0..595 {
+        app.update();
+    }
+    assert_eq!(state(&app, walker), EmissaryState::Walking);
+    assert!(app.world().get::<AwaitingEmissary>(contract).is_some());
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(state(&app, walker), EmissaryState::Departing);
+    assert!(app.world().get::<AwaitingEmissary>(contract).is_none(), "the offer turns up anyway");
+    assert!(app.world().get::<ContractHighlight>(contract).is_none(), "without any fuss");
+}
@@ -0,0 +1,46 @@
+use bevy::math::I64Vec2;
+use ld58::factions::{
+    reputation_level_min_score, reputation_progress_fraction, reputation_score_to_level, Faction,
+    ReputationLevel, REPUTATION_BANDS,
+};
+use ld58::grid::GridPosition;
+use ld58::world_gen::UnlockProgressBar;
+
+#[test]
+fn progress_fills_exactly_at_each_band_edge() {
+    for (level, min) in REPUTATION_BANDS.into_iter().filter(|(_, min)| *min > 0) {
+        let just_short = reputation_progress_fraction(min as i32 - 1, level);
+        assert!(just_short < 1.0, "{level:?} full a point early");
+        assert!(reputation_score_to_level(min - 1) < level);
+
+        assert_eq!(reputation_progress_fraction(min as i32, level), 1.0, "{level:?} not full on its edge");
+        assert_eq!(reputation_score_to_level(min), level, "bar and unlock disagree on {level:?}");
+    }
+}
+
+#[test]
+fn progress_fraction_is_proportional_and_clamped() {
+    let trusted = reputation_level_min_score(ReputationLevel::Trusted) as i32;
+    assert!((reputation_progress_fraction(trusted / 2, ReputationLevel::Trusted) - (trusted / 2) as f32 / trusted as f32).abs() < 1e-6);
+    assert_eq!(reputation_progress_fraction(-20, ReputationLevel::Trusted), 0.0);
+    assert_eq!(reputation_progress_fraction(100, ReputationLevel::Trusted), 1.0);
+    // Hostile needs nothing
+    assert_eq!(reputation_progress_fraction(0, ReputationLevel::Hostile), 1.0);
+}
+
+#[test]
+fn unlock_nudge_fires_once() {
+    let mut bar = UnlockProgressBar {
+        faction: Faction::Academia,
+        required: ReputationLevel::Trusted,
+        compass: "west",
+        center: GridPosition(I64Vec2::new(-30, 0)),
+        nudged: false,
+    };
+    let edge = reputation_level_min_score(ReputationLevel::Trusted) as i32;
+    let fired: Vec<bool> = [edge - 10, edge - 6, edge - 5, edge - 3, edge - 5, edge]
+        .into_iter()
+        .map(|score| bar.take_nudge(score))
+        .collect();
+    assert_eq!(fired, [false, false, true, false, false, false]);
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use bevy::math::I64Vec2;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use ld58::contracts::{AssociatedWithSink, Contract, ContractBundle, ContractDescription, ContractFulfillment, ContractStatus, ContractTimeout};
+use ld58::factions::Faction;
+use ld58::factory::buildings::buildings::Building;
+use ld58::factory::buildings::combiner::Combiner;
+use ld58::factory::buildings::sink::SinkBuilding;
+use ld58::factory::buildings::source::SourceBuilding;
+use ld58::factory::buildings::splitter::Splitter;
+use ld58::factory::feedback::{nodes_in_cycles, BuildingGraph, FeedbackLoop};
+use ld58::factory::logical::{BasicDataType, Dataset, SinkDeliveries};
+use ld58::factory::physical::{PhysicalLink, LINK_THROUGHPUT};
+use ld58::factory::{ConstructBuildingEvent, MarkedForRemoval};
+use ld58::grid::{Direction, GridPosition, Orientation};
+use ld58::headless::headless_app;
+
+/// Ten ticks of `HEADLESS_TICK` per second
+const TICKS_PER_SECOND: usize = 10;
+
+fn entities(n: usize) -> Vec<Entity> {
+    let mut world = World::new();
+    (0..n).map(|_| world.spawn_empty().id()).collect()
+}
+
+fn graph(edges: &[(Entity, Entity)]) -> BuildingGraph {
+    let mut graph = BuildingGraph::new();
+    for (from, to) in edges {
+        graph.entry(*from).or_default().insert(*to);
+    }
+    graph
+}
+
+#[test]
+fn two_buildings_feeding_each_other_are_a_cycle() {
+    let e = entities(4);
+    // source -> a <-> b -> sink
+    let graph = graph(&[(e[0], e[1]), (e[1], e[2]), (e[2], e[1]), (e[2], e[3])]);
+    let scope: HashSet<_> = e.iter().copied().collect();
+    assert_eq!(nodes_in_cycles(&graph, &scope), HashSet::from([e[1], e[2]]));
+}
+
+#[test]
+fn chains_and_self_loops() {
+    let e = entities(3);
+    let chain = graph(&[(e[0], e[1]), (e[1], e[2])]);
+    let scope: HashSet<_> = e.iter().copied().collect();
+    assert!(nodes_in_cycles(&chain, &scope).is_empty());
+
+    let self_loop = graph(&[(e[0], e[1]), (e[1], e[1])]);
+    assert_eq!(nodes_in_cycles(&self_loop, &scope), HashSet::from([e[1]]));
+
+    // a loop outside the scope isn't looked at
+    let cycle = graph(&[(e[0], e[1]), (e[1], e[0])]);
+    assert!(nodes_in_cycles(&cycle, &HashSet::from([e[2]])).is_empty());
+}
+
+fn single(data_type: BasicDataType) -> Dataset {
+    Dataset { contents: HashMap::from([(data_type, HashSet::new())]) }
+}
+
+fn place(app: &mut App, building: Arc<dyn Building>, x: i64, y: i64) {
+    app.world_mut().write_message(ConstructBuildingEvent {
+        building,
+        grid_position: I64Vec2::new(x, y),
+        orientation: Orientation::new(Direction::Up, false),
+        replaces: Vec::new(),
+        free: false,
+    });
+    // one at a time, so each wire hooks onto the end of the one before it
+    app.update();
+}
+
+fn wire() -> Arc<dyn Building> {
+    Arc::new(PhysicalLink { throughput: LINK_THROUGHPUT })
+}
+
+fn source(data_type: BasicDataType) -> Arc<dyn Building> {
+    Arc::new(SourceBuilding {
+        directions: vec![Direction::Up],
+        throughput: 10.0,
+        limited: false,
+        size: I64Vec2::new(1, 1),
+        shape: single(data_type),
+    })
+}
+
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+fn find<T: Component>(app: &mut App) -> Entity {
+    app.world_mut().query_filtered::<Entity, With<T>>().single(app.world()).expect("one built")
+}
+
+fn looped(app: &App, building: Entity) -> bool {
+    app.world().get::<FeedbackLoop>(building).is_some()
+}
+
+fn delivered(app: &App, sink: Entity) -> f32 {
+    let deliveries = app.world().get::<SinkDeliveries>(sink).expect("sink tracks deliveries");
+    deliveries.datasets.iter().map(|(_, rate)| rate).sum()
+}
+
+/// Going up: a source into a two-input combiner at (0,0)-(-1,0), straight into a two-output
+/// splitter at (0,1)-(-1,1). The splitter's right output goes up a wire to a sink, its left one
+/// round the west side and back into the combiner's second input, so the two feed each other
+#[test]
+fn two_building_loop_stops_and_recovers() {
+    let mut app = headless_app();
+    app.update();
+    place(&mut app, source(BasicDataType::Behavioural), 0, -1);
+    place(&mut app, Arc::new(Combiner::new(20.0, 2)), 0, 0);
+    place(&mut app, Arc::new(Splitter::new(20.0, 2)), 0, 1);
+    place(&mut app, wire(), 0, 2);
+    place(&mut app, Arc::new(SinkBuilding { size: I64Vec2::new(1, 1) }), 0, 3);
+    for (x, y) in [(-1, 2), (-2, 2), (-2, 1), (-2, 0), (-2, -1), (-1, -1)] {
+        place(&mut app, wire(), x, y);
+    }
+    run(&mut app, 5);
+
+    let combiner = find::<Combiner>(&mut app);
+    let splitter = find::<Splitter>(&mut app);
+    let sink = find::<SinkBuilding>(&mut app);
+    let mut dataset = single(BasicDataType::Behavioural);
+    dataset.contents.insert(BasicDataType::Economic, HashSet::new());
+    app.world_mut().spawn((
+        ContractBundle {
+            contract: Contract,
+            status: ContractStatus::Active,
+            dataset,
+            faction: Faction::Corporate,
+            timeout: ContractTimeout(120.0),
+            description: ContractDescription { name: "Loop test".to_string(), description: String::new() },
+            fulfillment_info: ContractFulfillment::new(1.0, 10.0),
+        },
+        AssociatedWithSink(sink),
+    ));
+
+    assert!(looped(&app, combiner) && looped(&app, splitter), "both ends of the loop are marked");
+    assert!(!looped(&app, sink), "the sink hangs off the loop, it isn't in it");
+    run(&mut app, 3 * TICKS_PER_SECOND);
+    assert_eq!(delivered(&app, sink), 0.0, "nothing comes out of a loop");
+
+    // break it where it comes back in, and feed that input from a second source instead
+    let return_wire = app
+        .world_mut()
+        .query_filtered::<(Entity, &GridPosition), With<PhysicalLink>>()
+        .iter(app.world())
+        .find(|(_, position)| position.0 == I64Vec2::new(-1, -1))
+        .map(|(entity, _)| entity)
+        .expect("return wire built");
+    app.world_mut().entity_mut(return_wire).insert(MarkedForRemoval);
+    run(&mut app, 3);
+    place(&mut app, source(BasicDataType::Economic), -1, -1);
+    run(&mut app, 3 * TICKS_PER_SECOND);
+
+    assert!(!looped(&app, combiner) && !looped(&app, splitter), "markers come off with the loop");
+    assert!(delivered(&app, sink) > 0.0, "flow through to the sink again");
+}
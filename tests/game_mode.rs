@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use ld58::factions::{Faction, FactionReputations};
+use ld58::factory::buildings::buildings::Building;
+use ld58::factory::physical::{PhysicalLink, LINK_THROUGHPUT};
+use ld58::factory::ConstructBuildingEvent;
+use ld58::game_mode::GameMode;
+use ld58::grid::{Direction, Orientation};
+use ld58::headless::headless_app;
+use ld58::pause::GameState;
+use ld58::player::Player;
+
+fn mode_app(mode: GameMode) -> App {
+    let mut app = headless_app();
+    app.insert_resource(mode);
+    app.update();
+    app
+}
+
+/// Money spent on one wire
+fn wire_cost(app: &mut App) -> i32 {
+    let before = app.world().resource::<Player>().money;
+    let wire = PhysicalLink { throughput: LINK_THROUGHPUT };
+    app.world_mut().write_message(ConstructBuildingEvent {
+        building: Arc::new(wire),
+        grid_position: I64Vec2::new(5, 5),
+        orientation: Orientation::new(Direction::Up, false),
+        replaces: Vec::new(),
+        free: false,
+    });
+    app.update();
+    before - app.world().resource::<Player>().money
+}
+
+#[test]
+fn placement_cost_and_affordability() {
+    let normal = GameMode::default();
+    let sandbox = GameMode::sandbox();
+    assert_eq!(normal.placement_cost(25), 25);
+    assert_eq!(sandbox.placement_cost(25), 0);
+    assert!(!normal.can_afford(25, 10));
+    assert!(normal.can_afford(25, 25));
+    assert!(sandbox.can_afford(25, -1000), "sandbox builds on any balance");
+}
+
+#[test]
+fn upkeep_locking_and_records() {
+    let normal = GameMode::default();
+    let sandbox = GameMode::sandbox();
+    assert!(normal.upkeep_enabled() && !sandbox.upkeep_enabled());
+    assert!(normal.starts_locked() && !sandbox.starts_locked());
+    assert!(normal.counts_for_records() && !sandbox.counts_for_records());
+}
+
+#[test]
+fn contract_scheduling() {
+    assert!(GameMode::default().contracts_enabled());
+    assert!(GameMode::sandbox().contracts_enabled());
+    let quiet = GameMode { sandbox: true, contract_generation: false };
+    assert!(!quiet.contracts_enabled());
+    // the switch only means anything in sandbox
+    let normal = GameMode { sandbox: false, contract_generation: false };
+    assert!(normal.contracts_enabled());
+}
+
+#[test]
+fn construction_charges_only_outside_sandbox() {
+    let mut normal = mode_app(GameMode::default());
+    let cost = PhysicalLink { throughput: LINK_THROUGHPUT }.data().cost;
+    assert_eq!(wire_cost(&mut normal), cost);
+
+    let mut sandbox = mode_app(GameMode::sandbox());
+    assert_eq!(wire_cost(&mut sandbox), 0);
+}
+
+/// Reputation's set when the run leaves the setup screen, after the mode's been picked there
+fn reputation_after_setup(mode: GameMode) -> i32 {
+    let mut app = headless_app();
+    app.update();
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Setup);
+    app.update();
+    app.insert_resource(mode);
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Running);
+    app.update();
+    app.world().resource::<FactionReputations>().get(Faction::Criminal)
+}
+
+#[test]
+fn reputation_init_follows_the_mode_picked_at_setup() {
+    assert_eq!(reputation_after_setup(GameMode::sandbox()), 100);
+    assert_eq!(reputation_after_setup(GameMode::default()), FactionReputations::default().get(Faction::Criminal));
+}
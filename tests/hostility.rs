@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy_prng::WyRand;
+use ld58::camera::ShakeScreen;
+use ld58::events::{AddNewsfeedItemEvent, InteractiveEventLibrary};
+use ld58::factions::{reputation_level_min_score, Faction, FactionReputations, ReputationLevel};
+use ld58::factory::physical::{PhysicalLink, LINK_THROUGHPUT};
+use ld58::factory::ConstructBuildingEvent;
+use ld58::grid::{Direction, Orientation};
+use ld58::headless::headless_app;
+use ld58::hostility::{
+    roll_interval, Hostility, HostilityPlugin, ESCALATION_SECONDS, MAX_ESCALATION, SABOTAGE_INTERVAL_SECONDS,
+};
+use rand::SeedableRng;
+
+/// Ten ticks of `HEADLESS_TICK` per second
+const TICKS_PER_SECOND: usize = 10;
+
+#[test]
+fn sabotage_comes_faster_the_longer_hostility_lasts() {
+    let mut rng = WyRand::seed_from_u64(58);
+    let (start_min, start_max) = (*SABOTAGE_INTERVAL_SECONDS.start(), *SABOTAGE_INTERVAL_SECONDS.end());
+    for _ in 0..100 {
+        let interval = roll_interval(0.0, &mut rng);
+        assert!((start_min..=start_max).contains(&interval), "{interval}s at the start");
+
+        // one escalation step in, twice as often
+        let interval = roll_interval(ESCALATION_SECONDS, &mut rng);
+        assert!((start_min / 2.0..=start_max / 2.0).contains(&interval), "{interval}s after one step");
+
+        // capped however long it goes on
+        let interval = roll_interval(100.0 * ESCALATION_SECONDS, &mut rng);
+        let (min, max) = (start_min / MAX_ESCALATION, start_max / MAX_ESCALATION);
+        assert!((min - 1e-3..=max + 1e-3).contains(&interval), "{interval}s long into it");
+    }
+}
+
+#[derive(Resource, Default)]
+struct Sabotages(usize);
+
+fn count_sabotages(mut news: MessageReader<AddNewsfeedItemEvent>, mut sabotages: ResMut<Sabotages>) {
+    sabotages.0 += news.read().filter(|item| item.headline.starts_with("Saboteurs")).count();
+}
+
+/// Headless with hostility on top, a placed wire to cut and nothing else to sabotage with
+fn hostile_app() -> App {
+    let mut app = headless_app();
+    app.insert_resource(InteractiveEventLibrary::new(Vec::new()))
+        .init_resource::<Sabotages>()
+        .add_message::<ShakeScreen>()
+        .add_plugins(HostilityPlugin)
+        .add_systems(Last, count_sabotages);
+    app.update();
+    app.world_mut().write_message(ConstructBuildingEvent {
+        building: Arc::new(PhysicalLink { throughput: LINK_THROUGHPUT }),
+        grid_position: I64Vec2::ZERO,
+        orientation: Orientation::new(Direction::Up, false),
+        replaces: Vec::new(),
+        free: false,
+    });
+    app.update();
+    app
+}
+
+fn run_seconds(app: &mut App, seconds: usize) {
+    for _ in 0..seconds * TICKS_PER_SECOND {
+        app.update();
+    }
+}
+
+fn set_level(app: &mut App, faction: Faction, level: ReputationLevel) {
+    let score = reputation_level_min_score(level) as i32;
+    app.world_mut().resource_mut::<FactionReputations>().set(faction, score);
+}
+
+#[test]
+fn sabotage_stops_once_back_at_untrusted() {
+    let mut app = hostile_app();
+    set_level(&mut app, Faction::Corporate, ReputationLevel::Hostile);
+    // past the longest first wait
+    run_seconds(&mut app, *SABOTAGE_INTERVAL_SECONDS.end() as usize + 1);
+    assert!(app.world().resource::<Hostility>().spell(Faction::Corporate).is_some());
+    assert!(app.world().resource::<Sabotages>().0 >= 1, "a hostile faction strikes");
+
+    set_level(&mut app, Faction::Corporate, ReputationLevel::Untrusted);
+    app.update();
+    assert!(app.world().resource::<Hostility>().spell(Faction::Corporate).is_none());
+    app.world_mut().resource_mut::<Sabotages>().0 = 0;
+    run_seconds(&mut app, 2 * *SABOTAGE_INTERVAL_SECONDS.end() as usize);
+    assert_eq!(app.world().resource::<Sabotages>().0, 0, "nothing more once it's let go");
+}
@@ -0,0 +1,70 @@
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy_prng::WyRand;
+use ld58::factory::logical::{BasicDataType, DataAttribute, Dataset};
+use ld58::market::{MarketPrices, MAX_MARKET_MULTIPLIER, MIN_MARKET_MULTIPLIER};
+use rand::SeedableRng;
+
+fn dataset(types: &[(BasicDataType, &[DataAttribute])]) -> Dataset {
+    Dataset {
+        contents: types
+            .iter()
+            .map(|(t, attributes)| (*t, attributes.iter().copied().collect::<HashSet<_>>()))
+            .collect::<HashMap<_, _>>(),
+    }
+}
+
+#[test]
+fn random_walk_stays_in_bounds() {
+    let mut prices = MarketPrices::default();
+    let mut rng = WyRand::seed_from_u64(58);
+    for _ in 0..10_000 {
+        prices.step(&mut rng);
+        for (data_type, multiplier) in prices.multipliers.iter() {
+            assert!(
+                (MIN_MARKET_MULTIPLIER..=MAX_MARKET_MULTIPLIER).contains(multiplier),
+                "{data_type:?} walked to {multiplier}"
+            );
+        }
+    }
+}
+
+#[test]
+fn random_walk_is_deterministic_per_seed() {
+    let walk = |seed| {
+        let mut prices = MarketPrices::default();
+        let mut rng = WyRand::seed_from_u64(seed);
+        (0..50).for_each(|_| { prices.step(&mut rng); });
+        let mut multipliers: Vec<_> = prices.multipliers.into_iter().collect();
+        multipliers.sort_by_key(|(t, _)| *t);
+        multipliers
+    };
+    assert_eq!(walk(7), walk(7));
+}
+
+#[test]
+fn market_shock_applies_exactly() {
+    let mut prices = MarketPrices::default();
+    prices.shock(BasicDataType::Biometric, 0.25);
+    assert_eq!(prices.get(BasicDataType::Biometric), 1.25);
+    assert_eq!(prices.previous(BasicDataType::Biometric), 1.0);
+    assert_eq!(prices.get(BasicDataType::Economic), 1.0, "other types are left alone");
+
+    prices.shock(BasicDataType::Biometric, 5.0);
+    assert_eq!(prices.get(BasicDataType::Biometric), MAX_MARKET_MULTIPLIER);
+}
+
+#[test]
+fn two_type_payout_uses_the_average_multiplier() {
+    let mut prices = MarketPrices::default();
+    prices.set(BasicDataType::Biometric, 1.5);
+    prices.set(BasicDataType::Economic, 0.7);
+    // attributes don't tip the balance
+    let contract = dataset(&[
+        (BasicDataType::Biometric, &[]),
+        (BasicDataType::Economic, &[DataAttribute::Cleaned]),
+    ]);
+
+    assert!((prices.dataset_multiplier(&contract) - 1.1).abs() < 1e-6);
+    assert!((prices.payout(10.0, &contract) - 11.0).abs() < 1e-5);
+    assert_eq!(prices.payout(10.0, &dataset(&[])), 10.0);
+}
@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use bevy::camera::{CameraProjection, RenderTargetInfo};
+use bevy::ecs::message::Messages;
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use ld58::factory::buildings::aggregator::Aggregator;
+use ld58::factory::buildings::buildings::Building;
+use ld58::factory::buildings::trunk_link::TrunkLink;
+use ld58::factory::ConstructBuildingEvent;
+use ld58::game_mode::GameMode;
+use ld58::grid::{Direction, Grid, GridPosition, Orientation, WorldMap};
+use ld58::player::Player;
+use ld58::settings::Settings;
+use ld58::ui::shop::{
+    handle_placement_click, update_selected_building_position, BuildingOrientation, PlacementState, SelectedBuilding,
+    SelectedBuildingType,
+};
+use ld58::world_gen::WorldBounds;
+
+const GRID_SCALE: f32 = 64.0;
+/// Where the camera looks, so every cell the tests use is on screen
+const CAMERA_CENTRE: Vec2 = Vec2::new(320.0, 0.0);
+
+/// What the mouse does on a frame, on top of where the cursor is
+#[derive(Clone, Copy)]
+enum Mouse {
+    Idle,
+    Press,
+    Hold,
+    Release,
+}
+
+/// The ghost and the placement click on `MinimalPlugins`, with a window to move the cursor over.
+/// There's no renderer, so the camera's viewport is filled in by hand
+fn placement_app(building: Arc<dyn Building>) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<WorldMap>()
+        .init_resource::<WorldBounds>()
+        .init_resource::<PlacementState>()
+        .init_resource::<Player>()
+        .init_resource::<GameMode>()
+        .init_resource::<Settings>()
+        .insert_resource(Grid { scale: GRID_SCALE, base_offset: 0.0 })
+        .insert_resource(SelectedBuildingType(Some(building)))
+        .add_message::<ConstructBuildingEvent>()
+        .add_systems(Update, (update_selected_building_position, handle_placement_click).chain());
+    app.world_mut().spawn((
+        SelectedBuilding,
+        BuildingOrientation(Orientation::new(Direction::Right, false)),
+        Sprite::default(),
+    ));
+    let window = Window::default();
+    let size = Vec2::new(window.width(), window.height());
+    app.world_mut().spawn((window, PrimaryWindow));
+    let mut projection = OrthographicProjection::default_2d();
+    projection.update(size.x, size.y);
+    let mut camera = Camera::default();
+    camera.computed.target_info = Some(RenderTargetInfo { physical_size: size.as_uvec2(), scale_factor: 1.0 });
+    camera.computed.clip_from_view = projection.get_clip_from_view();
+    // no TransformPlugin either, the global transform is what the cursor maths reads
+    app.world_mut().spawn((
+        Camera2d,
+        camera,
+        Projection::Orthographic(projection),
+        Transform::from_translation(CAMERA_CENTRE.extend(0.0)),
+        GlobalTransform::from_translation(CAMERA_CENTRE.extend(0.0)),
+    ));
+    app.update();
+    app
+}
+
+/// Puts the cursor over the middle of `cell`
+fn point_at(app: &mut App, cell: I64Vec2) {
+    let world = (cell.as_vec2() + 0.5) * GRID_SCALE;
+    let mut windows = app.world_mut().query::<&mut Window>();
+    let mut window = windows.single_mut(app.world_mut()).unwrap();
+    let centre = Vec2::new(window.width(), window.height()) / 2.0;
+    let offset = world - CAMERA_CENTRE;
+    // screen y runs down
+    window.set_cursor_position(Some(centre + Vec2::new(offset.x, -offset.y)));
+}
+
+/// One frame with the cursor over `cell`, returning the placements it sent out
+fn frame(app: &mut App, cell: I64Vec2, mouse: Mouse) -> Vec<I64Vec2> {
+    frame_events(app, cell, mouse).into_iter().map(|(cell, _)| cell).collect()
+}
+
+/// Same as `frame`, keeping the orientation each placement went out with
+fn frame_events(app: &mut App, cell: I64Vec2, mouse: Mouse) -> Vec<(I64Vec2, Orientation)> {
+    point_at(app, cell);
+    {
+        let mut buttons = app.world_mut().resource_mut::<ButtonInput<MouseButton>>();
+        match mouse {
+            Mouse::Press => buttons.press(MouseButton::Left),
+            Mouse::Release => buttons.release(MouseButton::Left),
+            Mouse::Idle | Mouse::Hold => {}
+        }
+    }
+    app.update();
+    let sent = app
+        .world()
+        .resource::<Messages<ConstructBuildingEvent>>()
+        .iter_current_update_messages()
+        .map(|event| (event.grid_position, event.orientation))
+        .collect();
+    // what InputPlugin would do at the start of the next frame
+    app.world_mut().resource_mut::<ButtonInput<MouseButton>>().clear();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().clear();
+    sent
+}
+
+fn hold_shift(app: &mut App) {
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::ShiftLeft);
+}
+
+/// Something's still picked and its ghost is still following the cursor
+fn selection_active(app: &mut App) -> bool {
+    let ghosts = app.world_mut().query_filtered::<(), With<SelectedBuilding>>().iter(app.world()).count();
+    app.world().resource::<SelectedBuildingType>().0.is_some() && ghosts == 1
+}
+
+#[test]
+fn one_click_while_moving_places_once() {
+    let mut app = placement_app(Arc::new(TrunkLink::default()));
+    hold_shift(&mut app);
+    // pressed on one cell, the cursor's moved on by the time it's let go
+    let script = [
+        (I64Vec2::new(0, 0), Mouse::Press),
+        (I64Vec2::new(1, 0), Mouse::Hold),
+        (I64Vec2::new(2, 0), Mouse::Release),
+        (I64Vec2::new(3, 0), Mouse::Idle),
+    ];
+    let placed: Vec<_> = script.iter().flat_map(|(cell, mouse)| frame(&mut app, *cell, *mouse)).collect();
+    assert_eq!(placed.len(), 1);
+    assert_eq!(placed[0], I64Vec2::new(0, 0), "placed where the ghost was on the press");
+}
+
+#[test]
+fn quick_clicks_place_once_per_click() {
+    let mut app = placement_app(Arc::new(TrunkLink::default()));
+    hold_shift(&mut app);
+    let mut placed = 0;
+    for x in [0, 3, 6] {
+        placed += frame(&mut app, I64Vec2::new(x, 0), Mouse::Press).len();
+        placed += frame(&mut app, I64Vec2::new(x, 0), Mouse::Release).len();
+    }
+    assert_eq!(placed, 3);
+}
+
+#[test]
+fn double_click_on_one_cell_is_debounced() {
+    let mut app = placement_app(Arc::new(TrunkLink::default()));
+    hold_shift(&mut app);
+    let mut placed = 0;
+    placed += frame(&mut app, I64Vec2::ZERO, Mouse::Press).len();
+    placed += frame(&mut app, I64Vec2::ZERO, Mouse::Release).len();
+    placed += frame(&mut app, I64Vec2::ZERO, Mouse::Press).len();
+    assert_eq!(placed, 1, "second press within the debounce window on the same cell");
+}
+
+#[test]
+fn held_button_over_a_bigger_building_doesnt_repeat() {
+    let mut app = placement_app(Arc::new(TrunkLink::default()));
+    hold_shift(&mut app);
+    let mut placed = frame(&mut app, I64Vec2::ZERO, Mouse::Press).len();
+    for x in 1..6 {
+        placed += frame(&mut app, I64Vec2::new(x * 2, 0), Mouse::Hold).len();
+    }
+    assert_eq!(placed, 1, "only 1x1 buildings sweep");
+}
+
+#[test]
+fn shift_clicks_chain_copies_with_the_same_orientation() {
+    let mut app = placement_app(Arc::new(TrunkLink::default()));
+    hold_shift(&mut app);
+    let mut placed = Vec::new();
+    for x in [0, 1] {
+        placed.extend(frame_events(&mut app, I64Vec2::new(x, 0), Mouse::Press));
+        placed.extend(frame_events(&mut app, I64Vec2::new(x, 0), Mouse::Release));
+    }
+    assert_eq!(placed.iter().map(|(cell, _)| *cell).collect::<Vec<_>>(), vec![I64Vec2::new(0, 0), I64Vec2::new(1, 0)]);
+    let ghost = Orientation::new(Direction::Right, false);
+    assert!(placed.iter().all(|(_, orientation)| *orientation == ghost));
+    assert!(selection_active(&mut app), "shift keeps the ghost for the next copy");
+}
+
+#[test]
+fn click_without_shift_ends_the_selection() {
+    let mut app = placement_app(Arc::new(TrunkLink::default()));
+    assert!(selection_active(&mut app));
+    let mut placed = frame(&mut app, I64Vec2::ZERO, Mouse::Press).len();
+    placed += frame(&mut app, I64Vec2::ZERO, Mouse::Release).len();
+    assert_eq!(placed, 1);
+    assert!(!selection_active(&mut app));
+}
+
+#[test]
+fn sweep_ends_the_selection_when_let_go() {
+    let mut app = placement_app(Arc::new(Aggregator::new(5.0)));
+    let mut placed = frame(&mut app, I64Vec2::ZERO, Mouse::Press);
+    placed.extend(frame(&mut app, I64Vec2::new(2, 0), Mouse::Hold));
+    assert_eq!(placed, [I64Vec2::new(0, 0), I64Vec2::new(1, 0), I64Vec2::new(2, 0)]);
+    assert!(selection_active(&mut app), "kept while the button's down");
+    frame(&mut app, I64Vec2::new(2, 0), Mouse::Release);
+    assert!(!selection_active(&mut app));
+}
+
+#[test]
+fn blocked_click_keeps_the_selection() {
+    let mut app = placement_app(Arc::new(Aggregator::new(5.0)));
+    let occupant = app.world_mut().spawn_empty().id();
+    app.world_mut().resource_mut::<WorldMap>().0.insert(GridPosition(I64Vec2::ZERO), vec![occupant]);
+
+    let mut placed = frame(&mut app, I64Vec2::ZERO, Mouse::Press).len();
+    placed += frame(&mut app, I64Vec2::ZERO, Mouse::Release).len();
+    assert_eq!(placed, 0);
+    assert!(selection_active(&mut app), "nothing went down, so nothing to end");
+}
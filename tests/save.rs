@@ -0,0 +1,40 @@
+use ld58::factory::logical::BasicDataType;
+use ld58::game_mode::GameMode;
+use ld58::save::SaveGame;
+
+/// A save from before the run settings were kept, everything else empty
+const OLD_SAVE: &str = r#"(
+    version: "0.1.0",
+    money: 500,
+    current_year: 2,
+    bankruptcy_stage: 0,
+    reputations: [],
+    buildings: [],
+    sources: [],
+    sinks: [],
+    lock_cells: [],
+    clusters: [],
+    contracts: [],
+)"#;
+
+#[test]
+fn older_saves_load_as_normal_runs() {
+    let save: SaveGame = ron::from_str(OLD_SAVE).unwrap();
+    assert_eq!(save.mode, GameMode::default());
+    assert_eq!(save.seed, None);
+    assert!(save.market.is_empty(), "the market starts flat");
+}
+
+#[test]
+fn run_settings_survive_a_round_trip() {
+    let mut save: SaveGame = ron::from_str(OLD_SAVE).unwrap();
+    save.mode = GameMode { sandbox: true, contract_generation: false };
+    save.seed = Some(1234);
+    save.market = vec![(BasicDataType::Biometric, 1.4), (BasicDataType::Economic, 0.6)];
+
+    let text = ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default()).unwrap();
+    let loaded: SaveGame = ron::from_str(&text).unwrap();
+    assert_eq!(loaded.mode, save.mode);
+    assert_eq!(loaded.seed, Some(1234));
+    assert_eq!(loaded.market, save.market);
+}
@@ -0,0 +1,792 @@
+use std::sync::Arc;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::math::I64Vec2;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use ld58::assets::GameAssets;
+use ld58::contracts::{
+    AssociatedWithSink, Contract, ContractBundle, ContractDescription, ContractFulfillment,
+    ContractFulfillmentStatus, ContractStatus, ContractTimeout, FAILED_CONTRACT_REPUTATION_PENALTY,
+    FAILING_CONTRACT_SECONDS,
+};
+use ld58::controls::InputBindings;
+use ld58::economics::{contract_infra_share, ContractEarnings, EconomicsPlugin, SinkInfraCost};
+use ld58::events::{
+    BuildingCounts, EventChoice, EventState, InteractiveEventData, PlayerChoiceEvent, ShowInteractiveEvent,
+};
+use ld58::factions::{Faction, FactionReputations, ReputationLevel};
+use ld58::factory::buildings::aggregator::{aggregate_dataset, can_aggregate, DEFAULT_MIN_AGGREGATE_TYPES};
+use ld58::factory::buildings::buildings::Building;
+use ld58::factory::buildings::combiner::Combiner;
+use ld58::factory::buildings::sink::SinkBuilding;
+use ld58::factory::buildings::source::SourceBuilding;
+use ld58::factory::buildings::splitter::Splitter;
+use ld58::factory::buildings::{Tile, Undeletable};
+use ld58::factory::logical::{allocate_fairly, BasicDataType, DataAttribute, Dataset, LogicalLink, SinkDeliveries};
+use ld58::factory::physical::{ConnectionPassStats, PhysicalLink, PhysicalSink, PhysicalSource, LINK_THROUGHPUT};
+use ld58::factory::undo::{RemovalRecord, UndoBuffer};
+use ld58::factory::{ConstructBuildingEvent, MarkedForRemoval};
+use ld58::game_mode::GameMode;
+use ld58::grid::{are_positions_free, calculate_occupied_cells_rotated, Direction, GridPosition, Orientation, WorldMap};
+use ld58::headless::headless_app;
+use ld58::lod::ClusterBounds;
+use ld58::pause::GameState;
+use ld58::player::Player;
+use ld58::save::{save_path, BuildingDescriptor, LoadGameRequest, SaveGamePlugin, SaveGameRequest};
+use ld58::trace::{TraceCategory, TraceFilter, TraceLog};
+use ld58::ui::interactive_event::{
+    handle_choice_click, open_waiting_urgent_event, route_events_by_urgency, EventChoiceButton, InteractiveEventModal,
+    ModalSpawnCooldown, QueuedEvents, StoredEventData,
+};
+use ld58::ui::shop::wire_refund;
+use ld58::world_gen::WorldBounds;
+
+const SOURCE_THROUGHPUT: f32 = 10.0;
+/// Low enough that the source alone exceeds it, high enough that the sink cap doesn't bind
+const CONTRACT_THRESHOLD: f64 = 4.5;
+const CONTRACT_MONEY: f64 = 10.0;
+/// Ten ticks of `HEADLESS_TICK` per second
+const TICKS_PER_SECOND: usize = 10;
+
+type ShareQuery<'w, 's> = Query<'w, 's, (Entity, &'static ContractStatus, &'static ContractFulfillment, &'static AssociatedWithSink)>;
+
+fn behavioural() -> Dataset {
+    Dataset {
+        contents: HashMap::from([(BasicDataType::Behavioural, HashSet::new())]),
+    }
+}
+
+fn construct(app: &mut App, building: Arc<dyn Building>, x: i64) {
+    construct_at(app, building, I64Vec2::new(x, 0));
+}
+
+fn construct_at(app: &mut App, building: Arc<dyn Building>, cell: I64Vec2) {
+    app.world_mut().write_message(ConstructBuildingEvent {
+        building,
+        grid_position: cell,
+        orientation: Orientation::new(Direction::Up, false),
+        replaces: Vec::new(),
+        free: false,
+    });
+}
+
+/// Source at x=0 facing right, wire on 1..=2, sink at 3, all going through construction
+fn spawn_chain(app: &mut App) -> Entity {
+    construct(
+        app,
+        Arc::new(SourceBuilding {
+            directions: vec![Direction::Right],
+            throughput: SOURCE_THROUGHPUT,
+            limited: false,
+            size: I64Vec2::new(1, 1),
+            shape: behavioural(),
+        }),
+        0,
+    );
+    for x in 1..=2 {
+        construct(app, Arc::new(PhysicalLink { throughput: LINK_THROUGHPUT }), x);
+    }
+    construct(app, Arc::new(SinkBuilding { size: I64Vec2::new(1, 1) }), 3);
+    // one to spawn, a few more for connections and logical links to settle
+    run(app, 5);
+
+    app.world_mut()
+        .query_filtered::<Entity, With<SinkBuilding>>()
+        .single(app.world())
+        .expect("one sink built")
+}
+
+fn offer_contract(app: &mut App, sink: Entity, total_required: f64) -> Entity {
+    app.world_mut()
+        .spawn((
+            ContractBundle {
+                contract: Contract,
+                status: ContractStatus::Active,
+                dataset: behavioural(),
+                faction: Faction::Corporate,
+                timeout: ContractTimeout(120.0),
+                description: ContractDescription {
+                    name: "Test contract".to_string(),
+                    description: String::new(),
+                },
+                fulfillment_info: ContractFulfillment::new(CONTRACT_THRESHOLD, CONTRACT_MONEY)
+                    .with_total_required(total_required),
+            },
+            AssociatedWithSink(sink),
+        ))
+        .id()
+}
+
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+fn delivered_per_sec(app: &App, sink: Entity) -> f32 {
+    let deliveries = app.world().get::<SinkDeliveries>(sink).expect("sink tracks deliveries");
+    deliveries.datasets.iter().map(|(_, rate)| rate).sum()
+}
+
+fn setup() -> (App, Entity) {
+    let mut app = headless_app();
+    app.update();
+    let sink = spawn_chain(&mut app);
+    (app, sink)
+}
+
+#[test]
+fn chain_delivers_source_throughput() {
+    let (mut app, sink) = setup();
+    let contract = offer_contract(&mut app, sink, f64::INFINITY);
+    run(&mut app, 10 * TICKS_PER_SECOND);
+
+    let rate = delivered_per_sec(&app, sink);
+    assert!(
+        (rate - SOURCE_THROUGHPUT).abs() < SOURCE_THROUGHPUT * 0.1,
+        "sink should take what the source makes, got {rate}"
+    );
+
+    let fulfillment = app.world().get::<ContractFulfillment>(contract).unwrap();
+    assert_eq!(fulfillment.status, ContractFulfillmentStatus::Exceeding);
+    assert!(fulfillment.delivered > 5.0 * SOURCE_THROUGHPUT as f64, "delivered {}", fulfillment.delivered);
+}
+
+#[test]
+fn fulfilled_contract_pays_and_builds_reputation() {
+    let (mut app, sink) = setup();
+    offer_contract(&mut app, sink, f64::INFINITY);
+    let money_before = app.world().resource::<Player>().money;
+    let reputation_before = app.world().resource::<FactionReputations>().get(Faction::Corporate);
+
+    // past the first reputation tick
+    run(&mut app, 35 * TICKS_PER_SECOND);
+
+    let money_after = app.world().resource::<Player>().money;
+    let reputation_after = app.world().resource::<FactionReputations>().get(Faction::Corporate);
+    assert!(money_after > money_before, "money {money_before} -> {money_after}");
+    assert!(reputation_after > reputation_before, "reputation {reputation_before} -> {reputation_after}");
+}
+
+#[test]
+fn contract_completes_after_its_volume() {
+    let (mut app, sink) = setup();
+    let contract = offer_contract(&mut app, sink, 40.0);
+    let money_before = app.world().resource::<Player>().money;
+
+    let mut completed = false;
+    for _ in 0..30 * TICKS_PER_SECOND {
+        app.update();
+        if app.world().get::<ContractStatus>(contract) == Some(&ContractStatus::Completed) {
+            completed = true;
+            break;
+        }
+    }
+    assert!(completed, "contract never delivered its volume");
+    assert!(app.world().resource::<Player>().money > money_before);
+}
+
+#[test]
+fn removing_a_wire_stops_delivery() {
+    let (mut app, sink) = setup();
+    offer_contract(&mut app, sink, f64::INFINITY);
+    run(&mut app, 5 * TICKS_PER_SECOND);
+    assert!(delivered_per_sec(&app, sink) > 0.0);
+
+    let wire = app
+        .world_mut()
+        .query_filtered::<Entity, (With<PhysicalLink>, Without<ld58::factory::buildings::Tile>)>()
+        .iter(app.world())
+        .next()
+        .expect("wires built");
+    app.world_mut().entity_mut(wire).insert(MarkedForRemoval);
+    run(&mut app, 5 * TICKS_PER_SECOND);
+
+    assert_eq!(delivered_per_sec(&app, sink), 0.0);
+}
+
+#[test]
+fn failing_contract_is_pulled_and_penalized_once() {
+    let (mut app, sink) = setup();
+    let contract = offer_contract(&mut app, sink, f64::INFINITY);
+    run(&mut app, 5 * TICKS_PER_SECOND);
+    let wire = app
+        .world_mut()
+        .query_filtered::<Entity, (With<PhysicalLink>, Without<Tile>)>()
+        .iter(app.world())
+        .next()
+        .expect("wires built");
+    app.world_mut().entity_mut(wire).insert(MarkedForRemoval);
+    run(&mut app, 2 * TICKS_PER_SECOND);
+    let fulfillment = app.world().get::<ContractFulfillment>(contract).unwrap();
+    assert_eq!(fulfillment.status, ContractFulfillmentStatus::Failing);
+    let reputation_before = app.world().resource::<FactionReputations>().get(Faction::Corporate);
+
+    run(&mut app, FAILING_CONTRACT_SECONDS as usize * TICKS_PER_SECOND);
+    assert_eq!(app.world().get::<ContractStatus>(contract), Some(&ContractStatus::Failed));
+    assert_eq!(
+        app.world().resource::<FactionReputations>().get(Faction::Corporate),
+        reputation_before - FAILED_CONTRACT_REPUTATION_PENALTY
+    );
+
+    run(&mut app, 30 * TICKS_PER_SECOND);
+    assert_eq!(
+        app.world().resource::<FactionReputations>().get(Faction::Corporate),
+        reputation_before - FAILED_CONTRACT_REPUTATION_PENALTY,
+        "penalized once, not every frame it stays failed"
+    );
+}
+
+#[test]
+fn source_output_split_follows_demand() {
+    let close = |got: Vec<f32>, want: &[f32]| got.iter().zip(want).all(|(a, b)| (a - b).abs() < 1e-3);
+    // a capped line gets its want, an uncapped one the rest
+    assert!(close(allocate_fairly(10.0, &[3.0, f32::INFINITY]), &[3.0, 7.0]));
+    // short on supply it goes round in equal slices
+    assert!(close(allocate_fairly(10.0, &[8.0, 8.0]), &[5.0, 5.0]));
+    assert!(close(allocate_fairly(10.0, &[1.0, 20.0, 20.0]), &[1.0, 4.5, 4.5]));
+    // spare output goes out in proportion to demand
+    assert!(close(allocate_fairly(9.0, &[1.0, 2.0]), &[3.0, 6.0]));
+}
+
+/// One data type passes the aggregator untouched, two or more come out tagged, and a higher
+/// `min_types` holds off until it's met
+#[test]
+fn aggregator_needs_several_data_types() {
+    let single = behavioural();
+    let pair = Dataset {
+        contents: HashMap::from([
+            (BasicDataType::Behavioural, HashSet::new()),
+            (BasicDataType::Biometric, HashSet::from([DataAttribute::DeIdentified])),
+        ]),
+    };
+
+    assert!(!can_aggregate(&single, DEFAULT_MIN_AGGREGATE_TYPES));
+    assert_eq!(aggregate_dataset(&single, DEFAULT_MIN_AGGREGATE_TYPES), single, "single type shouldn't change");
+
+    assert!(can_aggregate(&pair, DEFAULT_MIN_AGGREGATE_TYPES));
+    let aggregated = aggregate_dataset(&pair, DEFAULT_MIN_AGGREGATE_TYPES);
+    for (data_type, attributes) in aggregated.contents.iter() {
+        assert!(attributes.contains(&DataAttribute::Aggregated), "{:?} not tagged Aggregated", data_type);
+    }
+    // what was already there stays
+    assert!(aggregated.contents[&BasicDataType::Biometric].contains(&DataAttribute::DeIdentified));
+
+    assert_eq!(aggregate_dataset(&pair, 3), pair, "two types shouldn't satisfy a three-type aggregator");
+    // a zero minimum tags anything, the old behaviour
+    assert!(aggregate_dataset(&single, 0).contents[&BasicDataType::Behavioural].contains(&DataAttribute::Aggregated));
+}
+
+fn queued_event(id: &str, urgent: bool) -> InteractiveEventData {
+    InteractiveEventData {
+        event_id: id.to_string(),
+        title: id.to_string(),
+        description: String::new(),
+        faction: None,
+        choices: Vec::new(),
+        popup_urgency: urgent,
+        expires_in: 60.0,
+        expiry_seconds: 60.0,
+    }
+}
+
+#[test]
+fn displaced_urgent_events_reopen_in_order() {
+    let mut queue = QueuedEvents::default();
+    queue.events.push(queued_event("bubble", false));
+    assert!(queue.pop_urgent().is_none(), "bubbles wait to be clicked");
+
+    // "open" is up when "first" and "second" arrive together: "first" takes its place
+    queue.requeue_displaced(queued_event("open", false));
+    queue.queue_urgent(queued_event("second", true));
+
+    let order: Vec<_> = std::iter::from_fn(|| queue.pop_urgent()).map(|e| e.event_id).collect();
+    assert_eq!(order, ["open", "second"]);
+    assert_eq!(queue.events.len(), 1);
+    assert_eq!(queue.events[0].event_id, "bubble");
+}
+
+fn answerable_event(id: &str) -> InteractiveEventData {
+    InteractiveEventData {
+        choices: vec![EventChoice { text: "Fine".to_string(), requirements: Vec::new(), consequences: Vec::new() }],
+        ..queued_event(id, true)
+    }
+}
+
+fn open_modals(app: &mut App) -> Vec<String> {
+    app.world_mut()
+        .query_filtered::<&StoredEventData, With<InteractiveEventModal>>()
+        .iter(app.world())
+        .map(|stored| stored.event_data.event_id.clone())
+        .collect()
+}
+
+/// Clicks the open modal's first choice, like the mouse would
+fn answer(app: &mut App) {
+    let button = app
+        .world_mut()
+        .query_filtered::<Entity, With<EventChoiceButton>>()
+        .iter(app.world())
+        .next()
+        .expect("the modal has a choice");
+    *app.world_mut().get_mut::<Interaction>(button).unwrap() = Interaction::Pressed;
+}
+
+/// Two urgent events written the same frame: one modal opens, the other waits behind it and
+/// opens once the first is answered
+#[test]
+fn urgent_events_in_one_frame_are_answered_in_order() {
+    let mut app = headless_app();
+    app.init_resource::<GameAssets>()
+        .init_resource::<QueuedEvents>()
+        .init_resource::<ModalSpawnCooldown>()
+        .init_resource::<EventState>()
+        .init_resource::<BuildingCounts>()
+        .add_message::<ShowInteractiveEvent>()
+        .add_message::<PlayerChoiceEvent>()
+        .add_systems(
+            Update,
+            (route_events_by_urgency, open_waiting_urgent_event.run_if(in_state(GameState::Running))).chain(),
+        )
+        .add_systems(Update, handle_choice_click);
+    app.update();
+
+    for id in ["first", "second"] {
+        app.world_mut().write_message(ShowInteractiveEvent(answerable_event(id)));
+    }
+    run(&mut app, 2);
+    assert_eq!(*app.world().resource::<State<GameState>>().get(), GameState::EventModal);
+
+    let mut answered = Vec::new();
+    for _ in 0..3 {
+        let open = open_modals(&mut app);
+        assert!(open.len() <= 1, "only one modal at a time, got {:?}", open);
+        let Some(id) = open.into_iter().next() else { break };
+        answered.push(id);
+        answer(&mut app);
+        // one frame to close it and go back to Running, one for the next to open
+        run(&mut app, 2);
+    }
+    assert_eq!(answered, ["first", "second"]);
+    assert!(app.world().resource::<QueuedEvents>().events.is_empty());
+    assert_eq!(*app.world().resource::<State<GameState>>().get(), GameState::Running);
+}
+
+#[test]
+fn placement_stays_inside_the_world() {
+    let bounds = WorldBounds::default();
+    let world_map = WorldMap::default();
+    let free = |cell: I64Vec2| are_positions_free(&world_map, &bounds, &[GridPosition(cell)]);
+    assert!(free(bounds.min) && free(bounds.max));
+    assert!(!free(bounds.max + I64Vec2::X));
+    assert!(!free(bounds.min - I64Vec2::Y));
+    assert!(!free(I64Vec2::new(500, 500)));
+}
+
+/// All three cells of a 3x1 splitter are taken in the `WorldMap`, and free again once it's removed
+#[test]
+fn splitter_footprint_is_taken_then_freed() {
+    let mut app = headless_app();
+    let anchor = I64Vec2::new(20, 20);
+    let orientation = Orientation::new(Direction::Right, false);
+    let splitter = spawn_building(&mut app, Splitter::new(10.0, 3), anchor, orientation);
+    app.update();
+
+    let cells: Vec<GridPosition> =
+        calculate_occupied_cells_rotated(anchor, 3, 1, orientation).into_iter().map(GridPosition).collect();
+    let free = |app: &App, cells: &[GridPosition]| {
+        are_positions_free(app.world().resource::<WorldMap>(), app.world().resource::<WorldBounds>(), cells)
+    };
+    for cell in cells.iter() {
+        assert!(!free(&app, &[*cell]), "splitter cell {:?} not in the WorldMap", cell);
+    }
+
+    app.world_mut().entity_mut(splitter).insert(MarkedForRemoval);
+    run(&mut app, 3);
+    assert!(app.world().get_entity(splitter).is_err());
+    assert!(free(&app, &cells), "splitter cells still occupied after removal");
+}
+
+const LINK_LINE_LENGTH: i64 = 500;
+
+/// Appending to a long wire run shouldn't walk it again: every connection pass stays about the
+/// size of one placement, and once capped the whole line is one logical link
+#[test]
+fn long_wire_run_grows_without_rewalking() {
+    let mut app = headless_app();
+    let origin = I64Vec2::new(-40, -30);
+    spawn_building(
+        &mut app,
+        SourceBuilding {
+            directions: vec![Direction::Right],
+            throughput: SOURCE_THROUGHPUT,
+            limited: false,
+            size: I64Vec2::new(1, 1),
+            shape: behavioural(),
+        },
+        origin,
+        Orientation::new(Direction::Up, false),
+    );
+    app.update();
+
+    for placed in 1..=LINK_LINE_LENGTH {
+        spawn_building(
+            &mut app,
+            PhysicalLink { throughput: LINK_THROUGHPUT },
+            origin + I64Vec2::new(placed, 0),
+            Orientation::default(),
+        );
+        app.update();
+        let stats = app.world().resource::<ConnectionPassStats>();
+        // a new link touches at most its own cell and four neighbours, a couple of entities each
+        assert!(stats.visited <= 10, "connection pass visited {} entities for one placement", stats.visited);
+        if placed > 2 {
+            assert!(stats.chain_steps <= 4, "rewalked {} segments after {} links", stats.chain_steps, placed);
+        }
+    }
+
+    spawn_building(
+        &mut app,
+        SinkBuilding { size: I64Vec2::new(1, 1) },
+        origin + I64Vec2::new(LINK_LINE_LENGTH + 1, 0),
+        Orientation::new(Direction::Right, false),
+    );
+    run(&mut app, 3);
+    let lengths: Vec<usize> =
+        app.world_mut().query::<&LogicalLink>().iter(app.world()).map(|link| link.links.len()).collect();
+    assert_eq!(lengths, [LINK_LINE_LENGTH as usize], "one logical link down the whole line");
+}
+
+#[test]
+fn trace_log_drops_the_oldest_past_capacity() {
+    let mut log = TraceLog::with_capacity(3);
+    for i in 0..5 {
+        log.push(TraceCategory::Payout, format!("payout {i}"), Vec::new());
+    }
+    assert_eq!(log.len(), 3);
+    let messages: Vec<_> = log.entries().map(|e| e.message.as_str()).collect();
+    assert_eq!(messages, ["payout 2", "payout 3", "payout 4"]);
+}
+
+#[test]
+fn contract_failure_is_traced_in_order() {
+    let (mut app, sink) = setup();
+    let contract = offer_contract(&mut app, sink, f64::INFINITY);
+    run(&mut app, 5 * TICKS_PER_SECOND);
+
+    let wire = app
+        .world_mut()
+        .query_filtered::<Entity, (With<PhysicalLink>, Without<ld58::factory::buildings::Tile>)>()
+        .iter(app.world())
+        .next()
+        .expect("wires built");
+    app.world_mut().entity_mut(wire).insert(MarkedForRemoval);
+    run(&mut app, 5 * TICKS_PER_SECOND);
+
+    let filter = TraceFilter { category: Some(TraceCategory::Fulfillment), entity: Some(contract) };
+    let log = app.world().resource::<TraceLog>();
+    let entries: Vec<_> = log.filtered(&filter).collect();
+    let exceeding = entries.iter().position(|e| e.message.contains("-> Exceeding")).expect("traced meeting the contract");
+    let failing = entries.iter().position(|e| e.message.contains("Exceeding -> Failing")).expect("traced the failure");
+    assert!(exceeding < failing, "{:#?}", entries);
+    assert!(entries.windows(2).all(|w| w[0].time <= w[1].time), "entries out of order");
+    assert!(entries.iter().all(|e| e.entities.contains(&sink)), "every entry names the sink too");
+}
+
+#[test]
+fn contract_earnings_accumulate_at_payout() {
+    let (mut app, sink) = setup();
+    let contract = offer_contract(&mut app, sink, f64::INFINITY);
+    let earned = |app: &App| app.world().get::<ContractEarnings>(contract).unwrap().0;
+    assert_eq!(earned(&app), 0.0);
+
+    run(&mut app, 5 * TICKS_PER_SECOND);
+    let first = earned(&app);
+    assert!(first > 0.0, "paid out while exceeding");
+    run(&mut app, 5 * TICKS_PER_SECOND);
+    let second = earned(&app);
+    assert!(second > first, "earnings {first} -> {second}");
+
+    // only active contracts are paid, what's been earned stays on the books
+    *app.world_mut().get_mut::<ContractStatus>(contract).unwrap() = ContractStatus::Completed;
+    run(&mut app, 3 * TICKS_PER_SECOND);
+    assert_eq!(earned(&app), second);
+}
+
+#[test]
+fn infra_cost_cache_follows_the_topology() {
+    let mut app = headless_app();
+    app.add_plugins(EconomicsPlugin);
+    app.update();
+    let sink = spawn_chain(&mut app);
+    offer_contract(&mut app, sink, f64::INFINITY);
+    let cost = |app: &App| app.world().get::<SinkInfraCost>(sink).map(|c| c.0);
+    // any recompute re-inserts the cost, so the tick moves even when the total doesn't
+    let computed_at = |app: &App| app.world().entity(sink).get_ref::<SinkInfraCost>().map(|c| c.last_changed());
+    let wire_cost = PhysicalLink { throughput: LINK_THROUGHPUT }.data().cost;
+    assert_eq!(cost(&app), Some(2 * wire_cost), "both wires, the source is free");
+
+    // throughput ticks rewrite the links without changing them
+    let before = computed_at(&app);
+    run(&mut app, 3 * TICKS_PER_SECOND);
+    assert_eq!(computed_at(&app), before, "recomputed without a topology change");
+    assert_eq!(cost(&app), Some(2 * wire_cost));
+
+    let wire = app
+        .world_mut()
+        .query_filtered::<Entity, (With<PhysicalLink>, Without<ld58::factory::buildings::Tile>)>()
+        .iter(app.world())
+        .next()
+        .expect("wires built");
+    app.world_mut().entity_mut(wire).insert(MarkedForRemoval);
+    run(&mut app, 5);
+    assert_ne!(computed_at(&app), before, "the link change was never picked up");
+    assert_eq!(cost(&app), Some(0), "nothing reaches the sink any more");
+}
+
+#[test]
+fn infra_share_splits_by_threshold_not_live_throughput() {
+    let mut world = World::new();
+    let sink = world.spawn_empty().id();
+    let contract = |world: &mut World, threshold: f64| {
+        world
+            .spawn((ContractStatus::Active, ContractFulfillment::new(threshold, CONTRACT_MONEY), AssociatedWithSink(sink)))
+            .id()
+    };
+    let small = contract(&mut world, 1.0);
+    let large = contract(&mut world, 3.0);
+    let shares = |world: &mut World| {
+        world
+            .run_system_once(move |contracts: ShareQuery| {
+                (contract_infra_share(small, sink, 100, &contracts), contract_infra_share(large, sink, 100, &contracts))
+            })
+            .unwrap()
+    };
+    assert_eq!(shares(&mut world), (25.0, 75.0));
+
+    // the small one is briefly drawing everything, the split holds
+    world.get_mut::<ContractFulfillment>(small).unwrap().update_throughput(9.0);
+    world.get_mut::<ContractFulfillment>(large).unwrap().update_throughput(0.5);
+    assert_eq!(shares(&mut world), (25.0, 75.0));
+
+    *world.get_mut::<ContractStatus>(large).unwrap() = ContractStatus::Completed;
+    assert_eq!(shares(&mut world), (100.0, 0.0), "finished contracts drop out of the split");
+}
+
+const SAVE_TEST_SLOT: &str = "headless_round_trip";
+
+/// The headless simulation plus saving, with the resources the windowed app gets elsewhere
+fn saving_app() -> App {
+    let mut app = headless_app();
+    app.init_resource::<GameAssets>()
+        .init_resource::<ClusterBounds>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<InputBindings>()
+        .add_plugins(SaveGamePlugin);
+    app.update();
+    app
+}
+
+/// Straight into the world, skipping construction and its costs
+fn spawn_building(app: &mut App, building: impl Building, cell: I64Vec2, orientation: Orientation) -> Entity {
+    let world = app.world_mut();
+    let entity = building.spawn(&mut world.commands(), GridPosition(cell), orientation);
+    world.flush();
+    entity
+}
+
+/// Spawned like world gen does it, so it's saved as part of the world rather than as a build
+fn spawn_world_building(app: &mut App, building: impl Building, cell: I64Vec2) -> Entity {
+    let entity = spawn_building(app, building, cell, Orientation::default());
+    app.world_mut().entity_mut(entity).insert(Undeletable);
+    entity
+}
+
+fn contract_on(app: &mut App, sink: Entity) -> Option<Entity> {
+    app.world_mut()
+        .query::<(Entity, &AssociatedWithSink)>()
+        .iter(app.world())
+        .find(|(_, associated)| associated.0 == sink)
+        .map(|(entity, _)| entity)
+}
+
+/// Going up: world source, wire, a two-way splitter, wire, world sink with an active
+/// contract. Saved, then loaded into an app that's never seen it.
+#[test]
+fn saved_factory_picks_up_where_it_left_off() {
+    let mut app = saving_app();
+    spawn_world_building(
+        &mut app,
+        SourceBuilding {
+            directions: vec![Direction::Up],
+            throughput: SOURCE_THROUGHPUT,
+            limited: false,
+            size: I64Vec2::new(1, 1),
+            shape: behavioural(),
+        },
+        I64Vec2::new(0, -2),
+    );
+    let sink = spawn_world_building(&mut app, SinkBuilding { size: I64Vec2::new(1, 1) }, I64Vec2::new(0, 2));
+    app.world_mut().entity_mut(sink).insert((Faction::Corporate, ReputationLevel::Neutral));
+    construct_at(&mut app, Arc::new(Splitter::new(20.0, 2)), I64Vec2::ZERO);
+    for y in [-1, 1] {
+        construct_at(&mut app, Arc::new(PhysicalLink { throughput: LINK_THROUGHPUT }), I64Vec2::new(0, y));
+    }
+    let contract = offer_contract(&mut app, sink, f64::INFINITY);
+    run(&mut app, 5 * TICKS_PER_SECOND);
+
+    let before = app.world().get::<ContractFulfillment>(contract).unwrap();
+    let (throughput, delivered, status) = (before.throughput, before.delivered, before.status);
+    assert!(throughput > 0.0, "flowing before the save");
+    app.world_mut().write_message(SaveGameRequest { slot: SAVE_TEST_SLOT.to_string() });
+    app.update();
+
+    let mut loaded = saving_app();
+    // something removed in the run that's about to be replaced
+    loaded.world_mut().resource_mut::<UndoBuffer>().record(RemovalRecord {
+        name: "Link".to_string(),
+        position: GridPosition(I64Vec2::new(5, 5)),
+        orientation: Orientation::default(),
+        is_link: true,
+        descriptor: BuildingDescriptor::Link { throughput: LINK_THROUGHPUT },
+    });
+    loaded.world_mut().write_message(LoadGameRequest { slot: SAVE_TEST_SLOT.to_string() });
+    // one frame to spawn it all back, a couple more for the links to resolve
+    run(&mut loaded, 3);
+    let _ = std::fs::remove_file(save_path(SAVE_TEST_SLOT));
+
+    let sink = loaded
+        .world_mut()
+        .query_filtered::<Entity, With<SinkBuilding>>()
+        .single(loaded.world())
+        .expect("sink back");
+    let contract = contract_on(&mut loaded, sink).expect("contract reattached to the sink");
+    assert_eq!(loaded.world().get::<ContractStatus>(contract), Some(&ContractStatus::Active));
+    assert_eq!(loaded.world_mut().query::<&Splitter>().iter(loaded.world()).count(), 1);
+    assert!(loaded.world().resource::<UndoBuffer>().0.is_empty(), "nothing to undo into the loaded world");
+    let delivered_at_load = loaded.world().get::<ContractFulfillment>(contract).unwrap().delivered;
+    assert!((delivered_at_load - delivered).abs() < SOURCE_THROUGHPUT as f64, "delivered carried over");
+
+    // within two fulfilment ticks
+    run(&mut loaded, 2 * TICKS_PER_SECOND);
+    let after = loaded.world().get::<ContractFulfillment>(contract).unwrap();
+    assert!(
+        (after.throughput - throughput).abs() < throughput * 0.1,
+        "throughput {} before the save, {} after the load",
+        throughput,
+        after.throughput
+    );
+    assert_eq!(after.status, status);
+    assert!(after.delivered > delivered_at_load, "still delivering");
+}
+
+fn source_up(data_type: BasicDataType) -> Arc<dyn Building> {
+    Arc::new(SourceBuilding {
+        directions: vec![Direction::Up],
+        throughput: SOURCE_THROUGHPUT,
+        limited: false,
+        size: I64Vec2::new(1, 1),
+        shape: Dataset { contents: HashMap::from([(data_type, HashSet::new())]) },
+    })
+}
+
+fn wire_at(app: &mut App, cell: I64Vec2) -> Option<Entity> {
+    app.world_mut()
+        .query_filtered::<(Entity, &GridPosition), (With<PhysicalLink>, Without<Tile>)>()
+        .iter(app.world())
+        .find(|(_, position)| position.0 == cell)
+        .map(|(entity, _)| entity)
+}
+
+/// The buildings a wire's two ends are hooked onto, tiles resolved to their building
+fn wire_ends(app: &App, wire: Entity) -> Vec<Entity> {
+    let owner = |entity: Entity| app.world().get::<Tile>(entity).map_or(entity, |tile| tile.0);
+    let sink = app.world().get::<PhysicalSink>(wire).map(|s| owner(s.0));
+    let source = app.world().get::<PhysicalSource>(wire).map(|s| owner(s.0));
+    sink.into_iter().chain(source).collect()
+}
+
+/// Going up: a source, a straight run of wires on x=0 and a sink. A two-input combiner
+/// then goes down over the (0,0) wire, its second input fed by another source
+#[test]
+fn combiner_over_a_wire_run_reconnects_both_sides() {
+    let mut app = headless_app();
+    app.update();
+    for (building, y) in [(source_up(BasicDataType::Behavioural), -4), (Arc::new(SinkBuilding { size: I64Vec2::new(1, 1) }) as Arc<dyn Building>, 4)] {
+        construct_at(&mut app, building, I64Vec2::new(0, y));
+        app.update();
+    }
+    for y in -3..=3 {
+        construct_at(&mut app, Arc::new(PhysicalLink { throughput: LINK_THROUGHPUT }), I64Vec2::new(0, y));
+        // one at a time, so each wire hooks onto the end of the one before it
+        app.update();
+    }
+    construct_at(&mut app, source_up(BasicDataType::Economic), I64Vec2::new(-1, -1));
+    run(&mut app, 5);
+
+    let replaced = wire_at(&mut app, I64Vec2::ZERO).expect("wire under the footprint");
+    let refund = wire_refund(app.world().get(replaced), &GameMode::default());
+    let combiner_cost = Combiner::new(20.0, 2).data().cost;
+    let money_before = app.world().resource::<Player>().money;
+    app.world_mut().write_message(ConstructBuildingEvent {
+        building: Arc::new(Combiner::new(20.0, 2)),
+        grid_position: I64Vec2::ZERO,
+        orientation: Orientation::new(Direction::Up, false),
+        replaces: vec![replaced],
+        free: false,
+    });
+    app.update();
+    let charged = money_before - app.world().resource::<Player>().money;
+    // the throughput timer may have taken a second of the combiner's upkeep on the same frame
+    let upkeep = Combiner::new(20.0, 2).data().upkeep_per_sec;
+    assert!(
+        (charged - (combiner_cost - refund)).abs() <= upkeep,
+        "charged {charged}, expected {combiner_cost} less a {refund} refund"
+    );
+    run(&mut app, 5);
+
+    assert!(app.world().get_entity(replaced).is_err(), "the replaced wire is gone");
+    assert!(wire_at(&mut app, I64Vec2::ZERO).is_none());
+    let combiner = app
+        .world_mut()
+        .query_filtered::<Entity, With<Combiner>>()
+        .single(app.world())
+        .expect("combiner built");
+    for y in [-1, 1] {
+        let wire = wire_at(&mut app, I64Vec2::new(0, y)).unwrap();
+        assert!(wire_ends(&app, wire).contains(&combiner), "wire at (0,{y}) hooked onto the combiner");
+    }
+
+    // nothing still pointing at what was taken out
+    let mut ends = app.world_mut().query::<AnyOf<(&PhysicalSink, &PhysicalSource)>>();
+    for (sink, source) in ends.iter(app.world()) {
+        for end in sink.map(|s| s.0).into_iter().chain(source.map(|s| s.0)) {
+            assert_ne!(end, replaced);
+            assert!(app.world().get_entity(end).is_ok(), "connection to a despawned {end:?}");
+        }
+    }
+
+    let sink = app
+        .world_mut()
+        .query_filtered::<Entity, With<SinkBuilding>>()
+        .single(app.world())
+        .expect("one sink built");
+    let mut dataset = behavioural();
+    dataset.contents.insert(BasicDataType::Economic, HashSet::new());
+    app.world_mut().spawn((
+        ContractBundle {
+            contract: Contract,
+            status: ContractStatus::Active,
+            dataset,
+            faction: Faction::Corporate,
+            timeout: ContractTimeout(120.0),
+            description: ContractDescription { name: "Merged".to_string(), description: String::new() },
+            fulfillment_info: ContractFulfillment::new(1.0, CONTRACT_MONEY),
+        },
+        AssociatedWithSink(sink),
+    ));
+    run(&mut app, 3 * TICKS_PER_SECOND);
+    assert!(delivered_per_sec(&app, sink) > 0.0, "data goes through the combiner to the sink");
+}
@@ -0,0 +1,93 @@
+use bevy::math::I64Vec2;
+use bevy::platform::collections::HashSet;
+use ld58::factory::buildings::sink::{classify_sink_sides, PortAccess, SinkAccess};
+use ld58::world_gen::WorldBounds;
+
+/// Classifies a sink against a set of locked cells and a set of cells holding player builds,
+/// with the world's edge counting as locked the same way `update_sink_access` does it
+fn classify(anchor: I64Vec2, size: I64Vec2, locked: &[I64Vec2], occupied: &[I64Vec2]) -> SinkAccess {
+    let bounds = WorldBounds::default();
+    let locked: HashSet<_> = locked.iter().copied().collect();
+    let occupied: HashSet<_> = occupied.iter().copied().collect();
+    classify_sink_sides(
+        anchor,
+        size,
+        |cell| !bounds.contains(cell) || locked.contains(&cell),
+        |cell| occupied.contains(&cell),
+    )
+}
+
+#[test]
+fn sink_in_open_ground_is_open_all_round() {
+    let access = classify(I64Vec2::ZERO, I64Vec2::splat(2), &[], &[]);
+    assert_eq!(
+        access,
+        SinkAccess { up: PortAccess::Open, down: PortAccess::Open, left: PortAccess::Open, right: PortAccess::Open }
+    );
+    assert!(access.has_access());
+}
+
+#[test]
+fn sink_against_the_world_edge_is_blocked_on_that_side() {
+    let bounds = WorldBounds::default();
+    // flush with the left edge, so the column to its left is off the map
+    let anchor = I64Vec2::new(bounds.min.x, 0);
+    let access = classify(anchor, I64Vec2::splat(2), &[], &[]);
+    assert_eq!(access.left, PortAccess::Blocked);
+    assert_eq!(access.right, PortAccess::Open);
+    assert_eq!(access.up, PortAccess::Open);
+    assert_eq!(access.down, PortAccess::Open);
+}
+
+#[test]
+fn sink_against_a_locked_border() {
+    // a locked cluster runs down the whole right side
+    let locked: Vec<_> = (-1..=2).map(|y| I64Vec2::new(2, y)).collect();
+    let access = classify(I64Vec2::ZERO, I64Vec2::splat(2), &locked, &[]);
+    assert_eq!(access.right, PortAccess::Blocked);
+    assert_eq!(access.left, PortAccess::Open);
+}
+
+#[test]
+fn one_free_cell_is_enough_to_open_a_side() {
+    // only half the top edge is locked
+    let access = classify(I64Vec2::ZERO, I64Vec2::splat(2), &[I64Vec2::new(0, 2)], &[]);
+    assert_eq!(access.up, PortAccess::Open);
+}
+
+#[test]
+fn player_builds_make_a_side_occupied_not_blocked() {
+    let wires = [I64Vec2::new(0, -1), I64Vec2::new(1, -1)];
+    let access = classify(I64Vec2::ZERO, I64Vec2::splat(2), &[], &wires);
+    assert_eq!(access.down, PortAccess::Occupied);
+    assert!(access.has_access());
+
+    // locked wins over occupied on the same cell
+    let access = classify(I64Vec2::ZERO, I64Vec2::splat(2), &wires, &wires);
+    assert_eq!(access.down, PortAccess::Blocked);
+}
+
+#[test]
+fn boxed_in_sink_has_no_access() {
+    let anchor = I64Vec2::new(10, 10);
+    let mut locked = Vec::new();
+    for x in 9..=12 {
+        for y in 9..=12 {
+            let cell = I64Vec2::new(x, y);
+            if !(10..=11).contains(&x) || !(10..=11).contains(&y) {
+                locked.push(cell);
+            }
+        }
+    }
+    let access = classify(anchor, I64Vec2::splat(2), &locked, &[]);
+    assert_eq!(
+        access,
+        SinkAccess {
+            up: PortAccess::Blocked,
+            down: PortAccess::Blocked,
+            left: PortAccess::Blocked,
+            right: PortAccess::Blocked,
+        }
+    );
+    assert!(!access.has_access());
+}
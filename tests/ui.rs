@@ -0,0 +1,19 @@
+use ld58::settings::{MAX_UI_TEXT_SCALE, MIN_UI_TEXT_SCALE};
+use ld58::ui::interactive_event::{scaled_font_size, MAX_SCALED_FONT_SIZE, MIN_SCALED_FONT_SIZE};
+
+#[test]
+fn scaled_font_size_follows_the_preference() {
+    // 1vw of a 1920 window is 19.2px, the preference multiplies it
+    assert!((scaled_font_size(1.0, 1920.0, 1.0) - 19.2).abs() < 1e-3);
+    assert!((scaled_font_size(1.0, 1920.0, MAX_UI_TEXT_SCALE) - 28.8).abs() < 1e-3);
+    assert!((scaled_font_size(1.0, 1920.0, MIN_UI_TEXT_SCALE) - 15.36).abs() < 1e-3);
+}
+
+#[test]
+fn scaled_font_size_clamps_the_worst_cases() {
+    // modal title at 1.5x on a 4k window
+    assert_eq!(scaled_font_size(3.0, 3840.0, MAX_UI_TEXT_SCALE), MAX_SCALED_FONT_SIZE);
+    // small print at 0.8x on a tiny window
+    assert_eq!(scaled_font_size(0.6, 800.0, MIN_UI_TEXT_SCALE), MIN_SCALED_FONT_SIZE);
+    assert_eq!(scaled_font_size(1.0, 0.0, 1.0), MIN_SCALED_FONT_SIZE);
+}
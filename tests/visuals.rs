@@ -0,0 +1,176 @@
+use bevy::math::I64Vec2;
+use bevy::prelude::*;
+use ld58::assets::{AtlasId, GameAssets};
+use ld58::dev::DevMode;
+use ld58::factions::{Faction, FactionReputations, Locked, ReputationLevel};
+use ld58::factory::building_visuals::{decorate_sinks, SinkTierPips, SinkTrim};
+use ld58::factory::buildings::sink::SinkBuilding;
+use ld58::factory::logical::{BasicDataType, DataAttribute};
+use ld58::factory::source_visuals::{
+    AugmentationEffect, AugmentedIndicator, DataTypeIcon, FloatingAnimation, GlowSprite, ScanningFlashEffect,
+    SourceLodDot,
+};
+use ld58::grid::{Grid, GridAtlasSprite, GridPosition, Orientation};
+use ld58::lod::{ClusterBound, ClusterBounds, ClusterLockQuad, LodFlattened, LodLevel, LodPlugin};
+use ld58::world_gen::LockMarker;
+
+fn visuals_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<GameAssets>()
+        .insert_resource(Grid { scale: 64.0, base_offset: 0.0 })
+        .add_systems(Update, decorate_sinks);
+    app
+}
+
+fn count<T: Component>(app: &mut App) -> usize {
+    app.world_mut().query_filtered::<(), With<T>>().iter(app.world()).count()
+}
+
+#[test]
+fn sink_decorations_are_children_and_go_with_the_sink() {
+    let mut app = visuals_app();
+    let sink = app
+        .world_mut()
+        .spawn((
+            SinkBuilding { size: I64Vec2::new(2, 2) },
+            GridPosition(I64Vec2::new(5, 5)),
+            Faction::Academia,
+            ReputationLevel::Trusted,
+        ))
+        .id();
+    app.update();
+
+    let children: Vec<Entity> = app.world().get::<Children>(sink).expect("sink has decorations").to_vec();
+    assert!(children.iter().any(|c| app.world().get::<SinkTrim>(*c).is_some()), "trim under the sink");
+    assert!(children.iter().any(|c| app.world().get::<SinkTierPips>(*c).is_some()), "pips under the sink");
+
+    app.world_mut().entity_mut(sink).despawn();
+    app.update();
+    assert_eq!(count::<SinkTrim>(&mut app), 0);
+    assert_eq!(count::<SinkTierPips>(&mut app), 0);
+    assert_eq!(count::<Sprite>(&mut app), 0, "the pips' own sprites went too");
+}
+
+#[test]
+fn unaligned_sinks_stay_plain() {
+    let mut app = visuals_app();
+    let sink = app
+        .world_mut()
+        .spawn((SinkBuilding { size: I64Vec2::ONE }, GridPosition(I64Vec2::ZERO)))
+        .id();
+    app.update();
+    assert!(app.world().get::<Children>(sink).is_none());
+}
+
+fn lod_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<GameAssets>()
+        .init_resource::<DevMode>()
+        .init_resource::<FactionReputations>()
+        .insert_resource(Grid { scale: 64.0, base_offset: 0.0 })
+        .add_plugins(LodPlugin);
+    app
+}
+
+fn zoom(app: &mut App, camera: Entity, scale: f32) {
+    if let Some(mut projection) = app.world_mut().get_mut::<Projection>(camera)
+        && let Projection::Orthographic(orthographic) = projection.as_mut()
+    {
+        orthographic.scale = scale;
+    }
+    // one to switch level and queue the changes, one to see them applied
+    app.update();
+    app.update();
+}
+
+fn visibility(app: &App, entity: Entity) -> Visibility {
+    *app.world().get::<Visibility>(entity).unwrap()
+}
+
+#[test]
+fn zoom_transitions_toggle_each_decoration_type() {
+    let mut app = lod_app();
+    let camera = app
+        .world_mut()
+        .spawn((Camera2d, Projection::Orthographic(OrthographicProjection::default_2d())))
+        .id();
+    let source = app.world_mut().spawn_empty().id();
+    let icon = app
+        .world_mut()
+        .spawn((DataTypeIcon { data_type: BasicDataType::Economic, parent_source: source }, Visibility::Inherited))
+        .id();
+    let decorations = [
+        icon,
+        app.world_mut().spawn((AugmentationEffect { attribute: DataAttribute::Cleaned, parent_icon: icon }, Visibility::Inherited)).id(),
+        app.world_mut().spawn((AugmentedIndicator { parent_icon: icon, base_scale: 1.0, time_offset: 0.0 }, Visibility::Inherited)).id(),
+        app.world_mut().spawn((GlowSprite { parent_icon: icon }, Visibility::Inherited)).id(),
+        app.world_mut().spawn((ScanningFlashEffect { parent_icon: icon, timer: 0.0, flash_interval: 1.0 }, Visibility::Inherited)).id(),
+        app.world_mut().spawn((FloatingAnimation { base_y: 0.0, time_offset: 0.0 }, Visibility::Inherited)).id(),
+    ];
+    let dot = app.world_mut().spawn((SourceLodDot, Visibility::Hidden)).id();
+    let building_sprite = Sprite { color: Color::WHITE, custom_size: Some(Vec2::splat(64.0)), ..default() };
+    let building = app
+        .world_mut()
+        .spawn((
+            GridAtlasSprite {
+                atlas_id: AtlasId::Buildings1x1,
+                atlas_index: 0,
+                grid_width: 1,
+                grid_height: 1,
+                orientation: Orientation::default(),
+            },
+            building_sprite.clone(),
+        ))
+        .id();
+    let lock_cell = app
+        .world_mut()
+        .spawn((LockMarker, Locked, Faction::Criminal, ReputationLevel::Friendly, Visibility::Inherited))
+        .id();
+    app.world_mut().resource_mut::<ClusterBounds>().0.insert(
+        0,
+        ClusterBound { min: I64Vec2::ZERO, max: I64Vec2::new(3, 3), faction: Faction::Criminal, reputation: ReputationLevel::Friendly },
+    );
+    app.update();
+    assert_eq!(*app.world().resource::<LodLevel>(), LodLevel::Full);
+
+    zoom(&mut app, camera, 4.0);
+    assert_eq!(*app.world().resource::<LodLevel>(), LodLevel::Reduced);
+    for decoration in decorations {
+        assert_eq!(visibility(&app, decoration), Visibility::Hidden, "hidden when reduced");
+    }
+    assert_eq!(visibility(&app, dot), Visibility::Inherited, "the dot stands in for the icons");
+    assert!(app.world().get::<LodFlattened>(building).is_none(), "buildings keep their sprites until minimal");
+    assert_eq!(visibility(&app, lock_cell), Visibility::Inherited);
+
+    zoom(&mut app, camera, 8.0);
+    assert_eq!(*app.world().resource::<LodLevel>(), LodLevel::Minimal);
+    let flat = app.world().get::<LodFlattened>(building).expect("drawn as a flat quad").0;
+    assert_eq!(visibility(&app, building), Visibility::Hidden);
+    assert_eq!(visibility(&app, flat), Visibility::Visible, "shows under its hidden building");
+    assert_ne!(app.world().get::<Sprite>(flat).unwrap().color, building_sprite.color);
+    // tinted while zoomed out, say by damage
+    let tint = Color::srgb(0.55, 0.3, 0.25);
+    app.world_mut().get_mut::<Sprite>(building).unwrap().color = tint;
+    assert_eq!(visibility(&app, lock_cell), Visibility::Hidden, "merged into the cluster quad");
+    let quad = app
+        .world_mut()
+        .query_filtered::<Entity, With<ClusterLockQuad>>()
+        .single(app.world())
+        .expect("one quad for the locked cluster");
+    assert_eq!(visibility(&app, quad), Visibility::Visible);
+
+    zoom(&mut app, camera, 1.0);
+    assert_eq!(*app.world().resource::<LodLevel>(), LodLevel::Full);
+    for decoration in decorations {
+        assert_eq!(visibility(&app, decoration), Visibility::Inherited, "everything comes back");
+    }
+    assert_eq!(visibility(&app, dot), Visibility::Hidden);
+    assert!(app.world().get::<LodFlattened>(building).is_none());
+    assert!(app.world().get_entity(flat).is_err(), "the quad goes");
+    assert_eq!(visibility(&app, building), Visibility::Inherited);
+    assert_eq!(app.world().get::<Sprite>(building).unwrap().color, tint, "changes made while zoomed out stay");
+    assert_eq!(visibility(&app, lock_cell), Visibility::Inherited);
+    assert_eq!(visibility(&app, quad), Visibility::Hidden, "kept for next time, just hidden");
+}
@@ -0,0 +1,128 @@
+use bevy::ecs::message::Messages;
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use ld58::assets::GameAssets;
+use ld58::settings::Settings;
+use ld58::ui::widgets::{
+    snap_value, spawn_slider, Slider, SliderTrack, WidgetChanged, WidgetFocus, WidgetValue, WidgetsPlugin,
+};
+
+fn widgets_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, WidgetsPlugin)).init_resource::<ButtonInput<KeyCode>>();
+    app
+}
+
+fn percent(value: f32) -> String {
+    format!("{:.0}%", value * 100.0)
+}
+
+/// A 0..1 slider snapping to quarters, returns the slider and its track
+fn quarter_slider(app: &mut App) -> (Entity, Entity) {
+    let assets = GameAssets::default();
+    let mut slider = Entity::PLACEHOLDER;
+    app.world_mut().commands().spawn(Node::default()).with_children(|panel| {
+        slider = spawn_slider(panel, &assets, "Volume", 0.0, 0.0, 1.0, Some(0.25), percent);
+    });
+    app.world_mut().flush();
+    let track = app
+        .world_mut()
+        .query::<(Entity, &SliderTrack)>()
+        .iter(app.world())
+        .find(|(_, track)| track.slider == slider)
+        .map(|(entity, _)| entity)
+        .expect("slider has a track");
+    (slider, track)
+}
+
+/// What the pointer layer would leave on the track for a press at `x` along it, 0 to 1
+fn press_track(app: &mut App, track: Entity, x: f32) {
+    app.world_mut().entity_mut(track).insert((
+        Interaction::Pressed,
+        // normalized is relative to the node centre
+        RelativeCursorPosition { normalized: Some(Vec2::new(x - 0.5, 0.0)), ..default() },
+    ));
+}
+
+fn sent(app: &App) -> Vec<WidgetValue> {
+    app.world()
+        .resource::<Messages<WidgetChanged>>()
+        .iter_current_update_messages()
+        .map(|change| change.value)
+        .collect()
+}
+
+#[test]
+fn snapping_counts_from_the_minimum_and_clamps() {
+    assert_eq!(snap_value(0.6, 0.0, 1.0, Some(0.25)), 0.5);
+    assert_eq!(snap_value(0.63, 0.0, 1.0, Some(0.25)), 0.75);
+    assert_eq!(snap_value(0.7, 0.1, 1.0, Some(0.25)), 0.6, "steps start at min, not zero");
+    assert_eq!(snap_value(7.0, 0.0, 1.0, Some(0.25)), 1.0);
+    assert_eq!(snap_value(-1.0, 0.0, 1.0, None), 0.0);
+    assert_eq!(snap_value(0.33, 0.0, 1.0, None), 0.33, "no step, no snapping");
+}
+
+#[test]
+fn dragging_a_slider_sends_snapped_values() {
+    let mut app = widgets_app();
+    let (slider, track) = quarter_slider(&mut app);
+
+    press_track(&mut app, track, 0.6);
+    app.update();
+    assert_eq!(sent(&app), vec![WidgetValue::Float(0.5)]);
+    assert_eq!(app.world().resource::<WidgetFocus>().0, Some(slider), "clicking focuses it");
+
+    press_track(&mut app, track, 0.7);
+    app.update();
+    assert_eq!(sent(&app), vec![WidgetValue::Float(0.75)]);
+
+    // still inside the same step, nothing changes so nothing's sent
+    press_track(&mut app, track, 0.72);
+    app.update();
+    assert!(sent(&app).is_empty());
+
+    // dragged off the end of the track
+    press_track(&mut app, track, 1.4);
+    app.update();
+    assert_eq!(sent(&app), vec![WidgetValue::Float(1.0)]);
+    assert_eq!(app.world().get::<Slider>(slider).unwrap().value, 1.0);
+}
+
+#[test]
+fn released_track_sends_nothing() {
+    let mut app = widgets_app();
+    let (_, track) = quarter_slider(&mut app);
+    press_track(&mut app, track, 0.6);
+    app.world_mut().entity_mut(track).insert(Interaction::Hovered);
+    app.update();
+    assert!(sent(&app).is_empty());
+}
+
+#[test]
+fn arrow_keys_nudge_the_focused_slider_by_a_step() {
+    let mut app = widgets_app();
+    let (slider, _) = quarter_slider(&mut app);
+    // stands in for layout, unsized widgets drop out of the focus order
+    app.world_mut()
+        .entity_mut(slider)
+        .insert(ComputedNode { size: Vec2::new(160.0, 12.0), ..default() });
+    app.world_mut().resource_mut::<WidgetFocus>().0 = Some(slider);
+
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::ArrowRight);
+    app.update();
+    assert_eq!(sent(&app), vec![WidgetValue::Float(0.25)]);
+    assert_eq!(app.world().get::<Slider>(slider).unwrap().value, 0.25);
+}
+
+#[test]
+fn screen_shake_follows_the_level_and_reduce_motion() {
+    let mut settings = Settings::default();
+    settings.screen_shake = 0;
+    assert_eq!(settings.screen_shake_strength(), 0.0);
+    settings.screen_shake = 2;
+    assert_eq!(settings.screen_shake_strength(), 1.0);
+    settings.screen_shake = 1;
+    assert!(settings.screen_shake_strength() > 0.0 && settings.screen_shake_strength() < 1.0);
+    settings.reduce_motion = true;
+    assert_eq!(settings.screen_shake_strength(), 0.0, "reduce motion wins over the level");
+}
@@ -0,0 +1,19 @@
+use ld58::world_gen::{generate_clusters, WorldSeed};
+
+/// Same seed, same world: the cluster layout comes out identical twice over
+#[test]
+fn same_seed_generates_the_same_clusters() {
+    let seed = WorldSeed(1234);
+    assert_eq!(seed.noise_offset(), WorldSeed(1234).noise_offset());
+    let first = generate_clusters(seed.noise_offset());
+    let second = generate_clusters(WorldSeed(1234).noise_offset());
+    assert_eq!(first.cluster_map, second.cluster_map, "cluster_map differs for seed {}", seed.0);
+    assert_eq!(first.center_map, second.center_map, "center_map differs for seed {}", seed.0);
+    assert_eq!(first.unlocked_cells, second.unlocked_cells);
+    assert_eq!(first.locked_cells, second.locked_cells);
+}
+
+#[test]
+fn different_seeds_move_the_noise() {
+    assert_ne!(WorldSeed(1234).noise_offset(), WorldSeed(4321).noise_offset());
+}